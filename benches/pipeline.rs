@@ -0,0 +1,101 @@
+//! Benchmarks the stages of the analysis pipeline on synthetic repositories
+//!
+//! Each stage is measured on its own, with the inputs of the earlier stages
+//! prepared up front, so a regression can be traced to the stage causing it.
+//! Run with `cargo bench --bench pipeline`.
+
+mod support;
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use kotlin_multiplatform_coverage::adapters::{
+    DependencyRepositoryImpl, FileSystem, SourceFileRepositoryImpl, SymbolRepositoryImpl,
+    SymbolUsageRepositoryImpl,
+};
+use kotlin_multiplatform_coverage::use_cases::{
+    CalculateDependenciesUseCase, DetectUsageUseCase, ExtractSymbolsUseCase,
+};
+use kotlin_multiplatform_coverage::SourceFileRepository;
+use support::{synthetic_repo, ROOT};
+
+/// Symbol extraction from shared sources, by number of shared classes
+fn bench_extraction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("extraction");
+    group.sample_size(10);
+
+    for symbols in [100, 500, 1000] {
+        let fs: Arc<dyn FileSystem> = Arc::new(synthetic_repo(symbols, 0));
+        let kmp_files = SourceFileRepositoryImpl::new()
+            .with_file_system(fs.clone())
+            .find_kmp_files(ROOT)
+            .unwrap();
+        let symbol_repository = SymbolRepositoryImpl::new().with_file_system(fs);
+
+        group.bench_with_input(BenchmarkId::from_parameter(symbols), &kmp_files, |b, files| {
+            b.iter(|| ExtractSymbolsUseCase::new(&symbol_repository).execute(files).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+/// Usage detection in app code, by number of shared classes and app files
+fn bench_usage_detection(c: &mut Criterion) {
+    let mut group = c.benchmark_group("usage_detection");
+    group.sample_size(10);
+
+    for (symbols, app_files) in [(50, 500), (200, 500), (50, 2000)] {
+        let fs: Arc<dyn FileSystem> = Arc::new(synthetic_repo(symbols, app_files));
+        let source_file_repository = SourceFileRepositoryImpl::new().with_file_system(fs.clone());
+        let symbol_repository = SymbolRepositoryImpl::new().with_file_system(fs);
+        let usage_repository = SymbolUsageRepositoryImpl::new();
+
+        let kmp_files = source_file_repository.find_kmp_files(ROOT).unwrap();
+        let app_files_by_platform = source_file_repository.find_app_files(ROOT).unwrap();
+        let shared_symbols = ExtractSymbolsUseCase::new(&symbol_repository)
+            .execute(&kmp_files)
+            .unwrap();
+
+        let id = BenchmarkId::from_parameter(format!("{}x{}", symbols, app_files));
+        group.bench_function(id, |b| {
+            b.iter(|| {
+                DetectUsageUseCase::new(&source_file_repository, &usage_repository)
+                    .execute(&app_files_by_platform, &shared_symbols)
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+/// Dependency graph construction over shared and app files, by number of app files
+fn bench_graph_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("graph_build");
+    group.sample_size(10);
+
+    for app_files in [250, 1000, 2000] {
+        let fs: Arc<dyn FileSystem> = Arc::new(synthetic_repo(50, app_files));
+        let source_file_repository = SourceFileRepositoryImpl::new().with_file_system(fs.clone());
+        let mut all_files = source_file_repository.find_kmp_files(ROOT).unwrap();
+        for files in source_file_repository.find_app_files(ROOT).unwrap().into_values() {
+            all_files.extend(files);
+        }
+
+        group.bench_with_input(BenchmarkId::from_parameter(app_files), &all_files, |b, files| {
+            b.iter(|| {
+                // The graph is per-run state, so each iteration starts from an empty one
+                let dependency_repository = DependencyRepositoryImpl::new().with_file_system(fs.clone());
+                CalculateDependenciesUseCase::new(&dependency_repository)
+                    .build_graph(files)
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_extraction, bench_usage_detection, bench_graph_build);
+criterion_main!(benches);
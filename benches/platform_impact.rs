@@ -0,0 +1,35 @@
+//! Benchmarks the impact calculation on large synthetic repositories
+//!
+//! Every app file is checked for membership in its platform while usages and
+//! affected files are tallied, so the cost grows with repository size.
+//! Run with `cargo bench --bench platform_impact`.
+
+mod support;
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use kotlin_multiplatform_coverage::infrastructure::Analyzer;
+use kotlin_multiplatform_coverage::use_cases::AnalysisOptions;
+use support::{synthetic_repo, ROOT};
+
+fn bench_platform_impact(c: &mut Criterion) {
+    let mut group = c.benchmark_group("platform_impact");
+    group.sample_size(10);
+
+    for app_files in [250, 1000, 2000] {
+        let fs = Arc::new(synthetic_repo(50, app_files));
+        group.bench_with_input(BenchmarkId::from_parameter(app_files), &fs, |b, fs| {
+            b.iter(|| {
+                Analyzer::with_file_system(fs.clone(), AnalysisOptions::default(), Vec::new())
+                    .analyze(ROOT)
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_platform_impact);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+//! Synthetic KMP repositories shared by the benchmarks
+
+use std::path::Path;
+
+use kotlin_multiplatform_coverage::adapters::MemoryFileSystem;
+
+/// Project root of every synthetic repository
+pub const ROOT: &str = "/bench/repo";
+
+/// A shared module with `symbols` classes and an Android app with `app_files` files using them
+pub fn synthetic_repo(symbols: usize, app_files: usize) -> MemoryFileSystem {
+    let root = Path::new(ROOT);
+    let mut fs = MemoryFileSystem::new();
+
+    fs.insert(
+        root.join("shared/build.gradle.kts"),
+        "plugins { kotlin(\"multiplatform\") }\nkotlin { sourceSets { val commonMain by getting } }\n",
+    );
+    for i in 0..symbols {
+        fs.insert(
+            root.join(format!("shared/src/commonMain/kotlin/com/example/Shared{}.kt", i)),
+            format!("package com.example\n\nclass Shared{} {{\n    fun value() = {}\n}}\n", i, i),
+        );
+    }
+
+    fs.insert(
+        root.join("app/build.gradle.kts"),
+        "plugins { id(\"com.android.application\") }\ndependencies { implementation(project(\":shared\")) }\n",
+    );
+    fs.insert(root.join("app/src/main/AndroidManifest.xml"), "<manifest/>");
+    for i in 0..app_files {
+        // Every other file uses shared code, so both affected and unaffected files are measured
+        let body = if i % 2 == 0 {
+            format!("    val shared = Shared{}()\n", i % symbols.max(1))
+        } else {
+            "    val local = 1\n".to_string()
+        };
+        fs.insert(
+            root.join(format!("app/src/main/kotlin/com/example/app/Screen{}.kt", i)),
+            format!("package com.example.app\n\nclass Screen{} {{\n{}}}\n", i, body),
+        );
+    }
+
+    fs
+}
@@ -0,0 +1,96 @@
+//! Package ownership of shared modules
+//! Reads the optional `OWNERS` file at the root of each KMP module, which assigns
+//! owners to package prefixes so reports can say whom to contact about an API
+
+use anyhow::Result;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
+use crate::domain::OwnerRule;
+
+/// Name of the ownership file looked up in each shared module
+pub const OWNERS_FILE: &str = "OWNERS";
+
+/// Finds the package owners declared by KMP modules
+pub struct ApiOwnersScanner {
+    fs: Arc<dyn FileSystem>,
+}
+
+impl ApiOwnersScanner {
+    pub fn new() -> Self {
+        Self {
+            fs: Arc::new(OsFileSystem),
+        }
+    }
+
+    /// Reads `OWNERS` files through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// Reads the `OWNERS` file of every module the given KMP files belong to
+    pub fn scan(&self, kmp_file_paths: &[String]) -> Result<Vec<OwnerRule>> {
+        let modules: BTreeSet<&str> = kmp_file_paths
+            .iter()
+            .filter_map(|path| path.find("/src/").map(|idx| &path[..idx]))
+            .collect();
+
+        let mut rules = Vec::new();
+        for module in modules {
+            let path = Path::new(module).join(OWNERS_FILE);
+            if self.fs.is_file(&path) {
+                rules.extend(Self::parse(module, &self.fs.read_to_string(&path)?));
+            }
+        }
+        Ok(rules)
+    }
+
+    /// One rule per `<package> <owner>...` line; `*` assigns the module's default owners
+    ///
+    /// Blank lines, `#` comments, and lines without owners are ignored.
+    pub fn parse(module: &str, content: &str) -> Vec<OwnerRule> {
+        content
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let package = fields.next()?;
+                let owners: Vec<String> = fields.map(str::to_string).collect();
+                (!owners.is_empty()).then(|| OwnerRule {
+                    module: module.to_string(),
+                    package: if package == "*" { String::new() } else { package.to_string() },
+                    owners,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for ApiOwnersScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_owners_file() {
+        let rules = ApiOwnersScanner::parse(
+            "shared",
+            "# Shared API owners\n\n* @platform-team\ncom.example.auth @auth-team alice@example.com # login\ncom.example.legacy\n",
+        );
+
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].package, "");
+        assert_eq!(rules[0].owners, vec!["@platform-team"]);
+        assert_eq!(rules[1].module, "shared");
+        assert_eq!(rules[1].package, "com.example.auth");
+        assert_eq!(rules[1].owners, vec!["@auth-team", "alice@example.com"]);
+    }
+}
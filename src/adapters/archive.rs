@@ -0,0 +1,351 @@
+//! Source archive support
+//! Loads `.tar.gz`, `.tgz`, `.tar`, and `.zip` snapshots into an in-memory file tree
+//! so they can be analyzed without unpacking to disk
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use log::debug;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek};
+use std::path::{Component, Path, PathBuf};
+
+use crate::adapters::file_system::MemoryFileSystem;
+
+/// Entries larger than this are skipped without being decompressed
+const MAX_ENTRY_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Upper bound on the decompressed size of all kept entries
+const MAX_TOTAL_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Extensions of entries that are never text sources, skipped before reading
+const BINARY_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "ico", "icns", "pdf", "ttf", "otf", "woff", "woff2",
+    "jar", "aar", "apk", "aab", "ipa", "klib", "class", "dex", "so", "dylib", "dll", "a", "o",
+    "zip", "gz", "tgz", "xz", "bz2", "7z", "mp3", "mp4", "mov", "wav", "car", "keystore", "jks",
+];
+
+/// Size limits applied while reading entries
+#[derive(Debug, Clone, Copy)]
+struct ArchiveLimits {
+    max_entry_bytes: u64,
+    max_total_bytes: u64,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_entry_bytes: MAX_ENTRY_BYTES,
+            max_total_bytes: MAX_TOTAL_BYTES,
+        }
+    }
+}
+
+/// Tracks the decompressed bytes kept so far against the limits
+struct EntryReader {
+    limits: ArchiveLimits,
+    total_bytes: u64,
+    entries: Vec<(PathBuf, String)>,
+}
+
+impl EntryReader {
+    fn new(limits: ArchiveLimits) -> Self {
+        Self {
+            limits,
+            total_bytes: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Whether an entry is worth decompressing, judged from its path and declared size
+    fn wants(&self, path: &Path, size: u64) -> bool {
+        let binary = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| BINARY_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+        !binary && size <= self.limits.max_entry_bytes
+    }
+
+    /// Reads one entry, keeping it if it is UTF-8 text within the size limits
+    fn read(&mut self, path: PathBuf, entry: impl Read) -> Result<()> {
+        // Declared sizes can lie, so never read past the per-entry limit
+        let mut bytes = Vec::new();
+        entry.take(self.limits.max_entry_bytes + 1).read_to_end(&mut bytes)?;
+        let len = bytes.len() as u64;
+        if len > self.limits.max_entry_bytes {
+            debug!("Skipping oversized archive entry {}", path.display());
+            return Ok(());
+        }
+        let Ok(content) = String::from_utf8(bytes) else {
+            return Ok(());
+        };
+
+        self.total_bytes += len;
+        if self.total_bytes > self.limits.max_total_bytes {
+            bail!(
+                "Archive expands to more than {} MiB of text files; unpack it and analyze the directory instead",
+                self.limits.max_total_bytes / (1024 * 1024)
+            );
+        }
+        self.entries.push((path, content));
+        Ok(())
+    }
+}
+
+/// Supported archive formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    TarGz,
+    Tar,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Detects the archive format from the file name, if it is one
+    pub fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+
+    /// Strips the archive extension from a file name (`repo.tar.gz` -> `repo`)
+    pub fn strip_extension(self, file_name: &str) -> &str {
+        let extensions: &[&str] = match self {
+            Self::TarGz => &[".tar.gz", ".tgz"],
+            Self::Tar => &[".tar"],
+            Self::Zip => &[".zip"],
+        };
+        extensions
+            .iter()
+            .find_map(|ext| {
+                let split = file_name.len().checked_sub(ext.len())?;
+                let (stem, suffix) = file_name.split_at_checked(split)?;
+                suffix.eq_ignore_ascii_case(ext).then_some(stem)
+            })
+            .unwrap_or(file_name)
+    }
+}
+
+/// Loads an archive into memory
+///
+/// Non-UTF-8 entries (images, binaries) are skipped, as are entries with a known
+/// binary extension or larger than 8 MiB, which are never decompressed. Fails once
+/// the kept entries exceed 512 MiB in total. When every entry sits
+/// under one top-level directory, as with `git archive --prefix` or GitHub
+/// tarballs, that directory is stripped so the archive root is the project root.
+pub fn load_archive(path: &Path, format: ArchiveFormat) -> Result<MemoryFileSystem> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open archive {}", path.display()))?;
+
+    let entries = read_entries(file, format, ArchiveLimits::default())
+        .with_context(|| format!("Failed to read archive {}", path.display()))?;
+    let fs = into_file_system(entries);
+
+    debug!("Loaded {} text files from {}", fs.len(), path.display());
+    Ok(fs)
+}
+
+/// Loads an archive that is already in memory (e.g. uploaded to the browser demo)
+pub fn load_archive_bytes(bytes: &[u8], format: ArchiveFormat) -> Result<MemoryFileSystem> {
+    let entries = read_entries(Cursor::new(bytes), format, ArchiveLimits::default()).context("Failed to read archive")?;
+    let fs = into_file_system(entries);
+
+    debug!("Loaded {} text files from archive bytes", fs.len());
+    Ok(fs)
+}
+
+fn read_entries(
+    reader: impl Read + Seek,
+    format: ArchiveFormat,
+    limits: ArchiveLimits,
+) -> Result<Vec<(PathBuf, String)>> {
+    match format {
+        ArchiveFormat::TarGz => read_tar(GzDecoder::new(reader), limits),
+        ArchiveFormat::Tar => read_tar(reader, limits),
+        ArchiveFormat::Zip => read_zip(reader, limits),
+    }
+}
+
+fn into_file_system(entries: Vec<(PathBuf, String)>) -> MemoryFileSystem {
+    let strip = common_root(&entries);
+    let mut fs = MemoryFileSystem::new();
+    for (entry_path, content) in entries {
+        let relative = match &strip {
+            Some(root) => entry_path.strip_prefix(root).unwrap_or(&entry_path).to_path_buf(),
+            None => entry_path,
+        };
+        fs.insert(relative, content);
+    }
+    fs
+}
+
+fn read_tar(reader: impl Read, limits: ArchiveLimits) -> Result<Vec<(PathBuf, String)>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut kept = EntryReader::new(limits);
+
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let Some(path) = safe_path(&entry.path()?) else {
+            continue;
+        };
+        if kept.wants(&path, entry.size()) {
+            kept.read(path, entry)?;
+        }
+    }
+
+    Ok(kept.entries)
+}
+
+fn read_zip(reader: impl Read + Seek, limits: ArchiveLimits) -> Result<Vec<(PathBuf, String)>> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut kept = EntryReader::new(limits);
+
+    for index in 0..archive.len() {
+        let entry = archive.by_index(index)?;
+        if !entry.is_file() {
+            continue;
+        }
+        let Some(path) = entry.enclosed_name().and_then(|p| safe_path(&p)) else {
+            continue;
+        };
+        if kept.wants(&path, entry.size()) {
+            kept.read(path, entry)?;
+        }
+    }
+
+    Ok(kept.entries)
+}
+
+/// Keeps only relative paths that stay inside the archive root
+fn safe_path(path: &Path) -> Option<PathBuf> {
+    let mut safe = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => safe.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!safe.as_os_str().is_empty()).then_some(safe)
+}
+
+/// Top-level directory shared by every entry, if there is exactly one
+fn common_root(entries: &[(PathBuf, String)]) -> Option<PathBuf> {
+    let mut roots = entries.iter().map(|(path, _)| {
+        let mut components = path.components();
+        let first = components.next()?;
+        components.next().map(|_| PathBuf::from(first.as_os_str()))
+    });
+    let first = roots.next()??;
+    roots.all(|root| root.as_ref() == Some(&first)).then_some(first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::file_system::FileSystem;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(ArchiveFormat::detect(Path::new("repo.tar.gz")), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::detect(Path::new("repo.TGZ")), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::detect(Path::new("repo.zip")), Some(ArchiveFormat::Zip));
+        assert_eq!(ArchiveFormat::detect(Path::new("repo")), None);
+        assert_eq!(ArchiveFormat::TarGz.strip_extension("repo-main.tar.gz"), "repo-main");
+    }
+
+    #[test]
+    fn test_load_tar_gz_strips_common_root() -> Result<()> {
+        let temp = TempDir::new()?;
+        let archive_path = temp.path().join("snapshot.tar.gz");
+
+        let encoder = GzEncoder::new(File::create(&archive_path)?, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (path, content) in [
+            ("repo-main/shared/build.gradle.kts", "plugins { kotlin(\"multiplatform\") }"),
+            ("repo-main/shared/src/commonMain/kotlin/User.kt", "class User"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, path, content.as_bytes())?;
+        }
+        builder.into_inner()?.finish()?.flush()?;
+
+        let fs = load_archive(&archive_path, ArchiveFormat::TarGz)?;
+        assert_eq!(fs.len(), 2);
+        assert_eq!(
+            fs.read_to_string(Path::new("shared/src/commonMain/kotlin/User.kt"))?,
+            "class User"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_zip() -> Result<()> {
+        let temp = TempDir::new()?;
+        let archive_path = temp.path().join("snapshot.zip");
+
+        let mut writer = zip::ZipWriter::new(File::create(&archive_path)?);
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("app/Main.kt", options)?;
+        writer.write_all(b"class Main")?;
+        writer.start_file("iosApp/App.swift", options)?;
+        writer.write_all(b"struct App {}")?;
+        writer.finish()?;
+
+        let fs = load_archive(&archive_path, ArchiveFormat::Zip)?;
+        assert!(fs.is_file(Path::new("app/Main.kt")));
+        assert!(fs.is_dir(Path::new("iosApp")));
+
+        let from_bytes = load_archive_bytes(&std::fs::read(&archive_path)?, ArchiveFormat::Zip)?;
+        assert_eq!(from_bytes.read_to_string(Path::new("iosApp/App.swift"))?, "struct App {}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_entries_enforces_limits() -> Result<()> {
+        let temp = TempDir::new()?;
+        let archive_path = temp.path().join("snapshot.zip");
+
+        let mut writer = zip::ZipWriter::new(File::create(&archive_path)?);
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("app/Main.kt", options)?;
+        writer.write_all(b"class Main")?;
+        writer.start_file("app/logo.png", options)?;
+        writer.write_all(b"not really a png")?;
+        writer.start_file("app/Generated.kt", options)?;
+        writer.write_all(&[b'x'; 64])?;
+        writer.finish()?;
+
+        let small = ArchiveLimits {
+            max_entry_bytes: 32,
+            max_total_bytes: 1024,
+        };
+        let entries = read_entries(File::open(&archive_path)?, ArchiveFormat::Zip, small)?;
+        let paths: Vec<_> = entries.iter().map(|(path, _)| path.as_path()).collect();
+        assert_eq!(paths, vec![Path::new("app/Main.kt")]);
+
+        let tiny = ArchiveLimits {
+            max_entry_bytes: 32,
+            max_total_bytes: 4,
+        };
+        let error = read_entries(File::open(&archive_path)?, ArchiveFormat::Zip, tiny).unwrap_err();
+        assert!(error.to_string().contains("Archive expands to more than"));
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+//! Bazel and Buck build file support
+//! Finds Kotlin Multiplatform, Android, and iOS targets in `BUILD`, `BUILD.bazel`, and `BUCK`
+//! files, with the `srcs` globs that attribute files to them
+
+use regex::Regex;
+
+use crate::adapters::project_detector::ProjectType;
+use crate::domain::FileFilter;
+
+/// Build file names of Bazel and Buck packages
+pub const BUILD_FILES: [&str; 3] = ["BUILD.bazel", "BUILD", "BUCK"];
+
+/// A target declared in a build file
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildTarget {
+    /// Rule the target is declared with (`kt_android_library`, `swift_library`)
+    pub rule: String,
+    pub name: String,
+    pub project_type: ProjectType,
+    /// `srcs` globs and files, relative to the package directory, and `glob(exclude = ...)` patterns
+    pub sources: FileFilter,
+}
+
+/// Project type of the targets a rule declares, `None` for other rules and test rules
+pub fn rule_project_type(rule: &str) -> Option<ProjectType> {
+    if rule.ends_with("_test") {
+        return None;
+    }
+    if rule.contains("multiplatform") || rule.starts_with("kmp_") || rule.contains("_kmp_") {
+        Some(ProjectType::KotlinMultiplatform)
+    } else if rule.starts_with("android_") || rule.contains("_android_") {
+        Some(ProjectType::Android)
+    } else if rule.starts_with("ios_")
+        || rule.starts_with("apple_")
+        || matches!(rule, "swift_library" | "objc_library")
+    {
+        Some(ProjectType::IOS)
+    } else {
+        None
+    }
+}
+
+/// Targets of the recognized rules declared in a build file, skipping those without `srcs`
+pub fn parse(content: &str) -> Vec<BuildTarget> {
+    // Match: kt_android_library( at the start of a line, i.e. a top-level rule call
+    let call_regex = Regex::new(r"(?m)^([A-Za-z_][A-Za-z0-9_]*)\s*\(").unwrap();
+    let string_regex = Regex::new(r#""([^"]*)"|'([^']*)'"#).unwrap();
+    // Match: exclude = ["**/*Test.kt"]
+    let exclude_regex = Regex::new(r"exclude\s*=\s*\[([^\]]*)\]").unwrap();
+    let strings = |text: &str| -> Vec<String> {
+        string_regex
+            .captures_iter(text)
+            .filter_map(|c| c.get(1).or_else(|| c.get(2)))
+            .map(|m| m.as_str().to_string())
+            .collect()
+    };
+
+    let mut targets = Vec::new();
+    for call in call_regex.captures_iter(content) {
+        let rule = &call[1];
+        let Some(project_type) = rule_project_type(rule) else {
+            continue;
+        };
+        let body_start = call.get(0).unwrap().end();
+        let Some(body) = call_body(&content[body_start..]) else {
+            continue;
+        };
+
+        let mut name = None;
+        let mut sources = FileFilter::default();
+        for argument in split_arguments(body) {
+            let Some((key, value)) = argument.split_once('=') else {
+                continue;
+            };
+            match key.trim() {
+                "name" => name = strings(value).into_iter().next(),
+                "srcs" => {
+                    for excluded in exclude_regex.captures_iter(value) {
+                        sources.exclude.extend(strings(&excluded[1]));
+                    }
+                    sources.include = strings(&exclude_regex.replace_all(value, ""));
+                }
+                _ => {}
+            }
+        }
+
+        if let (Some(name), false) = (name, sources.include.is_empty()) {
+            targets.push(BuildTarget {
+                rule: rule.to_string(),
+                name,
+                project_type,
+                sources,
+            });
+        }
+    }
+    targets
+}
+
+/// The arguments of a call, up to its closing parenthesis
+fn call_body(rest: &str) -> Option<&str> {
+    let mut depth = 0;
+    let mut quote = None;
+    for (index, c) in rest.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')') if depth == 0 => return Some(&rest[..index]),
+            (None, ')' | ']' | '}') => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits call arguments at top-level commas
+fn split_arguments(body: &str) -> Vec<&str> {
+    let mut arguments = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    let mut quote = None;
+    for (index, c) in body.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                arguments.push(&body[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    arguments.push(&body[start..]);
+    arguments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_build_targets() {
+        let targets = parse(
+            r#"
+load("@rules_kotlin//kotlin:android.bzl", "kt_android_library")
+
+kt_android_library(
+    name = "app",
+    srcs = glob(["src/main/kotlin/**/*.kt"], exclude = ["**/generated/**"]) + ["Extra.kt"],
+    deps = ["//shared:shared"],
+)
+
+kt_android_local_test(
+    name = "app_test",
+    srcs = glob(["src/test/**/*.kt"]),
+)
+
+swift_library(name = 'ui', srcs = glob(['Sources/**/*.swift']))
+
+ios_application(
+    name = "ios",
+    deps = [":ui"],
+)
+
+java_library(name = "util", srcs = ["Util.java"])
+"#,
+        );
+
+        assert_eq!(
+            targets,
+            vec![
+                BuildTarget {
+                    rule: "kt_android_library".to_string(),
+                    name: "app".to_string(),
+                    project_type: ProjectType::Android,
+                    sources: FileFilter {
+                        include: vec!["src/main/kotlin/**/*.kt".to_string(), "Extra.kt".to_string()],
+                        exclude: vec!["**/generated/**".to_string()],
+                    },
+                },
+                BuildTarget {
+                    rule: "swift_library".to_string(),
+                    name: "ui".to_string(),
+                    project_type: ProjectType::IOS,
+                    sources: FileFilter {
+                        include: vec!["Sources/**/*.swift".to_string()],
+                        exclude: Vec::new(),
+                    },
+                },
+            ]
+        );
+        assert_eq!(
+            rule_project_type("kt_multiplatform_library"),
+            Some(ProjectType::KotlinMultiplatform)
+        );
+        assert_eq!(rule_project_type("kt_jvm_library"), None);
+    }
+}
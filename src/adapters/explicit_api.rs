@@ -0,0 +1,122 @@
+//! Kotlin explicit API mode of shared modules
+//! Reads `explicitApi()`, `explicitApiWarning()`, and `-Xexplicit-api=` from a module's
+//! build file, so extraction can follow the visibility the compiler enforces
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use crate::adapters::file_system::{FileStamp, FileSystem};
+use crate::domain::ExplicitApiMode;
+
+/// Explicit API mode configured by a Gradle build script, `None` when it is off
+pub fn parse_mode(build_script: &str) -> Option<ExplicitApiMode> {
+    let mut mode = None;
+    for line in build_script.lines() {
+        let line = line.split("//").next().unwrap_or_default();
+        if line.contains("explicitApiWarning()")
+            || line.contains("ExplicitApiMode.Warning")
+            || line.contains("-Xexplicit-api=warning")
+        {
+            mode = Some(ExplicitApiMode::Warning);
+        } else if line.contains("explicitApi()")
+            || line.contains("ExplicitApiMode.Strict")
+            || line.contains("-Xexplicit-api=strict")
+        {
+            mode = Some(ExplicitApiMode::Strict);
+        } else if line.contains("ExplicitApiMode.Disabled") {
+            mode = None;
+        }
+    }
+    mode
+}
+
+/// Looks up the explicit API mode of the module a KMP file belongs to
+///
+/// Each build file is read once and again only after it changes, so a
+/// long-lived analyzer follows edits to the module's configuration.
+pub struct ExplicitApiModes {
+    fs: Arc<dyn FileSystem>,
+    /// Module directory -> its build file, if any
+    modes: RwLock<HashMap<String, Option<BuildScript>>>,
+}
+
+/// A module's build file with the stamp it was read at and the mode it configures
+struct BuildScript {
+    path: PathBuf,
+    stamp: FileStamp,
+    mode: Option<ExplicitApiMode>,
+}
+
+impl ExplicitApiModes {
+    pub fn new(fs: Arc<dyn FileSystem>) -> Self {
+        Self {
+            fs,
+            modes: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Mode of the module whose `src/` directory contains `file_path`
+    pub fn of_file(&self, file_path: &str) -> Result<Option<ExplicitApiMode>> {
+        let Some(module) = file_path.find("/src/").map(|idx| &file_path[..idx]) else {
+            return Ok(None);
+        };
+        let build_file = match self.modes.read().unwrap().get(module) {
+            Some(None) => return Ok(None),
+            Some(Some(script)) => {
+                if self.fs.stamp(&script.path)? == script.stamp {
+                    return Ok(script.mode);
+                }
+                Some(script.path.clone())
+            }
+            None => ["build.gradle.kts", "build.gradle"]
+                .iter()
+                .map(|name| Path::new(module).join(name))
+                .find(|path| self.fs.is_file(path)),
+        };
+
+        let entry = match build_file {
+            Some(path) => Some(BuildScript {
+                stamp: self.fs.stamp(&path)?,
+                mode: parse_mode(&self.fs.read_to_string(&path)?),
+                path,
+            }),
+            None => None,
+        };
+        let mode = entry.as_ref().and_then(|script| script.mode);
+        self.modes.write().unwrap().insert(module.to_string(), entry);
+        Ok(mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::file_system::MemoryFileSystem;
+
+    #[test]
+    fn test_explicit_api_mode() -> Result<()> {
+        assert_eq!(parse_mode("kotlin {\n    explicitApi()\n}"), Some(ExplicitApiMode::Strict));
+        assert_eq!(parse_mode("kotlin { explicitApiWarning() }"), Some(ExplicitApiMode::Warning));
+        assert_eq!(
+            parse_mode("freeCompilerArgs.add(\"-Xexplicit-api=warning\")"),
+            Some(ExplicitApiMode::Warning)
+        );
+        assert_eq!(parse_mode("explicitApi = ExplicitApiMode.Disabled"), None);
+        assert_eq!(parse_mode("// explicitApi()\nkotlin { jvm() }"), None);
+
+        let mut fs = MemoryFileSystem::new();
+        fs.insert("repo/core/build.gradle.kts", "kotlin { explicitApi() }");
+        fs.insert("repo/legacy/build.gradle", "kotlin { jvm() }");
+        let modes = ExplicitApiModes::new(Arc::new(fs));
+        assert_eq!(
+            modes.of_file("repo/core/src/commonMain/kotlin/User.kt")?,
+            Some(ExplicitApiMode::Strict)
+        );
+        assert_eq!(modes.of_file("repo/legacy/src/commonMain/kotlin/Old.kt")?, None);
+        assert_eq!(modes.of_file("Loose.kt")?, None);
+
+        Ok(())
+    }
+}
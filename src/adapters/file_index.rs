@@ -0,0 +1,150 @@
+//! Cached listing of a file tree
+//! Walks a project once and answers the many directory listings and existence
+//! checks of project detection from memory
+
+use anyhow::Result;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::adapters::file_system::{FileEntry, FileStamp, FileSystem};
+
+/// Every entry of a tree down to a fixed depth, listed in a single walk
+///
+/// Implements [`FileSystem`] so detectors can use it in place of the file
+/// system they were given: listings and checks within the indexed depth come
+/// from the index, anything deeper and all file reads go to the wrapped one.
+pub struct FileIndex<'a> {
+    fs: &'a dyn FileSystem,
+    root: PathBuf,
+    depth: usize,
+    /// Sorted by path, so the entries below a directory are contiguous
+    entries: Vec<FileEntry>,
+    dirs: HashSet<PathBuf>,
+}
+
+impl<'a> FileIndex<'a> {
+    /// Lists `root` down to `depth` levels through `fs`
+    pub fn build(fs: &'a dyn FileSystem, root: &Path, depth: usize) -> Self {
+        let mut entries = fs.walk(root, depth);
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        let dirs = entries
+            .iter()
+            .filter(|e| e.is_dir)
+            .map(|e| e.path.clone())
+            .collect();
+
+        Self {
+            fs,
+            root: root.to_path_buf(),
+            depth,
+            entries,
+            dirs,
+        }
+    }
+
+    /// Number of indexed entries, `root` included
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Depth of `path` below the root, if it is inside the indexed tree
+    fn depth_of(&self, path: &Path) -> Option<usize> {
+        path.strip_prefix(&self.root)
+            .ok()
+            .map(|relative| relative.components().count())
+    }
+
+    /// Whether a listing of `path` down to `max_depth` levels is fully indexed
+    fn covers(&self, path: &Path, max_depth: usize) -> bool {
+        self.depth_of(path)
+            .is_some_and(|depth| depth.saturating_add(max_depth) <= self.depth)
+    }
+}
+
+impl FileSystem for FileIndex<'_> {
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        self.fs.read_to_string(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        if self.covers(path, 0) {
+            self.entries
+                .binary_search_by(|e| e.path.as_path().cmp(path))
+                .is_ok_and(|i| !self.entries[i].is_dir)
+        } else {
+            self.fs.is_file(path)
+        }
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        if self.covers(path, 0) {
+            self.dirs.contains(path)
+        } else {
+            self.fs.is_dir(path)
+        }
+    }
+
+    fn walk(&self, root: &Path, max_depth: usize) -> Vec<FileEntry> {
+        if !self.covers(root, max_depth) {
+            return self.fs.walk(root, max_depth);
+        }
+
+        let start = self.entries.partition_point(|e| e.path.as_path() < root);
+        self.entries[start..]
+            .iter()
+            .take_while(|e| e.path.starts_with(root))
+            .filter(|e| {
+                e.path
+                    .strip_prefix(root)
+                    .is_ok_and(|relative| relative.components().count() <= max_depth)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn stamp(&self, path: &Path) -> Result<FileStamp> {
+        self.fs.stamp(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::file_system::MemoryFileSystem;
+
+    fn paths(mut entries: Vec<FileEntry>) -> Vec<PathBuf> {
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        entries.into_iter().map(|e| e.path).collect()
+    }
+
+    #[test]
+    fn test_index_matches_file_system() {
+        let mut fs = MemoryFileSystem::new();
+        fs.insert("repo/shared/build.gradle.kts", "plugins { kotlin(\"multiplatform\") }");
+        fs.insert("repo/shared/src/commonMain/kotlin/User.kt", "class User");
+        fs.insert("repo/app/build.gradle.kts", "");
+
+        let root = Path::new("repo");
+        let index = FileIndex::build(&fs, root, 3);
+
+        assert!(index.is_dir(Path::new("repo/shared/src")));
+        assert!(index.is_file(Path::new("repo/app/build.gradle.kts")));
+        assert!(!index.exists(Path::new("repo/ios")));
+        assert_eq!(paths(index.walk(root, 2)), paths(fs.walk(root, 2)));
+        assert_eq!(
+            paths(index.walk(Path::new("repo/shared"), 2)),
+            paths(fs.walk(Path::new("repo/shared"), 2))
+        );
+
+        // Deeper than indexed: answered by the wrapped file system
+        assert!(index.is_file(Path::new("repo/shared/src/commonMain/kotlin/User.kt")));
+        assert_eq!(
+            paths(index.walk(Path::new("repo/shared"), 5)),
+            paths(fs.walk(Path::new("repo/shared"), 5))
+        );
+    }
+}
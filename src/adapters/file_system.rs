@@ -0,0 +1,341 @@
+//! File system abstraction
+//! Lets detection and analysis run over the real disk or a virtual file tree
+//! (in-memory sources, archive snapshots) through the same code paths
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+/// An entry produced by [`FileSystem::walk`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+/// Cheap fingerprint of a file's content, used to invalidate caches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    pub modified: Option<SystemTime>,
+    pub len: u64,
+}
+
+/// Read-only view of a file tree
+pub trait FileSystem: Send + Sync {
+    /// Reads a file as UTF-8 text
+    fn read_to_string(&self, path: &Path) -> Result<String>;
+
+    fn is_file(&self, path: &Path) -> bool;
+
+    fn is_dir(&self, path: &Path) -> bool;
+
+    fn exists(&self, path: &Path) -> bool {
+        self.is_file(path) || self.is_dir(path)
+    }
+
+    /// Lists `root` and everything below it up to `max_depth` levels, like `WalkDir`
+    fn walk(&self, root: &Path, max_depth: usize) -> Vec<FileEntry>;
+
+    /// Like [`walk`](Self::walk), leaving out every entry `skip` accepts (given its
+    /// path and whether it is a directory) and everything below a skipped directory
+    fn walk_pruned(&self, root: &Path, max_depth: usize, skip: &PruneFn<'_>) -> Vec<FileEntry> {
+        let mut skipped_dirs: Vec<PathBuf> = Vec::new();
+        self.walk(root, max_depth)
+            .into_iter()
+            .filter(|entry| {
+                if skipped_dirs.iter().any(|dir| entry.path.starts_with(dir)) {
+                    return false;
+                }
+                if !skip(&entry.path, entry.is_dir) {
+                    return true;
+                }
+                if entry.is_dir {
+                    skipped_dirs.push(entry.path.clone());
+                }
+                false
+            })
+            .collect()
+    }
+
+    /// Fingerprint of a file, used to tell whether cached results are still valid
+    fn stamp(&self, path: &Path) -> Result<FileStamp>;
+}
+
+/// Predicate of [`FileSystem::walk_pruned`]: whether to skip a path, given whether it is a directory
+pub type PruneFn<'a> = dyn Fn(&Path, bool) -> bool + Sync + 'a;
+
+/// Upper bound on threads listing a tree at once; directory reads stop scaling
+/// well before the core count on most disks
+const MAX_WALK_THREADS: usize = 8;
+
+/// The real file system
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsFileSystem;
+
+impl OsFileSystem {
+    fn walk_serial(root: &Path, min_depth: usize, max_depth: usize, skip: &PruneFn<'_>) -> Vec<FileEntry> {
+        WalkDir::new(root)
+            .min_depth(min_depth)
+            .max_depth(max_depth)
+            .into_iter()
+            .filter_entry(|e| !skip(e.path(), e.file_type().is_dir()))
+            .filter_map(|e| e.ok())
+            .map(|e| FileEntry {
+                is_dir: e.file_type().is_dir(),
+                path: e.into_path(),
+            })
+            .collect()
+    }
+}
+
+impl FileSystem for OsFileSystem {
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn walk(&self, root: &Path, max_depth: usize) -> Vec<FileEntry> {
+        self.walk_pruned(root, max_depth, &|_, _| false)
+    }
+
+    /// Lists the top-level directories of `root` on a bounded pool of threads,
+    /// never descending into skipped directories
+    ///
+    /// Entries come out in the same pre-order a serial `WalkDir` would produce.
+    fn walk_pruned(&self, root: &Path, max_depth: usize, skip: &PruneFn<'_>) -> Vec<FileEntry> {
+        if max_depth < 2 || cfg!(target_arch = "wasm32") {
+            return Self::walk_serial(root, 0, max_depth, skip);
+        }
+
+        let top = Self::walk_serial(root, 0, 1, skip);
+        let subdirs: Vec<usize> = (1..top.len()).filter(|&i| top[i].is_dir).collect();
+        let workers = thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(MAX_WALK_THREADS)
+            .min(subdirs.len());
+        if workers <= 1 {
+            return Self::walk_serial(root, 0, max_depth, skip);
+        }
+
+        let next = AtomicUsize::new(0);
+        let mut subtrees: Vec<(usize, Vec<FileEntry>)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        while let Some(&i) = subdirs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                            done.push((i, Self::walk_serial(&top[i].path, 1, max_depth - 1, skip)));
+                        }
+                        done
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_default())
+                .collect()
+        });
+        subtrees.sort_by_key(|(i, _)| *i);
+
+        let mut subtrees = subtrees.into_iter().peekable();
+        let mut entries = Vec::new();
+        for (i, entry) in top.into_iter().enumerate() {
+            entries.push(entry);
+            if let Some((_, below)) = subtrees.next_if(|(at, _)| *at == i) {
+                entries.extend(below);
+            }
+        }
+        entries
+    }
+
+    fn stamp(&self, path: &Path) -> Result<FileStamp> {
+        let metadata = fs::metadata(path)?;
+        Ok(FileStamp {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+/// A file tree held in memory, keyed by path
+///
+/// Directories are implied by the files below them. Paths are compared with
+/// `.` components removed, so `./shared` and `shared` refer to the same entry.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFileSystem {
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces a file
+    pub fn insert(&mut self, path: impl AsRef<Path>, content: impl Into<String>) {
+        self.files.insert(Self::normalize(path.as_ref()), content.into());
+    }
+
+    /// Number of files in the tree
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    fn normalize(path: &Path) -> PathBuf {
+        path.components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect()
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        self.files
+            .get(&Self::normalize(path))
+            .cloned()
+            .ok_or_else(|| anyhow!("No such file: {}", path.display()))
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.files.contains_key(&Self::normalize(path))
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        let dir = Self::normalize(path);
+        self.files
+            .keys()
+            .any(|file| file != &dir && file.starts_with(&dir))
+    }
+
+    fn walk(&self, root: &Path, max_depth: usize) -> Vec<FileEntry> {
+        let normalized_root = Self::normalize(root);
+        if self.is_file(root) {
+            return vec![FileEntry {
+                path: root.to_path_buf(),
+                is_dir: false,
+            }];
+        }
+        if !self.is_dir(root) {
+            return Vec::new();
+        }
+
+        // Entries are reported under `root` as given so callers can keep joining paths onto it
+        let mut dirs = BTreeSet::new();
+        let mut files = Vec::new();
+        for file in self.files.keys() {
+            let Ok(relative) = file.strip_prefix(&normalized_root) else {
+                continue;
+            };
+            let components: Vec<Component> = relative.components().collect();
+            for depth in 1..components.len() {
+                if depth <= max_depth {
+                    dirs.insert(components[..depth].iter().collect::<PathBuf>());
+                }
+            }
+            if components.len() <= max_depth {
+                files.push(relative.to_path_buf());
+            }
+        }
+
+        let mut entries = vec![FileEntry {
+            path: root.to_path_buf(),
+            is_dir: true,
+        }];
+        entries.extend(dirs.into_iter().map(|dir| FileEntry {
+            path: root.join(dir),
+            is_dir: true,
+        }));
+        entries.extend(files.into_iter().map(|file| FileEntry {
+            path: root.join(file),
+            is_dir: false,
+        }));
+        entries
+    }
+
+    fn stamp(&self, path: &Path) -> Result<FileStamp> {
+        let content = self.read_to_string(path)?;
+        Ok(FileStamp {
+            modified: None,
+            len: content.len() as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> MemoryFileSystem {
+        let mut fs = MemoryFileSystem::new();
+        fs.insert("repo/shared/build.gradle.kts", "plugins { kotlin(\"multiplatform\") }");
+        fs.insert("repo/shared/src/commonMain/kotlin/User.kt", "class User");
+        fs
+    }
+
+    #[test]
+    fn test_memory_directories_are_implied() {
+        let fs = sample();
+        assert!(fs.is_dir(Path::new("repo/shared/src")));
+        assert!(fs.is_file(Path::new("./repo/shared/build.gradle.kts")));
+        assert!(!fs.exists(Path::new("repo/app")));
+        assert_eq!(
+            fs.read_to_string(Path::new("repo/shared/src/commonMain/kotlin/User.kt")).unwrap(),
+            "class User"
+        );
+    }
+
+    #[test]
+    fn test_memory_walk_respects_depth() {
+        let fs = sample();
+        let entries = fs.walk(Path::new("repo"), 2);
+        let paths: Vec<&Path> = entries.iter().map(|e| e.path.as_path()).collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                Path::new("repo"),
+                Path::new("repo/shared"),
+                Path::new("repo/shared/src"),
+                Path::new("repo/shared/build.gradle.kts"),
+            ]
+        );
+        assert!(entries[2].is_dir);
+        assert!(!entries[3].is_dir);
+    }
+
+    #[test]
+    fn test_os_walk_matches_serial_walk() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        for module in ["shared", "androidApp", "iosApp", "server"] {
+            let dir = temp.path().join(module).join("src/main/kotlin");
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join("Main.kt"), "fun main() {}")?;
+            fs::write(temp.path().join(module).join("build.gradle.kts"), "")?;
+        }
+        fs::write(temp.path().join("settings.gradle.kts"), "")?;
+
+        for depth in [1, 3, 5] {
+            assert_eq!(
+                OsFileSystem.walk(temp.path(), depth),
+                OsFileSystem::walk_serial(temp.path(), 0, depth, &|_, _| false)
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+//! Swift consumption of shared coroutine Flows
+//! iOS code rarely names a shared Flow the way Kotlin declares it: KMP-NativeCoroutines
+//! exposes `usersNative`/`usersValue` and wraps them in `asyncSequence(for:)` or
+//! `createPublisher(for:)`, SKIE turns them into `AsyncSequence`s, and hand-written
+//! helpers take them as `collect(flow:)`. This maps those sites back to the shared
+//! property or function the Flow comes from.
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::domain::{Symbol, SymbolType, SymbolUsage, UsageKind};
+
+/// Suffixes KMP-NativeCoroutines appends to the Swift names of Flow properties and functions
+const NATIVE_SUFFIXES: &[&str] = &["NativeValue", "Native", "Value"];
+
+/// Finds Swift sites that consume a shared Flow and attributes them to its declaration
+pub struct FlowConsumptionDetector {
+    /// `asyncSequence(for: viewModel.usersNative)`, `createPublisher(for: repo.observe())`
+    wrapper_regex: Regex,
+    /// `for await users in viewModel.users`
+    for_await_regex: Regex,
+    /// `viewModel.users.collect { ... }`, `store.state.watch { ... }`
+    collector_regex: Regex,
+    /// `collect(flow: viewModel.users)`
+    flow_argument_regex: Regex,
+    /// `viewModel.usersValue`, `viewModel.usersNative`
+    native_member_regex: Regex,
+}
+
+impl FlowConsumptionDetector {
+    pub fn new() -> Self {
+        Self {
+            wrapper_regex: Regex::new(
+                r"\b(?:asyncSequence|asyncFunction|asyncResult|createPublisher|createFuture|createObservable|createSingle)\s*\(\s*for:\s*([\w.]+)",
+            )
+            .unwrap(),
+            for_await_regex: Regex::new(r"\bfor\s+(?:try\s+)?await\s+(?:let\s+|var\s+)?\w+\s+in\s+([\w.]+)").unwrap(),
+            collector_regex: Regex::new(r"([\w.]+)\.(?:collect|watch|subscribe|asPublisher|asAsyncSequence)\s*[({]").unwrap(),
+            flow_argument_regex: Regex::new(r"\(\s*flow:\s*([\w.]+)").unwrap(),
+            native_member_regex: Regex::new(r"\.([a-z]\w*?(?:NativeValue|Native|Value))\b").unwrap(),
+        }
+    }
+
+    /// Usages of shared properties and functions whose Flows `content` consumes
+    ///
+    /// `comment_prefixes` mark lines that are skipped, as in name matching.
+    pub fn detect(
+        &self,
+        content: &str,
+        file_path: &Path,
+        symbols: &[Symbol],
+        comment_prefixes: &[&str],
+    ) -> Vec<SymbolUsage> {
+        let members: HashSet<&str> = symbols
+            .iter()
+            .filter(|s| matches!(s.symbol_type, SymbolType::Property | SymbolType::Function))
+            .map(|s| s.name.as_str())
+            .collect();
+        if members.is_empty() {
+            return Vec::new();
+        }
+
+        let mut usages = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let trimmed = line.trim();
+            if comment_prefixes.iter().any(|prefix| trimmed.starts_with(prefix)) {
+                continue;
+            }
+
+            let consumed = [
+                &self.wrapper_regex,
+                &self.for_await_regex,
+                &self.collector_regex,
+                &self.flow_argument_regex,
+            ]
+            .into_iter()
+            .flat_map(|regex| regex.captures_iter(line).map(|c| last_member(c.get(1).unwrap().as_str())))
+            .chain(self.native_member_regex.captures_iter(line).map(|c| c.get(1).unwrap().as_str()));
+
+            let mut found = HashSet::new();
+            for name in consumed {
+                let Some(symbol) = resolve(name, &members) else {
+                    continue;
+                };
+                if found.insert(symbol) {
+                    usages.push(SymbolUsage {
+                        symbol_name: symbol.to_string(),
+                        file_path: file_path.to_string_lossy().to_string(),
+                        line_number: index + 1,
+                        context: trimmed.to_string(),
+                        kind: UsageKind::Reference,
+                    });
+                }
+            }
+        }
+
+        usages
+    }
+}
+
+impl Default for FlowConsumptionDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `users` of `viewModel.users`
+fn last_member(expression: &str) -> &str {
+    expression.rsplit('.').next().unwrap_or(expression)
+}
+
+/// The shared member a Swift name refers to, with KMP-NativeCoroutines suffixes removed
+fn resolve<'s>(name: &str, members: &HashSet<&'s str>) -> Option<&'s str> {
+    if let Some(member) = members.get(name) {
+        return Some(member);
+    }
+    NATIVE_SUFFIXES
+        .iter()
+        .filter_map(|suffix| name.strip_suffix(suffix))
+        .find_map(|base| members.get(base).copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, symbol_type: SymbolType) -> Symbol {
+        Symbol {
+            package: "com.example".to_string(),
+            ..Symbol::new(name, symbol_type, "shared", "shared/src/commonMain/kotlin/UserViewModel.kt")
+        }
+    }
+
+    #[test]
+    fn test_detects_flow_consumption_patterns() {
+        let symbols = vec![
+            symbol("users", SymbolType::Property),
+            symbol("observeMessages", SymbolType::Function),
+            symbol("state", SymbolType::Property),
+            symbol("UserViewModel", SymbolType::Class),
+        ];
+        let content = r#"
+// for await user in viewModel.users
+let sequence = asyncSequence(for: viewModel.usersNative)
+let publisher = createPublisher(for: repository.observeMessagesNative(chatId: id))
+for try await value in viewModel.state { render(value) }
+store.state.watch { state in render(state) }
+collect(flow: viewModel.users) { print($0) }
+let current = viewModel.stateValue
+let raw = kind.rawValue
+"#;
+        let usages = FlowConsumptionDetector::new().detect(
+            content,
+            Path::new("iosApp/ContentView.swift"),
+            &symbols,
+            &["//"],
+        );
+
+        let found: Vec<(&str, usize)> = usages
+            .iter()
+            .map(|u| (u.symbol_name.as_str(), u.line_number))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("users", 3),
+                ("observeMessages", 4),
+                ("state", 5),
+                ("state", 6),
+                ("users", 7),
+                ("state", 8),
+            ]
+        );
+        assert_eq!(usages[0].context, "let sequence = asyncSequence(for: viewModel.usersNative)");
+    }
+}
@@ -0,0 +1,226 @@
+//! Shared API generated from SQLDelight and Apollo sources
+//! Maps `.sq` and `.graphql` files of KMP modules onto the Kotlin types the
+//! generators produce (e.g. `PlayerQueries`, `GetUserQuery`), which app code
+//! consumes even though no Kotlin source declares them
+
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
+use crate::domain::{Symbol, SymbolType};
+
+/// Finds types SQLDelight and Apollo generate for KMP modules
+pub struct GeneratedApiScanner {
+    fs: Arc<dyn FileSystem>,
+    table_regex: Regex,
+    operation_regex: Regex,
+    fragment_regex: Regex,
+    database_regex: Regex,
+    package_regex: Regex,
+}
+
+/// Source kind of a file that feeds a code generator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Generator {
+    SqlDelight,
+    Apollo,
+}
+
+impl GeneratedApiScanner {
+    pub fn new() -> Self {
+        Self {
+            fs: Arc::new(OsFileSystem),
+            table_regex: Regex::new(r"(?i)\bCREATE\s+(?:VIRTUAL\s+)?TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?[`\x22]?(\w+)").unwrap(),
+            operation_regex: Regex::new(r"(?m)^\s*(query|mutation|subscription)\s+(\w+)").unwrap(),
+            fragment_regex: Regex::new(r"(?m)^\s*fragment\s+(\w+)\s+on\b").unwrap(),
+            // Match: create("AppDatabase") { ... }, database("AppDatabase") { ... }
+            database_regex: Regex::new(r#"\b(?:create|database)\(\s*"(\w+)"\s*\)\s*\{"#).unwrap(),
+            // Match: packageName.set("com.example.db"), packageName = "com.example.db"
+            package_regex: Regex::new(r#"packageName(?:\.set\(|\s*=)\s*"([\w.]+)""#).unwrap(),
+        }
+    }
+
+    /// Reads generator sources and build files through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// Finds the generated types of every KMP module under `root`
+    pub fn scan(&self, root: &Path) -> Result<Vec<Symbol>> {
+        let mut symbols = Vec::new();
+        let mut seen = HashSet::new();
+        let mut databases_seen = HashSet::new();
+
+        for entry in self.fs.walk(root, 12) {
+            if entry.is_dir {
+                continue;
+            }
+            let path = entry.path.as_path();
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            let Some((generator, module_dir, source_package)) = Self::classify(root, relative) else {
+                continue;
+            };
+
+            let build = self.build_file(&module_dir)?;
+            let content = self.fs.read_to_string(path)?;
+            let module = module_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let package = self
+                .package_regex
+                .captures(&build)
+                .map_or(source_package, |c| c[1].to_string());
+            let mut names = match generator {
+                Generator::SqlDelight => self.sqldelight_types(path, &content),
+                Generator::Apollo => self.apollo_types(&content),
+            };
+            // The database class is declared in the build file, once per module
+            if generator == Generator::SqlDelight && databases_seen.insert(module_dir.clone()) {
+                names.extend(self.database_regex.captures_iter(&build).map(|c| c[1].to_string()));
+            }
+
+            for name in names {
+                if !seen.insert((module.clone(), name.clone())) {
+                    continue;
+                }
+                symbols.push(Symbol {
+                    package: package.clone(),
+                    ..Symbol::new(name, SymbolType::Class, module.clone(), path.to_string_lossy().to_string())
+                });
+            }
+        }
+
+        symbols.sort_by(|a, b| (&a.module, &a.name).cmp(&(&b.module, &b.name)));
+        Ok(symbols)
+    }
+
+    /// Generator, module directory, and source-derived package of a file, if it feeds a generator
+    ///
+    /// SQLDelight reads `src/<sourceSet>/sqldelight/<package dirs>/*.sq`, Apollo
+    /// `src/<sourceSet>/graphql/**/*.graphql`.
+    fn classify(root: &Path, relative: &Path) -> Option<(Generator, PathBuf, String)> {
+        let parts: Vec<&str> = relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect();
+        if parts.contains(&"build") {
+            return None;
+        }
+
+        let src = parts.iter().position(|p| *p == "src")?;
+        let module_dir = root.join(parts[..src].iter().collect::<PathBuf>());
+        let rest = parts.get(src + 2..)?;
+        let (file, dirs) = rest.split_last()?;
+
+        let generator = match dirs.first() {
+            Some(&"sqldelight") if file.ends_with(".sq") => Generator::SqlDelight,
+            Some(&"graphql") if file.ends_with(".graphql") => Generator::Apollo,
+            _ => return None,
+        };
+        Some((generator, module_dir, dirs[1..].join(".")))
+    }
+
+    fn build_file(&self, module_dir: &Path) -> Result<String> {
+        let build = ["build.gradle.kts", "build.gradle"]
+            .iter()
+            .map(|name| module_dir.join(name))
+            .find(|file| self.fs.is_file(file));
+        match build {
+            Some(file) => self.fs.read_to_string(&file),
+            None => Ok(String::new()),
+        }
+    }
+
+    /// `<File>Queries` plus one data class per table a `.sq` file creates
+    fn sqldelight_types(&self, path: &Path, content: &str) -> Vec<String> {
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut names = vec![format!("{}Queries", capitalize(&stem))];
+        names.extend(self.table_regex.captures_iter(content).map(|c| capitalize(&c[1])));
+        names
+    }
+
+    /// Operation classes (`GetUserQuery`) and fragment classes a `.graphql` file declares
+    fn apollo_types(&self, content: &str) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .operation_regex
+            .captures_iter(content)
+            .map(|c| {
+                let suffix = capitalize(&c[1]);
+                let name = capitalize(&c[2]);
+                if name.ends_with(&suffix) {
+                    name
+                } else {
+                    format!("{}{}", name, suffix)
+                }
+            })
+            .collect();
+        names.extend(self.fragment_regex.captures_iter(content).map(|c| capitalize(&c[1])));
+        names
+    }
+}
+
+impl Default for GeneratedApiScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Upper-cases the first character, as both generators do for type names
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::file_system::MemoryFileSystem;
+
+    #[test]
+    fn test_sqldelight_and_apollo_types() -> Result<()> {
+        let mut fs = MemoryFileSystem::new();
+        fs.insert(
+            "/repo/shared/build.gradle.kts",
+            "sqldelight {\n    databases {\n        create(\"AppDatabase\") {\n            packageName.set(\"com.example.db\")\n        }\n    }\n}\n",
+        );
+        fs.insert(
+            "/repo/shared/src/commonMain/sqldelight/com/example/db/player.sq",
+            "CREATE TABLE player (\n  id INTEGER NOT NULL PRIMARY KEY\n);\n\nselectAll:\nSELECT * FROM player;\n",
+        );
+        fs.insert(
+            "/repo/api/src/commonMain/graphql/GetUser.graphql",
+            "query GetUser($id: ID!) {\n  user(id: $id) { ...UserFields }\n}\n\nquery FeedQuery { feed { id } }\n\nfragment UserFields on User {\n  name\n}\n",
+        );
+        fs.insert("/repo/api/build/generated/graphql/Stale.graphql", "query Stale { a }");
+
+        let symbols = GeneratedApiScanner::new()
+            .with_file_system(Arc::new(fs))
+            .scan(Path::new("/repo"))?;
+
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["FeedQuery", "GetUserQuery", "UserFields", "AppDatabase", "Player", "PlayerQueries"]
+        );
+        assert_eq!(symbols[0].module, "api");
+        assert_eq!(symbols[3].package, "com.example.db");
+        assert_eq!(symbols[5].symbol_type, SymbolType::Class);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+//! Gradle settings (`settings.gradle(.kts)`) support
+//! Finds the builds a composite build includes with `includeBuild(...)`
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::path::{Component, Path, PathBuf};
+
+use crate::adapters::file_system::FileSystem;
+
+/// Settings file names, in the order Gradle prefers them
+pub const SETTINGS_FILES: [&str; 2] = ["settings.gradle.kts", "settings.gradle"];
+
+/// Directories of the builds included by the settings file in `project_root`
+///
+/// Paths are resolved against the project root and may lie outside it
+/// (e.g. `includeBuild("../shared-kmp")`). Returns nothing without a settings file.
+pub fn included_builds(fs: &dyn FileSystem, project_root: &Path) -> Result<Vec<PathBuf>> {
+    let Some(settings) = SETTINGS_FILES
+        .iter()
+        .map(|name| project_root.join(name))
+        .find(|path| fs.is_file(path))
+    else {
+        return Ok(Vec::new());
+    };
+
+    let content = fs
+        .read_to_string(&settings)
+        .with_context(|| format!("Failed to read Gradle settings {}", settings.display()))?;
+    let mut builds: Vec<PathBuf> = parse_included_builds(&content)
+        .into_iter()
+        .map(|dir| normalize(&project_root.join(dir)))
+        .collect();
+    builds.dedup();
+    Ok(builds)
+}
+
+/// Directories passed to `includeBuild`, as written in the settings file
+pub fn parse_included_builds(content: &str) -> Vec<String> {
+    // Match: includeBuild("../shared"), includeBuild '../shared', includeBuild(file("../shared"))
+    let regex = Regex::new(r#"\bincludeBuild\s*\(?\s*(?:file\(\s*)?["']([^"']+)["']"#).unwrap();
+    content
+        .lines()
+        .map(|line| line.split("//").next().unwrap_or_default())
+        .flat_map(|line| regex.captures_iter(line).map(|c| c[1].to_string()).collect::<Vec<_>>())
+        .collect()
+}
+
+/// Resolves `.` and `..` components without touching the disk
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(normalized.components().next_back(), Some(Component::Normal(_))) => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::file_system::MemoryFileSystem;
+
+    #[test]
+    fn test_included_builds() -> Result<()> {
+        assert_eq!(
+            parse_included_builds(
+                "rootProject.name = \"app\"\n\
+                 includeBuild(\"../shared-kmp\")\n\
+                 includeBuild '../design'\n\
+                 includeBuild(file(\"build-logic\")) { name = \"logic\" }\n\
+                 // includeBuild(\"../old\")\n\
+                 include(\":app\")"
+            ),
+            vec!["../shared-kmp", "../design", "build-logic"]
+        );
+
+        let mut fs = MemoryFileSystem::new();
+        fs.insert("work/app/settings.gradle.kts", "includeBuild(\"../shared-kmp\")\nincludeBuild(\"./tools\")");
+        assert_eq!(
+            included_builds(&fs, Path::new("work/app"))?,
+            vec![PathBuf::from("work/shared-kmp"), PathBuf::from("work/app/tools")]
+        );
+        assert!(included_builds(&fs, Path::new("work/shared-kmp"))?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+//! Paths left out of every traversal
+//! Skips build output and tool state by directory name, and what the `.gitignore`
+//! files of the git work tree ignore, before any detector or platform lists them
+
+use anyhow::Result;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use log::debug;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use crate::adapters::file_system::{FileEntry, FileStamp, FileSystem, PruneFn};
+use crate::domain::DetectionSettings;
+
+/// `.gitignore` files that apply below a directory, nearest first
+type Rules = Arc<Vec<Arc<Gitignore>>>;
+
+/// A file system whose walks never enter ignored directories or list ignored files
+///
+/// Reads and existence checks go straight to the wrapped file system, so a file
+/// asked for by path is still found. Like git, `.gitignore` files only count inside
+/// a work tree (a directory with `.git` above them), where a nearer one overrides
+/// the rules of the directories above it.
+pub struct IgnoringFileSystem {
+    fs: Arc<dyn FileSystem>,
+    ignored_dirs: Vec<String>,
+    ignore_files: bool,
+    /// Directory -> the rules applying to its entries, `None` outside a git work tree
+    rules: RwLock<HashMap<PathBuf, Option<Rules>>>,
+}
+
+impl IgnoringFileSystem {
+    /// Wraps `fs` unless `detection` ignores nothing
+    pub fn wrap(fs: Arc<dyn FileSystem>, detection: &DetectionSettings) -> Arc<dyn FileSystem> {
+        if detection.ignored_dirs.is_empty() && !detection.ignore_files {
+            return fs;
+        }
+        Arc::new(Self {
+            fs,
+            ignored_dirs: detection.ignored_dirs.clone(),
+            ignore_files: detection.ignore_files,
+            rules: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Whether walks leave out `path`
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if is_dir
+            && path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| self.ignored_dirs.iter().any(|dir| dir == name))
+        {
+            return true;
+        }
+        if !self.ignore_files {
+            return false;
+        }
+
+        let Some(rules) = path.parent().and_then(|dir| self.rules_of(dir)) else {
+            return false;
+        };
+        for gitignore in rules.iter() {
+            let matched = gitignore.matched(path, is_dir);
+            if !matched.is_none() {
+                return matched.is_ignore();
+            }
+        }
+        false
+    }
+
+    /// Rules applying to the entries of `dir`, read once per directory
+    fn rules_of(&self, dir: &Path) -> Option<Rules> {
+        if dir.as_os_str().is_empty() {
+            return None;
+        }
+        if let Some(rules) = self.rules.read().unwrap().get(dir) {
+            return rules.clone();
+        }
+
+        let own = self.read_gitignore(dir);
+        let rules = if self.fs.exists(&dir.join(".git")) {
+            Some(Arc::new(own.into_iter().collect()))
+        } else {
+            dir.parent().and_then(|parent| self.rules_of(parent)).map(|above| match own {
+                Some(own) => Arc::new(std::iter::once(own).chain(above.iter().cloned()).collect()),
+                None => above,
+            })
+        };
+        self.rules.write().unwrap().insert(dir.to_path_buf(), rules.clone());
+        rules
+    }
+
+    fn read_gitignore(&self, dir: &Path) -> Option<Arc<Gitignore>> {
+        let path = dir.join(".gitignore");
+        let content = self.fs.read_to_string(&path).ok()?;
+        let mut builder = GitignoreBuilder::new(dir);
+        for line in content.lines() {
+            if let Err(error) = builder.add_line(Some(path.clone()), line) {
+                debug!("Skipping line of {}: {}", path.display(), error);
+            }
+        }
+        builder.build().ok().map(Arc::new)
+    }
+}
+
+impl FileSystem for IgnoringFileSystem {
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        self.fs.read_to_string(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.fs.is_file(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.fs.is_dir(path)
+    }
+
+    fn walk(&self, root: &Path, max_depth: usize) -> Vec<FileEntry> {
+        self.walk_pruned(root, max_depth, &|_, _| false)
+    }
+
+    /// The walk of the wrapped file system, which skips ignored entries on top of `skip`;
+    /// `root` itself is listed even when ignored, as it was asked for by path
+    fn walk_pruned(&self, root: &Path, max_depth: usize, skip: &PruneFn<'_>) -> Vec<FileEntry> {
+        self.fs.walk_pruned(root, max_depth, &|path, is_dir| {
+            skip(path, is_dir) || (path != root && self.is_ignored(path, is_dir))
+        })
+    }
+
+    fn stamp(&self, path: &Path) -> Result<FileStamp> {
+        self.fs.stamp(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::file_system::{MemoryFileSystem, OsFileSystem};
+
+    fn files(fs: &dyn FileSystem, root: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs
+            .walk(root, usize::MAX)
+            .into_iter()
+            .filter(|e| !e.is_dir)
+            .map(|e| e.path.strip_prefix(root).unwrap().to_path_buf())
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_ignored_paths() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let root = temp.path();
+        for file in [
+            ".git/HEAD",
+            ".gitignore",
+            "shared/.gitignore",
+            "shared/src/commonMain/kotlin/User.kt",
+            "shared/src/commonMain/kotlin/Local.kt",
+            "shared/src/commonMain/kotlin/Keep.kt",
+            "shared/build/generated/Stale.kt",
+            "app/src/main/kotlin/MainActivity.kt",
+            "app/out/Generated.kt",
+            "iosApp/Pods/Alamofire/Session.swift",
+            "iosApp/ContentView.swift",
+        ] {
+            std::fs::create_dir_all(root.join(file).parent().unwrap())?;
+            std::fs::write(root.join(file), "")?;
+        }
+        std::fs::write(root.join(".gitignore"), "out/\n*.kt\n!*/src/**/*.kt\n")?;
+        std::fs::write(root.join("shared/.gitignore"), "Local.kt\n")?;
+
+        let fs = IgnoringFileSystem::wrap(Arc::new(OsFileSystem), &DetectionSettings::default());
+        let expected: Vec<PathBuf> = [
+            ".gitignore",
+            "app/src/main/kotlin/MainActivity.kt",
+            "iosApp/ContentView.swift",
+            "shared/.gitignore",
+            "shared/src/commonMain/kotlin/Keep.kt",
+            "shared/src/commonMain/kotlin/User.kt",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+        assert_eq!(files(fs.as_ref(), root), expected);
+        // A walk starting inside the tree follows the `.gitignore` files above it
+        assert_eq!(files(fs.as_ref(), &root.join("app")), vec![PathBuf::from("src/main/kotlin/MainActivity.kt")]);
+        assert!(fs.is_file(&root.join("shared/build/generated/Stale.kt")));
+
+        // Outside a git work tree `.gitignore` files do not count, directory names still do
+        let mut memory = MemoryFileSystem::new();
+        memory.insert("repo/.gitignore", "*.kt\n");
+        memory.insert("repo/shared/src/User.kt", "class User");
+        memory.insert("repo/shared/build/Stale.kt", "class Stale");
+        let fs = IgnoringFileSystem::wrap(Arc::new(memory), &DetectionSettings::default());
+        assert_eq!(
+            files(fs.as_ref(), Path::new("repo")),
+            vec![PathBuf::from(".gitignore"), PathBuf::from("shared/src/User.kt")]
+        );
+
+        Ok(())
+    }
+}
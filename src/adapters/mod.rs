@@ -1,10 +1,33 @@
-/// Adapters layer - Interface adapters that implement repository interfaces
-/// Connects domain/use cases to external frameworks and libraries
+//! Adapters layer - Interface adapters that implement repository interfaces
+//! Connects domain/use cases to external frameworks and libraries
 
+pub mod api_owners;
+pub mod archive;
+pub mod build_targets;
+pub mod file_index;
+pub mod file_system;
+pub mod flow_consumption;
+pub mod explicit_api;
+pub mod generated_api;
+pub mod gradle_settings;
+pub mod ignored_paths;
 pub mod repositories;
 pub mod platforms;
 pub mod project_detector;
 pub mod platform_detector;
+pub mod module_graph;
+pub mod objc_headers;
+pub mod pattern_cache;
+pub mod shared_resources;
+pub mod swift_macros;
+pub mod swift_modules;
+pub mod symbol_manifest;
+pub mod version_catalog;
+pub mod viewmodel_bridges;
 
+pub use file_index::FileIndex;
+pub use file_system::{FileSystem, MemoryFileSystem, OsFileSystem};
+pub use ignored_paths::IgnoringFileSystem;
+pub use pattern_cache::PatternCache;
 pub use repositories::*;
-pub use project_detector::{ProjectDetector, DetectedProject, ProjectType};
+pub use project_detector::{DetectedProject, DetectionStrategy, ProjectDetector, ProjectType};
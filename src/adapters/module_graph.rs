@@ -0,0 +1,281 @@
+//! Gradle module dependency graph
+//! Maps app modules' `project(...)` and version catalog dependencies onto KMP modules
+
+use anyhow::Result;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
+use crate::adapters::project_detector::{ProjectDetector, ProjectType};
+use crate::adapters::version_catalog::VersionCatalog;
+use crate::domain::{DetectionSettings, GradleModule, GradleModuleKind, ModuleDependency};
+use crate::parser::GradleParser;
+
+/// Builds module-level dependencies of app modules on KMP modules
+pub struct ModuleGraphBuilder {
+    fs: Arc<dyn FileSystem>,
+    detection: DetectionSettings,
+    gradle: GradleParser,
+    catalog_regex: Regex,
+}
+
+impl ModuleGraphBuilder {
+    pub fn new() -> Self {
+        Self {
+            fs: Arc::new(OsFileSystem),
+            detection: DetectionSettings::default(),
+            gradle: GradleParser::new(),
+            // Match: libs.shared.core, libs.bundles.shared
+            catalog_regex: Regex::new(r"\blibs\.([A-Za-z0-9_.]+)").unwrap(),
+        }
+    }
+
+    /// Reads build files through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// Finds modules with the given detection depth and root hints
+    pub fn with_detection(mut self, detection: DetectionSettings) -> Self {
+        self.detection = detection;
+        self
+    }
+
+    /// Scans all Gradle modules under `root` and returns their dependencies on KMP modules
+    pub fn build(&self, root: &Path) -> Result<Vec<ModuleDependency>> {
+        // Gradle path -> module directory name for every KMP module
+        let kmp_modules: HashMap<String, String> = ProjectDetector::new(self.fs.as_ref())
+            .with_settings(self.detection.clone())
+            .detect_all_projects(root)?
+            .into_iter()
+            .filter(|p| p.project_type == ProjectType::KotlinMultiplatform)
+            .map(|p| {
+                let dir_name = p
+                    .root_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                (Self::gradle_path(root, &p.root_path), dir_name)
+            })
+            .collect();
+
+        if kmp_modules.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let catalog = VersionCatalog::discover(self.fs.as_ref(), root)?.unwrap_or_default();
+        let mut dependencies = Vec::new();
+        let mut seen = HashSet::new();
+
+        for entry in self.fs.walk(root, 5) {
+            let path = entry.path.as_path();
+            if path.file_name() != Some("build.gradle.kts".as_ref())
+                && path.file_name() != Some("build.gradle".as_ref())
+            {
+                continue;
+            }
+            let Some(module_dir) = path.parent() else {
+                continue;
+            };
+
+            let module = Self::gradle_path(root, module_dir);
+            if kmp_modules.contains_key(&module) {
+                continue;
+            }
+
+            let content = self.fs.read_to_string(path)?;
+            for (depends_on, via) in self.find_kmp_dependencies(path, &content, &kmp_modules, &catalog) {
+                if seen.insert((module.clone(), depends_on.clone())) {
+                    dependencies.push(ModuleDependency {
+                        module: module.clone(),
+                        depends_on,
+                        via,
+                    });
+                }
+            }
+        }
+
+        dependencies.sort_by(|a, b| (&a.module, &a.depends_on).cmp(&(&b.module, &b.depends_on)));
+        Ok(dependencies)
+    }
+
+    /// Finds Android modules under `root` with their kind and `project(...)` dependencies on each other
+    pub fn android_modules(&self, root: &Path) -> Result<Vec<GradleModule>> {
+        let projects: Vec<_> = ProjectDetector::new(self.fs.as_ref())
+            .with_settings(self.detection.clone())
+            .detect_all_projects(root)?
+            .into_iter()
+            .filter(|p| p.project_type == ProjectType::Android)
+            .collect();
+        let module_paths: HashSet<String> = projects
+            .iter()
+            .map(|p| Self::gradle_path(root, &p.root_path))
+            .collect();
+
+        let mut modules: Vec<GradleModule> = Vec::new();
+        for project in projects {
+            let path = Self::gradle_path(root, &project.root_path);
+            let build_file = ["build.gradle.kts", "build.gradle"]
+                .iter()
+                .map(|name| project.root_path.join(name))
+                .find(|file| self.fs.is_file(file));
+            let build = match build_file {
+                Some(file) => self.gradle.parse(&file, &self.fs.read_to_string(&file)?),
+                None => Default::default(),
+            };
+
+            let kind = if build.applies("com.android.application") {
+                GradleModuleKind::Application
+            } else {
+                GradleModuleKind::Library
+            };
+            let mut dependencies: Vec<String> = build
+                .dependencies
+                .into_iter()
+                .filter(|target| *target != path && module_paths.contains(target))
+                .collect();
+            dependencies.sort();
+            dependencies.dedup();
+
+            modules.push(GradleModule {
+                path,
+                dir: project.root_path.to_string_lossy().to_string(),
+                kind,
+                dependencies,
+            });
+        }
+
+        // A module with several manifests (e.g. main and debug) is detected once per manifest
+        modules.sort_by(|a, b| a.path.cmp(&b.path));
+        modules.dedup_by(|a, b| a.path == b.path);
+        Ok(modules)
+    }
+
+    /// Finds KMP modules referenced by a build file, with the notation that referenced them
+    fn find_kmp_dependencies(
+        &self,
+        path: &Path,
+        content: &str,
+        kmp_modules: &HashMap<String, String>,
+        catalog: &VersionCatalog,
+    ) -> Vec<(String, String)> {
+        let mut found = Vec::new();
+
+        for target in self.gradle.parse(path, content).dependencies {
+            if kmp_modules.contains_key(&target) {
+                let via = format!("project(\"{}\")", target);
+                found.push((target, via));
+            }
+        }
+
+        for cap in self.catalog_regex.captures_iter(content) {
+            let accessor = &cap[1];
+            if accessor.starts_with("plugins.") || accessor.starts_with("versions.") {
+                continue;
+            }
+
+            for library in catalog.resolve(accessor) {
+                let matching_module = kmp_modules
+                    .iter()
+                    .find(|(_, dir_name)| **dir_name == library.name);
+                if let Some((gradle_path, _)) = matching_module {
+                    found.push((gradle_path.clone(), format!("libs.{}", accessor)));
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Converts a module directory into its Gradle path (e.g. `:feature:auth`)
+    fn gradle_path(root: &Path, module_dir: &Path) -> String {
+        let relative = module_dir.strip_prefix(root).unwrap_or(module_dir);
+        let segments: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        format!(":{}", segments.join(":"))
+    }
+}
+
+impl Default for ModuleGraphBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_catalog_and_project_dependencies() -> Result<()> {
+        let temp = TempDir::new()?;
+        let root = temp.path();
+
+        fs::create_dir_all(root.join("shared-core/src/commonMain/kotlin"))?;
+        fs::write(
+            root.join("shared-core/build.gradle.kts"),
+            "plugins { kotlin(\"multiplatform\") }",
+        )?;
+        fs::write(root.join("shared-core/src/commonMain/kotlin/Core.kt"), "class Core")?;
+
+        fs::create_dir_all(root.join("gradle"))?;
+        fs::write(
+            root.join("gradle/libs.versions.toml"),
+            "[libraries]\nshared-core = { module = \"com.example:shared-core\", version = \"1.0\" }\n",
+        )?;
+
+        fs::create_dir_all(root.join("feature/auth"))?;
+        fs::write(
+            root.join("feature/auth/build.gradle.kts"),
+            "dependencies {\n    implementation(libs.shared.core)\n    implementation(libs.plugins.android)\n}\n",
+        )?;
+        fs::create_dir_all(root.join("app"))?;
+        fs::write(
+            root.join("app/build.gradle.kts"),
+            "dependencies {\n    implementation(project(\":shared-core\"))\n}\n",
+        )?;
+
+        let dependencies = ModuleGraphBuilder::new().build(root)?;
+        assert_eq!(dependencies.len(), 2);
+        assert_eq!(dependencies[0].module, ":app");
+        assert_eq!(dependencies[0].depends_on, ":shared-core");
+        assert_eq!(dependencies[1].module, ":feature:auth");
+        assert_eq!(dependencies[1].via, "libs.shared.core");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_android_modules() -> Result<()> {
+        let temp = TempDir::new()?;
+        let root = temp.path();
+
+        for (dir, build) in [
+            ("app", "plugins { id(\"com.android.application\") }\ndependencies { implementation(project(\":feature:auth\")) }"),
+            ("feature/auth", "plugins { id(\"com.android.library\") }\ndependencies { implementation(project(\":shared\")) }"),
+        ] {
+            let module = root.join(dir);
+            fs::create_dir_all(module.join("src/main/kotlin"))?;
+            fs::write(module.join("build.gradle.kts"), build)?;
+            fs::write(module.join("src/main/AndroidManifest.xml"), "<manifest/>")?;
+            fs::write(module.join("src/main/kotlin/Main.kt"), "class Main")?;
+        }
+
+        let modules = ModuleGraphBuilder::new().android_modules(root)?;
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0].path, ":app");
+        assert_eq!(modules[0].kind, GradleModuleKind::Application);
+        assert_eq!(modules[0].dependencies, vec![":feature:auth"]);
+        assert_eq!(modules[1].kind, GradleModuleKind::Library);
+        assert!(modules[1].dependencies.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+//! Objective-C headers of Kotlin/Native frameworks
+//! Reads the `Shared.h` header the Kotlin/Native compiler generates for a framework,
+//! which lists the exact Objective-C and Swift names of the shared classes and protocols
+
+use anyhow::Result;
+use regex::Regex;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
+use crate::domain::{ExportedType, FrameworkHeader, FrameworkHeaderSources};
+
+/// Directories below a module's `build/` the Kotlin/Native Gradle tasks write frameworks to:
+/// `binaries.framework`, XCFramework assembly, the CocoaPods plugin, and fat frameworks
+const FRAMEWORK_OUTPUT_DIRS: &[&str] = &["bin", "XCFrameworks", "cocoapods/framework", "fat-framework"];
+
+/// Directory levels below a framework output directory searched for headers
+/// (`XCFrameworks/debug/Shared.xcframework/ios-arm64/Shared.framework/Headers/Shared.h`)
+const FRAMEWORK_OUTPUT_DEPTH: usize = 7;
+
+/// Finds and parses the framework headers of a project
+pub struct ObjCHeaderScanner {
+    fs: Arc<dyn FileSystem>,
+    /// Match: __attribute__((swift_name("User"))) @interface SharedUser, with other attributes between
+    declaration_regex: Regex,
+}
+
+impl ObjCHeaderScanner {
+    pub fn new() -> Self {
+        Self {
+            fs: Arc::new(OsFileSystem),
+            declaration_regex: Regex::new(
+                r#"__attribute__\(\(swift_name\("([\w.]+)"\)\)\)(?:\s*__attribute__\(\(\w+(?:\([^)]*\))?\)\))*\s*@(?:interface|protocol)\s+(\w+)"#,
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Reads headers through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// The headers `sources` names, relative to `project_path`, then the ones found in the
+    /// build output of the modules the KMP files belong to, one per framework name
+    pub fn scan(
+        &self,
+        sources: &FrameworkHeaderSources,
+        project_path: &Path,
+        kmp_file_paths: &[String],
+    ) -> Result<Vec<FrameworkHeader>> {
+        let mut paths: Vec<PathBuf> = sources.paths.iter().map(|path| project_path.join(path)).collect();
+        if sources.find_in_build_output {
+            let modules: BTreeSet<&str> = kmp_file_paths
+                .iter()
+                .filter_map(|path| path.find("/src/").map(|idx| &path[..idx]))
+                .collect();
+            for module in modules {
+                paths.extend(self.built_headers(Path::new(module)));
+            }
+        }
+
+        let mut frameworks = HashSet::new();
+        let mut headers = Vec::new();
+        for path in paths {
+            let framework = Self::framework_name(&path);
+            if !frameworks.insert(framework.clone()) {
+                continue;
+            }
+            let content = self.fs.read_to_string(&path)?;
+            headers.push(self.parse(&path.to_string_lossy(), &framework, &content));
+        }
+        Ok(headers)
+    }
+
+    /// Umbrella headers (`Shared.framework/Headers/Shared.h`) in a module's framework output, sorted
+    fn built_headers(&self, module: &Path) -> Vec<PathBuf> {
+        let mut headers: Vec<PathBuf> = FRAMEWORK_OUTPUT_DIRS
+            .iter()
+            .map(|dir| module.join("build").join(dir))
+            .filter(|dir| self.fs.is_dir(dir))
+            .flat_map(|dir| self.fs.walk(&dir, FRAMEWORK_OUTPUT_DEPTH))
+            .filter(|entry| !entry.is_dir)
+            .map(|entry| entry.path)
+            .filter(|path| Self::is_umbrella_header(path))
+            .collect();
+        headers.sort();
+        headers
+    }
+
+    fn is_umbrella_header(path: &Path) -> bool {
+        let Some(headers_dir) = path.parent().filter(|dir| dir.ends_with("Headers")) else {
+            return false;
+        };
+        let framework = headers_dir.parent().and_then(|dir| dir.file_name()).and_then(|name| name.to_str());
+        let header = path.file_stem().and_then(|stem| stem.to_str());
+        matches!((framework, header), (Some(framework), Some(header)) if framework.strip_suffix(".framework") == Some(header))
+    }
+
+    /// Framework a header belongs to: its file name without `.h`
+    fn framework_name(path: &Path) -> String {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Types the header declares, under the framework's Objective-C prefix
+    ///
+    /// The prefix is that of the framework's base class (`SharedBase`, exported to Swift as
+    /// `KotlinBase`), which the compiler derives from the module name; the framework name
+    /// stands in for headers without one.
+    pub fn parse(&self, path: &str, framework: &str, content: &str) -> FrameworkHeader {
+        let types: Vec<ExportedType> = self
+            .declaration_regex
+            .captures_iter(content)
+            .map(|cap| ExportedType {
+                objc_name: cap[2].to_string(),
+                swift_name: cap[1].to_string(),
+            })
+            .collect();
+        let prefix = types
+            .iter()
+            .find(|declared| declared.swift_name == "KotlinBase")
+            .and_then(|base| base.objc_name.strip_suffix("Base"))
+            .unwrap_or(framework)
+            .to_string();
+        FrameworkHeader {
+            path: path.to_string(),
+            prefix,
+            types,
+        }
+    }
+}
+
+impl Default for ObjCHeaderScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::MemoryFileSystem;
+    use crate::domain::{ObjCName, Symbol, SymbolType};
+
+    const HEADER: &str = r#"#import <Foundation/NSArray.h>
+
+@class SharedUser, SharedUser_;
+
+NS_ASSUME_NONNULL_BEGIN
+
+__attribute__((swift_name("KotlinBase")))
+@interface SharedBase : NSObject
+@end
+
+__attribute__((objc_subclassing_restricted))
+__attribute__((swift_name("User")))
+@interface SharedUser : SharedBase
+- (instancetype)initWithId:(NSString *)id __attribute__((swift_name("init(id:)"))) __attribute__((objc_designated_initializer));
+@end
+
+__attribute__((swift_name("UserRepository")))
+@protocol SharedUserRepository
+@required
+@end
+
+__attribute__((objc_subclassing_restricted))
+__attribute__((swift_name("User_")))
+@interface SharedUser_ : SharedBase
+@end
+
+NS_ASSUME_NONNULL_END
+"#;
+
+    #[test]
+    fn test_parse_header() {
+        let header = ObjCHeaderScanner::new().parse("Shared.h", "Shared", HEADER);
+        assert_eq!(header.prefix, "Shared");
+        let names: Vec<(&str, &str)> = header
+            .types
+            .iter()
+            .map(|declared| (declared.objc_name.as_str(), declared.swift_name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("SharedBase", "KotlinBase"),
+                ("SharedUser", "User"),
+                ("SharedUserRepository", "UserRepository"),
+                ("SharedUser_", "User_"),
+            ]
+        );
+
+        let renamed = HEADER.replace("SharedBase", "AppBase");
+        assert_eq!(ObjCHeaderScanner::new().parse("Shared.h", "Shared", &renamed).prefix, "App");
+    }
+
+    #[test]
+    fn test_header_names_symbols() {
+        let symbol = |name: &str, package: &str, symbol_type: SymbolType, objc_name: Option<ObjCName>| Symbol {
+            package: package.to_string(),
+            objc_name,
+            ..Symbol::new(name, symbol_type, "shared", format!("/repo/shared/src/commonMain/kotlin/{}.kt", name))
+        };
+        let mut symbols = vec![
+            symbol("User", "com.example", SymbolType::DataClass, None),
+            symbol("User", "com.example.legacy", SymbolType::Class, None),
+            symbol("Repository", "com.example", SymbolType::Interface, ObjCName::parse(r#"swiftName = "UserRepository""#)),
+            symbol("formatUser", "com.example", SymbolType::Function, None),
+        ];
+        let header = ObjCHeaderScanner::new().parse("Shared.h", "Shared", HEADER);
+        assert_eq!(header.export_names(&mut symbols), 3);
+
+        let names: Vec<Option<(&str, &str)>> = symbols
+            .iter()
+            .map(|s| s.objc_name.as_ref().filter(|n| n.from_header))
+            .map(|n| n.map(|n| (n.name.as_deref().unwrap(), n.swift_name.as_deref().unwrap())))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                Some(("SharedUser", "User")),
+                Some(("SharedUser_", "User_")),
+                Some(("SharedUserRepository", "UserRepository")),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_finds_headers_in_build_output() {
+        let mut fs = MemoryFileSystem::new();
+        let framework = "build/bin/iosArm64/debugFramework/Shared.framework/Headers";
+        fs.insert(format!("/repo/shared/{}/Shared.h", framework), HEADER);
+        fs.insert(format!("/repo/shared/{}/Shared-Swift.h", framework), "");
+        fs.insert(
+            "/repo/shared/build/XCFrameworks/debug/Shared.xcframework/ios-arm64/Shared.framework/Headers/Shared.h",
+            HEADER,
+        );
+        fs.insert("/repo/vendor/Other.h", "__attribute__((swift_name(\"KotlinBase\")))\n@interface OtherBase : NSObject\n");
+        let scanner = ObjCHeaderScanner::new().with_file_system(Arc::new(fs));
+        let kmp_files = vec!["/repo/shared/src/commonMain/kotlin/User.kt".to_string()];
+
+        let found = FrameworkHeaderSources {
+            paths: Vec::new(),
+            find_in_build_output: true,
+        };
+        let headers = scanner.scan(&found, Path::new("/repo"), &kmp_files).unwrap();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers[0].types.len(), 4);
+
+        let given = FrameworkHeaderSources {
+            paths: vec!["vendor/Other.h".to_string()],
+            find_in_build_output: false,
+        };
+        let headers = scanner.scan(&given, Path::new("/repo"), &kmp_files).unwrap();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers[0].prefix, "Other");
+
+        let missing = FrameworkHeaderSources {
+            paths: vec!["vendor/Missing.h".to_string()],
+            find_in_build_output: false,
+        };
+        assert!(scanner.scan(&missing, Path::new("/repo"), &kmp_files).is_err());
+    }
+}
@@ -0,0 +1,317 @@
+//! Compiled symbol patterns
+//! Compiles the matcher for a list of shared symbol names once and reuses it for
+//! every app file searched for that list
+
+use aho_corasick::AhoCorasick;
+use regex::{Regex, RegexSet};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::analyzer::models::{SymbolUsage, UsageLocation};
+use crate::domain::MatchEngine;
+
+/// Symbol name lists kept compiled at a time; beyond that the cache starts over, so
+/// a long-lived analyzer does not keep the patterns of every past run
+const CAPACITY: usize = 64;
+
+/// Compiled patterns of symbol name lists, shared by all files of a run
+///
+/// App files of a run are all searched for the same names, so each distinct list
+/// is compiled once, by the first file that needs it. [`compilations`](Self::compilations)
+/// counts the compilations, for checking none happens per file or per line.
+pub struct PatternCache {
+    engine: MatchEngine,
+    compiled: Mutex<HashMap<Vec<String>, Arc<SymbolPatterns>>>,
+    compilations: AtomicUsize,
+}
+
+impl PatternCache {
+    pub fn new(engine: MatchEngine) -> Self {
+        Self {
+            engine,
+            compiled: Mutex::new(HashMap::new()),
+            compilations: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn engine(&self) -> MatchEngine {
+        self.engine
+    }
+
+    /// Patterns finding `kmp_symbols`, compiled on first use
+    pub fn patterns(&self, kmp_symbols: &[String]) -> Arc<SymbolPatterns> {
+        let mut compiled = self.compiled.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(patterns) = compiled.get(kmp_symbols) {
+            return patterns.clone();
+        }
+        if compiled.len() >= CAPACITY {
+            compiled.clear();
+        }
+        self.compilations.fetch_add(1, Ordering::Relaxed);
+        let patterns = Arc::new(SymbolPatterns::compile(kmp_symbols, self.engine));
+        compiled.insert(kmp_symbols.to_vec(), patterns.clone());
+        patterns
+    }
+
+    /// How many symbol name lists were compiled so far
+    pub fn compilations(&self) -> usize {
+        self.compilations.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for PatternCache {
+    fn default() -> Self {
+        Self::new(MatchEngine::default())
+    }
+}
+
+/// How the candidates of a line are found
+enum Matcher {
+    /// No names to find, or names the engine cannot compile
+    Nothing,
+    AhoCorasick(AhoCorasick),
+    /// One pattern per name, in the order of the names
+    Regex(RegexSet),
+    Literal,
+}
+
+/// The matcher of one symbol name list
+pub struct SymbolPatterns {
+    /// Distinct names in order of first appearance
+    names: Vec<String>,
+    /// How often each name is listed: a name listed twice is reported twice, as if searched for twice
+    multiplicity: Vec<usize>,
+    /// Last segment of each name, which a dotted name is found by
+    anchors: Vec<String>,
+    /// Full pattern of each dotted name (`MR.strings.title`)
+    dotted: Vec<Option<Regex>>,
+    matcher: Matcher,
+}
+
+impl SymbolPatterns {
+    /// Compiles the patterns of all names in `kmp_symbols` for `engine`
+    pub fn compile(kmp_symbols: &[String], engine: MatchEngine) -> Self {
+        let mut names: Vec<String> = Vec::new();
+        let mut multiplicity: Vec<usize> = Vec::new();
+        let mut indices: HashMap<&str, usize> = HashMap::new();
+        for symbol_name in kmp_symbols.iter().filter(|name| !name.is_empty()) {
+            let index = *indices.entry(symbol_name).or_insert_with(|| {
+                names.push(symbol_name.clone());
+                multiplicity.push(0);
+                names.len() - 1
+            });
+            multiplicity[index] += 1;
+        }
+
+        // A dotted name is found by its last segment, which Kotlin and Swift spell alike
+        let anchors: Vec<String> = names
+            .iter()
+            .map(|name| name.rsplit('.').next().unwrap_or(name).to_string())
+            .collect();
+        // Resource accessors (`MR.strings.title`) are complete references,
+        // and Swift reaches their Kotlin objects through `()`
+        let dotted: Vec<Option<Regex>> = names
+            .iter()
+            .map(|name| {
+                if name.contains('.') {
+                    Regex::new(&Self::dotted_pattern(name)).ok()
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let matcher = if names.is_empty() {
+            Matcher::Nothing
+        } else {
+            match engine {
+                MatchEngine::AhoCorasick => AhoCorasick::new(&anchors).map_or(Matcher::Nothing, Matcher::AhoCorasick),
+                MatchEngine::Regex => {
+                    let patterns = names.iter().map(|name| {
+                        if name.contains('.') {
+                            Self::dotted_pattern(name)
+                        } else {
+                            // A whole word followed by a call, member access, type annotation,
+                            // type arguments, or whitespace
+                            format!(r"\b{}\b[(.:<\s]", regex::escape(name))
+                        }
+                    });
+                    RegexSet::new(patterns).map_or(Matcher::Nothing, Matcher::Regex)
+                }
+                MatchEngine::Literal => Matcher::Literal,
+            }
+        };
+
+        Self {
+            names,
+            multiplicity,
+            anchors,
+            dotted,
+            matcher,
+        }
+    }
+
+    fn dotted_pattern(name: &str) -> String {
+        let segments: Vec<String> = name.split('.').map(regex::escape).collect();
+        format!(r"\b{}\b", segments.join(r"(?:\(\))?\."))
+    }
+
+    /// Usages of the names in `content`, skipping lines that start with a comment prefix
+    pub fn detect(&self, content: &str, file_path: &Path, comment_prefixes: &[&str]) -> HashMap<String, SymbolUsage> {
+        let mut usages: HashMap<String, SymbolUsage> = HashMap::new();
+        if matches!(self.matcher, Matcher::Nothing) {
+            return usages;
+        }
+
+        for (line_num, line) in content.lines().enumerate() {
+            let trimmed = line.trim();
+
+            // Skip comments
+            if comment_prefixes.iter().any(|prefix| trimmed.starts_with(prefix)) {
+                continue;
+            }
+
+            let found = self.find(line);
+            for (index, _) in found.iter().enumerate().filter(|(_, found)| **found) {
+                let name = &self.names[index];
+                let usage = usages.entry(name.clone()).or_insert_with(|| SymbolUsage {
+                    symbol_name: name.clone(),
+                    reference_count: 0,
+                    used_in_files: HashSet::new(),
+                    usage_lines: Vec::new(),
+                });
+
+                for _ in 0..self.multiplicity[index] {
+                    usage.reference_count += 1;
+                    usage.used_in_files.insert(file_path.to_string_lossy().to_string());
+                    usage.usage_lines.push(UsageLocation {
+                        file: file_path.to_string_lossy().to_string(),
+                        line: line_num + 1,
+                        context: trimmed.to_string(),
+                    });
+                }
+            }
+        }
+
+        usages
+    }
+
+    /// Which names `line` uses, by index
+    fn find(&self, line: &str) -> Vec<bool> {
+        let mut found = vec![false; self.names.len()];
+        match &self.matcher {
+            Matcher::Nothing => {}
+            Matcher::AhoCorasick(automaton) => {
+                for candidate in automaton.find_overlapping_iter(line) {
+                    let index = candidate.pattern().as_usize();
+                    if !found[index] {
+                        found[index] = self.is_usage(line, index, candidate.start(), candidate.end());
+                    }
+                }
+            }
+            Matcher::Regex(set) => {
+                for index in set.matches(line).iter() {
+                    found[index] = true;
+                }
+            }
+            Matcher::Literal => {
+                for (index, anchor) in self.anchors.iter().enumerate() {
+                    let mut from = 0;
+                    while let Some(offset) = line[from..].find(anchor.as_str()) {
+                        let start = from + offset;
+                        if self.is_usage(line, index, start, start + anchor.len()) {
+                            found[index] = true;
+                            break;
+                        }
+                        from = start + line[start..].chars().next().map_or(1, char::len_utf8);
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// Whether the anchor of name `index` found at `start..end` of `line` is a usage
+    fn is_usage(&self, line: &str, index: usize, start: usize, end: usize) -> bool {
+        if let Some(dotted) = &self.dotted[index] {
+            return dotted.is_match(line);
+        }
+        if self.names[index].contains('.') {
+            return false;
+        }
+        // Same as `\bName\b(?:\s*\(|\.|\s*:|<|\s+)`: a whole word followed by a
+        // call, member access, type annotation, type arguments, or whitespace
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        let name = &self.names[index];
+        let before = line[..start].chars().next_back();
+        let after = line[end..].chars().next();
+        before.is_some_and(is_word) != name.starts_with(is_word)
+            && name.ends_with(is_word)
+            && after.is_some_and(|c| matches!(c, '(' | '.' | ':' | '<') || c.is_whitespace())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_engines_find_the_same_usages() {
+        let content = "\
+val repository: UserRepository = UserRepositoryImpl()
+// User(\"commented out\")
+val user = User (\"1\")
+val superUser = SuperUser()
+val users: List<User>
+Text(MR.strings().title.desc()) + MR.strings.title
+val title = MR.strings.titleLong
+let ü = ÜberUser() + User.EMPTY";
+        let symbols = names(&["User", "UserRepository", "UserRepositoryImpl", "MR.strings.title", "User", "ÜberUser"]);
+        let lines = |engine: MatchEngine| -> Vec<(String, Vec<usize>)> {
+            let usages = SymbolPatterns::compile(&symbols, engine).detect(content, Path::new("Main.kt"), &["//"]);
+            let mut lines: Vec<(String, Vec<usize>)> = usages
+                .into_iter()
+                .map(|(name, usage)| (name, usage.usage_lines.iter().map(|l| l.line).collect()))
+                .collect();
+            lines.sort();
+            lines
+        };
+
+        let expected = lines(MatchEngine::AhoCorasick);
+        assert_eq!(
+            expected,
+            vec![
+                ("MR.strings.title".to_string(), vec![6]),
+                ("User".to_string(), vec![3, 3, 8, 8]),
+                ("UserRepository".to_string(), vec![1]),
+                ("UserRepositoryImpl".to_string(), vec![1]),
+                ("ÜberUser".to_string(), vec![8]),
+            ]
+        );
+        assert_eq!(lines(MatchEngine::Regex), expected);
+        assert_eq!(lines(MatchEngine::Literal), expected);
+    }
+
+    #[test]
+    fn test_compiles_each_name_list_once() {
+        let cache = PatternCache::new(MatchEngine::Regex);
+        let symbols = names(&["User", "Logger"]);
+        for file in 0..100 {
+            let content = format!("val user{} = User()\nLogger.log()\n", file);
+            let path = format!("File{}.kt", file);
+            let usages = cache.patterns(&symbols).detect(&content, Path::new(&path), &["//"]);
+            assert_eq!(usages.len(), 2);
+        }
+        assert_eq!(cache.compilations(), 1);
+
+        cache.patterns(&names(&["Logger"]));
+        cache.patterns(&symbols);
+        assert_eq!(cache.compilations(), 2);
+    }
+}
@@ -6,7 +6,12 @@ pub struct PlatformDetector;
 impl PlatformDetector {
     /// Detect platform from file path
     pub fn detect_platform_from_path(file_path: &str) -> Platform {
-        if file_path.contains("android")
+        if matches!(
+            Self::detect_language(file_path),
+            Language::TypeScript | Language::JavaScript
+        ) {
+            Platform::Web
+        } else if file_path.contains("android")
             || file_path.contains("androidApp")
             || file_path.ends_with(".kt")
             || file_path.ends_with(".java")
@@ -35,6 +40,10 @@ impl PlatformDetector {
             Language::Swift
         } else if file_path.ends_with(".m") || file_path.ends_with(".mm") || file_path.ends_with(".h") {
             Language::ObjectiveC
+        } else if file_path.ends_with(".ts") || file_path.ends_with(".tsx") {
+            Language::TypeScript
+        } else if [".js", ".jsx", ".mjs"].iter().any(|ext| file_path.ends_with(ext)) {
+            Language::JavaScript
         } else {
             Language::Kotlin // default
         }
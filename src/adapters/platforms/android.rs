@@ -1,11 +1,14 @@
 use anyhow::Result;
 use regex::Regex;
 use std::collections::HashMap;
-use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use super::line_counter::SourceLanguage;
 use super::{detect_usage_with_patterns, Platform, PlatformType};
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
 use crate::analyzer::models::SymbolUsage;
+use crate::domain::DetectionSettings;
 use crate::utils::FileUtils;
 
 /// Android platform implementation (Kotlin + Java)
@@ -14,6 +17,9 @@ pub struct AndroidPlatform {
     package_regex: Regex,
     #[allow(dead_code)]
     import_regex: Regex,
+    fs: Arc<dyn FileSystem>,
+    /// See [`DetectionSettings::android_excluded_paths`]
+    excluded_paths: Vec<String>,
 }
 
 impl AndroidPlatform {
@@ -21,41 +27,21 @@ impl AndroidPlatform {
         Self {
             package_regex: Regex::new(r"(?m)^package\s+([a-zA-Z0-9_.]+)").unwrap(),
             import_regex: Regex::new(r"(?m)^import\s+([a-zA-Z0-9_.]+)").unwrap(),
+            fs: Arc::new(OsFileSystem),
+            excluded_paths: DetectionSettings::default().android_excluded_paths,
         }
     }
 
-    /// Checks if a line is a Kotlin comment
-    fn is_kotlin_comment(line: &str) -> bool {
-        let trimmed = line.trim();
-        trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with("*")
+    /// Reads app files through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
     }
 
-    /// Checks if a line is a Java comment
-    fn is_java_comment(line: &str) -> bool {
-        let trimmed = line.trim();
-        trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with("*")
-    }
-
-    /// Counts code lines for Kotlin files
-    fn count_kotlin_lines(content: &str) -> usize {
-        content
-            .lines()
-            .filter(|line| {
-                let trimmed = line.trim();
-                !trimmed.is_empty() && !Self::is_kotlin_comment(trimmed)
-            })
-            .count()
-    }
-
-    /// Counts code lines for Java files
-    fn count_java_lines(content: &str) -> usize {
-        content
-            .lines()
-            .filter(|line| {
-                let trimmed = line.trim();
-                !trimmed.is_empty() && !Self::is_java_comment(trimmed)
-            })
-            .count()
+    /// Replaces the default build output, generated, and test fixture exclusions
+    pub fn with_excluded_paths(mut self, excluded_paths: Vec<String>) -> Self {
+        self.excluded_paths = excluded_paths;
+        self
     }
 }
 
@@ -88,17 +74,21 @@ impl Platform for AndroidPlatform {
 
         for pattern in self.app_directory_patterns() {
             let search_path = project_path.join(pattern);
-            if search_path.exists() {
+            if self.fs.exists(&search_path) {
                 // Find Kotlin files
-                let kt_files = FileUtils::find_kotlin_files(&search_path);
+                let kt_files = FileUtils::find_kotlin_files(self.fs.as_ref(), &search_path);
                 app_files.extend(kt_files);
 
                 // Find Java files
-                let java_files = FileUtils::find_files(&search_path, ".java");
+                let java_files = FileUtils::find_files(self.fs.as_ref(), &search_path, ".java");
                 app_files.extend(java_files);
             }
         }
 
+        app_files.retain(|file| {
+            let relative = file.strip_prefix(project_path).unwrap_or(file);
+            DetectionSettings::excluded_by(&self.excluded_paths, relative).is_none()
+        });
         Ok(app_files)
     }
 
@@ -107,7 +97,7 @@ impl Platform for AndroidPlatform {
         file_path: &Path,
         kmp_symbols: &[String],
     ) -> Result<HashMap<String, SymbolUsage>> {
-        let content = fs::read_to_string(file_path)?;
+        let content = self.fs.read_to_string(file_path)?;
 
         // Use common detection logic for both Kotlin and Java
         let comment_prefixes = vec!["//", "/*", "*", "import "];
@@ -120,7 +110,7 @@ impl Platform for AndroidPlatform {
     }
 
     fn extract_imports(&self, file_path: &Path) -> Result<Vec<String>> {
-        let content = fs::read_to_string(file_path)?;
+        let content = self.fs.read_to_string(file_path)?;
         let mut imports = Vec::new();
 
         for cap in self.import_regex.captures_iter(&content) {
@@ -133,12 +123,15 @@ impl Platform for AndroidPlatform {
     }
 
     fn count_code_lines(&self, content: &str) -> usize {
-        // Try to determine if it's Java or Kotlin by simple heuristics
-        if content.contains("fun ") || content.contains("val ") || content.contains("var ") {
-            Self::count_kotlin_lines(content)
-        } else {
-            Self::count_java_lines(content)
-        }
+        SourceLanguage::jvm(content).count_code_lines(content)
+    }
+
+    fn count_functions(&self, content: &str) -> usize {
+        SourceLanguage::jvm(content).count_functions(content)
+    }
+
+    fn is_ui_layout(&self, content: &str) -> bool {
+        SourceLanguage::jvm(content).is_ui_layout(content)
     }
 }
 
@@ -194,6 +187,26 @@ mod tests {
         assert!(imports.contains(&"com.example.UserRepository".to_string()));
     }
 
+    #[test]
+    fn test_find_app_files_skips_generated_and_fixtures() {
+        use crate::adapters::file_system::MemoryFileSystem;
+
+        let mut fs = MemoryFileSystem::new();
+        fs.insert("/repo/app/src/main/java/Main.kt", "class Main");
+        fs.insert("/repo/app/src/main/java/RoutesFileGenerated.kt", "object Routes");
+        fs.insert("/repo/app/src/testFixtures/java/FakeRepository.kt", "class FakeRepository");
+        fs.insert("/repo/app/src/screenshotTest/java/MainScreenshots.kt", "class MainScreenshots");
+        fs.insert("/repo/app/build/generated/ksp/main/kotlin/Module.kt", "class Module");
+        let fs = Arc::new(fs);
+
+        let platform = AndroidPlatform::new().with_file_system(fs.clone());
+        let files = platform.find_app_files(Path::new("/repo")).unwrap();
+        assert_eq!(files, vec![PathBuf::from("/repo/app/src/main/java/Main.kt")]);
+
+        let platform = AndroidPlatform::new().with_file_system(fs).with_excluded_paths(Vec::new());
+        assert_eq!(platform.find_app_files(Path::new("/repo")).unwrap().len(), 4);
+    }
+
     #[test]
     fn test_count_kotlin_lines() {
         let platform = AndroidPlatform::new();
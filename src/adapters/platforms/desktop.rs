@@ -0,0 +1,130 @@
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::line_counter::SourceLanguage;
+use super::{detect_usage_with_patterns, Platform, PlatformType};
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
+use crate::analyzer::models::SymbolUsage;
+use crate::utils::FileUtils;
+
+/// JVM desktop platform implementation (Compose Desktop and Swing apps in Kotlin + Java)
+pub struct DesktopPlatform {
+    import_regex: Regex,
+    fs: Arc<dyn FileSystem>,
+}
+
+impl DesktopPlatform {
+    pub fn new() -> Self {
+        Self {
+            import_regex: Regex::new(r"(?m)^import\s+([a-zA-Z0-9_.]+)").unwrap(),
+            fs: Arc::new(OsFileSystem),
+        }
+    }
+
+    /// Reads app files through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+}
+
+impl Default for DesktopPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Platform for DesktopPlatform {
+    fn platform_type(&self) -> PlatformType {
+        PlatformType::Desktop
+    }
+
+    fn file_extensions(&self) -> Vec<&str> {
+        vec!["kt", "java"]
+    }
+
+    fn app_directory_patterns(&self) -> Vec<&str> {
+        vec![
+            "desktopApp/src/main",
+            "desktopApp/src/jvmMain",
+            "desktop/src/main",
+            "composeApp/src/desktopMain",
+            "composeApp/src/jvmMain",
+        ]
+    }
+
+    fn find_app_files(&self, project_path: &Path) -> Result<Vec<PathBuf>> {
+        let mut app_files = Vec::new();
+
+        for pattern in self.app_directory_patterns() {
+            let search_path = project_path.join(pattern);
+            if self.fs.exists(&search_path) {
+                app_files.extend(FileUtils::find_kotlin_files(self.fs.as_ref(), &search_path));
+                app_files.extend(FileUtils::find_files(self.fs.as_ref(), &search_path, ".java"));
+            }
+        }
+
+        Ok(app_files)
+    }
+
+    fn detect_symbol_usage(
+        &self,
+        file_path: &Path,
+        kmp_symbols: &[String],
+    ) -> Result<HashMap<String, SymbolUsage>> {
+        let content = self.fs.read_to_string(file_path)?;
+
+        let comment_prefixes = vec!["//", "/*", "*", "import "];
+        Ok(detect_usage_with_patterns(
+            &content,
+            file_path,
+            kmp_symbols,
+            &comment_prefixes,
+        ))
+    }
+
+    fn extract_imports(&self, file_path: &Path) -> Result<Vec<String>> {
+        let content = self.fs.read_to_string(file_path)?;
+
+        Ok(self
+            .import_regex
+            .captures_iter(&content)
+            .map(|cap| cap[1].to_string())
+            .collect())
+    }
+
+    fn count_code_lines(&self, content: &str) -> usize {
+        SourceLanguage::jvm(content).count_code_lines(content)
+    }
+
+    fn count_functions(&self, content: &str) -> usize {
+        SourceLanguage::jvm(content).count_functions(content)
+    }
+
+    fn is_ui_layout(&self, content: &str) -> bool {
+        SourceLanguage::jvm(content).is_ui_layout(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::file_system::MemoryFileSystem;
+
+    #[test]
+    fn test_find_desktop_files() {
+        let mut fs = MemoryFileSystem::new();
+        fs.insert("/repo/desktopApp/src/main/kotlin/Main.kt", "fun main() = application {}");
+        fs.insert("/repo/composeApp/src/jvmMain/kotlin/Window.kt", "@Composable\nfun AppWindow() {}");
+        fs.insert("/repo/desktopApp/src/test/kotlin/MainTest.kt", "class MainTest");
+
+        let platform = DesktopPlatform::new().with_file_system(Arc::new(fs));
+        let files = platform.find_app_files(Path::new("/repo")).unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert!(platform.is_ui_layout("@Composable\nfun AppWindow() {\n    Text(\"Hi\")\n}\n"));
+    }
+}
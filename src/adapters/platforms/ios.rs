@@ -1,19 +1,26 @@
 use anyhow::Result;
 use regex::Regex;
 use std::collections::HashMap;
-use std::fs;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+use std::sync::Arc;
 
+use super::line_counter::SourceLanguage;
 use super::{detect_usage_with_patterns, Platform, PlatformType};
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
 use crate::analyzer::models::SymbolUsage;
+use crate::domain::{DetectionSettings, Symbol};
+
+/// Module names of frameworks built from shared code (`Shared`, `ComposeApp`, `UserKMP`, ...)
+const KMP_FRAMEWORK_NAMES: &str = r"Shared|ComposeApp|[A-Z][a-zA-Z]*KMP|[A-Z][a-zA-Z]*Shared";
 
 /// iOS platform implementation (Swift + Objective-C)
 pub struct IOSPlatform {
     #[allow(dead_code)]
     import_regex: Regex,
-    #[allow(dead_code)]
     kmp_framework_regex: Regex,
+    fs: Arc<dyn FileSystem>,
+    /// See [`DetectionSettings::ios_excluded_dirs`]
+    excluded_dirs: Vec<String>,
 }
 
 impl IOSPlatform {
@@ -22,82 +29,62 @@ impl IOSPlatform {
             // Match: import Shared, import ComposeApp, etc.
             import_regex: Regex::new(r"(?m)^import\s+([A-Za-z0-9_]+)").unwrap(),
             // Detect KMP framework imports (common patterns)
-            kmp_framework_regex: Regex::new(r"(?m)^import\s+(Shared|ComposeApp|[A-Z][a-zA-Z]*KMP|[A-Z][a-zA-Z]*Shared)").unwrap(),
+            kmp_framework_regex: Regex::new(&format!(r"(?m)^import\s+({})", KMP_FRAMEWORK_NAMES)).unwrap(),
+            fs: Arc::new(OsFileSystem),
+            excluded_dirs: DetectionSettings::default().ios_excluded_dirs,
         }
     }
 
-    /// Checks if a line is a Swift comment
-    fn is_swift_comment(line: &str) -> bool {
-        let trimmed = line.trim();
-        trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with("*")
-    }
-
-    /// Checks if a line is an Objective-C comment
-    fn is_objc_comment(line: &str) -> bool {
-        let trimmed = line.trim();
-        trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with("*")
-    }
-
-    /// Counts code lines for Swift files
-    fn count_swift_lines(content: &str) -> usize {
-        content
-            .lines()
-            .filter(|line| {
-                let trimmed = line.trim();
-                !trimmed.is_empty() && !Self::is_swift_comment(trimmed)
-            })
-            .count()
+    /// Reads app files through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
     }
 
-    /// Counts code lines for Objective-C files
-    fn count_objc_lines(content: &str) -> usize {
-        content
-            .lines()
-            .filter(|line| {
-                let trimmed = line.trim();
-                !trimmed.is_empty() && !Self::is_objc_comment(trimmed)
-            })
-            .count()
+    /// Replaces the default vendored dependency and build output exclusions
+    pub fn with_excluded_dirs(mut self, excluded_dirs: Vec<String>) -> Self {
+        self.excluded_dirs = excluded_dirs;
+        self
     }
 
     /// Finds Swift files in a directory
-    fn find_swift_files(root: &Path) -> Vec<PathBuf> {
-        WalkDir::new(root)
+    fn find_swift_files(&self, root: &Path) -> Vec<PathBuf> {
+        self.fs
+            .walk(root, usize::MAX)
             .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
+            .filter(|e| !e.is_dir)
             .filter(|e| {
-                e.path()
+                e.path
                     .extension()
                     .and_then(|ext| ext.to_str())
                     .map(|ext| ext == "swift")
                     .unwrap_or(false)
             })
-            .map(|e| e.path().to_path_buf())
+            .map(|e| e.path)
             .collect()
     }
 
     /// Finds Objective-C files in a directory
-    fn find_objc_files(root: &Path) -> Vec<PathBuf> {
-        WalkDir::new(root)
+    fn find_objc_files(&self, root: &Path) -> Vec<PathBuf> {
+        self.fs
+            .walk(root, usize::MAX)
             .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
+            .filter(|e| !e.is_dir)
             .filter(|e| {
-                e.path()
+                e.path
                     .extension()
                     .and_then(|ext| ext.to_str())
                     .map(|ext| ext == "m" || ext == "mm" || ext == "h")
                     .unwrap_or(false)
             })
-            .map(|e| e.path().to_path_buf())
+            .map(|e| e.path)
             .collect()
     }
 
     /// Detects if a Swift file imports KMP framework
     #[allow(dead_code)]
     pub fn has_kmp_import(&self, file_path: &Path) -> Result<bool> {
-        let content = fs::read_to_string(file_path)?;
+        let content = self.fs.read_to_string(file_path)?;
         Ok(self.kmp_framework_regex.is_match(&content))
     }
 }
@@ -132,17 +119,21 @@ impl Platform for IOSPlatform {
 
         for pattern in self.app_directory_patterns() {
             let search_path = project_path.join(pattern);
-            if search_path.exists() {
+            if self.fs.exists(&search_path) {
                 // Find Swift files
-                let swift_files = Self::find_swift_files(&search_path);
+                let swift_files = self.find_swift_files(&search_path);
                 app_files.extend(swift_files);
 
                 // Find Objective-C files
-                let objc_files = Self::find_objc_files(&search_path);
+                let objc_files = self.find_objc_files(&search_path);
                 app_files.extend(objc_files);
             }
         }
 
+        app_files.retain(|file| {
+            let relative = file.strip_prefix(project_path).unwrap_or(file);
+            DetectionSettings::excluded_by(&self.excluded_dirs, relative).is_none()
+        });
         Ok(app_files)
     }
 
@@ -151,7 +142,7 @@ impl Platform for IOSPlatform {
         file_path: &Path,
         kmp_symbols: &[String],
     ) -> Result<HashMap<String, SymbolUsage>> {
-        let content = fs::read_to_string(file_path)?;
+        let content = self.fs.read_to_string(file_path)?;
 
         // Swift and Objective-C use similar comment syntax
         let comment_prefixes = vec!["//", "/*", "*", "import ", "#import"];
@@ -164,7 +155,7 @@ impl Platform for IOSPlatform {
     }
 
     fn extract_imports(&self, file_path: &Path) -> Result<Vec<String>> {
-        let content = fs::read_to_string(file_path)?;
+        let content = self.fs.read_to_string(file_path)?;
         let mut imports = Vec::new();
 
         for cap in self.import_regex.captures_iter(&content) {
@@ -185,18 +176,111 @@ impl Platform for IOSPlatform {
     }
 
     fn count_code_lines(&self, content: &str) -> usize {
-        // Detect if Swift or Objective-C by file patterns
-        if content.contains("func ") || content.contains("let ") || content.contains("var ") {
-            Self::count_swift_lines(content)
-        } else {
-            Self::count_objc_lines(content)
+        SourceLanguage::apple(content).count_code_lines(content)
+    }
+
+    fn count_functions(&self, content: &str) -> usize {
+        SourceLanguage::apple(content).count_functions(content)
+    }
+
+    fn is_ui_layout(&self, content: &str) -> bool {
+        SourceLanguage::apple(content).is_ui_layout(content)
+    }
+
+    fn imports_shared_framework(&self, content: &str) -> bool {
+        self.kmp_framework_regex.is_match(content)
+    }
+}
+
+/// Names Kotlin/Native exports shared symbols under to Swift and Objective-C
+///
+/// The generated Objective-C header prefixes classes, interfaces, and objects with
+/// the framework name (`SharedUser`), `@ObjCName` renames declarations, and a name
+/// clashing with another exported one gets a `_` appended (`User_`). Apple files are
+/// searched for these besides the Kotlin name, unless the names were read from the
+/// header itself.
+pub struct ExportedNames {
+    /// Match: import Shared, @import Shared;, #import <Shared/Shared.h>
+    framework_regex: Regex,
+}
+
+impl ExportedNames {
+    pub fn new() -> Self {
+        Self {
+            framework_regex: Regex::new(&format!(
+                r"(?m)^\s*(?:import\s+|@import\s+|#import\s+<)({})\b",
+                KMP_FRAMEWORK_NAMES
+            ))
+            .unwrap(),
+        }
+    }
+
+    /// Shared frameworks an Apple file imports, whose names prefix the Objective-C names
+    pub fn frameworks(&self, content: &str) -> Vec<String> {
+        let mut frameworks: Vec<String> = Vec::new();
+        for cap in self.framework_regex.captures_iter(content) {
+            if !frameworks.iter().any(|framework| framework == &cap[1]) {
+                frameworks.push(cap[1].to_string());
+            }
+        }
+        frameworks
+    }
+
+    /// Names an Apple file importing `frameworks` may reference `symbol` by, the Kotlin name first
+    ///
+    /// A symbol named by the framework header goes by its Swift and Objective-C names only.
+    pub fn aliases(symbol: &Symbol, frameworks: &[String]) -> Vec<String> {
+        if let Some(header) = symbol.objc_name.as_ref().filter(|names| names.from_header) {
+            let mut aliases: Vec<String> = Vec::new();
+            for name in header.swift_name.iter().chain(&header.name) {
+                if !aliases.contains(name) {
+                    aliases.push(name.clone());
+                }
+            }
+            return aliases;
         }
+        let mut aliases = vec![symbol.name.clone()];
+        // Dotted resource accessors keep their Kotlin spelling
+        if symbol.name.contains('.') {
+            return aliases;
+        }
+        let objc_name = symbol.objc_name.as_ref();
+        let swift = objc_name.and_then(|names| names.swift()).unwrap_or(&symbol.name);
+        let is_type = symbol.symbol_type.is_classifier();
+
+        let mut push = |alias: String| {
+            if !aliases.contains(&alias) {
+                aliases.push(alias);
+            }
+        };
+        push(swift.to_string());
+        if is_type {
+            push(format!("{}_", swift));
+            let objc = objc_name.and_then(|names| names.name.as_deref()).unwrap_or(&symbol.name);
+            if objc_name.is_some_and(|names| names.exact) {
+                push(objc.to_string());
+                push(format!("{}_", objc));
+            } else {
+                for framework in frameworks {
+                    push(format!("{}{}", framework, objc));
+                    push(format!("{}{}_", framework, objc));
+                }
+            }
+        }
+        aliases
+    }
+}
+
+impl Default for ExportedNames {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::{ObjCName, SymbolType};
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -232,6 +316,56 @@ mod tests {
         assert!(has_import);
     }
 
+    #[test]
+    fn test_imports_shared_framework() {
+        let platform = IOSPlatform::new();
+        assert!(platform.imports_shared_framework("import SwiftUI\nimport ComposeApp\n"));
+        assert!(!platform.imports_shared_framework("import SwiftUI\n// import Shared\n"));
+    }
+
+    #[test]
+    fn test_exported_names() {
+        let names = ExportedNames::new();
+        let frameworks = names.frameworks("import SwiftUI\nimport Shared\n#import <UserKMP/UserKMP.h>\nimport SharedUI\n");
+        assert_eq!(frameworks, vec!["Shared", "UserKMP"]);
+
+        let symbol = |name: &str, symbol_type: SymbolType, objc_name: Option<ObjCName>| Symbol {
+            package: "com.example".to_string(),
+            objc_name,
+            ..Symbol::new(name, symbol_type, "shared", "shared/src/commonMain/kotlin/User.kt")
+        };
+        let shared = ["Shared".to_string()];
+        assert_eq!(
+            ExportedNames::aliases(&symbol("User", SymbolType::DataClass, None), &shared),
+            vec!["User", "User_", "SharedUser", "SharedUser_"]
+        );
+        let renamed = ObjCName::parse(r#""SUser", swiftName = "AppUser""#);
+        assert_eq!(
+            ExportedNames::aliases(&symbol("User", SymbolType::Class, renamed), &shared),
+            vec!["User", "AppUser", "AppUser_", "SharedSUser", "SharedSUser_"]
+        );
+        let exact = ObjCName::parse(r#"name = "KUser", exact = true"#);
+        assert_eq!(
+            ExportedNames::aliases(&symbol("User", SymbolType::Interface, exact), &shared),
+            vec!["User", "KUser", "KUser_"]
+        );
+        let function = ObjCName::parse(r#"swiftName = "format""#);
+        assert_eq!(
+            ExportedNames::aliases(&symbol("formatUserName", SymbolType::Function, function), &shared),
+            vec!["formatUserName", "format"]
+        );
+        let from_header = ObjCName {
+            name: Some("SharedUser_".to_string()),
+            swift_name: Some("User_".to_string()),
+            exact: true,
+            from_header: true,
+        };
+        assert_eq!(
+            ExportedNames::aliases(&symbol("User", SymbolType::Class, Some(from_header)), &shared),
+            vec!["User_", "SharedUser_"]
+        );
+    }
+
     #[test]
     fn test_extract_swift_imports() {
         let platform = IOSPlatform::new();
@@ -0,0 +1,187 @@
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::line_counter::SourceLanguage;
+use super::{detect_usage_with_patterns, Platform, PlatformType};
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
+use crate::analyzer::models::SymbolUsage;
+use crate::utils::FileUtils;
+
+/// Extensions of TypeScript and JavaScript app sources
+pub const WEB_SOURCE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs"];
+
+/// Web platform implementation: Kotlin/JS and Wasm apps (`jsMain`, `wasmJsMain`)
+/// and the TypeScript/JavaScript apps consuming a shared module's JS exports
+pub struct JsPlatform {
+    kotlin_import_regex: Regex,
+    module_import_regex: Regex,
+    fs: Arc<dyn FileSystem>,
+}
+
+impl JsPlatform {
+    pub fn new() -> Self {
+        Self {
+            // Match: import com.example.User
+            kotlin_import_regex: Regex::new(r"(?m)^import\s+([a-zA-Z0-9_.]+)").unwrap(),
+            // Match: import { User } from "shared", import shared from 'shared', require("shared")
+            module_import_regex: Regex::new(
+                r#"(?m)(?:^\s*import\s+(?:[^'"]*?\s+from\s+)?|\brequire\(\s*)['"]([^'"]+)['"]"#,
+            )
+            .unwrap(),
+            fs: Arc::new(OsFileSystem),
+        }
+    }
+
+    /// Reads app files through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// Whether a file is TypeScript or JavaScript source, not a generated `.d.ts` declaration
+    pub fn is_web_source(path: &Path) -> bool {
+        let is_declaration = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(".d.ts"));
+        !is_declaration
+            && path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| WEB_SOURCE_EXTENSIONS.contains(&ext))
+    }
+
+    /// Finds TypeScript and JavaScript files in a directory
+    fn find_web_files(&self, root: &Path) -> Vec<PathBuf> {
+        self.fs
+            .walk(root, usize::MAX)
+            .into_iter()
+            .filter(|e| !e.is_dir && Self::is_web_source(&e.path))
+            .map(|e| e.path)
+            .collect()
+    }
+}
+
+impl Default for JsPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Platform for JsPlatform {
+    fn platform_type(&self) -> PlatformType {
+        PlatformType::Web
+    }
+
+    fn file_extensions(&self) -> Vec<&str> {
+        WEB_SOURCE_EXTENSIONS.to_vec()
+    }
+
+    fn app_directory_patterns(&self) -> Vec<&str> {
+        vec![
+            "webApp/src/jsMain",
+            "webApp/src/wasmJsMain",
+            "composeApp/src/jsMain",
+            "composeApp/src/wasmJsMain",
+            "web/src",
+            "frontend/src",
+        ]
+    }
+
+    fn find_app_files(&self, project_path: &Path) -> Result<Vec<PathBuf>> {
+        let mut app_files = Vec::new();
+
+        for pattern in self.app_directory_patterns() {
+            let search_path = project_path.join(pattern);
+            if self.fs.exists(&search_path) {
+                // Kotlin/JS sources in JS and Wasm source sets, TypeScript/JavaScript elsewhere
+                if pattern.ends_with("Main") {
+                    app_files.extend(FileUtils::find_kotlin_files(self.fs.as_ref(), &search_path));
+                } else {
+                    app_files.extend(self.find_web_files(&search_path));
+                }
+            }
+        }
+
+        Ok(app_files)
+    }
+
+    fn detect_symbol_usage(
+        &self,
+        file_path: &Path,
+        kmp_symbols: &[String],
+    ) -> Result<HashMap<String, SymbolUsage>> {
+        let content = self.fs.read_to_string(file_path)?;
+
+        let comment_prefixes = vec!["//", "/*", "*", "import "];
+        Ok(detect_usage_with_patterns(
+            &content,
+            file_path,
+            kmp_symbols,
+            &comment_prefixes,
+        ))
+    }
+
+    /// Kotlin imports of Kotlin/JS files, module specifiers of TypeScript and JavaScript files
+    fn extract_imports(&self, file_path: &Path) -> Result<Vec<String>> {
+        let content = self.fs.read_to_string(file_path)?;
+        let regex = if Self::is_web_source(file_path) {
+            &self.module_import_regex
+        } else {
+            &self.kotlin_import_regex
+        };
+
+        Ok(regex
+            .captures_iter(&content)
+            .map(|cap| cap[1].to_string())
+            .collect())
+    }
+
+    fn count_code_lines(&self, content: &str) -> usize {
+        SourceLanguage::web(content).count_code_lines(content)
+    }
+
+    fn count_functions(&self, content: &str) -> usize {
+        SourceLanguage::web(content).count_functions(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::file_system::MemoryFileSystem;
+
+    #[test]
+    fn test_find_web_files() -> Result<()> {
+        let mut fs = MemoryFileSystem::new();
+        fs.insert("/repo/web/src/App.tsx", "import { User } from \"shared\";\nconst user = new User(\"1\");\n");
+        fs.insert("/repo/web/src/shared.d.ts", "export declare class User {}");
+        fs.insert("/repo/web/src/main.js", "const shared = require('shared');");
+        fs.insert("/repo/webApp/src/wasmJsMain/kotlin/Main.kt", "import com.example.User\n\nfun main() {}");
+
+        let platform = JsPlatform::new().with_file_system(Arc::new(fs));
+        let mut files = platform.find_app_files(Path::new("/repo"))?;
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("/repo/web/src/App.tsx"),
+                PathBuf::from("/repo/web/src/main.js"),
+                PathBuf::from("/repo/webApp/src/wasmJsMain/kotlin/Main.kt"),
+            ]
+        );
+
+        assert_eq!(platform.extract_imports(Path::new("/repo/web/src/App.tsx"))?, vec!["shared"]);
+        assert_eq!(platform.extract_imports(Path::new("/repo/web/src/main.js"))?, vec!["shared"]);
+        assert_eq!(
+            platform.extract_imports(Path::new("/repo/webApp/src/wasmJsMain/kotlin/Main.kt"))?,
+            vec!["com.example.User"]
+        );
+        assert_eq!(platform.count_code_lines("// app\nconst a = 1;\n\n/* b */\n"), 1);
+
+        Ok(())
+    }
+}
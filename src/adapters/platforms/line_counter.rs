@@ -0,0 +1,476 @@
+//! Lexical code line counting
+//! Counts the lines a compiler would see code on: a line that only continues a block
+//! comment is not code, however it starts, and a line inside a multiline string
+//! literal is, even when it starts with `//` or `*`. Handles the comment and string
+//! syntax of Kotlin, Java, Swift, Objective-C and JavaScript/TypeScript: nested block
+//! comments, raw and multiline strings, and the code inside string templates and
+//! interpolations.
+//!
+//! With the `treesitter` feature, Kotlin, Java, Swift and Objective-C lines are counted
+//! from the file's syntax tree instead; the scanner remains for JavaScript/TypeScript,
+//! for builds without the feature, and for files the grammar cannot parse.
+
+#[cfg(feature = "treesitter")]
+use tree_sitter::{Language, Node, Parser};
+
+/// Language whose comment and string syntax a file is scanned with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceLanguage {
+    Kotlin,
+    Java,
+    Swift,
+    ObjectiveC,
+    /// JavaScript and TypeScript
+    JavaScript,
+}
+
+impl SourceLanguage {
+    /// Kotlin or Java, guessed from the content the way the Android platform always has
+    pub fn jvm(content: &str) -> Self {
+        if content.contains("fun ") || content.contains("val ") || content.contains("var ") {
+            SourceLanguage::Kotlin
+        } else {
+            SourceLanguage::Java
+        }
+    }
+
+    /// Swift or Objective-C, guessed from the content
+    pub fn apple(content: &str) -> Self {
+        if content.contains("func ") || content.contains("let ") || content.contains("var ") {
+            SourceLanguage::Swift
+        } else {
+            SourceLanguage::ObjectiveC
+        }
+    }
+
+    /// Kotlin or JavaScript/TypeScript, guessed from the content of a web app file
+    pub fn web(content: &str) -> Self {
+        if content.contains("fun ") || content.contains("val ") || content.starts_with("package ") {
+            SourceLanguage::Kotlin
+        } else {
+            SourceLanguage::JavaScript
+        }
+    }
+
+    /// Counts lines with code or string content, skipping blank and comment-only lines
+    pub fn count_code_lines(self, content: &str) -> usize {
+        #[cfg(feature = "treesitter")]
+        if let Some(count) = self.count_syntax_lines(content) {
+            return count;
+        }
+        self.count_scanned_lines(content)
+    }
+
+    /// [`SourceLanguage::count_code_lines`] with the lexical scanner
+    fn count_scanned_lines(self, content: &str) -> usize {
+        let mut scanner = Scanner::new(self);
+        content.lines().filter(|line| scanner.scan_line(line, &mut None)).count()
+    }
+
+    /// Tree-sitter grammar of the language, `None` for JavaScript/TypeScript
+    #[cfg(feature = "treesitter")]
+    fn grammar(self) -> Option<Language> {
+        match self {
+            SourceLanguage::Kotlin => Some(tree_sitter_kotlin_ng::LANGUAGE.into()),
+            SourceLanguage::Java => Some(tree_sitter_java::LANGUAGE.into()),
+            SourceLanguage::Swift => Some(tree_sitter_swift::LANGUAGE.into()),
+            SourceLanguage::ObjectiveC => Some(tree_sitter_objc::LANGUAGE.into()),
+            SourceLanguage::JavaScript => None,
+        }
+    }
+
+    /// Counts the lines holding non-whitespace text of any syntax node but a comment,
+    /// or `None` without a grammar or when the content does not parse
+    #[cfg(feature = "treesitter")]
+    fn count_syntax_lines(self, content: &str) -> Option<usize> {
+        let mut parser = Parser::new();
+        parser.set_language(&self.grammar()?).ok()?;
+        let tree = parser.parse(content, None)?;
+        if tree.root_node().has_error() {
+            return None;
+        }
+
+        let mut code_lines = vec![false; content.lines().count()];
+        mark_code_lines(tree.root_node(), content, &mut code_lines);
+        Some(code_lines.into_iter().filter(|&code| code).count())
+    }
+
+    /// The code of each code line, with comments and string contents left out
+    pub fn code_text(self, content: &str) -> Vec<String> {
+        let mut scanner = Scanner::new(self);
+        content
+            .lines()
+            .filter_map(|line| {
+                let mut text = Some(String::new());
+                scanner.scan_line(line, &mut text).then_some(text).flatten()
+            })
+            .collect()
+    }
+
+    /// Counts function and method declarations outside comments and string literals:
+    /// Kotlin `fun`, Swift `func`, JavaScript `function` and arrow functions, Objective-C
+    /// `-`/`+` methods, and Java method and constructor headers
+    pub fn count_functions(self, content: &str) -> usize {
+        self.code_text(content)
+            .iter()
+            .map(|line| match self {
+                SourceLanguage::Kotlin => after_keyword(line, "fun")
+                    .filter(|rest| rest.starts_with(char::is_whitespace) && !rest.trim_start().starts_with("interface"))
+                    .count(),
+                SourceLanguage::Swift => after_keyword(line, "func").count(),
+                SourceLanguage::JavaScript => after_keyword(line, "function").count() + line.matches("=>").count(),
+                SourceLanguage::ObjectiveC => {
+                    let line = line.trim_start();
+                    (line.starts_with(['-', '+']) && line[1..].trim_start().starts_with('(')) as usize
+                }
+                SourceLanguage::Java => is_java_method_header(line) as usize,
+            })
+            .sum()
+    }
+
+    /// `/* /* */ */` is one comment in Kotlin and Swift, but ends early in Java and C
+    fn nests_comments(self) -> bool {
+        matches!(self, SourceLanguage::Kotlin | SourceLanguage::Swift)
+    }
+
+    /// `'` starts a character literal everywhere but Swift and JavaScript
+    fn has_char_literals(self) -> bool {
+        !matches!(self, SourceLanguage::Swift | SourceLanguage::JavaScript)
+    }
+
+    /// Kotlin `"""` strings are raw; Java text blocks and Swift multiline strings are not
+    fn escapes_in_multiline(self) -> bool {
+        self != SourceLanguage::Kotlin
+    }
+}
+
+/// Marks the lines `node` has code or string text on
+///
+/// Text of a node not covered by its children (string contents, for grammars that
+/// have no node for them) counts as well; comment subtrees are skipped.
+#[cfg(feature = "treesitter")]
+fn mark_code_lines(node: Node, content: &str, code_lines: &mut [bool]) {
+    if node.kind().contains("comment") {
+        return;
+    }
+
+    // Byte and line up to which the node's text is accounted for
+    let (mut covered, mut line) = (node.start_byte(), node.start_position().row);
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.children(&mut cursor).collect();
+    for child in children {
+        if child.start_byte() > covered {
+            mark_text(&content[covered..child.start_byte()], line, code_lines);
+        }
+        mark_code_lines(child, content, code_lines);
+        if child.end_byte() > covered {
+            (covered, line) = (child.end_byte(), child.end_position().row);
+        }
+    }
+    if node.end_byte() > covered {
+        mark_text(&content[covered..node.end_byte()], line, code_lines);
+    }
+}
+
+/// Marks the lines of `text`, starting on `first_line`, that hold anything but whitespace
+#[cfg(feature = "treesitter")]
+fn mark_text(text: &str, first_line: usize, code_lines: &mut [bool]) {
+    for (line, text) in (first_line..).zip(text.split('\n')) {
+        if !text.trim().is_empty() {
+            if let Some(code) = code_lines.get_mut(line) {
+                *code = true;
+            }
+        }
+    }
+}
+
+/// What follows each occurrence of `keyword` as a whole word on a line of code
+fn after_keyword<'l>(line: &'l str, keyword: &'l str) -> impl Iterator<Item = &'l str> + 'l {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    line.match_indices(keyword).filter_map(move |(start, _)| {
+        let rest = &line[start + keyword.len()..];
+        (!line[..start].ends_with(is_word) && !rest.starts_with(is_word)).then_some(rest)
+    })
+}
+
+/// Whether a line of Java code starts a method or constructor with a body: a return type
+/// or modifier and a name before the parameters, as opposed to a call, an assignment, or a statement
+fn is_java_method_header(line: &str) -> bool {
+    const STATEMENTS: &[&str] = &[
+        "return", "new", "throw", "else", "if", "for", "while", "switch", "catch", "case", "do", "try",
+        "synchronized", "assert",
+    ];
+    let line = line.trim_start_matches(|c: char| c == '}' || c.is_whitespace()).trim_end();
+    let Some((head, _)) = line.split_once('(') else {
+        return false;
+    };
+    let words: Vec<&str> = head.split_whitespace().collect();
+    !line.ends_with(';')
+        && head.chars().all(|c| c.is_alphanumeric() || c.is_whitespace() || "_$@<>,[].?".contains(c))
+        && words.len() >= 2
+        && !STATEMENTS.contains(&words[0])
+        && !STATEMENTS.contains(&words[words.len() - 1])
+}
+
+/// What the scanner is inside of
+#[derive(Debug, Clone, Copy)]
+enum Mode {
+    /// Code; inside a template or interpolation, `close` ends it once `depth` is back to 0
+    Code { close: Option<char>, depth: usize },
+    BlockComment { depth: usize },
+    /// String literal delimited by `quote`; `hashes` is the `#` count of a Swift raw string
+    Str { quote: char, multiline: bool, hashes: usize },
+    Char,
+}
+
+struct Scanner {
+    language: SourceLanguage,
+    /// Innermost last; the bottom is always top-level code
+    stack: Vec<Mode>,
+}
+
+impl Scanner {
+    fn new(language: SourceLanguage) -> Self {
+        Self {
+            language,
+            stack: vec![Mode::Code { close: None, depth: 0 }],
+        }
+    }
+
+    /// Scans one line and reports whether it holds anything besides whitespace and comments
+    ///
+    /// When `text` is given, the line's code outside comments and literals is appended to it.
+    fn scan_line(&mut self, line: &str, text: &mut Option<String>) -> bool {
+        let chars: Vec<char> = line.chars().collect();
+        let mut code = false;
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            let mode = *self.stack.last().expect("scanner stack is never empty");
+            match mode {
+                Mode::Code { close, depth } => {
+                    if c.is_whitespace() {
+                        if let Some(text) = text {
+                            text.push(' ');
+                        }
+                        i += 1;
+                        continue;
+                    }
+                    if starts_with(&chars, i, "//") {
+                        break;
+                    }
+                    if starts_with(&chars, i, "/*") {
+                        self.stack.push(Mode::BlockComment { depth: 1 });
+                        i += 2;
+                        continue;
+                    }
+
+                    code = true;
+                    if let Some(text) = text {
+                        text.push(c);
+                    }
+                    let hashes = if self.language == SourceLanguage::Swift {
+                        count_run(&chars, i, '#')
+                    } else {
+                        0
+                    };
+                    if chars.get(i + hashes) == Some(&'"') {
+                        let multiline = self.language != SourceLanguage::JavaScript
+                            && starts_with(&chars, i + hashes, "\"\"\"");
+                        self.stack.push(Mode::Str { quote: '"', multiline, hashes });
+                        i += hashes + if multiline { 3 } else { 1 };
+                        continue;
+                    }
+                    if self.language == SourceLanguage::JavaScript && (c == '\'' || c == '`') {
+                        // Template literals span lines; quoted strings do not
+                        self.stack.push(Mode::Str { quote: c, multiline: c == '`', hashes: 0 });
+                        i += 1;
+                        continue;
+                    }
+                    if c == '\'' && self.language.has_char_literals() {
+                        self.stack.push(Mode::Char);
+                    } else if let Some(close) = close {
+                        let open = if close == '}' { '{' } else { '(' };
+                        if c == open {
+                            self.set_top(Mode::Code { close: Some(close), depth: depth + 1 });
+                        } else if c == close && depth == 0 {
+                            self.stack.pop();
+                        } else if c == close {
+                            self.set_top(Mode::Code { close: Some(close), depth: depth - 1 });
+                        }
+                    }
+                    i += 1;
+                }
+                Mode::BlockComment { depth } => {
+                    if starts_with(&chars, i, "*/") {
+                        if depth == 1 {
+                            self.stack.pop();
+                        } else {
+                            self.set_top(Mode::BlockComment { depth: depth - 1 });
+                        }
+                        i += 2;
+                    } else if self.language.nests_comments() && starts_with(&chars, i, "/*") {
+                        self.set_top(Mode::BlockComment { depth: depth + 1 });
+                        i += 2;
+                    } else {
+                        i += 1;
+                    }
+                }
+                Mode::Str { quote, multiline, hashes } => {
+                    if !c.is_whitespace() {
+                        code = true;
+                    }
+                    let delimiter = if multiline && quote == '"' { 3 } else { 1 };
+                    if starts_with(&chars, i, &quote.to_string().repeat(delimiter))
+                        && count_run(&chars, i + delimiter, '#') >= hashes
+                    {
+                        self.stack.pop();
+                        i += delimiter + hashes;
+                    } else if (self.language == SourceLanguage::Kotlin || quote == '`')
+                        && starts_with(&chars, i, "${")
+                    {
+                        self.stack.push(Mode::Code { close: Some('}'), depth: 0 });
+                        i += 2;
+                    } else if c == '\\'
+                        && (!multiline || self.language.escapes_in_multiline())
+                        && count_run(&chars, i + 1, '#') >= hashes
+                    {
+                        let next = i + 1 + hashes;
+                        if self.language == SourceLanguage::Swift && chars.get(next) == Some(&'(') {
+                            self.stack.push(Mode::Code { close: Some(')'), depth: 0 });
+                        }
+                        i = next + 1;
+                    } else {
+                        i += 1;
+                    }
+                }
+                Mode::Char => {
+                    code = true;
+                    if c == '\\' {
+                        i += 2;
+                    } else {
+                        if c == '\'' {
+                            self.stack.pop();
+                        }
+                        i += 1;
+                    }
+                }
+            }
+        }
+
+        // Only multiline strings and block comments outlive their line; anything
+        // else left open is malformed, so recover at the next line
+        if let Some(open) = self
+            .stack
+            .iter()
+            .position(|mode| matches!(mode, Mode::Str { multiline: false, .. } | Mode::Char))
+        {
+            self.stack.truncate(open);
+        }
+        code
+    }
+
+    fn set_top(&mut self, mode: Mode) {
+        if let Some(top) = self.stack.last_mut() {
+            *top = mode;
+        }
+    }
+}
+
+fn starts_with(chars: &[char], at: usize, pattern: &str) -> bool {
+    (at..).zip(pattern.chars()).all(|(index, expected)| chars.get(index) == Some(&expected))
+}
+
+/// Number of consecutive `c` starting at `at`
+fn count_run(chars: &[char], at: usize, c: char) -> usize {
+    chars.iter().skip(at).take_while(|&&found| found == c).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_comments_and_multiline_strings() {
+        let kotlin = r#"/*
+ * Licensed under MIT
+ /* nested */
+ still a comment
+ */
+val query = """
+    // not a comment
+    * neither
+"""
+val price = 2 *
+    3 /* trailing */
+val url = "http://example.com" // comment
+val template = "${"/*"}" + '"'
+fun main() {}
+"#;
+        assert_eq!(SourceLanguage::Kotlin.count_code_lines(kotlin), 9);
+
+        // Java comments do not nest: the first `*/` ends the comment
+        let java = "/* a /* b */\nint x = 1;\n/** doc\n * more\n */\nString s = \"\"\"\n  // text\n  \"\"\";\n";
+        assert_eq!(SourceLanguage::Java.count_code_lines(java), 4);
+
+        let swift = r##"let raw = #"a " quote "#
+let text = """
+    \(value.map { "\($0)" })
+    // text
+    """
+/* done */
+"##;
+        assert_eq!(SourceLanguage::Swift.count_code_lines(swift), 5);
+
+        let objc = "#import <Foundation/Foundation.h>\n/* header\n   comment */\nNSString *s = @\"// not a comment\";\n";
+        assert_eq!(SourceLanguage::ObjectiveC.count_code_lines(objc), 2);
+
+        let typescript = "/* header\n   comment */\nconst url = 'http://example.com'; // comment\nconst query = `\n  // not a comment\n  ${user.name /* inline */}\n`;\n";
+        assert_eq!(SourceLanguage::JavaScript.count_code_lines(typescript), 5);
+    }
+
+    #[cfg(feature = "treesitter")]
+    #[test]
+    fn test_syntax_tree_counts() {
+        let kotlin = "/*\n * License\n /* nested */\n */\nval query = \"\"\"\n    // not a comment\n\n    * neither\n\"\"\"\nval url = \"http://example.com\" // comment\nval template = \"${\"/*\"}\" + '\"'\n";
+        assert_eq!(SourceLanguage::Kotlin.count_syntax_lines(kotlin), Some(6));
+
+        let java = "/* a /* b */\nint x = 1;\n/** doc\n * more\n */\nclass A { String s = \"\"\"\n  // text\n  \"\"\"; }\n";
+        assert_eq!(SourceLanguage::Java.count_syntax_lines(java), Some(4));
+
+        let swift = "let raw = #\"a \" quote \"#\nlet text = \"\"\"\n    \\(value)\n    // text\n    \"\"\"\n/* done */\n";
+        assert_eq!(SourceLanguage::Swift.count_syntax_lines(swift), Some(5));
+
+        let objc = "#import <Foundation/Foundation.h>\n/* header\n   comment */\nNSString *s = @\"// not a comment\";\n";
+        assert_eq!(SourceLanguage::ObjectiveC.count_syntax_lines(objc), Some(2));
+
+        // Unparsable content and languages without a grammar fall back to the scanner
+        assert_eq!(SourceLanguage::Kotlin.count_syntax_lines("val x = ((\n"), None);
+        assert_eq!(SourceLanguage::JavaScript.count_syntax_lines("const x = 1;\n"), None);
+        assert_eq!(SourceLanguage::Kotlin.count_code_lines("val x = ((\n// c\n"), 1);
+    }
+
+    #[test]
+    fn test_count_functions() {
+        let kotlin = r#"fun interface Listener { fun onEvent() }
+// fun commented()
+val label = "fun text()"
+private suspend fun <T> List<T>.load(): T = first()
+val handler = fun(x: Int) = x
+"#;
+        assert_eq!(SourceLanguage::Kotlin.count_functions(kotlin), 2);
+
+        let java = "public class Repo {\n    @Override public List<User> users(int page) {\n    public Repo() {\n        if (ready) {\n        return load(page);\n        Log.d(TAG, \"x\");\n    } catch (Exception e) {\n    abstract void clear();\n}\n";
+        assert_eq!(SourceLanguage::Java.count_functions(java), 2);
+
+        let swift = "struct Login {\n    func submit() {}\n    /* func old() */\n    static func make() -> Login { Login() }\n}\n";
+        assert_eq!(SourceLanguage::Swift.count_functions(swift), 2);
+
+        let objc = "- (void)viewDidLoad {\n+ (instancetype)shared;\nint x = a - (b);\n";
+        assert_eq!(SourceLanguage::ObjectiveC.count_functions(objc), 2);
+
+        let typescript = "export function login(user: User) {}\nconst logout = () => {};\nconst text = 'function';\n";
+        assert_eq!(SourceLanguage::JavaScript.count_functions(typescript), 2);
+    }
+}
@@ -1,17 +1,31 @@
 use anyhow::Result;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use crate::analyzer::models::{SymbolUsage, UsageLocation};
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
+use crate::adapters::pattern_cache::SymbolPatterns;
+
+use crate::analyzer::models::SymbolUsage;
+use crate::domain::{DetectionSettings, MatchEngine};
 
 pub mod android;
+pub mod desktop;
 pub mod ios;
+pub mod js;
+pub mod line_counter;
+pub mod server;
+pub mod ui_layout;
 
 /// Platform type enumeration
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum PlatformType {
     Android,
     IOS,
+    Server,
+    Desktop,
+    Web,
 }
 
 impl PlatformType {
@@ -20,6 +34,9 @@ impl PlatformType {
         match self {
             PlatformType::Android => "Android",
             PlatformType::IOS => "iOS",
+            PlatformType::Server => "Server",
+            PlatformType::Desktop => "Desktop",
+            PlatformType::Web => "Web",
         }
     }
 }
@@ -64,6 +81,21 @@ pub trait Platform: Send + Sync {
 
     /// Counts code lines (excluding comments and empty lines)
     fn count_code_lines(&self, content: &str) -> usize;
+
+    /// Counts function and method declarations
+    fn count_functions(&self, _content: &str) -> usize {
+        0
+    }
+
+    /// Whether the file only declares UI layout (Compose screens, SwiftUI views)
+    fn is_ui_layout(&self, _content: &str) -> bool {
+        false
+    }
+
+    /// Whether the file imports the shared framework as a whole (Swift's `import Shared`)
+    fn imports_shared_framework(&self, _content: &str) -> bool {
+        false
+    }
 }
 
 /// Platform registry for managing multiple platforms
@@ -74,9 +106,30 @@ pub struct PlatformRegistry {
 impl PlatformRegistry {
     /// Creates a new PlatformRegistry with default platforms
     pub fn new() -> Self {
+        Self::with_file_system(Arc::new(OsFileSystem))
+    }
+
+    /// Creates the default platforms reading files through the given file system
+    pub fn with_file_system(fs: Arc<dyn FileSystem>) -> Self {
+        Self::with_settings(fs, &DetectionSettings::default())
+    }
+
+    /// Creates the default platforms, skipping the paths `detection` excludes
+    pub fn with_settings(fs: Arc<dyn FileSystem>, detection: &DetectionSettings) -> Self {
         let platforms: Vec<Box<dyn Platform>> = vec![
-            Box::new(android::AndroidPlatform::new()),
-            Box::new(ios::IOSPlatform::new()),
+            Box::new(
+                android::AndroidPlatform::new()
+                    .with_file_system(fs.clone())
+                    .with_excluded_paths(detection.android_excluded_paths.clone()),
+            ),
+            Box::new(
+                ios::IOSPlatform::new()
+                    .with_file_system(fs.clone())
+                    .with_excluded_dirs(detection.ios_excluded_dirs.clone()),
+            ),
+            Box::new(server::ServerPlatform::new().with_file_system(fs.clone())),
+            Box::new(desktop::DesktopPlatform::new().with_file_system(fs.clone())),
+            Box::new(js::JsPlatform::new().with_file_system(fs)),
         ];
 
         Self { platforms }
@@ -129,54 +182,16 @@ impl Default for PlatformRegistry {
 }
 
 /// Helper function to detect usage of symbols using regex patterns
+///
+/// Compiles the patterns for this one call; searches of many files for the same
+/// symbols share them through a [`PatternCache`](crate::adapters::PatternCache).
 pub fn detect_usage_with_patterns(
     content: &str,
     file_path: &Path,
     kmp_symbols: &[String],
     comment_prefixes: &[&str],
 ) -> HashMap<String, SymbolUsage> {
-    use regex::Regex;
-    use std::collections::HashSet;
-
-    let mut usages: HashMap<String, SymbolUsage> = HashMap::new();
-    let lines: Vec<&str> = content.lines().collect();
-
-    for (line_num, line) in lines.iter().enumerate() {
-        let trimmed = line.trim();
-
-        // Skip comments
-        if comment_prefixes.iter().any(|prefix| trimmed.starts_with(prefix)) {
-            continue;
-        }
-
-        // Check each symbol
-        for symbol_name in kmp_symbols {
-            // Match symbol usage in various contexts
-            let pattern = format!(r"\b{}\b(?:\s*\(|\.|\s*:|<|\s+)", regex::escape(symbol_name));
-            if let Ok(regex) = Regex::new(&pattern) {
-                if regex.is_match(line) {
-                    let usage = usages.entry(symbol_name.clone()).or_insert_with(|| {
-                        SymbolUsage {
-                            symbol_name: symbol_name.clone(),
-                            reference_count: 0,
-                            used_in_files: HashSet::new(),
-                            usage_lines: Vec::new(),
-                        }
-                    });
-
-                    usage.reference_count += 1;
-                    usage.used_in_files.insert(file_path.to_string_lossy().to_string());
-                    usage.usage_lines.push(UsageLocation {
-                        file: file_path.to_string_lossy().to_string(),
-                        line: line_num + 1,
-                        context: trimmed.to_string(),
-                    });
-                }
-            }
-        }
-    }
-
-    usages
+    SymbolPatterns::compile(kmp_symbols, MatchEngine::default()).detect(content, file_path, comment_prefixes)
 }
 
 #[cfg(test)]
@@ -186,13 +201,52 @@ mod tests {
     #[test]
     fn test_platform_registry() {
         let registry = PlatformRegistry::new();
-        assert_eq!(registry.get_all().len(), 2);
+        assert_eq!(registry.get_all().len(), 5);
 
         let android = registry.get(PlatformType::Android);
         assert!(android.is_some());
 
         let ios = registry.get(PlatformType::IOS);
         assert!(ios.is_some());
+
+        let server = registry.get(PlatformType::Server);
+        assert!(server.is_some());
+
+        let desktop = registry.get(PlatformType::Desktop);
+        assert!(desktop.is_some());
+
+        let web = registry.get(PlatformType::Web);
+        assert!(web.is_some());
+    }
+
+    #[test]
+    fn test_detect_usage_with_patterns() {
+        let content = "\
+val repository: UserRepository = UserRepositoryImpl()
+// User(\"commented out\")
+val user = User (\"1\")
+val superUser = SuperUser()
+val users: List<User>
+Text(MR.strings().title.desc()) + MR.strings.title
+val title = MR.strings.titleLong";
+        let symbols: Vec<String> = ["User", "UserRepository", "UserRepositoryImpl", "MR.strings.title", "User"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let usages = detect_usage_with_patterns(content, Path::new("Main.kt"), &symbols, &["//"]);
+        let lines = |name: &str| -> Vec<usize> {
+            usages
+                .get(name)
+                .map(|usage| usage.usage_lines.iter().map(|l| l.line).collect())
+                .unwrap_or_default()
+        };
+
+        // `User` is listed twice, so each of its lines is reported twice; `User>` is no usage
+        assert_eq!(lines("User"), vec![3, 3]);
+        assert_eq!(usages["User"].reference_count, 2);
+        assert_eq!(lines("UserRepository"), vec![1]);
+        assert_eq!(lines("UserRepositoryImpl"), vec![1]);
+        assert_eq!(lines("MR.strings.title"), vec![6]);
     }
 
     #[test]
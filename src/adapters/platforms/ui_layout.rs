@@ -0,0 +1,135 @@
+//! UI layout file classification
+//! Recognizes app files that only declare UI layout: Compose files made of
+//! `@Composable` functions, and SwiftUI files made of views without logic of their
+//! own. Shared business logic can hardly reach layout lines, so impact is also
+//! reported with these files left out.
+
+use super::line_counter::SourceLanguage;
+
+/// Kotlin declarations that carry state or logic beyond a composable layout
+const KOTLIN_LOGIC_DECLARATIONS: &[&str] = &["class", "interface", "object"];
+
+/// Swift declarations that carry state or logic beyond a SwiftUI view
+const SWIFT_LOGIC_DECLARATIONS: &[&str] = &["class", "actor", "protocol", "enum"];
+
+/// Conformances of the structs a SwiftUI layout file may declare
+const SWIFT_VIEW_CONFORMANCES: &[&str] = &["View", "ViewModifier", "PreviewProvider"];
+
+impl SourceLanguage {
+    /// Whether `content` only declares UI layout
+    ///
+    /// A Kotlin file qualifies when it declares composable functions and nothing
+    /// but composable or preview functions: no classes, interfaces, or objects. A
+    /// Swift file qualifies when it declares views and nothing but structs conforming
+    /// to `View`, `ViewModifier`, or `PreviewProvider`, whose only function is a
+    /// modifier's `body`. Java, Objective-C, and JavaScript files never do.
+    pub fn is_ui_layout(self, content: &str) -> bool {
+        let lines = self.code_text(content);
+        match self {
+            SourceLanguage::Kotlin => is_compose_layout(&lines),
+            SourceLanguage::Swift => is_swiftui_layout(&lines),
+            SourceLanguage::Java | SourceLanguage::ObjectiveC | SourceLanguage::JavaScript => false,
+        }
+    }
+}
+
+fn is_compose_layout(lines: &[String]) -> bool {
+    let mut composables = 0;
+    // An annotation applies to the next function, which may be declared lines later
+    let mut annotated = false;
+    for line in lines {
+        for word in words(line) {
+            match word {
+                "@Composable" | "@Preview" => annotated = true,
+                "fun" if annotated => {
+                    composables += 1;
+                    annotated = false;
+                }
+                "fun" => return false,
+                _ if KOTLIN_LOGIC_DECLARATIONS.contains(&word) => return false,
+                _ => {}
+            }
+        }
+    }
+    composables > 0
+}
+
+fn is_swiftui_layout(lines: &[String]) -> bool {
+    let mut views = 0;
+    for line in lines {
+        let words = words(line);
+        for (index, &word) in words.iter().enumerate() {
+            match word {
+                "struct" => {
+                    if !words[index + 1..].iter().any(|w| SWIFT_VIEW_CONFORMANCES.contains(w)) {
+                        return false;
+                    }
+                    views += 1;
+                }
+                "func" if words.get(index + 1) != Some(&"body") => return false,
+                _ if SWIFT_LOGIC_DECLARATIONS.contains(&word) => return false,
+                _ => {}
+            }
+        }
+    }
+    views > 0
+}
+
+/// Identifiers and keywords of a line of code, annotations and attributes with their `@`
+fn words(line: &str) -> Vec<&str> {
+    line.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '@'))
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compose_and_swiftui_layouts() {
+        let screen = r#"package com.example.ui
+
+@Composable
+fun ProfileScreen(user: User) {
+    // The class of a user decides nothing here
+    Column(modifier = Modifier.padding(16.dp)) {
+        Text("Profile of ${user.name}, object of our attention")
+    }
+}
+
+@Preview(showBackground = true)
+@Composable
+private fun ProfileScreenPreview() = ProfileScreen(User("1", "Ada"))
+"#;
+        assert!(SourceLanguage::Kotlin.is_ui_layout(screen));
+
+        let with_state = format!("{}\nclass ProfileState(val loading: Boolean)\n", screen);
+        assert!(!SourceLanguage::Kotlin.is_ui_layout(&with_state));
+        let with_logic = format!("{}\nfun formatName(user: User) = user.name.trim()\n", screen);
+        assert!(!SourceLanguage::Kotlin.is_ui_layout(&with_logic));
+        assert!(!SourceLanguage::Kotlin.is_ui_layout("val padding = 16.dp\n"));
+
+        let view = r#"import SwiftUI
+
+struct ProfileView: View {
+    let user: User
+
+    var body: some View {
+        Text("func of \(user.name)")
+            .padding()
+    }
+}
+
+struct ProfileView_Previews: PreviewProvider {
+    static var previews: some View { ProfileView(user: User()) }
+}
+"#;
+        assert!(SourceLanguage::Swift.is_ui_layout(view));
+
+        let with_model = format!("{}\nclass ProfileModel: ObservableObject {{}}\n", view);
+        assert!(!SourceLanguage::Swift.is_ui_layout(&with_model));
+        let with_helper = view.replace("    var body", "    func initials() -> String { \"\" }\n\n    var body");
+        assert!(!SourceLanguage::Swift.is_ui_layout(&with_helper));
+    }
+}
@@ -1,11 +1,19 @@
-/// Dynamic project detection module
-/// Automatically detects KMP, Android, and iOS projects by analyzing project structure
-/// and configuration files
+//! Dynamic project detection module
+//! Automatically detects KMP, Android, iOS, server, desktop, and web projects by analyzing project structure
+//! and configuration files
 
 use anyhow::Result;
-use std::fs;
+use log::warn;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+
+use crate::adapters::build_targets;
+use crate::adapters::file_index::FileIndex;
+use crate::adapters::file_system::FileSystem;
+use crate::adapters::gradle_settings;
+use crate::adapters::platforms::js::WEB_SOURCE_EXTENSIONS;
+use crate::domain::{is_variant_dir, DetectionSettings, FileFilter, Platform};
+use crate::parser::{BuildFileInfo, GradleParser};
 
 /// Detected project information
 #[derive(Debug, Clone)]
@@ -13,60 +21,357 @@ pub struct DetectedProject {
     pub project_type: ProjectType,
     pub root_path: PathBuf,
     pub source_dirs: Vec<PathBuf>,
+    /// Strategy that found the project
+    pub detected_by: DetectionStrategy,
+    /// How sure detection is (0 ~ 1): 1.0 for an explicit plugin, lower for
+    /// weaker evidence such as configuration blocks or directory names alone
+    pub confidence: f64,
+    /// Files of the source directories that belong to the project, relative to its root;
+    /// empty for all of them
+    pub sources: FileFilter,
+}
+
+/// How a project was recognized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectionStrategy {
+    /// A Gradle build file applying the KMP, Android, Kotlin/JS, Compose Desktop, or server framework plugin
+    GradlePlugin,
+    /// An `AndroidManifest.xml` below a Gradle module
+    Manifest,
+    /// An `.xcodeproj` or `.xcworkspace`
+    XcodeProject,
+    /// Conventional directory names (`shared/src/commonMain`, `iosApp`)
+    Structure,
+    /// A Bazel or Buck target with `srcs`
+    BuildTarget,
+    /// A `package.json` next to TypeScript or JavaScript sources
+    PackageJson,
+    /// A module or platform path given in the configuration
+    Configured,
+}
+
+/// Confidence of a project recognized only by conventional directory names
+const STRUCTURE_CONFIDENCE: f64 = 0.5;
+
+impl DetectionStrategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DetectionStrategy::GradlePlugin => "gradle-plugin",
+            DetectionStrategy::Manifest => "manifest",
+            DetectionStrategy::XcodeProject => "xcodeproj",
+            DetectionStrategy::Structure => "structure",
+            DetectionStrategy::BuildTarget => "build-target",
+            DetectionStrategy::PackageJson => "package-json",
+            DetectionStrategy::Configured => "config",
+        }
+    }
 }
 
 /// Type of detected project
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum ProjectType {
     KotlinMultiplatform,
     Android,
     IOS,
+    /// JVM backend module (Ktor, Spring)
+    Server,
+    /// JVM desktop app module (Compose Desktop)
+    Desktop,
+    /// Kotlin/JS or Wasm app module, or a TypeScript/JavaScript app
+    Web,
+}
+
+impl ProjectType {
+    /// Type of the app projects of `platform`
+    pub fn for_platform(platform: &Platform) -> Self {
+        match platform {
+            Platform::Android => ProjectType::Android,
+            Platform::IOS => ProjectType::IOS,
+            Platform::Server => ProjectType::Server,
+            Platform::Desktop => ProjectType::Desktop,
+            Platform::Web => ProjectType::Web,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProjectType::KotlinMultiplatform => "KMP",
+            ProjectType::Android => "Android",
+            ProjectType::IOS => "iOS",
+            ProjectType::Server => "Server",
+            ProjectType::Desktop => "Desktop",
+            ProjectType::Web => "Web",
+        }
+    }
 }
 
+/// Build file markers of JVM server frameworks
+const SERVER_FRAMEWORK_MARKERS: &[&str] = &[
+    "io.ktor.plugin",
+    "ktor-server",
+    "org.springframework.boot",
+    "spring-boot-starter",
+];
+
+/// Words in a module directory name that mark it as a sample rather than production code
+const SAMPLE_MODULE_WORDS: &[&str] = &[
+    "sample", "samples", "demo", "demos", "example", "examples", "benchmark", "benchmarks",
+    "playground", "showcase",
+];
+
+/// Gradle plugins only applied to test, benchmark, or baseline profile modules
+const SAMPLE_MODULE_PLUGINS: &[&str] = &[
+    "com.android.test",
+    "androidx.benchmark",
+    "androidx.baselineprofile",
+];
+
 /// Main project detector
-pub struct ProjectDetector;
+pub struct ProjectDetector<'a> {
+    fs: &'a dyn FileSystem,
+    settings: DetectionSettings,
+    /// Directory the file globs of the settings are relative to
+    scan_root: Option<PathBuf>,
+    gradle: GradleParser,
+}
+
+impl<'a> ProjectDetector<'a> {
+    pub fn new(fs: &'a dyn FileSystem) -> Self {
+        Self {
+            fs,
+            settings: DetectionSettings::default(),
+            scan_root: None,
+            gradle: GradleParser::new(),
+        }
+    }
+
+    /// Replaces the default detection depth and root hints
+    pub fn with_settings(mut self, settings: DetectionSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Applies the include and exclude globs of the settings to source files, relative to `root`
+    pub fn with_scan_root(mut self, root: &Path) -> Self {
+        self.scan_root = Some(root.to_path_buf());
+        self
+    }
 
-impl ProjectDetector {
     /// Scans a directory and detects all projects
-    pub fn detect_all_projects(root_path: &Path) -> Result<Vec<DetectedProject>> {
+    ///
+    /// The directory, every root hint below it, and every build its Gradle settings
+    /// include with `includeBuild(...)` are searched; a project found from several
+    /// roots is reported once. Module and platform paths of the settings then replace
+    /// what was detected for their project type.
+    pub fn detect_all_projects(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
+        let mut projects = self.detect_all_roots(root_path)?;
+
+        let mut configured = Vec::new();
+        if !self.settings.module_paths.is_empty() {
+            configured.push((ProjectType::KotlinMultiplatform, &self.settings.module_paths));
+        }
+        for (platform, paths) in &self.settings.platform_paths {
+            configured.push((ProjectType::for_platform(platform), paths));
+        }
+        for (project_type, paths) in configured {
+            projects.retain(|project| project.project_type != project_type);
+            for path in paths {
+                let project_dir = root_path.join(path);
+                if !self.fs.is_dir(&project_dir) {
+                    warn!("Configured {} path {} is not a directory", project_type.name(), project_dir.display());
+                    continue;
+                }
+                projects.push(DetectedProject {
+                    source_dirs: self.configured_source_dirs(&project_type, &project_dir)?,
+                    project_type: project_type.clone(),
+                    root_path: project_dir,
+                    detected_by: DetectionStrategy::Configured,
+                    confidence: 1.0,
+                    sources: FileFilter::default(),
+                });
+            }
+        }
+
+        Ok(projects)
+    }
+
+    /// Source directories of a configured module or app: the conventional ones of its
+    /// type, or the whole directory when it has none
+    fn configured_source_dirs(&self, project_type: &ProjectType, project_dir: &Path) -> Result<Vec<PathBuf>> {
+        let source_dirs = match project_type {
+            ProjectType::KotlinMultiplatform => self.find_kmp_source_dirs(project_dir)?,
+            ProjectType::Android => self.find_android_source_dirs(project_dir)?,
+            ProjectType::IOS => self.find_ios_source_dirs(project_dir)?,
+            ProjectType::Server | ProjectType::Desktop | ProjectType::Web => Vec::new(),
+        };
+        if source_dirs.is_empty() {
+            return Ok(vec![project_dir.to_path_buf()]);
+        }
+        Ok(source_dirs)
+    }
+
+    /// Detects projects from the directory, its root hints, and its included builds
+    fn detect_all_roots(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
+        let mut projects = self.detect_from(root_path)?;
+
+        let mut extra_roots = Vec::new();
+        for hint in &self.settings.root_hints {
+            let hint_root = root_path.join(hint);
+            if !self.fs.is_dir(&hint_root) {
+                warn!("Project root hint {} is not a directory", hint_root.display());
+                continue;
+            }
+            extra_roots.push(hint_root);
+        }
+        for build_root in gradle_settings::included_builds(self.fs, root_path)? {
+            if !self.fs.is_dir(&build_root) {
+                warn!("Included build {} is not a directory", build_root.display());
+                continue;
+            }
+            extra_roots.push(build_root);
+        }
+
+        for extra_root in extra_roots {
+            for project in self.detect_from(&extra_root)? {
+                let known = projects.iter().any(|p: &DetectedProject| {
+                    p.project_type == project.project_type && p.root_path == project.root_path
+                });
+                if !known {
+                    projects.push(project);
+                }
+            }
+        }
+
+        Ok(projects)
+    }
+
+    /// Runs every strategy from one search root
+    ///
+    /// The tree is listed once up front; every strategy reads that listing.
+    fn detect_from(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
+        let index = FileIndex::build(self.fs, root_path, self.settings.depth);
+        let detector = ProjectDetector {
+            fs: &index,
+            settings: self.settings.clone(),
+            scan_root: self.scan_root.clone(),
+            gradle: self.gradle.clone(),
+        };
+        detector.detect_with_strategies(root_path)
+    }
+
+    fn detect_with_strategies(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
         let mut projects = Vec::new();
 
         // Find KMP projects
-        projects.extend(Self::find_kmp_projects(root_path)?);
+        projects.extend(self.find_kmp_projects(root_path)?);
 
         // Find Android projects
-        projects.extend(Self::find_android_projects(root_path)?);
+        projects.extend(self.find_android_projects(root_path)?);
 
         // Find iOS projects
-        projects.extend(Self::find_ios_projects(root_path)?);
+        projects.extend(self.find_ios_projects(root_path)?);
+
+        // Find JVM server projects
+        projects.extend(self.find_server_projects(root_path)?);
+
+        // Find JVM desktop apps
+        projects.extend(self.find_desktop_projects(root_path)?);
+
+        // Find Kotlin/JS and TypeScript/JavaScript web apps
+        projects.extend(self.find_web_projects(root_path)?);
+
+        // Find Bazel and Buck targets
+        if self.settings.build_targets {
+            projects.extend(self.find_build_target_projects(root_path)?);
+        }
+
+        Ok(projects)
+    }
+
+    /// Finds Bazel and Buck packages declaring KMP, Android, or iOS targets
+    ///
+    /// One project is reported per package and type, attributed the files its
+    /// targets' `srcs` match; files of nested packages belong to those.
+    fn find_build_target_projects(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
+        let build_files: Vec<PathBuf> = self
+            .fs
+            .walk(root_path, self.settings.depth)
+            .into_iter()
+            .filter(|entry| {
+                !entry.is_dir
+                    && entry
+                        .path
+                        .file_name()
+                        .is_some_and(|name| build_targets::BUILD_FILES.iter().any(|f| name == *f))
+            })
+            .map(|entry| entry.path)
+            .collect();
+
+        let mut projects: Vec<DetectedProject> = Vec::new();
+        for build_file in &build_files {
+            let Some(package) = build_file.parent() else {
+                continue;
+            };
+            let nested_packages: Vec<String> = build_files
+                .iter()
+                .filter_map(|other| other.parent()?.strip_prefix(package).ok())
+                .filter(|relative| !relative.as_os_str().is_empty())
+                .map(|relative| relative.to_string_lossy().replace('\\', "/") + "/")
+                .collect();
+
+            let content = self.fs.read_to_string(build_file)?;
+            for target in build_targets::parse(&content) {
+                let known = projects
+                    .iter()
+                    .position(|p| p.project_type == target.project_type && p.root_path == package);
+                let index = known.unwrap_or_else(|| {
+                    projects.push(DetectedProject {
+                        project_type: target.project_type.clone(),
+                        root_path: package.to_path_buf(),
+                        source_dirs: vec![package.to_path_buf()],
+                        detected_by: DetectionStrategy::BuildTarget,
+                        confidence: 1.0,
+                        sources: FileFilter {
+                            include: Vec::new(),
+                            exclude: nested_packages.clone(),
+                        },
+                    });
+                    projects.len() - 1
+                });
+                let project = &mut projects[index];
+                project.sources.include.extend(target.sources.include);
+                project.sources.exclude.extend(target.sources.exclude);
+            }
+        }
 
         Ok(projects)
     }
 
     /// Finds Kotlin Multiplatform projects
-    fn find_kmp_projects(root_path: &Path) -> Result<Vec<DetectedProject>> {
+    fn find_kmp_projects(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
         let mut projects = Vec::new();
 
         // Strategy 1: Look for build.gradle.kts with kotlin("multiplatform")
-        for entry in WalkDir::new(root_path)
-            .max_depth(5)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-            if path.file_name() == Some("build.gradle.kts".as_ref())
-                || path.file_name() == Some("build.gradle".as_ref())
+        for entry in self.fs.walk(root_path, self.settings.depth) {
+            let path = entry.path.as_path();
+            if path.file_name() != Some("build.gradle.kts".as_ref())
+                && path.file_name() != Some("build.gradle".as_ref())
             {
-                if Self::is_kmp_gradle_file(path)? {
-                    if let Some(project_dir) = path.parent() {
-                        let source_dirs = Self::find_kmp_source_dirs(project_dir)?;
-                        if !source_dirs.is_empty() {
-                            projects.push(DetectedProject {
-                                project_type: ProjectType::KotlinMultiplatform,
-                                root_path: project_dir.to_path_buf(),
-                                source_dirs,
-                            });
-                        }
+                continue;
+            }
+            if let Some(confidence) = self.kmp_gradle_confidence(path)? {
+                if let Some(project_dir) = path.parent() {
+                    let source_dirs = self.find_kmp_source_dirs(project_dir)?;
+                    if !source_dirs.is_empty() {
+                        projects.push(DetectedProject {
+                            project_type: ProjectType::KotlinMultiplatform,
+                            root_path: project_dir.to_path_buf(),
+                            source_dirs,
+                            detected_by: DetectionStrategy::GradlePlugin,
+                            confidence,
+                            sources: FileFilter::default(),
+                        });
                     }
                 }
             }
@@ -74,32 +379,47 @@ impl ProjectDetector {
 
         // Strategy 2: Look for typical KMP directory structures
         if projects.is_empty() {
-            projects.extend(Self::find_kmp_by_structure(root_path)?);
+            projects.extend(self.find_kmp_by_structure(root_path)?);
         }
 
         Ok(projects)
     }
 
+    /// What a Gradle build script declares
+    fn build_file(&self, path: &Path) -> Result<BuildFileInfo> {
+        Ok(self.gradle.parse(path, &self.fs.read_to_string(path)?))
+    }
+
     /// Checks if a gradle file is a KMP project
-    fn is_kmp_gradle_file(path: &Path) -> Result<bool> {
-        let content = fs::read_to_string(path)?;
+    fn is_kmp_gradle_file(&self, path: &Path) -> Result<bool> {
+        Ok(self.kmp_gradle_confidence(path)?.is_some())
+    }
 
-        // Check for multiplatform plugin
-        let has_multiplatform = content.contains("kotlin(\"multiplatform\")")
-            || content.contains("kotlin-multiplatform")
-            || content.contains("org.jetbrains.kotlin.multiplatform");
+    /// Confidence that a gradle file belongs to a KMP project, `None` if it does not
+    ///
+    /// The multiplatform plugin is conclusive; source set configuration alone is not.
+    fn kmp_gradle_confidence(&self, path: &Path) -> Result<Option<f64>> {
+        let content = self.fs.read_to_string(path)?;
+        let build = self.gradle.parse(path, &content);
 
         // Check for KMP-specific configurations
-        let has_kmp_config = content.contains("commonMain")
+        let has_kmp_config = !build.targets.is_empty()
+            || content.contains("commonMain")
             || content.contains("androidMain")
             || content.contains("iosMain")
             || content.contains("sourceSets");
 
-        Ok(has_multiplatform || has_kmp_config)
+        Ok(if build.is_multiplatform {
+            Some(1.0)
+        } else if has_kmp_config {
+            Some(0.7)
+        } else {
+            None
+        })
     }
 
     /// Finds KMP source directories within a project
-    fn find_kmp_source_dirs(project_root: &Path) -> Result<Vec<PathBuf>> {
+    fn find_kmp_source_dirs(&self, project_root: &Path) -> Result<Vec<PathBuf>> {
         let mut source_dirs = Vec::new();
 
         // Common KMP source set names
@@ -110,6 +430,14 @@ impl ProjectDetector {
             "androidMain",
             "iosMain/kotlin",
             "iosMain",
+            "jvmMain/kotlin",
+            "jvmMain",
+            "desktopMain/kotlin",
+            "desktopMain",
+            "jsMain/kotlin",
+            "jsMain",
+            "wasmJsMain/kotlin",
+            "wasmJsMain",
             "commonTest/kotlin",
             "commonTest",
             "src/commonMain/kotlin",
@@ -118,25 +446,29 @@ impl ProjectDetector {
             "src/androidMain",
             "src/iosMain/kotlin",
             "src/iosMain",
+            "src/jvmMain/kotlin",
+            "src/jvmMain",
+            "src/desktopMain/kotlin",
+            "src/desktopMain",
+            "src/jsMain/kotlin",
+            "src/jsMain",
+            "src/wasmJsMain/kotlin",
+            "src/wasmJsMain",
         ];
 
         for source_set in &kmp_source_sets {
             let src_path = project_root.join(source_set);
-            if src_path.exists() && src_path.is_dir() {
+            if self.fs.is_dir(&src_path) {
                 source_dirs.push(src_path);
             }
         }
 
         // Also look for "shared" module (common in KMP projects)
         let shared_path = project_root.join("shared/src");
-        if shared_path.exists() {
-            for entry in WalkDir::new(shared_path)
-                .max_depth(3)
-                .into_iter()
-                .filter_map(|e| e.ok())
-            {
-                let path = entry.path();
-                if path.is_dir()
+        if self.fs.exists(&shared_path) {
+            for entry in self.fs.walk(&shared_path, 3) {
+                let path = entry.path.as_path();
+                if entry.is_dir
                     && (path.ends_with("commonMain") || path.ends_with("kotlin"))
                 {
                     source_dirs.push(path.to_path_buf());
@@ -148,25 +480,24 @@ impl ProjectDetector {
     }
 
     /// Finds KMP projects by directory structure patterns
-    fn find_kmp_by_structure(root_path: &Path) -> Result<Vec<DetectedProject>> {
+    fn find_kmp_by_structure(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
         let mut projects = Vec::new();
 
         // Look for directories with "shared" + commonMain structure
-        for entry in WalkDir::new(root_path)
-            .max_depth(3)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-            if path.is_dir() && path.file_name() == Some("shared".as_ref()) {
+        for entry in self.fs.walk(root_path, self.settings.depth.saturating_sub(2)) {
+            let path = entry.path.as_path();
+            if entry.is_dir && path.file_name() == Some("shared".as_ref()) {
                 let common_main = path.join("src/commonMain");
-                if common_main.exists() {
-                    let source_dirs = Self::find_kmp_source_dirs(path)?;
+                if self.fs.exists(&common_main) {
+                    let source_dirs = self.find_kmp_source_dirs(path)?;
                     if !source_dirs.is_empty() {
                         projects.push(DetectedProject {
                             project_type: ProjectType::KotlinMultiplatform,
                             root_path: path.to_path_buf(),
                             source_dirs,
+                            detected_by: DetectionStrategy::Structure,
+                            confidence: STRUCTURE_CONFIDENCE,
+                            sources: FileFilter::default(),
                         });
                     }
                 }
@@ -177,38 +508,39 @@ impl ProjectDetector {
     }
 
     /// Finds Android projects
-    fn find_android_projects(root_path: &Path) -> Result<Vec<DetectedProject>> {
+    fn find_android_projects(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
         let mut projects = Vec::new();
 
         // Strategy 1: Look for AndroidManifest.xml
-        for entry in WalkDir::new(root_path)
-            .max_depth(5)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
+        for entry in self.fs.walk(root_path, self.settings.depth) {
+            let path = entry.path.as_path();
             if path.file_name() == Some("AndroidManifest.xml".as_ref()) {
                 if let Some(manifest_dir) = path.parent() {
                     // Go up to find the module root (usually one or two levels up)
                     let mut project_root = manifest_dir;
+                    let mut has_build_file = false;
                     for _ in 0..3 {
                         if let Some(parent) = project_root.parent() {
                             let build_gradle = parent.join("build.gradle");
                             let build_gradle_kts = parent.join("build.gradle.kts");
-                            if build_gradle.exists() || build_gradle_kts.exists() {
-                                project_root = parent;
+                            project_root = parent;
+                            if self.fs.exists(&build_gradle) || self.fs.exists(&build_gradle_kts) {
+                                has_build_file = true;
                                 break;
                             }
-                            project_root = parent;
                         }
                     }
 
-                    let source_dirs = Self::find_android_source_dirs(project_root)?;
+                    let source_dirs = self.find_android_source_dirs(project_root)?;
                     if !source_dirs.is_empty() {
                         projects.push(DetectedProject {
                             project_type: ProjectType::Android,
                             root_path: project_root.to_path_buf(),
                             source_dirs,
+                            detected_by: DetectionStrategy::Manifest,
+                            // Without a build file the module root is a guess
+                            confidence: if has_build_file { 0.9 } else { 0.6 },
+                            sources: FileFilter::default(),
                         });
                     }
                 }
@@ -217,14 +549,14 @@ impl ProjectDetector {
 
         // Strategy 2: Look for build.gradle with Android plugin
         if projects.is_empty() {
-            projects.extend(Self::find_android_by_gradle(root_path)?);
+            projects.extend(self.find_android_by_gradle(root_path)?);
         }
 
         Ok(projects)
     }
 
     /// Finds Android source directories
-    fn find_android_source_dirs(project_root: &Path) -> Result<Vec<PathBuf>> {
+    fn find_android_source_dirs(&self, project_root: &Path) -> Result<Vec<PathBuf>> {
         let mut source_dirs = Vec::new();
 
         // Common Android source directories
@@ -243,40 +575,55 @@ impl ProjectDetector {
 
         for pattern in &android_src_patterns {
             let src_path = project_root.join(pattern);
-            if src_path.exists() && src_path.is_dir() {
+            if self.fs.is_dir(&src_path) {
                 // Check if it contains actual source files
-                if Self::contains_source_files(&src_path, &["kt", "java"])? {
+                if self.contains_source_files(&src_path, &["kt", "java"])? {
                     source_dirs.push(src_path);
                 }
             }
         }
 
+        // Source sets of the requested variants (`src/paid`, `src/paidRelease`)
+        if !self.settings.variants.is_empty() {
+            for src in ["src", "app/src", "android/src", "androidApp/src"] {
+                for entry in self.fs.walk(&project_root.join(src), 1) {
+                    let is_variant = entry.is_dir
+                        && entry.path.file_name().and_then(|n| n.to_str()).is_some_and(|name| {
+                            self.settings.variants.iter().any(|variant| is_variant_dir(name, variant))
+                        });
+                    if is_variant && self.contains_source_files(&entry.path, &["kt", "java"])? {
+                        source_dirs.push(entry.path);
+                    }
+                }
+            }
+        }
+
         Ok(source_dirs)
     }
 
     /// Finds Android projects by analyzing gradle files
-    fn find_android_by_gradle(root_path: &Path) -> Result<Vec<DetectedProject>> {
+    fn find_android_by_gradle(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
         let mut projects = Vec::new();
 
-        for entry in WalkDir::new(root_path)
-            .max_depth(5)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-            if path.file_name() == Some("build.gradle.kts".as_ref())
-                || path.file_name() == Some("build.gradle".as_ref())
+        for entry in self.fs.walk(root_path, self.settings.depth) {
+            let path = entry.path.as_path();
+            if path.file_name() != Some("build.gradle.kts".as_ref())
+                && path.file_name() != Some("build.gradle".as_ref())
             {
-                if Self::is_android_gradle_file(path)? {
-                    if let Some(project_dir) = path.parent() {
-                        let source_dirs = Self::find_android_source_dirs(project_dir)?;
-                        if !source_dirs.is_empty() {
-                            projects.push(DetectedProject {
-                                project_type: ProjectType::Android,
-                                root_path: project_dir.to_path_buf(),
-                                source_dirs,
-                            });
-                        }
+                continue;
+            }
+            if let Some(confidence) = self.android_gradle_confidence(path)? {
+                if let Some(project_dir) = path.parent() {
+                    let source_dirs = self.find_android_source_dirs(project_dir)?;
+                    if !source_dirs.is_empty() {
+                        projects.push(DetectedProject {
+                            project_type: ProjectType::Android,
+                            root_path: project_dir.to_path_buf(),
+                            source_dirs,
+                            detected_by: DetectionStrategy::GradlePlugin,
+                            confidence,
+                            sources: FileFilter::default(),
+                        });
                     }
                 }
             }
@@ -286,35 +633,45 @@ impl ProjectDetector {
     }
 
     /// Checks if a gradle file is an Android project
-    fn is_android_gradle_file(path: &Path) -> Result<bool> {
-        let content = fs::read_to_string(path)?;
+    fn is_android_gradle_file(&self, path: &Path) -> Result<bool> {
+        Ok(self.android_gradle_confidence(path)?.is_some())
+    }
 
-        Ok(content.contains("com.android.application")
-            || content.contains("com.android.library")
-            || content.contains("android {"))
+    /// Confidence that a gradle file belongs to an Android project, `None` if it does not
+    fn android_gradle_confidence(&self, path: &Path) -> Result<Option<f64>> {
+        let content = self.fs.read_to_string(path)?;
+
+        Ok(
+            if self.gradle.parse(path, &content).is_android() {
+                Some(1.0)
+            } else if content.contains("android {") {
+                Some(0.8)
+            } else {
+                None
+            },
+        )
     }
 
     /// Finds iOS projects
-    fn find_ios_projects(root_path: &Path) -> Result<Vec<DetectedProject>> {
+    fn find_ios_projects(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
         let mut projects = Vec::new();
 
         // Strategy 1: Look for .xcodeproj or .xcworkspace
-        for entry in WalkDir::new(root_path)
-            .max_depth(4)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
+        for entry in self.fs.walk(root_path, self.settings.depth.saturating_sub(1)) {
+            let path = entry.path.as_path();
             if let Some(file_name) = path.file_name() {
                 let name = file_name.to_string_lossy();
                 if name.ends_with(".xcodeproj") || name.ends_with(".xcworkspace") {
                     if let Some(project_dir) = path.parent() {
-                        let source_dirs = Self::find_ios_source_dirs(project_dir)?;
+                        let source_dirs = self.find_ios_source_dirs(project_dir)?;
                         if !source_dirs.is_empty() {
                             projects.push(DetectedProject {
                                 project_type: ProjectType::IOS,
                                 root_path: project_dir.to_path_buf(),
                                 source_dirs,
+                                detected_by: DetectionStrategy::XcodeProject,
+                                confidence: 0.9,
+                                sources: FileFilter::default(),
                             });
                         }
                     }
@@ -324,14 +681,14 @@ impl ProjectDetector {
 
         // Strategy 2: Look for typical iOS directory structures
         if projects.is_empty() {
-            projects.extend(Self::find_ios_by_structure(root_path)?);
+            projects.extend(self.find_ios_by_structure(root_path)?);
         }
 
         Ok(projects)
     }
 
     /// Finds iOS source directories
-    fn find_ios_source_dirs(project_root: &Path) -> Result<Vec<PathBuf>> {
+    fn find_ios_source_dirs(&self, project_root: &Path) -> Result<Vec<PathBuf>> {
         let mut source_dirs = Vec::new();
 
         // Common iOS app directory names
@@ -339,16 +696,16 @@ impl ProjectDetector {
 
         for dir_name in &ios_dir_names {
             let ios_path = project_root.join(dir_name);
-            if ios_path.exists() && ios_path.is_dir() {
+            if self.fs.is_dir(&ios_path) {
                 // Check if it contains Swift or Objective-C files
-                if Self::contains_source_files(&ios_path, &["swift", "m", "mm"])? {
+                if self.contains_source_files(&ios_path, &["swift", "m", "mm"])? {
                     source_dirs.push(ios_path.clone());
                 }
 
                 // Also check subdirectory with the same name (e.g., iosApp/iosApp)
                 let sub_dir = ios_path.join(dir_name);
-                if sub_dir.exists()
-                    && Self::contains_source_files(&sub_dir, &["swift", "m", "mm"])?
+                if self.fs.exists(&sub_dir)
+                    && self.contains_source_files(&sub_dir, &["swift", "m", "mm"])?
                 {
                     source_dirs.push(sub_dir);
                 }
@@ -357,38 +714,215 @@ impl ProjectDetector {
 
         // Look for any directory containing Swift files
         if source_dirs.is_empty() {
-            for entry in WalkDir::new(project_root)
-                .max_depth(3)
-                .into_iter()
-                .filter_map(|e| e.ok())
-            {
-                let path = entry.path();
-                if path.is_dir()
-                    && Self::contains_source_files(path, &["swift", "m", "mm"])?
+            for entry in self.fs.walk(project_root, 3) {
+                let path = entry.path.as_path();
+                if entry.is_dir
+                    && self.contains_source_files(path, &["swift", "m", "mm"])?
                 {
                     source_dirs.push(path.to_path_buf());
                 }
             }
         }
 
+        // Local Swift packages the app is split into (e.g. `iosApp/Packages/Features`)
+        for entry in self.fs.walk(project_root, 4) {
+            let path = entry.path.as_path();
+            if path.file_name() != Some("Package.swift".as_ref())
+                || path.components().any(|c| c.as_os_str() == ".build")
+            {
+                continue;
+            }
+            let Some(sources) = path.parent().map(|dir| dir.join("Sources")) else {
+                continue;
+            };
+            if self.fs.is_dir(&sources) && !source_dirs.iter().any(|dir| sources.starts_with(dir)) {
+                source_dirs.push(sources);
+            }
+        }
+
         Ok(source_dirs)
     }
 
     /// Finds iOS projects by directory structure
-    fn find_ios_by_structure(root_path: &Path) -> Result<Vec<DetectedProject>> {
+    fn find_ios_by_structure(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
         let mut projects = Vec::new();
 
         let ios_indicators = ["iosApp", "iOS", "ios"];
 
         for indicator in &ios_indicators {
             let ios_path = root_path.join(indicator);
-            if ios_path.exists() && ios_path.is_dir() {
-                let source_dirs = Self::find_ios_source_dirs(&ios_path)?;
+            if self.fs.is_dir(&ios_path) {
+                let source_dirs = self.find_ios_source_dirs(&ios_path)?;
                 if !source_dirs.is_empty() {
                     projects.push(DetectedProject {
                         project_type: ProjectType::IOS,
                         root_path: ios_path,
                         source_dirs,
+                        detected_by: DetectionStrategy::Structure,
+                        confidence: STRUCTURE_CONFIDENCE,
+                        sources: FileFilter::default(),
+                    });
+                }
+            }
+        }
+
+        Ok(projects)
+    }
+
+    /// Finds JVM server modules (Ktor, Spring) that may consume the shared code
+    fn find_server_projects(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
+        let mut projects = Vec::new();
+
+        for entry in self.fs.walk(root_path, self.settings.depth) {
+            let path = entry.path.as_path();
+            if path.file_name() != Some("build.gradle.kts".as_ref())
+                && path.file_name() != Some("build.gradle".as_ref())
+            {
+                continue;
+            }
+            if !self.is_server_gradle_file(path)? {
+                continue;
+            }
+            let Some(project_dir) = path.parent() else {
+                continue;
+            };
+
+            let source_dirs: Vec<PathBuf> = ["src/main/kotlin", "src/main/java"]
+                .iter()
+                .map(|dir| project_dir.join(dir))
+                .filter(|dir| self.fs.is_dir(dir))
+                .collect();
+            if !source_dirs.is_empty() {
+                projects.push(DetectedProject {
+                    project_type: ProjectType::Server,
+                    root_path: project_dir.to_path_buf(),
+                    source_dirs,
+                    detected_by: DetectionStrategy::GradlePlugin,
+                    // A framework dependency also fits a library built on the framework
+                    confidence: 0.9,
+                    sources: FileFilter::default(),
+                });
+            }
+        }
+
+        Ok(projects)
+    }
+
+    /// Checks if a gradle file is a server module rather than a KMP or Android module
+    /// that merely mentions a server framework
+    fn is_server_gradle_file(&self, path: &Path) -> Result<bool> {
+        let content = self.fs.read_to_string(path)?;
+        let uses_framework = content
+            .lines()
+            .filter(|line| !line.contains("apply false"))
+            .any(|line| SERVER_FRAMEWORK_MARKERS.iter().any(|marker| line.contains(marker)));
+
+        Ok(uses_framework && !self.is_kmp_gradle_file(path)? && !self.is_android_gradle_file(path)?)
+    }
+
+    /// Finds Compose Desktop app modules
+    ///
+    /// A multiplatform module with a desktop target is a KMP project; its `desktopMain`
+    /// and `jvmMain` code counts as shared.
+    fn find_desktop_projects(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
+        let mut projects = Vec::new();
+
+        for entry in self.fs.walk(root_path, self.settings.depth) {
+            let path = entry.path.as_path();
+            if path.file_name() != Some("build.gradle.kts".as_ref())
+                && path.file_name() != Some("build.gradle".as_ref())
+            {
+                continue;
+            }
+            let Some(confidence) = self.desktop_gradle_confidence(path)? else {
+                continue;
+            };
+            let Some(project_dir) = path.parent() else {
+                continue;
+            };
+
+            let source_dirs: Vec<PathBuf> = ["src/main/kotlin", "src/main/java", "src/jvmMain/kotlin", "src/desktopMain/kotlin"]
+                .iter()
+                .map(|dir| project_dir.join(dir))
+                .filter(|dir| self.fs.is_dir(dir))
+                .collect();
+            if !source_dirs.is_empty() {
+                projects.push(DetectedProject {
+                    project_type: ProjectType::Desktop,
+                    root_path: project_dir.to_path_buf(),
+                    source_dirs,
+                    detected_by: DetectionStrategy::GradlePlugin,
+                    confidence,
+                    sources: FileFilter::default(),
+                });
+            }
+        }
+
+        Ok(projects)
+    }
+
+    /// Confidence that a gradle file belongs to a Compose Desktop app rather than a KMP or
+    /// Android module, `None` if it does not
+    ///
+    /// A `compose.desktop { application { ... } }` block is conclusive; a
+    /// `compose.desktop` dependency alone also fits a desktop UI library.
+    fn desktop_gradle_confidence(&self, path: &Path) -> Result<Option<f64>> {
+        let content = self.fs.read_to_string(path)?;
+        if !content.contains("compose.desktop") || self.is_kmp_gradle_file(path)? || self.is_android_gradle_file(path)? {
+            return Ok(None);
+        }
+
+        Ok(Some(if content.contains("compose.desktop {") { 1.0 } else { 0.9 }))
+    }
+
+    /// Finds web apps: Kotlin/JS Gradle modules, and `package.json` packages with
+    /// TypeScript or JavaScript sources that may consume a shared module's JS exports
+    fn find_web_projects(&self, root_path: &Path) -> Result<Vec<DetectedProject>> {
+        let mut projects = Vec::new();
+
+        for entry in self.fs.walk(root_path, self.settings.depth) {
+            let path = entry.path.as_path();
+            let Some(project_dir) = path.parent() else {
+                continue;
+            };
+            let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+
+            if file_name == "build.gradle.kts" || file_name == "build.gradle" {
+                let build = self.build_file(path)?;
+                let is_kotlin_js = build.applies("org.jetbrains.kotlin.js") && !build.is_multiplatform;
+                if !is_kotlin_js {
+                    continue;
+                }
+                let source_dirs: Vec<PathBuf> = ["src/main/kotlin", "src/jsMain/kotlin", "src/wasmJsMain/kotlin"]
+                    .iter()
+                    .map(|dir| project_dir.join(dir))
+                    .filter(|dir| self.fs.is_dir(dir))
+                    .collect();
+                if !source_dirs.is_empty() {
+                    projects.push(DetectedProject {
+                        project_type: ProjectType::Web,
+                        root_path: project_dir.to_path_buf(),
+                        source_dirs,
+                        detected_by: DetectionStrategy::GradlePlugin,
+                        confidence: 1.0,
+                        sources: FileFilter::default(),
+                    });
+                }
+            } else if file_name == "package.json" {
+                // Dependencies installed with `--no-ignore` are not apps
+                if path.components().any(|c| c.as_os_str() == "node_modules") {
+                    continue;
+                }
+                let source_dir = project_dir.join("src");
+                if self.fs.is_dir(&source_dir) && self.contains_source_files(&source_dir, WEB_SOURCE_EXTENSIONS)? {
+                    projects.push(DetectedProject {
+                        project_type: ProjectType::Web,
+                        root_path: project_dir.to_path_buf(),
+                        source_dirs: vec![source_dir],
+                        detected_by: DetectionStrategy::PackageJson,
+                        // Any web app has one, whether or not it consumes the shared module
+                        confidence: 0.8,
+                        sources: FileFilter::default(),
                     });
                 }
             }
@@ -398,13 +932,9 @@ impl ProjectDetector {
     }
 
     /// Checks if a directory contains source files with given extensions
-    fn contains_source_files(dir: &Path, extensions: &[&str]) -> Result<bool> {
-        for entry in WalkDir::new(dir)
-            .max_depth(10)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            if let Some(ext) = entry.path().extension() {
+    fn contains_source_files(&self, dir: &Path, extensions: &[&str]) -> Result<bool> {
+        for entry in self.fs.walk(dir, 10) {
+            if let Some(ext) = entry.path.extension() {
                 if let Some(ext_str) = ext.to_str() {
                     if extensions.contains(&ext_str) {
                         return Ok(true);
@@ -415,40 +945,142 @@ impl ProjectDetector {
         Ok(false)
     }
 
+    /// Checks whether a project is a sample, demo, or benchmark module rather than production code
+    ///
+    /// Only directories below `scan_root` are matched by name, so a checkout that itself
+    /// lives in e.g. `~/demo` is not excluded wholesale.
+    pub fn is_sample_project(&self, scan_root: &Path, project: &DetectedProject) -> Result<bool> {
+        let relative = project
+            .root_path
+            .strip_prefix(scan_root)
+            .unwrap_or(&project.root_path);
+        let named_as_sample = relative.components().any(|component| {
+            split_words(&component.as_os_str().to_string_lossy())
+                .iter()
+                .any(|word| SAMPLE_MODULE_WORDS.contains(&word.as_str()))
+        });
+        if named_as_sample {
+            return Ok(true);
+        }
+
+        for build_file in ["build.gradle.kts", "build.gradle"] {
+            let path = project.root_path.join(build_file);
+            if self.fs.is_file(&path) {
+                let build = self.build_file(&path)?;
+                if SAMPLE_MODULE_PLUGINS.iter().any(|plugin| build.applies(plugin)) {
+                    return Ok(true);
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
     /// Gets all source files from a project
-    pub fn get_all_source_files(project: &DetectedProject) -> Result<Vec<PathBuf>> {
+    pub fn get_all_source_files(&self, project: &DetectedProject) -> Result<Vec<PathBuf>> {
+        Ok(self.collect_source_files(project).0)
+    }
+
+    /// Number of files in the project's source directories left out by the exclusion settings
+    pub fn count_excluded_source_files(&self, project: &DetectedProject) -> usize {
+        self.collect_source_files(project).1
+    }
+
+    /// Source files of the project, and the number of source files excluded
+    fn collect_source_files(&self, project: &DetectedProject) -> (Vec<PathBuf>, usize) {
         let mut files = Vec::new();
+        let mut excluded = 0;
+        // Source directories may overlap (e.g. src/main and src/main/java)
+        let mut seen = HashSet::new();
 
         let extensions = match project.project_type {
             ProjectType::KotlinMultiplatform => vec!["kt", "kts"],
             ProjectType::Android => vec!["kt", "kts", "java"],
+            ProjectType::Server | ProjectType::Desktop => vec!["kt", "java"],
             ProjectType::IOS => vec!["swift", "m", "mm", "h"],
+            ProjectType::Web => [&["kt"], WEB_SOURCE_EXTENSIONS].concat(),
+        };
+        let exclusions: &[String] = match project.project_type {
+            ProjectType::Android => &self.settings.android_excluded_paths,
+            ProjectType::IOS => &self.settings.ios_excluded_dirs,
+            _ => &[],
         };
 
         for source_dir in &project.source_dirs {
-            for entry in WalkDir::new(source_dir)
-                .into_iter()
-                .filter_map(|e| e.ok())
-            {
-                if entry.file_type().is_file() {
-                    if let Some(ext) = entry.path().extension() {
-                        if let Some(ext_str) = ext.to_str() {
-                            if extensions.contains(&ext_str) {
-                                files.push(entry.path().to_path_buf());
-                            }
-                        }
-                    }
+            for entry in self.fs.walk(source_dir, usize::MAX) {
+                // TypeScript declarations (`.d.ts`) are generated typings, not sources
+                let is_source = !entry.is_dir
+                    && entry
+                        .path
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .is_some_and(|ext| extensions.contains(&ext))
+                    && !entry.path.to_string_lossy().ends_with(".d.ts");
+                if !is_source || !seen.insert(entry.path.clone()) {
+                    continue;
+                }
+                // Relative to the project, so a checkout that itself lives in e.g.
+                // `~/build` is not excluded wholesale
+                let relative = entry
+                    .path
+                    .strip_prefix(&project.root_path)
+                    .or_else(|_| entry.path.strip_prefix(source_dir))
+                    .unwrap_or(&entry.path);
+                if DetectionSettings::excluded_by(exclusions, relative).is_some()
+                    || !project.sources.allows(relative)
+                    || !self.is_selected(&entry.path)
+                {
+                    excluded += 1;
+                } else {
+                    files.push(entry.path.clone());
                 }
             }
         }
 
-        Ok(files)
+        (files, excluded)
+    }
+
+    /// Whether the include and exclude globs let a source file through
+    fn is_selected(&self, path: &Path) -> bool {
+        match &self.scan_root {
+            Some(root) => self.settings.files.allows(path.strip_prefix(root).unwrap_or(path)),
+            None => true,
+        }
     }
 }
 
+/// Splits a directory name into lowercase words at separators and camelCase boundaries
+fn split_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lower {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::adapters::file_system::{MemoryFileSystem, OsFileSystem};
+    use crate::domain::FileFilter;
     use std::fs;
     use tempfile::TempDir;
 
@@ -473,7 +1105,7 @@ mod tests {
             "class Test",
         )?;
 
-        let projects = ProjectDetector::detect_all_projects(root)?;
+        let projects = ProjectDetector::new(&OsFileSystem).detect_all_projects(root)?;
         let kmp_projects: Vec<_> = projects
             .iter()
             .filter(|p| p.project_type == ProjectType::KotlinMultiplatform)
@@ -498,7 +1130,7 @@ mod tests {
         )?;
         fs::write(app.join("src/main/java/Test.java"), "class Test {}")?;
 
-        let projects = ProjectDetector::detect_all_projects(root)?;
+        let projects = ProjectDetector::new(&OsFileSystem).detect_all_projects(root)?;
         let android_projects: Vec<_> = projects
             .iter()
             .filter(|p| p.project_type == ProjectType::Android)
@@ -509,6 +1141,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_overlapping_source_dirs_list_files_once() -> Result<()> {
+        let temp = TempDir::new()?;
+        let app = temp.path().join("app");
+        fs::create_dir_all(app.join("src/main/java"))?;
+        fs::write(
+            app.join("src/main/AndroidManifest.xml"),
+            r#"<manifest package="com.example"/>"#,
+        )?;
+        fs::write(app.join("src/main/java/Test.java"), "class Test {}")?;
+
+        let detector = ProjectDetector::new(&OsFileSystem);
+        let projects = detector.detect_all_projects(temp.path())?;
+        let android = projects
+            .iter()
+            .find(|p| p.project_type == ProjectType::Android)
+            .expect("Should detect Android project");
+        // Both src/main/java and src/main are source directories
+        assert!(android.source_dirs.len() > 1);
+
+        let files = detector.get_all_source_files(android)?;
+        assert_eq!(files, vec![app.join("src/main/java/Test.java")]);
+
+        Ok(())
+    }
+
     #[test]
     fn test_detect_ios_project() -> Result<()> {
         let temp = TempDir::new()?;
@@ -519,7 +1177,7 @@ mod tests {
         fs::create_dir_all(&ios_app)?;
         fs::write(ios_app.join("ContentView.swift"), "import SwiftUI")?;
 
-        let projects = ProjectDetector::detect_all_projects(root)?;
+        let projects = ProjectDetector::new(&OsFileSystem).detect_all_projects(root)?;
         let ios_projects: Vec<_> = projects
             .iter()
             .filter(|p| p.project_type == ProjectType::IOS)
@@ -529,4 +1187,453 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_detect_projects_in_memory() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        memory.insert("repo/shared/build.gradle.kts", "plugins { kotlin(\"multiplatform\") }");
+        memory.insert("repo/shared/src/commonMain/kotlin/Test.kt", "class Test");
+        memory.insert("repo/app/src/main/AndroidManifest.xml", "<manifest/>");
+        memory.insert("repo/app/build.gradle.kts", "plugins { id(\"com.android.application\") }");
+        memory.insert("repo/app/src/main/java/Main.kt", "class Main");
+
+        let detector = ProjectDetector::new(&memory);
+        let projects = detector.detect_all_projects(Path::new("repo"))?;
+        let kmp = projects
+            .iter()
+            .find(|p| p.project_type == ProjectType::KotlinMultiplatform)
+            .expect("Should detect KMP project");
+        assert_eq!(
+            detector.get_all_source_files(kmp)?,
+            vec![PathBuf::from("repo/shared/src/commonMain/kotlin/Test.kt")]
+        );
+        assert!(projects.iter().any(|p| p.project_type == ProjectType::Android));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_projects_from_applied_plugins() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        // The root build declares the plugins for its modules without applying them
+        memory.insert(
+            "repo/build.gradle.kts",
+            "plugins {\n    alias(libs.plugins.kotlinMultiplatform) apply false\n    alias(libs.plugins.androidApplication) apply false\n}\n",
+        );
+        memory.insert(
+            "repo/shared/build.gradle.kts",
+            "plugins {\n    alias(libs.plugins.kotlinMultiplatform)\n}\n\nkotlin {\n    iosArm64()\n}\n",
+        );
+        memory.insert("repo/shared/src/commonMain/kotlin/Greeting.kt", "class Greeting");
+        memory.insert(
+            "repo/legacy/build.gradle",
+            "// apply plugin: 'kotlin-multiplatform'\napply plugin: 'com.android.library'\n",
+        );
+        memory.insert("repo/legacy/src/main/java/Legacy.kt", "class Legacy");
+
+        let projects = ProjectDetector::new(&memory).detect_all_projects(Path::new("repo"))?;
+        let found = |project_type: ProjectType| -> Vec<(PathBuf, f64)> {
+            projects
+                .iter()
+                .filter(|p| p.project_type == project_type)
+                .map(|p| (p.root_path.clone(), p.confidence))
+                .collect()
+        };
+        assert_eq!(found(ProjectType::KotlinMultiplatform), vec![(PathBuf::from("repo/shared"), 1.0)]);
+        assert_eq!(found(ProjectType::Android), vec![(PathBuf::from("repo/legacy"), 1.0)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ios_vendored_dirs_are_excluded() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        memory.insert("repo/ios/Wallet.xcodeproj/project.pbxproj", "// Xcode project file");
+        memory.insert("repo/ios/Wallet/ContentView.swift", "import Shared");
+        memory.insert("repo/ios/Pods/Alamofire/Source/Session.swift", "open class Session {}");
+        memory.insert("repo/ios/Carthage/Checkouts/Kingfisher/Image.swift", "struct Image {}");
+        memory.insert("repo/ios/DerivedData/Build/Generated.swift", "struct Generated {}");
+        memory.insert("repo/ios/.build/checkouts/Dep.swift", "struct Dep {}");
+
+        let swift_files = |settings: DetectionSettings| -> Result<usize> {
+            let detector = ProjectDetector::new(&memory).with_settings(settings);
+            let projects = detector.detect_all_projects(Path::new("repo"))?;
+            let mut count = 0;
+            for project in projects.iter().filter(|p| p.project_type == ProjectType::IOS) {
+                count += detector.get_all_source_files(project)?.len();
+            }
+            Ok(count)
+        };
+
+        assert_eq!(swift_files(DetectionSettings::default())?, 1);
+        let detector = ProjectDetector::new(&memory);
+        let projects = detector.detect_all_projects(Path::new("repo"))?;
+        let excluded: usize = projects.iter().map(|p| detector.count_excluded_source_files(p)).sum();
+        assert_eq!(excluded, 4);
+        let everything = DetectionSettings {
+            ios_excluded_dirs: Vec::new(),
+            ..Default::default()
+        };
+        assert_eq!(swift_files(everything)?, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_globs() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        memory.insert("repo/app/build.gradle.kts", "plugins { id(\"com.android.application\") }");
+        memory.insert("repo/app/src/main/kotlin/Main.kt", "class Main");
+        memory.insert("repo/app/src/main/kotlin/MainTest.kt", "class MainTest");
+        memory.insert("repo/app/src/main/kotlin/fixtures/Fake.kt", "class Fake");
+        memory.insert("repo/app/src/main/kotlin/legacy/Old.kt", "class Old");
+
+        let files = |include: &[&str], exclude: &[&str]| -> Result<Vec<String>> {
+            let settings = DetectionSettings {
+                files: FileFilter {
+                    include: include.iter().map(|g| g.to_string()).collect(),
+                    exclude: exclude.iter().map(|g| g.to_string()).collect(),
+                },
+                ..Default::default()
+            };
+            let detector = ProjectDetector::new(&memory)
+                .with_settings(settings)
+                .with_scan_root(Path::new("repo"));
+            let mut names = Vec::new();
+            for project in detector.detect_all_projects(Path::new("repo"))? {
+                for file in detector.get_all_source_files(&project)? {
+                    names.push(file.file_name().unwrap().to_string_lossy().to_string());
+                }
+            }
+            names.sort();
+            Ok(names)
+        };
+
+        assert_eq!(files(&[], &[])?, ["Fake.kt", "Main.kt", "MainTest.kt", "Old.kt"]);
+        assert_eq!(files(&[], &["*Test.kt", "fixtures"])?, ["Main.kt", "Old.kt"]);
+        assert_eq!(files(&[], &["app/**/legacy"])?, ["Fake.kt", "Main.kt", "MainTest.kt"]);
+        assert_eq!(files(&["app/src/main/kotlin/*.kt"], &["M?inTest.kt"])?, ["Main.kt"]);
+        assert!(files(&["shared/**"], &[])?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_server_projects() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        memory.insert(
+            "repo/build.gradle.kts",
+            "plugins { id(\"io.ktor.plugin\") version \"2.3.12\" apply false }",
+        );
+        memory.insert("repo/src/main/kotlin/Build.kt", "object Build");
+        memory.insert(
+            "repo/server/build.gradle.kts",
+            "plugins { kotlin(\"jvm\"); id(\"io.ktor.plugin\") }\ndependencies { implementation(project(\":shared\")) }",
+        );
+        memory.insert("repo/server/src/main/kotlin/Application.kt", "fun main() {}");
+        memory.insert(
+            "repo/api/build.gradle",
+            "dependencies { implementation 'org.springframework.boot:spring-boot-starter-web' }",
+        );
+        memory.insert("repo/api/src/main/java/Api.java", "class Api {}");
+        memory.insert(
+            "repo/shared/build.gradle.kts",
+            "plugins { kotlin(\"multiplatform\") }\nkotlin { sourceSets { jvmMain.dependencies { implementation(\"io.ktor:ktor-server-core\") } } }",
+        );
+        memory.insert("repo/shared/src/main/kotlin/Legacy.kt", "class Legacy");
+
+        let detector = ProjectDetector::new(&memory);
+        let mut servers: Vec<PathBuf> = detector
+            .detect_all_projects(Path::new("repo"))?
+            .into_iter()
+            .filter(|p| p.project_type == ProjectType::Server)
+            .map(|p| p.root_path)
+            .collect();
+        servers.sort();
+
+        assert_eq!(servers, vec![PathBuf::from("repo/api"), PathBuf::from("repo/server")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_desktop_projects() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        memory.insert(
+            "repo/desktopApp/build.gradle.kts",
+            "plugins { kotlin(\"jvm\"); id(\"org.jetbrains.compose\") }\ndependencies { implementation(compose.desktop.currentOs) }\ncompose.desktop {\n    application { mainClass = \"MainKt\" }\n}",
+        );
+        memory.insert("repo/desktopApp/src/main/kotlin/Main.kt", "fun main() = application {}");
+        memory.insert(
+            "repo/composeApp/build.gradle.kts",
+            "plugins { kotlin(\"multiplatform\") }\nkotlin { jvm(\"desktop\") }\ncompose.desktop {\n    application { mainClass = \"MainKt\" }\n}",
+        );
+        memory.insert("repo/composeApp/src/desktopMain/kotlin/Main.kt", "fun main() {}");
+
+        let detector = ProjectDetector::new(&memory);
+        let desktop: Vec<DetectedProject> = detector
+            .detect_all_projects(Path::new("repo"))?
+            .into_iter()
+            .filter(|p| p.project_type == ProjectType::Desktop)
+            .collect();
+
+        assert_eq!(desktop.len(), 1);
+        assert_eq!(desktop[0].root_path, PathBuf::from("repo/desktopApp"));
+        assert_eq!(desktop[0].confidence, 1.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_web_projects() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        memory.insert("repo/web/package.json", "{ \"name\": \"web\" }");
+        memory.insert("repo/web/src/App.tsx", "import { User } from \"shared\";");
+        memory.insert("repo/web/src/shared.d.ts", "export declare class User {}");
+        memory.insert("repo/web/node_modules/left-pad/package.json", "{}");
+        memory.insert("repo/web/node_modules/left-pad/src/index.js", "module.exports = {}");
+        memory.insert("repo/docs/package.json", "{ \"name\": \"docs\" }");
+        memory.insert("repo/docs/README.md", "# Docs");
+        memory.insert("repo/kjs/build.gradle.kts", "plugins { kotlin(\"js\") }");
+        memory.insert("repo/kjs/src/jsMain/kotlin/Main.kt", "fun main() {}");
+
+        let detector = ProjectDetector::new(&memory);
+        let mut web: Vec<DetectedProject> = detector
+            .detect_all_projects(Path::new("repo"))?
+            .into_iter()
+            .filter(|p| p.project_type == ProjectType::Web)
+            .collect();
+        web.sort_by(|a, b| a.root_path.cmp(&b.root_path));
+
+        assert_eq!(web.len(), 2);
+        assert_eq!(web[0].root_path, PathBuf::from("repo/kjs"));
+        assert_eq!(web[0].detected_by, DetectionStrategy::GradlePlugin);
+        assert_eq!(web[1].root_path, PathBuf::from("repo/web"));
+        assert_eq!(web[1].detected_by, DetectionStrategy::PackageJson);
+        assert_eq!(detector.get_all_source_files(&web[1])?, vec![PathBuf::from("repo/web/src/App.tsx")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detection_depth_and_root_hints() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        memory.insert("mono/shared/build.gradle.kts", "plugins { kotlin(\"multiplatform\") }");
+        memory.insert("mono/shared/src/commonMain/kotlin/User.kt", "class User");
+        memory.insert(
+            "mono/products/mobile/clients/legacy/android/app/build.gradle.kts",
+            "plugins { id(\"com.android.application\") }",
+        );
+        memory.insert(
+            "mono/products/mobile/clients/legacy/android/app/src/main/kotlin/Main.kt",
+            "class Main",
+        );
+        let root = Path::new("mono");
+        let found = |detector: ProjectDetector| -> Result<Vec<(ProjectType, DetectionStrategy)>> {
+            Ok(detector
+                .detect_all_projects(root)?
+                .into_iter()
+                .map(|p| (p.project_type, p.detected_by))
+                .collect())
+        };
+
+        let kmp = (ProjectType::KotlinMultiplatform, DetectionStrategy::GradlePlugin);
+        let android = (ProjectType::Android, DetectionStrategy::GradlePlugin);
+        assert_eq!(found(ProjectDetector::new(&memory))?, vec![kmp.clone()]);
+
+        let deeper = DetectionSettings {
+            depth: 7,
+            ..Default::default()
+        };
+        assert_eq!(
+            found(ProjectDetector::new(&memory).with_settings(deeper))?,
+            vec![kmp.clone(), android.clone()]
+        );
+
+        let hinted = DetectionSettings {
+            root_hints: vec!["products/mobile/clients".to_string(), "shared".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            found(ProjectDetector::new(&memory).with_settings(hinted))?,
+            vec![kmp, android]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_included_builds() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        memory.insert("work/app/settings.gradle.kts", "includeBuild(\"../shared-kmp\")\ninclude(\":android\")");
+        memory.insert(
+            "work/app/android/build.gradle.kts",
+            "plugins { id(\"com.android.application\") }",
+        );
+        memory.insert("work/app/android/src/main/kotlin/Main.kt", "class Main");
+        memory.insert("work/shared-kmp/shared/build.gradle.kts", "plugins { kotlin(\"multiplatform\") }");
+        memory.insert("work/shared-kmp/shared/src/commonMain/kotlin/User.kt", "class User");
+
+        let projects = ProjectDetector::new(&memory).detect_all_projects(Path::new("work/app"))?;
+        let found: Vec<(ProjectType, PathBuf)> =
+            projects.into_iter().map(|p| (p.project_type, p.root_path)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (ProjectType::Android, PathBuf::from("work/app/android")),
+                (ProjectType::KotlinMultiplatform, PathBuf::from("work/shared-kmp/shared")),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_configured_module_and_platform_paths() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        memory.insert("repo/shared/build.gradle.kts", "plugins { kotlin(\"multiplatform\") }");
+        memory.insert("repo/shared/src/commonMain/kotlin/User.kt", "class User");
+        memory.insert("repo/core/model/src/commonMain/kotlin/Id.kt", "class Id");
+        memory.insert("repo/androidApp/build.gradle.kts", "plugins { id(\"com.android.application\") }");
+        memory.insert("repo/androidApp/src/main/kotlin/Main.kt", "class Main");
+        memory.insert("repo/legacy/Screen.kt", "class Screen");
+        memory.insert("repo/iosApp/iosApp.xcodeproj/project.pbxproj", "");
+        memory.insert("repo/iosApp/iosApp/ContentView.swift", "struct ContentView {}");
+
+        let settings = DetectionSettings {
+            module_paths: vec!["core/model".to_string()],
+            platform_paths: vec![(Platform::Android, vec!["legacy".to_string(), "missing".to_string()])],
+            ..Default::default()
+        };
+        let projects = ProjectDetector::new(&memory).with_settings(settings).detect_all_projects(Path::new("repo"))?;
+        let found: Vec<(ProjectType, PathBuf, DetectionStrategy)> =
+            projects.iter().map(|p| (p.project_type.clone(), p.root_path.clone(), p.detected_by)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (ProjectType::IOS, PathBuf::from("repo/iosApp"), DetectionStrategy::XcodeProject),
+                (
+                    ProjectType::KotlinMultiplatform,
+                    PathBuf::from("repo/core/model"),
+                    DetectionStrategy::Configured,
+                ),
+                (ProjectType::Android, PathBuf::from("repo/legacy"), DetectionStrategy::Configured),
+            ]
+        );
+        assert_eq!(projects[1].source_dirs, vec![PathBuf::from("repo/core/model/src/commonMain/kotlin"), PathBuf::from("repo/core/model/src/commonMain")]);
+        assert_eq!(projects[2].source_dirs, vec![PathBuf::from("repo/legacy")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_build_targets() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        memory.insert(
+            "mono/shared/BUILD.bazel",
+            "kt_multiplatform_library(name = \"shared\", srcs = glob([\"src/**/*.kt\"]))",
+        );
+        memory.insert("mono/shared/src/User.kt", "class User");
+        memory.insert(
+            "mono/android/BUILD",
+            "kt_android_library(\n    name = \"app\",\n    srcs = glob([\"src/**/*.kt\"], exclude = [\"**/*Fake.kt\"]),\n)",
+        );
+        memory.insert("mono/android/src/Main.kt", "class Main");
+        memory.insert("mono/android/src/UserFake.kt", "class UserFake");
+        memory.insert("mono/android/scripts/Tool.kt", "class Tool");
+        memory.insert("mono/android/feature/BUILD", "kt_android_library(name = \"feature\", srcs = [\"src/Feature.kt\"])");
+        memory.insert("mono/android/feature/src/Feature.kt", "class Feature");
+        let root = Path::new("mono");
+
+        assert!(ProjectDetector::new(&memory).detect_all_projects(root)?.is_empty());
+
+        let settings = DetectionSettings {
+            build_targets: true,
+            ..Default::default()
+        };
+        let detector = ProjectDetector::new(&memory).with_settings(settings);
+        let mut files: Vec<(ProjectType, DetectionStrategy, Vec<PathBuf>)> = Vec::new();
+        for project in detector.detect_all_projects(root)? {
+            let mut sources = detector.get_all_source_files(&project)?;
+            sources.sort();
+            files.push((project.project_type, project.detected_by, sources));
+        }
+        files.sort_by(|a, b| a.2.cmp(&b.2));
+        assert_eq!(
+            files,
+            vec![
+                (
+                    ProjectType::Android,
+                    DetectionStrategy::BuildTarget,
+                    vec![PathBuf::from("mono/android/feature/src/Feature.kt")]
+                ),
+                (
+                    ProjectType::Android,
+                    DetectionStrategy::BuildTarget,
+                    vec![PathBuf::from("mono/android/src/Main.kt")]
+                ),
+                (
+                    ProjectType::KotlinMultiplatform,
+                    DetectionStrategy::BuildTarget,
+                    vec![PathBuf::from("mono/shared/src/User.kt")]
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detection_confidence() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        memory.insert("repo/core/build.gradle.kts", "kotlin { sourceSets { commonMain { } } }");
+        memory.insert("repo/core/src/commonMain/kotlin/Core.kt", "class Core");
+        memory.insert("repo/app/build.gradle", "android { namespace 'com.example' }");
+        memory.insert("repo/app/src/main/kotlin/Main.kt", "class Main");
+        memory.insert("repo/iosApp/iosApp/ContentView.swift", "import SwiftUI");
+
+        let projects: Vec<(ProjectType, DetectionStrategy, f64)> = ProjectDetector::new(&memory)
+            .detect_all_projects(Path::new("repo"))?
+            .into_iter()
+            .map(|p| (p.project_type, p.detected_by, p.confidence))
+            .collect();
+
+        assert_eq!(
+            projects,
+            vec![
+                (ProjectType::KotlinMultiplatform, DetectionStrategy::GradlePlugin, 0.7),
+                (ProjectType::Android, DetectionStrategy::GradlePlugin, 0.8),
+                (ProjectType::IOS, DetectionStrategy::Structure, STRUCTURE_CONFIDENCE),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_sample_modules() -> Result<()> {
+        let mut memory = MemoryFileSystem::new();
+        memory.insert("demo/app/build.gradle.kts", "plugins { id(\"com.android.application\") }");
+        memory.insert("demo/sampleApp/build.gradle.kts", "plugins { id(\"com.android.application\") }");
+        memory.insert("demo/macrobench/build.gradle.kts", "plugins { id(\"com.android.test\") }");
+
+        let detector = ProjectDetector::new(&memory);
+        let root = Path::new("demo");
+        let project = |dir: &str| DetectedProject {
+            project_type: ProjectType::Android,
+            root_path: root.join(dir),
+            source_dirs: Vec::new(),
+            detected_by: DetectionStrategy::GradlePlugin,
+            confidence: 1.0,
+            sources: FileFilter::default(),
+        };
+
+        assert!(!detector.is_sample_project(root, &project("app"))?);
+        assert!(detector.is_sample_project(root, &project("sampleApp"))?);
+        assert!(detector.is_sample_project(root, &project("macrobench"))?);
+        assert!(detector.is_sample_project(root, &project("examples/compose"))?);
+        assert_eq!(split_words("androidDemo-v2"), vec!["android", "demo", "v2"]);
+
+        Ok(())
+    }
 }
@@ -1,20 +1,49 @@
 use anyhow::Result;
-use std::sync::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
+use crate::adapters::module_graph::ModuleGraphBuilder;
+use crate::adapters::swift_modules::SwiftModuleDetector;
 use crate::analyzer::dependency_graph::DependencyGraph;
-use crate::domain::{DependencyRepository, SourceFile};
+use crate::domain::{
+    DependencyEdge, DependencyRepository, DetectionSettings, GradleModule, ModuleDependency, SourceFile, SwiftModule,
+};
 
 /// Adapter implementation of DependencyRepository
 pub struct DependencyRepositoryImpl {
+    fs: Arc<dyn FileSystem>,
+    detection: DetectionSettings,
     graph: Mutex<DependencyGraph>,
 }
 
 impl DependencyRepositoryImpl {
     pub fn new() -> Self {
         Self {
+            fs: Arc::new(OsFileSystem),
+            detection: DetectionSettings::default(),
             graph: Mutex::new(DependencyGraph::new()),
         }
     }
+
+    /// Reads sources and build files through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// Lets imports a file never refers to make it a transitive dependent
+    pub fn with_unused_imports(mut self, follow: bool) -> Self {
+        self.graph = Mutex::new(DependencyGraph::new().with_unused_imports(follow));
+        self
+    }
+
+    /// Finds Gradle modules with the given detection depth and root hints
+    pub fn with_detection(mut self, detection: DetectionSettings) -> Self {
+        self.detection = detection;
+        self
+    }
 }
 
 impl Default for DependencyRepositoryImpl {
@@ -25,15 +54,39 @@ impl Default for DependencyRepositoryImpl {
 
 impl DependencyRepository for DependencyRepositoryImpl {
     fn build_dependency_graph(&self, file_paths: &[String]) -> Result<()> {
-        let paths: Vec<std::path::PathBuf> = file_paths
+        let sources = file_paths
             .iter()
-            .map(|s| std::path::PathBuf::from(s))
-            .collect();
+            .map(|path| {
+                let path = PathBuf::from(path);
+                let content = self.fs.read_to_string(&path)?;
+                Ok((path, content))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-        self.graph.lock().unwrap().build(&paths)?;
+        self.graph.lock().unwrap().build_from_sources(&sources);
         Ok(())
     }
 
+    fn build_module_graph(&self, project_path: &str) -> Result<Vec<ModuleDependency>> {
+        ModuleGraphBuilder::new()
+            .with_file_system(self.fs.clone())
+            .with_detection(self.detection.clone())
+            .build(Path::new(project_path))
+    }
+
+    fn find_android_modules(&self, project_path: &str) -> Result<Vec<GradleModule>> {
+        ModuleGraphBuilder::new()
+            .with_file_system(self.fs.clone())
+            .with_detection(self.detection.clone())
+            .android_modules(Path::new(project_path))
+    }
+
+    fn find_swift_modules(&self, project_path: &str) -> Result<Vec<SwiftModule>> {
+        SwiftModuleDetector::new()
+            .with_file_system(self.fs.clone())
+            .detect(Path::new(project_path))
+    }
+
     fn calculate_transitive_dependencies(&self, direct_files: &[String]) -> Result<Vec<String>> {
         let direct_set: std::collections::HashSet<String> =
             direct_files.iter().cloned().collect();
@@ -43,6 +96,22 @@ impl DependencyRepository for DependencyRepositoryImpl {
         Ok(transitive_set.into_iter().collect())
     }
 
+    fn calculate_file_dependencies(&self, files: &[String]) -> Result<HashMap<String, HashSet<String>>> {
+        let graph = self.graph.lock().unwrap();
+        Ok(files
+            .iter()
+            .map(|file| (file.clone(), graph.get_all_dependencies(file)))
+            .collect())
+    }
+
+    fn find_dependency_edges(&self, file: &str) -> Result<Vec<DependencyEdge>> {
+        Ok(self.graph.lock().unwrap().edges_from(file))
+    }
+
+    fn find_dependency_chain(&self, file: &str, targets: &HashSet<String>) -> Result<Vec<DependencyEdge>> {
+        Ok(self.graph.lock().unwrap().dependency_chain(file, targets))
+    }
+
     fn extract_imports(&self, source_file: &SourceFile) -> Result<Vec<String>> {
         use regex::Regex;
 
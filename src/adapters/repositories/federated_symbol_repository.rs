@@ -0,0 +1,105 @@
+use anyhow::Result;
+use log::info;
+use std::collections::HashSet;
+
+use crate::adapters::symbol_manifest::SymbolManifest;
+use crate::domain::{FrameworkHeader, ImplicitPublicDeclaration, OwnerRule, Symbol, SymbolRepository, SymbolType};
+
+/// SymbolRepository decorator that adds symbols published by other repositories
+///
+/// Local extraction still runs; manifest symbols are appended unless a local
+/// symbol with the same package, name, and type already exists.
+pub struct FederatedSymbolRepository<'a> {
+    inner: &'a dyn SymbolRepository,
+    manifests: Vec<SymbolManifest>,
+}
+
+impl<'a> FederatedSymbolRepository<'a> {
+    pub fn new(inner: &'a dyn SymbolRepository, manifests: Vec<SymbolManifest>) -> Self {
+        Self { inner, manifests }
+    }
+}
+
+impl SymbolRepository for FederatedSymbolRepository<'_> {
+    fn extract_kmp_symbols(&self, kmp_file_paths: &[String]) -> Result<Vec<Symbol>> {
+        let mut symbols = self.inner.extract_kmp_symbols(kmp_file_paths)?;
+
+        let mut known: HashSet<(String, String, SymbolType)> = symbols
+            .iter()
+            .map(|s| (s.package.clone(), s.name.clone(), s.symbol_type.clone()))
+            .collect();
+
+        for manifest in &self.manifests {
+            let before = symbols.len();
+            for symbol in manifest.symbols.iter().map(|s| &s.symbol) {
+                let key = (
+                    symbol.package.clone(),
+                    symbol.name.clone(),
+                    symbol.symbol_type.clone(),
+                );
+                if known.insert(key) {
+                    symbols.push(symbol.clone());
+                }
+            }
+            info!(
+                "Loaded {} symbols from manifest '{}'",
+                symbols.len() - before,
+                manifest.source
+            );
+        }
+
+        Ok(symbols)
+    }
+
+    fn extract_resource_symbols(&self, project_path: &str) -> Result<Vec<Symbol>> {
+        self.inner.extract_resource_symbols(project_path)
+    }
+
+    fn extract_generated_symbols(&self, project_path: &str) -> Result<Vec<Symbol>> {
+        self.inner.extract_generated_symbols(project_path)
+    }
+
+    fn find_framework_headers(&self, project_path: &str, kmp_file_paths: &[String]) -> Result<Vec<FrameworkHeader>> {
+        self.inner.find_framework_headers(project_path, kmp_file_paths)
+    }
+
+    fn find_owner_rules(&self, kmp_file_paths: &[String]) -> Result<Vec<OwnerRule>> {
+        self.inner.find_owner_rules(kmp_file_paths)
+    }
+
+    fn find_implicit_public_declarations(&self, kmp_file_paths: &[String]) -> Result<Vec<ImplicitPublicDeclaration>> {
+        self.inner.find_implicit_public_declarations(kmp_file_paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str) -> Symbol {
+        Symbol {
+            package: "com.example".to_string(),
+            ..Symbol::new(name, SymbolType::Class, "shared", "shared/src/commonMain/kotlin/User.kt")
+        }
+    }
+
+    struct LocalRepository;
+
+    impl SymbolRepository for LocalRepository {
+        fn extract_kmp_symbols(&self, _paths: &[String]) -> Result<Vec<Symbol>> {
+            Ok(vec![symbol("User")])
+        }
+    }
+
+    #[test]
+    fn test_merges_manifest_symbols() {
+        let manifest =
+            SymbolManifest::from_symbols("shared-lib", vec![symbol("User"), symbol("UserRepository")]);
+        let local = LocalRepository;
+        let repo = FederatedSymbolRepository::new(&local, vec![manifest]);
+
+        let symbols = repo.extract_kmp_symbols(&[]).unwrap();
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "UserRepository"]);
+    }
+}
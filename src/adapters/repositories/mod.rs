@@ -1,11 +1,25 @@
-/// Repository implementations for the domain layer
+//! Repository implementations for the domain layer
 
 pub mod symbol_repository_impl;
 pub mod source_file_repository_impl;
 pub mod symbol_usage_repository_impl;
 pub mod dependency_repository_impl;
+pub mod federated_symbol_repository;
+pub mod warm_start_repository;
+pub mod symbol_history_repository_impl;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod run_history_repository_impl;
+pub mod run_stats_repository_impl;
+pub mod suppression_repository_impl;
 
 pub use symbol_repository_impl::SymbolRepositoryImpl;
 pub use source_file_repository_impl::SourceFileRepositoryImpl;
 pub use symbol_usage_repository_impl::SymbolUsageRepositoryImpl;
 pub use dependency_repository_impl::DependencyRepositoryImpl;
+pub use federated_symbol_repository::FederatedSymbolRepository;
+pub use warm_start_repository::{WarmStart, WarmStartSymbolRepository, WarmStartUsageRepository};
+pub use symbol_history_repository_impl::SymbolHistoryRepositoryImpl;
+#[cfg(not(target_arch = "wasm32"))]
+pub use run_history_repository_impl::RunHistoryRepositoryImpl;
+pub use run_stats_repository_impl::RunStatsRepositoryImpl;
+pub use suppression_repository_impl::SuppressionRepositoryImpl;
@@ -0,0 +1,323 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::domain::{ApiSymbol, RunHistoryRepository, RunSnapshot, SymbolType};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS runs (
+        id INTEGER PRIMARY KEY,
+        generated_at INTEGER NOT NULL,
+        commit_sha TEXT,
+        impact_ratio REAL NOT NULL,
+        build_url TEXT,
+        has_api INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS platform_ratios (
+        run_id INTEGER NOT NULL REFERENCES runs(id) ON DELETE CASCADE,
+        platform TEXT NOT NULL,
+        impact_ratio REAL NOT NULL,
+        PRIMARY KEY (run_id, platform)
+    );
+    CREATE TABLE IF NOT EXISTS symbol_references (
+        run_id INTEGER NOT NULL REFERENCES runs(id) ON DELETE CASCADE,
+        symbol TEXT NOT NULL,
+        refs INTEGER NOT NULL,
+        PRIMARY KEY (run_id, symbol)
+    );
+    CREATE TABLE IF NOT EXISTS api_symbols (
+        run_id INTEGER NOT NULL REFERENCES runs(id) ON DELETE CASCADE,
+        module TEXT NOT NULL,
+        package TEXT NOT NULL,
+        name TEXT NOT NULL,
+        symbol_type TEXT NOT NULL,
+        file TEXT NOT NULL
+    );
+";
+
+/// Adapter implementation of RunHistoryRepository backed by a SQLite database
+///
+/// Each run is a row of `runs`, with its platform ratios, symbol references, and
+/// public API in child tables, so the history can also be queried with `sqlite3`.
+/// A JSON run history left by older versions is imported on the first load: either
+/// the configured file itself (`file = "run-history.json"`), kept as is while the
+/// database is written to a `.db` sibling, or a `.json` sibling of the database.
+pub struct RunHistoryRepositoryImpl {
+    path: PathBuf,
+    legacy_path: PathBuf,
+}
+
+impl RunHistoryRepositoryImpl {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let configured = path.into();
+        let is_json = configured.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+            || (configured.extension().is_none_or(|ext| ext != "db") && is_json_file(&configured));
+        if is_json {
+            Self {
+                path: configured.with_extension("db"),
+                legacy_path: configured,
+            }
+        } else {
+            Self {
+                legacy_path: configured.with_extension("json"),
+                path: configured,
+            }
+        }
+    }
+
+    fn open(&self) -> Result<Connection> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let connection = Connection::open(&self.path)
+            .with_context(|| format!("Failed to open run history {}", self.path.display()))?;
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+        connection
+            .execute_batch(SCHEMA)
+            .with_context(|| format!("Invalid run history {}", self.path.display()))?;
+        Ok(connection)
+    }
+
+    fn load_legacy_json(&self) -> Result<Vec<RunSnapshot>> {
+        if !self.legacy_path.is_file() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.legacy_path)
+            .with_context(|| format!("Failed to read run history {}", self.legacy_path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid run history {}", self.legacy_path.display()))
+    }
+}
+
+/// Whether `path` is a JSON document rather than a SQLite database
+fn is_json_file(path: &Path) -> bool {
+    let mut head = [0u8; 64];
+    let Ok(read) = fs::File::open(path).and_then(|mut file| file.read(&mut head)) else {
+        return false;
+    };
+    head[..read]
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|byte| matches!(byte, b'[' | b'{'))
+}
+
+impl RunHistoryRepository for RunHistoryRepositoryImpl {
+    fn load(&self) -> Result<Vec<RunSnapshot>> {
+        if !self.path.is_file() {
+            return self.load_legacy_json();
+        }
+
+        let connection = self.open()?;
+        let mut runs_query = connection.prepare(
+            "SELECT id, generated_at, commit_sha, impact_ratio, build_url, has_api FROM runs ORDER BY id",
+        )?;
+        let rows = runs_query.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, f64>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, bool>(5)?,
+            ))
+        })?;
+
+        let mut platforms_query =
+            connection.prepare("SELECT platform, impact_ratio FROM platform_ratios WHERE run_id = ?1")?;
+        let mut references_query =
+            connection.prepare("SELECT symbol, refs FROM symbol_references WHERE run_id = ?1")?;
+        let mut api_query = connection.prepare(
+            "SELECT module, package, name, symbol_type, file FROM api_symbols WHERE run_id = ?1 ORDER BY rowid",
+        )?;
+
+        let mut runs = Vec::new();
+        for row in rows {
+            let (id, generated_at, commit_sha, impact_ratio, build_url, has_api) = row?;
+            let platform_ratios = platforms_query
+                .query_map([id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<rusqlite::Result<BTreeMap<String, f64>>>()?;
+            let symbol_references = references_query
+                .query_map([id], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?
+                .collect::<rusqlite::Result<BTreeMap<String, usize>>>()?;
+            let api = if has_api {
+                let symbols = api_query
+                    .query_map([id], |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get::<_, String>(3)?, row.get(4)?))
+                    })?
+                    .map(|row| {
+                        let (module, package, name, symbol_type, file) = row?;
+                        Ok(ApiSymbol {
+                            module,
+                            package,
+                            name,
+                            symbol_type: parse_symbol_type(&symbol_type)?,
+                            file,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Some(symbols)
+            } else {
+                None
+            };
+
+            runs.push(RunSnapshot {
+                generated_at: generated_at as u64,
+                commit_sha,
+                impact_ratio,
+                platform_ratios,
+                symbol_references,
+                api,
+                build_url,
+            });
+        }
+
+        Ok(runs)
+    }
+
+    fn save(&self, runs: &[RunSnapshot]) -> Result<()> {
+        let mut connection = self.open()?;
+        let transaction = connection.transaction()?;
+
+        // The use case hands over the whole trimmed history, so replace it in one transaction
+        transaction.execute("DELETE FROM runs", [])?;
+        for run in runs {
+            insert_run(&transaction, run)?;
+        }
+
+        transaction
+            .commit()
+            .with_context(|| format!("Failed to write run history {}", self.path.display()))
+    }
+}
+
+fn insert_run(connection: &Connection, run: &RunSnapshot) -> Result<()> {
+    connection.execute(
+        "INSERT INTO runs (generated_at, commit_sha, impact_ratio, build_url, has_api) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![run.generated_at as i64, run.commit_sha, run.impact_ratio, run.build_url, run.api.is_some()],
+    )?;
+    let run_id = connection.last_insert_rowid();
+
+    for (platform, ratio) in &run.platform_ratios {
+        connection.execute(
+            "INSERT INTO platform_ratios (run_id, platform, impact_ratio) VALUES (?1, ?2, ?3)",
+            params![run_id, platform, ratio],
+        )?;
+    }
+    for (symbol, refs) in &run.symbol_references {
+        connection.execute(
+            "INSERT INTO symbol_references (run_id, symbol, refs) VALUES (?1, ?2, ?3)",
+            params![run_id, symbol, *refs as i64],
+        )?;
+    }
+    for symbol in run.api.iter().flatten() {
+        connection.execute(
+            "INSERT INTO api_symbols (run_id, module, package, name, symbol_type, file) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                run_id,
+                symbol.module,
+                symbol.package,
+                symbol.name,
+                format_symbol_type(&symbol.symbol_type)?,
+                symbol.file
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Symbol types are stored under their JSON name (`DataClass`), as in reports and manifests
+fn format_symbol_type(symbol_type: &SymbolType) -> Result<String> {
+    match serde_json::to_value(symbol_type)? {
+        serde_json::Value::String(name) => Ok(name),
+        other => Ok(other.to_string()),
+    }
+}
+
+fn parse_symbol_type(name: &str) -> Result<SymbolType> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .with_context(|| format!("Unknown symbol type {name} in run history"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn snapshot(generated_at: u64, api: Option<Vec<ApiSymbol>>) -> RunSnapshot {
+        RunSnapshot {
+            generated_at,
+            commit_sha: Some("abc123".to_string()),
+            impact_ratio: 0.4,
+            platform_ratios: BTreeMap::from([("android".to_string(), 0.5), ("ios".to_string(), 0.25)]),
+            symbol_references: BTreeMap::from([("User".to_string(), 7)]),
+            api,
+            build_url: None,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let temp = TempDir::new()?;
+        let repo = RunHistoryRepositoryImpl::new(temp.path().join(".kmp-coverage/run-history.db"));
+        assert!(repo.load()?.is_empty());
+
+        let api = vec![ApiSymbol {
+            module: "shared".to_string(),
+            package: "com.example".to_string(),
+            name: "User".to_string(),
+            symbol_type: SymbolType::DataClass,
+            file: "User.kt".to_string(),
+        }];
+        let runs = vec![snapshot(10, None), snapshot(20, Some(api))];
+        repo.save(&runs)?;
+        assert_eq!(repo.load()?, runs);
+
+        repo.save(&runs[1..])?;
+        assert_eq!(repo.load()?, runs[1..]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_imports_legacy_json_history() -> Result<()> {
+        let temp = TempDir::new()?;
+        let runs = vec![snapshot(10, None)];
+        fs::write(temp.path().join("run-history.json"), serde_json::to_string(&runs)?)?;
+
+        let repo = RunHistoryRepositoryImpl::new(temp.path().join("run-history.db"));
+        assert_eq!(repo.load()?, runs);
+        repo.save(&runs)?;
+        assert!(temp.path().join("run-history.db").is_file());
+        assert_eq!(repo.load()?, runs);
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrates_configured_json_history() -> Result<()> {
+        let temp = TempDir::new()?;
+        let configured = temp.path().join(".kmp-coverage/run-history.json");
+        fs::create_dir_all(configured.parent().unwrap())?;
+        let runs = vec![snapshot(10, None)];
+        fs::write(&configured, serde_json::to_string(&runs)?)?;
+
+        let repo = RunHistoryRepositoryImpl::new(&configured);
+        assert_eq!(repo.load()?, runs);
+        let runs = vec![snapshot(10, None), snapshot(20, None)];
+        repo.save(&runs)?;
+        assert!(temp.path().join(".kmp-coverage/run-history.db").is_file());
+        assert_eq!(RunHistoryRepositoryImpl::new(&configured).load()?, runs);
+
+        // A JSON history under a name without the `.json` extension migrates the same way
+        let renamed = temp.path().join("history");
+        fs::write(&renamed, serde_json::to_string(&runs[..1])?)?;
+        let repo = RunHistoryRepositoryImpl::new(&renamed);
+        assert_eq!(repo.load()?, runs[..1]);
+        repo.save(&runs)?;
+        assert!(temp.path().join("history.db").is_file());
+        assert_eq!(RunHistoryRepositoryImpl::new(&renamed).load()?, runs);
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::domain::{RunStats, RunStatsRepository};
+
+/// Comment written at the top of the statistics file
+const HEADER: &str = "# Anonymous kmp-coverage run statistics, kept on this machine only.\n\
+# Nothing is sent anywhere; attach this file to bug reports if you like.\n\n";
+
+/// Adapter implementation of RunStatsRepository backed by a TOML file
+pub struct RunStatsRepositoryImpl {
+    path: PathBuf,
+}
+
+impl RunStatsRepositoryImpl {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl RunStatsRepository for RunStatsRepositoryImpl {
+    fn load(&self) -> Result<RunStats> {
+        if !self.path.is_file() {
+            return Ok(RunStats::default());
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read run statistics {}", self.path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid run statistics {}", self.path.display()))
+    }
+
+    fn save(&self, stats: &RunStats) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = format!("{}{}", HEADER, toml::to_string_pretty(stats)?);
+        fs::write(&self.path, content)
+            .with_context(|| format!("Failed to write run statistics {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::RunSample;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let temp = TempDir::new()?;
+        let repo = RunStatsRepositoryImpl::new(temp.path().join(".kmp-coverage/run-stats.toml"));
+        assert_eq!(repo.load()?, RunStats::default());
+
+        let mut stats = RunStats::default();
+        let sample = RunSample {
+            duration_ms: 1_200,
+            app_files: 250,
+            shared_symbols: 12,
+            format: "html".to_string(),
+            features: vec!["history".to_string(), "redact".to_string()],
+        };
+        stats.record(&sample, "0.1.0", 1_700_000_000);
+        stats.record(&RunSample { duration_ms: 800, ..sample }, "0.1.0", 1_700_000_600);
+        repo.save(&stats)?;
+
+        let content = fs::read_to_string(temp.path().join(".kmp-coverage/run-stats.toml"))?;
+        assert!(content.starts_with("# Anonymous"));
+        let loaded = repo.load()?;
+        assert_eq!(loaded, stats);
+        assert_eq!(loaded.runs, 2);
+        assert_eq!(loaded.first_run, 1_700_000_000);
+        assert_eq!((loaded.total_duration_ms, loaded.max_duration_ms, loaded.last_duration_ms), (2_000, 1_200, 800));
+        assert_eq!(loaded.app_files["100-999"], 2);
+        assert_eq!(loaded.shared_symbols["10-99"], 2);
+        assert_eq!(loaded.features["redact"], 2);
+        Ok(())
+    }
+}
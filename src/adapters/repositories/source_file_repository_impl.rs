@@ -1,25 +1,82 @@
 use anyhow::Result;
 use log::{debug, info};
 use std::collections::HashMap;
-use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
+use crate::adapters::file_system::{FileStamp, FileSystem, OsFileSystem};
 use crate::adapters::project_detector::{ProjectDetector, ProjectType};
 use crate::adapters::platforms::{PlatformRegistry, PlatformType};
-use crate::domain::{Language, Platform, SourceFile, SourceFileRepository};
+use crate::domain::{
+    DetectionSettings, Language, Platform, ProjectDetection, SourceFile, SourceFileRepository,
+};
 use crate::utils::FileUtils;
 
 /// Adapter implementation of SourceFileRepository with dynamic project detection
+///
+/// File contents are cached by path and reused until the file's modification
+/// time or size changes. Sample, demo, and benchmark app modules are left out
+/// of the app files unless [`with_sample_modules`](Self::with_sample_modules) is set.
 pub struct SourceFileRepositoryImpl {
+    fs: Arc<dyn FileSystem>,
     platform_registry: PlatformRegistry,
+    include_sample_modules: bool,
+    detection: DetectionSettings,
+    cache: RwLock<HashMap<String, CachedSourceFile>>,
+}
+
+/// Cached file content with the stamp it was read at
+struct CachedSourceFile {
+    stamp: FileStamp,
+    source_file: SourceFile,
 }
 
 impl SourceFileRepositoryImpl {
     pub fn new() -> Self {
         Self {
+            fs: Arc::new(OsFileSystem),
             platform_registry: PlatformRegistry::new(),
+            include_sample_modules: false,
+            detection: DetectionSettings::default(),
+            cache: RwLock::new(HashMap::new()),
         }
     }
 
+    /// Reads project files through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.platform_registry = PlatformRegistry::with_settings(fs.clone(), &self.detection);
+        self.fs = fs;
+        self
+    }
+
+    /// Counts sample, demo, and benchmark app modules as app code
+    pub fn with_sample_modules(mut self, include: bool) -> Self {
+        self.include_sample_modules = include;
+        self
+    }
+
+    /// Replaces the default project detection depth and root hints
+    pub fn with_detection(mut self, detection: DetectionSettings) -> Self {
+        self.platform_registry = PlatformRegistry::with_settings(self.fs.clone(), &detection);
+        self.detection = detection;
+        self
+    }
+
+    fn detector(&self, root: &Path) -> ProjectDetector<'_> {
+        ProjectDetector::new(self.fs.as_ref())
+            .with_settings(self.detection.clone())
+            .with_scan_root(root)
+    }
+
+    /// Drops the files the include and exclude globs leave out
+    fn select(&self, root: &Path, files: Vec<PathBuf>) -> Vec<String> {
+        files
+            .into_iter()
+            .filter(|file| self.detection.files.allows(file.strip_prefix(root).unwrap_or(file)))
+            .map(|file| file.to_string_lossy().to_string())
+            .collect()
+    }
+
     fn detect_language(file_path: &str) -> Language {
         if file_path.ends_with(".kt") || file_path.ends_with(".kts") {
             Language::Kotlin
@@ -27,6 +84,10 @@ impl SourceFileRepositoryImpl {
             Language::Java
         } else if file_path.ends_with(".swift") {
             Language::Swift
+        } else if file_path.ends_with(".ts") || file_path.ends_with(".tsx") {
+            Language::TypeScript
+        } else if [".js", ".jsx", ".mjs"].iter().any(|ext| file_path.ends_with(ext)) {
+            Language::JavaScript
         } else {
             Language::ObjectiveC
         }
@@ -36,6 +97,19 @@ impl SourceFileRepositoryImpl {
         match platform_type {
             PlatformType::Android => Platform::Android,
             PlatformType::IOS => Platform::IOS,
+            PlatformType::Server => Platform::Server,
+            PlatformType::Desktop => Platform::Desktop,
+            PlatformType::Web => Platform::Web,
+        }
+    }
+
+    fn platform_type(platform: Platform) -> PlatformType {
+        match platform {
+            Platform::Android => PlatformType::Android,
+            Platform::IOS => PlatformType::IOS,
+            Platform::Server => PlatformType::Server,
+            Platform::Desktop => PlatformType::Desktop,
+            Platform::Web => PlatformType::Web,
         }
     }
 }
@@ -48,11 +122,12 @@ impl Default for SourceFileRepositoryImpl {
 
 impl SourceFileRepository for SourceFileRepositoryImpl {
     fn find_kmp_files(&self, project_path: &str) -> Result<Vec<String>> {
-        let path = std::path::Path::new(project_path);
+        let path = Path::new(project_path);
         info!("🔍 Dynamically detecting KMP projects in: {}", project_path);
 
         // Use dynamic project detection
-        let all_projects = ProjectDetector::detect_all_projects(path)?;
+        let detector = self.detector(path);
+        let all_projects = detector.detect_all_projects(path)?;
         let kmp_projects: Vec<_> = all_projects
             .iter()
             .filter(|p| p.project_type == ProjectType::KotlinMultiplatform)
@@ -63,10 +138,15 @@ impl SourceFileRepository for SourceFileRepositoryImpl {
         let mut kmp_files = Vec::new();
 
         for project in kmp_projects {
-            debug!("  KMP project root: {:?}", project.root_path);
+            debug!(
+                "  KMP project root: {:?} (detected by {}, confidence {:.2})",
+                project.root_path,
+                project.detected_by.name(),
+                project.confidence
+            );
             debug!("  Source directories: {} dirs", project.source_dirs.len());
 
-            let files = ProjectDetector::get_all_source_files(project)?;
+            let files = detector.get_all_source_files(project)?;
             debug!("  Source files: {}", files.len());
 
             kmp_files.extend(files.into_iter().map(|p| p.to_string_lossy().to_string()));
@@ -83,11 +163,27 @@ impl SourceFileRepository for SourceFileRepositoryImpl {
     }
 
     fn find_app_files(&self, project_path: &str) -> Result<HashMap<Platform, Vec<String>>> {
-        let path = std::path::Path::new(project_path);
+        let path = Path::new(project_path);
         info!("🔍 Dynamically detecting platform projects in: {}", project_path);
 
         // Use dynamic project detection
-        let all_projects = ProjectDetector::detect_all_projects(path)?;
+        let detector = self.detector(path);
+        let detected = detector.detect_all_projects(path)?;
+        let any_app_projects = detected
+            .iter()
+            .any(|p| p.project_type != ProjectType::KotlinMultiplatform);
+
+        let mut all_projects = Vec::new();
+        for project in detected {
+            if !self.include_sample_modules
+                && project.project_type != ProjectType::KotlinMultiplatform
+                && detector.is_sample_project(path, &project)?
+            {
+                info!("⏭️  Skipping sample module: {}", project.root_path.display());
+                continue;
+            }
+            all_projects.push(project);
+        }
 
         let mut result = HashMap::new();
 
@@ -102,8 +198,13 @@ impl SourceFileRepository for SourceFileRepositoryImpl {
             let mut android_files = Vec::new();
 
             for project in android_projects {
-                debug!("  Android project root: {:?}", project.root_path);
-                let files = ProjectDetector::get_all_source_files(project)?;
+                debug!(
+                "  Android project root: {:?} (detected by {}, confidence {:.2})",
+                project.root_path,
+                project.detected_by.name(),
+                project.confidence
+            );
+                let files = detector.get_all_source_files(project)?;
                 debug!("  Android files: {}", files.len());
                 android_files.extend(files.into_iter().map(|p| p.to_string_lossy().to_string()));
             }
@@ -123,8 +224,13 @@ impl SourceFileRepository for SourceFileRepositoryImpl {
             let mut ios_files = Vec::new();
 
             for project in ios_projects {
-                debug!("  iOS project root: {:?}", project.root_path);
-                let files = ProjectDetector::get_all_source_files(project)?;
+                debug!(
+                "  iOS project root: {:?} (detected by {}, confidence {:.2})",
+                project.root_path,
+                project.detected_by.name(),
+                project.confidence
+            );
+                let files = detector.get_all_source_files(project)?;
                 debug!("  iOS files: {}", files.len());
                 ios_files.extend(files.into_iter().map(|p| p.to_string_lossy().to_string()));
             }
@@ -133,8 +239,86 @@ impl SourceFileRepository for SourceFileRepositoryImpl {
             result.insert(Platform::IOS, ios_files);
         }
 
+        // Process JVM server projects
+        let server_projects: Vec<_> = all_projects
+            .iter()
+            .filter(|p| p.project_type == ProjectType::Server)
+            .collect();
+
+        if !server_projects.is_empty() {
+            info!("✓ Found {} server project(s)", server_projects.len());
+            let mut server_files = Vec::new();
+
+            for project in server_projects {
+                debug!(
+                "  Server project root: {:?} (detected by {}, confidence {:.2})",
+                project.root_path,
+                project.detected_by.name(),
+                project.confidence
+            );
+                let files = detector.get_all_source_files(project)?;
+                debug!("  Server files: {}", files.len());
+                server_files.extend(files.into_iter().map(|p| p.to_string_lossy().to_string()));
+            }
+
+            info!("🖥️  Total server files: {}", server_files.len());
+            result.insert(Platform::Server, server_files);
+        }
+
+        // Process JVM desktop projects
+        let desktop_projects: Vec<_> = all_projects
+            .iter()
+            .filter(|p| p.project_type == ProjectType::Desktop)
+            .collect();
+
+        if !desktop_projects.is_empty() {
+            info!("✓ Found {} desktop project(s)", desktop_projects.len());
+            let mut desktop_files = Vec::new();
+
+            for project in desktop_projects {
+                debug!(
+                "  Desktop project root: {:?} (detected by {}, confidence {:.2})",
+                project.root_path,
+                project.detected_by.name(),
+                project.confidence
+            );
+                let files = detector.get_all_source_files(project)?;
+                debug!("  Desktop files: {}", files.len());
+                desktop_files.extend(files.into_iter().map(|p| p.to_string_lossy().to_string()));
+            }
+
+            info!("💻 Total desktop files: {}", desktop_files.len());
+            result.insert(Platform::Desktop, desktop_files);
+        }
+
+        // Process Kotlin/JS and TypeScript/JavaScript web projects
+        let web_projects: Vec<_> = all_projects
+            .iter()
+            .filter(|p| p.project_type == ProjectType::Web)
+            .collect();
+
+        if !web_projects.is_empty() {
+            info!("✓ Found {} web project(s)", web_projects.len());
+            let mut web_files = Vec::new();
+
+            for project in web_projects {
+                debug!(
+                "  Web project root: {:?} (detected by {}, confidence {:.2})",
+                project.root_path,
+                project.detected_by.name(),
+                project.confidence
+            );
+                let files = detector.get_all_source_files(project)?;
+                debug!("  Web files: {}", files.len());
+                web_files.extend(files.into_iter().map(|p| p.to_string_lossy().to_string()));
+            }
+
+            info!("🌐 Total web files: {}", web_files.len());
+            result.insert(Platform::Web, web_files);
+        }
+
         // Fallback: if no projects detected, use legacy platform registry
-        if result.is_empty() {
+        if !any_app_projects {
             info!("⚠️  No platform projects auto-detected, falling back to pattern matching");
             result = self.find_app_files_legacy(path)?;
         }
@@ -142,43 +326,97 @@ impl SourceFileRepository for SourceFileRepositoryImpl {
         Ok(result)
     }
 
+    fn find_projects(&self, project_path: &str) -> Result<Vec<ProjectDetection>> {
+        let path = Path::new(project_path);
+        let relative = |dir: &Path| {
+            dir.strip_prefix(path)
+                .unwrap_or(dir)
+                .to_string_lossy()
+                .to_string()
+        };
+        let detector = self.detector(path);
+        Ok(detector
+            .detect_all_projects(path)?
+            .into_iter()
+            .map(|project| ProjectDetection {
+                project_type: project.project_type.name().to_string(),
+                root: relative(&project.root_path),
+                detected_by: project.detected_by.name().to_string(),
+                confidence: project.confidence,
+                source_dirs: project.source_dirs.iter().map(|dir| relative(dir)).collect(),
+                excluded_files: detector.count_excluded_source_files(&project),
+            })
+            .collect())
+    }
+
     fn read_source_file(&self, file_path: &str) -> Result<SourceFile> {
-        let content = fs::read_to_string(file_path)?;
+        let stamp = self.fs.stamp(Path::new(file_path))?;
+        if let Some(cached) = self.cache.read().unwrap().get(file_path) {
+            if cached.stamp == stamp {
+                return Ok(cached.source_file.clone());
+            }
+        }
+
+        let content = self.fs.read_to_string(Path::new(file_path))?;
         let language = Self::detect_language(file_path);
 
         // Detect platform from path or file extension
-        let platform = if file_path.contains("android") || file_path.ends_with(".kt") || file_path.ends_with(".java") {
+        let platform = if matches!(language, Language::TypeScript | Language::JavaScript) {
+            Platform::Web
+        } else if file_path.contains("android") || file_path.ends_with(".kt") || file_path.ends_with(".java") {
             Platform::Android
         } else {
             Platform::IOS
         };
 
-        Ok(SourceFile {
+        let source_file = SourceFile {
             path: file_path.to_string(),
             platform,
             language,
             content,
-        })
+        };
+        self.cache.write().unwrap().insert(
+            file_path.to_string(),
+            CachedSourceFile {
+                stamp,
+                source_file: source_file.clone(),
+            },
+        );
+
+        Ok(source_file)
     }
 
     fn count_code_lines(&self, content: &str, platform: Platform) -> usize {
-        let platform_type = match platform {
-            Platform::Android => PlatformType::Android,
-            Platform::IOS => PlatformType::IOS,
-        };
-
-        if let Some(platform_impl) = self.platform_registry.get(platform_type) {
+        if let Some(platform_impl) = self.platform_registry.get(Self::platform_type(platform)) {
             platform_impl.count_code_lines(content)
         } else {
             0
         }
     }
+
+    fn count_functions(&self, content: &str, platform: Platform) -> usize {
+        self.platform_registry
+            .get(Self::platform_type(platform))
+            .map_or(0, |platform_impl| platform_impl.count_functions(content))
+    }
+
+    fn is_ui_layout(&self, content: &str, platform: Platform) -> bool {
+        self.platform_registry
+            .get(Self::platform_type(platform))
+            .is_some_and(|platform_impl| platform_impl.is_ui_layout(content))
+    }
+
+    fn imports_shared_framework(&self, content: &str, platform: Platform) -> bool {
+        self.platform_registry
+            .get(Self::platform_type(platform))
+            .is_some_and(|platform_impl| platform_impl.imports_shared_framework(content))
+    }
 }
 
 // Legacy fallback methods
 impl SourceFileRepositoryImpl {
     /// Legacy method for finding KMP files using hardcoded patterns
-    fn find_kmp_files_legacy(&self, path: &std::path::Path) -> Result<Vec<String>> {
+    fn find_kmp_files_legacy(&self, path: &Path) -> Result<Vec<String>> {
         let mut kmp_files = Vec::new();
 
         // Look for commonMain, androidMain, iosMain directories
@@ -186,34 +424,30 @@ impl SourceFileRepositoryImpl {
 
         for pattern in &kmp_patterns {
             let search_path = path.join(pattern);
-            if search_path.exists() {
-                let files = FileUtils::find_kotlin_files(&search_path);
-                kmp_files.extend(files.into_iter().map(|p| p.to_string_lossy().to_string()));
+            if self.fs.exists(&search_path) {
+                let files = FileUtils::find_kotlin_files(self.fs.as_ref(), &search_path);
+                kmp_files.extend(self.select(path, files));
             }
         }
 
         // Also search for 'shared' module
         let shared_path = path.join("shared");
-        if shared_path.exists() {
-            let files = FileUtils::find_kotlin_files(&shared_path);
-            kmp_files.extend(files.into_iter().map(|p| p.to_string_lossy().to_string()));
+        if self.fs.exists(&shared_path) {
+            let files = FileUtils::find_kotlin_files(self.fs.as_ref(), &shared_path);
+            kmp_files.extend(self.select(path, files));
         }
 
         Ok(kmp_files)
     }
 
     /// Legacy method for finding app files using platform registry
-    fn find_app_files_legacy(&self, path: &std::path::Path) -> Result<HashMap<Platform, Vec<String>>> {
+    fn find_app_files_legacy(&self, path: &Path) -> Result<HashMap<Platform, Vec<String>>> {
         let platform_files = self.platform_registry.find_all_app_files(path)?;
 
         let mut result = HashMap::new();
         for (platform_type, files) in platform_files {
             let platform = Self::convert_platform(&platform_type);
-            let file_strings: Vec<String> = files
-                .into_iter()
-                .map(|p| p.to_string_lossy().to_string())
-                .collect();
-            result.insert(platform, file_strings);
+            result.insert(platform, self.select(path, files));
         }
 
         Ok(result)
@@ -0,0 +1,37 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::domain::{SuppressionBaseline, SuppressionRepository};
+
+/// Adapter implementation of SuppressionRepository backed by a JSON file
+pub struct SuppressionRepositoryImpl {
+    path: PathBuf,
+}
+
+impl SuppressionRepositoryImpl {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl SuppressionRepository for SuppressionRepositoryImpl {
+    fn load(&self) -> Result<SuppressionBaseline> {
+        if !self.path.is_file() {
+            return Ok(SuppressionBaseline::default());
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read suppressions {}", self.path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid suppressions {}", self.path.display()))
+    }
+
+    fn save(&self, baseline: &SuppressionBaseline) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(baseline)?)
+            .with_context(|| format!("Failed to write suppressions {}", self.path.display()))
+    }
+}
@@ -0,0 +1,67 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::domain::{SymbolHistory, SymbolHistoryRepository};
+
+/// Adapter implementation of SymbolHistoryRepository backed by a JSON file
+pub struct SymbolHistoryRepositoryImpl {
+    path: PathBuf,
+}
+
+impl SymbolHistoryRepositoryImpl {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl SymbolHistoryRepository for SymbolHistoryRepositoryImpl {
+    fn load(&self) -> Result<Option<SymbolHistory>> {
+        if !self.path.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read symbol history {}", self.path.display()))?;
+        let history = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid symbol history {}", self.path.display()))?;
+        Ok(Some(history))
+    }
+
+    fn save(&self, history: &SymbolHistory) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(history)?)
+            .with_context(|| format!("Failed to write symbol history {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::SymbolSighting;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let temp = TempDir::new()?;
+        let repo = SymbolHistoryRepositoryImpl::new(temp.path().join(".kmp-coverage/history.json"));
+        assert!(repo.load()?.is_none());
+
+        let mut history = SymbolHistory::default();
+        history.symbols.insert(
+            "User".to_string(),
+            SymbolSighting {
+                first_seen: 1_700_000_000,
+                runs: 3,
+                baseline: false,
+            },
+        );
+        repo.save(&history)?;
+
+        let loaded = repo.load()?.unwrap();
+        assert_eq!(loaded.symbols["User"].runs, 3);
+        Ok(())
+    }
+}
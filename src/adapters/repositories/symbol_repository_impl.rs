@@ -1,21 +1,77 @@
 use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
 
+use crate::adapters::api_owners::ApiOwnersScanner;
+use crate::adapters::explicit_api::ExplicitApiModes;
+use crate::adapters::file_system::{FileStamp, FileSystem, OsFileSystem};
+use crate::adapters::generated_api::GeneratedApiScanner;
+use crate::adapters::objc_headers::ObjCHeaderScanner;
+use crate::adapters::shared_resources::SharedResourceScanner;
 use crate::analyzer::symbol_extractor::SymbolExtractor;
-use crate::domain::{Symbol, SymbolRepository, SymbolType};
+use crate::analyzer::models::{KmpSymbol, Visibility};
+use crate::domain::{
+    ExplicitApiMode, FrameworkHeader, FrameworkHeaderSources, ImplicitPublicDeclaration, LineRange, OwnerRule, Symbol, SymbolParser, SymbolRepository, SymbolType,
+};
 
 /// Adapter implementation of SymbolRepository
 /// Uses the existing SymbolExtractor from analyzer layer
+///
+/// Extracted symbols are cached per file until its modification time or size changes,
+/// or its module's explicit API mode does.
 pub struct SymbolRepositoryImpl {
+    fs: Arc<dyn FileSystem>,
     extractor: SymbolExtractor,
+    explicit_api: ExplicitApiModes,
+    objc_headers: FrameworkHeaderSources,
+    cache: RwLock<HashMap<String, CachedSymbols>>,
+}
+
+/// Symbols extracted from one file, with the stamp and explicit API mode they were extracted at
+struct CachedSymbols {
+    stamp: FileStamp,
+    mode: Option<ExplicitApiMode>,
+    symbols: Vec<Symbol>,
 }
 
 impl SymbolRepositoryImpl {
     pub fn new() -> Self {
+        let fs: Arc<dyn FileSystem> = Arc::new(OsFileSystem);
         Self {
+            explicit_api: ExplicitApiModes::new(fs.clone()),
+            fs,
             extractor: SymbolExtractor::new(),
+            objc_headers: FrameworkHeaderSources::default(),
+            cache: RwLock::new(HashMap::new()),
         }
     }
 
+    /// Reads KMP sources through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.explicit_api = ExplicitApiModes::new(fs.clone());
+        self.fs = fs;
+        self
+    }
+
+    /// Extracts declarations with the given parser
+    pub fn with_parser(mut self, parser: SymbolParser) -> Self {
+        self.extractor = SymbolExtractor::new().with_parser(parser);
+        self
+    }
+
+    /// Reads the exported names of shared classifiers from the given framework headers
+    pub fn with_objc_headers(mut self, sources: FrameworkHeaderSources) -> Self {
+        self.objc_headers = sources;
+        self
+    }
+
+    /// Public and internal declarations of a file
+    fn extract(&self, content: &str, path: &Path) -> Vec<KmpSymbol> {
+        let module = Self::determine_module_name(&path.to_string_lossy());
+        self.extractor.extract_symbols_from_source(content, path, &module)
+    }
+
     fn determine_module_name(file_path: &str) -> String {
         if let Some(idx) = file_path.find("/src/") {
             let before_src = &file_path[..idx];
@@ -30,11 +86,19 @@ impl SymbolRepositoryImpl {
     fn convert_symbol_type(old_type: &crate::analyzer::models::SymbolType) -> SymbolType {
         match old_type {
             crate::analyzer::models::SymbolType::Class => SymbolType::Class,
+            crate::analyzer::models::SymbolType::ValueClass => SymbolType::ValueClass,
+            crate::analyzer::models::SymbolType::Enum => SymbolType::Enum,
+            crate::analyzer::models::SymbolType::EnumEntry => SymbolType::EnumEntry,
+            crate::analyzer::models::SymbolType::SealedClass => SymbolType::SealedClass,
+            crate::analyzer::models::SymbolType::DataClass => SymbolType::DataClass,
+            crate::analyzer::models::SymbolType::AnnotationClass => SymbolType::AnnotationClass,
             crate::analyzer::models::SymbolType::Interface => SymbolType::Interface,
             crate::analyzer::models::SymbolType::Object => SymbolType::Object,
             crate::analyzer::models::SymbolType::Function => SymbolType::Function,
             crate::analyzer::models::SymbolType::Property => SymbolType::Property,
             crate::analyzer::models::SymbolType::TypeAlias => SymbolType::TypeAlias,
+            crate::analyzer::models::SymbolType::Expect => SymbolType::Expect,
+            crate::analyzer::models::SymbolType::Actual => SymbolType::Actual,
         }
     }
 }
@@ -50,22 +114,103 @@ impl SymbolRepository for SymbolRepositoryImpl {
         let mut symbols = Vec::new();
 
         for file_path in kmp_file_paths {
-            let module = Self::determine_module_name(file_path);
-            let path = std::path::Path::new(file_path);
+            let path = Path::new(file_path);
+            let stamp = self.fs.stamp(path)?;
+            let mode = self.explicit_api.of_file(file_path)?;
+            if let Some(cached) = self.cache.read().unwrap().get(file_path) {
+                if cached.stamp == stamp && cached.mode == mode {
+                    symbols.extend(cached.symbols.iter().cloned());
+                    continue;
+                }
+            }
 
-            let extracted = self.extractor.extract_symbols(path, &module)?;
+            let content = self.fs.read_to_string(path)?;
+            let extracted = self.extract(&content, path);
 
-            for old_symbol in extracted {
-                symbols.push(Symbol {
+            let mut file_symbols = Vec::new();
+            // The compiler rejects implicitly public declarations in strict mode
+            for old_symbol in extracted
+                .into_iter()
+                .filter(|symbol| mode != Some(ExplicitApiMode::Strict) || symbol.visibility_declared)
+            {
+                file_symbols.push(Symbol {
                     name: old_symbol.name,
                     symbol_type: Self::convert_symbol_type(&old_symbol.symbol_type),
                     module: old_symbol.module,
+                    package: old_symbol.package,
                     file_path: old_symbol.file_path,
-                    is_public: old_symbol.is_public,
+                    is_public: old_symbol.visibility == Visibility::Public,
+                    is_documented: old_symbol.is_documented,
+                    annotations: old_symbol.annotations,
+                    actual_target: old_symbol.actual_target,
+                    js_name: old_symbol.js_name,
+                    objc_name: old_symbol.objc_name,
+                    lines: Some(LineRange::new(old_symbol.lines.0, old_symbol.lines.1)),
                 });
             }
+
+            symbols.extend(file_symbols.iter().cloned());
+            self.cache.write().unwrap().insert(
+                file_path.clone(),
+                CachedSymbols {
+                    stamp,
+                    mode,
+                    symbols: file_symbols,
+                },
+            );
         }
 
         Ok(symbols)
     }
+
+    fn extract_resource_symbols(&self, project_path: &str) -> Result<Vec<Symbol>> {
+        SharedResourceScanner::new()
+            .with_file_system(self.fs.clone())
+            .scan(Path::new(project_path))
+    }
+
+    fn extract_generated_symbols(&self, project_path: &str) -> Result<Vec<Symbol>> {
+        GeneratedApiScanner::new()
+            .with_file_system(self.fs.clone())
+            .scan(Path::new(project_path))
+    }
+
+    fn find_framework_headers(&self, project_path: &str, kmp_file_paths: &[String]) -> Result<Vec<FrameworkHeader>> {
+        if self.objc_headers.is_empty() {
+            return Ok(Vec::new());
+        }
+        ObjCHeaderScanner::new()
+            .with_file_system(self.fs.clone())
+            .scan(&self.objc_headers, Path::new(project_path), kmp_file_paths)
+    }
+
+    fn find_owner_rules(&self, kmp_file_paths: &[String]) -> Result<Vec<OwnerRule>> {
+        ApiOwnersScanner::new()
+            .with_file_system(self.fs.clone())
+            .scan(kmp_file_paths)
+    }
+
+    fn find_implicit_public_declarations(&self, kmp_file_paths: &[String]) -> Result<Vec<ImplicitPublicDeclaration>> {
+        let mut declarations = Vec::new();
+        for file_path in kmp_file_paths {
+            let Some(mode) = self.explicit_api.of_file(file_path)? else {
+                continue;
+            };
+            let path = Path::new(file_path);
+            let content = self.fs.read_to_string(path)?;
+            declarations.extend(
+                self.extract(&content, path)
+                    .into_iter()
+                    // Members of internal classes are internal without a modifier
+                    .filter(|symbol| !symbol.visibility_declared && symbol.visibility == Visibility::Public)
+                    .map(|symbol| ImplicitPublicDeclaration {
+                        name: symbol.name,
+                        file_path: symbol.file_path,
+                        line: symbol.lines.0,
+                        mode,
+                    }),
+            );
+        }
+        Ok(declarations)
+    }
 }
@@ -1,14 +1,87 @@
 use anyhow::Result;
+use regex::Regex;
 
-use crate::domain::{SourceFile, Symbol, SymbolUsage, SymbolUsageRepository};
-use crate::adapters::platforms::detect_usage_with_patterns;
+use crate::domain::{Language, MatchEngine, SourceFile, Symbol, SymbolUsage, SymbolUsageRepository, UsageKind};
+use crate::adapters::flow_consumption::FlowConsumptionDetector;
+use crate::adapters::pattern_cache::PatternCache;
+use crate::adapters::platforms::ios::ExportedNames;
+use crate::adapters::swift_macros::SwiftMacroDetector;
+use crate::adapters::viewmodel_bridges::ViewModelBridgeDetector;
 
 /// Adapter implementation of SymbolUsageRepository
-pub struct SymbolUsageRepositoryImpl;
+///
+/// Besides matching symbol names, files are checked for shared ViewModels
+/// reached through bridge libraries, and Swift files for consumption of shared
+/// Flows through their interop wrappers and shared types named in macros and
+/// builder closures. TypeScript and JavaScript files see
+/// shared symbols under their `@JsName`, Swift and Objective-C files under the
+/// names Kotlin/Native exports them with as well. Symbol name patterns are compiled once
+/// per name list and shared by all files.
+pub struct SymbolUsageRepositoryImpl {
+    patterns: PatternCache,
+    exported_names: ExportedNames,
+    flow_consumption: FlowConsumptionDetector,
+    swift_macros: SwiftMacroDetector,
+    viewmodel_bridges: ViewModelBridgeDetector,
+    /// Match: import { User, Session as AppSession } from "shared"
+    js_import_regex: Regex,
+}
 
 impl SymbolUsageRepositoryImpl {
     pub fn new() -> Self {
-        Self
+        Self {
+            patterns: PatternCache::default(),
+            exported_names: ExportedNames::new(),
+            flow_consumption: FlowConsumptionDetector::new(),
+            swift_macros: SwiftMacroDetector::new(),
+            viewmodel_bridges: ViewModelBridgeDetector::new(),
+            js_import_regex: Regex::new(
+                r#"(?m)^\s*import\s+(?:type\s+)?(?:[\w$]+\s*,\s*)?\{([^}]*)\}\s*from\s*['"]"#,
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Searches app files for symbol names with the given engine
+    pub fn with_match_engine(mut self, engine: MatchEngine) -> Self {
+        self.patterns = PatternCache::new(engine);
+        self
+    }
+
+    /// The compiled symbol name patterns
+    pub fn pattern_cache(&self) -> &PatternCache {
+        &self.patterns
+    }
+
+    fn is_web(source_file: &SourceFile) -> bool {
+        matches!(source_file.language, Language::TypeScript | Language::JavaScript)
+    }
+
+    /// Name `source_file` refers to `symbol` by: its `@JsName` in TypeScript and JavaScript
+    fn name_in<'a>(source_file: &SourceFile, symbol: &'a Symbol) -> &'a str {
+        match &symbol.js_name {
+            Some(js_name) if Self::is_web(source_file) => js_name,
+            _ => &symbol.name,
+        }
+    }
+
+    /// Names `source_file` may refer to each symbol by, with the symbol they stand for
+    fn searched_names<'s>(&self, source_file: &SourceFile, symbols: &'s [Symbol]) -> Vec<(String, &'s Symbol)> {
+        if !matches!(source_file.language, Language::Swift | Language::ObjectiveC) {
+            return symbols
+                .iter()
+                .map(|symbol| (Self::name_in(source_file, symbol).to_string(), symbol))
+                .collect();
+        }
+        let frameworks = self.exported_names.frameworks(&source_file.content);
+        symbols
+            .iter()
+            .flat_map(|symbol| {
+                ExportedNames::aliases(symbol, &frameworks)
+                    .into_iter()
+                    .map(move |alias| (alias, symbol))
+            })
+            .collect()
     }
 
     fn get_comment_prefixes(source_file: &SourceFile) -> Vec<&'static str> {
@@ -19,7 +92,126 @@ impl SymbolUsageRepositoryImpl {
             crate::domain::Language::Swift | crate::domain::Language::ObjectiveC => {
                 vec!["//", "/*", "*", "import ", "#import"]
             }
+            crate::domain::Language::TypeScript | crate::domain::Language::JavaScript => {
+                vec!["//", "/*", "*", "import "]
+            }
+        }
+    }
+}
+
+impl SymbolUsageRepositoryImpl {
+    /// Kotlin and Java imports of shared symbols, matched by qualified name
+    ///
+    /// Name matching skips import lines, so these are the only usages of kind
+    /// [`UsageKind::ImportOnly`]. References through an import alias
+    /// (`import com.example.User as AppUser`) are credited to the shared symbol.
+    fn detect_imports(
+        &self,
+        source_file: &SourceFile,
+        symbols: &[Symbol],
+        comment_prefixes: &[&str],
+    ) -> Vec<SymbolUsage> {
+        let qualified: Vec<(String, &str)> = symbols
+            .iter()
+            .filter(|s| !s.package.is_empty())
+            .map(|s| (format!("{}.{}", s.package, s.name), s.name.as_str()))
+            .collect();
+
+        let mut usages = Vec::new();
+        for (index, line) in source_file.content.lines().enumerate() {
+            let Some(import) = line.trim().strip_prefix("import ") else {
+                continue;
+            };
+            let import = import.trim_start_matches("static ").trim_end_matches(';');
+            let (path, alias) = match import.split_once(" as ") {
+                Some((path, alias)) => (path.trim(), Some(alias.trim())),
+                None => (import.trim(), None),
+            };
+
+            let mut seen = std::collections::HashSet::new();
+            for (qualified, name) in &qualified {
+                let matches = path
+                    .strip_prefix(qualified.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'));
+                if matches && seen.insert(*name) {
+                    usages.push(SymbolUsage {
+                        symbol_name: name.to_string(),
+                        file_path: source_file.path.clone(),
+                        line_number: index + 1,
+                        context: line.trim().to_string(),
+                        kind: UsageKind::ImportOnly,
+                    });
+                    if let Some(alias) = alias.filter(|_| path == qualified) {
+                        usages.extend(self.alias_usages(source_file, alias, name, comment_prefixes));
+                    }
+                }
+            }
+        }
+        usages
+    }
+
+    /// Named imports of shared symbols from JS modules, matched by exported name
+    ///
+    /// References through a renamed import (`import { User as AppUser }`) are
+    /// credited to the shared symbol.
+    fn detect_js_imports(
+        &self,
+        source_file: &SourceFile,
+        symbols: &[Symbol],
+        comment_prefixes: &[&str],
+    ) -> Vec<SymbolUsage> {
+        let content = &source_file.content;
+        let mut usages = Vec::new();
+        for cap in self.js_import_regex.captures_iter(content) {
+            let start = cap.get(0).map_or(0, |m| m.start());
+            let line_number = content[..start].matches('\n').count() + 1;
+            let context = content.lines().nth(line_number - 1).unwrap_or_default().trim();
+
+            for specifier in cap[1].split(',') {
+                let specifier = specifier.trim().trim_start_matches("type ").trim();
+                let (exported, alias) = match specifier.split_once(" as ") {
+                    Some((exported, alias)) => (exported.trim(), Some(alias.trim())),
+                    None => (specifier, None),
+                };
+                let Some(symbol) = symbols.iter().find(|s| Self::name_in(source_file, s) == exported) else {
+                    continue;
+                };
+                usages.push(SymbolUsage {
+                    symbol_name: symbol.name.clone(),
+                    file_path: source_file.path.clone(),
+                    line_number,
+                    context: context.to_string(),
+                    kind: UsageKind::ImportOnly,
+                });
+                if let Some(alias) = alias {
+                    usages.extend(self.alias_usages(source_file, alias, &symbol.name, comment_prefixes));
+                }
+            }
         }
+        usages
+    }
+
+    fn alias_usages(
+        &self,
+        source_file: &SourceFile,
+        alias: &str,
+        symbol_name: &str,
+        comment_prefixes: &[&str],
+    ) -> Vec<SymbolUsage> {
+        let path = std::path::Path::new(&source_file.path);
+        let aliases = [alias.to_string()];
+        let found = self.patterns.patterns(&aliases).detect(&source_file.content, path, comment_prefixes);
+        found
+            .into_values()
+            .flat_map(|usage| usage.usage_lines)
+            .map(|location| SymbolUsage {
+                symbol_name: symbol_name.to_string(),
+                file_path: location.file,
+                line_number: location.line,
+                kind: UsageKind::classify(&location.context, alias),
+                context: location.context,
+            })
+            .collect()
     }
 }
 
@@ -35,29 +227,64 @@ impl SymbolUsageRepository for SymbolUsageRepositoryImpl {
         source_file: &SourceFile,
         symbols: &[Symbol],
     ) -> Result<Vec<SymbolUsage>> {
-        let symbol_names: Vec<String> = symbols.iter().map(|s| s.name.clone()).collect();
+        let searched = self.searched_names(source_file, symbols);
+        let symbol_names: Vec<String> = searched.iter().map(|(name, _)| name.clone()).collect();
         let comment_prefixes = Self::get_comment_prefixes(source_file);
 
         let path = std::path::Path::new(&source_file.path);
-        let usages_map = detect_usage_with_patterns(
-            &source_file.content,
-            path,
-            &symbol_names,
-            &comment_prefixes,
-        );
+        let mut usages_map: Vec<_> = self
+            .patterns
+            .patterns(&symbol_names)
+            .detect(&source_file.content, path, &comment_prefixes)
+            .into_iter()
+            .collect();
+        // By name, so a file's usages (and the warm start data recording them) keep one order
+        usages_map.sort_by(|(a, _), (b, _)| a.cmp(b));
 
         let mut usages = Vec::new();
-        for (symbol_name, symbol_usage) in usages_map {
+        for (found_name, symbol_usage) in usages_map {
+            let symbol_name = searched
+                .iter()
+                .find(|(name, _)| *name == found_name)
+                .map_or(found_name.clone(), |(_, s)| s.name.clone());
             for usage_location in symbol_usage.usage_lines {
                 usages.push(SymbolUsage {
                     symbol_name: symbol_name.clone(),
                     file_path: usage_location.file,
                     line_number: usage_location.line,
+                    kind: UsageKind::classify(&usage_location.context, &found_name),
                     context: usage_location.context,
                 });
             }
         }
 
+        usages.extend(self.viewmodel_bridges.detect(
+            &source_file.content,
+            path,
+            symbols,
+            &comment_prefixes,
+        ));
+        if matches!(source_file.language, Language::Kotlin | Language::Java) {
+            usages.extend(self.detect_imports(source_file, symbols, &comment_prefixes));
+        }
+        if Self::is_web(source_file) {
+            usages.extend(self.detect_js_imports(source_file, symbols, &comment_prefixes));
+        }
+        if source_file.language == Language::Swift {
+            usages.extend(self.flow_consumption.detect(
+                &source_file.content,
+                path,
+                symbols,
+                &comment_prefixes,
+            ));
+            usages.extend(self.swift_macros.detect(
+                &source_file.content,
+                path,
+                symbols,
+                &comment_prefixes,
+            ));
+        }
+
         Ok(usages)
     }
 }
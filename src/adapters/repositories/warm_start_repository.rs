@@ -0,0 +1,293 @@
+use anyhow::Result;
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::slice;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::adapters::file_system::FileSystem;
+use crate::domain::{
+    FrameworkHeader, ImplicitPublicDeclaration, OwnerRule, SourceFile, Symbol, SymbolRepository, SymbolParser, SymbolUsage, SymbolUsageRepository, WarmFile, WarmStartState,
+};
+
+/// Results of a previous run reused for unchanged files, and this run's results recorded for the next
+///
+/// One analysis shares it between a [`WarmStartSymbolRepository`] and a
+/// [`WarmStartUsageRepository`]. A file's results are reused only when its content
+/// hash matches, and app file usages only when the shared symbols searched for match too.
+pub struct WarmStart {
+    previous: WarmStartState,
+    recorded: Mutex<WarmStartState>,
+    /// Hash of the symbol list last searched for, keyed by the list's address and length
+    symbol_table: Mutex<Option<((usize, usize), String)>>,
+    reused_files: AtomicUsize,
+}
+
+impl WarmStart {
+    /// Reuses `previous` results extracted by the same tool version with the same `parser`
+    pub fn new(previous: WarmStartState, parser: SymbolParser) -> Self {
+        let tool_version = env!("CARGO_PKG_VERSION").to_string();
+        let previous = if previous.files.is_empty() {
+            previous
+        } else if previous.tool_version != tool_version {
+            warn!(
+                "Not resuming from results of version {} (this is {}); starting cold",
+                previous.tool_version, tool_version
+            );
+            WarmStartState::default()
+        } else if previous.parser != parser {
+            warn!(
+                "Not resuming from symbols extracted with the {} parser (this run uses {}); starting cold",
+                previous.parser.name(),
+                parser.name()
+            );
+            WarmStartState::default()
+        } else {
+            previous
+        };
+        Self {
+            previous,
+            recorded: Mutex::new(WarmStartState {
+                tool_version,
+                parser,
+                ..Default::default()
+            }),
+            symbol_table: Mutex::new(None),
+            reused_files: AtomicUsize::new(0),
+        }
+    }
+
+    /// This run's results, for the report
+    pub fn finish(self) -> WarmStartState {
+        let recorded = self.recorded.into_inner().unwrap();
+        info!(
+            "Reused results of {} of {} files from the previous run",
+            self.reused_files.into_inner(),
+            recorded.files.len()
+        );
+        recorded
+    }
+
+    /// The previous results for a file, when its content is unchanged
+    fn reusable(&self, path: &str, hash: &str) -> Option<&WarmFile> {
+        let file = self.previous.files.get(path).filter(|file| file.hash == hash)?;
+        self.reused_files.fetch_add(1, Ordering::Relaxed);
+        Some(file)
+    }
+
+    fn record(&self, path: &str, hash: String, update: impl FnOnce(&mut WarmFile)) {
+        let mut recorded = self.recorded.lock().unwrap();
+        let file = recorded.files.entry(path.to_string()).or_default();
+        file.hash = hash;
+        update(file);
+    }
+
+    /// Hash of a symbol list, computed once per list since every app file is searched for the same one
+    fn symbol_table(&self, symbols: &[Symbol]) -> Result<String> {
+        let key = (symbols.as_ptr() as usize, symbols.len());
+        let mut cached = self.symbol_table.lock().unwrap();
+        if let Some((cached_key, hash)) = cached.as_ref() {
+            if *cached_key == key {
+                return Ok(hash.clone());
+            }
+        }
+        let hash = hex::encode(Sha256::digest(serde_json::to_vec(symbols)?));
+        self.recorded.lock().unwrap().symbol_table = hash.clone();
+        *cached = Some((key, hash.clone()));
+        Ok(hash)
+    }
+}
+
+fn content_hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// SymbolRepository decorator that reuses the symbols of shared files unchanged since the previous run
+pub struct WarmStartSymbolRepository<'a> {
+    inner: &'a dyn SymbolRepository,
+    fs: Arc<dyn FileSystem>,
+    warm_start: &'a WarmStart,
+}
+
+impl<'a> WarmStartSymbolRepository<'a> {
+    pub fn new(inner: &'a dyn SymbolRepository, fs: Arc<dyn FileSystem>, warm_start: &'a WarmStart) -> Self {
+        Self { inner, fs, warm_start }
+    }
+}
+
+impl SymbolRepository for WarmStartSymbolRepository<'_> {
+    fn extract_kmp_symbols(&self, kmp_file_paths: &[String]) -> Result<Vec<Symbol>> {
+        let mut symbols = Vec::new();
+        for file_path in kmp_file_paths {
+            let hash = content_hash(&self.fs.read_to_string(Path::new(file_path))?);
+            let file_symbols = match self.warm_start.reusable(file_path, &hash) {
+                Some(previous) => previous.symbols.clone(),
+                None => self.inner.extract_kmp_symbols(slice::from_ref(file_path))?,
+            };
+            symbols.extend(file_symbols.iter().cloned());
+            self.warm_start.record(file_path, hash, |file| file.symbols = file_symbols);
+        }
+        Ok(symbols)
+    }
+
+    fn extract_resource_symbols(&self, project_path: &str) -> Result<Vec<Symbol>> {
+        self.inner.extract_resource_symbols(project_path)
+    }
+
+    fn extract_generated_symbols(&self, project_path: &str) -> Result<Vec<Symbol>> {
+        self.inner.extract_generated_symbols(project_path)
+    }
+
+    fn find_framework_headers(&self, project_path: &str, kmp_file_paths: &[String]) -> Result<Vec<FrameworkHeader>> {
+        self.inner.find_framework_headers(project_path, kmp_file_paths)
+    }
+
+    fn find_owner_rules(&self, kmp_file_paths: &[String]) -> Result<Vec<OwnerRule>> {
+        self.inner.find_owner_rules(kmp_file_paths)
+    }
+
+    fn find_implicit_public_declarations(&self, kmp_file_paths: &[String]) -> Result<Vec<ImplicitPublicDeclaration>> {
+        self.inner.find_implicit_public_declarations(kmp_file_paths)
+    }
+}
+
+/// SymbolUsageRepository decorator that reuses the usages of app files unchanged since the previous run
+pub struct WarmStartUsageRepository<'a> {
+    inner: &'a dyn SymbolUsageRepository,
+    warm_start: &'a WarmStart,
+}
+
+impl<'a> WarmStartUsageRepository<'a> {
+    pub fn new(inner: &'a dyn SymbolUsageRepository, warm_start: &'a WarmStart) -> Self {
+        Self { inner, warm_start }
+    }
+}
+
+impl SymbolUsageRepository for WarmStartUsageRepository<'_> {
+    fn detect_symbol_usage(&self, source_file: &SourceFile, symbols: &[Symbol]) -> Result<Vec<SymbolUsage>> {
+        let hash = content_hash(&source_file.content);
+        let same_symbols = self.warm_start.symbol_table(symbols)? == self.warm_start.previous.symbol_table;
+        let usages = match self.warm_start.reusable(&source_file.path, &hash).filter(|_| same_symbols) {
+            Some(previous) => previous.usages.clone(),
+            None => self.inner.detect_symbol_usage(source_file, symbols)?,
+        };
+        self.warm_start.record(&source_file.path, hash, |file| file.usages = usages.clone());
+        Ok(usages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::file_system::MemoryFileSystem;
+    use crate::domain::{Language, Platform, SymbolType, UsageKind};
+    use std::sync::atomic::AtomicUsize;
+
+    /// Counts the files it is asked about, finding one symbol per shared file and one usage per app file
+    #[derive(Default)]
+    struct CountingRepository {
+        calls: AtomicUsize,
+    }
+
+    impl SymbolRepository for CountingRepository {
+        fn extract_kmp_symbols(&self, kmp_file_paths: &[String]) -> Result<Vec<Symbol>> {
+            self.calls.fetch_add(kmp_file_paths.len(), Ordering::Relaxed);
+            Ok(kmp_file_paths
+                .iter()
+                .map(|path| Symbol {
+                    package: "com.example".to_string(),
+                    ..Symbol::new(Path::new(path).file_stem().unwrap().to_string_lossy().to_string(), SymbolType::Class, "shared", path.clone())
+                })
+                .collect())
+        }
+    }
+
+    impl SymbolUsageRepository for CountingRepository {
+        fn detect_symbol_usage(&self, source_file: &SourceFile, symbols: &[Symbol]) -> Result<Vec<SymbolUsage>> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            Ok(symbols
+                .iter()
+                .filter(|symbol| source_file.content.contains(&symbol.name))
+                .map(|symbol| SymbolUsage {
+                    symbol_name: symbol.name.clone(),
+                    file_path: source_file.path.clone(),
+                    line_number: 1,
+                    context: source_file.content.clone(),
+                    kind: UsageKind::Reference,
+                })
+                .collect())
+        }
+    }
+
+    /// Runs extraction and detection once, returning the symbols, the usages, and the recorded state
+    fn run(
+        fs: &MemoryFileSystem,
+        inner: &CountingRepository,
+        previous: WarmStartState,
+    ) -> Result<(Vec<Symbol>, Vec<SymbolUsage>, WarmStartState)> {
+        let fs: Arc<dyn FileSystem> = Arc::new(fs.clone());
+        let warm_start = WarmStart::new(previous, SymbolParser::Regex);
+        let symbols = WarmStartSymbolRepository::new(inner, fs.clone(), &warm_start)
+            .extract_kmp_symbols(&["shared/User.kt".to_string(), "shared/Order.kt".to_string()])?;
+        let app_file = SourceFile {
+            path: "app/Main.kt".to_string(),
+            platform: Platform::Android,
+            language: Language::Kotlin,
+            content: fs.read_to_string(Path::new("app/Main.kt"))?,
+        };
+        let usages = WarmStartUsageRepository::new(inner, &warm_start).detect_symbol_usage(&app_file, &symbols)?;
+        Ok((symbols, usages, warm_start.finish()))
+    }
+
+    #[test]
+    fn test_reuses_results_of_unchanged_files() -> Result<()> {
+        let mut fs = MemoryFileSystem::new();
+        fs.insert("shared/User.kt", "class User");
+        fs.insert("shared/Order.kt", "class Order");
+        fs.insert("app/Main.kt", "val user = User()");
+
+        let cold = CountingRepository::default();
+        let (symbols, usages, state) = run(&fs, &cold, WarmStartState::default())?;
+        assert_eq!(cold.calls.load(Ordering::Relaxed), 3);
+        assert_eq!(usages.len(), 1);
+
+        // A round trip through the JSON report, as `--resume` reads it
+        let state: WarmStartState = serde_json::from_str(&serde_json::to_string(&state)?)?;
+        let warm = CountingRepository::default();
+        let (warm_symbols, warm_usages, _) = run(&fs, &warm, state.clone())?;
+        assert_eq!(warm.calls.load(Ordering::Relaxed), 0);
+        assert_eq!(warm_symbols, symbols);
+        assert_eq!(warm_usages.len(), 1);
+
+        // A changed shared file is extracted again; the app file still searches for the same symbols
+        fs.insert("shared/Order.kt", "class Order\nclass Invoice");
+        let changed = CountingRepository::default();
+        run(&fs, &changed, state.clone())?;
+        assert_eq!(changed.calls.load(Ordering::Relaxed), 1);
+
+        fs.insert("app/Main.kt", "val order = Order()");
+        let changed = CountingRepository::default();
+        let (_, usages, _) = run(&fs, &changed, state.clone())?;
+        assert_eq!(changed.calls.load(Ordering::Relaxed), 2);
+        assert_eq!(usages[0].symbol_name, "Order");
+
+        let other_version = WarmStartState {
+            tool_version: "0.0.0".to_string(),
+            ..state.clone()
+        };
+        let cold_again = CountingRepository::default();
+        run(&fs, &cold_again, other_version)?;
+        assert_eq!(cold_again.calls.load(Ordering::Relaxed), 3);
+
+        let other_parser = WarmStartState {
+            parser: SymbolParser::TreeSitter,
+            ..state
+        };
+        let cold_again = CountingRepository::default();
+        run(&fs, &cold_again, other_parser)?;
+        assert_eq!(cold_again.calls.load(Ordering::Relaxed), 3);
+
+        Ok(())
+    }
+}
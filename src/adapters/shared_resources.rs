@@ -0,0 +1,253 @@
+//! Shared resources generated by moko-resources and libres
+//! Turns resource files of KMP modules into the accessors app code references
+//! (e.g. `MR.strings.title`, `Res.image.logo`)
+
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::{Component, Path};
+use std::sync::Arc;
+
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
+use crate::domain::{Symbol, SymbolType};
+
+/// Resource generator a resource file belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Generator {
+    Moko,
+    Libres,
+}
+
+/// Finds resource accessors generated for KMP modules
+pub struct SharedResourceScanner {
+    fs: Arc<dyn FileSystem>,
+    string_regex: Regex,
+    plural_regex: Regex,
+    color_regex: Regex,
+    moko_class_regex: Regex,
+    moko_package_regex: Regex,
+    libres_class_regex: Regex,
+    libres_package_regex: Regex,
+}
+
+impl SharedResourceScanner {
+    pub fn new() -> Self {
+        Self {
+            fs: Arc::new(OsFileSystem),
+            string_regex: Regex::new(r#"<string\s+name="([^"]+)""#).unwrap(),
+            plural_regex: Regex::new(r#"<plurals?\s+name="([^"]+)""#).unwrap(),
+            color_regex: Regex::new(r#"<color\s+name="([^"]+)""#).unwrap(),
+            // Match: resourcesClassName.set("SharedRes"), multiplatformResourcesClassName = "SharedRes"
+            moko_class_regex: Regex::new(
+                r#"(?:resourcesClassName\.set\(|multiplatformResourcesClassName\s*=)\s*"(\w+)""#,
+            )
+            .unwrap(),
+            moko_package_regex: Regex::new(
+                r#"(?:resourcesPackage\.set\(|multiplatformResourcesPackage\s*=)\s*"([\w.]+)""#,
+            )
+            .unwrap(),
+            libres_class_regex: Regex::new(r#"generatedClassName\s*=\s*"(\w+)""#).unwrap(),
+            libres_package_regex: Regex::new(r#"generatedClassPackage\s*=\s*"([\w.]+)""#).unwrap(),
+        }
+    }
+
+    /// Reads resource and build files through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// Finds the resource symbols of every KMP module under `root`, one per accessor
+    pub fn scan(&self, root: &Path) -> Result<Vec<Symbol>> {
+        let mut symbols = Vec::new();
+        let mut seen = HashSet::new();
+
+        for entry in self.fs.walk(root, 9) {
+            if entry.is_dir {
+                continue;
+            }
+            let path = entry.path.as_path();
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            let Some((generator, module_dir, category)) = Self::classify(root, relative) else {
+                continue;
+            };
+
+            let names = self.resource_names(path, generator, category)?;
+            if names.is_empty() {
+                continue;
+            }
+
+            let build = ["build.gradle.kts", "build.gradle"]
+                .iter()
+                .map(|name| module_dir.join(name))
+                .find(|file| self.fs.is_file(file));
+            let build = match build {
+                Some(file) => self.fs.read_to_string(&file)?,
+                None => String::new(),
+            };
+            let (class_regex, package_regex, default_class) = match generator {
+                Generator::Moko => (&self.moko_class_regex, &self.moko_package_regex, "MR"),
+                Generator::Libres => (&self.libres_class_regex, &self.libres_package_regex, "Res"),
+            };
+            let class_name = class_regex
+                .captures(&build)
+                .map_or(default_class.to_string(), |c| c[1].to_string());
+            let package = package_regex
+                .captures(&build)
+                .map(|c| c[1].to_string())
+                .unwrap_or_default();
+            let module = module_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            for (accessor_category, name) in names {
+                let name = format!("{}.{}.{}", class_name, accessor_category, name);
+                if !seen.insert((module.clone(), name.clone())) {
+                    continue;
+                }
+                symbols.push(Symbol {
+                    package: package.clone(),
+                    ..Symbol::new(name, SymbolType::Resource, module.clone(), path.to_string_lossy().to_string())
+                });
+            }
+        }
+
+        symbols.sort_by(|a, b| (&a.module, &a.name).cmp(&(&b.module, &b.name)));
+        Ok(symbols)
+    }
+
+    /// Generator, module directory, and resource directory of a file, if it is a shared resource
+    ///
+    /// moko-resources keeps resources in `src/<sourceSet>/moko-resources/` (older
+    /// versions: `src/<sourceSet>/resources/MR/`); libres in `src/<sourceSet>/libres/`.
+    fn classify<'p>(root: &Path, relative: &'p Path) -> Option<(Generator, std::path::PathBuf, &'p str)> {
+        let parts: Vec<&str> = relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect();
+        if parts.contains(&"build") {
+            return None;
+        }
+
+        let src = parts.iter().position(|p| *p == "src")?;
+        let module_dir = root.join(parts[..src].iter().collect::<std::path::PathBuf>());
+        let rest = parts.get(src + 2..)?;
+
+        let (generator, category) = match rest {
+            ["moko-resources", dir, .., _] => (Generator::Moko, *dir),
+            ["resources", "MR", dir, .., _] => (Generator::Moko, *dir),
+            ["libres", dir, .., _] => (Generator::Libres, *dir),
+            _ => return None,
+        };
+        Some((generator, module_dir, category))
+    }
+
+    /// Accessor category and name of every resource a file declares
+    fn resource_names(
+        &self,
+        path: &Path,
+        generator: Generator,
+        category: &str,
+    ) -> Result<Vec<(&'static str, String)>> {
+        let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // Scale variants (`logo@2x.png`) share one accessor
+        let stem = stem.split('@').next().unwrap_or_default().to_string();
+        let is_xml = file_name.ends_with(".xml");
+
+        let names = match (generator, category) {
+            (Generator::Moko, "images") => vec![("images", stem)],
+            (Generator::Moko, "files") => vec![("files", stem)],
+            (Generator::Moko, "assets") => vec![("assets", stem)],
+            (Generator::Moko, "fonts") => vec![("fonts", stem)],
+            (Generator::Libres, "images") => vec![("image", stem)],
+            (Generator::Moko, _) if is_xml => {
+                let content = self.fs.read_to_string(path)?;
+                let mut names = Vec::new();
+                for (regex, accessor) in [
+                    (&self.string_regex, "strings"),
+                    (&self.plural_regex, "plurals"),
+                    (&self.color_regex, "colors"),
+                ] {
+                    names.extend(regex.captures_iter(&content).map(|c| (accessor, c[1].to_string())));
+                }
+                names
+            }
+            (Generator::Libres, "strings") if is_xml => {
+                let content = self.fs.read_to_string(path)?;
+                self.string_regex
+                    .captures_iter(&content)
+                    .chain(self.plural_regex.captures_iter(&content))
+                    .map(|c| ("string", c[1].to_string()))
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+
+        Ok(names.into_iter().filter(|(_, name)| !name.is_empty()).collect())
+    }
+}
+
+impl Default for SharedResourceScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::file_system::MemoryFileSystem;
+
+    #[test]
+    fn test_moko_and_libres_resources() -> Result<()> {
+        let mut fs = MemoryFileSystem::new();
+        fs.insert(
+            "/repo/shared/build.gradle.kts",
+            "multiplatformResources {\n    resourcesPackage.set(\"com.example.res\")\n    resourcesClassName.set(\"SharedRes\")\n}\n",
+        );
+        fs.insert(
+            "/repo/shared/src/commonMain/moko-resources/base/strings.xml",
+            "<resources>\n    <string name=\"title\">Title</string>\n    <plural name=\"items\"/>\n</resources>\n",
+        );
+        fs.insert(
+            "/repo/shared/src/commonMain/moko-resources/ru/strings.xml",
+            "<resources>\n    <string name=\"title\">Заголовок</string>\n</resources>\n",
+        );
+        fs.insert("/repo/shared/src/commonMain/moko-resources/images/logo@2x.png", "");
+        fs.insert("/repo/shared/build/generated/moko-resources/images/stale.png", "");
+        fs.insert(
+            "/repo/design/src/commonMain/libres/strings/strings_en.xml",
+            "<resources>\n    <string name=\"greeting\">Hi</string>\n</resources>\n",
+        );
+        fs.insert("/repo/design/src/commonMain/libres/images/avatar.svg", "<svg/>");
+
+        let symbols = SharedResourceScanner::new()
+            .with_file_system(Arc::new(fs))
+            .scan(Path::new("/repo"))?;
+
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Res.image.avatar",
+                "Res.string.greeting",
+                "SharedRes.images.logo",
+                "SharedRes.plurals.items",
+                "SharedRes.strings.title",
+            ]
+        );
+        assert_eq!(symbols[0].module, "design");
+        assert_eq!(symbols[2].package, "com.example.res");
+        assert!(symbols.iter().all(|s| s.symbol_type == SymbolType::Resource));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+//! Shared types referenced from Swift macros and result-builder closures
+//! Swift 5.9 macros and SwiftUI/SwiftData builders name shared types where name
+//! matching does not look for them: as generic arguments (`#Predicate<User>`,
+//! `FetchDescriptor<User>` inside `@Query(...)`), inside macro and attribute
+//! arguments (`@Query(sort: \User.createdAt)`, `#Preview { UserRow(user: .sample) }`),
+//! and as closure parameter types (`{ (user: User) -> Bool in ... }`).
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::domain::{Symbol, SymbolType, SymbolUsage, UsageKind};
+
+/// Finds shared types in Swift macro and builder positions
+pub struct SwiftMacroDetector {
+    /// `#Predicate<User>`, `#Expression<User, Bool>`
+    macro_generics_regex: Regex,
+    /// `#Preview(`, `#expect(`, `@Query(`, `@Relationship(`
+    arguments_start_regex: Regex,
+    /// `#Preview { UserRow(user: .sample) }`
+    macro_closure_regex: Regex,
+    /// `{ (user: User, index: Int) -> Bool in`
+    closure_parameters_regex: Regex,
+}
+
+impl SwiftMacroDetector {
+    pub fn new() -> Self {
+        Self {
+            macro_generics_regex: Regex::new(r"#\w+\s*<([^{}]*?)>").unwrap(),
+            arguments_start_regex: Regex::new(r"[#@]\w+\s*\(").unwrap(),
+            macro_closure_regex: Regex::new(r"#\w+\b[^{}]*\{([^}]*)").unwrap(),
+            closure_parameters_regex: Regex::new(
+                r"\{\s*\(([^()]*)\)\s*(?:async\s+)?(?:throws\s+)?(?:->\s*[^{}]*?\s+)?in\b",
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Usages of shared types named in macro arguments and closure signatures of `content`
+    ///
+    /// `comment_prefixes` mark lines that are skipped, as in name matching.
+    pub fn detect(
+        &self,
+        content: &str,
+        file_path: &Path,
+        symbols: &[Symbol],
+        comment_prefixes: &[&str],
+    ) -> Vec<SymbolUsage> {
+        let types: Vec<&str> = symbols
+            .iter()
+            .filter(|s| !matches!(s.symbol_type, SymbolType::Function | SymbolType::Property | SymbolType::Resource))
+            .map(|s| s.name.as_str())
+            .filter(|name| name.starts_with(|c: char| c.is_uppercase()))
+            .collect();
+        if types.is_empty() {
+            return Vec::new();
+        }
+
+        let mut usages = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let trimmed = line.trim();
+            if comment_prefixes.iter().any(|prefix| trimmed.starts_with(prefix)) {
+                continue;
+            }
+
+            // Type positions first, so a type both annotated and passed is reported as annotated
+            let type_spans = self
+                .macro_generics_regex
+                .captures_iter(line)
+                .chain(self.closure_parameters_regex.captures_iter(line))
+                .map(|cap| (cap.get(1).unwrap().as_str(), UsageKind::TypeAnnotation));
+            let argument_spans = self
+                .arguments_start_regex
+                .find_iter(line)
+                .map(|start| balanced_arguments(&line[start.end()..]))
+                .chain(self.macro_closure_regex.captures_iter(line).map(|cap| cap.get(1).unwrap().as_str()))
+                .map(|span| (span, UsageKind::Reference));
+
+            let mut found = HashSet::new();
+            for (span, kind) in type_spans.chain(argument_spans) {
+                for name in types.iter().filter(|name| contains_word(span, name)) {
+                    if found.insert(*name) {
+                        usages.push(SymbolUsage {
+                            symbol_name: name.to_string(),
+                            file_path: file_path.to_string_lossy().to_string(),
+                            line_number: index + 1,
+                            context: trimmed.to_string(),
+                            kind,
+                        });
+                    }
+                }
+            }
+        }
+
+        usages
+    }
+}
+
+impl Default for SwiftMacroDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Arguments up to the parenthesis closing an opened argument list, or the rest of the line
+fn balanced_arguments(rest: &str) -> &str {
+    let mut depth = 1usize;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &rest[..i];
+                }
+            }
+            _ => {}
+        }
+    }
+    rest
+}
+
+/// Whether `text` contains `word` not as part of a longer identifier
+fn contains_word(text: &str, word: &str) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(word).any(|(at, _)| {
+        !text[..at].chars().next_back().is_some_and(is_word)
+            && !text[at + word.len()..].chars().next().is_some_and(is_word)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, symbol_type: SymbolType) -> Symbol {
+        Symbol {
+            package: "com.example".to_string(),
+            ..Symbol::new(name, symbol_type, "shared", "shared/src/commonMain/kotlin/User.kt")
+        }
+    }
+
+    #[test]
+    fn test_detects_macro_and_builder_usages() {
+        let symbols = vec![
+            symbol("User", SymbolType::Class),
+            symbol("Team", SymbolType::Class),
+            symbol("UserFilter", SymbolType::Interface),
+            symbol("Sample", SymbolType::Object),
+            symbol("format", SymbolType::Function),
+        ];
+        let content = r#"
+// #Predicate<User> { $0.isActive }
+let active = #Predicate<User> { $0.isActive }
+@Query(sort: \Team.name, order: .forward) var teams: [Team]
+@Query(FetchDescriptor<User>()) var users
+let sorted = users.sorted { (lhs: User, rhs: User) -> Bool in lhs.name < rhs.name }
+#Preview("Row") { UserRow(user: Sample.shared.user) }
+#expect(filters.contains { $0 is UserFilter })
+let superUsers = #Predicate<SuperUser> { $0.isAdmin }
+"#;
+        let usages = SwiftMacroDetector::new().detect(
+            content,
+            Path::new("iosApp/UsersView.swift"),
+            &symbols,
+            &["//"],
+        );
+
+        let found: Vec<(&str, usize, UsageKind)> = usages
+            .iter()
+            .map(|u| (u.symbol_name.as_str(), u.line_number, u.kind))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("User", 3, UsageKind::TypeAnnotation),
+                ("Team", 4, UsageKind::Reference),
+                ("User", 5, UsageKind::Reference),
+                ("User", 6, UsageKind::TypeAnnotation),
+                ("Sample", 7, UsageKind::Reference),
+                ("UserFilter", 8, UsageKind::Reference),
+            ]
+        );
+    }
+}
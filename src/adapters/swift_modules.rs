@@ -0,0 +1,285 @@
+//! Swift module discovery
+//! Finds local Swift Package Manager targets and Xcode targets of iOS apps
+
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
+use crate::domain::{SwiftModule, SwiftModuleKind};
+
+/// Directories holding fetched or built packages rather than the app's own modules
+const IGNORED_DIRS: &[&str] = &[".build", "build", "Pods", "Carthage", "DerivedData", "checkouts"];
+
+/// Finds Swift modules and their dependencies on each other
+pub struct SwiftModuleDetector {
+    fs: Arc<dyn FileSystem>,
+    target_regex: Regex,
+    name_regex: Regex,
+    path_regex: Regex,
+    quoted_regex: Regex,
+    native_target_regex: Regex,
+    product_type_regex: Regex,
+    package_products_regex: Regex,
+    comment_regex: Regex,
+}
+
+/// A module before its dependencies are resolved against the other modules
+struct Candidate {
+    name: String,
+    dir: String,
+    kind: SwiftModuleKind,
+    dependencies: Vec<String>,
+}
+
+impl SwiftModuleDetector {
+    pub fn new() -> Self {
+        Self {
+            fs: Arc::new(OsFileSystem),
+            // Match: .target(, .executableTarget(, .testTarget(
+            target_regex: Regex::new(r"\.(target|executableTarget|testTarget|binaryTarget|plugin)\s*\(").unwrap(),
+            name_regex: Regex::new(r#"\bname:\s*"([^"]+)""#).unwrap(),
+            path_regex: Regex::new(r#"\bpath:\s*"([^"]+)""#).unwrap(),
+            quoted_regex: Regex::new(r#""([^"]+)""#).unwrap(),
+            native_target_regex: Regex::new(r"isa = PBXNativeTarget;").unwrap(),
+            product_type_regex: Regex::new(r#"productType = "([^"]+)";"#).unwrap(),
+            package_products_regex: Regex::new(r"packageProductDependencies = \(([^)]*)\)").unwrap(),
+            comment_regex: Regex::new(r"/\* ([^*]+?) \*/").unwrap(),
+        }
+    }
+
+    /// Reads manifests and project files through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// Finds the Swift modules under `root`: local SPM targets and Xcode app targets
+    pub fn detect(&self, root: &Path) -> Result<Vec<SwiftModule>> {
+        let mut candidates = Vec::new();
+
+        for entry in self.fs.walk(root, 6) {
+            let path = entry.path.as_path();
+            if entry.is_dir || Self::is_ignored(root, path) {
+                continue;
+            }
+            let Some(parent) = path.parent() else {
+                continue;
+            };
+
+            if path.file_name() == Some("Package.swift".as_ref()) {
+                let content = self.fs.read_to_string(path)?;
+                candidates.extend(self.package_targets(&content, parent));
+            } else if path.file_name() == Some("project.pbxproj".as_ref())
+                && parent.extension() == Some("xcodeproj".as_ref())
+            {
+                let content = self.fs.read_to_string(path)?;
+                let project_dir = parent.parent().unwrap_or(root);
+                candidates.extend(self.xcode_targets(&content, project_dir));
+            }
+        }
+
+        let names: HashSet<String> = candidates.iter().map(|c| c.name.clone()).collect();
+        let mut modules: Vec<SwiftModule> = candidates
+            .into_iter()
+            .map(|candidate| {
+                let mut dependencies: Vec<String> = candidate
+                    .dependencies
+                    .into_iter()
+                    .filter(|dep| *dep != candidate.name && names.contains(dep))
+                    .collect();
+                dependencies.sort();
+                dependencies.dedup();
+                SwiftModule {
+                    name: candidate.name,
+                    dir: candidate.dir,
+                    kind: candidate.kind,
+                    dependencies,
+                }
+            })
+            .collect();
+
+        // The same target may be seen through several manifests; keep the first
+        modules.sort_by(|a, b| a.name.cmp(&b.name));
+        modules.dedup_by(|a, b| a.name == b.name);
+        Ok(modules)
+    }
+
+    /// Source targets declared by a `Package.swift`, with the names they depend on
+    fn package_targets(&self, content: &str, package_dir: &Path) -> Vec<Candidate> {
+        let starts: Vec<_> = self.target_regex.captures_iter(content).collect();
+        let mut targets = Vec::new();
+
+        for (i, cap) in starts.iter().enumerate() {
+            if !matches!(&cap[1], "target" | "executableTarget") {
+                continue;
+            }
+            let start = cap.get(0).map_or(0, |m| m.end());
+            let end = starts
+                .get(i + 1)
+                .and_then(|next| next.get(0))
+                .map_or(content.len(), |m| m.start());
+            let declaration = &content[start..end];
+
+            let Some(name) = self.name_regex.captures(declaration).map(|c| c[1].to_string()) else {
+                continue;
+            };
+            let dir = match self.path_regex.captures(declaration) {
+                Some(path) => package_dir.join(&path[1]),
+                None => package_dir.join("Sources").join(&name),
+            };
+
+            // Both `"Name"` and `.target(name: "Name")` / `.product(name: "Name", ...)` forms
+            let dependencies = declaration
+                .find("dependencies:")
+                .map(|at| {
+                    let list = &declaration[at..];
+                    let list = &list[..list.find(']').unwrap_or(list.len())];
+                    self.quoted_regex
+                        .captures_iter(list)
+                        .map(|c| c[1].to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            targets.push(Candidate {
+                name,
+                dir: dir.to_string_lossy().to_string(),
+                kind: SwiftModuleKind::Package,
+                dependencies,
+            });
+        }
+
+        targets
+    }
+
+    /// Non-test native targets of an Xcode project, with their Swift package products
+    fn xcode_targets(&self, content: &str, project_dir: &Path) -> Vec<Candidate> {
+        let mut targets = Vec::new();
+
+        for found in self.native_target_regex.find_iter(content) {
+            let block = &content[found.end()..];
+            let block = &block[..block.find("};").unwrap_or(block.len())];
+
+            let Some(name) = block
+                .lines()
+                .map(str::trim)
+                .find_map(|line| line.strip_prefix("name = "))
+                .map(|name| name.trim_end_matches(';').trim_matches('"').to_string())
+            else {
+                continue;
+            };
+            let is_test = self
+                .product_type_regex
+                .captures(block)
+                .is_some_and(|c| c[1].contains("test"));
+            if is_test {
+                continue;
+            }
+
+            // Targets conventionally keep their sources in a folder named after them
+            let target_dir = project_dir.join(&name);
+            let dir = if self.fs.is_dir(&target_dir) {
+                target_dir
+            } else {
+                project_dir.to_path_buf()
+            };
+
+            let dependencies = self
+                .package_products_regex
+                .captures(block)
+                .map(|list| {
+                    self.comment_regex
+                        .captures_iter(&list[1])
+                        .map(|c| c[1].to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            targets.push(Candidate {
+                name,
+                dir: dir.to_string_lossy().to_string(),
+                kind: SwiftModuleKind::XcodeTarget,
+                dependencies,
+            });
+        }
+
+        targets
+    }
+
+    fn is_ignored(root: &Path, path: &Path) -> bool {
+        path.strip_prefix(root)
+            .unwrap_or(path)
+            .components()
+            .any(|c| IGNORED_DIRS.iter().any(|dir| c.as_os_str() == *dir))
+    }
+}
+
+impl Default for SwiftModuleDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::file_system::MemoryFileSystem;
+
+    #[test]
+    fn test_packages_and_xcode_targets() -> Result<()> {
+        let mut fs = MemoryFileSystem::new();
+        fs.insert(
+            "/repo/iosApp/Packages/Features/Package.swift",
+            r#"
+let package = Package(
+    name: "Features",
+    targets: [
+        .target(name: "FeatureAuth", dependencies: ["DesignSystem", .product(name: "Shared", package: "shared")]),
+        .target(name: "DesignSystem", path: "Sources/UI"),
+        .testTarget(name: "FeatureAuthTests", dependencies: ["FeatureAuth"]),
+    ]
+)
+"#,
+        );
+        fs.insert("/repo/iosApp/iosApp/ContentView.swift", "struct ContentView {}");
+        fs.insert(
+            "/repo/iosApp/iosApp.xcodeproj/project.pbxproj",
+            r#"
+/* Begin PBXNativeTarget section */
+		7555FF7A242A565900829871 /* iosApp */ = {
+			isa = PBXNativeTarget;
+			name = iosApp;
+			packageProductDependencies = (
+				7A1B2C3D4E5F000000000001 /* FeatureAuth */,
+			);
+			productType = "com.apple.product-type.application";
+		};
+		7555FF8A242A565900829871 /* iosAppTests */ = {
+			isa = PBXNativeTarget;
+			name = iosAppTests;
+			productType = "com.apple.product-type.bundle.unit-test";
+		};
+/* End PBXNativeTarget section */
+"#,
+        );
+
+        let modules = SwiftModuleDetector::new()
+            .with_file_system(Arc::new(fs))
+            .detect(Path::new("/repo"))?;
+
+        let names: Vec<&str> = modules.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["DesignSystem", "FeatureAuth", "iosApp"]);
+
+        assert_eq!(modules[0].dir, "/repo/iosApp/Packages/Features/Sources/UI");
+        assert_eq!(modules[1].kind, SwiftModuleKind::Package);
+        assert_eq!(modules[1].dependencies, vec!["DesignSystem"]);
+        assert_eq!(modules[2].kind, SwiftModuleKind::XcodeTarget);
+        assert_eq!(modules[2].dir, "/repo/iosApp/iosApp");
+        assert_eq!(modules[2].dependencies, vec!["FeatureAuth"]);
+
+        Ok(())
+    }
+}
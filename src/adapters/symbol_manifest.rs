@@ -0,0 +1,254 @@
+//! Portable symbol manifests
+//! Lets an app-only repository analyze impact against shared code published elsewhere
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+use crate::domain::{Platform, Symbol, SymbolType};
+
+/// Manifest schema version written by this tool
+pub const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// How long connecting to the server of a remote manifest may take
+#[cfg(not(target_arch = "wasm32"))]
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the server may then keep us waiting for its response headers, and again for the body
+#[cfg(not(target_arch = "wasm32"))]
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A published list of shared symbols
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolManifest {
+    pub schema_version: u32,
+    /// Name of the repository or project the symbols come from
+    pub source: String,
+    pub symbols: Vec<ManifestSymbol>,
+}
+
+/// A shared symbol together with the names platform code sees it under
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSymbol {
+    #[serde(flatten)]
+    pub symbol: Symbol,
+    /// Platform name (e.g. `iOS`) -> name exported to that platform
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub exported_names: BTreeMap<String, String>,
+}
+
+impl ManifestSymbol {
+    pub fn new(symbol: Symbol) -> Self {
+        let exported_names = [Platform::Android, Platform::IOS, Platform::Web]
+            .iter()
+            .filter_map(|platform| {
+                Self::exported_name(&symbol, platform).map(|name| (platform.name().to_string(), name))
+            })
+            .collect();
+        Self {
+            symbol,
+            exported_names,
+        }
+    }
+
+    /// Name platform code uses to reference the symbol, if it is exported at all
+    ///
+    /// Kotlin/Native exposes top-level functions and properties to Swift through a
+    /// `<FileName>Kt` facade class, under their `@ObjCName` Swift name if given, and
+    /// does not export typealiases. Kotlin/JS exports only `@JsExport` declarations to
+    /// JavaScript, under their `@JsName` if given.
+    fn exported_name(symbol: &Symbol, platform: &Platform) -> Option<String> {
+        let swift_name = || {
+            symbol
+                .objc_name
+                .as_ref()
+                .and_then(|names| names.swift())
+                .unwrap_or(&symbol.name)
+                .to_string()
+        };
+        match (platform, &symbol.symbol_type) {
+            (Platform::IOS, SymbolType::TypeAlias) => None,
+            (Platform::IOS, SymbolType::Function | SymbolType::Property) => {
+                let file_stem = Path::new(&symbol.file_path).file_stem()?.to_string_lossy();
+                Some(format!("{}Kt.{}", file_stem, swift_name()))
+            }
+            (Platform::IOS, _) => Some(swift_name()),
+            (Platform::Web, _) if !symbol.has_annotation("JsExport") => None,
+            (Platform::Web, _) => Some(symbol.js_name.clone().unwrap_or_else(|| symbol.name.clone())),
+            _ => Some(symbol.name.clone()),
+        }
+    }
+}
+
+impl SymbolManifest {
+    /// Builds a manifest of the current schema version from extracted symbols
+    pub fn from_symbols(source: &str, symbols: Vec<Symbol>) -> Self {
+        Self {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            source: source.to_string(),
+            symbols: symbols.into_iter().map(ManifestSymbol::new).collect(),
+        }
+    }
+
+    /// Loads a manifest from a local path or an `http(s)://` URL
+    ///
+    /// Relative paths are resolved against `base_dir`.
+    pub fn load_from(location: &str, base_dir: &Path) -> Result<Self> {
+        let content = if location.starts_with("http://") || location.starts_with("https://") {
+            Self::fetch(location)?
+        } else {
+            let path = base_dir.join(location);
+            fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read symbol manifest {}", path.display()))?
+        };
+
+        Self::parse(&content).with_context(|| format!("Invalid symbol manifest {}", location))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fetch(url: &str) -> Result<String> {
+        Self::fetch_with_timeouts(url, CONNECT_TIMEOUT, READ_TIMEOUT)
+    }
+
+    /// Fetches `url`, so a hung manifest server fails the run instead of stalling CI
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fetch_with_timeouts(url: &str, connect: Duration, read: Duration) -> Result<String> {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_connect(Some(connect))
+            .timeout_recv_response(Some(read))
+            .timeout_recv_body(Some(read))
+            .build()
+            .into();
+        agent
+            .get(url)
+            .call()
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .with_context(|| format!("Failed to fetch symbol manifest from {}", url))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn fetch(url: &str) -> Result<String> {
+        bail!("Cannot fetch symbol manifest {}: remote manifests are not supported in the browser build", url)
+    }
+
+    /// Parses manifest JSON, rejecting schema versions newer than this tool understands
+    pub fn parse(content: &str) -> Result<Self> {
+        let manifest: Self = serde_json::from_str(content)?;
+        if manifest.schema_version > MANIFEST_SCHEMA_VERSION {
+            bail!(
+                "Unsupported manifest schema version {} (supported: {})",
+                manifest.schema_version,
+                MANIFEST_SCHEMA_VERSION
+            );
+        }
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    const MANIFEST: &str = r#"{
+        "schema_version": 1,
+        "source": "shared-lib",
+        "symbols": [
+            {
+                "name": "UserRepository",
+                "symbol_type": "Interface",
+                "module": "shared",
+                "package": "com.example",
+                "file_path": "shared/src/commonMain/kotlin/User.kt",
+                "is_public": true
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_load_manifest_from_relative_path() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "{}", MANIFEST).unwrap();
+
+        let dir = file.path().parent().unwrap();
+        let name = file.path().file_name().unwrap().to_str().unwrap();
+        let manifest = SymbolManifest::load_from(name, dir).unwrap();
+
+        assert_eq!(manifest.source, "shared-lib");
+        assert_eq!(manifest.symbols.len(), 1);
+        assert_eq!(manifest.symbols[0].symbol.name, "UserRepository");
+        assert!(manifest.symbols[0].exported_names.is_empty());
+    }
+
+    #[test]
+    fn test_exported_names_round_trip() {
+        let function = Symbol {
+            package: "com.example".to_string(),
+            ..Symbol::new("formatDate", SymbolType::Function, "shared", "shared/src/commonMain/kotlin/DateUtils.kt")
+        };
+        let manifest = SymbolManifest::from_symbols("shared-lib", vec![function]);
+
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert!(json.contains(r#""id":"shared:com.example.formatDate:function""#));
+        let parsed = SymbolManifest::parse(&json).unwrap();
+        let names = &parsed.symbols[0].exported_names;
+        assert_eq!(names["Android"], "formatDate");
+        assert_eq!(names["iOS"], "DateUtilsKt.formatDate");
+        assert!(!names.contains_key("Web"));
+        assert_eq!(parsed.symbols[0].symbol.package, "com.example");
+        assert_eq!(parsed.symbols[0].symbol.id(), "shared:com.example.formatDate:function");
+    }
+
+    /// Serves one connection on a local port, answering with `response` after `delay`
+    #[cfg(not(target_arch = "wasm32"))]
+    fn serve_once(response: String, delay: Duration) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = std::io::Read::read(&mut stream, &mut request);
+            std::thread::sleep(delay);
+            let _ = stream.write_all(response.as_bytes());
+        });
+        format!("http://{}/symbols.json", address)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_load_manifest_from_url() {
+        let ok = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            MANIFEST.len(),
+            MANIFEST
+        );
+        let url = serve_once(ok, Duration::ZERO);
+        let manifest = SymbolManifest::load_from(&url, Path::new("unused")).unwrap();
+        assert_eq!(manifest.symbols[0].symbol.name, "UserRepository");
+
+        let missing = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string();
+        let url = serve_once(missing, Duration::ZERO);
+        let error = SymbolManifest::load_from(&url, Path::new("unused")).unwrap_err();
+        assert_eq!(error.to_string(), format!("Failed to fetch symbol manifest from {}", url));
+
+        // A server that accepts but never answers runs into the read timeout
+        let url = serve_once(String::new(), Duration::from_secs(5));
+        let started = std::time::Instant::now();
+        let error = SymbolManifest::fetch_with_timeouts(&url, Duration::from_secs(1), Duration::from_millis(200))
+            .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(4));
+        assert_eq!(error.to_string(), format!("Failed to fetch symbol manifest from {}", url));
+        assert!(format!("{:#}", error).contains("timeout"), "{:#}", error);
+    }
+
+    #[test]
+    fn test_reject_newer_schema() {
+        let newer = MANIFEST.replace("\"schema_version\": 1", "\"schema_version\": 99");
+        assert!(SymbolManifest::parse(&newer).is_err());
+    }
+}
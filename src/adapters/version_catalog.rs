@@ -0,0 +1,157 @@
+//! Gradle version catalog (`gradle/libs.versions.toml`) support
+//! Resolves type-safe accessors such as `libs.shared.core` to library coordinates
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::adapters::file_system::FileSystem;
+
+/// Default catalog location relative to the project root
+pub const DEFAULT_CATALOG_PATH: &str = "gradle/libs.versions.toml";
+
+/// Library coordinates declared in a version catalog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogLibrary {
+    pub group: String,
+    pub name: String,
+}
+
+/// Parsed version catalog keyed by normalized accessor (e.g. `shared.core`)
+#[derive(Debug, Clone, Default)]
+pub struct VersionCatalog {
+    libraries: HashMap<String, CatalogLibrary>,
+    bundles: HashMap<String, Vec<String>>,
+}
+
+impl VersionCatalog {
+    /// Loads `gradle/libs.versions.toml` from the project root, if present
+    pub fn discover(fs: &dyn FileSystem, project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(DEFAULT_CATALOG_PATH);
+        if !fs.is_file(&path) {
+            return Ok(None);
+        }
+
+        let content = fs
+            .read_to_string(&path)
+            .with_context(|| format!("Failed to read version catalog {}", path.display()))?;
+        Self::parse(&content)
+            .map(Some)
+            .with_context(|| format!("Invalid version catalog {}", path.display()))
+    }
+
+    /// Parses version catalog TOML content
+    pub fn parse(content: &str) -> Result<Self> {
+        let document: toml::Table = toml::from_str(content)?;
+        let mut catalog = Self::default();
+
+        if let Some(libraries) = document.get("libraries").and_then(|v| v.as_table()) {
+            for (alias, declaration) in libraries {
+                if let Some(library) = Self::parse_library(declaration) {
+                    catalog.libraries.insert(Self::normalize_alias(alias), library);
+                }
+            }
+        }
+
+        if let Some(bundles) = document.get("bundles").and_then(|v| v.as_table()) {
+            for (alias, members) in bundles {
+                let members = members
+                    .as_array()
+                    .map(|m| {
+                        m.iter()
+                            .filter_map(|v| v.as_str())
+                            .map(Self::normalize_alias)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                catalog.bundles.insert(Self::normalize_alias(alias), members);
+            }
+        }
+
+        Ok(catalog)
+    }
+
+    /// Resolves an accessor written after `libs.` (e.g. `shared.core` or `bundles.shared`)
+    pub fn resolve(&self, accessor: &str) -> Vec<&CatalogLibrary> {
+        if let Some(bundle) = accessor.strip_prefix("bundles.") {
+            return self
+                .bundles
+                .get(bundle)
+                .map(|members| members.iter().filter_map(|m| self.libraries.get(m)).collect())
+                .unwrap_or_default();
+        }
+
+        self.libraries.get(accessor).into_iter().collect()
+    }
+
+    /// Normalizes an alias the way Gradle generates accessors (`-`, `_` become `.`)
+    pub fn normalize_alias(alias: &str) -> String {
+        alias.replace(['-', '_'], ".")
+    }
+
+    fn parse_library(declaration: &toml::Value) -> Option<CatalogLibrary> {
+        let coordinates = |notation: &str| {
+            let mut parts = notation.split(':');
+            Some(CatalogLibrary {
+                group: parts.next()?.to_string(),
+                name: parts.next()?.to_string(),
+            })
+        };
+
+        match declaration {
+            toml::Value::String(notation) => coordinates(notation),
+            toml::Value::Table(table) => {
+                if let Some(module) = table.get("module").and_then(|v| v.as_str()) {
+                    return coordinates(module);
+                }
+                Some(CatalogLibrary {
+                    group: table.get("group")?.as_str()?.to_string(),
+                    name: table.get("name")?.as_str()?.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG: &str = r#"
+[versions]
+shared = "1.0.0"
+
+[libraries]
+shared-core = { module = "com.example:shared-core", version.ref = "shared" }
+shared_auth = { group = "com.example", name = "shared-auth", version.ref = "shared" }
+ktor-client = "io.ktor:ktor-client-core:2.3.0"
+
+[bundles]
+shared = ["shared-core", "shared_auth"]
+"#;
+
+    #[test]
+    fn test_resolve_library_accessors() {
+        let catalog = VersionCatalog::parse(CATALOG).unwrap();
+
+        let core = catalog.resolve("shared.core");
+        assert_eq!(core.len(), 1);
+        assert_eq!(core[0].name, "shared-core");
+
+        let ktor = catalog.resolve("ktor.client");
+        assert_eq!(ktor[0].group, "io.ktor");
+        assert!(catalog.resolve("unknown").is_empty());
+    }
+
+    #[test]
+    fn test_resolve_bundle() {
+        let catalog = VersionCatalog::parse(CATALOG).unwrap();
+        let names: Vec<&str> = catalog
+            .resolve("bundles.shared")
+            .iter()
+            .map(|l| l.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["shared-core", "shared-auth"]);
+    }
+}
@@ -0,0 +1,158 @@
+//! Shared ViewModels reached through platform bridge libraries
+//! KMM-ViewModel, moko-mvvm, and the DI libraries around them hand shared
+//! ViewModels to app code through generic accessors (`viewModels<LoginViewModel>()`,
+//! `ObservableViewModel<LoginViewModel>`), factory functions
+//! (`ViewModels.shared.getLoginViewModel()`), and key-path bindings
+//! (`viewModel.state(\.isLoading)`), none of which name matching recognizes.
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::domain::{Symbol, SymbolType, SymbolUsage, UsageKind};
+
+/// Finds bridge-library references to shared ViewModels and their state
+pub struct ViewModelBridgeDetector {
+    /// `by viewModels<LoginViewModel>()`, `koinViewModel<LoginViewModel>()`,
+    /// `@StateObject var owner: ObservableViewModel<LoginViewModel>`
+    accessor_regex: Regex,
+    /// `getLoginViewModel()`, `createLoginViewModel(...)`
+    factory_regex: Regex,
+    /// moko-mvvm SwiftUI bindings: `viewModel.state(\.isLoading)`, `viewModel.binding(\.email)`
+    key_path_regex: Regex,
+}
+
+impl ViewModelBridgeDetector {
+    pub fn new() -> Self {
+        Self {
+            accessor_regex: Regex::new(
+                r"\b(?:viewModels|activityViewModels|navGraphViewModels|viewModel|hiltViewModel|koinViewModel|koinNavViewModel|getViewModel|sharedViewModel|getViewModelFactory|ObservableViewModel|ViewModelWrapper|ViewModelOwner)\s*<\s*(?:[\w.]+\.)?([A-Z]\w*)\s*>",
+            )
+            .unwrap(),
+            factory_regex: Regex::new(r"\b(?:get|create|provide|make)([A-Z]\w*ViewModel)\s*\(").unwrap(),
+            key_path_regex: Regex::new(
+                r"\.(?:state|stateNullable|binding|bindingNullable|publisher)\s*\(\s*\\\.(\w+)",
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Usages of shared ViewModel classes and properties that `content` reaches through a bridge
+    ///
+    /// `comment_prefixes` mark lines that are skipped, as in name matching.
+    pub fn detect(
+        &self,
+        content: &str,
+        file_path: &Path,
+        symbols: &[Symbol],
+        comment_prefixes: &[&str],
+    ) -> Vec<SymbolUsage> {
+        let names_of = |symbol_type: SymbolType| -> HashSet<&str> {
+            symbols
+                .iter()
+                .filter(|s| s.symbol_type == symbol_type)
+                .map(|s| s.name.as_str())
+                .collect()
+        };
+        let classes = names_of(SymbolType::Class);
+        let properties = names_of(SymbolType::Property);
+        if classes.is_empty() && properties.is_empty() {
+            return Vec::new();
+        }
+
+        let mut usages = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let trimmed = line.trim();
+            if comment_prefixes.iter().any(|prefix| trimmed.starts_with(prefix)) {
+                continue;
+            }
+
+            let captured = |regex: &Regex| -> Vec<String> {
+                regex.captures_iter(line).map(|c| c[1].to_string()).collect()
+            };
+            // Accessors and factories hand out an instance; key paths read its state
+            let referenced = captured(&self.accessor_regex)
+                .into_iter()
+                .chain(captured(&self.factory_regex))
+                .filter(|name| classes.contains(name.as_str()))
+                .map(|name| (name, UsageKind::Instantiation))
+                .chain(
+                    captured(&self.key_path_regex)
+                        .into_iter()
+                        .filter(|name| properties.contains(name.as_str()))
+                        .map(|name| (name, UsageKind::Reference)),
+                );
+
+            let mut found = HashSet::new();
+            for (name, kind) in referenced {
+                if found.insert(name.clone()) {
+                    usages.push(SymbolUsage {
+                        symbol_name: name,
+                        file_path: file_path.to_string_lossy().to_string(),
+                        line_number: index + 1,
+                        context: trimmed.to_string(),
+                        kind,
+                    });
+                }
+            }
+        }
+
+        usages
+    }
+}
+
+impl Default for ViewModelBridgeDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, symbol_type: SymbolType) -> Symbol {
+        Symbol {
+            package: "com.example.login".to_string(),
+            ..Symbol::new(name, symbol_type, "shared", "shared/src/commonMain/kotlin/LoginViewModel.kt")
+        }
+    }
+
+    #[test]
+    fn test_detects_bridge_references() {
+        let symbols = vec![
+            symbol("LoginViewModel", SymbolType::Class),
+            symbol("isLoading", SymbolType::Property),
+            symbol("ProfileViewModel", SymbolType::Class),
+        ];
+        let detector = ViewModelBridgeDetector::new();
+        let detect = |content: &str, file: &str| -> Vec<(String, usize)> {
+            detector
+                .detect(content, Path::new(file), &symbols, &["//"])
+                .into_iter()
+                .map(|u| (u.symbol_name, u.line_number))
+                .collect()
+        };
+
+        let kotlin = "private val login by viewModels<LoginViewModel>()\n\
+                      val profile = koinViewModel<com.example.profile.ProfileViewModel>()\n\
+                      // val old = viewModel<LoginViewModel>()\n\
+                      val other = viewModels<SettingsViewModel>()\n";
+        assert_eq!(
+            detect(kotlin, "app/LoginActivity.kt"),
+            vec![("LoginViewModel".to_string(), 1), ("ProfileViewModel".to_string(), 2)]
+        );
+
+        let swift = "@StateObject var owner = ObservableViewModel<LoginViewModel>(ViewModels.shared.getLoginViewModel())\n\
+                     let profile = ViewModels.shared.createProfileViewModel(userId: id)\n\
+                     if viewModel.state(\\.isLoading) { ProgressView() }\n";
+        assert_eq!(
+            detect(swift, "iosApp/LoginView.swift"),
+            vec![
+                ("LoginViewModel".to_string(), 1),
+                ("ProfileViewModel".to_string(), 2),
+                ("isLoading".to_string(), 3),
+            ]
+        );
+    }
+}
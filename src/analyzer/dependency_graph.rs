@@ -1,8 +1,10 @@
 use anyhow::Result;
 use regex::Regex;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
+
+use crate::domain::{DependencyEdge, ImportSite};
 
 /// Builds and analyzes dependency graph between files
 pub struct DependencyGraph {
@@ -12,6 +14,11 @@ pub struct DependencyGraph {
     reverse_dependencies: HashMap<String, HashSet<String>>,
     /// Package to file mapping (for resolving imports)
     package_map: HashMap<String, String>,
+    /// Import statements behind each edge, by importing file and imported file;
+    /// unused imports are kept even when they make no dependency
+    imports: HashMap<String, BTreeMap<String, Vec<ImportSite>>>,
+    /// Whether an import the file never refers to still makes it a dependent
+    follow_unused_imports: bool,
 }
 
 impl DependencyGraph {
@@ -21,66 +28,88 @@ impl DependencyGraph {
             dependencies: HashMap::new(),
             reverse_dependencies: HashMap::new(),
             package_map: HashMap::new(),
+            imports: HashMap::new(),
+            follow_unused_imports: false,
         }
     }
 
+    /// Keeps dependency edges of imports the importing file never refers to
+    pub fn with_unused_imports(mut self, follow: bool) -> Self {
+        self.follow_unused_imports = follow;
+        self
+    }
+
     /// Builds the dependency graph from the given files
     pub fn build(&mut self, files: &[PathBuf]) -> Result<()> {
+        let sources = files
+            .iter()
+            .map(|file| Ok((file.clone(), fs::read_to_string(file)?)))
+            .collect::<Result<Vec<_>>>()?;
+        self.build_from_sources(&sources);
+        Ok(())
+    }
+
+    /// Builds the dependency graph from files whose content was already read
+    pub fn build_from_sources(&mut self, sources: &[(PathBuf, String)]) {
         // First pass: build package map
-        for file in files {
-            if let Ok(package_name) = self.extract_package_name(file) {
-                if let Some(class_name) = self.extract_primary_class_name(file) {
-                    let full_name = format!("{}.{}", package_name, class_name);
-                    self.package_map.insert(full_name, file.to_string_lossy().to_string());
-                }
+        for (file, content) in sources {
+            let package_name = self.extract_package_name(content);
+            if let Some(class_name) = self.extract_primary_class_name(content) {
+                let full_name = format!("{}.{}", package_name, class_name);
+                self.package_map.insert(full_name, file.to_string_lossy().to_string());
             }
         }
 
         // Second pass: build dependency graph
-        for file in files {
+        for (file, content) in sources {
             let file_path = file.to_string_lossy().to_string();
-            let imports = self.extract_imports(file)?;
 
             let mut deps = HashSet::new();
-            for import in imports {
+            let mut sites: BTreeMap<String, Vec<ImportSite>> = BTreeMap::new();
+            for (line, import, statement) in self.extract_import_lines(content) {
                 // Try to resolve import to file path
-                if let Some(dep_file) = self.resolve_import(&import) {
-                    deps.insert(dep_file.clone());
-
-                    // Update reverse dependencies
-                    self.reverse_dependencies
-                        .entry(dep_file)
-                        .or_insert_with(HashSet::new)
-                        .insert(file_path.clone());
+                let Some(dep_file) = self.resolve_import(&import) else {
+                    continue;
+                };
+                let used = Self::refers_to_import(content, &import);
+                sites.entry(dep_file.clone()).or_default().push(ImportSite { line, statement, used });
+
+                // Importing alone does not make a file depend on another
+                if !self.follow_unused_imports && !used {
+                    continue;
                 }
+                deps.insert(dep_file.clone());
+
+                // Update reverse dependencies
+                self.reverse_dependencies
+                    .entry(dep_file)
+                    .or_default()
+                    .insert(file_path.clone());
             }
 
+            self.imports.insert(file_path.clone(), sites);
             self.dependencies.insert(file_path, deps);
         }
-
-        Ok(())
     }
 
-    /// Extracts package name from a Kotlin file
-    fn extract_package_name(&self, file: &Path) -> Result<String> {
-        let content = fs::read_to_string(file)?;
+    /// Extracts package name from Kotlin source
+    fn extract_package_name(&self, content: &str) -> String {
         let package_regex = Regex::new(r"(?m)^package\s+([a-zA-Z0-9_.]+)").unwrap();
 
-        if let Some(cap) = package_regex.captures(&content) {
+        if let Some(cap) = package_regex.captures(content) {
             if let Some(package) = cap.get(1) {
-                return Ok(package.as_str().to_string());
+                return package.as_str().to_string();
             }
         }
 
-        Ok(String::new())
+        String::new()
     }
 
-    /// Extracts primary class/interface/object name from a Kotlin file
-    fn extract_primary_class_name(&self, file: &Path) -> Option<String> {
-        let content = fs::read_to_string(file).ok()?;
+    /// Extracts primary class/interface/object name from Kotlin source
+    fn extract_primary_class_name(&self, content: &str) -> Option<String> {
         let class_regex = Regex::new(r"(?m)^\s*(?:public\s+)?(?:class|interface|object)\s+([A-Z][a-zA-Z0-9_]*)").unwrap();
 
-        if let Some(cap) = class_regex.captures(&content) {
+        if let Some(cap) = class_regex.captures(content) {
             if let Some(name) = cap.get(1) {
                 return Some(name.as_str().to_string());
             }
@@ -89,19 +118,43 @@ impl DependencyGraph {
         None
     }
 
-    /// Extracts import statements from a Kotlin file
-    fn extract_imports(&self, file: &Path) -> Result<Vec<String>> {
-        let content = fs::read_to_string(file)?;
-        let import_regex = Regex::new(r"(?m)^import\s+([a-zA-Z0-9_.]+)").unwrap();
+    /// Extracts the 1-based line, imported name, and statement of each import in Kotlin source
+    fn extract_import_lines(&self, content: &str) -> Vec<(usize, String, String)> {
+        let import_regex = Regex::new(r"^import\s+([a-zA-Z0-9_.]+)").unwrap();
+
+        content
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                let import = import_regex.captures(line)?.get(1)?.as_str().to_string();
+                Some((index + 1, import, line.trim_end().to_string()))
+            })
+            .collect()
+    }
 
-        let mut imports = Vec::new();
-        for cap in import_regex.captures_iter(&content) {
-            if let Some(import) = cap.get(1) {
-                imports.push(import.as_str().to_string());
-            }
+    /// Whether code outside the import lines names what `import` brings in (or its alias)
+    ///
+    /// Wildcard imports are always considered used.
+    fn refers_to_import(content: &str, import: &str) -> bool {
+        let is_import = |line: &str| line.trim_start().starts_with("import ");
+        let alias = content
+            .lines()
+            .filter(|line| is_import(line))
+            .find_map(|line| {
+                let (path, alias) = line.trim().trim_start_matches("import ").split_once(" as ")?;
+                (path.trim() == import).then(|| alias.trim().trim_end_matches(';'))
+            });
+        let name = alias.unwrap_or_else(|| import.rsplit('.').next().unwrap_or(import));
+        if name.is_empty() {
+            return true;
         }
 
-        Ok(imports)
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        content.lines().filter(|line| !is_import(line)).any(|line| {
+            line.match_indices(name).any(|(at, _)| {
+                !line[..at].ends_with(is_ident) && !line[at + name.len()..].starts_with(is_ident)
+            })
+        })
     }
 
     /// Resolves an import statement to a file path
@@ -158,7 +211,6 @@ impl DependencyGraph {
     }
 
     /// Gets all dependencies of a file (direct and transitive)
-    #[allow(dead_code)]
     pub fn get_all_dependencies(&self, file: &str) -> HashSet<String> {
         let mut all_deps = HashSet::new();
         let mut queue = VecDeque::new();
@@ -185,6 +237,65 @@ impl DependencyGraph {
         all_deps
     }
 
+    /// Edges from `file` to each file it imports, with the import statements behind them
+    pub fn edges_from(&self, file: &str) -> Vec<DependencyEdge> {
+        self.imports
+            .get(file)
+            .into_iter()
+            .flatten()
+            .map(|(to, imports)| DependencyEdge {
+                from: file.to_string(),
+                to: to.clone(),
+                imports: imports.clone(),
+            })
+            .collect()
+    }
+
+    /// The shortest chain of dependency edges from `file` to any of `targets`, empty
+    /// when `file` depends on none of them
+    pub fn dependency_chain(&self, file: &str, targets: &HashSet<String>) -> Vec<DependencyEdge> {
+        let mut previous: HashMap<&str, &str> = HashMap::new();
+        let mut queue = VecDeque::from([file]);
+        let mut found = None;
+        while let Some(current) = queue.pop_front() {
+            if current != file && targets.contains(current) {
+                found = Some(current);
+                break;
+            }
+            let mut deps: Vec<&str> = self.dependencies.get(current).into_iter().flatten().map(String::as_str).collect();
+            // Visit in path order so the chain does not depend on hashing
+            deps.sort_unstable();
+            for dep in deps {
+                if dep != file && !previous.contains_key(dep) {
+                    previous.insert(dep, current);
+                    queue.push_back(dep);
+                }
+            }
+        }
+
+        let mut chain = Vec::new();
+        let mut to = found;
+        while let Some(current) = to {
+            let Some(&from) = previous.get(current) else {
+                break;
+            };
+            let imports = self
+                .imports
+                .get(from)
+                .and_then(|sites| sites.get(current))
+                .cloned()
+                .unwrap_or_default();
+            chain.push(DependencyEdge {
+                from: from.to_string(),
+                to: current.to_string(),
+                imports,
+            });
+            to = Some(from);
+        }
+        chain.reverse();
+        chain
+    }
+
     /// Gets statistics about the dependency graph
     #[allow(dead_code)]
     pub fn get_stats(&self) -> DependencyStats {
@@ -216,28 +327,50 @@ pub struct DependencyStats {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
-    use tempfile::NamedTempFile;
 
     #[test]
     fn test_extract_package_name() {
         let graph = DependencyGraph::new();
-        let mut file = NamedTempFile::new().unwrap();
-        writeln!(file, "package com.example.app").unwrap();
 
-        let package = graph.extract_package_name(file.path()).unwrap();
+        let package = graph.extract_package_name("package com.example.app\n");
         assert_eq!(package, "com.example.app");
     }
 
     #[test]
     fn test_extract_imports() {
         let graph = DependencyGraph::new();
-        let mut file = NamedTempFile::new().unwrap();
-        writeln!(file, "import com.example.UserRepository").unwrap();
-        writeln!(file, "import com.example.User").unwrap();
+        let content = "import com.example.UserRepository\nimport com.example.User\n";
 
-        let imports = graph.extract_imports(file.path()).unwrap();
+        let imports = graph.extract_import_lines(content);
         assert_eq!(imports.len(), 2);
-        assert!(imports.contains(&"com.example.UserRepository".to_string()));
+        assert_eq!(
+            imports[1],
+            (2, "com.example.User".to_string(), "import com.example.User".to_string())
+        );
+        assert!(imports.iter().any(|(_, import, _)| import == "com.example.UserRepository"));
+    }
+
+    #[test]
+    fn test_unused_imports_are_not_dependencies() {
+        let sources = vec![
+            (PathBuf::from("Session.kt"), "package com.example.auth\n\nclass Session\n".to_string()),
+            (
+                PathBuf::from("Uses.kt"),
+                "package com.example.app\n\nimport com.example.auth.Session as AuthSession\n\nclass Uses(val session: AuthSession)\n".to_string(),
+            ),
+            (
+                PathBuf::from("Stale.kt"),
+                "package com.example.app\n\nimport com.example.auth.Session\n\nclass Stale\n".to_string(),
+            ),
+        ];
+        let direct = HashSet::from(["Session.kt".to_string()]);
+
+        let mut graph = DependencyGraph::new();
+        graph.build_from_sources(&sources);
+        assert_eq!(graph.compute_transitive_impact(&direct), HashSet::from(["Uses.kt".to_string()]));
+
+        let mut graph = DependencyGraph::new().with_unused_imports(true);
+        graph.build_from_sources(&sources);
+        assert_eq!(graph.compute_transitive_impact(&direct).len(), 2);
     }
 }
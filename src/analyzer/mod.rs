@@ -1,6 +1,8 @@
-/// Legacy analyzer module - kept for backward compatibility
-/// Most functionality has been moved to use_cases and adapters
+//! Legacy analyzer module - kept for backward compatibility
+//! Most functionality has been moved to use_cases and adapters
 
 pub mod dependency_graph;
 pub mod models;
-pub mod symbol_extractor;
\ No newline at end of file
+pub mod symbol_extractor;
+#[cfg(feature = "treesitter")]
+pub mod tree_sitter_extractor;
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
+use crate::domain::ObjCName;
+
 /// Analysis result structure
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AnalysisResult {
@@ -67,21 +69,76 @@ pub struct KmpSymbol {
     pub symbol_type: SymbolType,
     /// Module where the symbol is defined
     pub module: String,
+    /// Package where the symbol is defined
+    pub package: String,
     /// File path where the symbol is defined
     pub file_path: String,
-    /// Whether the symbol is public
-    pub is_public: bool,
+    /// Visibility outside the declaring file, narrowed by enclosing declarations
+    pub visibility: Visibility,
+    /// Whether the visibility modifier is written out rather than implied, as explicit API mode requires
+    pub visibility_declared: bool,
+    /// Whether the symbol has a KDoc comment
+    pub is_documented: bool,
+    /// Annotations declared on the symbol (e.g. `Deprecated`)
+    pub annotations: Vec<String>,
+    /// Platform type an `actual typealias` points to (simple name)
+    pub actual_target: Option<String>,
+    /// Name given with `@JsName`, under which Kotlin/JS exports the symbol
+    pub js_name: Option<String>,
+    /// Names given with `@ObjCName`, under which Kotlin/Native exports the symbol
+    pub objc_name: Option<ObjCName>,
+    /// First and last line of the declaration, 1-based
+    pub lines: (usize, usize),
+}
+
+/// Kotlin visibility of a declaration, from the widest to the narrowest
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Visibility {
+    Public,
+    Internal,
+    Protected,
+    Private,
+}
+
+impl Visibility {
+    /// The visibility a modifier keyword states
+    pub fn from_modifier(modifier: &str) -> Option<Self> {
+        match modifier {
+            "public" => Some(Visibility::Public),
+            "internal" => Some(Visibility::Internal),
+            "protected" => Some(Visibility::Protected),
+            "private" => Some(Visibility::Private),
+            _ => None,
+        }
+    }
+
+    /// Whether code of other files in the module can use the declaration
+    pub fn is_module_visible(&self) -> bool {
+        matches!(self, Visibility::Public | Visibility::Internal)
+    }
 }
 
 /// Symbol type enumeration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum SymbolType {
     Class,
+    ValueClass,
+    /// `enum class`, whose entries are extracted as [`EnumEntry`](Self::EnumEntry) symbols
+    Enum,
+    EnumEntry,
+    /// `sealed class` or `sealed interface`
+    SealedClass,
+    DataClass,
+    AnnotationClass,
     Interface,
     Object,
     Function,
     Property,
     TypeAlias,
+    /// `expect` declaration of any kind, implemented per platform
+    Expect,
+    /// `actual` declaration of any kind but a typealias, implementing an `expect` one
+    Actual,
 }
 
 /// Symbol usage statistics
@@ -1,137 +1,598 @@
 use anyhow::Result;
+#[cfg(feature = "treesitter")]
+use log::debug;
+use log::warn;
 use regex::Regex;
 use std::fs;
 use std::path::Path;
 
-use super::models::{KmpSymbol, SymbolType};
+use super::models::{KmpSymbol, SymbolType, Visibility};
+#[cfg(feature = "treesitter")]
+use super::tree_sitter_extractor::TreeSitterExtractor;
+use crate::domain::{ObjCName, SymbolParser};
 
-/// Extracts public symbols from KMP source code
+/// Extracts public and internal symbols from KMP source code
 pub struct SymbolExtractor {
+    package_regex: Regex,
     class_regex: Regex,
+    value_class_regex: Regex,
+    enum_regex: Regex,
+    sealed_regex: Regex,
+    data_class_regex: Regex,
+    annotation_class_regex: Regex,
     interface_regex: Regex,
     object_regex: Regex,
     function_regex: Regex,
     property_regex: Regex,
     typealias_regex: Regex,
+    expect_regex: Regex,
+    actual_regex: Regex,
+    actual_typealias_regex: Regex,
+    /// Syntax tree extraction tried before the regular expressions, with `--parser treesitter`
+    #[cfg(feature = "treesitter")]
+    tree_sitter: Option<TreeSitterExtractor>,
 }
 
 impl SymbolExtractor {
     /// Creates a new SymbolExtractor instance
     pub fn new() -> Self {
+        // Declarations may start with context parameters: context(scope: Scope) fun name
+        let context = r"(?:context\s*\([^)]*\)\s*)?";
+        // Any visibility modifier; which one is read from the match
+        let visibility = r"(?:(?:public|internal|protected|private)\s+)?";
+        // Annotations on the declaration's own line: @JvmStatic fun create()
+        let annotations = r"(?:@[A-Za-z][a-zA-Z0-9_.]*[ \t]+)*";
+        // Receiver of an extension: String., List<T>., User?.
+        let receiver = r"(?:[A-Za-z_][a-zA-Z0-9_.]*?(?:<[^(=]*?>)?\??\.)?";
         Self {
-            // Match: public class ClassName, class ClassName (public by default in Kotlin)
-            class_regex: Regex::new(r"(?m)^\s*(?:public\s+)?class\s+([A-Z][a-zA-Z0-9_]*)").unwrap(),
+            // Match: package com.example.feature
+            package_regex: Regex::new(r"(?m)^\s*package\s+([a-zA-Z0-9_.]+)").unwrap(),
+            // Match: public class ClassName, class ClassName (public by default in Kotlin), context(Scope) class ClassName
+            class_regex: Regex::new(&format!(r"(?m)^\s*{context}{visibility}class\s+([A-Z][a-zA-Z0-9_]*)")).unwrap(),
+            // Match: @JvmInline value class Email, inline class Email
+            value_class_regex: Regex::new(&format!(
+                r"(?m)^\s*(?:@JvmInline\s+)?{context}{visibility}(?:value|inline)\s+class\s+([A-Z][a-zA-Z0-9_]*)"
+            ))
+            .unwrap(),
+            // Match: enum class Status, @Serializable enum class Status(val code: Int)
+            enum_regex: Regex::new(&format!(
+                r"(?m)^\s*{annotations}{context}{visibility}enum\s+class\s+([A-Z][a-zA-Z0-9_]*)"
+            ))
+            .unwrap(),
+            // Match: sealed class Result, sealed interface LoadState
+            sealed_regex: Regex::new(&format!(
+                r"(?m)^\s*{annotations}{context}{visibility}sealed\s+(?:class|interface)\s+([A-Z][a-zA-Z0-9_]*)"
+            ))
+            .unwrap(),
+            // Match: data class User, @Serializable data class User; not data object
+            data_class_regex: Regex::new(&format!(
+                r"(?m)^\s*{annotations}{context}{visibility}data\s+class\s+([A-Z][a-zA-Z0-9_]*)"
+            ))
+            .unwrap(),
+            // Match: annotation class Experimental
+            annotation_class_regex: Regex::new(&format!(
+                r"(?m)^\s*{annotations}{visibility}annotation\s+class\s+([A-Z][a-zA-Z0-9_]*)"
+            ))
+            .unwrap(),
             // Match: public interface InterfaceName
-            interface_regex: Regex::new(r"(?m)^\s*(?:public\s+)?interface\s+([A-Z][a-zA-Z0-9_]*)").unwrap(),
-            // Match: public object ObjectName
-            object_regex: Regex::new(r"(?m)^\s*(?:public\s+)?object\s+([A-Z][a-zA-Z0-9_]*)").unwrap(),
-            // Match: public fun functionName, fun functionName
-            function_regex: Regex::new(r"(?m)^\s*(?:public\s+)?fun\s+([a-z][a-zA-Z0-9_]*)\s*\(").unwrap(),
-            // Match: public val/var propertyName
-            property_regex: Regex::new(r"(?m)^\s*(?:public\s+)?(?:val|var)\s+([a-z][a-zA-Z0-9_]*)\s*[:=]").unwrap(),
+            interface_regex: Regex::new(&format!(r"(?m)^\s*{visibility}interface\s+([A-Z][a-zA-Z0-9_]*)")).unwrap(),
+            // Match: public object ObjectName, data object Loading, companion object Factory
+            object_regex: Regex::new(&format!(
+                r"(?m)^\s*{visibility}(?:(?:data|companion)\s+)?object\s+([A-Z][a-zA-Z0-9_]*)"
+            ))
+            .unwrap(),
+            // Match: public fun functionName, suspend fun load, fun String.toSlug, fun <T> List<T>.second;
+            // not overrides, which belong to the supertype's API
+            function_regex: Regex::new(&format!(
+                r"(?m)^\s*{annotations}{context}{visibility}(?:(?:suspend|inline|operator|infix|tailrec|external|open|abstract|final)\s+)*fun\s+(?:<[^(]*?>\s*)?{receiver}([a-z][a-zA-Z0-9_]*)\s*\("
+            ))
+            .unwrap(),
+            // Match: public val/var propertyName, const val MAX_SIZE, val String.slug; an explicit
+            // backing field (`field = ...`) below is not a property
+            property_regex: Regex::new(&format!(
+                r"(?m)^\s*{annotations}{context}{visibility}(?:(?:const|lateinit|open|abstract|final)\s+)*(?:val|var)\s+(?:<[^(]*?>\s*)?{receiver}([a-zA-Z_][a-zA-Z0-9_]*)\s*[:=]"
+            ))
+            .unwrap(),
             // Match: public typealias AliasName
-            typealias_regex: Regex::new(r"(?m)^\s*(?:public\s+)?typealias\s+([A-Z][a-zA-Z0-9_]*)").unwrap(),
+            typealias_regex: Regex::new(&format!(r"(?m)^\s*{visibility}typealias\s+([A-Z][a-zA-Z0-9_]*)")).unwrap(),
+            // Match: expect class Platform, expect fun platformName, expect val <T> List<T>.second
+            expect_regex: Regex::new(&format!(
+                r"(?m)^\s*{visibility}expect\s+(?:[a-z]+\s+)*?(?:class|interface|object|fun|val|var)\s+(?:<[^>]*>\s*)?(?:[A-Za-z_][a-zA-Z0-9_]*(?:<[^>]*>)?\??\.)?([A-Za-z_][a-zA-Z0-9_]*)"
+            ))
+            .unwrap(),
+            // Match: actual class Platform, actual fun platformName; not actual typealias
+            actual_regex: Regex::new(&format!(
+                r"(?m)^\s*{visibility}actual\s+(?:[a-z]+\s+)*?(?:class|interface|object|fun|val|var)\s+(?:<[^>]*>\s*)?(?:[A-Za-z_][a-zA-Z0-9_]*(?:<[^>]*>)?\??\.)?([A-Za-z_][a-zA-Z0-9_]*)"
+            ))
+            .unwrap(),
+            // Match: actual typealias Foo = AndroidFoo (captures the alias and its target)
+            actual_typealias_regex: Regex::new(&format!(
+                r"(?m)^\s*{visibility}actual\s+typealias\s+([A-Z][a-zA-Z0-9_]*)(?:<[^=]*>)?\s*=\s*([A-Za-z_][a-zA-Z0-9_.]*)"
+            ))
+            .unwrap(),
+            #[cfg(feature = "treesitter")]
+            tree_sitter: None,
         }
     }
 
-    /// Extracts all public symbols from a Kotlin file
+    /// Extracts with the given parser; the regular expressions remain the fallback for
+    /// files the syntax tree cannot be built for, and for builds without tree-sitter
+    pub fn with_parser(self, parser: SymbolParser) -> Self {
+        if !parser.is_available() {
+            warn!("This build has no {} parser; extracting symbols with regex", parser.name());
+        }
+        Self {
+            #[cfg(feature = "treesitter")]
+            tree_sitter: (parser == SymbolParser::TreeSitter).then(TreeSitterExtractor::new),
+            ..self
+        }
+    }
+
+    /// Extracts the public and internal symbols of a Kotlin file
     pub fn extract_symbols(&self, file_path: &Path, module: &str) -> Result<Vec<KmpSymbol>> {
         let content = fs::read_to_string(file_path)?;
+        Ok(self.extract_symbols_from_source(&content, file_path, module))
+    }
+
+    /// Extracts the public and internal symbols of Kotlin source already read from `file_path`
+    ///
+    /// A declaration is as visible as the narrowest of its own visibility and that of the
+    /// declarations it is nested in; private and protected declarations are left out.
+    pub fn extract_symbols_from_source(
+        &self,
+        content: &str,
+        file_path: &Path,
+        module: &str,
+    ) -> Vec<KmpSymbol> {
+        let mut symbols = self
+            .extract_from_tree(content, file_path, module)
+            .unwrap_or_else(|| self.extract_declarations(content, file_path, module));
+        symbols.retain(|symbol| symbol.visibility.is_module_visible());
+        symbols
+    }
+
+    /// Declarations read from the syntax tree, which states the visibility of each, when
+    /// the tree-sitter parser is selected and the file parses
+    #[cfg(feature = "treesitter")]
+    fn extract_from_tree(&self, content: &str, file_path: &Path, module: &str) -> Option<Vec<KmpSymbol>> {
+        let symbols = self.tree_sitter.as_ref()?.extract(content, file_path, module);
+        if symbols.is_none() {
+            debug!("tree-sitter cannot parse {}; extracting its symbols with regex", file_path.display());
+        }
+        symbols
+    }
+
+    #[cfg(not(feature = "treesitter"))]
+    fn extract_from_tree(&self, _content: &str, _file_path: &Path, _module: &str) -> Option<Vec<KmpSymbol>> {
+        None
+    }
+
+    fn extract_declarations(&self, content: &str, file_path: &Path, module: &str) -> Vec<KmpSymbol> {
         let mut symbols = Vec::new();
 
-        // Skip if file is private or internal
-        if self.is_private_file(&content) {
-            return Ok(symbols);
+        let package = self
+            .package_regex
+            .captures(content)
+            .and_then(|cap| cap.get(1))
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+
+        let extractors = [
+            (&self.class_regex, SymbolType::Class),
+            (&self.value_class_regex, SymbolType::ValueClass),
+            (&self.enum_regex, SymbolType::Enum),
+            (&self.sealed_regex, SymbolType::SealedClass),
+            (&self.data_class_regex, SymbolType::DataClass),
+            (&self.annotation_class_regex, SymbolType::AnnotationClass),
+            (&self.interface_regex, SymbolType::Interface),
+            (&self.object_regex, SymbolType::Object),
+            (&self.function_regex, SymbolType::Function),
+            (&self.property_regex, SymbolType::Property),
+            (&self.typealias_regex, SymbolType::TypeAlias),
+            (&self.expect_regex, SymbolType::Expect),
+            (&self.actual_regex, SymbolType::Actual),
+        ];
+
+        for (regex, symbol_type) in extractors {
+            for cap in regex.captures_iter(content) {
+                if let (Some(declaration), Some(name)) = (cap.get(0), cap.get(1)) {
+                    let header = Self::declaration_header(content, name.start());
+                    let declared = Self::declared_visibility(declaration.as_str());
+                    let mut annotations = header.annotations;
+                    annotations.extend(Self::inline_annotations(declaration.as_str()));
+                    symbols.push(KmpSymbol {
+                        name: name.as_str().to_string(),
+                        symbol_type: symbol_type.clone(),
+                        module: module.to_string(),
+                        package: package.clone(),
+                        file_path: file_path.to_string_lossy().to_string(),
+                        visibility: declared.unwrap_or(Visibility::Public),
+                        visibility_declared: declared.is_some(),
+                        is_documented: header.documented,
+                        annotations,
+                        actual_target: None,
+                        js_name: header.js_name,
+                        objc_name: header.objc_name,
+                        lines: (Self::line_of(content, name.start()), 0),
+                    });
+                }
+            }
         }
 
-        // Extract classes
-        for cap in self.class_regex.captures_iter(&content) {
-            if let Some(name) = cap.get(1) {
+        // Extract actual typealiases together with the platform type they point to
+        for cap in self.actual_typealias_regex.captures_iter(content) {
+            if let (Some(declaration), Some(name), Some(target)) = (cap.get(0), cap.get(1), cap.get(2)) {
+                let header = Self::declaration_header(content, name.start());
+                let declared = Self::declared_visibility(declaration.as_str());
+                let target = target.as_str();
                 symbols.push(KmpSymbol {
                     name: name.as_str().to_string(),
-                    symbol_type: SymbolType::Class,
+                    symbol_type: SymbolType::TypeAlias,
                     module: module.to_string(),
+                    package: package.clone(),
                     file_path: file_path.to_string_lossy().to_string(),
-                    is_public: true,
+                    visibility: declared.unwrap_or(Visibility::Public),
+                    visibility_declared: declared.is_some(),
+                    is_documented: header.documented,
+                    annotations: header.annotations,
+                    actual_target: Some(target.rsplit('.').next().unwrap_or(target).to_string()),
+                    js_name: header.js_name,
+                    objc_name: header.objc_name,
+                    lines: (Self::line_of(content, name.start()), 0),
                 });
             }
         }
 
-        // Extract interfaces
-        for cap in self.interface_regex.captures_iter(&content) {
-            if let Some(name) = cap.get(1) {
+        // Extract the entries of enum classes, as visible as their class
+        for cap in self.enum_regex.captures_iter(content) {
+            let Some(declaration) = cap.get(0) else {
+                continue;
+            };
+            let visibility = Self::declared_visibility(declaration.as_str()).unwrap_or(Visibility::Public);
+            for entry in Self::enum_entries(content, declaration.end()) {
+                // Only an entry starting its line can have a KDoc or annotations above it
+                let line_start = content[..entry.start].rfind('\n').map_or(0, |i| i + 1);
+                let header = if content[line_start..entry.start].trim().is_empty() {
+                    Self::declaration_header(content, entry.start)
+                } else {
+                    DeclarationHeader::default()
+                };
+                let mut annotations = header.annotations;
+                annotations.extend(entry.annotations.into_iter().map(str::to_string));
                 symbols.push(KmpSymbol {
-                    name: name.as_str().to_string(),
-                    symbol_type: SymbolType::Interface,
+                    name: entry.name.to_string(),
+                    symbol_type: SymbolType::EnumEntry,
                     module: module.to_string(),
+                    package: package.clone(),
                     file_path: file_path.to_string_lossy().to_string(),
-                    is_public: true,
+                    visibility,
+                    visibility_declared: false,
+                    is_documented: header.documented,
+                    annotations,
+                    actual_target: None,
+                    js_name: header.js_name,
+                    objc_name: header.objc_name,
+                    lines: (Self::line_of(content, entry.offset), 0),
                 });
             }
         }
 
-        // Extract objects
-        for cap in self.object_regex.captures_iter(&content) {
-            if let Some(name) = cap.get(1) {
-                symbols.push(KmpSymbol {
-                    name: name.as_str().to_string(),
-                    symbol_type: SymbolType::Object,
-                    module: module.to_string(),
-                    file_path: file_path.to_string_lossy().to_string(),
-                    is_public: true,
-                });
+        Self::assign_spans(content, &mut symbols);
+        Self::narrow_visibility(content, &mut symbols);
+        symbols
+    }
+
+    /// Names of the entries of the enum class whose name ends at `offset`, with the offset of each
+    ///
+    /// Entries run from the opening brace of the class body to the first `;` or the closing
+    /// brace; their annotations, arguments, and bodies are skipped.
+    fn enum_entries(content: &str, offset: usize) -> Vec<EnumEntry<'_>> {
+        let bytes = content.as_bytes();
+        let Some(mut i) = Self::enum_body(content, offset) else {
+            return Vec::new();
+        };
+        let mut entries = Vec::new();
+        loop {
+            i = Self::skip_trivia(content, i);
+            let start = i;
+            let mut annotations = Vec::new();
+            while bytes.get(i) == Some(&b'@') {
+                let name_len = content[i + 1..]
+                    .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | ':')))
+                    .unwrap_or(content.len() - i - 1);
+                let annotation = &content[i + 1..i + 1 + name_len];
+                annotations.push(annotation.rsplit(':').next().unwrap_or(annotation));
+                i = Self::skip_trivia(content, i + 1 + name_len);
+                if bytes.get(i) == Some(&b'(') {
+                    i = Self::skip_trivia(content, Self::skip_group(content, i));
+                }
+            }
+            let name_len = content[i..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(content.len() - i);
+            if name_len == 0 {
+                break;
+            }
+            entries.push(EnumEntry {
+                name: &content[i..i + name_len],
+                offset: i,
+                start,
+                annotations,
+            });
+            // Arguments are skipped as brackets; an entry's own body is skipped whole
+            let mut next = Self::skip_to_separator(content, i + name_len);
+            while let Some((open, b'{')) = next {
+                next = Self::skip_to_separator(content, Self::skip_group(content, open));
+            }
+            match next {
+                Some((comma, b',')) => i = comma + 1,
+                _ => break,
             }
         }
+        entries
+    }
 
-        // Extract functions
-        for cap in self.function_regex.captures_iter(&content) {
-            if let Some(name) = cap.get(1) {
-                symbols.push(KmpSymbol {
-                    name: name.as_str().to_string(),
-                    symbol_type: SymbolType::Function,
-                    module: module.to_string(),
-                    file_path: file_path.to_string_lossy().to_string(),
-                    is_public: true,
-                });
+    /// Offset just past the `{` opening the body of a class whose name ends at `offset`,
+    /// skipping its constructor and supertypes; `None` when the class has no body
+    fn enum_body(content: &str, offset: usize) -> Option<usize> {
+        let mut i = Self::skip_trivia(content, offset);
+        let mut supertypes = offset;
+        if content.as_bytes().get(i) == Some(&b'(') {
+            i = Self::skip_group(content, i);
+            supertypes = i;
+        }
+        let mut next = Self::skip_to_separator(content, i)?;
+        while next.1 == b',' {
+            next = Self::skip_to_separator(content, next.0 + 1)?;
+        }
+        let (open, b'{') = next else {
+            return None;
+        };
+        // The next declaration, not this class, starts on a line beginning with a keyword or annotation
+        let ends_early = content[supertypes..open].lines().skip(1).any(|line| {
+            line.trim_start()
+                .starts_with(|c: char| c.is_ascii_lowercase() || c == '@')
+        });
+        (!ends_early).then_some(open + 1)
+    }
+
+    /// Skips whitespace and comments from `i`
+    fn skip_trivia(content: &str, mut i: usize) -> usize {
+        loop {
+            let rest = &content[i..];
+            let trimmed = rest.trim_start();
+            i += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                i += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                i += trimmed.find("*/").map_or(trimmed.len(), |end| end + 2);
+            } else {
+                return i;
             }
         }
+    }
 
-        // Extract properties
-        for cap in self.property_regex.captures_iter(&content) {
-            if let Some(name) = cap.get(1) {
-                symbols.push(KmpSymbol {
-                    name: name.as_str().to_string(),
-                    symbol_type: SymbolType::Property,
-                    module: module.to_string(),
-                    file_path: file_path.to_string_lossy().to_string(),
-                    is_public: true,
-                });
+    /// Scans from `i` to the first `,`, `;`, `{`, or `}` outside brackets, strings, and
+    /// comments, returning its offset and the character
+    fn skip_to_separator(content: &str, i: usize) -> Option<(usize, u8)> {
+        Self::scan(content, i, false)
+    }
+
+    /// Offset past the bracket closing the one at `open`
+    fn skip_group(content: &str, open: usize) -> usize {
+        Self::scan(content, open, true).map_or(content.len(), |(close, _)| close + 1)
+    }
+
+    /// Finds the first separator at bracket depth 0 from `start`, or with `group`, the
+    /// bracket closing the one at `start`
+    fn scan(content: &str, start: usize, group: bool) -> Option<(usize, u8)> {
+        let bytes = content.as_bytes();
+        let mut depth = 0usize;
+        let mut i = start;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'/' if matches!(bytes.get(i + 1), Some(b'/' | b'*')) => {
+                    i = Self::skip_trivia(content, i);
+                    continue;
+                }
+                b'"' => {
+                    i += 1;
+                    while i < bytes.len() && bytes[i] != b'"' {
+                        i += if bytes[i] == b'\\' { 2 } else { 1 };
+                    }
+                }
+                b'(' | b'[' => depth += 1,
+                b'{' if depth > 0 || group => depth += 1,
+                b')' | b']' | b'}' if depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 && group {
+                        return Some((i, bytes[i]));
+                    }
+                }
+                separator @ (b',' | b';' | b'{' | b'}') if depth == 0 => return Some((i, separator)),
+                _ => {}
             }
+            i += 1;
         }
+        None
+    }
 
-        // Extract type aliases
-        for cap in self.typealias_regex.captures_iter(&content) {
-            if let Some(name) = cap.get(1) {
-                symbols.push(KmpSymbol {
-                    name: name.as_str().to_string(),
-                    symbol_type: SymbolType::TypeAlias,
-                    module: module.to_string(),
-                    file_path: file_path.to_string_lossy().to_string(),
-                    is_public: true,
-                });
+    /// Visibility modifier a matched declaration states, after any annotations and context parameters
+    fn declared_visibility(declaration: &str) -> Option<Visibility> {
+        let mut declaration = declaration.trim_start();
+        while let Some(annotated) = declaration.strip_prefix('@') {
+            declaration = annotated.split_once(char::is_whitespace).map_or("", |(_, rest)| rest.trim_start());
+        }
+        let modifiers = declaration
+            .strip_prefix("context")
+            .and_then(|rest| rest.split_once(')'))
+            .map_or(declaration, |(_, rest)| rest.trim_start());
+        modifiers.split_whitespace().next().and_then(Visibility::from_modifier)
+    }
+
+    /// Names of the annotations a matched declaration starts with, such as `JvmStatic`
+    fn inline_annotations(declaration: &str) -> impl Iterator<Item = String> + '_ {
+        declaration
+            .split_whitespace()
+            .map_while(|word| word.strip_prefix('@'))
+            .map(str::to_string)
+    }
+
+    /// Indentation of a 1-based line
+    fn indent(lines: &[&str], line: usize) -> usize {
+        lines
+            .get(line - 1)
+            .map_or(0, |text| text.len() - text.trim_start().len())
+    }
+
+    /// 1-based line containing `offset`
+    fn line_of(content: &str, offset: usize) -> usize {
+        content[..offset].matches('\n').count() + 1
+    }
+
+    /// Ends each declaration on the line before the next declaration not nested in it
+    ///
+    /// A declaration indented deeper than the one before it is a member, so a class
+    /// spans its members while a member ends where the next member starts.
+    fn assign_spans(content: &str, symbols: &mut [KmpSymbol]) {
+        let lines: Vec<&str> = content.lines().collect();
+        let indent = |line: usize| Self::indent(&lines, line);
+        let mut starts: Vec<usize> = symbols.iter().map(|symbol| symbol.lines.0).collect();
+        starts.sort_unstable();
+        starts.dedup();
+
+        for symbol in symbols {
+            let start = symbol.lines.0;
+            let end = starts
+                .iter()
+                .find(|&&next| next > start && indent(next) <= indent(start))
+                .map_or(lines.len(), |next| next - 1);
+            symbol.lines.1 = end.max(start);
+        }
+    }
+
+    /// Narrows each declaration to the visibility of the declarations it is nested in,
+    /// so the members of a private or internal class are private or internal too
+    fn narrow_visibility(content: &str, symbols: &mut [KmpSymbol]) {
+        let lines: Vec<&str> = content.lines().collect();
+        let containers: Vec<(usize, (usize, usize), Visibility)> = symbols
+            .iter()
+            .map(|symbol| (Self::indent(&lines, symbol.lines.0), symbol.lines, symbol.visibility))
+            .collect();
+
+        for symbol in symbols {
+            let (indent, start) = (Self::indent(&lines, symbol.lines.0), symbol.lines.0);
+            for &(container_indent, (first, last), visibility) in &containers {
+                if container_indent < indent && first < start && start <= last {
+                    symbol.visibility = symbol.visibility.max(visibility);
+                }
+            }
+        }
+    }
+
+    /// Parses the KDoc and annotations written above the declaration containing `offset`
+    ///
+    /// Blank lines and annotations (including multi-line argument lists) between
+    /// the KDoc and the declaration are skipped.
+    fn declaration_header(content: &str, offset: usize) -> DeclarationHeader {
+        let line_start = content[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let mut rest = content[..line_start].trim_end();
+        let mut header = DeclarationHeader::default();
+
+        loop {
+            if rest.ends_with("*/") {
+                if let Some(open) = rest.rfind("/*") {
+                    header.documented = rest[open..].starts_with("/**");
+                }
+                break;
+            }
+
+            // Strip an annotation argument list such as `@Deprecated("...")`
+            let mut end = rest.len();
+            let mut arguments = "";
+            if rest.ends_with(')') {
+                match Self::matching_paren(rest) {
+                    Some(open) => {
+                        end = open;
+                        arguments = &rest[open + 1..rest.len() - 1];
+                    }
+                    None => break,
+                }
+            }
+
+            let head = rest[..end].trim_end();
+            let name_start = head
+                .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == ':'))
+                .map(|i| i + head[i..].chars().next().map_or(1, char::len_utf8))
+                .unwrap_or(0);
+            let name = &head[name_start..];
+            // Context parameters on a line of their own sit between annotations and the declaration
+            if name == "context" && end < rest.len() && !head[..name_start].ends_with('@') {
+                rest = head[..name_start].trim_end();
+                continue;
             }
+            if name.is_empty() || !head[..name_start].ends_with('@') {
+                break;
+            }
+
+            // Drop use-site targets such as `@get:JvmName`
+            let name = name.rsplit(':').next().unwrap_or(name);
+            if name.rsplit('.').next() == Some("JsName") {
+                // `@JsName("userName")` or `@JsName(name = "userName")`
+                let argument = arguments.split_once('=').map_or(arguments, |(_, value)| value);
+                header.js_name = Some(argument.trim().trim_matches('"').to_string()).filter(|n| !n.is_empty());
+            } else if name.rsplit('.').next() == Some("ObjCName") {
+                header.objc_name = ObjCName::parse(arguments);
+            }
+            header.annotations.push(name.to_string());
+            rest = head[..name_start - 1].trim_end();
         }
 
-        Ok(symbols)
+        header.annotations.reverse();
+        header
     }
 
-    /// Checks if the file contains private or internal markers
-    fn is_private_file(&self, content: &str) -> bool {
-        // Simple heuristic: if file starts with private/internal package
-        content.contains("internal ") || content.starts_with("private ")
+    /// Finds the byte offset of the `(` matching the trailing `)` of `text`
+    fn matching_paren(text: &str) -> Option<usize> {
+        let mut depth = 0usize;
+        for (i, c) in text.char_indices().rev() {
+            match c {
+                ')' => depth += 1,
+                '(' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
     }
 }
 
+/// Entry of an enum class body
+struct EnumEntry<'a> {
+    name: &'a str,
+    /// Offset of the name
+    offset: usize,
+    /// Offset of the first annotation written before the name, or of the name
+    start: usize,
+    /// Annotations written on the entry's own line
+    annotations: Vec<&'a str>,
+}
+
+/// KDoc and annotations found above a declaration
+#[derive(Debug, Default)]
+struct DeclarationHeader {
+    documented: bool,
+    annotations: Vec<String>,
+    /// Argument of `@JsName`
+    js_name: Option<String>,
+    /// Arguments of `@ObjCName`
+    objc_name: Option<ObjCName>,
+}
+
 impl Default for SymbolExtractor {
     fn default() -> Self {
         Self::new()
@@ -167,4 +628,295 @@ mod tests {
         assert_eq!(symbols[0].name, "getUserData");
         assert_eq!(symbols[0].symbol_type, SymbolType::Function);
     }
+
+    #[test]
+    fn test_extract_declared_visibility() {
+        let extractor = SymbolExtractor::new();
+        let content = "public class Session {\n    internal fun token() = \"\"\n    private fun refresh() {}\n}\nfun helper() {}\n";
+        let path = Path::new("Session.kt");
+
+        let declared: Vec<(String, Visibility, bool)> = extractor
+            .extract_symbols_from_source(content, path, "test")
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.visibility, symbol.visibility_declared))
+            .collect();
+        assert_eq!(
+            declared,
+            vec![
+                ("Session".to_string(), Visibility::Public, true),
+                ("token".to_string(), Visibility::Internal, true),
+                ("helper".to_string(), Visibility::Public, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_visibility() {
+        let extractor = SymbolExtractor::new();
+        let content = "internal class Cache {\n    fun get(key: String) = null\n}\n\nprivate object Secrets {\n    val token = \"\"\n}\n\nclass Session\n";
+
+        let symbols = extractor.extract_symbols_from_source(content, Path::new("Cache.kt"), "test");
+        let visibility: Vec<(&str, Visibility)> = symbols.iter().map(|s| (s.name.as_str(), s.visibility)).collect();
+        assert_eq!(
+            visibility,
+            vec![
+                ("Cache", Visibility::Internal),
+                ("Session", Visibility::Public),
+                ("get", Visibility::Internal),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_package() {
+        let extractor = SymbolExtractor::new();
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "package com.example.auth").unwrap();
+        writeln!(file, "class AuthRepository {{}}").unwrap();
+
+        let symbols = extractor.extract_symbols(file.path(), "test").unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].package, "com.example.auth");
+    }
+
+    #[test]
+    fn test_detect_kdoc() {
+        let extractor = SymbolExtractor::new();
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "/**\n * Loads users.\n */\n@Throws(Exception::class)\nclass UserLoader {{}}").unwrap();
+        writeln!(file, "/* plain comment */\nclass UserCache {{}}").unwrap();
+
+        let symbols = extractor.extract_symbols(file.path(), "test").unwrap();
+        let loader = symbols.iter().find(|s| s.name == "UserLoader").unwrap();
+        let cache = symbols.iter().find(|s| s.name == "UserCache").unwrap();
+        assert!(loader.is_documented);
+        assert!(!cache.is_documented);
+    }
+
+    #[test]
+    fn test_extract_annotations() {
+        let extractor = SymbolExtractor::new();
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "/** Old API. */").unwrap();
+        writeln!(file, "@Deprecated(").unwrap();
+        writeln!(file, "    \"Use fetchUser() instead\",").unwrap();
+        writeln!(file, "    ReplaceWith(\"fetchUser(id)\")").unwrap();
+        writeln!(file, ")").unwrap();
+        writeln!(file, "@kotlin.js.JsName(\"load\") @get:JvmName(\"x\") @ObjCName(swiftName = \"load\")").unwrap();
+        writeln!(file, "fun loadUser(id: String) {{}}").unwrap();
+
+        let symbols = extractor.extract_symbols(file.path(), "test").unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert!(symbols[0].is_documented);
+        assert_eq!(symbols[0].annotations, vec!["Deprecated", "kotlin.js.JsName", "JvmName", "ObjCName"]);
+        assert_eq!(symbols[0].js_name.as_deref(), Some("load"));
+        assert_eq!(symbols[0].objc_name.as_ref().and_then(|names| names.swift()), Some("load"));
+    }
+
+    #[test]
+    fn test_extract_actual_typealias() {
+        let extractor = SymbolExtractor::new();
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "actual typealias AtomicRef<T> = java.util.concurrent.atomic.AtomicReference<T>").unwrap();
+
+        let symbols = extractor.extract_symbols(file.path(), "test").unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name, "AtomicRef");
+        assert_eq!(symbols[0].symbol_type, SymbolType::TypeAlias);
+        assert_eq!(symbols[0].actual_target.as_deref(), Some("AtomicReference"));
+    }
+
+    #[test]
+    fn test_extract_extensions_and_object_members() {
+        let extractor = SymbolExtractor::new();
+        let source = r#"fun String.toSlug(): String = lowercase()
+suspend fun <T : Comparable<T>> List<T>?.maxOrNull(): T? = null
+val Map<String, List<Int>>.total: Int get() = size
+
+class Session {
+    override fun toString() = "Session"
+
+    companion object Factory {
+        const val MAX_RETRIES = 3
+        @JvmStatic fun create(): Session = Session()
+    }
+
+    object Keys {
+        @JvmField val token = "token"
+        fun all() = listOf(token)
+    }
+}
+"#;
+
+        let symbols = extractor.extract_symbols_from_source(source, Path::new("Session.kt"), "test");
+        let mut found: Vec<(&str, &SymbolType)> = symbols.iter().map(|s| (s.name.as_str(), &s.symbol_type)).collect();
+        found.sort_by_key(|(name, _)| *name);
+        assert_eq!(
+            found,
+            vec![
+                ("Factory", &SymbolType::Object),
+                ("Keys", &SymbolType::Object),
+                ("MAX_RETRIES", &SymbolType::Property),
+                ("Session", &SymbolType::Class),
+                ("all", &SymbolType::Function),
+                ("create", &SymbolType::Function),
+                ("maxOrNull", &SymbolType::Function),
+                ("toSlug", &SymbolType::Function),
+                ("token", &SymbolType::Property),
+                ("total", &SymbolType::Property),
+            ]
+        );
+        let create = symbols.iter().find(|s| s.name == "create").unwrap();
+        assert_eq!(create.annotations, vec!["JvmStatic"]);
+        assert!(!create.visibility_declared);
+    }
+
+    #[test]
+    fn test_extract_expect_actual() {
+        let extractor = SymbolExtractor::new();
+        let source = r#"expect class Platform()
+expect fun platformName(): String
+internal expect abstract class Clock
+actual val <T> List<T>.second: T get() = this[1]
+actual typealias Lock = java.util.concurrent.locks.ReentrantLock
+"#;
+
+        let symbols = extractor.extract_symbols_from_source(source, Path::new("Platform.kt"), "test");
+        let found: Vec<(&str, &SymbolType)> = symbols.iter().map(|s| (s.name.as_str(), &s.symbol_type)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Platform", &SymbolType::Expect),
+                ("platformName", &SymbolType::Expect),
+                ("Clock", &SymbolType::Expect),
+                ("second", &SymbolType::Actual),
+                ("Lock", &SymbolType::TypeAlias),
+            ]
+        );
+        assert_eq!(symbols[2].visibility, Visibility::Internal);
+    }
+
+    #[test]
+    fn test_extract_modern_declarations() {
+        let extractor = SymbolExtractor::new();
+        let source = r#"package com.example
+
+@JvmInline
+value class Email(val value: String)
+
+@JvmInline public value class UserId(val raw: Long)
+
+interface LoadState {
+    data object Loading : LoadState
+}
+
+@JsExport
+context(logger: Logger)
+fun logIn(email: Email) {}
+
+context(Scope) public fun refresh() {}
+
+val city: StateFlow<String>
+    field = MutableStateFlow("")
+"#;
+
+        let symbols = extractor.extract_symbols_from_source(source, Path::new("Modern.kt"), "test");
+        let find = |name: &str| symbols.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(symbols.len(), 7);
+        assert_eq!(find("Email").symbol_type, SymbolType::ValueClass);
+        assert_eq!(find("UserId").symbol_type, SymbolType::ValueClass);
+        assert!(find("UserId").visibility_declared);
+        assert_eq!(find("Loading").symbol_type, SymbolType::Object);
+        assert_eq!(find("logIn").symbol_type, SymbolType::Function);
+        assert_eq!(find("logIn").annotations, vec!["JsExport"]);
+        assert!(find("refresh").visibility_declared);
+        assert_eq!(find("city").symbol_type, SymbolType::Property);
+        assert_eq!(find("city").lines, (18, 19));
+    }
+
+    #[test]
+    fn test_extract_class_kinds_and_enum_entries() {
+        let extractor = SymbolExtractor::new();
+        let source = r#"package com.example
+
+@Serializable data class User(val id: String)
+
+sealed interface LoadState
+
+internal sealed class Result<out T> {
+    data object Empty : Result<Nothing>()
+}
+
+annotation class Experimental
+
+enum class Role { ADMIN, MEMBER }
+
+internal enum class Status(
+    val code: Int,
+) : Coded, Labeled {
+    /** Signed in */
+    ACTIVE(1),
+    @Deprecated("Use ACTIVE") LEGACY(code = listOf(2, 3).sum()) {
+        override fun label() = "legacy, ignored"
+    },
+    // Not yet signed in
+    PENDING(4),
+    ;
+
+    fun next(): Status = entries[(ordinal + 1) % entries.size]
+}
+
+enum class Empty
+
+class Holder {
+    val role = Role.ADMIN
+}
+"#;
+
+        let symbols = extractor.extract_symbols_from_source(source, Path::new("Kinds.kt"), "test");
+        let mut found: Vec<(&str, &SymbolType)> = symbols.iter().map(|s| (s.name.as_str(), &s.symbol_type)).collect();
+        found.sort_by_key(|(name, _)| *name);
+        assert_eq!(
+            found,
+            vec![
+                ("ACTIVE", &SymbolType::EnumEntry),
+                ("ADMIN", &SymbolType::EnumEntry),
+                ("Empty", &SymbolType::Enum),
+                ("Empty", &SymbolType::Object),
+                ("Experimental", &SymbolType::AnnotationClass),
+                ("Holder", &SymbolType::Class),
+                ("LEGACY", &SymbolType::EnumEntry),
+                ("LoadState", &SymbolType::SealedClass),
+                ("MEMBER", &SymbolType::EnumEntry),
+                ("PENDING", &SymbolType::EnumEntry),
+                ("Result", &SymbolType::SealedClass),
+                ("Role", &SymbolType::Enum),
+                ("Status", &SymbolType::Enum),
+                ("User", &SymbolType::DataClass),
+                ("code", &SymbolType::Property),
+                ("next", &SymbolType::Function),
+                ("role", &SymbolType::Property),
+            ]
+        );
+        let find = |name: &str| symbols.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(find("User").annotations, vec!["Serializable"]);
+        assert!(find("ACTIVE").is_documented);
+        assert_eq!(find("ACTIVE").visibility, Visibility::Internal);
+        assert_eq!(find("LEGACY").annotations, vec!["Deprecated"]);
+        let nested = symbols.iter().find(|s| s.symbol_type == SymbolType::Object).unwrap();
+        assert_eq!(nested.visibility, Visibility::Internal);
+    }
+
+    #[test]
+    fn test_declaration_spans() {
+        let extractor = SymbolExtractor::new();
+        let source = "package com.example\n\nclass UserRepository {\n    fun getUser(id: String): User? {\n        return null\n    }\n\n    fun saveUser(user: User) {}\n}\n\nfun formatUserName(user: User) = user.name\n";
+
+        let symbols = extractor.extract_symbols_from_source(source, Path::new("User.kt"), "test");
+        let lines = |name: &str| symbols.iter().find(|s| s.name == name).unwrap().lines;
+        assert_eq!(lines("UserRepository"), (3, 10));
+        assert_eq!(lines("getUser"), (4, 7));
+        assert_eq!(lines("saveUser"), (8, 10));
+        assert_eq!(lines("formatUserName"), (11, 11));
+    }
 }
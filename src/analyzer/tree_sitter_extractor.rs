@@ -0,0 +1,380 @@
+//! Symbol extraction from a tree-sitter Kotlin syntax tree
+//! Unlike the line-based regular expressions, the tree sees declarations whatever
+//! their modifiers (`suspend fun`, `data class`), layout (multi-line headers, generic
+//! parameters), and nesting, and knows the visibility of each declaration and its
+//! enclosing classes.
+
+use std::path::Path;
+use tree_sitter::{Language, Node, Parser};
+
+use super::models::{KmpSymbol, SymbolType, Visibility};
+use crate::domain::ObjCName;
+
+/// Extracts the public and internal declarations of Kotlin syntax trees
+pub struct TreeSitterExtractor {
+    language: Language,
+}
+
+/// Declaration-independent fields of the symbols of one file
+struct FileContext<'a> {
+    content: &'a str,
+    file_path: String,
+    module: &'a str,
+    package: String,
+}
+
+impl TreeSitterExtractor {
+    pub fn new() -> Self {
+        Self {
+            language: tree_sitter_kotlin_ng::LANGUAGE.into(),
+        }
+    }
+
+    /// Public and internal declarations of `content`, or `None` when the
+    /// source has syntax the grammar cannot parse
+    pub fn extract(&self, content: &str, file_path: &Path, module: &str) -> Option<Vec<KmpSymbol>> {
+        // Parsers hold per-parse state and are not `Sync`, so each file gets its own
+        let mut parser = Parser::new();
+        parser.set_language(&self.language).ok()?;
+        let tree = parser.parse(content, None)?;
+        let root = tree.root_node();
+        if root.has_error() {
+            return None;
+        }
+
+        let package = children(root)
+            .find(|node| node.kind() == "package_header")
+            .and_then(|header| children(header).find(|node| node.kind() == "qualified_identifier"))
+            .map(|name| text(name, content).to_string())
+            .unwrap_or_default();
+        let file = FileContext {
+            content,
+            file_path: file_path.to_string_lossy().to_string(),
+            module,
+            package,
+        };
+
+        let mut symbols = Vec::new();
+        Self::collect(root, Visibility::Public, &file, &mut symbols);
+        Some(symbols)
+    }
+
+    /// Adds the public and internal declarations among the children of a file or class body
+    /// whose own visibility is `outer`
+    fn collect(container: Node, outer: Visibility, file: &FileContext, symbols: &mut Vec<KmpSymbol>) {
+        for node in children(container) {
+            let modifiers = Modifiers::of(node, file.content);
+            let visibility = modifiers.visibility().unwrap_or(Visibility::Public).max(outer);
+            // Private and protected declarations hide their members too;
+            // overrides belong to the supertype's API
+            if !visibility.is_module_visible() || modifiers.has("override") {
+                continue;
+            }
+
+            let declaration = match node.kind() {
+                "class_declaration" => {
+                    let symbol_type = if modifiers.has("sealed") {
+                        SymbolType::SealedClass
+                    } else if children_all(node).any(|child| child.kind() == "interface") {
+                        SymbolType::Interface
+                    } else if modifiers.has("enum") {
+                        SymbolType::Enum
+                    } else if modifiers.has("data") {
+                        SymbolType::DataClass
+                    } else if modifiers.has("annotation") {
+                        SymbolType::AnnotationClass
+                    } else if modifiers.has("value") || modifiers.has("inline") {
+                        SymbolType::ValueClass
+                    } else {
+                        SymbolType::Class
+                    };
+                    node.child_by_field_name("name").map(|name| (name, symbol_type))
+                }
+                // Named companion objects only; an unnamed one shares its class's name
+                "object_declaration" | "companion_object" => {
+                    node.child_by_field_name("name").map(|name| (name, SymbolType::Object))
+                }
+                "enum_entry" => children(node)
+                    .find(|child| child.kind() == "identifier")
+                    .map(|name| (name, SymbolType::EnumEntry)),
+                "function_declaration" => node.child_by_field_name("name").map(|name| (name, SymbolType::Function)),
+                "property_declaration" => children(node)
+                    .find(|child| child.kind() == "variable_declaration")
+                    .and_then(|variable| children(variable).find(|child| child.kind() == "identifier"))
+                    .map(|name| (name, SymbolType::Property)),
+                // `typealias Name = ...` names the alias in the `type` field
+                "type_alias" => node.child_by_field_name("type").map(|name| (name, SymbolType::TypeAlias)),
+                _ => None,
+            };
+
+            // `expect`/`actual` declarations are matched across source sets by name, whatever their kind
+            let declaration = declaration.map(|(name, symbol_type)| match symbol_type {
+                _ if modifiers.has("expect") => (name, SymbolType::Expect),
+                SymbolType::TypeAlias => (name, symbol_type),
+                _ if modifiers.has("actual") => (name, SymbolType::Actual),
+                _ => (name, symbol_type),
+            });
+            if let Some((name, symbol_type)) = declaration {
+                let actual_target = if symbol_type == SymbolType::TypeAlias && modifiers.has("actual") {
+                    Self::alias_target(node, file.content)
+                } else {
+                    None
+                };
+                symbols.push(KmpSymbol {
+                    name: text(name, file.content).to_string(),
+                    symbol_type,
+                    module: file.module.to_string(),
+                    package: file.package.clone(),
+                    file_path: file.file_path.clone(),
+                    visibility,
+                    visibility_declared: modifiers.visibility().is_some(),
+                    is_documented: Self::documented(node, file.content),
+                    annotations: modifiers.annotations,
+                    actual_target,
+                    js_name: modifiers.js_name,
+                    objc_name: modifiers.objc_name,
+                    lines: (name.start_position().row + 1, node.end_position().row + 1),
+                });
+            }
+
+            if node.kind() == "class_declaration" {
+                Self::constructor_properties(node, visibility, file, symbols);
+            }
+            // Members of classes, objects, and companion objects; not local declarations in function bodies
+            if matches!(node.kind(), "class_declaration" | "object_declaration" | "companion_object") {
+                for body in children(node).filter(|child| matches!(child.kind(), "class_body" | "enum_class_body")) {
+                    Self::collect(body, visibility, file, symbols);
+                }
+            }
+        }
+    }
+
+    /// Properties declared with `val`/`var` in a primary constructor
+    fn constructor_properties(class: Node, outer: Visibility, file: &FileContext, symbols: &mut Vec<KmpSymbol>) {
+        let parameters = children(class)
+            .filter(|child| child.kind() == "primary_constructor")
+            .flat_map(children)
+            .filter(|child| child.kind() == "class_parameters")
+            .flat_map(children)
+            .filter(|child| child.kind() == "class_parameter");
+        for parameter in parameters {
+            let modifiers = Modifiers::of(parameter, file.content);
+            let visibility = modifiers.visibility().unwrap_or(Visibility::Public).max(outer);
+            let is_property = children_all(parameter).any(|child| matches!(child.kind(), "val" | "var"));
+            if !is_property || !visibility.is_module_visible() || modifiers.has("override") {
+                continue;
+            }
+            let Some(name) = children(parameter).find(|child| child.kind() == "identifier") else {
+                continue;
+            };
+            let line = name.start_position().row + 1;
+            symbols.push(KmpSymbol {
+                name: text(name, file.content).to_string(),
+                symbol_type: SymbolType::Property,
+                module: file.module.to_string(),
+                package: file.package.clone(),
+                file_path: file.file_path.clone(),
+                visibility,
+                visibility_declared: modifiers.visibility().is_some(),
+                is_documented: false,
+                annotations: modifiers.annotations,
+                actual_target: None,
+                js_name: modifiers.js_name,
+                objc_name: modifiers.objc_name,
+                lines: (line, line),
+            });
+        }
+    }
+
+    /// Simple name of the type an alias points to: `AtomicReference` of `java.util.concurrent.atomic.AtomicReference<T>`
+    fn alias_target(alias: Node, content: &str) -> Option<String> {
+        let target = children(alias).find(|child| child.kind() == "user_type")?;
+        children(target)
+            .filter(|child| child.kind() == "identifier")
+            .last()
+            .map(|name| text(name, content).to_string())
+    }
+
+    /// Whether a KDoc comment directly precedes the declaration
+    fn documented(declaration: Node, content: &str) -> bool {
+        declaration
+            .prev_named_sibling()
+            .is_some_and(|comment| comment.kind() == "block_comment" && text(comment, content).starts_with("/**"))
+    }
+}
+
+impl Default for TreeSitterExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Modifier keywords and annotations of a declaration
+#[derive(Default)]
+struct Modifiers<'a> {
+    keywords: Vec<&'a str>,
+    annotations: Vec<String>,
+    /// Argument of `@JsName`
+    js_name: Option<String>,
+    /// Arguments of `@ObjCName`
+    objc_name: Option<ObjCName>,
+}
+
+impl<'a> Modifiers<'a> {
+    fn of(declaration: Node, content: &'a str) -> Self {
+        let mut modifiers = Self::default();
+        for node in children(declaration).filter(|child| child.kind() == "modifiers").flat_map(children) {
+            if node.kind() != "annotation" {
+                modifiers.keywords.push(text(node, content));
+                continue;
+            }
+            // `@Name`, `@Name(arguments)`, or `@get:Name(arguments)`
+            let invocation = children(node).find(|child| child.kind() == "constructor_invocation");
+            let Some(name) = children(invocation.unwrap_or(node)).find(|child| child.kind() == "user_type") else {
+                continue;
+            };
+            let name = text(name, content).to_string();
+            if name.rsplit('.').next() == Some("JsName") {
+                modifiers.js_name = invocation
+                    .into_iter()
+                    .flat_map(children)
+                    .filter(|child| child.kind() == "value_arguments")
+                    .flat_map(children)
+                    .find_map(|argument| children(argument).find(|child| child.kind() == "string_literal"))
+                    .map(|literal| text(literal, content).trim_matches('"').to_string())
+                    .filter(|name| !name.is_empty());
+            } else if name.rsplit('.').next() == Some("ObjCName") {
+                modifiers.objc_name = invocation
+                    .into_iter()
+                    .flat_map(children)
+                    .find(|child| child.kind() == "value_arguments")
+                    .and_then(|arguments| {
+                        let arguments = text(arguments, content);
+                        ObjCName::parse(arguments.strip_prefix('(')?.strip_suffix(')')?)
+                    });
+            }
+            modifiers.annotations.push(name);
+        }
+        modifiers
+    }
+
+    fn has(&self, keyword: &str) -> bool {
+        self.keywords.contains(&keyword)
+    }
+
+    /// The visibility modifier, if one is written
+    fn visibility(&self) -> Option<Visibility> {
+        self.keywords.iter().find_map(|keyword| Visibility::from_modifier(keyword))
+    }
+}
+
+/// Named children of a node
+fn children(node: Node) -> impl Iterator<Item = Node> {
+    (0..node.named_child_count()).filter_map(move |i| node.named_child(i))
+}
+
+/// All children of a node, including keywords and punctuation
+fn children_all(node: Node) -> impl Iterator<Item = Node> {
+    (0..node.child_count()).filter_map(move |i| node.child(i))
+}
+
+fn text<'a>(node: Node, content: &'a str) -> &'a str {
+    &content[node.byte_range()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_from_syntax_tree() {
+        let source = r#"package com.example.user
+
+/** A user. */
+@Deprecated(
+    "Use Account",
+    ReplaceWith("Account")
+)
+@kotlin.js.JsName("AppUser") @get:JvmName("x")
+public data class User<T : Any>(
+    val id: String,
+    private val secret: String,
+    name: String,
+) : Entity {
+    override fun toString() = id
+
+    suspend fun <R> load(
+        mapper: (T) -> R,
+    ): R? = null
+
+    fun String.masked() = "*"
+
+    internal fun cache() {}
+
+    enum class Role { ADMIN, MEMBER }
+
+    companion object Factory {
+        const val TABLE = "users"
+    }
+}
+
+@ObjCName("KLoadState", exact = true) sealed interface LoadState
+
+internal class Hidden {
+    fun invisible() {}
+}
+
+fun helper() {
+    fun local() {}
+}
+
+actual typealias AtomicRef<T> = java.util.concurrent.atomic.AtomicReference<T>
+"#;
+        let symbols = TreeSitterExtractor::new()
+            .extract(source, Path::new("User.kt"), "shared")
+            .unwrap();
+
+        let found: Vec<(&str, SymbolType)> = symbols.iter().map(|s| (s.name.as_str(), s.symbol_type.clone())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("User", SymbolType::DataClass),
+                ("id", SymbolType::Property),
+                ("load", SymbolType::Function),
+                ("masked", SymbolType::Function),
+                ("cache", SymbolType::Function),
+                ("Role", SymbolType::Enum),
+                ("ADMIN", SymbolType::EnumEntry),
+                ("MEMBER", SymbolType::EnumEntry),
+                ("Factory", SymbolType::Object),
+                ("TABLE", SymbolType::Property),
+                ("LoadState", SymbolType::SealedClass),
+                ("Hidden", SymbolType::Class),
+                ("invisible", SymbolType::Function),
+                ("helper", SymbolType::Function),
+                ("AtomicRef", SymbolType::TypeAlias),
+            ]
+        );
+
+        let user = &symbols[0];
+        assert_eq!(user.package, "com.example.user");
+        assert!(user.is_documented);
+        assert!(user.visibility_declared);
+        assert_eq!(user.annotations, vec!["Deprecated", "kotlin.js.JsName", "JvmName"]);
+        assert_eq!(user.js_name.as_deref(), Some("AppUser"));
+        let load_state = symbols.iter().find(|s| s.name == "LoadState").and_then(|s| s.objc_name.as_ref()).unwrap();
+        assert_eq!((load_state.name.as_deref(), load_state.exact), (Some("KLoadState"), true));
+        assert_eq!(user.lines, (9, 29));
+        assert_eq!(symbols[2].lines, (16, 18));
+        assert_eq!(symbols[4].visibility, Visibility::Internal);
+        assert_eq!(symbols[12].visibility, Visibility::Internal);
+        assert!(!symbols[12].visibility_declared);
+        assert_eq!(symbols[14].actual_target.as_deref(), Some("AtomicReference"));
+    }
+
+    #[test]
+    fn test_unparsable_source() {
+        let extractor = TreeSitterExtractor::new();
+        assert!(extractor.extract("class User {", Path::new("User.kt"), "shared").is_none());
+    }
+}
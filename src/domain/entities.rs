@@ -1,39 +1,456 @@
-use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 
 /// Core domain entity: KMP Symbol
+///
+/// Serialized with its stable [`id`](Symbol::id) alongside the fields.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(into = "IdentifiedSymbol")]
 pub struct Symbol {
     pub name: String,
     pub symbol_type: SymbolType,
     pub module: String,
+    /// Kotlin package the symbol is declared in (empty for the root package)
+    #[serde(default)]
+    pub package: String,
     pub file_path: String,
     pub is_public: bool,
+    /// Whether the declaration carries a KDoc comment
+    #[serde(default)]
+    pub is_documented: bool,
+    /// Annotations declared on the symbol, as written (e.g. `Deprecated`)
+    #[serde(default)]
+    pub annotations: Vec<String>,
+    /// Platform type an `actual typealias` resolves to (simple name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual_target: Option<String>,
+    /// Name given with `@JsName`, under which Kotlin/JS exports the symbol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub js_name: Option<String>,
+    /// Names given with `@ObjCName`, under which Kotlin/Native exports the symbol to Apple code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objc_name: Option<ObjCName>,
+    /// Lines the declaration spans, members included; unknown for resources, generated
+    /// types, and symbols read from a manifest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lines: Option<LineRange>,
+}
+
+impl Symbol {
+    /// Public, undocumented symbol in the root package, without annotations or line range
+    pub fn new(
+        name: impl Into<String>,
+        symbol_type: SymbolType,
+        module: impl Into<String>,
+        file_path: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            symbol_type,
+            module: module.into(),
+            package: String::new(),
+            file_path: file_path.into(),
+            is_public: true,
+            is_documented: false,
+            annotations: Vec::new(),
+            actual_target: None,
+            js_name: None,
+            objc_name: None,
+            lines: None,
+        }
+    }
+
+    /// Checks whether the symbol carries the given annotation (simple or qualified name)
+    pub fn has_annotation(&self, name: &str) -> bool {
+        self.annotations
+            .iter()
+            .any(|a| a == name || a.rsplit('.').next() == Some(name))
+    }
+
+    /// Whether the symbol is marked `@Deprecated`
+    pub fn is_deprecated(&self) -> bool {
+        self.has_annotation("Deprecated")
+    }
+
+    /// Whether the symbol is declared in a test source set rather than shared production code
+    pub fn is_test_only(&self) -> bool {
+        is_test_source_path(&self.file_path)
+    }
+
+    /// Whether a platform's build compiles the source set declaring the symbol
+    ///
+    /// `commonMain` and unrecognized source sets reach every platform, `jvmMain` the server
+    /// and desktop builds (and Android too when shared with it, as `jvmAndroidMain`),
+    /// `desktopMain` the desktop build, Apple and native source sets iOS, and JS and Wasm
+    /// source sets the web.
+    pub fn exported_to(&self, platform: &Platform) -> bool {
+        let source_set = Path::new(&self.file_path).components().find_map(|c| {
+            let name = c.as_os_str().to_str()?;
+            name.strip_suffix("Main").filter(|prefix| !prefix.is_empty())
+        });
+        let Some(source_set) = source_set else {
+            return true;
+        };
+        let source_set = source_set.to_lowercase();
+        let targets_ios = ["ios", "apple", "native", "darwin"].iter().any(|p| source_set.starts_with(p));
+        if source_set.contains("jvm") && source_set.contains("android") {
+            matches!(platform, Platform::Android | Platform::Server | Platform::Desktop)
+        } else if source_set.starts_with("android") {
+            *platform == Platform::Android
+        } else if source_set.starts_with("jvm") {
+            matches!(platform, Platform::Server | Platform::Desktop)
+        } else if source_set.starts_with("desktop") {
+            *platform == Platform::Desktop
+        } else if targets_ios {
+            *platform == Platform::IOS
+        } else if ["js", "wasm", "web"].iter().any(|p| source_set.starts_with(p)) {
+            *platform == Platform::Web
+        } else {
+            true
+        }
+    }
+
+    /// Identity that survives edits, file renames, and moves within a module
+    /// (`shared:com.example.UserRepository:interface`)
+    pub fn id(&self) -> String {
+        symbol_id(&self.module, &self.package, &self.name, &self.symbol_type)
+    }
+}
+
+/// Arguments of `@ObjCName`, which renames a declaration in the Objective-C header
+/// Kotlin/Native generates and in Swift
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ObjCName {
+    /// Objective-C name, which classes and protocols carry with the framework prefix unless `exact`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Swift name, when it differs from the Objective-C one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub swift_name: Option<String>,
+    /// Whether the Objective-C name is used as is, without the framework prefix
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exact: bool,
+    /// Whether both names were read from the generated framework header, so they are
+    /// exactly the names Swift and Objective-C see rather than guesses
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub from_header: bool,
+}
+
+impl ObjCName {
+    /// Parses the argument list of `@ObjCName("User", swiftName = "AppUser", exact = true)`,
+    /// without its parentheses; `None` when it names nothing
+    pub fn parse(arguments: &str) -> Option<Self> {
+        let mut objc_name = Self::default();
+        for (position, argument) in arguments.split(',').enumerate() {
+            let (key, value) = match argument.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => (["name", "swiftName", "exact"].get(position).copied().unwrap_or_default(), argument.trim()),
+            };
+            let text = Some(value.trim_matches('"').to_string()).filter(|text| !text.is_empty());
+            match key {
+                "name" => objc_name.name = text,
+                "swiftName" => objc_name.swift_name = text,
+                "exact" => objc_name.exact = value == "true",
+                _ => {}
+            }
+        }
+        (objc_name.name.is_some() || objc_name.swift_name.is_some()).then_some(objc_name)
+    }
+
+    /// Name Swift code uses: the Swift name, else the Objective-C one
+    pub fn swift(&self) -> Option<&str> {
+        self.swift_name.as_deref().or(self.name.as_deref())
+    }
+}
+
+/// Class or protocol declared by the Objective-C header Kotlin/Native generates for a framework
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedType {
+    /// Objective-C name, with the framework prefix (`SharedUser`)
+    pub objc_name: String,
+    /// Name given by `swift_name` (`User`, or `User_` when it clashed)
+    pub swift_name: String,
+}
+
+/// Where framework headers to read exported names from are looked for
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FrameworkHeaderSources {
+    /// Header paths, relative to the project root
+    pub paths: Vec<String>,
+    /// Also look for the headers the Kotlin/Native Gradle tasks wrote to the KMP modules' `build/`
+    pub find_in_build_output: bool,
+}
+
+impl FrameworkHeaderSources {
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && !self.find_in_build_output
+    }
+}
+
+/// Generated Objective-C header of a Kotlin/Native framework (`Shared.framework/Headers/Shared.h`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameworkHeader {
+    pub path: String,
+    /// Prefix of the framework's Objective-C names (`Shared`)
+    pub prefix: String,
+    /// Declared types in header order
+    pub types: Vec<ExportedType>,
+}
+
+impl FrameworkHeader {
+    /// Gives the shared classifiers the header declares their exported names, returning how many it named
+    ///
+    /// The header does not record Kotlin names, so a declared type is taken for a symbol when
+    /// its Swift or Objective-C name is the one the symbol would export, up to the `_` suffixes
+    /// added on clashes. Each declared type names one symbol, the first asking for it, with
+    /// unsuffixed names preferred.
+    pub fn export_names(&self, symbols: &mut [Symbol]) -> usize {
+        let mut claimed = vec![false; self.types.len()];
+        let mut named = 0;
+        for symbol in symbols.iter_mut() {
+            if !symbol.symbol_type.is_classifier() || symbol.name.contains('.') {
+                continue;
+            }
+            let source = symbol.objc_name.as_ref().filter(|names| !names.from_header);
+            let swift = source.and_then(|names| names.swift()).unwrap_or(&symbol.name);
+            let objc_base = source.and_then(|names| names.name.as_deref()).unwrap_or(&symbol.name);
+            let objc = if source.is_some_and(|names| names.exact) {
+                objc_base.to_string()
+            } else {
+                format!("{}{}", self.prefix, objc_base)
+            };
+
+            let declared = self
+                .types
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !claimed[*index])
+                .filter(|(_, declared)| {
+                    declared.swift_name.trim_end_matches('_') == swift
+                        || declared.objc_name.trim_end_matches('_') == objc
+                })
+                .min_by_key(|(_, declared)| declared.swift_name.len() + declared.objc_name.len());
+            if let Some((index, declared)) = declared {
+                claimed[index] = true;
+                named += 1;
+                symbol.objc_name = Some(ObjCName {
+                    name: Some(declared.objc_name.clone()),
+                    swift_name: Some(declared.swift_name.clone()),
+                    exact: true,
+                    from_header: true,
+                });
+            }
+        }
+        named
+    }
+}
+
+/// Stable symbol ID: module, fully qualified name, and kind, without the declaring file
+pub fn symbol_id(module: &str, package: &str, name: &str, symbol_type: &SymbolType) -> String {
+    if package.is_empty() {
+        format!("{}:{}:{}", module, name, symbol_type.id_tag())
+    } else {
+        format!("{}:{}.{}:{}", module, package, name, symbol_type.id_tag())
+    }
+}
+
+/// Serialized form of a [`Symbol`], which leads with its stable ID
+#[derive(Serialize)]
+struct IdentifiedSymbol {
+    id: String,
+    name: String,
+    symbol_type: SymbolType,
+    module: String,
+    package: String,
+    file_path: String,
+    is_public: bool,
+    is_documented: bool,
+    annotations: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actual_target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    js_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    objc_name: Option<ObjCName>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lines: Option<LineRange>,
+}
+
+impl From<Symbol> for IdentifiedSymbol {
+    fn from(symbol: Symbol) -> Self {
+        Self {
+            id: symbol.id(),
+            name: symbol.name,
+            symbol_type: symbol.symbol_type,
+            module: symbol.module,
+            package: symbol.package,
+            file_path: symbol.file_path,
+            is_public: symbol.is_public,
+            is_documented: symbol.is_documented,
+            annotations: symbol.annotations,
+            actual_target: symbol.actual_target,
+            js_name: symbol.js_name,
+            objc_name: symbol.objc_name,
+            lines: symbol.lines,
+        }
+    }
+}
+
+/// Whether a source set holds tests (`commonTest`, `androidUnitTest`, `iosX64Test`, `test`)
+pub fn is_test_source_set(name: &str) -> bool {
+    name == "test" || name.ends_with("Test")
+}
+
+/// Whether a file lies in a test source set, judged by its directories
+pub fn is_test_source_path(path: &str) -> bool {
+    Path::new(path)
+        .parent()
+        .is_some_and(|dir| {
+            dir.components()
+                .any(|c| c.as_os_str().to_str().is_some_and(is_test_source_set))
+        })
+}
+
+/// Module and source set of a file in a Gradle source tree: `("shared", "iosMain")` for
+/// `shared/src/iosMain/kotlin/Platform.kt`
+pub fn module_source_set(path: &str) -> Option<(&str, &str)> {
+    let components: Vec<&str> = Path::new(path).components().filter_map(|c| c.as_os_str().to_str()).collect();
+    let src = components.iter().position(|c| *c == "src").filter(|&src| src > 0)?;
+    Some((components[src - 1], components.get(src + 1)?))
+}
+
+/// Intermediate source sets of the default hierarchy template, by the target family
+/// prefix of the source sets depending on them
+const SOURCE_SET_PARENTS: &[(&str, &[&str])] = &[
+    ("ios", &["iosMain", "appleMain", "nativeMain"]),
+    ("macos", &["macosMain", "appleMain", "nativeMain"]),
+    ("tvos", &["tvosMain", "appleMain", "nativeMain"]),
+    ("watchos", &["watchosMain", "appleMain", "nativeMain"]),
+    ("apple", &["appleMain", "nativeMain"]),
+    ("linux", &["linuxMain", "nativeMain"]),
+    ("mingw", &["mingwMain", "nativeMain"]),
+    ("androidNative", &["androidNativeMain", "nativeMain"]),
+    ("js", &["webMain"]),
+    ("wasmJs", &["webMain"]),
+];
+
+/// Whether `source_set` sees the declarations of `other`: it is `other`, `commonMain`,
+/// or an intermediate source set above it in the default hierarchy (`iosMain` for `iosArm64Main`)
+pub fn source_set_depends_on(source_set: &str, other: &str) -> bool {
+    source_set == other
+        || other == "commonMain"
+        || SOURCE_SET_PARENTS.iter().any(|(family, parents)| {
+            source_set
+                .strip_prefix(family)
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+                && parents.contains(&other)
+        })
 }
 
 /// Symbol type enumeration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum SymbolType {
     Class,
+    /// `value class` (or legacy `inline class`) wrapping a single value
+    ValueClass,
+    /// `enum class`
+    Enum,
+    /// Entry of an `enum class`, referenced as `Status.ACTIVE`
+    EnumEntry,
+    /// `sealed class` or `sealed interface`
+    SealedClass,
+    /// `data class`
+    DataClass,
+    /// `annotation class`
+    AnnotationClass,
     Interface,
     Object,
     Function,
     Property,
     TypeAlias,
+    /// Accessor generated for a shared resource (e.g. `MR.strings.title`)
+    Resource,
+    /// `expect` declaration of any kind, which platform source sets implement
+    Expect,
+    /// `actual` declaration implementing an `expect` one; `actual typealias` stays a
+    /// [`TypeAlias`](Self::TypeAlias) with its target
+    Actual,
+}
+
+impl SymbolType {
+    /// Whether the symbol is a class, interface, or object, which Kotlin/Native exports as an
+    /// Objective-C class or protocol of its own
+    pub fn is_classifier(&self) -> bool {
+        matches!(
+            self,
+            Self::Class
+                | Self::ValueClass
+                | Self::Enum
+                | Self::SealedClass
+                | Self::DataClass
+                | Self::AnnotationClass
+                | Self::Interface
+                | Self::Object
+                | Self::Expect
+                | Self::Actual
+        )
+    }
+
+    /// Lower-case kind used in stable symbol IDs
+    pub fn id_tag(&self) -> &'static str {
+        match self {
+            Self::Class => "class",
+            Self::ValueClass => "valueclass",
+            Self::Enum => "enum",
+            Self::EnumEntry => "enumentry",
+            Self::SealedClass => "sealedclass",
+            Self::DataClass => "dataclass",
+            Self::AnnotationClass => "annotationclass",
+            Self::Interface => "interface",
+            Self::Object => "object",
+            Self::Function => "function",
+            Self::Property => "property",
+            Self::TypeAlias => "typealias",
+            Self::Resource => "resource",
+            Self::Expect => "expect",
+            Self::Actual => "actual",
+        }
+    }
 }
 
 /// Platform enumeration
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum Platform {
     Android,
     IOS,
+    /// JVM backend (Ktor, Spring) sharing code with the apps
+    Server,
+    /// JVM desktop apps (Compose Desktop) built from `desktopMain`/`jvmMain`
+    Desktop,
+    /// Kotlin/JS and Wasm apps, and the TypeScript/JavaScript apps consuming their exports
+    Web,
 }
 
 impl Platform {
+    /// Every platform, in report order
+    pub const ALL: [Platform; 5] = [
+        Platform::Android,
+        Platform::IOS,
+        Platform::Server,
+        Platform::Desktop,
+        Platform::Web,
+    ];
+
     pub fn name(&self) -> &str {
         match self {
             Platform::Android => "Android",
             Platform::IOS => "iOS",
+            Platform::Server => "Server",
+            Platform::Desktop => "Desktop",
+            Platform::Web => "Web",
         }
     }
 }
@@ -55,6 +472,8 @@ pub enum Language {
     Java,
     Swift,
     ObjectiveC,
+    TypeScript,
+    JavaScript,
 }
 
 /// Symbol usage in a specific location
@@ -64,57 +483,3002 @@ pub struct SymbolUsage {
     pub file_path: String,
     pub line_number: usize,
     pub context: String,
+    #[serde(default)]
+    pub kind: UsageKind,
+}
+
+/// How a usage site uses a shared symbol
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageKind {
+    /// `UserRepositoryImpl()`, `new User(...)`
+    Instantiation,
+    /// `val repo: UserRepository`, `List<User>`, `user as? User`
+    TypeAnnotation,
+    /// `class Cache : UserRepository`, `struct Row: Identifiable, UserView`
+    Inheritance,
+    /// `Logger.log(...)`, `User.Companion`, `Platform.shared`
+    StaticAccess,
+    /// `import com.example.User`
+    ImportOnly,
+    /// Any other reference, such as calling a shared function or reading a property
+    #[default]
+    Reference,
+}
+
+impl UsageKind {
+    pub const ALL: [UsageKind; 6] = [
+        Self::Instantiation,
+        Self::TypeAnnotation,
+        Self::Inheritance,
+        Self::StaticAccess,
+        Self::ImportOnly,
+        Self::Reference,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Instantiation => "instantiation",
+            Self::TypeAnnotation => "type annotation",
+            Self::Inheritance => "inheritance",
+            Self::StaticAccess => "static access",
+            Self::ImportOnly => "import only",
+            Self::Reference => "reference",
+        }
+    }
+
+    /// Classifies the first whole-word occurrence of `symbol_name` on a source line
+    ///
+    /// Only type-like (capitalized) names are instantiated, annotated, inherited,
+    /// or accessed statically; other names are plain references.
+    pub fn classify(line: &str, symbol_name: &str) -> Self {
+        let line = line.trim();
+        if ["import ", "#import", "@import"].iter().any(|p| line.starts_with(p)) {
+            return Self::ImportOnly;
+        }
+        let is_type = symbol_name.starts_with(|c: char| c.is_uppercase());
+        let Some(at) = find_word(line, symbol_name) else {
+            return Self::Reference;
+        };
+        if !is_type {
+            return Self::Reference;
+        }
+
+        let before = line[..at].trim_end();
+        let after = line[at + symbol_name.len()..].trim_start();
+        let declares_type = ["class ", "interface ", "object ", "struct ", "extension ", "enum ", "protocol "]
+            .iter()
+            .any(|keyword| before.starts_with(keyword) || before.contains(&format!(" {}", keyword)));
+        if declares_type
+            && (before.contains(':') || ["extends", "implements"].iter().any(|k| before.contains(k)))
+            && !before.contains('(')
+        {
+            return Self::Inheritance;
+        }
+        if after.starts_with('(') || before.ends_with("new") {
+            return Self::Instantiation;
+        }
+        if after.starts_with('.') || after.starts_with("::") {
+            return Self::StaticAccess;
+        }
+        let annotates = [":", "<", ",", "->", "as", "as?", "as!", "is"]
+            .iter()
+            .any(|token| before.ends_with(token) && !before.ends_with(&format!("{}{}", token, token)));
+        if annotates || after.starts_with('>') || after.starts_with('?') || after.starts_with('[') {
+            return Self::TypeAnnotation;
+        }
+        Self::Reference
+    }
+}
+
+/// Byte offset of the first occurrence of `word` not inside a longer identifier
+pub(crate) fn find_word(line: &str, word: &str) -> Option<usize> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(word).map(|(at, _)| at).find(|&at| {
+        !line[..at].ends_with(is_ident) && !line[at + word.len()..].starts_with(is_ident)
+    })
+}
+
+/// Usage sites of one call-site kind
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UsageKindCount {
+    pub kind: UsageKind,
+    pub usages: usize,
+    /// Shared symbols used this way at least once
+    pub symbols: usize,
+    pub files: usize,
+}
+
+impl UsageKindCount {
+    /// Counts per kind, in [`UsageKind::ALL`] order, leaving out kinds that never occur
+    pub fn tally<'u>(usages: impl IntoIterator<Item = &'u SymbolUsage>) -> Vec<Self> {
+        let mut tallies: BTreeMap<UsageKind, (usize, HashSet<&str>, HashSet<&str>)> = BTreeMap::new();
+        for usage in usages {
+            let (count, symbols, files) = tallies.entry(usage.kind).or_default();
+            *count += 1;
+            symbols.insert(usage.symbol_name.as_str());
+            files.insert(usage.file_path.as_str());
+        }
+        tallies
+            .into_iter()
+            .map(|(kind, (usages, symbols, files))| Self {
+                kind,
+                usages,
+                symbols: symbols.len(),
+                files: files.len(),
+            })
+            .collect()
+    }
+}
+
+/// Imports of shared symbols whose file has no other usage of the symbol, by file and line
+pub fn unused_imports(
+    imports: &HashMap<String, Vec<SymbolUsage>>,
+    symbol_usages: &HashMap<String, Vec<SymbolUsage>>,
+) -> Vec<SymbolUsage> {
+    let mut unused: Vec<SymbolUsage> = imports
+        .iter()
+        .flat_map(|(name, imports)| {
+            let used_in: HashSet<&str> = symbol_usages
+                .get(name)
+                .into_iter()
+                .flatten()
+                .map(|u| u.file_path.as_str())
+                .collect();
+            imports
+                .iter()
+                .filter(move |import| !used_in.contains(import.file_path.as_str()))
+                .cloned()
+        })
+        .collect();
+    unused.sort_by(|a, b| (&a.file_path, a.line_number).cmp(&(&b.file_path, b.line_number)));
+    unused
+}
+
+/// An app file that imports shared code but references none of it, a candidate for cleanup
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StaleIntegration {
+    pub file_path: String,
+    pub platform: String,
+    /// Shared symbols the file imports by name; empty when it imports the whole framework,
+    /// as Swift's `import Shared` does
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<String>,
+}
+
+/// App files importing the shared framework or shared symbols without any usage of a
+/// shared symbol, in path order
+pub fn stale_integrations(
+    framework_import_files: &HashSet<String>,
+    imports: &HashMap<String, Vec<SymbolUsage>>,
+    symbol_usages: &HashMap<String, Vec<SymbolUsage>>,
+    file_platforms: &HashMap<&str, &Platform>,
+) -> Vec<StaleIntegration> {
+    let used: HashSet<&str> = symbol_usages.values().flatten().map(|u| u.file_path.as_str()).collect();
+    let mut stale: BTreeMap<&str, BTreeSet<&str>> = framework_import_files
+        .iter()
+        .map(|file| (file.as_str(), BTreeSet::new()))
+        .collect();
+    for (name, imports) in imports {
+        for import in imports {
+            stale.entry(import.file_path.as_str()).or_default().insert(name.as_str());
+        }
+    }
+    stale
+        .into_iter()
+        .filter(|(file, _)| !used.contains(file))
+        .map(|(file, names)| StaleIntegration {
+            file_path: file.to_string(),
+            platform: file_platforms.get(file).map_or("Unknown", |p| p.name()).to_string(),
+            imports: names.into_iter().map(str::to_string).collect(),
+        })
+        .collect()
+}
+
+/// Shared types app code only declares values of or imports, never constructs, extends, or calls into
+pub fn pass_through_symbols(symbols: &[Symbol], symbol_usages: &HashMap<String, Vec<SymbolUsage>>) -> Vec<String> {
+    let mut names: Vec<String> = symbols
+        .iter()
+        .filter(|s| {
+            matches!(
+                s.symbol_type,
+                SymbolType::Class
+                    | SymbolType::ValueClass
+                    | SymbolType::Enum
+                    | SymbolType::SealedClass
+                    | SymbolType::DataClass
+                    | SymbolType::Interface
+            )
+        })
+        .filter(|s| {
+            symbol_usages.get(&s.name).is_some_and(|usages| {
+                !usages.is_empty()
+                    && usages
+                        .iter()
+                        .all(|u| matches!(u.kind, UsageKind::TypeAnnotation | UsageKind::ImportOnly))
+            })
+        })
+        .map(|s| s.name.clone())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Impact figures of one app file, for tools that decorate files (heat maps, IDE gutters)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct FileMetrics {
+    pub path: String,
+    pub platform: String,
+    pub total_lines: usize,
+    /// All of the file's code lines when it uses shared code directly or depends on a
+    /// file that does, otherwise 0
+    pub affected_lines: usize,
+    /// Whether the file itself uses shared code, rather than only depending on a file that does
+    pub direct: bool,
+    /// Usage sites of shared symbols in the file
+    pub usages: usize,
+    /// Shared symbols the file uses, in name order
+    pub symbols: Vec<String>,
+    /// Whether the file only declares UI layout, such as a Compose screen or a SwiftUI view
+    #[serde(default)]
+    pub ui_layout: bool,
+    /// Architectural layer the file belongs to (`presentation`, `domain`, `data`, `other`)
+    #[serde(default)]
+    pub layer: String,
+    /// For a file affected only through its dependencies, the chain of imports from it to
+    /// the nearest file using shared code directly
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affected_via: Vec<DependencyEdge>,
+}
+
+/// A set in sorted order, so the same analysis serializes to the same bytes every run
+fn sorted_set<S: Serializer>(set: &HashSet<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    set.iter().collect::<BTreeSet<_>>().serialize(serializer)
+}
+
+/// A map in key order, so the same analysis serializes to the same bytes every run
+fn sorted_map<V: Serialize, S: Serializer>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
 }
 
 /// Impact analysis result - aggregated domain entity
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ImpactAnalysis {
     pub total_symbols: usize,
+    /// Extracted shared symbols, for the reporters that link or locate them; left out of
+    /// serialized reports, which would otherwise repeat every declaration of the shared API
+    #[serde(skip)]
+    pub symbols: Vec<Symbol>,
     pub total_app_files: usize,
     pub total_app_lines: usize,
+    #[serde(serialize_with = "sorted_set")]
     pub affected_files: HashSet<String>,
     pub affected_lines: usize,
+    /// Affected over total app code, counted in `metric`
     pub impact_ratio: f64,
+    /// What `impact_ratio` and the platforms' impact ratios count
+    #[serde(default)]
+    pub metric: ImpactMetric,
+    /// Impact in files, code lines, and functions alike
+    #[serde(default)]
+    pub metrics: ImpactMetrics,
+    #[serde(serialize_with = "sorted_map")]
     pub platform_impacts: HashMap<String, PlatformImpact>,
+    /// App code in UI layout files, and the impact ratio without it
+    #[serde(default)]
+    pub ui_layout: UiLayoutImpact,
+    #[serde(serialize_with = "sorted_map")]
     pub symbol_usages: HashMap<String, Vec<SymbolUsage>>,
+    #[serde(default)]
+    pub documentation: DocumentationCoverage,
+    /// Affected app lines per shared line
+    #[serde(default)]
+    pub roi: SharedCodeRoi,
+    #[serde(default)]
+    pub parity: ParitySummary,
+    /// Shared symbols declared, exported to each platform, imported, and referenced
+    #[serde(default)]
+    pub funnel: ApiFunnel,
+    #[serde(default)]
+    pub stability_tiers: Vec<StabilityTierUsage>,
+    /// App usage per symbol category (data models, repositories, ...)
+    #[serde(default)]
+    pub categories: Vec<CategoryUsage>,
+    /// App usage per Kotlin package of the shared code, most referenced first
+    #[serde(default)]
+    pub packages: Vec<PackageUsage>,
+    /// Owners of the most used shared APIs, most used first; empty without `OWNERS` files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_owners: Vec<ApiOwnership>,
+    #[serde(default)]
+    pub module_dependencies: Vec<ModuleDependency>,
+    /// Impact per Android Gradle module, application modules first
+    #[serde(default)]
+    pub android_modules: Vec<AndroidModuleImpact>,
+    /// Impact per Swift module, Xcode targets first
+    #[serde(default)]
+    pub ios_modules: Vec<SwiftModuleImpact>,
+    /// Progress toward the configured coverage goals
+    #[serde(default)]
+    pub goals: Vec<GoalProgress>,
+    /// Impact per requested product variant or scheme
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<VariantImpact>,
+    /// `actual` declarations per platform source set of each module with `expect` declarations
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expect_actual: Vec<ActualizationCoverage>,
+    /// Per-file figures of every app file, in path order
+    #[serde(default)]
+    pub files: Vec<FileMetrics>,
+    /// Usage sites per call-site kind, import statements included
+    #[serde(default)]
+    pub usage_kinds: Vec<UsageKindCount>,
+    /// Shared types app code only passes through, by name
+    #[serde(default)]
+    pub pass_through_symbols: Vec<String>,
+    /// Imports of shared symbols the importing file never uses, by file and line
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unused_imports: Vec<SymbolUsage>,
+    /// App files that import shared code without using any of it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stale_integrations: Vec<StaleIntegration>,
+    /// Usages left out of every count by the suppression baseline
+    #[serde(default)]
+    pub suppressed_usages: usize,
+    /// Baseline entries that no longer match any usage
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stale_suppressions: Vec<Suppression>,
+    /// Lint findings about the shared API
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+    /// How and from what the report was produced
+    pub metadata: Option<RunMetadata>,
+    /// Shared API added, removed, and renamed since the previous recorded run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_churn: Option<ApiChurn>,
+    /// Base and touched symbols of a `--diff` run; the rest of the analysis only counts
+    /// those symbols
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<DiffImpact>,
+    /// Raw per-file results for a later `--resume` run to reuse, when recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warm_start: Option<WarmStartState>,
 }
 
-/// Platform-specific impact
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct PlatformImpact {
-    pub platform_name: String,
+/// Provenance of a report, so runs can be compared and verified
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RunMetadata {
+    pub tool_version: String,
+    /// Commit the analyzed project was at, when known
+    pub commit_sha: Option<String>,
+    /// Unix timestamp (seconds) of the run
+    pub generated_at: u64,
+    /// SHA-256 of the effective configuration
+    pub config_hash: String,
+    pub host: Option<String>,
+    /// CI build that produced the report, when run in a recognized CI environment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci: Option<CiEnvironment>,
+    /// Hex HMAC-SHA256 over the metadata and headline numbers, when a signing key is set
+    pub signature: Option<String>,
+}
+
+/// The CI build a report comes from, so published numbers trace back to it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CiEnvironment {
+    /// `GitHub Actions`, `GitLab CI`, or `Jenkins`
+    pub provider: String,
+    /// Page of the build or job run
+    pub build_url: Option<String>,
+    /// Branch being built; the source branch for pull and merge requests
+    pub branch: Option<String>,
+    /// Pull or merge request number, when the build is for one
+    pub pull_request: Option<u64>,
+}
+
+/// Target impact coverage for a platform or an app module
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageGoal {
+    /// Platform name (`android`, `ios`), app module directory, or Gradle path (`:feature:payments`)
+    pub name: String,
+    /// Target impact ratio
+    pub target: Ratio,
+    /// Whether missing the goal fails the run
+    pub enforce: bool,
+}
+
+impl CoverageGoal {
+    /// Returns the platform the goal targets, if its name is a platform name
+    pub fn platform(&self) -> Option<Platform> {
+        Platform::ALL
+            .into_iter()
+            .find(|p| p.name().eq_ignore_ascii_case(&self.name))
+    }
+
+    /// Whether an app file, relative to the project root, belongs to the goal's module
+    pub fn matches_module_file(&self, relative_path: &Path) -> bool {
+        let Some(dir) = relative_path.parent() else {
+            return false;
+        };
+        match self.name.strip_prefix(':') {
+            Some(gradle_path) => dir.starts_with(gradle_path.replace(':', "/")),
+            None => dir.components().any(|c| c.as_os_str() == self.name.as_str()),
+        }
+    }
+}
+
+/// Progress of the analysis toward a coverage goal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalProgress {
+    pub name: String,
+    /// Target impact ratio (0.0 ~ 1.0)
+    pub target: f64,
+    pub impact_ratio: f64,
+    pub affected_lines: usize,
+    pub total_lines: usize,
+    /// Reached the target; a goal matching no app lines is never met
+    pub met: bool,
+    pub enforce: bool,
+}
+
+/// An exact ratio of two counts, such as affected over total app lines
+///
+/// Ratios compare by value with integer arithmetic, so a run exactly at a threshold
+/// meets it however the division would have rounded. A zero denominator is a ratio of 0.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Ratio {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+impl Ratio {
+    pub fn new(numerator: usize, denominator: usize) -> Self {
+        Self {
+            numerator: numerator as u64,
+            denominator: denominator as u64,
+        }
+    }
+
+    /// A percentage written in decimal, e.g. `45.23` or `40`, as the exact ratio it denotes
+    pub fn parse_percent(text: &str) -> Result<Self, String> {
+        let invalid = || format!("`{}` is not a percentage", text);
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+        let digits = format!("{}{}", whole, fraction);
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) || fraction.len() > 12 {
+            return Err(invalid());
+        }
+        let numerator: u64 = digits.parse().map_err(|_| invalid())?;
+        Ok(Self {
+            numerator,
+            denominator: 100 * 10u64.pow(fraction.len() as u32),
+        })
+    }
+
+    /// A ratio only known as a float, to 12 decimal places, which keeps the decimal
+    /// values of configuration files exact
+    pub fn approximating(value: f64) -> Self {
+        const SCALE: f64 = 1e12;
+        Self {
+            numerator: (value.max(0.0) * SCALE).round() as u64,
+            denominator: SCALE as u64,
+        }
+    }
+
+    pub fn value(&self) -> f64 {
+        let (numerator, denominator) = self.parts();
+        numerator as f64 / denominator as f64
+    }
+
+    /// Numerator and non-zero denominator
+    fn parts(&self) -> (u128, u128) {
+        match self.denominator {
+            0 => (0, 1),
+            denominator => (self.numerator as u128, denominator as u128),
+        }
+    }
+}
+
+impl PartialEq for Ratio {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ratio {}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ((a, b), (c, d)) = (self.parts(), other.parts());
+        (a * d).cmp(&(c * b))
+    }
+}
+
+/// How a ratio is rounded to the last displayed digit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rounding {
+    /// Halves away from zero: 12.345% → 12.35%
+    #[default]
+    HalfUp,
+    /// Halves to the even digit: 12.345% → 12.34%
+    HalfEven,
+    /// Toward zero, so a displayed value never exceeds the exact one
+    Down,
+    /// Away from zero
+    Up,
+}
+
+/// Decimal places and rounding of the percentages reports display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RatioPrecision {
+    pub decimals: u32,
+    pub rounding: Rounding,
+}
+
+impl Default for RatioPrecision {
+    fn default() -> Self {
+        Self {
+            decimals: 2,
+            rounding: Rounding::HalfUp,
+        }
+    }
+}
+
+impl RatioPrecision {
+    /// Most decimal places a percentage can be displayed with
+    pub const MAX_DECIMALS: u32 = 6;
+
+    /// `ratio` as a percentage rounded to `decimals` places: `45.23` for 4523/10000
+    ///
+    /// Rounds the exact ratio, so the result is off by at most one unit in the last place.
+    pub fn percent(&self, ratio: Ratio) -> f64 {
+        let scale = 10u128.pow(self.decimals.min(Self::MAX_DECIMALS));
+        let (numerator, denominator) = ratio.parts();
+        let scaled = numerator * 100 * scale;
+        let (quotient, remainder) = (scaled / denominator, scaled % denominator);
+        let round_up = match self.rounding {
+            Rounding::HalfUp => 2 * remainder >= denominator,
+            Rounding::HalfEven => 2 * remainder > denominator || (2 * remainder == denominator && quotient % 2 == 1),
+            Rounding::Down => false,
+            Rounding::Up => remainder > 0,
+        };
+        (quotient + round_up as u128) as f64 / scale as f64
+    }
+}
+
+/// What impact coverage divides affected by total app code in
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImpactMetric {
+    /// App files
+    Files,
+    /// Code lines, blank and comment-only lines left out
+    #[default]
+    Lines,
+    /// Function and method declarations
+    Functions,
+}
+
+impl ImpactMetric {
+    pub const ALL: [ImpactMetric; 3] = [ImpactMetric::Files, ImpactMetric::Lines, ImpactMetric::Functions];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImpactMetric::Files => "files",
+            ImpactMetric::Lines => "lines",
+            ImpactMetric::Functions => "functions",
+        }
+    }
+
+    /// Parses a metric name, e.g. `functions`
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|metric| metric.name().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("unknown metric `{}` (files, lines, functions)", name.trim()))
+    }
+}
+
+/// Affected and total app code counted in one [`ImpactMetric`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub struct MetricImpact {
+    pub affected: usize,
+    pub total: usize,
+    pub impact_ratio: f64,
+}
+
+impl MetricImpact {
+    pub fn new(affected: usize, total: usize) -> Self {
+        Self {
+            affected,
+            total,
+            impact_ratio: Ratio::new(affected, total).value(),
+        }
+    }
+
+    pub fn ratio(&self) -> Ratio {
+        Ratio::new(self.affected, self.total)
+    }
+}
+
+/// Impact of the same app code in every [`ImpactMetric`], a file or function counting
+/// as affected when its file uses shared code directly or through its dependencies
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub struct ImpactMetrics {
+    pub files: MetricImpact,
+    pub lines: MetricImpact,
+    pub functions: MetricImpact,
+}
+
+impl ImpactMetrics {
+    pub fn get(&self, metric: ImpactMetric) -> &MetricImpact {
+        match metric {
+            ImpactMetric::Files => &self.files,
+            ImpactMetric::Lines => &self.lines,
+            ImpactMetric::Functions => &self.functions,
+        }
+    }
+
+    /// The metrics of several bodies of app code together
+    pub fn sum<'m>(metrics: impl IntoIterator<Item = &'m ImpactMetrics>) -> Self {
+        let (mut files, mut lines, mut functions) = ((0, 0), (0, 0), (0, 0));
+        for metrics in metrics {
+            files = (files.0 + metrics.files.affected, files.1 + metrics.files.total);
+            lines = (lines.0 + metrics.lines.affected, lines.1 + metrics.lines.total);
+            functions = (functions.0 + metrics.functions.affected, functions.1 + metrics.functions.total);
+        }
+        Self {
+            files: MetricImpact::new(files.0, files.1),
+            lines: MetricImpact::new(lines.0, lines.1),
+            functions: MetricImpact::new(functions.0, functions.1),
+        }
+    }
+}
+
+/// How many of a module's `expect` declarations one of its platform source sets provides an `actual` for
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ActualizationCoverage {
+    pub module: String,
+    /// Platform source set, such as `androidMain` or `iosMain`
+    pub source_set: String,
+    /// `expect` declarations the source set must implement
+    pub expected: usize,
+    /// Qualified names of the `expect` declarations without an `actual` the source set sees, in name order
+    pub missing: Vec<String>,
+}
+
+impl ActualizationCoverage {
+    pub fn actualized(&self) -> usize {
+        self.expected - self.missing.len()
+    }
+
+    pub fn ratio(&self) -> Ratio {
+        Ratio::new(self.actualized(), self.expected)
+    }
+}
+
+/// Whether an [`ImpactThreshold`] is a floor or a ceiling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThresholdKind {
+    Min,
+    Max,
+}
+
+/// Impact coverage a run must stay above or below, overall or on one platform
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImpactThreshold {
+    pub kind: ThresholdKind,
+    /// `None` for the overall impact ratio
+    pub platform: Option<Platform>,
+    /// Bound on the impact ratio, exactly as given
+    pub bound: Ratio,
+}
+
+impl ImpactThreshold {
+    /// Parses `PERCENT` (overall) or `PLATFORM=PERCENT`, e.g. `40` or `ios=25.5`
+    pub fn parse(kind: ThresholdKind, spec: &str) -> Result<Self, String> {
+        let (platform, percent) = match spec.split_once('=') {
+            Some((name, percent)) => {
+                let platform = Platform::ALL
+                    .into_iter()
+                    .find(|p| p.name().eq_ignore_ascii_case(name.trim()))
+                    .ok_or_else(|| format!("unknown platform `{}` (android, ios, server, desktop, web)", name.trim()))?;
+                (Some(platform), percent)
+            }
+            None => (None, spec),
+        };
+        let percent = percent.trim().trim_end_matches('%');
+        let bound = Ratio::parse_percent(percent)?;
+        if bound > Ratio::new(1, 1) {
+            return Err(format!("{} is not between 0 and 100", percent));
+        }
+        Ok(Self { kind, platform, bound })
+    }
+
+    /// Why the analysis breaks the threshold, or `None` when it holds
+    ///
+    /// Compares the exact ratio of affected to total app code in the analysis' metric, not a
+    /// rounded percentage. A platform without app code has an impact ratio of 0.
+    pub fn violation(&self, analysis: &ImpactAnalysis) -> Option<String> {
+        let (scope, actual) = match &self.platform {
+            Some(platform) => (
+                platform.name().to_string(),
+                analysis
+                    .platform_impacts
+                    .values()
+                    .find(|impact| impact.platform_name == platform.name())
+                    .map_or(Ratio::default(), |impact| impact.exact_ratio(analysis.metric)),
+            ),
+            None => ("Overall".to_string(), analysis.exact_impact_ratio()),
+        };
+        let (holds, relation) = match self.kind {
+            ThresholdKind::Min => (actual >= self.bound, "below the minimum"),
+            ThresholdKind::Max => (actual <= self.bound, "above the maximum"),
+        };
+        (!holds).then(|| {
+            format!(
+                "{} impact {:.2}% is {} of {}%",
+                scope,
+                actual.value() * 100.0,
+                relation,
+                self.bound.value() * 100.0
+            )
+        })
+    }
+}
+
+/// Impact of one Android product flavor or iOS scheme
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VariantImpact {
+    pub name: String,
+    /// App files the variant builds: common ones plus its own
     pub total_files: usize,
+    /// App files only this variant builds
+    pub specific_files: usize,
     pub total_lines: usize,
-    pub affected_files: HashSet<String>,
     pub affected_lines: usize,
     pub impact_ratio: f64,
-    pub top_symbols: Vec<(String, usize)>,
+    /// Impact ratio per platform name
+    pub platform_ratios: BTreeMap<String, f64>,
 }
 
-impl ImpactAnalysis {
-    #[allow(dead_code)]
-    pub fn new() -> Self {
-        Self::default()
+/// The requested variant an app file is specific to, if any
+///
+/// Android files are matched by their source set (`src/paid`, `src/paidDebug`),
+/// other files by any directory (`iosAppPaid/`, `Free/`). A directory belongs to
+/// a variant when it is named after it, starts with it followed by a capitalized
+/// word, or ends with its capitalized name.
+pub fn path_variant<'v>(relative_path: &Path, variants: &'v [String]) -> Option<&'v str> {
+    let dirs: Vec<&str> = relative_path
+        .parent()?
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect();
+    let candidates: Vec<&str> = match dirs.iter().position(|dir| *dir == "src") {
+        Some(src) => dirs.get(src + 1).copied().into_iter().collect(),
+        None => dirs,
+    };
+
+    variants
+        .iter()
+        .find(|variant| candidates.iter().any(|dir| is_variant_dir(dir, variant)))
+        .map(String::as_str)
+}
+
+/// Whether a directory (`paid`, `paidRelease`, `iosAppPaid`) belongs to `variant`
+pub fn is_variant_dir(dir: &str, variant: &str) -> bool {
+    let (dir_lower, variant_lower) = (dir.to_lowercase(), variant.to_lowercase());
+    if dir_lower == variant_lower {
+        return true;
     }
+    let starts = dir_lower.starts_with(&variant_lower)
+        && dir
+            .get(variant.len()..)
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_uppercase()));
+    let mut capitalized = variant.chars();
+    let capitalized: String = capitalized
+        .next()
+        .map(|first| first.to_uppercase().chain(capitalized).collect())
+        .unwrap_or_default();
+    starts || (dir.len() > variant.len() && dir.ends_with(&capitalized))
+}
 
-    pub fn calculate_impact_ratio(&mut self) {
-        if self.total_app_lines > 0 {
-            self.impact_ratio = self.affected_lines as f64 / self.total_app_lines as f64;
+/// One repository of a batch run: a row of the repository × platform matrix
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct BatchRepository {
+    pub name: String,
+    /// Local path or clone URL from the repository list
+    pub location: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_sha: Option<String>,
+    pub total_app_lines: usize,
+    pub affected_lines: usize,
+    pub impact_ratio: f64,
+    /// Impact ratio per platform name, for the platforms the repository has app code for
+    pub platform_ratios: BTreeMap<String, f64>,
+    /// Why the repository could not be analyzed; its numbers are all zero then
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BatchRepository {
+    /// Headline numbers of an analyzed repository
+    pub fn analyzed(name: &str, location: &str, commit_sha: Option<String>, analysis: &ImpactAnalysis) -> Self {
+        Self {
+            name: name.to_string(),
+            location: location.to_string(),
+            commit_sha,
+            total_app_lines: analysis.total_app_lines,
+            affected_lines: analysis.affected_lines,
+            impact_ratio: analysis.impact_ratio,
+            platform_ratios: analysis
+                .platform_impacts
+                .iter()
+                .filter(|(_, impact)| impact.total_lines > 0)
+                .map(|(platform, impact)| (platform.clone(), impact.impact_ratio))
+                .collect(),
+            error: None,
         }
     }
-}
 
-impl PlatformImpact {
-    pub fn new(platform_name: String) -> Self {
+    /// A repository that could not be checked out or analyzed
+    pub fn failed(name: &str, location: &str, error: String) -> Self {
         Self {
-            platform_name,
-            ..Default::default()
+            name: name.to_string(),
+            location: location.to_string(),
+            error: Some(error),
+            ..Self::default()
         }
     }
+}
 
-    pub fn calculate_impact_ratio(&mut self) {
-        if self.total_lines > 0 {
+/// Results of analyzing a list of repositories with one shared configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct BatchSummary {
+    pub repositories: Vec<BatchRepository>,
+}
+
+impl BatchSummary {
+    /// Platforms any repository has app code for, in name order
+    pub fn platforms(&self) -> Vec<&str> {
+        let platforms: BTreeSet<&str> = self
+            .repositories
+            .iter()
+            .flat_map(|r| r.platform_ratios.keys().map(String::as_str))
+            .collect();
+        platforms.into_iter().collect()
+    }
+
+    /// Repositories that could not be analyzed
+    pub fn failures(&self) -> Vec<&BatchRepository> {
+        self.repositories.iter().filter(|r| r.error.is_some()).collect()
+    }
+}
+
+/// A figure as two reports state it
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct Change<T> {
+    pub before: T,
+    pub after: T,
+}
+
+impl Change<usize> {
+    pub fn delta(&self) -> i64 {
+        self.after as i64 - self.before as i64
+    }
+}
+
+impl Change<f64> {
+    pub fn delta(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+/// Differences between two analysis reports, such as those of two releases
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ReportDiff {
+    pub commit: Change<Option<String>>,
+    pub impact_ratio: Change<f64>,
+    pub affected_lines: Change<usize>,
+    pub total_app_lines: Change<usize>,
+    /// Every platform of either report, in name order
+    pub platforms: Vec<PlatformDelta>,
+    /// Shared symbols whose usage sites changed in number, largest change first
+    pub symbols: Vec<SymbolDelta>,
+    /// App files added, removed, or changed in lines, impact, or used symbols, in path order
+    pub files: Vec<FileDelta>,
+}
+
+/// Impact change of one platform
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PlatformDelta {
+    pub platform: String,
+    pub impact_ratio: Change<f64>,
+    pub affected_lines: Change<usize>,
+    pub total_lines: Change<usize>,
+}
+
+/// Usage change of one shared symbol; 0 on one side means it was unused or absent there
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SymbolDelta {
+    pub symbol: String,
+    pub usages: Change<usize>,
+}
+
+/// Whether an app file exists in both reports
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// Change of one app file's figures
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileDelta {
+    /// Path relative to the project root of the reports
+    pub path: String,
+    pub platform: String,
+    pub status: FileStatus,
+    pub total_lines: Change<usize>,
+    pub affected_lines: Change<usize>,
+    pub usages: Change<usize>,
+    /// Shared symbols the file uses only in the later report
+    pub added_symbols: Vec<String>,
+    /// Shared symbols the file uses only in the earlier report
+    pub removed_symbols: Vec<String>,
+}
+
+impl ReportDiff {
+    /// Compares `before` with `after`
+    ///
+    /// Reports may come from different checkouts, so files are matched by their path
+    /// below each report's project root, taken as the deepest directory holding all of
+    /// its shared declarations and app files. Per-file figures need reports that list
+    /// `files`; older reports compare by headline, platform, and symbol only.
+    pub fn between(before: &ImpactAnalysis, after: &ImpactAnalysis) -> Self {
+        let commit = |analysis: &ImpactAnalysis| analysis.metadata.as_ref().and_then(|m| m.commit_sha.clone());
+
+        let platform_names: BTreeSet<&String> =
+            before.platform_impacts.keys().chain(after.platform_impacts.keys()).collect();
+        let platforms = platform_names
+            .into_iter()
+            .map(|name| {
+                let figures = |analysis: &ImpactAnalysis| {
+                    analysis.platform_impacts.get(name).map_or((0.0, 0, 0), |impact| {
+                        (impact.impact_ratio, impact.affected_lines, impact.total_lines)
+                    })
+                };
+                let (before, after) = (figures(before), figures(after));
+                PlatformDelta {
+                    platform: name.clone(),
+                    impact_ratio: Change { before: before.0, after: after.0 },
+                    affected_lines: Change { before: before.1, after: after.1 },
+                    total_lines: Change { before: before.2, after: after.2 },
+                }
+            })
+            .collect();
+
+        let symbol_names: BTreeSet<&String> =
+            before.symbol_usages.keys().chain(after.symbol_usages.keys()).collect();
+        let count = |analysis: &ImpactAnalysis, name: &str| analysis.symbol_usages.get(name).map_or(0, Vec::len);
+        let mut symbols: Vec<SymbolDelta> = symbol_names
+            .into_iter()
+            .map(|name| SymbolDelta {
+                symbol: name.clone(),
+                usages: Change { before: count(before, name), after: count(after, name) },
+            })
+            .filter(|delta| delta.usages.before != delta.usages.after)
+            .collect();
+        symbols.sort_by(|a, b| {
+            b.usages
+                .delta()
+                .abs()
+                .cmp(&a.usages.delta().abs())
+                .then_with(|| a.symbol.cmp(&b.symbol))
+        });
+
+        Self {
+            commit: Change { before: commit(before), after: commit(after) },
+            impact_ratio: Change { before: before.impact_ratio, after: after.impact_ratio },
+            affected_lines: Change { before: before.affected_lines, after: after.affected_lines },
+            total_app_lines: Change { before: before.total_app_lines, after: after.total_app_lines },
+            platforms,
+            symbols,
+            files: Self::file_deltas(before, after),
+        }
+    }
+
+    fn file_deltas(before: &ImpactAnalysis, after: &ImpactAnalysis) -> Vec<FileDelta> {
+        let by_path = |analysis: &'_ ImpactAnalysis| -> BTreeMap<String, FileMetrics> {
+            let root = Self::project_root(analysis);
+            analysis
+                .files
+                .iter()
+                .map(|file| (relative_file(&root, &file.path), file.clone()))
+                .collect()
+        };
+        let (mut before, after) = (by_path(before), by_path(after));
+
+        let empty = |file: &FileMetrics| FileMetrics {
+            total_lines: 0,
+            affected_lines: 0,
+            usages: 0,
+            symbols: Vec::new(),
+            ..file.clone()
+        };
+        let mut deltas = Vec::new();
+        for (path, new) in after {
+            let (old, status) = match before.remove(&path) {
+                Some(old) => (old, FileStatus::Changed),
+                None => (empty(&new), FileStatus::Added),
+            };
+            deltas.push(Self::file_delta(path, &old, &new, status));
+        }
+        for (path, old) in before {
+            let new = empty(&old);
+            deltas.push(Self::file_delta(path, &old, &new, FileStatus::Removed));
+        }
+        deltas.retain(|delta| {
+            delta.status != FileStatus::Changed
+                || delta.total_lines.before != delta.total_lines.after
+                || delta.affected_lines.before != delta.affected_lines.after
+                || delta.usages.before != delta.usages.after
+                || !delta.added_symbols.is_empty()
+                || !delta.removed_symbols.is_empty()
+        });
+        deltas.sort_by(|a, b| a.path.cmp(&b.path));
+        deltas
+    }
+
+    fn file_delta(path: String, old: &FileMetrics, new: &FileMetrics, status: FileStatus) -> FileDelta {
+        let only_in = |a: &FileMetrics, b: &FileMetrics| -> Vec<String> {
+            a.symbols.iter().filter(|s| !b.symbols.contains(s)).cloned().collect()
+        };
+        FileDelta {
+            path,
+            platform: new.platform.clone(),
+            status,
+            total_lines: Change { before: old.total_lines, after: new.total_lines },
+            affected_lines: Change { before: old.affected_lines, after: new.affected_lines },
+            usages: Change { before: old.usages, after: new.usages },
+            added_symbols: only_in(new, old),
+            removed_symbols: only_in(old, new),
+        }
+    }
+
+    /// Deepest directory holding every shared declaration and app file of the report
+    fn project_root(analysis: &ImpactAnalysis) -> String {
+        let mut paths = analysis
+            .symbols
+            .iter()
+            .map(|s| s.file_path.as_str())
+            .chain(analysis.files.iter().map(|f| f.path.as_str()))
+            .map(|path| Path::new(path).parent().unwrap_or(Path::new("")));
+        let Some(first) = paths.next() else {
+            return String::new();
+        };
+        let mut root = first.to_path_buf();
+        for dir in paths {
+            while !dir.starts_with(&root) {
+                if !root.pop() {
+                    return String::new();
+                }
+            }
+        }
+        root.to_string_lossy().to_string()
+    }
+}
+
+/// Guards against pathological app files, such as minified or generated code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputLimits {
+    /// Files larger than this many bytes are not scanned for usages
+    pub max_file_bytes: usize,
+    /// Lines longer than this many bytes are not scanned for usages
+    pub max_line_length: usize,
+    /// Usage locations kept per symbol in a single file
+    pub max_usages_per_symbol_per_file: usize,
+    /// Characters of source kept as the context of a usage
+    pub max_context_length: usize,
+}
+
+impl Default for InputLimits {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: 2 * 1024 * 1024,
+            max_line_length: 2_000,
+            max_usages_per_symbol_per_file: 100,
+            max_context_length: 200,
+        }
+    }
+}
+
+/// Where and how deep project detection looks for KMP, Android, iOS, and server projects
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DetectionSettings {
+    /// Directory levels below a search root that build files and Xcode projects are looked for in
+    pub depth: usize,
+    /// Directories (relative to the analyzed one) searched as additional roots,
+    /// for modules nested deeper than `depth`
+    pub root_hints: Vec<String>,
+    /// KMP module directories (relative to the analyzed one); when given, they replace
+    /// the detected KMP modules
+    pub module_paths: Vec<String>,
+    /// App directories (relative to the analyzed one) per platform; a platform listed
+    /// here has its detected projects replaced
+    pub platform_paths: Vec<(Platform, Vec<String>)>,
+    /// Android product flavors or iOS schemes whose own source sets (`src/paid`,
+    /// `src/paidRelease`) are scanned besides `src/main`
+    pub variants: Vec<String>,
+    /// Directory names whose files are never iOS app code (CocoaPods and Carthage
+    /// checkouts, Xcode and SwiftPM build output)
+    pub ios_excluded_dirs: Vec<String>,
+    /// Paths whose files are never Android app code: `build/` matches a directory,
+    /// `*FileGenerated.kt` the end of a file name
+    pub android_excluded_paths: Vec<String>,
+    /// Globs selecting the shared and app files scanned, on every platform
+    pub files: FileFilter,
+    /// Also detect projects from Bazel and Buck targets, attributing them the files their `srcs` match
+    pub build_targets: bool,
+    /// Directory names never traversed on any platform: build output, IDE and tool state
+    pub ignored_dirs: Vec<String>,
+    /// Skip what the `.gitignore` files of the git work tree ignore
+    pub ignore_files: bool,
+}
+
+impl DetectionSettings {
+    /// Vendored dependency sources and build output skipped during iOS discovery by default
+    pub const DEFAULT_IOS_EXCLUDED_DIRS: &'static [&'static str] = &["Pods", "Carthage", "DerivedData", ".build"];
+
+    /// Build output, tool state, and dependency checkouts never traversed by default
+    pub const DEFAULT_IGNORED_DIRS: &'static [&'static str] = &[
+        ".git", ".gradle", ".kotlin", ".idea", "build", ".build", "DerivedData", "Pods", "Carthage", "node_modules",
+    ];
+
+    /// Build output, generated sources, and test-only source sets skipped during Android discovery by default
+    pub const DEFAULT_ANDROID_EXCLUDED_PATHS: &'static [&'static str] =
+        &["build/", "testFixtures/", "screenshotTest/", "*FileGenerated.kt"];
+
+    /// The first of `patterns` that excludes `relative`, a path below a project root
+    ///
+    /// `name/` matches a directory, `*suffix` the end of the file name, and a plain
+    /// name any component.
+    pub fn excluded_by<'p>(patterns: &'p [String], relative: &Path) -> Option<&'p str> {
+        let components: Vec<String> = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let (file_name, dirs) = components.split_last()?;
+        patterns
+            .iter()
+            .find(|pattern| {
+                if let Some(dir) = pattern.strip_suffix('/') {
+                    dirs.iter().any(|d| d == dir)
+                } else if let Some(suffix) = pattern.strip_prefix('*') {
+                    file_name.ends_with(suffix)
+                } else {
+                    components.iter().any(|c| c == *pattern)
+                }
+            })
+            .map(String::as_str)
+    }
+}
+
+impl Default for DetectionSettings {
+    fn default() -> Self {
+        Self {
+            depth: 5,
+            root_hints: Vec::new(),
+            module_paths: Vec::new(),
+            platform_paths: Vec::new(),
+            variants: Vec::new(),
+            ios_excluded_dirs: Self::DEFAULT_IOS_EXCLUDED_DIRS.iter().map(|d| d.to_string()).collect(),
+            android_excluded_paths: Self::DEFAULT_ANDROID_EXCLUDED_PATHS.iter().map(|p| p.to_string()).collect(),
+            files: FileFilter::default(),
+            build_targets: false,
+            ignored_dirs: Self::DEFAULT_IGNORED_DIRS.iter().map(|d| d.to_string()).collect(),
+            ignore_files: true,
+        }
+    }
+}
+
+/// Include and exclude globs over paths relative to the analyzed directory
+///
+/// `*` and `?` match within one path component and `**` any number of components.
+/// A glob without `/` matches any single component (`build`, `*Test.kt`), and a glob
+/// matching a directory also matches everything below it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FileFilter {
+    /// When non-empty, only files matching one of these are scanned
+    pub include: Vec<String>,
+    /// Files matching any of these are never scanned, even when included
+    pub exclude: Vec<String>,
+}
+
+impl FileFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether `relative`, a file path below the analyzed directory, is scanned
+    pub fn allows(&self, relative: &Path) -> bool {
+        let components: Vec<String> = relative
+            .components()
+            .filter(|c| matches!(c, Component::Normal(_)))
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let matches = |pattern: &String| glob_matches_path(pattern, &components);
+        (self.include.is_empty() || self.include.iter().any(matches)) && !self.exclude.iter().any(matches)
+    }
+}
+
+/// Whether a [`FileFilter`] glob matches a path or one of its parent directories
+fn glob_matches_path(pattern: &str, components: &[String]) -> bool {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    if !pattern.contains('/') {
+        return components.iter().any(|component| glob_matches(pattern, component));
+    }
+    let segments: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    (1..=components.len()).any(|len| segments_match(&segments, &components[..len]))
+}
+
+/// Whether glob segments match path components exactly, `**` standing for any number of them
+fn segments_match(segments: &[&str], components: &[String]) -> bool {
+    match segments.split_first() {
+        None => components.is_empty(),
+        Some((&"**", rest)) => (0..=components.len()).any(|skip| segments_match(rest, &components[skip..])),
+        Some((segment, rest)) => components
+            .split_first()
+            .is_some_and(|(component, remaining)| glob_matches(segment, component) && segments_match(rest, remaining)),
+    }
+}
+
+/// Whether a glob of `*` and `?` wildcards matches one path component
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    // Backtracking over the last `*`: (pattern index after it, text index it matched up to)
+    let (mut p, mut t) = (0, 0);
+    let mut star = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((after_star, matched)) => {
+                    p = after_star;
+                    t = matched + 1;
+                    star = Some((after_star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Subtrees an analysis is restricted to; empty means the whole project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AnalysisScope {
+    /// Directories relative to the analyzed one (`shared/feature-auth`, `appAuth/`)
+    pub roots: Vec<String>,
+}
+
+impl AnalysisScope {
+    pub fn new(roots: Vec<String>) -> Self {
+        Self { roots }
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        !self.roots.is_empty()
+    }
+
+    /// Whether `file` (as listed under `project_path`) lies in one of the scoped subtrees
+    pub fn contains(&self, project_path: &str, file: &str) -> bool {
+        if !self.is_restricted() {
+            return true;
+        }
+        let file = Path::new(file);
+        let relative = file.strip_prefix(project_path).unwrap_or(file);
+        self.roots.iter().any(|root| relative.starts_with(normalize(root)))
+    }
+}
+
+/// A relative path without `.` components, so `./shared/` and `shared` compare equal
+fn normalize(path: &str) -> PathBuf {
+    Path::new(path)
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+/// Lines `start..=end` of a file, 1-based
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl LineRange {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    pub fn overlaps(&self, other: &LineRange) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+}
+
+/// Lines changed since a base commit, which a diff-aware analysis is restricted to
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DiffScope {
+    /// Commit, branch, or tag the changes are taken against
+    pub base_ref: String,
+    /// Changed lines per file, relative to the analyzed directory and `/`-separated
+    pub files: BTreeMap<String, Vec<LineRange>>,
+}
+
+impl DiffScope {
+    /// Changed lines of `file` (as listed under `project_path`), `None` when it is unchanged
+    pub fn changed_lines(&self, project_path: &str, file: &str) -> Option<&[LineRange]> {
+        let file = Path::new(file);
+        let relative = normalize(&file.strip_prefix(project_path).unwrap_or(file).to_string_lossy());
+        self.files
+            .iter()
+            .find(|(changed, _)| Path::new(changed.as_str()) == relative)
+            .map(|(_, lines)| lines.as_slice())
+    }
+
+    /// Whether a change falls within the symbol's declaration
+    ///
+    /// Symbols without known lines are touched by any change to their file.
+    pub fn touches(&self, project_path: &str, symbol: &Symbol) -> bool {
+        match (self.changed_lines(project_path, &symbol.file_path), &symbol.lines) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(changed), Some(lines)) => changed.iter().any(|range| range.overlaps(lines)),
+        }
+    }
+}
+
+/// What a diff-aware analysis was restricted to
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DiffImpact {
+    /// Commit, branch, or tag the changes are taken against
+    pub base_ref: String,
+    /// Shared files changed since the base, in path order
+    pub changed_files: Vec<String>,
+    /// Shared symbols whose declarations the changes touch, by name
+    pub touched_symbols: Vec<String>,
+    /// Changed lines per file, relative to the analyzed directory and `/`-separated
+    #[serde(default)]
+    pub changed_lines: BTreeMap<String, Vec<LineRange>>,
+}
+
+/// Raw symbol extraction and usage detection results, keyed by file content
+///
+/// Recorded in a report so the next run can reuse them for files whose content
+/// did not change, before hooks, suppressions, or limits are applied.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WarmStartState {
+    /// Tool version that produced the results; other versions start cold
+    pub tool_version: String,
+    /// Parser the shared symbols were extracted with; results of another parser start cold
+    #[serde(default)]
+    pub parser: SymbolParser,
+    /// Hash of the shared symbols app files were searched for
+    pub symbol_table: String,
+    /// Shared and app files by path
+    pub files: BTreeMap<String, WarmFile>,
+}
+
+/// Content hash of one file and what was extracted from or detected in it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WarmFile {
+    /// SHA-256 of the content, hex encoded
+    pub hash: String,
+    /// Symbols a shared file declares
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<Symbol>,
+    /// Usages detected in an app file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub usages: Vec<SymbolUsage>,
+}
+
+/// Usages of one shared symbol in one app file, known to be false positives
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Suppression {
+    pub symbol: String,
+    /// App file, relative to the analyzed directory and `/`-separated
+    pub file: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Suppression {
+    /// Whether `usage`, found under `project_path`, is one this suppression removes
+    pub fn matches(&self, project_path: &str, usage: &SymbolUsage) -> bool {
+        usage.symbol_name == self.symbol && normalize(&self.file) == normalize(&relative_file(project_path, &usage.file_path))
+    }
+}
+
+/// The checked-in suppression baseline
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SuppressionBaseline {
+    pub suppressions: Vec<Suppression>,
+}
+
+impl SuppressionBaseline {
+    pub fn is_empty(&self) -> bool {
+        self.suppressions.is_empty()
+    }
+
+    /// Removes the suppressed usages, returning how many were removed from `usages`
+    /// and the suppressions that matched nothing in `usages` or `imports`
+    pub fn apply(
+        &self,
+        project_path: &str,
+        usages: &mut HashMap<String, Vec<SymbolUsage>>,
+        imports: &mut HashMap<String, Vec<SymbolUsage>>,
+    ) -> (usize, Vec<Suppression>) {
+        let mut matched = vec![false; self.suppressions.len()];
+        let mut retain = |usages: &mut HashMap<String, Vec<SymbolUsage>>| {
+            let mut removed = 0;
+            for list in usages.values_mut() {
+                list.retain(|usage| match self.suppressions.iter().position(|s| s.matches(project_path, usage)) {
+                    Some(index) => {
+                        matched[index] = true;
+                        removed += 1;
+                        false
+                    }
+                    None => true,
+                });
+            }
+            usages.retain(|_, list| !list.is_empty());
+            removed
+        };
+        let removed = retain(usages);
+        retain(imports);
+
+        let stale = self
+            .suppressions
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(suppression, _)| suppression.clone())
+            .collect();
+        (removed, stale)
+    }
+}
+
+/// `file` relative to `project_path`, `/`-separated, as suppressions record it
+pub fn relative_file(project_path: &str, file: &str) -> String {
+    let path = Path::new(file);
+    let relative = path.strip_prefix(project_path).unwrap_or(path);
+    normalize(&relative.to_string_lossy()).to_string_lossy().replace('\\', "/")
+}
+
+/// A project found by project detection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectDetection {
+    /// `KMP`, `Android`, `iOS`, or `Server`
+    pub project_type: String,
+    /// Project directory, relative to the analyzed one
+    pub root: String,
+    /// Strategy that found the project (`gradle-plugin`, `manifest`, `xcodeproj`, `structure`)
+    pub detected_by: String,
+    /// How sure detection is (0 ~ 1); low values point at projects worth a root hint or config fix
+    pub confidence: f64,
+    /// Source directories, relative to the analyzed one
+    pub source_dirs: Vec<String>,
+    /// Files in the source directories left out as build output, generated code,
+    /// test fixtures, or vendored dependencies
+    #[serde(default)]
+    pub excluded_files: usize,
+}
+
+/// Severity of a lint finding
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn name(&self) -> &str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A lint finding reported alongside the impact analysis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// Identifier of the lint rule (e.g. `single-platform-drift`)
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    /// Shared symbol the finding is about, if any
+    pub symbol: Option<String>,
+}
+
+/// When a shared symbol was first seen and how many runs have seen it since
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SymbolSighting {
+    /// Unix timestamp (seconds) of the first run that saw the symbol
+    pub first_seen: u64,
+    pub runs: u32,
+    /// Present when the history was created; such symbols are not "new"
+    pub baseline: bool,
+}
+
+/// Per-symbol history persisted between runs
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SymbolHistory {
+    pub symbols: BTreeMap<String, SymbolSighting>,
+}
+
+/// Anonymous, machine-local statistics accumulated over runs, for attaching to bug reports
+///
+/// Holds no paths, names, or numbers that identify the analyzed code.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct RunStats {
+    /// Tool version of the most recent run
+    pub tool_version: String,
+    pub runs: u64,
+    /// Unix timestamps (seconds) of the first and most recent run
+    pub first_run: u64,
+    pub last_run: u64,
+    pub total_duration_ms: u64,
+    pub max_duration_ms: u64,
+    pub last_duration_ms: u64,
+    /// Runs per bucket of app files analyzed (`100-999`)
+    pub app_files: BTreeMap<String, u64>,
+    /// Runs per bucket of shared symbols extracted
+    pub shared_symbols: BTreeMap<String, u64>,
+    /// Runs per output format
+    pub formats: BTreeMap<String, u64>,
+    /// Runs per command line flag or config option in use
+    pub features: BTreeMap<String, u64>,
+}
+
+/// What one run contributes to the [`RunStats`]
+#[derive(Debug, Clone, Default)]
+pub struct RunSample {
+    pub duration_ms: u64,
+    pub app_files: usize,
+    pub shared_symbols: usize,
+    pub format: String,
+    pub features: Vec<String>,
+}
+
+impl RunStats {
+    /// Adds one run; `now` is a Unix timestamp in seconds
+    pub fn record(&mut self, sample: &RunSample, tool_version: &str, now: u64) {
+        if self.runs == 0 {
+            self.first_run = now;
+        }
+        self.runs += 1;
+        self.last_run = now;
+        self.tool_version = tool_version.to_string();
+        self.total_duration_ms += sample.duration_ms;
+        self.max_duration_ms = self.max_duration_ms.max(sample.duration_ms);
+        self.last_duration_ms = sample.duration_ms;
+
+        *self.app_files.entry(size_bucket(sample.app_files)).or_default() += 1;
+        *self.shared_symbols.entry(size_bucket(sample.shared_symbols)).or_default() += 1;
+        *self.formats.entry(sample.format.clone()).or_default() += 1;
+        for feature in &sample.features {
+            *self.features.entry(feature.clone()).or_default() += 1;
+        }
+    }
+}
+
+/// Order-of-magnitude bucket of a count (`0-9`, `10-99`, ..., `100000+`)
+pub fn size_bucket(count: usize) -> String {
+    let mut lower = 1;
+    while lower * 10 <= count && lower < 100_000 {
+        lower *= 10;
+    }
+    match lower {
+        1 => "0-9".to_string(),
+        100_000 => "100000+".to_string(),
+        _ => format!("{}-{}", lower, lower * 10 - 1),
+    }
+}
+
+/// Headline numbers of one analysis run, kept to chart trends across runs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunSnapshot {
+    /// Unix timestamp (seconds) of the run
+    pub generated_at: u64,
+    pub commit_sha: Option<String>,
+    pub impact_ratio: f64,
+    /// Impact ratio per platform name
+    pub platform_ratios: BTreeMap<String, f64>,
+    /// References of the most used shared symbols
+    pub symbol_references: BTreeMap<String, usize>,
+    /// Public shared API of the run; absent in runs recorded before it was kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api: Option<Vec<ApiSymbol>>,
+    /// CI build that recorded the run, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_url: Option<String>,
+}
+
+impl RunSnapshot {
+    /// Maximum number of symbols whose references are kept per run
+    pub const MAX_SYMBOLS: usize = 50;
+
+    pub fn from_analysis(analysis: &ImpactAnalysis, generated_at: u64) -> Self {
+        let mut references: Vec<(&String, usize)> = analysis
+            .symbol_usages
+            .iter()
+            .map(|(name, usages)| (name, usages.len()))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        references.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        references.truncate(Self::MAX_SYMBOLS);
+
+        Self {
+            generated_at,
+            commit_sha: analysis.metadata.as_ref().and_then(|m| m.commit_sha.clone()),
+            impact_ratio: analysis.impact_ratio,
+            platform_ratios: analysis
+                .platform_impacts
+                .iter()
+                .map(|(name, impact)| (name.clone(), impact.impact_ratio))
+                .collect(),
+            symbol_references: references
+                .into_iter()
+                .map(|(name, count)| (name.clone(), count))
+                .collect(),
+            api: Some(ApiSymbol::from_symbols(&analysis.symbols)),
+            build_url: analysis
+                .metadata
+                .as_ref()
+                .and_then(|m| m.ci.as_ref())
+                .and_then(|ci| ci.build_url.clone()),
+        }
+    }
+}
+
+/// A public shared symbol as kept in the run history
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiSymbol {
+    pub module: String,
+    pub package: String,
+    pub name: String,
+    pub symbol_type: SymbolType,
+    /// File name of the declaration, without directories so runs from different checkouts compare
+    pub file: String,
+}
+
+impl ApiSymbol {
+    /// The public symbols, sorted and without overload duplicates
+    pub fn from_symbols(symbols: &[Symbol]) -> Vec<Self> {
+        let api: BTreeSet<Self> = symbols
+            .iter()
+            .filter(|s| s.is_public)
+            .map(|s| Self {
+                module: s.module.clone(),
+                package: s.package.clone(),
+                name: s.name.clone(),
+                symbol_type: s.symbol_type.clone(),
+                file: Path::new(&s.file_path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            })
+            .collect();
+        api.into_iter().collect()
+    }
+
+    /// Fully qualified name (`com.example.UserRepository`)
+    pub fn qualified_name(&self) -> String {
+        if self.package.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}.{}", self.package, self.name)
+        }
+    }
+
+    /// Stable ID, the identity across runs: moving a declaration to another file is not an API change
+    pub fn id(&self) -> String {
+        symbol_id(&self.module, &self.package, &self.name, &self.symbol_type)
+    }
+
+    /// Whether `other` looks like this symbol under a new name: same kind, module, and
+    /// package, declared in the same file or in a file renamed along with it
+    fn is_renamed_to(&self, other: &Self) -> bool {
+        let named_after_file =
+            |s: &Self| Path::new(&s.file).file_stem().is_some_and(|stem| *stem == *s.name);
+        self.symbol_type == other.symbol_type
+            && self.module == other.module
+            && self.package == other.package
+            && (self.file == other.file || (named_after_file(self) && named_after_file(other)))
+    }
+}
+
+/// A shared symbol that was removed and re-added under another name
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ApiRename {
+    pub from: ApiSymbol,
+    pub to: ApiSymbol,
+}
+
+/// Shared API changes between two runs, with the impact change over the same span
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiChurn {
+    /// Unix timestamp (seconds) of the run compared against
+    pub since: u64,
+    pub since_commit: Option<String>,
+    pub added: Vec<ApiSymbol>,
+    pub removed: Vec<ApiSymbol>,
+    pub renamed: Vec<ApiRename>,
+    /// Change of the overall impact ratio since that run
+    pub impact_ratio_change: f64,
+    /// Change of the impact ratio per platform name
+    pub platform_ratio_changes: BTreeMap<String, f64>,
+}
+
+impl ApiChurn {
+    /// Changes from `previous` to `current`; `None` when either run has no API recorded
+    pub fn between(previous: &RunSnapshot, current: &RunSnapshot) -> Option<Self> {
+        let (before, after) = (previous.api.as_ref()?, current.api.as_ref()?);
+        let before_ids: HashSet<_> = before.iter().map(ApiSymbol::id).collect();
+        let after_ids: HashSet<_> = after.iter().map(ApiSymbol::id).collect();
+
+        let removed: Vec<ApiSymbol> = before
+            .iter()
+            .filter(|s| !after_ids.contains(&s.id()))
+            .cloned()
+            .collect();
+        let added: Vec<ApiSymbol> = after
+            .iter()
+            .filter(|s| !before_ids.contains(&s.id()))
+            .cloned()
+            .collect();
+
+        // Only unambiguous pairs count as renames; the rest stay added and removed
+        let pairs: Vec<(usize, usize)> = removed
+            .iter()
+            .enumerate()
+            .filter_map(|(i, from)| {
+                let mut candidates = added.iter().enumerate().filter(|(_, to)| from.is_renamed_to(to));
+                match (candidates.next(), candidates.next()) {
+                    (Some((j, to)), None)
+                        if removed.iter().filter(|other| other.is_renamed_to(to)).count() == 1 =>
+                    {
+                        Some((i, j))
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+        let renamed = pairs
+            .iter()
+            .map(|&(i, j)| ApiRename {
+                from: removed[i].clone(),
+                to: added[j].clone(),
+            })
+            .collect();
+        let removed = removed
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !pairs.iter().any(|pair| pair.0 == *i))
+            .map(|(_, symbol)| symbol)
+            .collect();
+        let added = added
+            .into_iter()
+            .enumerate()
+            .filter(|(j, _)| !pairs.iter().any(|pair| pair.1 == *j))
+            .map(|(_, symbol)| symbol)
+            .collect();
+
+        let platform_ratio_changes = current
+            .platform_ratios
+            .iter()
+            .map(|(platform, ratio)| {
+                let before = previous.platform_ratios.get(platform).copied().unwrap_or(0.0);
+                (platform.clone(), ratio - before)
+            })
+            .collect();
+
+        Some(Self {
+            since: previous.generated_at,
+            since_commit: previous.commit_sha.clone(),
+            added,
+            removed,
+            renamed,
+            impact_ratio_change: current.impact_ratio - previous.impact_ratio,
+            platform_ratio_changes,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.renamed.is_empty()
+    }
+}
+
+/// An import statement behind a file-level dependency
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImportSite {
+    /// 1-based line of the statement
+    pub line: usize,
+    /// The statement as written, e.g. `import com.example.auth.Session as AuthSession`
+    pub statement: String,
+    /// Whether code outside the import lines refers to what it imports
+    pub used: bool,
+}
+
+/// How one source file depends on another, by the imports that resolve to it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DependencyEdge {
+    pub from: String,
+    pub to: String,
+    pub imports: Vec<ImportSite>,
+}
+
+/// Gradle-level dependency of an app module on a KMP module
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModuleDependency {
+    /// Gradle path of the consuming module (e.g. `:feature:auth`)
+    pub module: String,
+    /// Gradle path of the KMP module (e.g. `:shared`)
+    pub depends_on: String,
+    /// Declaration that introduced the dependency (e.g. `libs.shared.core`)
+    pub via: String,
+}
+
+/// Kind of an Android Gradle module
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum GradleModuleKind {
+    /// Applies `com.android.application`
+    Application,
+    /// Any other Android module (libraries, feature modules)
+    Library,
+}
+
+/// An Android Gradle module and the app modules it depends on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GradleModule {
+    /// Gradle path (e.g. `:feature:auth`)
+    pub path: String,
+    /// Module directory
+    pub dir: String,
+    pub kind: GradleModuleKind,
+    /// Gradle paths of the other Android modules it declares `project(...)` dependencies on
+    pub dependencies: Vec<String>,
+}
+
+/// How shared code reaches an app module
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ModuleReach {
+    /// The module's own files use shared symbols
+    Direct,
+    /// Only modules it depends on use or declare shared code
+    ViaModules,
+    None,
+}
+
+impl ModuleReach {
+    pub fn name(&self) -> &str {
+        match self {
+            ModuleReach::Direct => "direct",
+            ModuleReach::ViaModules => "via modules",
+            ModuleReach::None => "none",
+        }
+    }
+}
+
+/// Impact of shared code on one Android Gradle module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AndroidModuleImpact {
+    /// Gradle path (e.g. `:feature:auth`)
+    pub module: String,
+    pub kind: GradleModuleKind,
+    pub reach: ModuleReach,
+    /// Declares a dependency on a KMP module in its build file
+    pub declares_shared_dependency: bool,
+    /// Modules it depends on (directly or transitively) that use or declare shared code
+    pub via_modules: Vec<String>,
+    pub total_files: usize,
+    pub total_lines: usize,
+    pub affected_files: usize,
+    pub affected_lines: usize,
+    pub impact_ratio: f64,
+    /// References to shared symbols from the module's own files
+    pub references: usize,
+}
+
+/// Kind of a Swift module
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SwiftModuleKind {
+    /// Native target of an Xcode project (apps, app extensions, frameworks)
+    XcodeTarget,
+    /// Target of a local Swift package
+    Package,
+}
+
+/// A Swift module of the iOS app and the other Swift modules it depends on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SwiftModule {
+    /// Target name (e.g. `FeatureAuth`)
+    pub name: String,
+    /// Source directory
+    pub dir: String,
+    pub kind: SwiftModuleKind,
+    /// Names of the other Swift modules it depends on
+    pub dependencies: Vec<String>,
+}
+
+/// Impact of shared code on one Swift module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwiftModuleImpact {
+    /// Target name (e.g. `FeatureAuth`)
+    pub module: String,
+    pub kind: SwiftModuleKind,
+    pub reach: ModuleReach,
+    /// Modules it depends on (directly or transitively) that use shared code
+    pub via_modules: Vec<String>,
+    pub total_files: usize,
+    pub total_lines: usize,
+    pub affected_files: usize,
+    pub affected_lines: usize,
+    pub impact_ratio: f64,
+    /// References to shared symbols from the module's own files
+    pub references: usize,
+}
+
+/// Platform-specific impact
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PlatformImpact {
+    pub platform_name: String,
+    pub total_files: usize,
+    pub total_lines: usize,
+    #[serde(serialize_with = "sorted_set")]
+    pub affected_files: HashSet<String>,
+    pub affected_lines: usize,
+    pub impact_ratio: f64,
+    /// Impact in files, code lines, and functions alike
+    #[serde(default)]
+    pub metrics: ImpactMetrics,
+    pub top_symbols: Vec<(String, usize)>,
+    /// App usages of shared symbols marked `@Deprecated`
+    #[serde(default)]
+    pub deprecated_usages: Vec<SymbolUsage>,
+    /// Shared files most of the platform's usage flows through, most used first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entry_points: Vec<EntryPoint>,
+    /// The platform's code in UI layout files, and its impact ratio without it
+    #[serde(default)]
+    pub ui_layout: UiLayoutImpact,
+    /// Impact per architectural layer with app files, in layer precedence order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<LayerImpact>,
+}
+
+/// App code in files that only declare UI layout (Compose screens, SwiftUI views
+/// without logic), which shared business logic can hardly reach
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct UiLayoutImpact {
+    pub files: usize,
+    pub total_lines: usize,
+    pub affected_lines: usize,
+    /// Impact ratio of the remaining app code, UI layout files left out of both sides
+    pub impact_ratio_excluding_ui: f64,
+}
+
+impl UiLayoutImpact {
+    /// Sets the impact ratio excluding UI layout, given the figures including it
+    pub fn calculate_impact_ratio(&mut self, total_lines: usize, affected_lines: usize) {
+        let total_lines = total_lines.saturating_sub(self.total_lines);
+        if total_lines > 0 {
+            self.impact_ratio_excluding_ui =
+                affected_lines.saturating_sub(self.affected_lines) as f64 / total_lines as f64;
+        }
+    }
+}
+
+/// A shared file app code enters the shared API through, such as a facade or a DI module
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EntryPoint {
+    /// Most used shared symbol declared in the file
+    pub symbol: String,
+    pub module: String,
+    pub file: String,
+    /// Usage sites of the file's symbols and of the used symbols it leads to
+    pub usages: usize,
+    /// Share of the platform's usage sites that flow through the entry point
+    pub share: f64,
+    /// Other used shared symbols collapsed into it, in name order
+    pub reached_symbols: Vec<String>,
+}
+
+impl EntryPoint {
+    /// Entry points reported per platform unless configured otherwise
+    pub const DEFAULT_LIMIT: usize = 5;
+
+    /// The `limit` entry points most of `usages` flows through
+    ///
+    /// `dependencies` maps each shared file to the shared files it transitively
+    /// depends on. The file of a used symbol is an entry point unless another file
+    /// with used symbols depends on it without being depended on in turn; usages of
+    /// deeper symbols count toward every entry point whose file leads to them.
+    pub fn collapse(
+        symbols: &[Symbol],
+        usages: &[&SymbolUsage],
+        dependencies: &HashMap<String, HashSet<String>>,
+        limit: usize,
+    ) -> Vec<Self> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for usage in usages {
+            *counts.entry(usage.symbol_name.as_str()).or_default() += 1;
+        }
+
+        // A name declared in several files stays with its first declaration
+        let mut declared: BTreeMap<&str, &Symbol> = BTreeMap::new();
+        for symbol in symbols.iter().filter(|s| counts.contains_key(s.name.as_str())) {
+            declared.entry(symbol.name.as_str()).or_insert(symbol);
+        }
+        let mut file_symbols: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for (name, symbol) in &declared {
+            file_symbols.entry(symbol.file_path.as_str()).or_default().push(name);
+        }
+
+        let leads_to = |from: &str, to: &str| dependencies.get(from).is_some_and(|deps| deps.contains(to));
+        let files: Vec<&str> = file_symbols.keys().copied().collect();
+        let mut entry_points: Vec<Self> = files
+            .iter()
+            .filter(|file| {
+                !files
+                    .iter()
+                    .any(|other| other != *file && leads_to(other, file) && !leads_to(file, other))
+            })
+            .map(|root| {
+                let reached: Vec<&str> = files
+                    .iter()
+                    .filter(|file| *file == root || leads_to(root, file))
+                    .flat_map(|file| file_symbols[file].iter().copied())
+                    .collect();
+                let symbol = file_symbols[root]
+                    .iter()
+                    .max_by(|a, b| counts[*a].cmp(&counts[*b]).then_with(|| b.cmp(a)))
+                    .copied()
+                    .unwrap_or_default();
+                let flowing: usize = reached.iter().map(|name| counts[name]).sum();
+                let mut reached_symbols: Vec<String> = reached
+                    .iter()
+                    .filter(|name| **name != symbol)
+                    .map(|name| name.to_string())
+                    .collect();
+                reached_symbols.sort();
+                Self {
+                    symbol: symbol.to_string(),
+                    module: declared[symbol].module.clone(),
+                    file: root.to_string(),
+                    usages: flowing,
+                    share: flowing as f64 / usages.len().max(1) as f64,
+                    reached_symbols,
+                }
+            })
+            .collect();
+
+        entry_points.sort_by(|a, b| b.usages.cmp(&a.usages).then_with(|| a.symbol.cmp(&b.symbol)));
+        entry_points.truncate(limit);
+        entry_points
+    }
+}
+
+/// Ordered stability tiers that classify shared symbols by their annotations
+///
+/// A symbol belongs to the first tier listing one of its annotations, or to
+/// the implicit `stable` tier otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StabilityTiers {
+    tiers: Vec<(String, Vec<String>)>,
+}
+
+impl StabilityTiers {
+    /// Tier of symbols without any tier annotation
+    pub const STABLE: &'static str = "stable";
+
+    pub fn new(tiers: Vec<(String, Vec<String>)>) -> Self {
+        Self { tiers }
+    }
+
+    /// Returns the tier name for a symbol
+    pub fn classify(&self, symbol: &Symbol) -> &str {
+        self.tiers
+            .iter()
+            .find(|(_, annotations)| annotations.iter().any(|a| symbol.has_annotation(a)))
+            .map(|(name, _)| name.as_str())
+            .unwrap_or(Self::STABLE)
+    }
+
+    /// All tier names in precedence order, ending with `stable`
+    pub fn names(&self) -> Vec<&str> {
+        self.tiers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .chain(std::iter::once(Self::STABLE))
+            .collect()
+    }
+}
+
+impl Default for StabilityTiers {
+    fn default() -> Self {
+        Self::new(vec![
+            ("internal".to_string(), vec!["InternalApi".to_string()]),
+            ("experimental".to_string(), vec!["ExperimentalApi".to_string()]),
+        ])
+    }
+}
+
+/// A named category of shared symbols and the patterns that include a symbol in it
+///
+/// A symbol matches when any pattern does. Package patterns are package
+/// prefixes (`com.example.network`) or, with a leading `*.`, a package segment
+/// matched anywhere (`*.model`); suffixes match the end of the symbol name
+/// (`Repository`); annotations match like [`Symbol::has_annotation`].
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SymbolCategory {
+    pub name: String,
+    pub packages: Vec<String>,
+    pub annotations: Vec<String>,
+    pub suffixes: Vec<String>,
+    /// Minimum share (0.0 ~ 1.0) of the category's shared symbols app code should use
+    pub threshold: Option<f64>,
+    /// Fail the run when the threshold is missed
+    pub enforce: bool,
+}
+
+impl SymbolCategory {
+    /// Whether any of the category's patterns include the symbol
+    pub fn matches(&self, symbol: &Symbol) -> bool {
+        self.packages
+            .iter()
+            .any(|pattern| Self::matches_package(pattern, &symbol.package))
+            || self.annotations.iter().any(|a| symbol.has_annotation(a))
+            || self.suffixes.iter().any(|suffix| symbol.name.ends_with(suffix.as_str()))
+    }
+
+    fn matches_package(pattern: &str, package: &str) -> bool {
+        match pattern.strip_prefix("*.") {
+            Some(segment) => package.split('.').any(|s| s == segment),
+            None => {
+                package == pattern
+                    || package
+                        .strip_prefix(pattern)
+                        .is_some_and(|rest| rest.starts_with('.'))
+            }
+        }
+    }
+}
+
+/// Ordered categories that sort shared symbols by architectural layer or concern
+///
+/// A symbol belongs to the first category that matches it, or to `other`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolCategories {
+    categories: Vec<SymbolCategory>,
+}
+
+impl SymbolCategories {
+    /// Category of symbols no rule matches
+    pub const OTHER: &'static str = "other";
+
+    pub fn new(categories: Vec<SymbolCategory>) -> Self {
+        Self { categories }
+    }
+
+    /// Returns the category name for a symbol
+    pub fn classify(&self, symbol: &Symbol) -> &str {
+        self.categories
+            .iter()
+            .find(|category| category.matches(symbol))
+            .map(|category| category.name.as_str())
+            .unwrap_or(Self::OTHER)
+    }
+
+    /// All category names in precedence order, ending with `other`
+    pub fn names(&self) -> Vec<&str> {
+        self.categories
+            .iter()
+            .map(|category| category.name.as_str())
+            .chain(std::iter::once(Self::OTHER))
+            .collect()
+    }
+
+    /// Looks up a category definition by name
+    pub fn get(&self, name: &str) -> Option<&SymbolCategory> {
+        self.categories.iter().find(|category| category.name == name)
+    }
+}
+
+impl Default for SymbolCategories {
+    fn default() -> Self {
+        let category = |name: &str, segments: &[&str], suffixes: &[&str]| SymbolCategory {
+            name: name.to_string(),
+            packages: segments.iter().map(|s| format!("*.{}", s)).collect(),
+            suffixes: suffixes.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        Self::new(vec![
+            category(
+                "data models",
+                &["model", "models", "dto", "entity", "entities"],
+                &["Dto", "Model", "Entity"],
+            ),
+            category(
+                "repositories",
+                &["repository", "repositories", "data"],
+                &["Repository", "RepositoryImpl", "DataSource"],
+            ),
+            category(
+                "use cases",
+                &["usecase", "usecases", "interactor", "interactors", "domain"],
+                &["UseCase", "Interactor"],
+            ),
+            category(
+                "utilities",
+                &["util", "utils", "common", "extensions"],
+                &["Utils", "Util", "Helper"],
+            ),
+        ])
+    }
+}
+
+/// Architectural layer of app code, recognized by package segments or paths
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AppLayer {
+    pub name: String,
+    /// Package prefixes (`com.example.feature.ui`) or segments (`*.ui`), matched
+    /// case-insensitively against the directories of an app file
+    pub packages: Vec<String>,
+    /// Paths in the pattern syntax of [`DetectionSettings::excluded_by`]
+    pub paths: Vec<String>,
+}
+
+impl AppLayer {
+    /// Whether the app file at `relative`, a path below the project root, belongs to the layer
+    pub fn matches(&self, relative: &Path) -> bool {
+        let dirs: Vec<String> = relative
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .map(|component| component.as_os_str().to_string_lossy().to_lowercase())
+            .collect();
+        self.packages.iter().any(|pattern| {
+            let pattern = pattern.to_lowercase();
+            match pattern.strip_prefix("*.") {
+                Some(segment) => dirs.iter().any(|dir| *dir == segment),
+                None => {
+                    let segments: Vec<&str> = pattern.split('.').collect();
+                    dirs.windows(segments.len()).any(|window| window == segments.as_slice())
+                }
+            }
+        }) || DetectionSettings::excluded_by(&self.paths, relative).is_some()
+    }
+}
+
+/// Ordered layers that split app code into presentation, domain, and data
+///
+/// A file belongs to the first layer that matches it, or to `other`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppLayers {
+    layers: Vec<AppLayer>,
+}
+
+impl AppLayers {
+    /// Layer of app files no rule matches
+    pub const OTHER: &'static str = "other";
+
+    pub fn new(layers: Vec<AppLayer>) -> Self {
+        Self { layers }
+    }
+
+    /// Returns the layer name for an app file below the project root
+    pub fn classify(&self, relative: &Path) -> &str {
+        self.layers
+            .iter()
+            .find(|layer| layer.matches(relative))
+            .map(|layer| layer.name.as_str())
+            .unwrap_or(Self::OTHER)
+    }
+
+    /// All layer names in precedence order, ending with `other`
+    pub fn names(&self) -> Vec<&str> {
+        self.layers
+            .iter()
+            .map(|layer| layer.name.as_str())
+            .chain(std::iter::once(Self::OTHER))
+            .collect()
+    }
+}
+
+impl Default for AppLayers {
+    fn default() -> Self {
+        let layer = |name: &str, segments: &[&str]| AppLayer {
+            name: name.to_string(),
+            packages: segments.iter().map(|s| format!("*.{}", s)).collect(),
+            ..Default::default()
+        };
+        Self::new(vec![
+            layer(
+                "presentation",
+                &["ui", "presentation", "screen", "screens", "view", "views", "viewmodel", "viewmodels", "compose"],
+            ),
+            layer(
+                "domain",
+                &["domain", "usecase", "usecases", "interactor", "interactors", "model", "models"],
+            ),
+            layer(
+                "data",
+                &["data", "repository", "repositories", "network", "api", "remote", "local", "db", "database", "datasource"],
+            ),
+        ])
+    }
+}
+
+/// Impact on the app code of one architectural layer of a platform
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct LayerImpact {
+    pub layer: String,
+    pub total_files: usize,
+    /// Files using shared code directly
+    pub affected_files: usize,
+    pub total_lines: usize,
+    pub affected_lines: usize,
+    pub impact_ratio: f64,
+}
+
+/// App usage of the shared symbols belonging to one category
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CategoryUsage {
+    pub category: String,
+    pub total_symbols: usize,
+    pub used_symbols: usize,
+    /// Share of the category's shared symbols app code uses (0.0 ~ 1.0)
+    pub used_ratio: f64,
+    pub references: usize,
+    pub affected_files: usize,
+    /// References per platform name
+    pub platform_references: BTreeMap<String, usize>,
+    /// Configured minimum `used_ratio`
+    pub threshold: Option<f64>,
+    /// Fail the run when the threshold is missed
+    pub enforce: bool,
+}
+
+impl CategoryUsage {
+    /// Whether the category meets its threshold (always true without one)
+    pub fn meets_threshold(&self) -> bool {
+        self.threshold
+            .is_none_or(|t| Ratio::new(self.used_symbols, self.total_symbols) >= Ratio::approximating(t))
+    }
+}
+
+/// App usage of shared symbols belonging to one stability tier
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct StabilityTierUsage {
+    pub tier: String,
+    pub total_symbols: usize,
+    pub used_symbols: usize,
+    pub references: usize,
+    pub affected_files: usize,
+    /// References per platform name
+    pub platform_references: BTreeMap<String, usize>,
+}
+
+/// App usage of the shared symbols declared in one Kotlin package
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PackageUsage {
+    /// Package name (empty for the root package)
+    pub package: String,
+    pub total_symbols: usize,
+    pub used_symbols: usize,
+    pub references: usize,
+    pub affected_files: usize,
+    /// References per platform name
+    pub platform_references: BTreeMap<String, usize>,
+}
+
+/// Owners a shared module's `OWNERS` file assigns to a package and its subpackages
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OwnerRule {
+    /// Directory of the KMP module declaring the rule
+    pub module: String,
+    /// Package prefix (empty for the module's default owners)
+    pub package: String,
+    pub owners: Vec<String>,
+}
+
+impl OwnerRule {
+    /// Owners of `symbol` by the most specific rule of the module declaring it
+    pub fn owners_of<'a>(rules: &'a [OwnerRule], symbol: &Symbol) -> Option<&'a [String]> {
+        let declared_in = |rule: &OwnerRule| {
+            symbol
+                .file_path
+                .strip_prefix(rule.module.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+        };
+        let covers = |rule: &OwnerRule| {
+            rule.package.is_empty()
+                || symbol
+                    .package
+                    .strip_prefix(rule.package.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        };
+        rules
+            .iter()
+            .filter(|rule| declared_in(rule) && covers(rule))
+            .max_by_key(|rule| (rule.module.len(), rule.package.len()))
+            .map(|rule| rule.owners.as_slice())
+    }
+}
+
+/// Owners a `CODEOWNERS` file assigns to paths of the repository
+///
+/// Patterns follow [`FileFilter`] globs, a leading `/` anchoring them at the root, and
+/// the last matching rule wins, as on GitHub and GitLab.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeOwners {
+    /// Pattern and owners of each rule, in file order
+    pub rules: Vec<(String, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// Where GitHub looks for the file, relative to the repository root, in order
+    pub const LOCATIONS: &'static [&'static str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+    /// Rules of a `CODEOWNERS` file; comments, section headers, and blank lines are skipped
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(|line| line.split_once(" #").map_or(line, |(rule, _)| rule).trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('[') && !line.starts_with("^["))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let pattern = fields.next()?.to_string();
+                Some((pattern, fields.map(str::to_string).collect()))
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Owners of `relative`, a `/`-separated path from the repository root; empty when no rule matches
+    pub fn owners_of(&self, relative: &str) -> &[String] {
+        let components: Vec<String> = relative.split('/').filter(|c| !c.is_empty()).map(str::to_string).collect();
+        self.rules
+            .iter()
+            .rev()
+            .find(|(pattern, _)| glob_matches_path(pattern, &components))
+            .map_or(&[], |(_, owners)| owners.as_slice())
+    }
+}
+
+/// Parser symbol extraction reads shared Kotlin declarations with
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolParser {
+    /// Line-based regular expressions
+    #[default]
+    Regex,
+    /// A tree-sitter Kotlin syntax tree, with regular expressions for files it cannot parse;
+    /// only in builds with the `treesitter` feature
+    TreeSitter,
+}
+
+impl SymbolParser {
+    pub const ALL: [SymbolParser; 2] = [SymbolParser::Regex, SymbolParser::TreeSitter];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SymbolParser::Regex => "regex",
+            SymbolParser::TreeSitter => "treesitter",
+        }
+    }
+
+    /// Parses a parser name, e.g. `treesitter`
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|parser| parser.name().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("unknown parser `{}` (regex, treesitter)", name.trim()))
+    }
+
+    /// Whether this build can parse with it
+    pub fn is_available(&self) -> bool {
+        match self {
+            SymbolParser::Regex => true,
+            SymbolParser::TreeSitter => cfg!(feature = "treesitter"),
+        }
+    }
+}
+
+/// Engine app files are searched for shared symbol names with
+///
+/// All engines find the same usages; they differ in how the cost grows with the
+/// number of symbols and the length of lines.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MatchEngine {
+    /// One automaton over all names finds each line's candidates in a single pass
+    #[default]
+    AhoCorasick,
+    /// One set of regular expressions, one per name, matched against each line
+    Regex,
+    /// A substring search per name and line; cheapest to set up for a few symbols
+    Literal,
+}
+
+impl MatchEngine {
+    pub const ALL: [MatchEngine; 3] = [MatchEngine::AhoCorasick, MatchEngine::Regex, MatchEngine::Literal];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MatchEngine::AhoCorasick => "aho-corasick",
+            MatchEngine::Regex => "regex",
+            MatchEngine::Literal => "literal",
+        }
+    }
+
+    /// Parses an engine name, e.g. `regex`
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|engine| engine.name().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("unknown match engine `{}` (aho-corasick, regex, literal)", name.trim()))
+    }
+}
+
+/// Kotlin explicit API mode of a shared module (`kotlin { explicitApi() }`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExplicitApiMode {
+    /// Declarations without a visibility modifier fail compilation, so they are not shared API
+    Strict,
+    /// Declarations without a visibility modifier compile with a warning and stay public
+    Warning,
+}
+
+impl ExplicitApiMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExplicitApiMode::Strict => "strict",
+            ExplicitApiMode::Warning => "warning",
+        }
+    }
+}
+
+/// A declaration of a module in explicit API mode that is public only by default
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImplicitPublicDeclaration {
+    pub name: String,
+    pub file_path: String,
+    /// 1-based line of the declaration
+    pub line: usize,
+    pub mode: ExplicitApiMode,
+}
+
+/// Owners of one of the shared APIs app code uses most
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ApiOwnership {
+    pub symbol: String,
+    pub package: String,
+    /// Owners from the declaring module's `OWNERS` file (empty when none covers the package)
+    pub owners: Vec<String>,
+    pub usages: usize,
+}
+
+impl ApiOwnership {
+    /// Maximum number of APIs kept in the owners table
+    pub const LIMIT: usize = 10;
+}
+
+/// KDoc coverage of the public shared API
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DocumentationCoverage {
+    pub total_symbols: usize,
+    pub documented_symbols: usize,
+    pub documented_ratio: f64,
+    /// Undocumented symbols used by app code, most referenced first
+    pub undocumented_hotspots: Vec<(String, usize)>,
+}
+
+impl DocumentationCoverage {
+    /// Maximum number of undocumented hotspots kept in the result
+    pub const MAX_HOTSPOTS: usize = 10;
+
+    pub fn from_symbols(
+        symbols: &[Symbol],
+        symbol_usages: &HashMap<String, Vec<SymbolUsage>>,
+    ) -> Self {
+        let public_symbols: Vec<&Symbol> = symbols.iter().filter(|s| s.is_public).collect();
+        let documented_symbols = public_symbols.iter().filter(|s| s.is_documented).count();
+
+        let documented_names: HashSet<&str> = public_symbols
+            .iter()
+            .filter(|s| s.is_documented)
+            .map(|s| s.name.as_str())
+            .collect();
+        let undocumented_names: HashSet<&str> = public_symbols
+            .iter()
+            .map(|s| s.name.as_str())
+            .filter(|name| !documented_names.contains(name))
+            .collect();
+
+        let mut undocumented_hotspots: Vec<(String, usize)> = undocumented_names
+            .into_iter()
+            .filter_map(|name| {
+                let count = symbol_usages.get(name).map_or(0, |u| u.len());
+                (count > 0).then(|| (name.to_string(), count))
+            })
+            .collect();
+        undocumented_hotspots.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        undocumented_hotspots.truncate(Self::MAX_HOTSPOTS);
+
+        let mut coverage = Self {
+            total_symbols: public_symbols.len(),
+            documented_symbols,
+            documented_ratio: 0.0,
+            undocumented_hotspots,
+        };
+        if coverage.total_symbols > 0 {
+            coverage.documented_ratio =
+                coverage.documented_symbols as f64 / coverage.total_symbols as f64;
+        }
+        coverage
+    }
+}
+
+/// Leverage of the shared code: how many affected app lines each shared line accounts for
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SharedCodeRoi {
+    /// Code lines of the shared modules' production source sets
+    pub shared_lines: usize,
+    /// Affected app lines summed over platforms
+    pub affected_app_lines: usize,
+    /// Affected app lines per platform
+    pub platform_affected_lines: BTreeMap<String, usize>,
+    /// `affected_app_lines / shared_lines`, 0 without shared code
+    pub leverage: f64,
+    /// How `leverage` is computed, for readers of the JSON report
+    pub formula: String,
+}
+
+impl SharedCodeRoi {
+    pub const FORMULA: &'static str = "leverage = sum(affected app lines per platform) / shared code lines";
+
+    pub fn new(shared_lines: usize, platform_impacts: &HashMap<String, PlatformImpact>) -> Self {
+        let platform_affected_lines: BTreeMap<String, usize> = platform_impacts
+            .iter()
+            .map(|(name, impact)| (name.clone(), impact.affected_lines))
+            .collect();
+        let affected_app_lines = platform_affected_lines.values().sum();
+        let leverage = if shared_lines > 0 {
+            affected_app_lines as f64 / shared_lines as f64
+        } else {
+            0.0
+        };
+
+        Self {
+            shared_lines,
+            affected_app_lines,
+            platform_affected_lines,
+            leverage,
+            formula: Self::FORMULA.to_string(),
+        }
+    }
+
+    /// One-line reading of the leverage for report summaries
+    pub fn describe(&self) -> String {
+        format!(
+            "1 shared line influences {:.1} app lines ({} / {})",
+            self.leverage, self.affected_app_lines, self.shared_lines
+        )
+    }
+}
+
+/// Which app platforms consume a shared symbol
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Parity {
+    BothPlatforms,
+    AndroidOnly,
+    IosOnly,
+    Unused,
+}
+
+/// Parity of a single shared symbol, with its references per platform
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolParity {
+    pub symbol_name: String,
+    pub parity: Parity,
+    pub android_references: usize,
+    pub ios_references: usize,
+}
+
+/// Cross-platform parity of the shared API
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ParitySummary {
+    pub both_platforms: usize,
+    pub android_only: usize,
+    pub ios_only: usize,
+    pub unused: usize,
+    /// Every shared symbol name, sorted by name
+    pub symbols: Vec<SymbolParity>,
+    /// Android-only symbols, most referenced first: candidates for iOS adoption
+    pub android_only_candidates: Vec<(String, usize)>,
+    /// iOS-only symbols, most referenced first: candidates for Android adoption
+    pub ios_only_candidates: Vec<(String, usize)>,
+}
+
+impl ParitySummary {
+    /// Maximum number of adoption candidates kept per platform
+    pub const MAX_CANDIDATES: usize = 10;
+
+    pub fn from_usages(
+        symbols: &[Symbol],
+        symbol_usages: &HashMap<String, Vec<SymbolUsage>>,
+        file_platforms: &HashMap<&str, &Platform>,
+    ) -> Self {
+        let names: BTreeSet<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        let mut summary = Self::default();
+
+        for name in names {
+            let mut android_references = 0;
+            let mut ios_references = 0;
+            for usage in symbol_usages.get(name).into_iter().flatten() {
+                match file_platforms.get(usage.file_path.as_str()) {
+                    Some(Platform::Android) => android_references += 1,
+                    Some(Platform::IOS) => ios_references += 1,
+                    // Parity compares the mobile apps; the backend and web are not adoption targets
+                    Some(Platform::Server | Platform::Desktop | Platform::Web) | None => {}
+                }
+            }
+
+            let parity = match (android_references > 0, ios_references > 0) {
+                (true, true) => Parity::BothPlatforms,
+                (true, false) => Parity::AndroidOnly,
+                (false, true) => Parity::IosOnly,
+                (false, false) => Parity::Unused,
+            };
+            match parity {
+                Parity::BothPlatforms => summary.both_platforms += 1,
+                Parity::AndroidOnly => {
+                    summary.android_only += 1;
+                    summary
+                        .android_only_candidates
+                        .push((name.to_string(), android_references));
+                }
+                Parity::IosOnly => {
+                    summary.ios_only += 1;
+                    summary.ios_only_candidates.push((name.to_string(), ios_references));
+                }
+                Parity::Unused => summary.unused += 1,
+            }
+
+            summary.symbols.push(SymbolParity {
+                symbol_name: name.to_string(),
+                parity,
+                android_references,
+                ios_references,
+            });
+        }
+
+        for candidates in [
+            &mut summary.android_only_candidates,
+            &mut summary.ios_only_candidates,
+        ] {
+            candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            candidates.truncate(Self::MAX_CANDIDATES);
+        }
+
+        summary
+    }
+}
+
+/// How much of the shared API makes it into app code, stage by stage
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ApiFunnel {
+    /// Public shared symbols declared
+    pub declared: usize,
+    /// One funnel per platform with app code, Android first
+    pub platforms: Vec<PlatformFunnel>,
+}
+
+/// Shared symbols that reach one platform's app code at each stage
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct PlatformFunnel {
+    pub platform: String,
+    /// Declared in a source set the platform compiles (`commonMain`, `iosMain`, ...)
+    pub exported: usize,
+    /// Imported by name, or referenced, by the platform's app files; Swift imports whole
+    /// frameworks, so iOS only counts references here
+    pub imported: usize,
+    /// Referenced in the platform's app code beyond import statements
+    pub referenced: usize,
+}
+
+impl PlatformFunnel {
+    /// The stage losing the most symbols: the names of the stages before and after it,
+    /// with their counts; `None` when no stage loses any
+    pub fn largest_drop(&self, declared: usize) -> Option<(&'static str, &'static str, usize, usize)> {
+        [
+            ("declared", "exported", declared, self.exported),
+            ("exported", "imported", self.exported, self.imported),
+            ("imported", "referenced", self.imported, self.referenced),
+        ]
+        .into_iter()
+        .filter(|(_, _, before, after)| after < before)
+        .max_by_key(|(_, _, before, after)| before - after)
+    }
+}
+
+impl ApiFunnel {
+    pub fn from_usages(
+        symbols: &[Symbol],
+        symbol_usages: &HashMap<String, Vec<SymbolUsage>>,
+        imports: &HashMap<String, Vec<SymbolUsage>>,
+        file_platforms: &HashMap<&str, &Platform>,
+    ) -> Self {
+        let public: Vec<&Symbol> = symbols.iter().filter(|s| s.is_public).collect();
+        let names = |platform: &Platform, usages: &HashMap<String, Vec<SymbolUsage>>| -> HashSet<String> {
+            usages
+                .iter()
+                .filter(|(_, usages)| {
+                    usages
+                        .iter()
+                        .any(|u| file_platforms.get(u.file_path.as_str()) == Some(&platform))
+                })
+                .map(|(name, _)| name.clone())
+                .collect()
+        };
+
+        let platforms = Platform::ALL
+            .into_iter()
+            .filter(|platform| file_platforms.values().any(|p| *p == platform))
+            .map(|platform| {
+                let exported: Vec<&&Symbol> = public.iter().filter(|s| s.exported_to(&platform)).collect();
+                let referenced = names(&platform, symbol_usages);
+                let imported: HashSet<String> = names(&platform, imports).union(&referenced).cloned().collect();
+                PlatformFunnel {
+                    platform: platform.name().to_string(),
+                    exported: exported.len(),
+                    imported: exported.iter().filter(|s| imported.contains(&s.name)).count(),
+                    referenced: exported.iter().filter(|s| referenced.contains(&s.name)).count(),
+                }
+            })
+            .collect();
+
+        Self {
+            declared: public.len(),
+            platforms,
+        }
+    }
+
+    /// The platform and stage losing the most symbols, e.g.
+    /// `iOS loses most between exported and imported (80 → 12)`
+    pub fn describe_largest_drop(&self) -> Option<String> {
+        self.platforms
+            .iter()
+            .filter_map(|funnel| Some((funnel, funnel.largest_drop(self.declared)?)))
+            .max_by_key(|(_, (_, _, before, after))| before - after)
+            .map(|(funnel, (from, to, before, after))| {
+                format!(
+                    "{} loses most between {} and {} ({} → {})",
+                    funnel.platform, from, to, before, after
+                )
+            })
+    }
+}
+
+impl ImpactAnalysis {
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the impact ratio in the analysis' [`ImpactMetric`]
+    pub fn calculate_impact_ratio(&mut self) {
+        let ratio = self.exact_impact_ratio();
+        if ratio.denominator > 0 {
+            self.impact_ratio = ratio.value();
+        }
+    }
+
+    /// Affected over total app code in the analysis' [`ImpactMetric`], exactly
+    pub fn exact_impact_ratio(&self) -> Ratio {
+        let (affected, total) = self.affected_of_total();
+        Ratio::new(affected, total)
+    }
+
+    /// Affected and total app code counted in the analysis' [`ImpactMetric`]
+    pub fn affected_of_total(&self) -> (usize, usize) {
+        match self.metric {
+            ImpactMetric::Lines => (self.affected_lines, self.total_app_lines),
+            metric => (self.metrics.get(metric).affected, self.metrics.get(metric).total),
+        }
+    }
+
+    /// Enforced coverage goals the analysis fell short of
+    pub fn missed_enforced_goals(&self) -> Vec<&GoalProgress> {
+        self.goals.iter().filter(|g| g.enforce && !g.met).collect()
+    }
+
+    /// Categories with an enforced threshold the analysis fell short of
+    pub fn missed_enforced_categories(&self) -> Vec<&CategoryUsage> {
+        self.categories
+            .iter()
+            .filter(|c| c.enforce && !c.meets_threshold())
+            .collect()
+    }
+
+    /// Why each broken threshold fails, in the order given
+    pub fn violated_thresholds(&self, thresholds: &[ImpactThreshold]) -> Vec<String> {
+        thresholds.iter().filter_map(|t| t.violation(self)).collect()
+    }
+
+    /// Total app usages of deprecated shared API across all platforms
+    pub fn deprecated_usage_count(&self) -> usize {
+        self.platform_impacts
+            .values()
+            .map(|p| p.deprecated_usages.len())
+            .sum()
+    }
+}
+
+impl PlatformImpact {
+    pub fn new(platform_name: String) -> Self {
+        Self {
+            platform_name,
+            ..Default::default()
+        }
+    }
+
+    pub fn calculate_impact_ratio(&mut self) {
+        if self.total_lines > 0 {
             self.impact_ratio = self.affected_lines as f64 / self.total_lines as f64;
         }
     }
+
+    /// Affected over total lines of the platform, exactly
+    pub fn exact_impact_ratio(&self) -> Ratio {
+        Ratio::new(self.affected_lines, self.total_lines)
+    }
+
+    /// Affected over total app code of the platform in `metric`, exactly
+    pub fn exact_ratio(&self, metric: ImpactMetric) -> Ratio {
+        match metric {
+            ImpactMetric::Lines => self.exact_impact_ratio(),
+            metric => self.metrics.get(metric).ratio(),
+        }
+    }
 }
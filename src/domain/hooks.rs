@@ -0,0 +1,73 @@
+//! Extension points for library consumers
+//! Filters registered here can rewrite or drop symbols and usages while the
+//! analysis runs, e.g. to apply company-specific naming conventions
+
+use std::fmt;
+use std::sync::Arc;
+
+use super::entities::{Symbol, SymbolUsage};
+
+/// Hook run on every extracted shared symbol before usages are searched
+pub trait SymbolFilter: Send + Sync {
+    /// Returns `false` to drop the symbol; may rewrite it in place
+    fn filter_symbol(&self, symbol: &mut Symbol) -> bool;
+}
+
+/// Hook run on every usage found in app code before it is counted
+pub trait UsageFilter: Send + Sync {
+    /// Returns `false` to drop the usage; may rewrite it in place
+    fn filter_usage(&self, usage: &mut SymbolUsage) -> bool;
+}
+
+/// Filters registered for an analysis, run in registration order
+#[derive(Clone, Default)]
+pub struct AnalysisHooks {
+    pub symbol_filters: Vec<Arc<dyn SymbolFilter>>,
+    pub usage_filters: Vec<Arc<dyn UsageFilter>>,
+}
+
+impl AnalysisHooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a filter for extracted symbols
+    pub fn with_symbol_filter(mut self, filter: impl SymbolFilter + 'static) -> Self {
+        self.symbol_filters.push(Arc::new(filter));
+        self
+    }
+
+    /// Registers a filter for detected usages
+    pub fn with_usage_filter(mut self, filter: impl UsageFilter + 'static) -> Self {
+        self.usage_filters.push(Arc::new(filter));
+        self
+    }
+
+    /// Runs the symbol filters, keeping the symbols none of them drops
+    pub fn filter_symbols(&self, symbols: Vec<Symbol>) -> Vec<Symbol> {
+        if self.symbol_filters.is_empty() {
+            return symbols;
+        }
+        symbols
+            .into_iter()
+            .filter_map(|mut symbol| {
+                let keep = self.symbol_filters.iter().all(|f| f.filter_symbol(&mut symbol));
+                keep.then_some(symbol)
+            })
+            .collect()
+    }
+
+    /// Runs the usage filters on one usage; `false` when one of them drops it
+    pub fn keep_usage(&self, usage: &mut SymbolUsage) -> bool {
+        self.usage_filters.iter().all(|f| f.filter_usage(usage))
+    }
+}
+
+impl fmt::Debug for AnalysisHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnalysisHooks")
+            .field("symbol_filters", &self.symbol_filters.len())
+            .field("usage_filters", &self.usage_filters.len())
+            .finish()
+    }
+}
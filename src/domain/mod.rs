@@ -1,8 +1,10 @@
-/// Domain layer - Core business entities and repository interfaces
-/// No dependencies on outer layers
+//! Domain layer - Core business entities and repository interfaces
+//! No dependencies on outer layers
 
 pub mod entities;
+pub mod hooks;
 pub mod repositories;
 
 pub use entities::*;
+pub use hooks::*;
 pub use repositories::*;
@@ -1,13 +1,40 @@
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use super::entities::{Platform, SourceFile, Symbol, SymbolUsage};
+use super::entities::{
+    DependencyEdge, FrameworkHeader, GradleModule, ImplicitPublicDeclaration, ModuleDependency, OwnerRule, Platform, ProjectDetection, RunSnapshot, RunStats, SourceFile, SuppressionBaseline, SwiftModule, Symbol, SymbolHistory, SymbolUsage,
+};
 
 /// Repository interface for symbol extraction
 /// Implemented by adapters layer
 pub trait SymbolRepository: Send + Sync {
     /// Extract all public symbols from KMP source files
     fn extract_kmp_symbols(&self, kmp_file_paths: &[String]) -> Result<Vec<Symbol>>;
+
+    /// Find accessors of shared resources (moko-resources, libres) of the project's KMP modules
+    fn extract_resource_symbols(&self, _project_path: &str) -> Result<Vec<Symbol>> {
+        Ok(Vec::new())
+    }
+
+    /// Find types code generators (SQLDelight, Apollo) produce for the project's KMP modules
+    fn extract_generated_symbols(&self, _project_path: &str) -> Result<Vec<Symbol>> {
+        Ok(Vec::new())
+    }
+
+    /// Read the Objective-C headers Kotlin/Native generated for the project's frameworks
+    fn find_framework_headers(&self, _project_path: &str, _kmp_file_paths: &[String]) -> Result<Vec<FrameworkHeader>> {
+        Ok(Vec::new())
+    }
+
+    /// Read the package owners declared by `OWNERS` files of the KMP files' modules
+    fn find_owner_rules(&self, _kmp_file_paths: &[String]) -> Result<Vec<OwnerRule>> {
+        Ok(Vec::new())
+    }
+
+    /// Find declarations of modules in explicit API mode that are public without saying so
+    fn find_implicit_public_declarations(&self, _kmp_file_paths: &[String]) -> Result<Vec<ImplicitPublicDeclaration>> {
+        Ok(Vec::new())
+    }
 }
 
 /// Repository interface for source file operations
@@ -19,11 +46,29 @@ pub trait SourceFileRepository: Send + Sync {
     /// Find all app source files grouped by platform
     fn find_app_files(&self, project_path: &str) -> Result<HashMap<Platform, Vec<String>>>;
 
+    /// Projects detected in the project, with the strategy that found each one
+    fn find_projects(&self, project_path: &str) -> Result<Vec<ProjectDetection>>;
+
     /// Read and parse a source file
     fn read_source_file(&self, file_path: &str) -> Result<SourceFile>;
 
     /// Count code lines in content (excluding comments/empty lines)
     fn count_code_lines(&self, content: &str, platform: Platform) -> usize;
+
+    /// Count function and method declarations in content
+    fn count_functions(&self, _content: &str, _platform: Platform) -> usize {
+        0
+    }
+
+    /// Whether content only declares UI layout, such as a Compose screen or a SwiftUI view
+    fn is_ui_layout(&self, _content: &str, _platform: Platform) -> bool {
+        false
+    }
+
+    /// Whether content imports the shared framework as a whole, such as Swift's `import Shared`
+    fn imports_shared_framework(&self, _content: &str, _platform: Platform) -> bool {
+        false
+    }
 }
 
 /// Repository interface for symbol usage detection
@@ -43,10 +88,68 @@ pub trait DependencyRepository: Send + Sync {
     /// Build dependency graph from source files
     fn build_dependency_graph(&self, file_paths: &[String]) -> Result<()>;
 
+    /// Find Gradle module dependencies of app modules on KMP modules
+    fn build_module_graph(&self, project_path: &str) -> Result<Vec<ModuleDependency>>;
+
+    /// Find Android Gradle modules and their dependencies on each other
+    fn find_android_modules(&self, project_path: &str) -> Result<Vec<GradleModule>>;
+
+    /// Find Swift modules (SPM and Xcode targets) and their dependencies on each other
+    fn find_swift_modules(&self, project_path: &str) -> Result<Vec<SwiftModule>>;
+
     /// Calculate transitive dependencies for given files
     fn calculate_transitive_dependencies(&self, direct_files: &[String]) -> Result<Vec<String>>;
 
+    /// Calculate the files each given file depends on, directly or transitively
+    fn calculate_file_dependencies(&self, files: &[String]) -> Result<HashMap<String, HashSet<String>>>;
+
+    /// Find the edges from a file to the files it imports, with the import statements behind them
+    fn find_dependency_edges(&self, file: &str) -> Result<Vec<DependencyEdge>>;
+
+    /// Find the shortest chain of dependency edges from a file to any of the target files
+    fn find_dependency_chain(&self, file: &str, targets: &HashSet<String>) -> Result<Vec<DependencyEdge>>;
+
     /// Extract imports from a source file
     #[allow(dead_code)]
     fn extract_imports(&self, source_file: &SourceFile) -> Result<Vec<String>>;
 }
+
+/// Repository interface for the symbol history kept between runs
+/// Implemented by adapters layer
+pub trait SymbolHistoryRepository: Send + Sync {
+    /// Load the stored history, or `None` before the first run
+    fn load(&self) -> Result<Option<SymbolHistory>>;
+
+    /// Persist the history for the next run
+    fn save(&self, history: &SymbolHistory) -> Result<()>;
+}
+
+/// Repository interface for the local run statistics
+/// Implemented by adapters layer
+pub trait RunStatsRepository: Send + Sync {
+    /// Load the accumulated statistics; empty when none were recorded yet
+    fn load(&self) -> Result<RunStats>;
+
+    /// Persist the statistics
+    fn save(&self, stats: &RunStats) -> Result<()>;
+}
+
+/// Repository interface for the suppression baseline
+/// Implemented by adapters layer
+pub trait SuppressionRepository: Send + Sync {
+    /// Load the baseline, empty when none was written yet
+    fn load(&self) -> Result<SuppressionBaseline>;
+
+    /// Persist the baseline
+    fn save(&self, baseline: &SuppressionBaseline) -> Result<()>;
+}
+
+/// Repository interface for the headline numbers of past runs
+/// Implemented by adapters layer
+pub trait RunHistoryRepository: Send + Sync {
+    /// Load the stored runs, oldest first
+    fn load(&self) -> Result<Vec<RunSnapshot>>;
+
+    /// Persist the runs for the next analysis
+    fn save(&self, runs: &[RunSnapshot]) -> Result<()>;
+}
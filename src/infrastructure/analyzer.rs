@@ -0,0 +1,118 @@
+use anyhow::Result;
+use std::sync::Arc;
+
+use crate::adapters::file_system::{FileSystem, OsFileSystem};
+use crate::adapters::symbol_manifest::SymbolManifest;
+use crate::adapters::{
+    DependencyRepositoryImpl, FederatedSymbolRepository, IgnoringFileSystem, SourceFileRepositoryImpl,
+    SymbolRepositoryImpl, SymbolUsageRepositoryImpl, WarmStart, WarmStartSymbolRepository,
+    WarmStartUsageRepository,
+};
+use crate::domain::{ImpactAnalysis, SymbolRepository, SymbolUsageRepository, WarmStartState};
+use crate::use_cases::{AnalysisOptions, AnalyzeImpactUseCase};
+
+/// Reusable, thread-safe analysis handle
+///
+/// Cloning is cheap and clones share the file and symbol caches, so a
+/// long-lived process can run concurrent analyses of the same project and
+/// only re-read files that changed since the previous run.
+#[derive(Clone)]
+pub struct Analyzer {
+    inner: Arc<AnalyzerInner>,
+}
+
+struct AnalyzerInner {
+    fs: Arc<dyn FileSystem>,
+    symbol_repository: SymbolRepositoryImpl,
+    source_file_repository: SourceFileRepositoryImpl,
+    symbol_usage_repository: SymbolUsageRepositoryImpl,
+    options: AnalysisOptions,
+    manifests: Vec<SymbolManifest>,
+}
+
+impl Analyzer {
+    pub fn new() -> Self {
+        Self::with_configuration(AnalysisOptions::default(), Vec::new())
+    }
+
+    /// Creates a handle with analysis options and federated symbol manifests
+    pub fn with_configuration(options: AnalysisOptions, manifests: Vec<SymbolManifest>) -> Self {
+        Self::with_file_system(Arc::new(OsFileSystem), options, manifests)
+    }
+
+    /// Creates a handle that reads the project through the given file system
+    /// (e.g. an in-memory tree or an archive snapshot), skipping the paths the
+    /// detection settings ignore
+    pub fn with_file_system(
+        fs: Arc<dyn FileSystem>,
+        options: AnalysisOptions,
+        manifests: Vec<SymbolManifest>,
+    ) -> Self {
+        let fs = IgnoringFileSystem::wrap(fs, &options.detection);
+        Self {
+            inner: Arc::new(AnalyzerInner {
+                symbol_repository: SymbolRepositoryImpl::new()
+                    .with_file_system(fs.clone())
+                    .with_parser(options.parser)
+                    .with_objc_headers(options.objc_headers.clone()),
+                source_file_repository: SourceFileRepositoryImpl::new()
+                    .with_file_system(fs.clone())
+                    .with_sample_modules(options.include_sample_modules)
+                    .with_detection(options.detection.clone()),
+                symbol_usage_repository: SymbolUsageRepositoryImpl::new().with_match_engine(options.matcher),
+                fs,
+                options,
+                manifests,
+            }),
+        }
+    }
+
+    /// Runs a complete impact analysis of the project
+    pub fn analyze(&self, project_path: &str) -> Result<ImpactAnalysis> {
+        let inner = &self.inner;
+        self.run(project_path, &inner.symbol_repository, &inner.symbol_usage_repository)
+    }
+
+    /// Runs the analysis reusing `previous` results for files whose content is unchanged,
+    /// and records this run's results in the analysis for the next one
+    pub fn analyze_with_warm_start(&self, project_path: &str, previous: WarmStartState) -> Result<ImpactAnalysis> {
+        let inner = &self.inner;
+        let warm_start = WarmStart::new(previous, inner.options.parser);
+        let symbol_repository =
+            WarmStartSymbolRepository::new(&inner.symbol_repository, inner.fs.clone(), &warm_start);
+        let symbol_usage_repository = WarmStartUsageRepository::new(&inner.symbol_usage_repository, &warm_start);
+        let mut analysis = self.run(project_path, &symbol_repository, &symbol_usage_repository)?;
+        analysis.warm_start = Some(warm_start.finish());
+        Ok(analysis)
+    }
+
+    fn run(
+        &self,
+        project_path: &str,
+        symbol_repository: &dyn SymbolRepository,
+        symbol_usage_repository: &dyn SymbolUsageRepository,
+    ) -> Result<ImpactAnalysis> {
+        let inner = &self.inner;
+        let symbol_repository = FederatedSymbolRepository::new(symbol_repository, inner.manifests.clone());
+        // The dependency graph is per-run state, so each analysis gets its own
+        let dependency_repository = DependencyRepositoryImpl::new()
+            .with_file_system(inner.fs.clone())
+            .with_detection(inner.options.detection.clone())
+            .with_unused_imports(inner.options.count_imports);
+
+        AnalyzeImpactUseCase::new(
+            &symbol_repository,
+            &inner.source_file_repository,
+            symbol_usage_repository,
+            &dependency_repository,
+        )
+        .with_options(inner.options.clone())
+        .execute(project_path)
+    }
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,177 @@
+//! Batch analysis
+//! Runs the impact analysis over a list of repositories, local checkouts or
+//! clone URLs, with one shared configuration, so a platform org can track all
+//! of its apps in a single repository × platform matrix
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::adapters::symbol_manifest::SymbolManifest;
+use crate::adapters::OsFileSystem;
+use crate::domain::{BatchRepository, BatchSummary};
+use crate::infrastructure::project_path;
+use crate::infrastructure::Analyzer;
+use crate::use_cases::AnalysisOptions;
+use crate::utils::GitUtils;
+
+/// One entry of a repository list
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchSource {
+    /// Name shown in the matrix
+    pub name: String,
+    /// Local path (relative to the list file) or clone URL
+    pub location: String,
+}
+
+impl BatchSource {
+    /// Parses a repository list: one `<path-or-url> [name]` per line
+    ///
+    /// Blank lines and lines starting with `#` are skipped. Without a name, the
+    /// last path segment is used, minus a `.git` suffix.
+    pub fn parse_list(content: &str) -> Result<Vec<Self>> {
+        let mut sources: Vec<Self> = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let location = fields.next().unwrap_or_default().to_string();
+            let name = fields.next().map_or_else(|| Self::default_name(&location), str::to_string);
+            if fields.next().is_some() {
+                anyhow::bail!("Line {}: expected `<path-or-url> [name]`, found `{}`", index + 1, line);
+            }
+            if sources.iter().any(|source| source.name == name) {
+                anyhow::bail!("Line {}: repository name `{}` is used twice", index + 1, name);
+            }
+            sources.push(Self { name, location });
+        }
+        Ok(sources)
+    }
+
+    /// Whether the location is a clone URL rather than a local path
+    pub fn is_remote(&self) -> bool {
+        self.location.contains("://") || self.location.starts_with("git@")
+    }
+
+    fn default_name(location: &str) -> String {
+        let trimmed = location.trim_end_matches('/');
+        let last = trimmed.rsplit(['/', ':']).next().unwrap_or(trimmed);
+        last.strip_suffix(".git").unwrap_or(last).to_string()
+    }
+}
+
+/// Checks out and analyzes each repository of a batch
+pub struct BatchRunner {
+    options: AnalysisOptions,
+    manifests: Vec<SymbolManifest>,
+    /// Directory local paths of the list resolve against
+    base_dir: PathBuf,
+    /// Directory remote repositories are cloned into, one subdirectory per name
+    clone_dir: PathBuf,
+}
+
+impl BatchRunner {
+    /// Default clone directory, relative to the repository list
+    pub const DEFAULT_CLONE_DIR: &'static str = ".kmp-coverage/batch";
+
+    pub fn new(options: AnalysisOptions, manifests: Vec<SymbolManifest>, base_dir: &Path) -> Self {
+        Self {
+            options,
+            manifests,
+            base_dir: base_dir.to_path_buf(),
+            clone_dir: base_dir.join(Self::DEFAULT_CLONE_DIR),
+        }
+    }
+
+    /// Clones remote repositories into `clone_dir` instead of the default
+    pub fn with_clone_dir(mut self, clone_dir: Option<&Path>) -> Self {
+        if let Some(clone_dir) = clone_dir {
+            self.clone_dir = clone_dir.to_path_buf();
+        }
+        self
+    }
+
+    /// Analyzes every repository; one that fails is recorded in the summary, not fatal
+    pub fn run(&self, sources: &[BatchSource]) -> BatchSummary {
+        let repositories = sources
+            .iter()
+            .map(|source| {
+                info!("Analyzing repository {} ({})", source.name, source.location);
+                self.analyze(source).unwrap_or_else(|error| {
+                    // One line, so the error fits a matrix cell; hints follow after `;`
+                    let error = format!("{:#}", error)
+                        .lines()
+                        .map(str::trim)
+                        .collect::<Vec<_>>()
+                        .join("; ");
+                    warn!("Skipping repository {}: {}", source.name, error);
+                    BatchRepository::failed(&source.name, &source.location, error)
+                })
+            })
+            .collect();
+        BatchSummary { repositories }
+    }
+
+    fn analyze(&self, source: &BatchSource) -> Result<BatchRepository> {
+        let path = self.checkout(source)?;
+        let root = path.to_string_lossy().to_string();
+        let fs = Arc::new(OsFileSystem);
+        project_path::validate_projects(fs.as_ref(), &path, &source.location, &self.options.detection)?;
+        let analysis = Analyzer::with_file_system(fs, self.options.clone(), self.manifests.clone())
+            .analyze(&root)?;
+        Ok(BatchRepository::analyzed(
+            &source.name,
+            &source.location,
+            GitUtils::head_commit(&path),
+            &analysis,
+        ))
+    }
+
+    /// Directory holding the repository's sources, cloning it first when remote
+    fn checkout(&self, source: &BatchSource) -> Result<PathBuf> {
+        if source.is_remote() {
+            let destination = self.clone_dir.join(&source.name);
+            std::fs::create_dir_all(&self.clone_dir)
+                .with_context(|| format!("Failed to create {}", self.clone_dir.display()))?;
+            GitUtils::clone_or_reuse(&source.location, &destination)?;
+            return Ok(destination);
+        }
+
+        let path = self.base_dir.join(&source.location);
+        project_path::validate_on_disk(&path.to_string_lossy())?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_repository_list() {
+        let content = "# Apps tracked by the platform team\n\
+                       ../consumer-app\n\
+                       https://github.com/example/wallet-app.git wallet\n\
+                       \n\
+                       git@github.com:example/travel-app.git\n";
+        let sources = BatchSource::parse_list(content).unwrap();
+        let found: Vec<(&str, &str, bool)> = sources
+            .iter()
+            .map(|s| (s.name.as_str(), s.location.as_str(), s.is_remote()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("consumer-app", "../consumer-app", false),
+                ("wallet", "https://github.com/example/wallet-app.git", true),
+                ("travel-app", "git@github.com:example/travel-app.git", true),
+            ]
+        );
+
+        assert!(BatchSource::parse_list("apps/one\nother/one\n").is_err());
+        assert!(BatchSource::parse_list("apps/one name extra\n").is_err());
+    }
+}
@@ -0,0 +1,70 @@
+//! Machine-readable description of what this build of the tool supports
+//! Printed by `--version-json` so wrappers can check capabilities before running an analysis
+
+use serde::Serialize;
+
+use crate::adapters::symbol_manifest::MANIFEST_SCHEMA_VERSION;
+use crate::domain::Platform;
+use crate::infrastructure::reporters::ReportFormat;
+
+/// Version of the capability report itself; bumped when fields change meaning or go away
+pub const CAPABILITIES_SCHEMA_VERSION: u32 = 1;
+
+/// Capabilities of the running build
+#[derive(Debug, Clone, Serialize)]
+pub struct Capabilities {
+    pub schema_version: u32,
+    pub tool_version: String,
+    /// Platforms app code is detected and measured for
+    pub platforms: Vec<String>,
+    /// Values accepted by `--format`
+    pub formats: Vec<String>,
+    /// Optional features compiled into this build
+    pub features: Vec<String>,
+    /// Schema version of symbol manifests written by `export-symbols` and accepted by `--symbols-from`
+    pub manifest_schema_version: u32,
+}
+
+impl Capabilities {
+    pub fn current() -> Self {
+        let mut features = vec!["source-archives", "symbol-manifests", "report-signing", "history"];
+        if cfg!(not(target_arch = "wasm32")) {
+            features.extend(["remote-manifests", "git-metadata", "self-update"]);
+        }
+        if cfg!(feature = "treesitter") {
+            features.push("treesitter");
+        }
+
+        Self {
+            schema_version: CAPABILITIES_SCHEMA_VERSION,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            platforms: Platform::ALL
+                .iter()
+                .map(|p| p.name().to_string())
+                .collect(),
+            formats: ReportFormat::NAMES.iter().map(|f| f.to_string()).collect(),
+            features: features.into_iter().map(str::to_string).collect(),
+            manifest_schema_version: MANIFEST_SCHEMA_VERSION,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::Reporter;
+
+    #[test]
+    fn test_capabilities() {
+        let capabilities = Capabilities::current();
+        assert_eq!(capabilities.platforms, vec!["Android", "iOS", "Server", "Desktop", "Web"]);
+        // Every advertised format is accepted
+        for format in &capabilities.formats {
+            assert!(Reporter::new(format).is_ok(), "{}", format);
+        }
+
+        let json = serde_json::to_value(&capabilities).unwrap();
+        assert_eq!(json["schema_version"], CAPABILITIES_SCHEMA_VERSION);
+        assert_eq!(json["tool_version"], env!("CARGO_PKG_VERSION"));
+    }
+}
@@ -0,0 +1,867 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::adapters::FileSystem;
+use crate::infrastructure::reporters::NumberFormat;
+use crate::domain::{AppLayer, AppLayers, CoverageGoal, DetectionSettings, FileFilter, ImpactMetric, ImpactThreshold, InputLimits, Platform, Severity, StabilityTiers, Ratio, RatioPrecision, Rounding, SymbolCategories, SymbolCategory, SymbolParser, MatchEngine, FrameworkHeaderSources, ThresholdKind};
+use crate::use_cases::{AnalysisOptions, DriftPolicy};
+
+/// File name looked up at the project root when no `--config` is given
+pub const CONFIG_FILE_NAME: &str = "kmp-coverage.toml";
+
+/// Project configuration loaded from `kmp-coverage.toml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub analysis: AnalysisConfig,
+    pub categories: CategoriesConfig,
+    pub detection: DetectionConfig,
+    pub federation: FederationConfig,
+    /// `[goals]` section: target impact percentage per platform or app module
+    pub goals: BTreeMap<String, GoalConfig>,
+    pub history: HistoryConfig,
+    pub layers: LayersConfig,
+    pub limits: LimitsConfig,
+    pub lint: LintConfig,
+    pub modules: ModulesConfig,
+    pub platforms: PlatformsConfig,
+    pub report: ReportConfig,
+    pub stability: StabilityConfig,
+    pub stats: StatsConfig,
+    pub suppressions: SuppressionsConfig,
+    pub thresholds: ThresholdsConfig,
+    pub update: UpdateConfig,
+}
+
+/// `[analysis]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnalysisConfig {
+    /// Attribute usages of `actual typealias` targets to the shared alias
+    pub resolve_actual_typealiases: bool,
+    /// Count sample, demo, and benchmark app modules as app code
+    pub include_sample_modules: bool,
+    /// Extract symbols declared in test source sets as shared API
+    pub include_test_symbols: bool,
+    /// Extract `internal` declarations as shared API
+    pub include_internal: bool,
+    /// Android product flavors or iOS schemes to report impact for separately
+    pub variants: Vec<String>,
+    /// Count import statements, and files that only import from affected files, as impact
+    pub count_imports: bool,
+    /// Shared entry points reported per platform (default: 5)
+    pub max_entry_points: Option<usize>,
+    /// App paths counted as UI layout besides recognized Compose and SwiftUI layout files
+    pub ui_layout_paths: Vec<String>,
+    /// Parser of shared Kotlin declarations: `regex` (default) or `treesitter`
+    pub parser: SymbolParser,
+    /// Engine app files are searched for symbol names with: `aho-corasick` (default), `regex`, or `literal`
+    pub matcher: MatchEngine,
+    /// Kotlin/Native framework headers (`Shared.h`), relative to the project root, to read the
+    /// exact Objective-C and Swift names of shared classes from
+    pub objc_headers: Vec<String>,
+    /// Also read the framework headers found in the KMP modules' build output
+    pub find_objc_headers: bool,
+    /// What impact coverage counts: `files`, `lines` (default), or `functions`
+    pub metric: ImpactMetric,
+}
+
+/// `[categories]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CategoriesConfig {
+    /// Ordered category rules; replaces the built-in categories when non-empty
+    pub rules: Vec<CategoryRuleConfig>,
+}
+
+/// One `[[categories.rules]]` entry; a symbol is included when any pattern matches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryRuleConfig {
+    pub name: String,
+    /// Package prefixes (`com.example.network`) or segments (`*.model`)
+    #[serde(default)]
+    pub packages: Vec<String>,
+    /// Annotations, simple or qualified (`Serializable`)
+    #[serde(default)]
+    pub annotations: Vec<String>,
+    /// Symbol name suffixes (`Repository`)
+    #[serde(default)]
+    pub suffixes: Vec<String>,
+    /// Minimum percentage (0 ~ 100) of the category's shared symbols app code should use
+    #[serde(default)]
+    pub threshold: Option<f64>,
+    /// Fail the run when the threshold is missed
+    #[serde(default)]
+    pub enforce: bool,
+}
+
+/// `[layers]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayersConfig {
+    /// Ordered layer rules; replaces the built-in presentation, domain, and data layers when non-empty
+    pub rules: Vec<LayerRuleConfig>,
+}
+
+/// One `[[layers.rules]]` entry; an app file is included when any pattern matches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayerRuleConfig {
+    pub name: String,
+    /// Package prefixes (`com.example.feature.ui`) or segments (`*.ui`)
+    #[serde(default)]
+    pub packages: Vec<String>,
+    /// Paths: `dir/` matches a directory, `*suffix` the end of a file name
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+/// `[detection]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DetectionConfig {
+    /// Directory levels below a search root that projects are looked for in
+    pub depth: usize,
+    /// Directories, relative to the project root, searched for nested projects
+    pub root_hints: Vec<String>,
+    /// Directory names skipped when collecting iOS app files; `[]` scans them all
+    pub ios_excluded_dirs: Vec<String>,
+    /// Paths skipped when collecting Android app files (`build/`, `*FileGenerated.kt`); `[]` scans them all
+    pub android_excluded_paths: Vec<String>,
+    /// Globs, relative to the project root, of the only files scanned
+    pub include: Vec<String>,
+    /// Globs, relative to the project root, of files never scanned
+    pub exclude: Vec<String>,
+    /// Detect projects from Bazel and Buck `BUILD` targets too
+    pub build_targets: bool,
+    /// Directory names never traversed (`build`, `.gradle`, `DerivedData`, ...); `[]` traverses them all
+    pub ignored_dirs: Vec<String>,
+    /// Skip what `.gitignore` files ignore inside a git work tree
+    pub ignore_files: bool,
+}
+
+impl Default for DetectionConfig {
+    fn default() -> Self {
+        let detection = DetectionSettings::default();
+        Self {
+            depth: detection.depth,
+            root_hints: detection.root_hints,
+            ios_excluded_dirs: detection.ios_excluded_dirs,
+            android_excluded_paths: detection.android_excluded_paths,
+            include: detection.files.include,
+            exclude: detection.files.exclude,
+            build_targets: detection.build_targets,
+            ignored_dirs: detection.ignored_dirs,
+            ignore_files: detection.ignore_files,
+        }
+    }
+}
+
+/// `[federation]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FederationConfig {
+    /// Symbol manifests (paths relative to the project root, or URLs) of shared code in other repositories
+    pub sources: Vec<String>,
+}
+
+/// One `[goals]` entry: `android = 40` or `"feature-payments" = { target = 60, enforce = true }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GoalConfig {
+    Target(f64),
+    Detailed(GoalTableConfig),
+}
+
+/// Table form of a `[goals]` entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GoalTableConfig {
+    /// Target impact percentage (0 ~ 100)
+    pub target: f64,
+    /// Fail the run when the goal is missed
+    #[serde(default)]
+    pub enforce: bool,
+}
+
+/// `[history]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistoryConfig {
+    /// Record every run so the HTML dashboard can chart trends
+    pub enabled: bool,
+    /// Run history SQLite database, relative to the project root
+    pub file: String,
+    /// Number of most recent runs kept
+    pub max_runs: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: ".kmp-coverage/run-history.db".to_string(),
+            max_runs: 365,
+        }
+    }
+}
+
+/// `[suppressions]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SuppressionsConfig {
+    /// Suppression baseline, relative to the project root; applied whenever it exists
+    pub file: String,
+}
+
+impl Default for SuppressionsConfig {
+    fn default() -> Self {
+        Self {
+            file: "kmp-coverage-suppressions.json".to_string(),
+        }
+    }
+}
+
+/// `[stats]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatsConfig {
+    /// Accumulate anonymous run statistics in a local file; nothing is sent anywhere
+    pub enabled: bool,
+    /// Statistics file, relative to the project root; by default a per-user file, so
+    /// the statistics never end up in the analyzed repository
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
+impl StatsConfig {
+    /// The configured `file` below `project_root`, else `run-stats.toml` in the user's
+    /// local data directory; `None` when the platform has none
+    pub fn stats_file(&self, project_root: &Path) -> Option<PathBuf> {
+        match &self.file {
+            Some(file) => Some(project_root.join(file)),
+            None => user_file(dirs::data_local_dir(), "run-stats.toml"),
+        }
+    }
+}
+
+/// `[update]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UpdateConfig {
+    /// Tell after a run when a newer release is published; asks GitHub at most once a day
+    pub notify: bool,
+    /// When the last check ran and what it found, relative to the project root; by default
+    /// a per-user file, since the check concerns the installed binary, not the project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
+impl UpdateConfig {
+    /// The configured `file` below `project_root`, else `update-check.json` in the user's
+    /// cache directory; `None` when the platform has none
+    pub fn state_file(&self, project_root: &Path) -> Option<PathBuf> {
+        match &self.file {
+            Some(file) => Some(project_root.join(file)),
+            None => user_file(dirs::cache_dir(), "update-check.json"),
+        }
+    }
+}
+
+/// `name` in this tool's directory below a per-user directory such as `~/.cache`
+fn user_file(user_dir: Option<PathBuf>, name: &str) -> Option<PathBuf> {
+    user_dir.map(|dir| dir.join("kotlin-multiplatform-coverage").join(name))
+}
+
+/// `[limits]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsConfig {
+    /// App files larger than this many bytes are not scanned for usages
+    pub max_file_bytes: usize,
+    /// Lines longer than this many bytes (e.g. minified code) are not scanned
+    pub max_line_length: usize,
+    /// Usage locations kept per symbol in a single file
+    pub max_usages_per_symbol_per_file: usize,
+    /// Characters of source kept as the context of a usage
+    pub max_context_length: usize,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        let limits = InputLimits::default();
+        Self {
+            max_file_bytes: limits.max_file_bytes,
+            max_line_length: limits.max_line_length,
+            max_usages_per_symbol_per_file: limits.max_usages_per_symbol_per_file,
+            max_context_length: limits.max_context_length,
+        }
+    }
+}
+
+/// `[lint]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    pub single_platform_drift: DriftConfig,
+}
+
+/// `[lint.single_platform_drift]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DriftConfig {
+    pub enabled: bool,
+    /// Flag new single-platform symbols seen in at least this many runs
+    pub after_runs: Option<u32>,
+    /// Flag new single-platform symbols first seen at least this many days ago
+    pub after_days: Option<u64>,
+    pub severity: Severity,
+    /// Symbol history file, relative to the project root
+    pub history_file: String,
+}
+
+impl Default for DriftConfig {
+    fn default() -> Self {
+        let policy = DriftPolicy::default();
+        Self {
+            enabled: false,
+            after_runs: policy.after_runs,
+            after_days: policy.after_days,
+            severity: policy.severity,
+            history_file: ".kmp-coverage/symbol-history.json".to_string(),
+        }
+    }
+}
+
+/// `[modules]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModulesConfig {
+    /// KMP module directories, relative to the project root; replaces the detected modules when non-empty
+    pub paths: Vec<String>,
+}
+
+/// `[platforms]` section: app directories per platform, relative to the project root
+///
+/// A platform given here has its detected projects replaced; the others are still detected.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlatformsConfig {
+    pub android: Vec<String>,
+    pub ios: Vec<String>,
+    pub server: Vec<String>,
+    pub desktop: Vec<String>,
+    pub web: Vec<String>,
+}
+
+impl PlatformsConfig {
+    /// Directories of the platforms given, in report order
+    pub fn paths(&self) -> Vec<(Platform, Vec<String>)> {
+        [
+            (Platform::Android, &self.android),
+            (Platform::IOS, &self.ios),
+            (Platform::Server, &self.server),
+            (Platform::Desktop, &self.desktop),
+            (Platform::Web, &self.web),
+        ]
+        .into_iter()
+        .filter(|(_, paths)| !paths.is_empty())
+        .map(|(platform, paths)| (platform, paths.clone()))
+        .collect()
+    }
+}
+
+/// `[thresholds]` section, in the syntax of `--min-impact` and `--max-impact`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThresholdsConfig {
+    /// Impact coverage the run must reach, `PERCENT` or `PLATFORM=PERCENT` (`["40", "ios=25"]`)
+    pub min_impact: Vec<String>,
+    /// Impact coverage the run must not exceed, in the same syntax
+    pub max_impact: Vec<String>,
+}
+
+/// `[report]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReportConfig {
+    /// Output format used when `--format` is not given (default: table)
+    pub format: Option<String>,
+    /// Base URL of the generated API docs used to link symbols
+    pub doc_base_url: Option<String>,
+    /// Embed highlighted source excerpts of the usage sites in the HTML dashboard
+    pub embed_sources: bool,
+    /// Size budget of the embedded excerpts (default: 1 MiB)
+    pub max_source_bytes: Option<usize>,
+    /// Sections of table and Markdown reports to show, e.g. `["platforms"]` or `["no-diagnostics"]` (default: all)
+    pub sections: Vec<String>,
+    /// Locale of the percentages and counts in table and Markdown reports, e.g. `de-DE` (default: `45.23%`, `1234`)
+    pub locale: Option<String>,
+    /// Decimal separator replacing the locale's
+    pub decimal_separator: Option<char>,
+    /// Thousands separator replacing the locale's; empty for ungrouped counts
+    pub grouping_separator: Option<String>,
+    /// Decimal places of percentages (default: 2)
+    pub precision: Option<u32>,
+    /// How percentages are rounded to their last decimal place (default: half-up)
+    pub rounding: Rounding,
+}
+
+/// `[stability]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StabilityConfig {
+    /// Ordered tier definitions; replaces the built-in tiers when non-empty
+    pub tiers: Vec<TierConfig>,
+}
+
+/// One `[[stability.tiers]]` entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TierConfig {
+    pub name: String,
+    pub annotations: Vec<String>,
+}
+
+impl Config {
+    /// Loads a configuration file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// Loads `kmp-coverage.toml` from the project root, or the defaults if absent
+    ///
+    /// The project may live on disk or inside a source archive, so the file is
+    /// read through the project's file system.
+    pub fn discover(fs: &dyn FileSystem, project_path: &Path) -> Result<Self> {
+        let path = project_path.join(CONFIG_FILE_NAME);
+        if !fs.is_file(&path) {
+            return Ok(Self::default());
+        }
+        let content = fs.read_to_string(&path)?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// Analysis options this configuration asks for
+    pub fn analysis_options(&self) -> AnalysisOptions {
+        AnalysisOptions {
+            stability_tiers: self.stability_tiers(),
+            categories: self.symbol_categories(),
+            layers: self.app_layers(),
+            resolve_actual_typealiases: self.analysis.resolve_actual_typealiases,
+            include_sample_modules: self.analysis.include_sample_modules,
+            include_test_symbols: self.analysis.include_test_symbols,
+            include_internal: self.analysis.include_internal,
+            count_imports: self.analysis.count_imports,
+            max_entry_points: self.analysis.max_entry_points,
+            ui_layout_paths: self.analysis.ui_layout_paths.clone(),
+            input_limits: self.input_limits(),
+            goals: self.coverage_goals(),
+            detection: self.detection_settings(),
+            parser: self.analysis.parser,
+            matcher: self.analysis.matcher,
+            objc_headers: FrameworkHeaderSources {
+                paths: self.analysis.objc_headers.clone(),
+                find_in_build_output: self.analysis.find_objc_headers,
+            },
+            metric: self.analysis.metric,
+            ..Default::default()
+        }
+    }
+
+    /// Separators of report numbers for `locale` (the `--locale` override), else `[report] locale`,
+    /// and percentages with `precision` decimal places (the `--precision` override), else `[report] precision`
+    pub fn number_format(&self, locale: Option<&str>, precision: Option<u32>) -> Result<NumberFormat> {
+        let mut numbers = match locale.or(self.report.locale.as_deref()) {
+            Some(locale) => NumberFormat::for_locale(locale)?,
+            None => NumberFormat::default(),
+        };
+        if let Some(separator) = self.report.decimal_separator {
+            numbers = numbers.with_decimal_separator(separator);
+        }
+        if let Some(separator) = &self.report.grouping_separator {
+            let mut chars = separator.chars();
+            let separator = match (chars.next(), chars.next()) {
+                (separator, None) => separator,
+                _ => anyhow::bail!("`[report] grouping_separator` must be one character or empty, got `{}`", separator),
+            };
+            numbers = numbers.with_grouping_separator(separator);
+        }
+        let decimals = precision.or(self.report.precision).unwrap_or(RatioPrecision::default().decimals);
+        if decimals > RatioPrecision::MAX_DECIMALS {
+            anyhow::bail!("Percentages have at most {} decimal places, got {}", RatioPrecision::MAX_DECIMALS, decimals);
+        }
+        Ok(numbers.with_precision(RatioPrecision {
+            decimals,
+            rounding: self.report.rounding,
+        }))
+    }
+
+    /// Policy for the single-platform drift lint
+    pub fn drift_policy(&self) -> DriftPolicy {
+        let drift = &self.lint.single_platform_drift;
+        DriftPolicy {
+            after_runs: drift.after_runs,
+            after_days: drift.after_days,
+            severity: drift.severity,
+        }
+    }
+
+    /// Coverage goals, in name order
+    pub fn coverage_goals(&self) -> Vec<CoverageGoal> {
+        self.goals
+            .iter()
+            .map(|(name, goal)| {
+                let (target, enforce) = match goal {
+                    GoalConfig::Target(target) => (*target, false),
+                    GoalConfig::Detailed(goal) => (goal.target, goal.enforce),
+                };
+                CoverageGoal {
+                    name: name.clone(),
+                    target: Ratio::parse_percent(&target.to_string()).unwrap_or_else(|_| Ratio::approximating(target / 100.0)),
+                    enforce,
+                }
+            })
+            .collect()
+    }
+
+    /// Depth and extra roots of project detection
+    pub fn detection_settings(&self) -> DetectionSettings {
+        DetectionSettings {
+            depth: self.detection.depth,
+            root_hints: self.detection.root_hints.clone(),
+            module_paths: self.modules.paths.clone(),
+            platform_paths: self.platforms.paths(),
+            variants: self.analysis.variants.clone(),
+            ios_excluded_dirs: self.detection.ios_excluded_dirs.clone(),
+            android_excluded_paths: self.detection.android_excluded_paths.clone(),
+            files: FileFilter {
+                include: self.detection.include.clone(),
+                exclude: self.detection.exclude.clone(),
+            },
+            build_targets: self.detection.build_targets,
+            ignored_dirs: self.detection.ignored_dirs.clone(),
+            ignore_files: self.detection.ignore_files,
+        }
+    }
+
+    /// Impact thresholds: `min` and `max` (the `--min-impact` and `--max-impact` flags) when
+    /// given, else those of `[thresholds]`
+    pub fn impact_thresholds(&self, min: &[ImpactThreshold], max: &[ImpactThreshold]) -> Result<Vec<ImpactThreshold>> {
+        let mut thresholds = Vec::new();
+        for (kind, flags, specs, key) in [
+            (ThresholdKind::Min, min, &self.thresholds.min_impact, "min_impact"),
+            (ThresholdKind::Max, max, &self.thresholds.max_impact, "max_impact"),
+        ] {
+            if !flags.is_empty() {
+                thresholds.extend(flags.iter().cloned());
+                continue;
+            }
+            for spec in specs {
+                let threshold = ImpactThreshold::parse(kind, spec)
+                    .map_err(|e| anyhow::anyhow!("Invalid `[thresholds] {}` entry `{}`: {}", key, spec, e))?;
+                thresholds.push(threshold);
+            }
+        }
+        Ok(thresholds)
+    }
+
+    /// Guards against pathological app files
+    pub fn input_limits(&self) -> InputLimits {
+        InputLimits {
+            max_file_bytes: self.limits.max_file_bytes,
+            max_line_length: self.limits.max_line_length,
+            max_usages_per_symbol_per_file: self.limits.max_usages_per_symbol_per_file,
+            max_context_length: self.limits.max_context_length,
+        }
+    }
+
+    /// Stability tiers to classify shared symbols with
+    pub fn stability_tiers(&self) -> StabilityTiers {
+        if self.stability.tiers.is_empty() {
+            return StabilityTiers::default();
+        }
+
+        StabilityTiers::new(
+            self.stability
+                .tiers
+                .iter()
+                .map(|t| (t.name.clone(), t.annotations.clone()))
+                .collect(),
+        )
+    }
+
+    /// Categories to sort shared symbols into
+    pub fn symbol_categories(&self) -> SymbolCategories {
+        if self.categories.rules.is_empty() {
+            return SymbolCategories::default();
+        }
+
+        SymbolCategories::new(
+            self.categories
+                .rules
+                .iter()
+                .map(|r| SymbolCategory {
+                    name: r.name.clone(),
+                    packages: r.packages.clone(),
+                    annotations: r.annotations.clone(),
+                    suffixes: r.suffixes.clone(),
+                    threshold: r.threshold.map(|t| t / 100.0),
+                    enforce: r.enforce,
+                })
+                .collect(),
+        )
+    }
+
+    /// Layers to split app code into
+    pub fn app_layers(&self) -> AppLayers {
+        if self.layers.rules.is_empty() {
+            return AppLayers::default();
+        }
+
+        AppLayers::new(
+            self.layers
+                .rules
+                .iter()
+                .map(|r| AppLayer {
+                    name: r.name.clone(),
+                    packages: r.packages.clone(),
+                    paths: r.paths.clone(),
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::MemoryFileSystem;
+    use crate::domain::{Symbol, SymbolType};
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = toml::from_str(
+            r#"
+            [report]
+            format = "markdown"
+            doc_base_url = "https://docs.example.com"
+
+            [analysis]
+            matcher = "aho-corasick"
+            objc_headers = ["shared/build/bin/iosArm64/debugFramework/Shared.framework/Headers/Shared.h"]
+
+            [[stability.tiers]]
+            name = "beta"
+            annotations = ["BetaApi"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.report.format.as_deref(), Some("markdown"));
+        assert_eq!(config.report.doc_base_url.as_deref(), Some("https://docs.example.com"));
+        assert_eq!(config.analysis_options().matcher, MatchEngine::AhoCorasick);
+        assert!(toml::from_str::<Config>("[analysis]\nmatcher = \"literal\"").is_ok());
+        assert!(toml::from_str::<Config>("[analysis]\nmatcher = \"glob\"").is_err());
+        let headers = config.analysis_options().objc_headers;
+        assert_eq!(headers.paths.len(), 1);
+        assert!(!headers.find_in_build_output);
+        let tiers = config.stability_tiers();
+        assert_eq!(tiers.names(), vec!["beta", StabilityTiers::STABLE]);
+    }
+
+    #[test]
+    fn test_update_state_file_outside_project() {
+        let project = Path::new("/repo");
+        let default = UpdateConfig::default().state_file(project);
+        assert!(default.is_none_or(|file| !file.starts_with(project) && file.ends_with("update-check.json")));
+
+        let config: Config = toml::from_str("[update]\nnotify = true\nfile = \"ci/update-check.json\"").unwrap();
+        assert_eq!(config.update.state_file(project), Some(project.join("ci/update-check.json")));
+    }
+
+    #[test]
+    fn test_stats_file_outside_project() {
+        let project = Path::new("/repo");
+        let default = StatsConfig::default().stats_file(project);
+        assert!(default.is_none_or(|file| !file.starts_with(project) && file.ends_with("run-stats.toml")));
+
+        let config: Config = toml::from_str("[stats]
+enabled = true
+file = \"ci/run-stats.toml\"").unwrap();
+        assert_eq!(config.stats.stats_file(project), Some(project.join("ci/run-stats.toml")));
+    }
+
+    #[test]
+    fn test_parse_drift_lint() {
+        let config: Config = toml::from_str(
+            r#"
+            [lint.single_platform_drift]
+            enabled = true
+            after_days = 14
+            severity = "error"
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.lint.single_platform_drift.enabled);
+        let policy = config.drift_policy();
+        assert_eq!(policy.after_days, Some(14));
+        assert_eq!(policy.after_runs, DriftPolicy::default().after_runs);
+        assert_eq!(policy.severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_discover_through_file_system() {
+        let mut fs = MemoryFileSystem::new();
+        fs.insert("kmp-coverage.toml", "[federation]\nsources = [\"shared.json\"]");
+
+        let config = Config::discover(&fs, Path::new(".")).unwrap();
+        assert_eq!(config.federation.sources, vec!["shared.json"]);
+        assert!(Config::discover(&fs, Path::new("missing")).unwrap().federation.sources.is_empty());
+    }
+
+    #[test]
+    fn test_parse_goals() {
+        let config: Config = toml::from_str(
+            r#"
+            [goals]
+            android = 40
+            ios = 25.5
+            "feature-payments" = { target = 60, enforce = true }
+            "#,
+        )
+        .unwrap();
+
+        let goals = config.coverage_goals();
+        assert_eq!(goals.len(), 3);
+        assert_eq!(goals[0].name, "android");
+        assert_eq!(goals[0].target, Ratio::new(2, 5));
+        assert!(!goals[0].enforce);
+        assert_eq!(goals[1].name, "feature-payments");
+        assert!(goals[1].enforce);
+        assert_eq!(goals[2].target, Ratio::new(51, 200));
+        assert!(toml::from_str::<Config>("[goals]\nios = { target = 1, gate = true }").is_err());
+    }
+
+    #[test]
+    fn test_parse_paths_and_thresholds() -> Result<()> {
+        let config: Config = toml::from_str(
+            r#"
+            [modules]
+            paths = ["shared", "core/model"]
+
+            [platforms]
+            ios = ["apps/ios"]
+
+            [thresholds]
+            min_impact = ["40", "ios=25"]
+            max_impact = ["90"]
+            "#,
+        )?;
+
+        let detection = config.detection_settings();
+        assert_eq!(detection.module_paths, vec!["shared", "core/model"]);
+        assert_eq!(detection.platform_paths, vec![(Platform::IOS, vec!["apps/ios".to_string()])]);
+        assert!(toml::from_str::<Config>("[platforms]\nwatch = [\"wear\"]").is_err());
+
+        let thresholds = config.impact_thresholds(&[], &[])?;
+        assert_eq!(thresholds.len(), 3);
+        assert_eq!(thresholds[1].platform, Some(Platform::IOS));
+        assert_eq!(thresholds[2].kind, ThresholdKind::Max);
+
+        // A flag replaces the entries of its kind only
+        let flag = ImpactThreshold::parse(ThresholdKind::Min, "50").unwrap();
+        let thresholds = config.impact_thresholds(std::slice::from_ref(&flag), &[])?;
+        assert_eq!(thresholds, vec![flag, ImpactThreshold::parse(ThresholdKind::Max, "90").unwrap()]);
+
+        let invalid: Config = toml::from_str("[thresholds]\nmin_impact = [\"watch=10\"]")?;
+        assert!(invalid.impact_thresholds(&[], &[]).unwrap_err().to_string().contains("min_impact"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_tiers() {
+        let tiers = Config::default().stability_tiers();
+        assert_eq!(tiers.names(), vec!["internal", "experimental", StabilityTiers::STABLE]);
+    }
+
+    #[test]
+    fn test_reject_unknown_keys() {
+        assert!(toml::from_str::<Config>("[report]\nunknown = 1").is_err());
+    }
+
+    #[test]
+    fn test_number_format() -> Result<()> {
+        let config: Config = toml::from_str("[report]\nlocale = \"de-DE\"\ngrouping_separator = \"\"")?;
+        let numbers = config.number_format(None, None)?;
+        assert_eq!(numbers.count(12345), "12345");
+        assert_eq!(numbers.percent(0.5), "50,00\u{a0}%");
+        assert_eq!(config.number_format(Some("en"), None)?.percent(0.5), "50.00%");
+        assert_eq!(Config::default().number_format(None, None)?, NumberFormat::default());
+
+        let config: Config = toml::from_str("[report]\ngrouping_separator = \"--\"")?;
+        assert!(config.number_format(None, None).is_err());
+
+        let config: Config = toml::from_str("[report]\nprecision = 1\nrounding = \"down\"")?;
+        assert_eq!(config.number_format(None, None)?.percent(0.4599), "45.9%");
+        assert_eq!(config.number_format(None, Some(3))?.percent(0.4599), "45.990%");
+        assert!(config.number_format(None, Some(7)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_categories() {
+        let config: Config = toml::from_str(
+            r#"
+            [[categories.rules]]
+            name = "networking"
+            packages = ["com.example.network", "*.api"]
+            suffixes = ["Client"]
+            threshold = 80
+            enforce = true
+
+            [[categories.rules]]
+            name = "models"
+            annotations = ["Serializable"]
+            "#,
+        )
+        .unwrap();
+
+        let categories = config.symbol_categories();
+        assert_eq!(categories.names(), vec!["networking", "models", SymbolCategories::OTHER]);
+        let networking = categories.get("networking").unwrap();
+        assert!((networking.threshold.unwrap() - 0.8).abs() < f64::EPSILON);
+        assert!(networking.enforce);
+
+        let symbol = |package: &str| Symbol {
+            package: package.to_string(),
+            ..Symbol::new("User", SymbolType::Class, "shared", "shared/src/commonMain/kotlin/Client.kt")
+        };
+        assert_eq!(categories.classify(&symbol("com.example.network.http")), "networking");
+        assert_eq!(categories.classify(&symbol("com.example.api")), "networking");
+        assert_eq!(categories.classify(&symbol("com.example.networking")), SymbolCategories::OTHER);
+
+        let client = Symbol {
+            name: "HttpClient".to_string(),
+            ..symbol("com.example")
+        };
+        assert_eq!(categories.classify(&client), "networking");
+        let model = Symbol {
+            annotations: vec!["kotlinx.serialization.Serializable".to_string()],
+            ..symbol("com.example")
+        };
+        assert_eq!(categories.classify(&model), "models");
+
+        let defaults = Config::default().symbol_categories();
+        assert_eq!(defaults.classify(&symbol("com.example.user.model")), "data models");
+    }
+}
@@ -0,0 +1,210 @@
+//! Interactive report diff browsing
+//! Pages through the platform, symbol, and file changes between two reports on any
+//! terminal, one command per line, so release comparisons need no JSON eyeballing
+
+use anyhow::Result;
+use std::io::{BufRead, Write};
+
+use crate::domain::ReportDiff;
+use crate::infrastructure::reporters::DiffReport;
+
+/// Navigates a [`ReportDiff`] from a terminal or any other reader and writer
+pub struct DiffViewer<'a> {
+    input: &'a mut dyn BufRead,
+    output: &'a mut dyn Write,
+}
+
+impl<'a> DiffViewer<'a> {
+    /// Entries listed per page
+    pub const PAGE_SIZE: usize = 20;
+
+    pub fn new(input: &'a mut dyn BufRead, output: &'a mut dyn Write) -> Self {
+        Self { input, output }
+    }
+
+    /// Shows the headline changes, then answers commands until `q` or end of input
+    ///
+    /// `p` shows the platform changes; `s` and `f` list changed symbols and files,
+    /// where `n`/`b` page, `/text` filters, and an entry number shows its details.
+    pub fn run(&mut self, diff: &ReportDiff) -> Result<()> {
+        writeln!(self.output, "{}", DiffReport::commits(diff))?;
+        writeln!(self.output, "Impact Coverage: {}", DiffReport::ratio(&diff.impact_ratio))?;
+        writeln!(self.output, "Affected Lines: {}", DiffReport::count(&diff.affected_lines))?;
+        writeln!(
+            self.output,
+            "{} platform(s), {} changed symbol(s), {} changed file(s)",
+            diff.platforms.len(),
+            diff.symbols.len(),
+            diff.files.len()
+        )?;
+
+        loop {
+            let Some(command) = self.prompt("[p]latforms [s]ymbols [f]iles [q]uit> ")? else {
+                break;
+            };
+            match command.as_str() {
+                "p" => {
+                    for platform in &diff.platforms {
+                        writeln!(
+                            self.output,
+                            "{}: impact {}, affected lines {}, total lines {}",
+                            platform.platform,
+                            DiffReport::ratio(&platform.impact_ratio),
+                            DiffReport::count(&platform.affected_lines),
+                            DiffReport::count(&platform.total_lines)
+                        )?;
+                    }
+                }
+                "s" => {
+                    let entries = diff
+                        .symbols
+                        .iter()
+                        .map(|s| format!("{}: {}", s.symbol, DiffReport::count(&s.usages)))
+                        .collect();
+                    let browsed = self.browse("Symbol usage changes", entries, |index| {
+                        let symbol = &diff.symbols[index].symbol;
+                        let files = |added: bool| {
+                            diff.files
+                                .iter()
+                                .filter(|f| {
+                                    if added { &f.added_symbols } else { &f.removed_symbols }.contains(symbol)
+                                })
+                                .map(|f| f.path.as_str())
+                                .collect::<Vec<_>>()
+                        };
+                        format!(
+                            "{}\n  Started using it: {}\n  Stopped using it: {}",
+                            symbol,
+                            Self::file_list(&files(true)),
+                            Self::file_list(&files(false))
+                        )
+                    })?;
+                    if !browsed {
+                        break;
+                    }
+                }
+                "f" => {
+                    let entries = diff
+                        .files
+                        .iter()
+                        .map(|f| format!("{} ({}, {})", f.path, f.platform, DiffReport::status(f.status)))
+                        .collect();
+                    let browsed = self.browse("Changed files", entries, |index| {
+                        let file = &diff.files[index];
+                        format!(
+                            "{} ({}, {})\n  Lines: {}\n  Affected lines: {}\n  Usages: {}\n  Symbols: {}",
+                            file.path,
+                            file.platform,
+                            DiffReport::status(file.status),
+                            DiffReport::count(&file.total_lines),
+                            DiffReport::count(&file.affected_lines),
+                            DiffReport::count(&file.usages),
+                            Some(DiffReport::symbol_changes(file))
+                                .filter(|changes| !changes.is_empty())
+                                .unwrap_or_else(|| "unchanged".to_string())
+                        )
+                    })?;
+                    if !browsed {
+                        break;
+                    }
+                }
+                "q" | "quit" => break,
+                "" => {}
+                other => writeln!(self.output, "Unknown command `{}`", other)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Pages through `entries` until an empty line; false when input ended
+    fn browse(&mut self, title: &str, entries: Vec<String>, details: impl Fn(usize) -> String) -> Result<bool> {
+        let mut filter = String::new();
+        let mut page = 0;
+        loop {
+            let shown: Vec<usize> = (0..entries.len())
+                .filter(|&i| entries[i].to_lowercase().contains(&filter))
+                .collect();
+            let pages = shown.len().div_ceil(Self::PAGE_SIZE).max(1);
+            page = page.min(pages - 1);
+
+            writeln!(self.output, "{} ({} of {}), page {}/{}", title, shown.len(), entries.len(), page + 1, pages)?;
+            for (number, &index) in shown.iter().enumerate().skip(page * Self::PAGE_SIZE).take(Self::PAGE_SIZE) {
+                writeln!(self.output, "{:>4}. {}", number + 1, entries[index])?;
+            }
+
+            let Some(command) = self.prompt("[n]ext [b]ack /filter <number>, empty to return> ")? else {
+                return Ok(false);
+            };
+            match command.as_str() {
+                "" => return Ok(true),
+                "n" => page = (page + 1).min(pages - 1),
+                "b" => page = page.saturating_sub(1),
+                _ if command.starts_with('/') => {
+                    filter = command[1..].trim().to_lowercase();
+                    page = 0;
+                }
+                _ => match command.parse::<usize>().ok().and_then(|n| shown.get(n.wrapping_sub(1))) {
+                    Some(&index) => writeln!(self.output, "{}", details(index))?,
+                    None => writeln!(self.output, "Unknown command `{}`", command)?,
+                },
+            }
+        }
+    }
+
+    fn prompt(&mut self, prompt: &str) -> Result<Option<String>> {
+        write!(self.output, "{}", prompt)?;
+        self.output.flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim().to_string()))
+    }
+
+    fn file_list(files: &[&str]) -> String {
+        if files.is_empty() {
+            "-".to_string()
+        } else {
+            files.join(", ")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Change, FileDelta, FileStatus, SymbolDelta};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_browse_files_and_symbols() {
+        let file = |path: &str, added: &[&str]| FileDelta {
+            path: path.to_string(),
+            platform: "Android".to_string(),
+            status: FileStatus::Changed,
+            total_lines: Change { before: 10, after: 12 },
+            affected_lines: Change { before: 0, after: 12 },
+            usages: Change { before: 0, after: 2 },
+            added_symbols: added.iter().map(|s| s.to_string()).collect(),
+            removed_symbols: Vec::new(),
+        };
+        let diff = ReportDiff {
+            symbols: vec![SymbolDelta {
+                symbol: "Logger".to_string(),
+                usages: Change { before: 1, after: 3 },
+            }],
+            files: vec![file("app/Main.kt", &["Logger"]), file("app/Settings.kt", &[])],
+            ..Default::default()
+        };
+
+        let mut input = Cursor::new("f\n/settings\n1\n\ns\n1\nq\n");
+        let mut output = Vec::new();
+        DiffViewer::new(&mut input, &mut output).run(&diff).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Changed files (2 of 2), page 1/1"));
+        assert!(output.contains("Changed files (1 of 2), page 1/1\n   1. app/Settings.kt (Android, changed)"));
+        assert!(output.contains("app/Settings.kt (Android, changed)\n  Lines: 10 → 12 (+2)"));
+        assert!(output.contains("Logger\n  Started using it: app/Main.kt\n  Stopped using it: -"));
+    }
+}
@@ -0,0 +1,84 @@
+//! Exit code contract of the command line tool
+//! Scripts tell a failed gate or an incomplete run apart from a broken one by
+//! the exit code alone, without parsing the report
+
+use thiserror::Error;
+
+use super::project_path::ProjectPathError;
+
+/// Any error without a more specific code
+pub const EXIT_ERROR: u8 = 1;
+/// The analysis ran and was reported, but a gate failed
+pub const EXIT_GATE_FAILED: u8 = 2;
+/// No project was detected under the given path
+pub const EXIT_DETECTION_FAILED: u8 = 3;
+/// Results were reported for only part of the input
+pub const EXIT_PARTIAL_RESULTS: u8 = 4;
+/// The project path does not exist
+pub const EXIT_PATH_NOT_FOUND: u8 = 5;
+/// The project path is a file but not a supported source archive
+pub const EXIT_NOT_A_DIRECTORY: u8 = 6;
+/// A `--scope` directory does not exist under the project path
+pub const EXIT_SCOPE_NOT_FOUND: u8 = 7;
+
+/// A run that produced its report but must not count as a success
+#[derive(Debug, Error)]
+pub enum RunFailure {
+    #[error("Found {count} usage(s) of deprecated shared API (--fail-on-deprecated-usage)")]
+    DeprecatedUsage { count: usize },
+    #[error("Missed {} coverage goal(s): {}", .names.len(), .names.join(", "))]
+    MissedGoals { names: Vec<String> },
+    #[error("Missed {} category threshold(s): {}", .names.len(), .names.join(", "))]
+    MissedCategories { names: Vec<String> },
+    #[error("Failed {} impact threshold(s):\n  {}", .violations.len(), .violations.join("\n  "))]
+    ImpactThresholds { violations: Vec<String> },
+    #[error("Found {count} lint error(s)")]
+    LintErrors { count: usize },
+    #[error("Failed to analyze {failed} of {total} repositories")]
+    PartialBatch { failed: usize, total: usize },
+}
+
+impl RunFailure {
+    /// Process exit code reported for this failure
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::PartialBatch { .. } => EXIT_PARTIAL_RESULTS,
+            _ => EXIT_GATE_FAILED,
+        }
+    }
+}
+
+/// Process exit code for an error ending the run
+///
+/// 2 for failed gates, 3 when no project is detected, 4 for partial results, 5 to 7 for
+/// an unusable project path (see [`ProjectPathError`]), and 1 for everything else.
+pub fn exit_code(error: &anyhow::Error) -> u8 {
+    if let Some(failure) = error.downcast_ref::<RunFailure>() {
+        failure.exit_code()
+    } else if let Some(path_error) = error.downcast_ref::<ProjectPathError>() {
+        path_error.exit_code()
+    } else {
+        EXIT_ERROR
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(exit_code(&anyhow::anyhow!("unreadable config")), EXIT_ERROR);
+        assert_eq!(exit_code(&RunFailure::LintErrors { count: 2 }.into()), EXIT_GATE_FAILED);
+        let partial = anyhow::Error::from(RunFailure::PartialBatch { failed: 1, total: 3 });
+        assert_eq!(exit_code(&partial.context("batch run")), EXIT_PARTIAL_RESULTS);
+        let missing = ProjectPathError::ScopeNotFound { scope: "feature".to_string() };
+        assert_eq!(exit_code(&missing.into()), EXIT_SCOPE_NOT_FOUND);
+        let undetected = ProjectPathError::NoProjects { path: "app".to_string(), suggestion: None };
+        assert_eq!(exit_code(&undetected.into()), EXIT_DETECTION_FAILED);
+        assert_eq!(
+            RunFailure::MissedGoals { names: vec!["android".to_string(), "ios".to_string()] }.to_string(),
+            "Missed 2 coverage goal(s): android, ios"
+        );
+    }
+}
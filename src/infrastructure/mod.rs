@@ -1,6 +1,37 @@
-/// Infrastructure layer - External frameworks and drivers
-/// CLI, Reporters, File I/O, etc.
+//! Infrastructure layer - External frameworks and drivers
+//! CLI, Reporters, File I/O, etc.
 
+pub mod analyzer;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod batch;
+pub mod capabilities;
+pub mod config;
+pub mod diff_viewer;
+pub mod exit_status;
+pub mod project_path;
+pub mod provenance;
+pub mod redaction;
 pub mod reporters;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod self_update;
+pub mod suppressions;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod watch;
 
+pub use analyzer::Analyzer;
+#[cfg(not(target_arch = "wasm32"))]
+pub use batch::{BatchRunner, BatchSource};
+pub use capabilities::Capabilities;
+pub use config::Config;
+pub use diff_viewer::DiffViewer;
+pub use exit_status::RunFailure;
+pub use provenance::Provenance;
+pub use redaction::Redactor;
 pub use reporters::Reporter;
+#[cfg(not(target_arch = "wasm32"))]
+pub use self_update::SelfUpdater;
+pub use suppressions::SuppressionPrompt;
+#[cfg(not(target_arch = "wasm32"))]
+pub use watch::ProjectWatcher;
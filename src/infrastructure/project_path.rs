@@ -0,0 +1,270 @@
+//! Validation of the project path given on the command line
+//! Turns the common mistakes (typos, pointing at a file, pointing at a
+//! directory without any project) into specific errors with suggestions
+
+use anyhow::Context;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use super::exit_status::{EXIT_DETECTION_FAILED, EXIT_NOT_A_DIRECTORY, EXIT_PATH_NOT_FOUND, EXIT_SCOPE_NOT_FOUND};
+use crate::adapters::archive::ArchiveFormat;
+use crate::adapters::{FileSystem, ProjectDetector};
+use crate::domain::{AnalysisScope, DetectionSettings};
+
+/// Why a project path cannot be analyzed
+#[derive(Debug, Error)]
+pub enum ProjectPathError {
+    #[error("Project path does not exist: {path}{}", did_you_mean(.suggestion))]
+    NotFound {
+        path: String,
+        /// Existing path closest to the one given
+        suggestion: Option<PathBuf>,
+    },
+    #[error(
+        "Project path is a file, not a directory: {path}\n  hint: pass the repository root, or a source archive (.tar.gz, .tgz, .tar, .zip)"
+    )]
+    NotADirectory { path: String },
+    #[error(
+        "No Kotlin Multiplatform, Android, iOS, server, desktop, or web projects found in {path}\n  hint: pass the repository root rather than a module or source directory{}",
+        did_you_mean(.suggestion)
+    )]
+    NoProjects {
+        path: String,
+        /// Enclosing directory that looks like a Gradle or Xcode project root
+        suggestion: Option<PathBuf>,
+    },
+    #[error("Scope is not a directory of the project: {scope}\n  hint: scopes are relative to the project path")]
+    ScopeNotFound { scope: String },
+}
+
+impl ProjectPathError {
+    /// Process exit code reported for this error
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::NoProjects { .. } => EXIT_DETECTION_FAILED,
+            Self::NotFound { .. } => EXIT_PATH_NOT_FOUND,
+            Self::NotADirectory { .. } => EXIT_NOT_A_DIRECTORY,
+            Self::ScopeNotFound { .. } => EXIT_SCOPE_NOT_FOUND,
+        }
+    }
+}
+
+fn did_you_mean(suggestion: &Option<PathBuf>) -> String {
+    suggestion
+        .as_ref()
+        .map(|path| format!("\n  hint: did you mean {}?", path.display()))
+        .unwrap_or_default()
+}
+
+/// Checks that `path` is an existing directory or source archive on disk
+pub fn validate_on_disk(path: &str) -> Result<(), ProjectPathError> {
+    let path_ref = Path::new(path);
+    if !path_ref.exists() {
+        return Err(ProjectPathError::NotFound {
+            path: path.to_string(),
+            suggestion: closest_existing(path_ref),
+        });
+    }
+    if path_ref.is_file() && ArchiveFormat::detect(path_ref).is_none() {
+        return Err(ProjectPathError::NotADirectory {
+            path: path.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that at least one project is detected under `root` of the opened file system
+///
+/// `display_path` is the path as the user gave it. Fails with a [`ProjectPathError`]
+/// when nothing is detected, and with the detection error itself when the tree
+/// cannot be read.
+pub fn validate_projects(
+    fs: &dyn FileSystem,
+    root: &Path,
+    display_path: &str,
+    detection: &DetectionSettings,
+) -> anyhow::Result<()> {
+    let projects = ProjectDetector::new(fs)
+        .with_settings(detection.clone())
+        .detect_all_projects(root)
+        .with_context(|| format!("Failed to detect projects in {}", display_path))?;
+    if !projects.is_empty() {
+        return Ok(());
+    }
+
+    Err(ProjectPathError::NoProjects {
+        path: display_path.to_string(),
+        suggestion: enclosing_project_root(fs, root),
+    }
+    .into())
+}
+
+/// Checks that every scoped subtree is a directory under `root` of the opened file system
+pub fn validate_scope(fs: &dyn FileSystem, root: &Path, scope: &AnalysisScope) -> Result<(), ProjectPathError> {
+    match scope.roots.iter().find(|dir| !fs.is_dir(&root.join(dir))) {
+        Some(dir) => Err(ProjectPathError::ScopeNotFound { scope: dir.clone() }),
+        None => Ok(()),
+    }
+}
+
+/// Closest existing path: the deepest existing ancestor, extended by the child
+/// whose name is nearest to the next missing component
+pub fn closest_existing(path: &Path) -> Option<PathBuf> {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    while !existing.as_os_str().is_empty() && !existing.exists() {
+        missing.push(existing.file_name()?.to_string_lossy().to_string());
+        existing = existing.parent()?;
+    }
+    let base = if existing.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        existing
+    };
+    let wanted = missing.last()?;
+
+    let closest = fs::read_dir(base)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .map(|name| (edit_distance(&name.to_lowercase(), &wanted.to_lowercase()), name))
+        .filter(|(distance, _)| *distance <= wanted.chars().count().div_ceil(2).max(2))
+        .min()
+        .map(|(_, name)| name);
+
+    Some(match closest {
+        Some(name) => existing.join(name),
+        None => base.to_path_buf(),
+    })
+}
+
+/// Nearest ancestor holding Gradle settings or an Xcode project, looked up in `fs`
+///
+/// Paths on disk are made absolute so a relative path still has ancestors to look at;
+/// an archive's file system, which holds no such paths, keeps the path as given.
+fn enclosing_project_root(fs: &dyn FileSystem, path: &Path) -> Option<PathBuf> {
+    let start = std::path::absolute(path)
+        .ok()
+        .filter(|absolute| fs.is_dir(absolute))
+        .unwrap_or_else(|| path.to_path_buf());
+    let is_root = |dir: &Path| {
+        ["settings.gradle.kts", "settings.gradle"]
+            .iter()
+            .any(|name| fs.is_file(&dir.join(name)))
+            || fs
+                .walk(dir, 1)
+                .iter()
+                .any(|entry| entry.path.extension().is_some_and(|ext| ext == "xcodeproj"))
+    };
+    start.ancestors().skip(1).find(|dir| is_root(dir)).map(Path::to_path_buf)
+}
+
+/// Levenshtein distance between two strings, by characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::file_system::{FileEntry, FileStamp};
+    use crate::adapters::{MemoryFileSystem, OsFileSystem};
+    use crate::infrastructure::exit_status::{self, EXIT_ERROR};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_validate_project_paths() -> anyhow::Result<()> {
+        let temp = TempDir::new()?;
+        let root = temp.path();
+        fs::create_dir_all(root.join("mobile/shared/src/commonMain/kotlin"))?;
+        fs::write(root.join("mobile/settings.gradle.kts"), "include(\":shared\")")?;
+        fs::write(root.join("mobile/shared/build.gradle.kts"), "plugins { kotlin(\"multiplatform\") }")?;
+        fs::write(root.join("mobile/shared/src/commonMain/kotlin/User.kt"), "class User")?;
+        fs::write(root.join("notes.txt"), "")?;
+
+        let typo = root.join("mobil/shared");
+        let error = validate_on_disk(typo.to_str().unwrap()).unwrap_err();
+        assert_eq!(error.exit_code(), EXIT_PATH_NOT_FOUND);
+        assert!(error.to_string().contains(&format!("did you mean {}", root.join("mobile").display())));
+
+        let file = root.join("notes.txt");
+        assert_eq!(validate_on_disk(file.to_str().unwrap()).unwrap_err().exit_code(), EXIT_NOT_A_DIRECTORY);
+
+        let mobile = root.join("mobile");
+        assert!(validate_on_disk(mobile.to_str().unwrap()).is_ok());
+        assert!(validate_projects(&OsFileSystem, &mobile, "mobile", &DetectionSettings::default()).is_ok());
+
+        let sources = mobile.join("shared/src/commonMain/kotlin");
+        let error = validate_projects(&OsFileSystem, &sources, "kotlin", &DetectionSettings::default()).unwrap_err();
+        assert_eq!(exit_status::exit_code(&error), EXIT_DETECTION_FAILED);
+        assert!(error.to_string().contains(&format!("did you mean {}?", mobile.display())));
+
+        // Inside an archive the suggestion comes from the archive's own tree
+        let mut archive = MemoryFileSystem::new();
+        archive.insert("mobile/settings.gradle.kts", "include(\":shared\")".to_string());
+        archive.insert("mobile/shared/src/commonMain/kotlin/User.kt", "class User".to_string());
+        let nested = Path::new("mobile/shared/src/commonMain/kotlin");
+        let error = validate_projects(&archive, nested, "kotlin", &DetectionSettings::default()).unwrap_err();
+        assert!(error.to_string().contains("did you mean mobile?"), "{}", error);
+
+        let scope = AnalysisScope::new(vec!["./shared/".to_string()]);
+        assert!(validate_scope(&OsFileSystem, &mobile, &scope).is_ok());
+        let scope = AnalysisScope::new(vec!["shared".to_string(), "appAuth".to_string()]);
+        let error = validate_scope(&OsFileSystem, &mobile, &scope).unwrap_err();
+        assert_eq!(error.exit_code(), EXIT_SCOPE_NOT_FOUND);
+        assert!(error.to_string().contains("appAuth"));
+
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        Ok(())
+    }
+
+    /// A tree whose files cannot be read, as with a permission error
+    struct UnreadableFileSystem(MemoryFileSystem);
+
+    impl FileSystem for UnreadableFileSystem {
+        fn read_to_string(&self, path: &Path) -> anyhow::Result<String> {
+            anyhow::bail!("Permission denied: {}", path.display())
+        }
+
+        fn is_file(&self, path: &Path) -> bool {
+            self.0.is_file(path)
+        }
+
+        fn is_dir(&self, path: &Path) -> bool {
+            self.0.is_dir(path)
+        }
+
+        fn walk(&self, root: &Path, max_depth: usize) -> Vec<FileEntry> {
+            self.0.walk(root, max_depth)
+        }
+
+        fn stamp(&self, path: &Path) -> anyhow::Result<FileStamp> {
+            self.0.stamp(path)
+        }
+    }
+
+    #[test]
+    fn test_detection_errors_are_not_reported_as_missing_projects() {
+        let mut tree = MemoryFileSystem::new();
+        tree.insert("settings.gradle.kts", "includeBuild(\"build-logic\")");
+        let fs = UnreadableFileSystem(tree);
+
+        let error = validate_projects(&fs, Path::new(""), "repo", &DetectionSettings::default()).unwrap_err();
+        assert_eq!(exit_status::exit_code(&error), EXIT_ERROR);
+        assert!(format!("{:#}", error).contains("Permission denied"), "{:#}", error);
+        assert!(!error.to_string().contains("No Kotlin Multiplatform"));
+    }
+}
@@ -0,0 +1,412 @@
+//! Report provenance
+//! Stamps every report with the tool version, commit, time, configuration hash,
+//! host, and CI build that produced it, and optionally signs the result with HMAC-SHA256
+
+use anyhow::Result;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs;
+
+use crate::domain::{
+    CiEnvironment, DetectionSettings, DiffScope, FrameworkHeaderSources, ImpactAnalysis, ImpactMetric, ImpactThreshold,
+    MatchEngine, RunMetadata, SymbolParser,
+};
+use crate::infrastructure::config::Config;
+use crate::use_cases::AnalysisOptions;
+
+/// Environment variable holding the report signing key
+pub const SIGNING_KEY_ENV: &str = "KMP_COVERAGE_SIGNING_KEY";
+
+/// CI environment variables that carry the commit being built, checked in order
+const CI_COMMIT_ENV: &[&str] = &["GITHUB_SHA", "CI_COMMIT_SHA", "BITBUCKET_COMMIT", "GIT_COMMIT"];
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// What the configuration hash covers: the configuration file, and the analysis options
+/// and thresholds in effect once command line flags are applied to it
+#[derive(Serialize)]
+struct EffectiveSettings<'a> {
+    config: &'a Config,
+    resolve_actual_typealiases: bool,
+    include_sample_modules: bool,
+    include_test_symbols: bool,
+    include_internal: bool,
+    count_imports: bool,
+    detection: &'a DetectionSettings,
+    scope: &'a [String],
+    diff: Option<&'a DiffScope>,
+    parser: SymbolParser,
+    matcher: MatchEngine,
+    objc_headers: &'a FrameworkHeaderSources,
+    metric: ImpactMetric,
+    thresholds: &'a [ImpactThreshold],
+}
+
+/// Attaches run metadata to analysis results and signs them
+pub struct Provenance {
+    signing_key: Option<Vec<u8>>,
+}
+
+impl Provenance {
+    pub fn new() -> Self {
+        Self { signing_key: None }
+    }
+
+    /// Signs reports with the given key
+    pub fn with_signing_key(mut self, key: Option<&str>) -> Self {
+        self.signing_key = key.filter(|k| !k.is_empty()).map(|k| k.as_bytes().to_vec());
+        self
+    }
+
+    /// Builds run metadata from a [`Provenance::config_hash`]; the commit falls back
+    /// to the CI environment when not given
+    pub fn metadata(config_hash: String, commit_sha: Option<String>, now: u64) -> RunMetadata {
+        RunMetadata {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            commit_sha: commit_sha.or_else(Self::ci_commit),
+            generated_at: now,
+            config_hash,
+            host: Self::host(),
+            ci: Self::ci_environment(|name| env::var(name).ok()),
+            signature: None,
+        }
+    }
+
+    /// Attaches the metadata to the analysis, unsigned
+    ///
+    /// Later steps (run history, API churn, redaction) still change the analysis, so
+    /// [`Provenance::sign`] runs separately, once the report is final.
+    pub fn stamp(&self, analysis: &mut ImpactAnalysis, mut metadata: RunMetadata) {
+        metadata.signature = None;
+        analysis.metadata = Some(metadata);
+    }
+
+    /// Signs the stamped analysis exactly as it will be emitted, when a key is configured
+    pub fn sign(&self, analysis: &mut ImpactAnalysis) -> Result<()> {
+        let (Some(key), Some(metadata)) = (&self.signing_key, &analysis.metadata) else {
+            return Ok(());
+        };
+        let mut mac = Self::mac(key);
+        mac.update(Self::signed_message(metadata, analysis)?.as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+        if let Some(metadata) = analysis.metadata.as_mut() {
+            metadata.signature = Some(signature);
+        }
+        Ok(())
+    }
+
+    /// Checks the signature of a stamped analysis against this key
+    pub fn verify(&self, analysis: &ImpactAnalysis) -> bool {
+        let (Some(key), Some(metadata)) = (&self.signing_key, &analysis.metadata) else {
+            return false;
+        };
+        let Some(signature) = metadata.signature.as_deref().and_then(|s| hex::decode(s).ok())
+        else {
+            return false;
+        };
+
+        let Ok(message) = Self::signed_message(metadata, analysis) else {
+            return false;
+        };
+        let mut mac = Self::mac(key);
+        mac.update(message.as_bytes());
+        mac.verify_slice(&signature).is_ok()
+    }
+
+    /// SHA-256 of the effective settings, serialized as JSON
+    ///
+    /// Command line flags count along with the configuration file, so two runs get the
+    /// same hash only when the settings that shape their numbers are the same.
+    pub fn config_hash(config: &Config, options: &AnalysisOptions, thresholds: &[ImpactThreshold]) -> Result<String> {
+        let settings = EffectiveSettings {
+            config,
+            resolve_actual_typealiases: options.resolve_actual_typealiases,
+            include_sample_modules: options.include_sample_modules,
+            include_test_symbols: options.include_test_symbols,
+            include_internal: options.include_internal,
+            count_imports: options.count_imports,
+            detection: &options.detection,
+            scope: &options.scope.roots,
+            diff: options.diff.as_ref(),
+            parser: options.parser,
+            matcher: options.matcher,
+            objc_headers: &options.objc_headers,
+            metric: options.metric,
+            thresholds,
+        };
+        let json = serde_json::to_string(&settings)?;
+        Ok(hex::encode(Sha256::digest(json.as_bytes())))
+    }
+
+    /// The signed text: the metadata, one field per line (the CI build as compact JSON
+    /// with sorted keys, empty outside CI), then the whole analysis without its metadata
+    /// as compact JSON with sorted keys
+    ///
+    /// A dashboard rebuilds the message by parsing the JSON report, dropping `metadata`,
+    /// and re-serializing the rest the same way.
+    pub fn signed_message(metadata: &RunMetadata, analysis: &ImpactAnalysis) -> Result<String> {
+        let mut unsigned = analysis.clone();
+        unsigned.metadata = None;
+        let body = serde_json::to_value(&unsigned)?.to_string();
+        Ok([
+            metadata.tool_version.clone(),
+            metadata.commit_sha.clone().unwrap_or_default(),
+            metadata.generated_at.to_string(),
+            metadata.config_hash.clone(),
+            metadata.host.clone().unwrap_or_default(),
+            match &metadata.ci {
+                Some(ci) => serde_json::to_value(ci)?.to_string(),
+                None => String::new(),
+            },
+            body,
+        ]
+        .join("\n"))
+    }
+
+    fn mac(key: &[u8]) -> HmacSha256 {
+        HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length")
+    }
+
+    /// The GitHub Actions, GitLab CI, or Jenkins build described by the environment
+    ///
+    /// `var` looks up an environment variable; empty values count as unset.
+    pub fn ci_environment(var: impl Fn(&str) -> Option<String>) -> Option<CiEnvironment> {
+        let var = |name: &str| var(name).filter(|value| !value.is_empty());
+        let number = |name: &str| var(name).and_then(|value| value.parse().ok());
+
+        if var("GITHUB_ACTIONS").is_some() {
+            let build_url = match (var("GITHUB_SERVER_URL"), var("GITHUB_REPOSITORY"), var("GITHUB_RUN_ID")) {
+                (Some(server), Some(repository), Some(run)) => {
+                    Some(format!("{}/{}/actions/runs/{}", server, repository, run))
+                }
+                _ => None,
+            };
+            // Pull request builds check out `refs/pull/<number>/merge`
+            let pull_request = var("GITHUB_REF").and_then(|git_ref| {
+                git_ref.strip_prefix("refs/pull/")?.split('/').next()?.parse().ok()
+            });
+            return Some(CiEnvironment {
+                provider: "GitHub Actions".to_string(),
+                build_url,
+                branch: var("GITHUB_HEAD_REF").or_else(|| var("GITHUB_REF_NAME")),
+                pull_request,
+            });
+        }
+        if var("GITLAB_CI").is_some() {
+            return Some(CiEnvironment {
+                provider: "GitLab CI".to_string(),
+                build_url: var("CI_JOB_URL").or_else(|| var("CI_PIPELINE_URL")),
+                branch: var("CI_MERGE_REQUEST_SOURCE_BRANCH_NAME").or_else(|| var("CI_COMMIT_REF_NAME")),
+                pull_request: number("CI_MERGE_REQUEST_IID"),
+            });
+        }
+        if var("JENKINS_URL").is_some() {
+            // Multibranch pipelines set CHANGE_* for pull requests; plain jobs only GIT_BRANCH
+            let branch = var("CHANGE_BRANCH").or_else(|| var("BRANCH_NAME")).or_else(|| {
+                var("GIT_BRANCH").map(|b| b.strip_prefix("origin/").unwrap_or(&b).to_string())
+            });
+            return Some(CiEnvironment {
+                provider: "Jenkins".to_string(),
+                build_url: var("BUILD_URL"),
+                branch,
+                pull_request: number("CHANGE_ID"),
+            });
+        }
+        None
+    }
+
+    fn ci_commit() -> Option<String> {
+        CI_COMMIT_ENV
+            .iter()
+            .find_map(|name| env::var(name).ok().filter(|v| !v.is_empty()))
+    }
+
+    fn host() -> Option<String> {
+        env::var("HOSTNAME")
+            .or_else(|_| env::var("COMPUTERNAME"))
+            .ok()
+            .or_else(|| fs::read_to_string("/etc/hostname").ok())
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+    }
+}
+
+impl Default for Provenance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AnalysisScope, ApiChurn, ApiSymbol, PlatformImpact, SymbolType, ThresholdKind};
+    use crate::infrastructure::redaction::Redactor;
+    use std::collections::BTreeMap;
+
+    fn stamped(provenance: &Provenance) -> ImpactAnalysis {
+        let mut analysis = ImpactAnalysis {
+            total_app_lines: 200,
+            affected_lines: 50,
+            ..Default::default()
+        };
+        analysis.calculate_impact_ratio();
+        let config_hash = Provenance::config_hash(&Config::default(), &AnalysisOptions::default(), &[]).unwrap();
+        let metadata = Provenance::metadata(config_hash, Some("abc123".to_string()), 1_700_000_000);
+        provenance.stamp(&mut analysis, metadata);
+        provenance.sign(&mut analysis).unwrap();
+        analysis
+    }
+
+    #[test]
+    fn test_signature_verifies_and_detects_tampering() {
+        let provenance = Provenance::new().with_signing_key(Some("secret"));
+        let mut analysis = stamped(&provenance);
+        let metadata = analysis.metadata.as_ref().unwrap();
+        assert_eq!(metadata.commit_sha.as_deref(), Some("abc123"));
+        assert_eq!(metadata.signature.as_ref().unwrap().len(), 64);
+        assert!(provenance.verify(&analysis));
+        assert!(!Provenance::new().with_signing_key(Some("other")).verify(&analysis));
+
+        let mut tampered = analysis.clone();
+        tampered.affected_lines += 1;
+        assert!(!provenance.verify(&tampered));
+
+        // Per-platform numbers are signed too
+        analysis.platform_impacts.insert("iOS".to_string(), PlatformImpact::new("iOS".to_string()));
+        assert!(!provenance.verify(&analysis));
+        let analysis = stamped(&provenance);
+        let reparsed: ImpactAnalysis = serde_json::from_str(&serde_json::to_string(&analysis).unwrap()).unwrap();
+        assert!(provenance.verify(&reparsed));
+    }
+
+    #[test]
+    fn test_signs_the_emitted_report() {
+        let provenance = Provenance::new().with_signing_key(Some("secret"));
+        let mut analysis = ImpactAnalysis {
+            total_app_lines: 200,
+            affected_lines: 50,
+            ..Default::default()
+        };
+        analysis.affected_files.insert("/repo/app/Main.kt".to_string());
+        let mut metadata = Provenance::metadata("hash".to_string(), Some("abc123".to_string()), 1_700_000_000);
+        metadata.ci = Some(CiEnvironment {
+            provider: "GitHub Actions".to_string(),
+            build_url: Some("https://github.com/example/app/actions/runs/42".to_string()),
+            branch: Some("main".to_string()),
+            pull_request: None,
+        });
+        provenance.stamp(&mut analysis, metadata);
+
+        // Steps after stamping change the body; the signature must cover the final one
+        let symbol = |name: &str| ApiSymbol {
+            module: "shared".to_string(),
+            package: "com.example".to_string(),
+            name: name.to_string(),
+            symbol_type: SymbolType::Class,
+            file: format!("{name}.kt"),
+        };
+        analysis.api_churn = Some(ApiChurn {
+            since: 1_699_000_000,
+            since_commit: None,
+            added: vec![symbol("User")],
+            removed: Vec::new(),
+            renamed: Vec::new(),
+            impact_ratio_change: 0.05,
+            platform_ratio_changes: BTreeMap::new(),
+        });
+        Redactor::new("/repo").redact(&mut analysis);
+        assert!(!analysis.affected_files.contains("/repo/app/Main.kt"));
+        provenance.sign(&mut analysis).unwrap();
+        assert!(provenance.verify(&analysis));
+
+        let reparsed: ImpactAnalysis = serde_json::from_str(&serde_json::to_string(&analysis).unwrap()).unwrap();
+        assert!(provenance.verify(&reparsed));
+
+        // The CI build is signed too
+        let mut moved = analysis.clone();
+        moved.metadata.as_mut().unwrap().ci.as_mut().unwrap().branch = Some("release".to_string());
+        assert!(!provenance.verify(&moved));
+    }
+
+    #[test]
+    fn test_unsigned_without_key() {
+        let analysis = stamped(&Provenance::new());
+        assert!(analysis.metadata.unwrap().signature.is_none());
+    }
+
+    #[test]
+    fn test_detects_ci_environment() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+
+        let github = Provenance::ci_environment(env(&[
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_SERVER_URL", "https://github.com"),
+            ("GITHUB_REPOSITORY", "example/app"),
+            ("GITHUB_RUN_ID", "42"),
+            ("GITHUB_REF", "refs/pull/17/merge"),
+            ("GITHUB_REF_NAME", "17/merge"),
+            ("GITHUB_HEAD_REF", "feature/login"),
+        ]))
+        .unwrap();
+        assert_eq!(github.provider, "GitHub Actions");
+        assert_eq!(github.build_url.as_deref(), Some("https://github.com/example/app/actions/runs/42"));
+        assert_eq!(github.branch.as_deref(), Some("feature/login"));
+        assert_eq!(github.pull_request, Some(17));
+
+        let gitlab = Provenance::ci_environment(env(&[
+            ("GITLAB_CI", "true"),
+            ("CI_JOB_URL", "https://gitlab.com/example/app/-/jobs/7"),
+            ("CI_COMMIT_REF_NAME", "main"),
+            ("CI_MERGE_REQUEST_IID", ""),
+        ]))
+        .unwrap();
+        assert_eq!(gitlab.build_url.as_deref(), Some("https://gitlab.com/example/app/-/jobs/7"));
+        assert_eq!((gitlab.branch.as_deref(), gitlab.pull_request), (Some("main"), None));
+
+        let jenkins = Provenance::ci_environment(env(&[
+            ("JENKINS_URL", "https://ci.example.com/"),
+            ("BUILD_URL", "https://ci.example.com/job/app/5/"),
+            ("GIT_BRANCH", "origin/release"),
+        ]))
+        .unwrap();
+        assert_eq!((jenkins.provider.as_str(), jenkins.branch.as_deref()), ("Jenkins", Some("release")));
+
+        assert!(Provenance::ci_environment(env(&[("CI", "true")])).is_none());
+    }
+
+    #[test]
+    fn test_config_hash_tracks_configuration() {
+        let hash = |config: &Config, options: &AnalysisOptions, thresholds: &[ImpactThreshold]| {
+            Provenance::config_hash(config, options, thresholds).unwrap()
+        };
+        let options = AnalysisOptions::default();
+        let default_hash = hash(&Config::default(), &options, &[]);
+        let mut config = Config::default();
+        config.analysis.resolve_actual_typealiases = true;
+
+        assert_eq!(default_hash, hash(&Config::default(), &options, &[]));
+        assert_ne!(default_hash, hash(&config, &options, &[]));
+
+        // Command line overrides change the hash as well
+        let scoped = AnalysisOptions {
+            scope: AnalysisScope::new(vec!["shared".to_string()]),
+            ..Default::default()
+        };
+        assert_ne!(default_hash, hash(&Config::default(), &scoped, &[]));
+        let mut filtered = AnalysisOptions::default();
+        filtered.detection.files.exclude.push("**/generated/**".to_string());
+        assert_ne!(default_hash, hash(&Config::default(), &filtered, &[]));
+        let lines = AnalysisOptions {
+            metric: ImpactMetric::Files,
+            ..Default::default()
+        };
+        assert_ne!(default_hash, hash(&Config::default(), &lines, &[]));
+        let threshold = ImpactThreshold::parse(ThresholdKind::Min, "40").unwrap();
+        assert_ne!(default_hash, hash(&Config::default(), &options, &[threshold]));
+    }
+}
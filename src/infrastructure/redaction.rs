@@ -0,0 +1,233 @@
+//! Report redaction
+//! Replaces file paths with salted hashes and strips code context, keeping the
+//! aggregate numbers, so reports can be shared outside the company
+
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::domain::{ImpactAnalysis, SymbolUsage};
+
+/// Environment variable holding the salt mixed into path hashes
+pub const REDACTION_SALT_ENV: &str = "KMP_COVERAGE_REDACTION_SALT";
+
+/// Hex digits kept of each path hash
+const HASH_LENGTH: usize = 12;
+
+/// Redacts analysis results in place
+pub struct Redactor {
+    project_root: String,
+    salt: String,
+    /// Original path (as found in the analysis) -> redacted path
+    redacted: BTreeMap<String, String>,
+}
+
+impl Redactor {
+    /// Paths are hashed relative to `project_root`, so they match across checkouts
+    pub fn new(project_root: &str) -> Self {
+        Self {
+            project_root: project_root.to_string(),
+            salt: String::new(),
+            redacted: BTreeMap::new(),
+        }
+    }
+
+    /// Mixes a secret into the hashes, so short or guessable paths cannot be recovered
+    /// by hashing candidates
+    pub fn with_salt(mut self, salt: Option<&str>) -> Self {
+        self.salt = salt.unwrap_or_default().to_string();
+        self
+    }
+
+    /// Hashes every file path, drops usage context and import statements, and scrubs paths from diagnostics
+    pub fn redact(mut self, analysis: &mut ImpactAnalysis) {
+        for symbol in &mut analysis.symbols {
+            symbol.file_path = self.path(&symbol.file_path);
+        }
+        analysis.affected_files = self.paths(&analysis.affected_files);
+        for usages in analysis.symbol_usages.values_mut() {
+            self.usages(usages);
+        }
+        self.usages(&mut analysis.unused_imports);
+        for stale in &mut analysis.stale_integrations {
+            stale.file_path = self.path(&stale.file_path);
+        }
+        for file in &mut analysis.files {
+            file.path = self.path(&file.path);
+            for edge in &mut file.affected_via {
+                edge.from = self.path(&edge.from);
+                edge.to = self.path(&edge.to);
+                for import in &mut edge.imports {
+                    import.statement.clear();
+                }
+            }
+        }
+        // Warm start data is raw paths and code; a redacted report cannot be resumed from
+        analysis.warm_start = None;
+        if let Some(diff) = &mut analysis.diff {
+            diff.changed_files = diff.changed_files.iter().map(|file| self.path(file)).collect();
+            diff.changed_lines = std::mem::take(&mut diff.changed_lines)
+                .into_iter()
+                .map(|(file, lines)| (self.path(&file), lines))
+                .collect();
+        }
+        for suppression in &mut analysis.stale_suppressions {
+            suppression.file = self.path(&suppression.file);
+        }
+        for impact in analysis.platform_impacts.values_mut() {
+            impact.affected_files = self.paths(&impact.affected_files);
+            self.usages(&mut impact.deprecated_usages);
+            for entry in &mut impact.entry_points {
+                entry.file = self.path(&entry.file);
+            }
+        }
+        if let Some(churn) = &mut analysis.api_churn {
+            let symbols = churn.added.iter_mut().chain(churn.removed.iter_mut()).chain(
+                churn
+                    .renamed
+                    .iter_mut()
+                    .flat_map(|rename| [&mut rename.from, &mut rename.to]),
+            );
+            for symbol in symbols {
+                symbol.file = self.path(&symbol.file);
+            }
+        }
+
+        // Longest first, so a path is not partly replaced through one of its prefixes
+        let mut replacements: Vec<(&String, &String)> = self.redacted.iter().collect();
+        replacements.sort_by_key(|(original, _)| std::cmp::Reverse(original.len()));
+        for diagnostic in &mut analysis.diagnostics {
+            for (original, redacted) in &replacements {
+                diagnostic.message = diagnostic.message.replace(original.as_str(), redacted);
+            }
+        }
+    }
+
+    fn usages(&mut self, usages: &mut [SymbolUsage]) {
+        for usage in usages {
+            usage.file_path = self.path(&usage.file_path);
+            usage.context.clear();
+        }
+    }
+
+    fn paths(&mut self, paths: &HashSet<String>) -> HashSet<String> {
+        paths.iter().map(|path| self.path(path)).collect()
+    }
+
+    /// `<hash>.<extension>`; the extension is kept so platform breakdowns stay readable
+    fn path(&mut self, path: &str) -> String {
+        if let Some(redacted) = self.redacted.get(path) {
+            return redacted.clone();
+        }
+
+        let full = Path::new(path);
+        let relative = full.strip_prefix(&self.project_root).unwrap_or(full);
+        let relative = relative.to_string_lossy().trim_start_matches("./").replace('\\', "/");
+        let digest = Sha256::digest(format!("{}{}", self.salt, relative).as_bytes());
+        let mut redacted = hex::encode(digest)[..HASH_LENGTH].to_string();
+        if let Some(extension) = full.extension() {
+            redacted.push('.');
+            redacted.push_str(&extension.to_string_lossy());
+        }
+
+        self.redacted.insert(path.to_string(), redacted.clone());
+        redacted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::MemoryFileSystem;
+    use crate::domain::{Diagnostic, DiffImpact, LineRange, PlatformImpact, Severity, Symbol, SymbolType, UsageKind};
+    use crate::infrastructure::reporters::ReviewPack;
+
+    #[test]
+    fn test_redacts_paths_and_context() {
+        let usage = SymbolUsage {
+            symbol_name: "UserRepository".to_string(),
+            file_path: "/repo/app/src/main/Main.kt".to_string(),
+            line_number: 3,
+            context: "val repo = UserRepositoryImpl()".to_string(),
+            kind: UsageKind::Instantiation,
+        };
+        let mut impact = PlatformImpact::new("Android".to_string());
+        impact.affected_files.insert(usage.file_path.clone());
+        impact.affected_lines = 40;
+        let mut analysis = ImpactAnalysis {
+            affected_lines: 40,
+            impact_ratio: 0.5,
+            affected_files: HashSet::from([usage.file_path.clone()]),
+            symbol_usages: [("UserRepository".to_string(), vec![usage])].into(),
+            platform_impacts: [("Android".to_string(), impact)].into(),
+            diagnostics: vec![Diagnostic {
+                rule: "pathological-input".to_string(),
+                severity: Severity::Warning,
+                message: "Skipped /repo/app/src/main/Main.kt: too long".to_string(),
+                symbol: None,
+            }],
+            ..Default::default()
+        };
+
+        Redactor::new("/repo").redact(&mut analysis);
+        let unsalted = analysis.affected_files.iter().next().unwrap().clone();
+        assert!(unsalted.ends_with(".kt"));
+        assert_eq!(unsalted.len(), HASH_LENGTH + 3);
+        assert!(!unsalted.contains("Main"));
+
+        let usage = &analysis.symbol_usages["UserRepository"][0];
+        assert_eq!(usage.file_path, unsalted);
+        assert!(usage.context.is_empty());
+        assert!(analysis.platform_impacts["Android"].affected_files.contains(&unsalted));
+        assert_eq!(analysis.diagnostics[0].message, format!("Skipped {}: too long", unsalted));
+        assert_eq!(analysis.affected_lines, 40);
+        assert_eq!(analysis.impact_ratio, 0.5);
+
+        // Same relative path from another checkout, different salt
+        let mut other = ImpactAnalysis {
+            affected_files: HashSet::from(["./app/src/main/Main.kt".to_string()]),
+            ..Default::default()
+        };
+        Redactor::new(".").redact(&mut other);
+        assert!(other.affected_files.contains(&unsalted));
+        let mut salted = other.clone();
+        salted.affected_files = HashSet::from(["/repo/app/src/main/Main.kt".to_string()]);
+        Redactor::new("/repo").with_salt(Some("secret")).redact(&mut salted);
+        assert!(!salted.affected_files.contains(&unsalted));
+    }
+
+    #[test]
+    fn test_redacts_diff_runs() {
+        let shared = "/repo/shared/src/commonMain/kotlin/User.kt";
+        let mut analysis = ImpactAnalysis {
+            symbols: vec![Symbol {
+                package: "com.example".to_string(),
+                lines: Some(LineRange::new(3, 5)),
+                ..Symbol::new("User", SymbolType::Class, "shared", shared)
+            }],
+            diff: Some(DiffImpact {
+                base_ref: "origin/main".to_string(),
+                changed_files: vec![shared.to_string()],
+                touched_symbols: vec!["User".to_string()],
+                changed_lines: BTreeMap::from([
+                    ("shared/src/commonMain/kotlin/User.kt".to_string(), vec![LineRange::new(4, 4)]),
+                    ("docs/internal/roadmap.md".to_string(), vec![LineRange::new(1, 9)]),
+                ]),
+            }),
+            ..Default::default()
+        };
+
+        Redactor::new("/repo").redact(&mut analysis);
+        let redacted = analysis.symbols[0].file_path.clone();
+        let diff = analysis.diff.as_ref().unwrap();
+        assert_eq!(diff.changed_files, vec![redacted.clone()]);
+        assert_eq!(diff.changed_lines[&redacted], vec![LineRange::new(4, 4)]);
+        let json = serde_json::to_string(&analysis).unwrap();
+        assert!(!json.contains("commonMain") && !json.contains("roadmap"), "{}", json);
+
+        // The review pack still finds the changed declaration among the hashed paths
+        let pack = ReviewPack::from_analysis(&analysis, &MemoryFileSystem::new(), Some("/repo")).unwrap();
+        assert_eq!(pack.files.len(), 1);
+        assert_eq!(pack.files[0].path, redacted);
+    }
+}
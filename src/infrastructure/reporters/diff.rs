@@ -0,0 +1,168 @@
+//! Report diff rendering
+//! Headline, platform, symbol, and file changes between two JSON reports, for
+//! release comparisons
+
+use prettytable::{Cell, Row, Table};
+
+use crate::domain::{Change, FileDelta, FileStatus, ReportDiff};
+
+/// Table and Markdown renderings of a [`ReportDiff`]
+pub struct DiffReport;
+
+impl DiffReport {
+    pub fn table(diff: &ReportDiff) -> String {
+        let mut output = String::from("=== Report Diff ===\n\n");
+        output.push_str(&format!("{}\n", Self::commits(diff)));
+        output.push_str(&format!("📊 Impact Coverage: {}\n", Self::ratio(&diff.impact_ratio)));
+        output.push_str(&format!("   Affected Lines: {}\n", Self::count(&diff.affected_lines)));
+        output.push_str(&format!("   App Lines: {}\n\n", Self::count(&diff.total_app_lines)));
+
+        if !diff.platforms.is_empty() {
+            output.push_str("=== Platform Changes ===\n\n");
+            let mut table = Table::new();
+            table.add_row(Row::new(vec![
+                Cell::new("Platform"),
+                Cell::new("Impact %"),
+                Cell::new("Affected Lines"),
+                Cell::new("Total Lines"),
+            ]));
+            for platform in &diff.platforms {
+                table.add_row(Row::new(vec![
+                    Cell::new(&platform.platform),
+                    Cell::new(&Self::ratio(&platform.impact_ratio)),
+                    Cell::new(&Self::count(&platform.affected_lines)),
+                    Cell::new(&Self::count(&platform.total_lines)),
+                ]));
+            }
+            output.push_str(&format!("{}\n", table));
+        }
+
+        if !diff.symbols.is_empty() {
+            output.push_str(&format!("=== Symbol Usage Changes ({}) ===\n\n", diff.symbols.len()));
+            let mut table = Table::new();
+            table.add_row(Row::new(vec![Cell::new("Symbol"), Cell::new("Usages")]));
+            for symbol in &diff.symbols {
+                table.add_row(Row::new(vec![Cell::new(&symbol.symbol), Cell::new(&Self::count(&symbol.usages))]));
+            }
+            output.push_str(&format!("{}\n", table));
+        }
+
+        if !diff.files.is_empty() {
+            output.push_str(&format!("=== Changed Files ({}) ===\n\n", diff.files.len()));
+            let mut table = Table::new();
+            table.add_row(Row::new(vec![
+                Cell::new("File"),
+                Cell::new("Platform"),
+                Cell::new("Status"),
+                Cell::new("Affected Lines"),
+                Cell::new("Usages"),
+                Cell::new("Symbols"),
+            ]));
+            for file in &diff.files {
+                table.add_row(Row::new(vec![
+                    Cell::new(&file.path),
+                    Cell::new(&file.platform),
+                    Cell::new(Self::status(file.status)),
+                    Cell::new(&Self::count(&file.affected_lines)),
+                    Cell::new(&Self::count(&file.usages)),
+                    Cell::new(&Self::symbol_changes(file)),
+                ]));
+            }
+            output.push_str(&format!("{}\n", table));
+        }
+
+        output
+    }
+
+    pub fn markdown(diff: &ReportDiff) -> String {
+        let mut md = String::from("# 🔀 Report Diff\n\n");
+        md.push_str(&format!("{}\n\n", Self::commits(diff)));
+        md.push_str("| Metric | Change |\n|--------|--------|\n");
+        md.push_str(&format!("| Impact Coverage | {} |\n", Self::ratio(&diff.impact_ratio)));
+        md.push_str(&format!("| Affected Lines | {} |\n", Self::count(&diff.affected_lines)));
+        md.push_str(&format!("| App Lines | {} |\n", Self::count(&diff.total_app_lines)));
+
+        if !diff.platforms.is_empty() {
+            md.push_str("\n## 📱 Platform Changes\n\n");
+            md.push_str("| Platform | Impact % | Affected Lines | Total Lines |\n");
+            md.push_str("|----------|----------|----------------|-------------|\n");
+            for platform in &diff.platforms {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    platform.platform,
+                    Self::ratio(&platform.impact_ratio),
+                    Self::count(&platform.affected_lines),
+                    Self::count(&platform.total_lines)
+                ));
+            }
+        }
+
+        if !diff.symbols.is_empty() {
+            md.push_str(&format!("\n## 🏆 Symbol Usage Changes ({})\n\n", diff.symbols.len()));
+            md.push_str("| Symbol | Usages |\n|--------|--------|\n");
+            for symbol in &diff.symbols {
+                md.push_str(&format!("| `{}` | {} |\n", symbol.symbol, Self::count(&symbol.usages)));
+            }
+        }
+
+        if !diff.files.is_empty() {
+            md.push_str(&format!("\n## 📄 Changed Files ({})\n\n", diff.files.len()));
+            md.push_str("| File | Platform | Status | Affected Lines | Usages | Symbols |\n");
+            md.push_str("|------|----------|--------|----------------|--------|---------|\n");
+            for file in &diff.files {
+                md.push_str(&format!(
+                    "| `{}` | {} | {} | {} | {} | {} |\n",
+                    file.path,
+                    file.platform,
+                    Self::status(file.status),
+                    Self::count(&file.affected_lines),
+                    Self::count(&file.usages),
+                    Self::symbol_changes(file)
+                ));
+            }
+        }
+
+        md
+    }
+
+    /// `a1b2c3d → e4f5a6b`, or `-` for a report without a commit
+    pub fn commits(diff: &ReportDiff) -> String {
+        let short = |sha: &Option<String>| {
+            sha.as_deref().map_or("-".to_string(), |sha| sha.chars().take(7).collect())
+        };
+        format!("Commit: {} → {}", short(&diff.commit.before), short(&diff.commit.after))
+    }
+
+    /// `12.50% → 15.00% (+2.50)`
+    pub fn ratio(change: &Change<f64>) -> String {
+        format!(
+            "{:.2}% → {:.2}% ({:+.2})",
+            change.before * 100.0,
+            change.after * 100.0,
+            change.delta() * 100.0
+        )
+    }
+
+    /// `120 → 135 (+15)`
+    pub fn count(change: &Change<usize>) -> String {
+        format!("{} → {} ({:+})", change.before, change.after, change.delta())
+    }
+
+    pub fn status(status: FileStatus) -> &'static str {
+        match status {
+            FileStatus::Added => "added",
+            FileStatus::Removed => "removed",
+            FileStatus::Changed => "changed",
+        }
+    }
+
+    /// `+Logger -User`
+    pub fn symbol_changes(file: &FileDelta) -> String {
+        file.added_symbols
+            .iter()
+            .map(|s| format!("+{}", s))
+            .chain(file.removed_symbols.iter().map(|s| format!("-{}", s)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
@@ -0,0 +1,114 @@
+use crate::domain::{Symbol, SymbolType};
+
+/// Builds deep links into a Dokka-generated HTML documentation site
+///
+/// Dokka lays pages out as `<module>/<package>/<name>` where every uppercase
+/// letter of the name is encoded as `-` followed by its lowercase form.
+pub struct DocLinkResolver {
+    base_url: String,
+}
+
+impl DocLinkResolver {
+    /// Creates a resolver for the given documentation base URL
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Returns the documentation URL for a symbol
+    pub fn link_for(&self, symbol: &Symbol) -> String {
+        let package = if symbol.package.is_empty() {
+            "[root]".to_string()
+        } else {
+            symbol.package.clone()
+        };
+        let page = Self::encode_name(&symbol.name);
+
+        match symbol.symbol_type {
+            SymbolType::Function | SymbolType::Property => format!(
+                "{}/{}/{}/{}.html",
+                self.base_url, symbol.module, package, page
+            ),
+            _ => format!(
+                "{}/{}/{}/{}/index.html",
+                self.base_url, symbol.module, package, page
+            ),
+        }
+    }
+
+    /// Returns the documentation URL for a symbol name, if the name is a shared symbol
+    /// with an API page; resource accessors have none
+    pub fn link_for_name(&self, symbols: &[Symbol], name: &str) -> Option<String> {
+        Self::find_symbol(symbols, name)
+            .filter(|symbol| symbol.symbol_type != SymbolType::Resource)
+            .map(|symbol| self.link_for(symbol))
+    }
+
+    /// Finds the best symbol to link for a name, preferring type declarations
+    pub fn find_symbol<'a>(symbols: &'a [Symbol], name: &str) -> Option<&'a Symbol> {
+        let mut candidates = symbols.iter().filter(|s| s.name == name);
+        let first = candidates.next()?;
+        if !matches!(first.symbol_type, SymbolType::Function | SymbolType::Property) {
+            return Some(first);
+        }
+        Some(
+            candidates
+                .find(|s| !matches!(s.symbol_type, SymbolType::Function | SymbolType::Property))
+                .unwrap_or(first),
+        )
+    }
+
+    /// Encodes a symbol name the way Dokka names its pages
+    fn encode_name(name: &str) -> String {
+        let mut encoded = String::with_capacity(name.len() + 4);
+        for c in name.chars() {
+            if c.is_ascii_uppercase() {
+                encoded.push('-');
+                encoded.push(c.to_ascii_lowercase());
+            } else {
+                encoded.push(c);
+            }
+        }
+        encoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, symbol_type: SymbolType, package: &str) -> Symbol {
+        Symbol {
+            package: package.to_string(),
+            ..Symbol::new(name, symbol_type, "shared", "shared/src/commonMain/kotlin/User.kt")
+        }
+    }
+
+    #[test]
+    fn test_class_link() {
+        let resolver = DocLinkResolver::new("https://docs.example.com/api/");
+        let link = resolver.link_for(&symbol("UserRepository", SymbolType::Interface, "com.example"));
+        assert_eq!(
+            link,
+            "https://docs.example.com/api/shared/com.example/-user-repository/index.html"
+        );
+    }
+
+    #[test]
+    fn test_function_link_in_root_package() {
+        let resolver = DocLinkResolver::new("https://docs.example.com");
+        let link = resolver.link_for(&symbol("formatUserName", SymbolType::Function, ""));
+        assert_eq!(link, "https://docs.example.com/shared/[root]/format-user-name.html");
+    }
+
+    #[test]
+    fn test_find_symbol_prefers_types() {
+        let symbols = vec![
+            symbol("User", SymbolType::Function, "com.example"),
+            symbol("User", SymbolType::Class, "com.example"),
+        ];
+        let found = DocLinkResolver::find_symbol(&symbols, "User").unwrap();
+        assert_eq!(found.symbol_type, SymbolType::Class);
+    }
+}
@@ -0,0 +1,234 @@
+use serde::Serialize;
+use std::path::Path;
+
+use crate::domain::{ImpactAnalysis, RunMetadata, Severity, Symbol};
+
+/// Annotations GitHub accepts per Checks API request
+pub const ANNOTATIONS_PER_REQUEST: usize = 50;
+
+/// Upper bound on annotations in one payload, to keep check runs readable
+pub const MAX_ANNOTATIONS: usize = 1000;
+
+/// Check run payload for the GitHub Checks API
+///
+/// `output` is the body of the create request; the annotations are pre-split
+/// into `annotation_batches` of at most [`ANNOTATIONS_PER_REQUEST`] so a
+/// wrapper action can send one update request per batch.
+#[derive(Debug, Serialize)]
+pub struct CheckRunPayload {
+    pub name: String,
+    pub conclusion: String,
+    pub output: CheckRunOutput,
+    pub annotation_batches: Vec<Vec<Annotation>>,
+    /// Number of usage sites left out because of [`MAX_ANNOTATIONS`]
+    pub omitted_annotations: usize,
+    /// CI build that produced the analysis, linked from the check run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details_url: Option<String>,
+    /// Provenance of the analysis, for the wrapper action to attach to the run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<RunMetadata>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckRunOutput {
+    pub title: String,
+    pub summary: String,
+}
+
+/// A line-level annotation on an app file
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Annotation {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub annotation_level: String,
+    pub title: String,
+    pub message: String,
+}
+
+impl CheckRunPayload {
+    /// Builds the payload, annotating every usage site of a shared symbol
+    ///
+    /// Paths are made relative to `project_root` since GitHub expects
+    /// repository-relative paths.
+    pub fn from_analysis(analysis: &ImpactAnalysis, project_root: Option<&str>) -> Self {
+        let mut annotations: Vec<Annotation> = analysis
+            .symbol_usages
+            .values()
+            .flatten()
+            .map(|usage| {
+                let origin = Self::find_symbol(&analysis.symbols, &usage.symbol_name)
+                    .map(|symbol| format!(" from module `{}`", symbol.module))
+                    .unwrap_or_default();
+                Annotation {
+                    path: Self::relative_path(&usage.file_path, project_root),
+                    start_line: usage.line_number,
+                    end_line: usage.line_number,
+                    annotation_level: "notice".to_string(),
+                    title: format!("Shared KMP symbol: {}", usage.symbol_name),
+                    message: format!("Uses shared `{}`{}", usage.symbol_name, origin),
+                }
+            })
+            .collect();
+        annotations.sort();
+
+        let omitted_annotations = annotations.len().saturating_sub(MAX_ANNOTATIONS);
+        annotations.truncate(MAX_ANNOTATIONS);
+
+        let errors = analysis
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count();
+
+        let failed = errors > 0
+            || !analysis.missed_enforced_goals().is_empty()
+            || !analysis.missed_enforced_categories().is_empty();
+
+        Self {
+            name: "KMP Impact Coverage".to_string(),
+            conclusion: if failed { "failure" } else { "success" }.to_string(),
+            output: CheckRunOutput {
+                title: format!("Impact coverage {:.2}%", analysis.impact_ratio * 100.0),
+                summary: Self::summary(analysis, omitted_annotations),
+            },
+            annotation_batches: annotations
+                .chunks(ANNOTATIONS_PER_REQUEST)
+                .map(<[Annotation]>::to_vec)
+                .collect(),
+            omitted_annotations,
+            details_url: analysis
+                .metadata
+                .as_ref()
+                .and_then(|m| m.ci.as_ref())
+                .and_then(|ci| ci.build_url.clone()),
+            metadata: analysis.metadata.clone(),
+        }
+    }
+
+    fn summary(analysis: &ImpactAnalysis, omitted_annotations: usize) -> String {
+        let mut summary = format!(
+            "{} of {} app lines in {} files are affected by {} shared symbols. {}.",
+            analysis.affected_lines,
+            analysis.total_app_lines,
+            analysis.affected_files.len(),
+            analysis.total_symbols,
+            analysis.roi.describe()
+        );
+        if let Some(line) = super::Reporter::suppression_line(analysis) {
+            summary.push_str(&format!(" {}.", line));
+        }
+        if !analysis.goals.is_empty() {
+            summary.push_str("\n\n**Coverage goals**\n");
+            for goal in &analysis.goals {
+                summary.push_str(&format!(
+                    "\n- {} `{}`: {:.2}% of {:.2}%{}",
+                    if goal.met { "✅" } else { "❌" },
+                    goal.name,
+                    goal.impact_ratio * 100.0,
+                    goal.target * 100.0,
+                    if goal.enforce { " (enforced)" } else { "" }
+                ));
+            }
+        }
+        let gated: Vec<_> = analysis.categories.iter().filter(|c| c.threshold.is_some()).collect();
+        if !gated.is_empty() {
+            summary.push_str("\n\n**Category thresholds**\n");
+            for category in gated {
+                summary.push_str(&format!(
+                    "\n- {} `{}`: {:.2}% of {:.2}%{}",
+                    if category.meets_threshold() { "✅" } else { "❌" },
+                    category.category,
+                    category.used_ratio * 100.0,
+                    category.threshold.unwrap_or_default() * 100.0,
+                    if category.enforce { " (enforced)" } else { "" }
+                ));
+            }
+        }
+        if !analysis.diagnostics.is_empty() {
+            summary.push_str("\n\n**Diagnostics**\n");
+            for diagnostic in &analysis.diagnostics {
+                summary.push_str(&format!(
+                    "\n- {} `{}`: {}",
+                    diagnostic.severity.name(),
+                    diagnostic.rule,
+                    diagnostic.message
+                ));
+            }
+        }
+        if omitted_annotations > 0 {
+            summary.push_str(&format!(
+                "\n\n{} further usage sites were not annotated.",
+                omitted_annotations
+            ));
+        }
+        summary
+    }
+
+    fn find_symbol<'a>(symbols: &'a [Symbol], name: &str) -> Option<&'a Symbol> {
+        symbols.iter().find(|s| s.name == name)
+    }
+
+    fn relative_path(file_path: &str, project_root: Option<&str>) -> String {
+        let path = Path::new(file_path);
+        let relative = project_root
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        relative
+            .to_string_lossy()
+            .trim_start_matches("./")
+            .replace('\\', "/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Diagnostic, SymbolUsage, UsageKind};
+
+    fn usage(file: &str, line: usize) -> SymbolUsage {
+        SymbolUsage {
+            symbol_name: "UserRepository".to_string(),
+            file_path: file.to_string(),
+            line_number: line,
+            context: "val repo = UserRepository()".to_string(),
+            kind: UsageKind::Instantiation,
+        }
+    }
+
+    #[test]
+    fn test_annotations_are_relative_and_chunked() {
+        let mut analysis = ImpactAnalysis::default();
+        let usages = (1..=60)
+            .map(|line| usage("/repo/app/src/main/kotlin/Main.kt", line))
+            .collect();
+        analysis.symbol_usages.insert("UserRepository".to_string(), usages);
+
+        let payload = CheckRunPayload::from_analysis(&analysis, Some("/repo"));
+        assert_eq!(payload.conclusion, "success");
+        assert_eq!(payload.annotation_batches.len(), 2);
+        assert_eq!(payload.annotation_batches[0].len(), ANNOTATIONS_PER_REQUEST);
+        assert_eq!(payload.annotation_batches[1].len(), 10);
+
+        let first = &payload.annotation_batches[0][0];
+        assert_eq!(first.path, "app/src/main/kotlin/Main.kt");
+        assert_eq!(first.start_line, 1);
+        assert_eq!(payload.omitted_annotations, 0);
+    }
+
+    #[test]
+    fn test_error_diagnostics_fail_the_check() {
+        let mut analysis = ImpactAnalysis::default();
+        analysis.diagnostics.push(Diagnostic {
+            rule: "single-platform-drift".to_string(),
+            severity: Severity::Error,
+            message: "`Foo` is only used on Android".to_string(),
+            symbol: Some("Foo".to_string()),
+        });
+
+        let payload = CheckRunPayload::from_analysis(&analysis, None);
+        assert_eq!(payload.conclusion, "failure");
+        assert!(payload.output.summary.contains("single-platform-drift"));
+    }
+}
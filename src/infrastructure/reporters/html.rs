@@ -0,0 +1,830 @@
+//! Self-contained HTML dashboard
+//! Renders the analysis as a single file with inline CSS, SVG trend charts, and a
+//! small inline script sorting the tables, so it can be published as-is (e.g. on
+//! GitHub Pages) and shared with people who never run the CLI
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::domain::{ImpactAnalysis, RunSnapshot};
+
+use super::doc_links::DocLinkResolver;
+use super::number_format::NumberFormat;
+use super::source_viewer::SourceExcerpts;
+
+/// Symbols listed in the churn table
+pub const MAX_CHURN_SYMBOLS: usize = 10;
+
+/// Symbols listed in the top symbols table, each with its usage locations
+pub const MAX_TOP_SYMBOLS: usize = 10;
+
+const CHART_WIDTH: f64 = 720.0;
+const CHART_HEIGHT: f64 = 240.0;
+const CHART_PADDING: f64 = 40.0;
+const SERIES_COLORS: &[&str] = &["#1f6feb", "#3fb950", "#d29922", "#a371f7", "#f85149"];
+
+const STYLE: &str = "body{font-family:-apple-system,Segoe UI,Helvetica,Arial,sans-serif;margin:2rem auto;max-width:960px;color:#1f2328;padding:0 1rem}\
+h1{font-size:1.6rem}h2{font-size:1.2rem;margin-top:2rem;border-bottom:1px solid #d0d7de;padding-bottom:.3rem}\
+.cards{display:flex;gap:1rem;flex-wrap:wrap}.card{border:1px solid #d0d7de;border-radius:6px;padding:.8rem 1.2rem;min-width:140px}\
+.card .value{font-size:1.5rem;font-weight:600}.card .label{color:#656d76;font-size:.85rem}\
+table{border-collapse:collapse;width:100%}th,td{border:1px solid #d0d7de;padding:.35rem .6rem;text-align:left}th{background:#f6f8fa}\
+td.num{text-align:right;font-variant-numeric:tabular-nums}.up{color:#1a7f37}.down{color:#cf222e}\
+.legend span{margin-right:1rem}.legend i{display:inline-block;width:.8rem;height:.8rem;margin-right:.3rem;vertical-align:middle}\
+.note{color:#656d76}footer{margin-top:2rem;color:#656d76;font-size:.8rem}\
+details{margin:.4rem 0}summary{cursor:pointer}pre.src{background:#f6f8fa;border:1px solid #d0d7de;border-radius:6px;padding:.5rem 0;overflow-x:auto;font-size:.8rem;margin:.3rem 0}\
+.src .line{display:block;padding:0 .6rem}.src .hit{background:#fff8c5}.src .gap{color:#8c959f;border-top:1px dashed #d0d7de}\
+.src .ln{display:inline-block;width:3.5em;color:#8c959f;user-select:none}.kw{color:#cf222e}.str{color:#0a3069}.com{color:#6e7781}.sym{color:#8250df;font-weight:600}\
+table.sortable th{cursor:pointer}table.sortable th:after{content:' \\2195';color:#8c959f}ul.sites{margin:.3rem 0;padding-left:1.2rem;font-size:.85rem}";
+
+/// Sorts a `table.sortable` by the clicked column, numerically when both cells are numbers;
+/// a second click reverses the order
+const SCRIPT: &str = "document.querySelectorAll('table.sortable th').forEach(function(th){\
+th.addEventListener('click',function(){\
+var column=Array.prototype.indexOf.call(th.parentNode.children,th);\
+var rows=Array.prototype.slice.call(th.closest('table').querySelectorAll('tr')).slice(1);\
+var ascending=th.dataset.order!=='asc';th.dataset.order=ascending?'asc':'desc';\
+rows.sort(function(a,b){\
+var x=a.children[column].textContent,y=b.children[column].textContent,nx=parseFloat(x),ny=parseFloat(y);\
+var order=isNaN(nx)||isNaN(ny)?x.localeCompare(y):nx-ny;return ascending?order:-order;});\
+rows.forEach(function(row){row.parentNode.appendChild(row);});});});";
+
+/// Single-file HTML dashboard of an analysis and the run history
+pub struct HtmlDashboard<'a> {
+    analysis: &'a ImpactAnalysis,
+    history: &'a [RunSnapshot],
+    sources: Option<&'a SourceExcerpts>,
+    project_root: Option<&'a str>,
+    doc_links: Option<&'a DocLinkResolver>,
+    numbers: NumberFormat,
+}
+
+impl<'a> HtmlDashboard<'a> {
+    /// `history` holds past runs oldest first, usually ending with the current one
+    pub fn new(analysis: &'a ImpactAnalysis, history: &'a [RunSnapshot]) -> Self {
+        Self {
+            analysis,
+            history,
+            sources: None,
+            project_root: None,
+            doc_links: None,
+            numbers: NumberFormat::default(),
+        }
+    }
+
+    /// Shows file paths relative to the project root
+    pub fn with_project_root(mut self, project_root: &'a str) -> Self {
+        self.project_root = Some(project_root);
+        self
+    }
+
+    /// Prints percentages with the given separators and precision
+    pub fn with_number_format(mut self, numbers: NumberFormat) -> Self {
+        self.numbers = numbers;
+        self
+    }
+
+    /// Links symbol names to their API documentation
+    pub fn with_doc_links(mut self, doc_links: &'a DocLinkResolver) -> Self {
+        self.doc_links = Some(doc_links);
+        self
+    }
+
+    /// Embeds highlighted source excerpts of the usage sites
+    pub fn with_sources(mut self, sources: &'a SourceExcerpts) -> Self {
+        self.sources = Some(sources);
+        self
+    }
+
+    /// Renders the dashboard; `footer` is shown verbatim (escaped) at the bottom
+    pub fn render(&self, footer: Option<&str>) -> String {
+        let analysis = self.analysis;
+        let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str("<title>KMP Impact Coverage</title>\n");
+        html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+        html.push_str("<h1>Kotlin Multiplatform Impact Coverage</h1>\n");
+
+        html.push_str("<div class=\"cards\">\n");
+        let affected_label = format!("Affected {}", analysis.metric.name());
+        let (affected, total) = analysis.affected_of_total();
+        for (label, value) in [
+            ("Impact coverage", self.numbers.percent(analysis.impact_ratio)),
+            (affected_label.as_str(), format!("{} / {}", affected, total)),
+            ("Directly affected files", analysis.affected_files.len().to_string()),
+            ("Shared symbols", analysis.total_symbols.to_string()),
+            (
+                "App lines per shared line",
+                format!(
+                    "{:.1} ({} / {})",
+                    analysis.roi.leverage, analysis.roi.affected_app_lines, analysis.roi.shared_lines
+                ),
+            ),
+        ] {
+            html.push_str(&format!(
+                "<div class=\"card\"><div class=\"value\">{}</div><div class=\"label\">{}</div></div>\n",
+                escape(&value),
+                label
+            ));
+        }
+        html.push_str("</div>\n");
+        if let Some(line) = super::Reporter::suppression_line(self.analysis) {
+            html.push_str(&format!("<p class=\"note\">{}</p>\n", escape(&line)));
+        }
+
+        self.push_platforms(&mut html);
+        self.push_entry_points(&mut html);
+        self.push_goals(&mut html);
+        self.push_variants(&mut html);
+        self.push_trend(&mut html);
+        self.push_churn(&mut html);
+        self.push_api_changes(&mut html);
+        self.push_top_symbols(&mut html);
+        self.push_usage_kinds(&mut html);
+        self.push_files(&mut html);
+        self.push_sources(&mut html);
+        self.push_diagnostics(&mut html);
+
+        if let Some(footer) = footer {
+            html.push_str(&format!("<footer>{}</footer>\n", escape(footer)));
+        }
+        html.push_str(&format!("<script>{}</script>\n</body>\n</html>\n", SCRIPT));
+        html
+    }
+
+    fn push_platforms(&self, html: &mut String) {
+        if self.analysis.platform_impacts.is_empty() {
+            return;
+        }
+        html.push_str("<h2>Platform Impact</h2>\n<table class=\"sortable\">\n");
+        html.push_str("<tr><th>Platform</th><th>Impact %</th><th>Affected Files</th><th>Affected Lines</th><th>Total Lines</th></tr>\n");
+
+        let mut platforms: Vec<_> = self.analysis.platform_impacts.iter().collect();
+        platforms.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, impact) in platforms {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                escape(name),
+                self.numbers.percent(impact.impact_ratio),
+                impact.affected_files.len(),
+                impact.affected_lines,
+                impact.total_lines
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    fn push_goals(&self, html: &mut String) {
+        if self.analysis.goals.is_empty() {
+            return;
+        }
+        html.push_str("<h2>Coverage Goals</h2>\n<table>\n");
+        html.push_str("<tr><th>Goal</th><th>Target %</th><th>Impact %</th><th>Status</th></tr>\n");
+        for goal in &self.analysis.goals {
+            let (class, status) = if goal.met { ("up", "met") } else { ("down", "missed") };
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"{}\">{}</td></tr>\n",
+                escape(&goal.name),
+                self.numbers.percent(goal.target),
+                self.numbers.percent(goal.impact_ratio),
+                class,
+                status
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    fn push_variants(&self, html: &mut String) {
+        if self.analysis.variants.is_empty() {
+            return;
+        }
+        html.push_str("<h2>Variant Impact</h2>\n<table>\n");
+        html.push_str("<tr><th>Variant</th><th>Impact %</th><th>Affected Lines</th><th>Total Lines</th><th>Files</th><th>Own Files</th><th>Per Platform</th></tr>\n");
+        for variant in &self.analysis.variants {
+            let platforms: Vec<String> = variant
+                .platform_ratios
+                .iter()
+                .map(|(platform, ratio)| format!("{} {}", escape(platform), self.numbers.percent(*ratio)))
+                .collect();
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}</td></tr>\n",
+                escape(&variant.name),
+                self.numbers.percent(variant.impact_ratio),
+                variant.affected_lines,
+                variant.total_lines,
+                variant.total_files,
+                variant.specific_files,
+                platforms.join(", ")
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    /// Line chart of overall and per-platform impact across runs
+    fn push_trend(&self, html: &mut String) {
+        html.push_str("<h2>Impact Over Time</h2>\n");
+        if self.history.len() < 2 {
+            html.push_str("<p class=\"note\">Trend charts appear once at least two runs are recorded in the run history.</p>\n");
+            return;
+        }
+
+        let platforms: BTreeSet<&str> = self
+            .history
+            .iter()
+            .flat_map(|run| run.platform_ratios.keys().map(String::as_str))
+            .collect();
+        let mut series: Vec<(&str, Vec<Option<f64>>)> = vec![(
+            "Overall",
+            self.history.iter().map(|run| Some(run.impact_ratio)).collect(),
+        )];
+        for platform in platforms {
+            series.push((
+                platform,
+                self.history
+                    .iter()
+                    .map(|run| run.platform_ratios.get(platform).copied())
+                    .collect(),
+            ));
+        }
+
+        // Scale the y axis to the next 10% above the highest value
+        let max_ratio = series
+            .iter()
+            .flat_map(|(_, values)| values.iter().flatten())
+            .fold(0.0_f64, |max, v| max.max(*v));
+        let y_max = ((max_ratio * 10.0).ceil() / 10.0).clamp(0.1, 1.0);
+
+        let plot_width = CHART_WIDTH - 2.0 * CHART_PADDING;
+        let plot_height = CHART_HEIGHT - 2.0 * CHART_PADDING;
+        let x = |index: usize| {
+            CHART_PADDING + plot_width * index as f64 / (self.history.len() - 1) as f64
+        };
+        let y = |ratio: f64| CHART_PADDING + plot_height * (1.0 - ratio / y_max);
+
+        html.push_str(&format!(
+            "<svg viewBox=\"0 0 {w} {h}\" width=\"100%\" role=\"img\" aria-label=\"Impact coverage over time\">\n",
+            w = CHART_WIDTH,
+            h = CHART_HEIGHT
+        ));
+        for step in 0..=4 {
+            let ratio = y_max * step as f64 / 4.0;
+            html.push_str(&format!(
+                "<line x1=\"{x1}\" x2=\"{x2}\" y1=\"{y:.1}\" y2=\"{y:.1}\" stroke=\"#d0d7de\"/>\
+                 <text x=\"{tx}\" y=\"{ty:.1}\" font-size=\"10\" text-anchor=\"end\" fill=\"#656d76\">{label:.0}%</text>\n",
+                x1 = CHART_PADDING,
+                x2 = CHART_WIDTH - CHART_PADDING,
+                y = y(ratio),
+                tx = CHART_PADDING - 4.0,
+                ty = y(ratio) + 3.0,
+                label = ratio * 100.0
+            ));
+        }
+        let last = self.history.len() - 1;
+        for (index, anchor) in [(0, "start"), (last, "end")] {
+            html.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"10\" text-anchor=\"{}\" fill=\"#656d76\">{}</text>\n",
+                x(index),
+                CHART_HEIGHT - CHART_PADDING / 2.0,
+                anchor,
+                format_date(self.history[index].generated_at)
+            ));
+        }
+
+        for (i, (_, values)) in series.iter().enumerate() {
+            let points: Vec<String> = values
+                .iter()
+                .enumerate()
+                .filter_map(|(index, value)| value.map(|v| format!("{:.1},{:.1}", x(index), y(v))))
+                .collect();
+            html.push_str(&format!(
+                "<polyline fill=\"none\" stroke=\"{}\" stroke-width=\"2\" points=\"{}\"/>\n",
+                SERIES_COLORS[i % SERIES_COLORS.len()],
+                points.join(" ")
+            ));
+        }
+        // Runs recorded in CI link their overall point to the build that produced them
+        for (index, run) in self.history.iter().enumerate() {
+            let Some(build_url) = &run.build_url else {
+                continue;
+            };
+            html.push_str(&format!(
+                "<a href=\"{url}\"><circle cx=\"{cx:.1}\" cy=\"{cy:.1}\" r=\"4\" fill=\"{color}\"><title>{date}: {ratio} (open build)</title></circle></a>\n",
+                url = escape(build_url),
+                cx = x(index),
+                cy = y(run.impact_ratio),
+                color = SERIES_COLORS[0],
+                date = format_date(run.generated_at),
+                ratio = self.numbers.percent(run.impact_ratio)
+            ));
+        }
+        html.push_str("</svg>\n<div class=\"legend\">");
+        for (i, (name, _)) in series.iter().enumerate() {
+            html.push_str(&format!(
+                "<span><i style=\"background:{}\"></i>{}</span>",
+                SERIES_COLORS[i % SERIES_COLORS.len()],
+                escape(name)
+            ));
+        }
+        html.push_str("</div>\n");
+    }
+
+    /// Symbols whose references changed most since the previous run
+    fn push_churn(&self, html: &mut String) {
+        let [.., previous, current] = self.history else {
+            return;
+        };
+
+        html.push_str("<h2>Top Symbol Churn</h2>\n");
+        let churn = symbol_churn(previous, current);
+        if churn.is_empty() {
+            html.push_str("<p class=\"note\">No change in symbol references since the previous run.</p>\n");
+            return;
+        }
+
+        html.push_str("<table>\n<tr><th>Symbol</th><th>Previous</th><th>Current</th><th>Change</th><th>Trend</th></tr>\n");
+        for (name, before, after) in churn {
+            let delta = after as i64 - before as i64;
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num {}\">{:+}</td><td>{}</td></tr>\n",
+                self.symbol_name(name),
+                before,
+                after,
+                if delta > 0 { "up" } else { "down" },
+                delta,
+                self.sparkline(name)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    /// Shared symbols added, removed, or renamed since the previous run
+    fn push_api_changes(&self, html: &mut String) {
+        let Some(churn) = &self.analysis.api_churn else {
+            return;
+        };
+
+        html.push_str(&format!(
+            "<h2>Shared API Changes</h2>\n<p class=\"note\">Since {} — impact {}</p>\n",
+            escape(churn.since_commit.as_deref().unwrap_or("the previous run")),
+            self.numbers.signed_percent(churn.impact_ratio_change)
+        ));
+        let changes = super::api_changes(churn);
+        if changes.is_empty() {
+            html.push_str("<p class=\"note\">No shared API added, removed, or renamed.</p>\n");
+            return;
+        }
+
+        html.push_str("<table>\n<tr><th>Change</th><th>Symbol</th></tr>\n");
+        for (change, symbol) in changes {
+            let class = match change {
+                "added" => "up",
+                "removed" => "down",
+                _ => "",
+            };
+            html.push_str(&format!(
+                "<tr><td class=\"{}\">{}</td><td><code>{}</code></td></tr>\n",
+                class,
+                change,
+                escape(&symbol)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    /// Tiny inline chart of a symbol's references across the history
+    fn sparkline(&self, symbol: &str) -> String {
+        let counts: Vec<usize> = self
+            .history
+            .iter()
+            .map(|run| run.symbol_references.get(symbol).copied().unwrap_or(0))
+            .collect();
+        let max = counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+        let step = 100.0 / (counts.len() - 1) as f64;
+        let points: Vec<String> = counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| format!("{:.1},{:.1}", i as f64 * step, 18.0 - 16.0 * *count as f64 / max))
+            .collect();
+        format!(
+            "<svg viewBox=\"0 0 100 20\" width=\"100\" height=\"20\"><polyline fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\" points=\"{}\"/></svg>",
+            SERIES_COLORS[0],
+            points.join(" ")
+        )
+    }
+
+    /// Most referenced symbols, followed by the usage locations of each, collapsed
+    fn push_top_symbols(&self, html: &mut String) {
+        if self.analysis.symbol_usages.is_empty() {
+            return;
+        }
+        html.push_str("<h2>Top Used KMP Symbols</h2>\n<table class=\"sortable\">\n<tr><th>Symbol</th><th>References</th><th>Used in Files</th></tr>\n");
+
+        let mut symbols: Vec<_> = self.analysis.symbol_usages.iter().collect();
+        symbols.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
+        symbols.truncate(MAX_TOP_SYMBOLS);
+        for (name, usages) in &symbols {
+            let files: BTreeSet<&str> = usages.iter().map(|u| u.file_path.as_str()).collect();
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                self.symbol_name(name),
+                usages.len(),
+                files.len()
+            ));
+        }
+        html.push_str("</table>\n");
+
+        for (name, usages) in symbols {
+            let mut sites: Vec<_> = usages.iter().collect();
+            sites.sort_by(|a, b| (&a.file_path, a.line_number).cmp(&(&b.file_path, b.line_number)));
+            html.push_str(&format!(
+                "<details><summary>{} — {} usage location(s)</summary>\n<ul class=\"sites\">\n",
+                self.symbol_name(name),
+                sites.len()
+            ));
+            for usage in sites {
+                html.push_str(&format!(
+                    "<li><code>{}:{}</code> {} <code>{}</code></li>\n",
+                    escape(&self.relative(&usage.file_path)),
+                    usage.line_number,
+                    usage.kind.label(),
+                    escape(usage.context.trim())
+                ));
+            }
+            html.push_str("</ul>\n</details>\n");
+        }
+    }
+
+    fn push_entry_points(&self, html: &mut String) {
+        let entry_points = super::Reporter::entry_points(self.analysis);
+        if entry_points.is_empty() {
+            return;
+        }
+        html.push_str("<h2>Shared Entry Points</h2>\n<table>\n<tr><th>Platform</th><th>Entry Point</th><th>Module</th><th>Usages</th><th>Share</th><th>Reaches</th></tr>\n");
+        for (platform, entry) in entry_points {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td title=\"{}\">{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td title=\"{}\">{}</td></tr>\n",
+                escape(platform),
+                escape(&entry.file),
+                self.symbol_name(&entry.symbol),
+                escape(&entry.module),
+                entry.usages,
+                self.numbers.percent(entry.share),
+                escape(&entry.reached_symbols.join(", ")),
+                escape(&super::Reporter::reached_summary(entry))
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    fn push_usage_kinds(&self, html: &mut String) {
+        if self.analysis.usage_kinds.is_empty() {
+            return;
+        }
+        html.push_str("<h2>Usage by Call-Site Kind</h2>\n<table>\n<tr><th>Kind</th><th>Usages</th><th>Share</th><th>Symbols</th><th>App Files</th></tr>\n");
+        for (count, share) in super::Reporter::usage_kind_shares(self.analysis) {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                count.kind.label(),
+                count.usages,
+                self.numbers.percent(share),
+                count.symbols,
+                count.files
+            ));
+        }
+        html.push_str("</table>\n");
+        if let Some(line) = super::Reporter::pass_through_line(self.analysis) {
+            html.push_str(&format!("<p>{}</p>\n", escape(&line)));
+        }
+    }
+
+    /// Affected app files, most affected lines first, with how shared code reaches each
+    fn push_files(&self, html: &mut String) {
+        let mut files: Vec<_> = self.analysis.files.iter().filter(|f| f.affected_lines > 0).collect();
+        if files.is_empty() {
+            return;
+        }
+        files.sort_by(|a, b| b.affected_lines.cmp(&a.affected_lines).then_with(|| a.path.cmp(&b.path)));
+
+        html.push_str("<h2>Affected Files</h2>\n<table class=\"sortable\">\n<tr><th>File</th><th>Platform</th><th>Layer</th><th>Affected Lines</th><th>Usages</th><th>Reached</th></tr>\n");
+        for file in files {
+            let reached = match file.affected_via.last() {
+                _ if file.direct => "directly".to_string(),
+                Some(edge) => format!("via {}", self.relative(&edge.to)),
+                None => "via dependencies".to_string(),
+            };
+            html.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}</td></tr>\n",
+                escape(&self.relative(&file.path)),
+                escape(&file.platform),
+                escape(&file.layer),
+                file.affected_lines,
+                file.usages,
+                escape(&reached)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    /// Collapsible excerpts of every file with usage sites, usage lines highlighted
+    fn push_sources(&self, html: &mut String) {
+        let Some(sources) = self.sources else {
+            return;
+        };
+
+        html.push_str("<h2>Usage Sites</h2>\n");
+        for excerpt in &sources.files {
+            html.push_str(&format!(
+                "<details><summary><code>{}</code> — {} usage line(s)</summary>\n<pre class=\"src\">",
+                escape(&excerpt.file),
+                excerpt.usages
+            ));
+            for line in &excerpt.lines {
+                let mut class = String::from("line");
+                if line.is_usage {
+                    class.push_str(" hit");
+                }
+                if line.after_gap {
+                    class.push_str(" gap");
+                }
+                html.push_str(&format!(
+                    "<span class=\"{}\"><span class=\"ln\">{}</span>{}</span>",
+                    class, line.number, line.html
+                ));
+            }
+            html.push_str("</pre>\n</details>\n");
+        }
+        if sources.omitted_files > 0 {
+            html.push_str(&format!(
+                "<p class=\"note\">{} more file(s) left out to keep excerpts under {} KiB; raise <code>max_source_bytes</code> in <code>[report]</code> to include them.</p>\n",
+                sources.omitted_files,
+                sources.max_bytes / 1024
+            ));
+        }
+    }
+
+    fn push_diagnostics(&self, html: &mut String) {
+        if self.analysis.diagnostics.is_empty() {
+            return;
+        }
+        html.push_str("<h2>Diagnostics</h2>\n<table>\n<tr><th>Severity</th><th>Rule</th><th>Message</th></tr>\n");
+        for diagnostic in &self.analysis.diagnostics {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td><code>{}</code></td><td>{}</td></tr>\n",
+                diagnostic.severity.name(),
+                escape(&diagnostic.rule),
+                escape(&diagnostic.message)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    /// A symbol name as code, linked to its API documentation when a doc base URL is set
+    fn symbol_name(&self, name: &str) -> String {
+        let code = format!("<code>{}</code>", escape(name));
+        match self.doc_links.and_then(|resolver| resolver.link_for_name(&self.analysis.symbols, name)) {
+            Some(link) => format!("<a href=\"{}\">{}</a>", escape(&link), code),
+            None => code,
+        }
+    }
+
+    /// `path` relative to the project root, when one is set
+    fn relative(&self, path: &str) -> String {
+        let full = Path::new(path);
+        let relative = self
+            .project_root
+            .and_then(|root| full.strip_prefix(root).ok())
+            .unwrap_or(full);
+        relative.to_string_lossy().trim_start_matches("./").replace('\\', "/")
+    }
+}
+
+/// Symbols with changed references between two runs, largest change first
+fn symbol_churn<'r>(previous: &'r RunSnapshot, current: &'r RunSnapshot) -> Vec<(&'r str, usize, usize)> {
+    let names: BTreeSet<&str> = previous
+        .symbol_references
+        .keys()
+        .chain(current.symbol_references.keys())
+        .map(String::as_str)
+        .collect();
+
+    let mut churn: Vec<(&str, usize, usize)> = names
+        .into_iter()
+        .map(|name| {
+            let before = previous.symbol_references.get(name).copied().unwrap_or(0);
+            let after = current.symbol_references.get(name).copied().unwrap_or(0);
+            (name, before, after)
+        })
+        .filter(|(_, before, after)| before != after)
+        .collect();
+    churn.sort_by_key(|(_, before, after)| std::cmp::Reverse(before.abs_diff(*after)));
+    churn.truncate(MAX_CHURN_SYMBOLS);
+    churn
+}
+
+/// Formats a Unix timestamp as a UTC `YYYY-MM-DD` date
+fn format_date(timestamp: u64) -> String {
+    // Civil-from-days conversion (proleptic Gregorian calendar)
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Escapes text for HTML element content and attribute values
+pub(super) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn run(generated_at: u64, impact_ratio: f64, references: &[(&str, usize)]) -> RunSnapshot {
+        RunSnapshot {
+            api: None,
+            generated_at,
+            commit_sha: None,
+            impact_ratio,
+            platform_ratios: BTreeMap::from([("Android".to_string(), impact_ratio)]),
+            symbol_references: references
+                .iter()
+                .map(|(name, count)| (name.to_string(), *count))
+                .collect(),
+            build_url: None,
+        }
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(1_700_000_000), "2023-11-14");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+    }
+
+    #[test]
+    fn test_churn_orders_by_largest_change() {
+        let previous = run(0, 0.1, &[("User", 10), ("Gone", 2), ("Stable", 5)]);
+        let current = run(86_400, 0.2, &[("User", 4), ("New", 3), ("Stable", 5)]);
+
+        let churn = symbol_churn(&previous, &current);
+        assert_eq!(churn, vec![("User", 10, 4), ("New", 0, 3), ("Gone", 2, 0)]);
+    }
+
+    #[test]
+    fn test_dashboard_embeds_trend_chart_with_history() {
+        let analysis = ImpactAnalysis::default();
+        let without_history = HtmlDashboard::new(&analysis, &[]).render(None);
+        assert!(!without_history.contains("<polyline"));
+
+        let mut history = vec![run(0, 0.1, &[("User", 1)]), run(86_400, 0.3, &[("User", 2)])];
+        history[1].build_url = Some("https://ci.example.com/job/7?a=1&b=2".to_string());
+        let html = HtmlDashboard::new(&analysis, &history).render(Some("<run>"));
+        assert!(html.contains("<a href=\"https://ci.example.com/job/7?a=1&amp;b=2\"><circle"));
+        assert_eq!(html.matches("<circle").count(), 1);
+        assert!(html.contains("aria-label=\"Impact coverage over time\""));
+        assert!(html.contains("Top Symbol Churn"));
+        assert!(html.contains("1970-01-02"));
+        assert!(html.contains("<footer>&lt;run&gt;</footer>"));
+        assert!(!html.contains("Usage Sites"));
+    }
+
+    #[test]
+    fn test_dashboard_embeds_source_excerpts() {
+        use super::super::source_viewer::{ExcerptLine, SourceExcerpt};
+
+        let analysis = ImpactAnalysis::default();
+        let sources = SourceExcerpts {
+            files: vec![SourceExcerpt {
+                file: "app/Main.kt".to_string(),
+                usages: 1,
+                lines: vec![ExcerptLine {
+                    number: 12,
+                    html: "<span class=\"sym\">User</span>()".to_string(),
+                    is_usage: true,
+                    after_gap: true,
+                }],
+            }],
+            omitted_files: 2,
+            max_bytes: 4096,
+        };
+        let html = HtmlDashboard::new(&analysis, &[]).with_sources(&sources).render(None);
+        assert!(html.contains("<summary><code>app/Main.kt</code> — 1 usage line(s)</summary>"));
+        assert!(html.contains("<span class=\"line hit gap\"><span class=\"ln\">12</span><span class=\"sym\">User</span>()</span>"));
+        assert!(html.contains("2 more file(s) left out to keep excerpts under 4 KiB"));
+    }
+
+    #[test]
+    fn test_dashboard_lists_affected_files_and_usage_locations() {
+        use crate::domain::{DependencyEdge, FileMetrics, SymbolUsage, UsageKind};
+        use std::collections::HashMap;
+
+        let usage = SymbolUsage {
+            symbol_name: "User".to_string(),
+            file_path: "/repo/app/Main.kt".to_string(),
+            line_number: 7,
+            context: "  val user = User(\"<a>\")".to_string(),
+            kind: UsageKind::Instantiation,
+        };
+        let file = |path: &str, affected_lines: usize, direct: bool| FileMetrics {
+            path: path.to_string(),
+            platform: "Android".to_string(),
+            total_lines: affected_lines.max(3),
+            affected_lines,
+            direct,
+            layer: "presentation".to_string(),
+            ..Default::default()
+        };
+        let mut screen = file("/repo/app/Screen.kt", 12, false);
+        screen.affected_via = vec![DependencyEdge {
+            from: screen.path.clone(),
+            to: usage.file_path.clone(),
+            imports: Vec::new(),
+        }];
+        let analysis = ImpactAnalysis {
+            symbol_usages: HashMap::from([("User".to_string(), vec![usage.clone()])]),
+            files: vec![file("/repo/app/Main.kt", 20, true), screen, file("/repo/app/Idle.kt", 0, false)],
+            ..Default::default()
+        };
+
+        let html = HtmlDashboard::new(&analysis, &[]).with_project_root("/repo").render(None);
+        assert!(html.contains("<h2>Affected Files</h2>\n<table class=\"sortable\">"));
+        assert!(html.contains("<tr><td><code>app/Main.kt</code></td><td>Android</td><td>presentation</td><td class=\"num\">20</td><td class=\"num\">0</td><td>directly</td></tr>"));
+        assert!(html.contains("<td>via app/Main.kt</td>"));
+        assert!(!html.contains("Idle.kt"));
+        assert!(html.find("app/Main.kt</code></td><td>Android") < html.find("app/Screen.kt"));
+        assert!(html.contains("<summary><code>User</code> — 1 usage location(s)</summary>"));
+        assert!(html.contains("<li><code>app/Main.kt:7</code> instantiation <code>val user = User(&quot;&lt;a&gt;&quot;)</code></li>"));
+        assert!(html.contains("<script>"));
+    }
+
+    #[test]
+    fn test_dashboard_links_symbols_to_docs() {
+        use crate::domain::{Symbol, SymbolType, SymbolUsage, UsageKind};
+        use std::collections::HashMap;
+
+        let usage = |name: &str| SymbolUsage {
+            symbol_name: name.to_string(),
+            file_path: "/repo/app/Main.kt".to_string(),
+            line_number: 3,
+            context: String::new(),
+            kind: UsageKind::Reference,
+        };
+        let user = Symbol {
+            package: "com.example".to_string(),
+            ..Symbol::new("User", SymbolType::Class, "shared", "shared/src/commonMain/kotlin/User.kt")
+        };
+        let analysis = ImpactAnalysis {
+            symbols: vec![user],
+            symbol_usages: HashMap::from([
+                ("User".to_string(), vec![usage("User")]),
+                ("Unknown".to_string(), vec![usage("Unknown")]),
+            ]),
+            ..Default::default()
+        };
+
+        let resolver = DocLinkResolver::new("https://docs.example.com/api/");
+        let html = HtmlDashboard::new(&analysis, &[]).with_doc_links(&resolver).render(None);
+        let link = "<a href=\"https://docs.example.com/api/shared/com.example/-user/index.html\"><code>User</code></a>";
+        assert!(html.contains(&format!("<tr><td>{}</td>", link)));
+        assert!(html.contains(&format!("<summary>{} — 1 usage location(s)</summary>", link)));
+        assert!(html.contains("<tr><td><code>Unknown</code></td>"));
+
+        let unlinked = HtmlDashboard::new(&analysis, &[]).render(None);
+        assert!(!unlinked.contains("<a href"));
+    }
+
+    #[test]
+    fn test_dashboard_uses_number_format() {
+        use crate::domain::{PlatformImpact, RatioPrecision};
+        use std::collections::HashMap;
+
+        let mut android = PlatformImpact::new("Android".to_string());
+        android.impact_ratio = 0.45678;
+        let analysis = ImpactAnalysis {
+            impact_ratio: 0.45678,
+            platform_impacts: HashMap::from([("Android".to_string(), android)]),
+            ..Default::default()
+        };
+
+        let default = HtmlDashboard::new(&analysis, &[]).render(None);
+        assert!(default.contains("<div class=\"value\">45.68%</div>"));
+
+        let precision = RatioPrecision {
+            decimals: 1,
+            ..Default::default()
+        };
+        let german = NumberFormat::for_locale("de-DE").unwrap().with_precision(precision);
+        let html = HtmlDashboard::new(&analysis, &[]).with_number_format(german).render(None);
+        assert!(html.contains("<div class=\"value\">45,7\u{a0}%</div>"));
+        assert!(html.contains("<tr><td>Android</td><td class=\"num\">45,7\u{a0}%</td>"));
+        assert!(!html.contains("45.68"));
+    }
+}
@@ -0,0 +1,219 @@
+//! Mermaid flowchart of the sharing topology
+//! Draws KMP modules pointing at the app packages that use them directly,
+//! with app files collapsed into their package (or directory for Swift)
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::domain::ImpactAnalysis;
+
+/// App package nodes drawn before the rest are merged into a single node
+pub const MAX_APP_NODES: usize = 40;
+
+const OTHER_PACKAGES: &str = "other packages";
+
+/// Flowchart definition of KMP modules → directly affected app packages
+pub struct MermaidDiagram;
+
+impl MermaidDiagram {
+    /// Builds the flowchart; file paths are shown relative to `project_root`
+    pub fn from_analysis(analysis: &ImpactAnalysis, project_root: Option<&str>) -> String {
+        let file_platforms: BTreeMap<&str, &str> = analysis
+            .platform_impacts
+            .iter()
+            .flat_map(|(name, impact)| {
+                impact
+                    .affected_files
+                    .iter()
+                    .map(move |file| (file.as_str(), name.as_str()))
+            })
+            .collect();
+
+        // (module, (platform, package)) -> references
+        let mut edges: BTreeMap<(&str, (String, String)), usize> = BTreeMap::new();
+        for usage in analysis.symbol_usages.values().flatten() {
+            let Some(symbol) = analysis.symbols.iter().find(|s| s.name == usage.symbol_name) else {
+                continue;
+            };
+            let platform = file_platforms
+                .get(usage.file_path.as_str())
+                .copied()
+                .unwrap_or("App");
+            let package = Self::package_label(&usage.file_path, project_root);
+            *edges
+                .entry((symbol.module.as_str(), (platform.to_string(), package)))
+                .or_default() += 1;
+        }
+
+        // Keep the most referenced packages and merge the rest per platform
+        let mut package_references: BTreeMap<&(String, String), usize> = BTreeMap::new();
+        for ((_, package), references) in &edges {
+            *package_references.entry(package).or_default() += references;
+        }
+        let mut ranked: Vec<(&(String, String), usize)> = package_references.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let kept: BTreeSet<(String, String)> = ranked
+            .into_iter()
+            .take(MAX_APP_NODES)
+            .map(|(package, _)| package.clone())
+            .collect();
+
+        let mut collapsed: BTreeMap<(&str, (String, String)), usize> = BTreeMap::new();
+        for ((module, package), references) in edges {
+            let package = if kept.contains(&package) {
+                package
+            } else {
+                (package.0, OTHER_PACKAGES.to_string())
+            };
+            *collapsed.entry((module, package)).or_default() += references;
+        }
+
+        let modules: BTreeSet<&str> = collapsed.keys().map(|(module, _)| *module).collect();
+        let mut packages_by_platform: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for (_, (platform, package)) in collapsed.keys() {
+            packages_by_platform
+                .entry(platform.as_str())
+                .or_default()
+                .insert(package.as_str());
+        }
+
+        let module_ids: BTreeMap<&str, String> = modules
+            .iter()
+            .enumerate()
+            .map(|(i, module)| (*module, format!("m{}", i)))
+            .collect();
+        let mut package_ids: BTreeMap<(&str, &str), String> = BTreeMap::new();
+
+        let mut diagram = String::from("flowchart LR\n");
+        diagram.push_str("    subgraph shared[\"Shared KMP modules\"]\n");
+        for (module, id) in &module_ids {
+            diagram.push_str(&format!("        {}[\"{}\"]\n", id, Self::escape(module)));
+        }
+        diagram.push_str("    end\n");
+
+        for (p, (platform, packages)) in packages_by_platform.iter().enumerate() {
+            diagram.push_str(&format!(
+                "    subgraph platform{}[\"{}\"]\n",
+                p,
+                Self::escape(platform)
+            ));
+            for package in packages {
+                let id = format!("p{}", package_ids.len());
+                diagram.push_str(&format!("        {}[\"{}\"]\n", id, Self::escape(package)));
+                package_ids.insert((platform, package), id);
+            }
+            diagram.push_str("    end\n");
+        }
+
+        for ((module, (platform, package)), references) in &collapsed {
+            diagram.push_str(&format!(
+                "    {} -->|{}| {}\n",
+                module_ids[module],
+                references,
+                package_ids[&(platform.as_str(), package.as_str())]
+            ));
+        }
+
+        diagram
+    }
+
+    /// Package of an app file: the Kotlin/Java package below the source root
+    /// (prefixed with the module), or the directory for other sources
+    fn package_label(file_path: &str, project_root: Option<&str>) -> String {
+        let path = Path::new(file_path);
+        let relative = project_root
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        let dir: Vec<String> = relative
+            .parent()
+            .map(|p| {
+                p.components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .filter(|c| c != ".")
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let source_root = dir.iter().position(|c| c == "src").filter(|i| {
+            matches!(dir.get(i + 2).map(String::as_str), Some("java" | "kotlin"))
+        });
+        match source_root {
+            Some(src) => {
+                let module = dir[..src].join("/");
+                let package = dir[src + 3..].join(".");
+                match (module.is_empty(), package.is_empty()) {
+                    (true, _) => package,
+                    (false, true) => module,
+                    (false, false) => format!("{} · {}", module, package),
+                }
+            }
+            None if dir.is_empty() => ".".to_string(),
+            None => dir.join("/"),
+        }
+    }
+
+    /// Mermaid labels are quoted, so only quotes need escaping
+    fn escape(label: &str) -> String {
+        label.replace('"', "#quot;")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{PlatformImpact, Symbol, SymbolType, SymbolUsage, UsageKind};
+
+    fn symbol(name: &str, module: &str) -> Symbol {
+        Symbol::new(name, SymbolType::Class, module, "")
+    }
+
+    fn usage(symbol_name: &str, file: &str) -> SymbolUsage {
+        SymbolUsage {
+            symbol_name: symbol_name.to_string(),
+            file_path: file.to_string(),
+            line_number: 1,
+            context: String::new(),
+            kind: UsageKind::Reference,
+        }
+    }
+
+    #[test]
+    fn test_package_label() {
+        let label = |file| MermaidDiagram::package_label(file, Some("/repo"));
+        assert_eq!(
+            label("/repo/app/src/main/java/com/example/Main.kt"),
+            "app · com.example"
+        );
+        assert_eq!(label("/repo/iosApp/Views/ContentView.swift"), "iosApp/Views");
+    }
+
+    #[test]
+    fn test_collapses_files_per_package() {
+        let mut analysis = ImpactAnalysis {
+            symbols: vec![symbol("User", "shared"), symbol("Clock", "core")],
+            ..Default::default()
+        };
+        let files = [
+            "/repo/app/src/main/kotlin/com/example/A.kt",
+            "/repo/app/src/main/kotlin/com/example/B.kt",
+        ];
+        analysis.symbol_usages.insert(
+            "User".to_string(),
+            files.iter().map(|f| usage("User", f)).collect(),
+        );
+        analysis
+            .symbol_usages
+            .insert("Clock".to_string(), vec![usage("Clock", files[0])]);
+        let mut android = PlatformImpact::new("Android".to_string());
+        android.affected_files = files.iter().map(|f| f.to_string()).collect();
+        analysis.platform_impacts.insert("Android".to_string(), android);
+
+        let diagram = MermaidDiagram::from_analysis(&analysis, Some("/repo"));
+        assert!(diagram.starts_with("flowchart LR\n"));
+        assert!(diagram.contains("m0[\"core\"]"));
+        assert!(diagram.contains("subgraph platform0[\"Android\"]"));
+        assert_eq!(diagram.matches("app · com.example").count(), 1);
+        assert!(diagram.contains("m0 -->|1| p0"));
+        assert!(diagram.contains("m1 -->|2| p0"));
+    }
+}
@@ -1,13 +1,53 @@
 use anyhow::Result;
 use prettytable::{Cell, Row, Table};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fs;
+use std::path::Path;
+use std::sync::Arc;
 
+use crate::adapters::{FileSystem, OsFileSystem};
 use crate::analyzer::models::AnalysisResult;
-use crate::domain::ImpactAnalysis;
+use crate::domain::{
+    AndroidModuleImpact, ApiChurn, AppLayers, BatchRepository, BatchSummary, EntryPoint, FileMetrics, PlatformImpact, CategoryUsage, GoalProgress, GradleModuleKind, ImpactAnalysis, ImpactMetric, LayerImpact,
+    ProjectDetection, ReportDiff, RunMetadata, RunSnapshot, StaleIntegration, SwiftModuleImpact, SwiftModuleKind, UsageKindCount, VariantImpact,
+};
+
+pub mod diff;
+pub mod doc_links;
+pub mod github_checks;
+pub mod html;
+pub mod mermaid;
+pub mod number_format;
+pub mod problem_matcher;
+pub mod review_pack;
+pub mod source_viewer;
+
+pub use diff::DiffReport;
+pub use doc_links::DocLinkResolver;
+pub use github_checks::CheckRunPayload;
+pub use html::HtmlDashboard;
+pub use mermaid::MermaidDiagram;
+pub use number_format::{ExactRatio, ExactRatios, FormattedNumbers, NumberFormat};
+pub use problem_matcher::ProblemLines;
+pub use review_pack::ReviewPack;
+pub use source_viewer::SourceExcerpts;
 
 /// Reporter for outputting analysis results in various formats
 pub struct Reporter {
     format: ReportFormat,
+    doc_links: Option<DocLinkResolver>,
+    project_root: Option<String>,
+    history: Vec<RunSnapshot>,
+    /// File system and size budget of the HTML source excerpts, when embedded
+    sources: Option<(Arc<dyn FileSystem>, usize)>,
+    /// Sections of table and Markdown impact reports shown after the summary
+    sections: BTreeSet<ReportSection>,
+    /// Print only the one-line summary of an impact report, and nothing for other reports
+    quiet: bool,
+    /// Separators of the percentages and counts in human-readable reports
+    numbers: NumberFormat,
+    /// File system app files are read through to locate usage columns for problem matchers
+    fs: Arc<dyn FileSystem>,
 }
 
 /// Report output format
@@ -16,6 +56,92 @@ pub enum ReportFormat {
     Table,
     Json,
     Markdown,
+    /// GitHub Checks API payload with line-level annotations
+    GitHubChecks,
+    /// Self-contained HTML dashboard with trend charts
+    Html,
+    /// Mermaid flowchart of KMP modules and the app packages using them
+    Mermaid,
+    /// Comma-separated values of the batch matrix
+    Csv,
+    /// Compiler-style `path:line:col: note: ...` lines for editor problem matchers
+    Problems,
+    /// Review comment fixtures of the files a `--diff` run changed, with `CODEOWNERS` reviewers
+    ReviewPack,
+}
+
+impl ReportFormat {
+    /// Names accepted by [`Reporter::new`]
+    pub const NAMES: &'static [&'static str] =
+        &["table", "json", "markdown", "github-checks", "html", "mermaid", "csv", "problems", "review-pack"];
+}
+
+/// Optional section of table and Markdown impact reports; the summary is always shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReportSection {
+    /// Per-platform impact, shared entry points, and variants
+    Platforms,
+    Goals,
+    TopSymbols,
+    /// API funnel, parity, stability, category, call-site kind, package, owner, and documentation breakdowns
+    Breakdowns,
+    /// Android and iOS modules, module dependencies, expect/actual coverage, and the sharing topology
+    Modules,
+    /// Deprecated API usage and unused shared imports to clean up
+    Suggestions,
+    ApiChanges,
+    Diagnostics,
+}
+
+impl ReportSection {
+    pub const ALL: &'static [ReportSection] = &[
+        ReportSection::Platforms,
+        ReportSection::Goals,
+        ReportSection::TopSymbols,
+        ReportSection::Breakdowns,
+        ReportSection::Modules,
+        ReportSection::Suggestions,
+        ReportSection::ApiChanges,
+        ReportSection::Diagnostics,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReportSection::Platforms => "platforms",
+            ReportSection::Goals => "goals",
+            ReportSection::TopSymbols => "top-symbols",
+            ReportSection::Breakdowns => "breakdowns",
+            ReportSection::Modules => "modules",
+            ReportSection::Suggestions => "suggestions",
+            ReportSection::ApiChanges => "api-changes",
+            ReportSection::Diagnostics => "diagnostics",
+        }
+    }
+
+    /// Sections selected by names such as `platforms` or `no-diagnostics`
+    ///
+    /// Listing sections shows only those; listing only `no-` names shows all but them.
+    pub fn select(names: &[String]) -> Result<BTreeSet<ReportSection>> {
+        let parse = |name: &str| {
+            Self::ALL.iter().copied().find(|section| section.name() == name).ok_or_else(|| {
+                let known: Vec<&str> = Self::ALL.iter().map(ReportSection::name).collect();
+                anyhow::anyhow!("Unknown report section `{}` (expected one of: {})", name, known.join(", "))
+            })
+        };
+
+        let mut shown = BTreeSet::new();
+        let mut hidden = BTreeSet::new();
+        for name in names.iter().map(|name| name.trim().to_lowercase()) {
+            match name.strip_prefix("no-") {
+                Some(name) => hidden.insert(parse(name)?),
+                None => shown.insert(parse(&name)?),
+            };
+        }
+        if shown.is_empty() {
+            shown.extend(Self::ALL.iter().copied());
+        }
+        Ok(&shown - &hidden)
+    }
 }
 
 impl Reporter {
@@ -25,10 +151,109 @@ impl Reporter {
             "table" => ReportFormat::Table,
             "json" => ReportFormat::Json,
             "markdown" | "md" => ReportFormat::Markdown,
+            "github-checks" => ReportFormat::GitHubChecks,
+            "html" => ReportFormat::Html,
+            "mermaid" => ReportFormat::Mermaid,
+            "csv" => ReportFormat::Csv,
+            "problems" => ReportFormat::Problems,
+            "review-pack" => ReportFormat::ReviewPack,
             _ => anyhow::bail!("Unsupported output format: {}", format),
         };
 
-        Ok(Self { format })
+        Ok(Self {
+            format,
+            doc_links: None,
+            project_root: None,
+            history: Vec::new(),
+            sources: None,
+            sections: ReportSection::ALL.iter().copied().collect(),
+            quiet: false,
+            numbers: NumberFormat::default(),
+            fs: Arc::new(OsFileSystem),
+        })
+    }
+
+    /// Limits table and Markdown impact reports to the given sections after the summary
+    pub fn with_sections(mut self, sections: BTreeSet<ReportSection>) -> Self {
+        self.sections = sections;
+        self
+    }
+
+    /// Enables deep links to each symbol's API documentation (e.g. a Dokka site)
+    pub fn with_doc_base_url(mut self, base_url: Option<&str>) -> Self {
+        self.doc_links = base_url.map(DocLinkResolver::new);
+        self
+    }
+
+    /// Past runs, oldest first, charted by the HTML dashboard
+    pub fn with_history(mut self, history: Vec<RunSnapshot>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Embeds highlighted excerpts of the usage sites in the HTML dashboard, read
+    /// through `fs` and capped at `max_bytes` in total
+    pub fn with_source_excerpts(mut self, fs: Arc<dyn FileSystem>, max_bytes: usize) -> Self {
+        self.sources = Some((fs, max_bytes));
+        self
+    }
+
+    /// Sets the analyzed project path, so reports can show repository-relative file paths
+    pub fn with_project_root(mut self, project_root: &str) -> Self {
+        self.project_root = Some(project_root.to_string());
+        self
+    }
+
+    /// Keeps the console to the one-line summary of an impact report; `--output` files are still written
+    pub fn with_quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    /// Reads app files through the given file system instead of the disk
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// Prints percentages and counts with the given separators; JSON keeps raw numbers next to them
+    pub fn with_number_format(mut self, numbers: NumberFormat) -> Self {
+        self.numbers = numbers;
+        self
+    }
+
+    /// Writes a report to `output_path`, or prints it; quiet runs print `summary` instead, if any
+    fn emit(&self, content: &str, output_path: Option<&str>, summary: Option<String>) -> Result<()> {
+        if let Some(path) = output_path {
+            fs::write(path, content)?;
+            if !self.quiet {
+                println!("Results saved to file: {}", path);
+            }
+        } else if !self.quiet {
+            println!("{}", content);
+        }
+        if let Some(summary) = summary.filter(|_| self.quiet) {
+            println!("{}", summary);
+        }
+        Ok(())
+    }
+
+    /// One line with the headline numbers of an impact report, for `--quiet` runs
+    pub fn summary_line(&self, analysis: &ImpactAnalysis) -> String {
+        let (affected, total) = analysis.affected_of_total();
+        format!(
+            "Impact coverage {} ({} / {} app {}, {} files directly affected, {} shared symbols)",
+            self.numbers.percent(analysis.impact_ratio),
+            self.numbers.count(affected),
+            self.numbers.count(total),
+            analysis.metric.name(),
+            analysis.affected_files.len(),
+            analysis.total_symbols
+        )
+    }
+
+    fn shows(&self, section: ReportSection) -> bool {
+        self.sections.contains(&section)
     }
 
     /// Outputs the analysis results as a report
@@ -36,19 +261,17 @@ impl Reporter {
     pub fn report(&self, result: &AnalysisResult, output_path: Option<&str>) -> Result<()> {
         let content = match self.format {
             ReportFormat::Table => self.format_as_table(result),
-            ReportFormat::Json => self.format_as_json(result)?,
+            ReportFormat::Json
+            | ReportFormat::GitHubChecks
+            | ReportFormat::Html
+            | ReportFormat::Mermaid
+            | ReportFormat::Problems
+            | ReportFormat::ReviewPack => self.format_as_json(result)?,
             ReportFormat::Markdown => self.format_as_markdown(result),
+            ReportFormat::Csv => anyhow::bail!("CSV output is only available for the batch matrix"),
         };
 
-        // Save to file or print to console
-        if let Some(path) = output_path {
-            fs::write(path, content)?;
-            println!("Results saved to file: {}", path);
-        } else {
-            println!("{}", content);
-        }
-
-        Ok(())
+        self.emit(&content, output_path, None)
     }
 
     /// Formats as a table
@@ -90,14 +313,14 @@ impl Reporter {
             }
 
             output.push_str(&platform_table.to_string());
-            output.push_str("\n");
+            output.push('\n');
         }
 
         // Top used symbols
         if !impact.symbol_usage.is_empty() {
             output.push_str("=== Top 10 Used KMP Symbols ===\n\n");
             let mut symbols: Vec<_> = impact.symbol_usage.iter().collect();
-            symbols.sort_by(|a, b| b.1.reference_count.cmp(&a.1.reference_count));
+            symbols.sort_by_key(|s| std::cmp::Reverse(s.1.reference_count));
 
             let mut symbol_table = Table::new();
             symbol_table.add_row(Row::new(vec![
@@ -115,7 +338,7 @@ impl Reporter {
             }
 
             output.push_str(&symbol_table.to_string());
-            output.push_str("\n");
+            output.push('\n');
         }
 
         // Module details (if any)
@@ -184,7 +407,7 @@ impl Reporter {
                     platform_impact.total_lines
                 ));
             }
-            md.push_str("\n");
+            md.push('\n');
         }
 
         // Top used symbols
@@ -194,7 +417,7 @@ impl Reporter {
             md.push_str("|--------|------------|---------------|\n");
 
             let mut symbols: Vec<_> = impact.symbol_usage.iter().collect();
-            symbols.sort_by(|a, b| b.1.reference_count.cmp(&a.1.reference_count));
+            symbols.sort_by_key(|s| std::cmp::Reverse(s.1.reference_count));
 
             for (symbol_name, usage) in symbols.iter().take(10) {
                 md.push_str(&format!(
@@ -204,7 +427,7 @@ impl Reporter {
                     usage.used_in_files.len()
                 ));
             }
-            md.push_str("\n");
+            md.push('\n');
         }
 
         // Symbol type breakdown
@@ -216,7 +439,12 @@ impl Reporter {
 
         for symbol in &impact.kmp_symbols {
             match symbol.symbol_type {
-                crate::analyzer::models::SymbolType::Class => class_count += 1,
+                crate::analyzer::models::SymbolType::Class
+                | crate::analyzer::models::SymbolType::ValueClass
+                | crate::analyzer::models::SymbolType::Enum
+                | crate::analyzer::models::SymbolType::SealedClass
+                | crate::analyzer::models::SymbolType::DataClass
+                | crate::analyzer::models::SymbolType::AnnotationClass => class_count += 1,
                 crate::analyzer::models::SymbolType::Function => function_count += 1,
                 crate::analyzer::models::SymbolType::Property => property_count += 1,
                 _ => other_count += 1,
@@ -249,6 +477,232 @@ impl Reporter {
         md
     }
 
+    /// Reports detected projects with the strategy and confidence of each detection
+    pub fn report_projects(&self, projects: &[ProjectDetection], output_path: Option<&str>) -> Result<()> {
+        let content = match self.format {
+            ReportFormat::Table => Self::format_projects_as_table(projects),
+            ReportFormat::Json => serde_json::to_string_pretty(projects)?,
+            ReportFormat::Markdown => Self::format_projects_as_markdown(projects),
+            _ => anyhow::bail!("The projects listing supports table, json, and markdown output"),
+        };
+
+        self.emit(&content, output_path, None)
+    }
+
+    fn format_projects_as_table(projects: &[ProjectDetection]) -> String {
+        let mut table = Table::new();
+        table.add_row(Row::new(vec![
+            Cell::new("Type"),
+            Cell::new("Root"),
+            Cell::new("Detected By"),
+            Cell::new("Confidence"),
+            Cell::new("Source Dirs"),
+        ]));
+        for project in projects {
+            table.add_row(Row::new(vec![
+                Cell::new(&project.project_type),
+                Cell::new(Self::display_root(&project.root)),
+                Cell::new(&project.detected_by),
+                Cell::new(&format!("{:.2}", project.confidence)),
+                Cell::new(&project.source_dirs.join("\n")),
+            ]));
+        }
+        format!("=== Detected Projects ===\n\n{}", table)
+    }
+
+    fn format_projects_as_markdown(projects: &[ProjectDetection]) -> String {
+        let mut md = String::from("# Detected Projects\n\n");
+        md.push_str("| Type | Root | Detected By | Confidence | Source Dirs |\n");
+        md.push_str("|------|------|-------------|------------|-------------|\n");
+        for project in projects {
+            md.push_str(&format!(
+                "| {} | `{}` | {} | {:.2} | {} |\n",
+                project.project_type,
+                Self::display_root(&project.root),
+                project.detected_by,
+                project.confidence,
+                project
+                    .source_dirs
+                    .iter()
+                    .map(|dir| format!("`{}`", dir))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        md
+    }
+
+    /// Reports the repository × platform matrix of a batch run
+    pub fn report_batch(&self, summary: &BatchSummary, output_path: Option<&str>) -> Result<()> {
+        let content = match self.format {
+            ReportFormat::Table => self.format_batch_as_table(summary),
+            ReportFormat::Json => serde_json::to_string_pretty(summary)?,
+            ReportFormat::Markdown => self.format_batch_as_markdown(summary),
+            ReportFormat::Csv => Self::format_batch_as_csv(summary),
+            _ => anyhow::bail!("The batch matrix supports table, json, markdown, and csv output"),
+        };
+
+        self.emit(&content, output_path, None)
+    }
+
+    /// Reports the changes between two analysis reports
+    pub fn report_diff(&self, diff: &ReportDiff, output_path: Option<&str>) -> Result<()> {
+        let content = match self.format {
+            ReportFormat::Table => DiffReport::table(diff),
+            ReportFormat::Json => serde_json::to_string_pretty(diff)?,
+            ReportFormat::Markdown => DiffReport::markdown(diff),
+            _ => anyhow::bail!("The report diff supports table, json, and markdown output"),
+        };
+
+        self.emit(&content, output_path, None)
+    }
+
+    fn format_batch_as_table(&self, summary: &BatchSummary) -> String {
+        let platforms = summary.platforms();
+        let mut header = vec![
+            Cell::new("Repository"),
+            Cell::new("Commit"),
+            Cell::new("Impact %"),
+            Cell::new("Affected Lines"),
+            Cell::new("App Lines"),
+        ];
+        header.extend(platforms.iter().map(|p| Cell::new(&format!("{} %", p))));
+        let mut table = Table::new();
+        table.add_row(Row::new(header));
+
+        for repository in &summary.repositories {
+            let mut row = vec![Cell::new(&repository.name), Cell::new(&Self::short_commit(repository))];
+            match &repository.error {
+                Some(_) => row.extend((0..3 + platforms.len()).map(|_| Cell::new("failed"))),
+                None => {
+                    row.extend([
+                        Cell::new(&self.numbers.percent(repository.impact_ratio)),
+                        Cell::new(&self.numbers.count(repository.affected_lines)),
+                        Cell::new(&self.numbers.count(repository.total_app_lines)),
+                    ]);
+                    row.extend(platforms.iter().map(|p| Cell::new(&self.batch_ratio(repository, p))));
+                }
+            }
+            table.add_row(Row::new(row));
+        }
+
+        let mut output = format!("=== Batch Impact Matrix ===\n\n{}", table);
+        for failed in summary.failures() {
+            output.push_str(&format!(
+                "\n{}: {}",
+                failed.name,
+                failed.error.as_deref().unwrap_or_default()
+            ));
+        }
+        output
+    }
+
+    fn format_batch_as_markdown(&self, summary: &BatchSummary) -> String {
+        let platforms = summary.platforms();
+        let mut md = String::from("# 🗂️ Batch Impact Matrix\n\n");
+        md.push_str("| Repository | Commit | Impact % | Affected Lines | App Lines |");
+        for platform in &platforms {
+            md.push_str(&format!(" {} % |", platform));
+        }
+        md.push_str("\n|------------|--------|----------|----------------|-----------|");
+        md.push_str(&"------|".repeat(platforms.len()));
+        md.push('\n');
+
+        for repository in &summary.repositories {
+            md.push_str(&format!("| {} | `{}` |", repository.name, Self::short_commit(repository)));
+            if repository.error.is_some() {
+                md.push_str(&" ⚠️ failed |".repeat(3 + platforms.len()));
+            } else {
+                md.push_str(&format!(
+                    " {} | {} | {} |",
+                    self.numbers.percent(repository.impact_ratio),
+                    self.numbers.count(repository.affected_lines),
+                    self.numbers.count(repository.total_app_lines)
+                ));
+                for platform in &platforms {
+                    md.push_str(&format!(" {} |", self.batch_ratio(repository, platform)));
+                }
+            }
+            md.push('\n');
+        }
+
+        let failures = summary.failures();
+        if !failures.is_empty() {
+            md.push_str(&format!("\n## ⚠️ Failed Repositories ({})\n\n", failures.len()));
+            for failed in failures {
+                md.push_str(&format!(
+                    "- **{}** (`{}`): {}\n",
+                    failed.name,
+                    failed.location,
+                    failed.error.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+        md
+    }
+
+    /// One row per repository; ratios are percentages, empty where a platform is absent
+    fn format_batch_as_csv(summary: &BatchSummary) -> String {
+        let platforms = summary.platforms();
+        let mut header = vec![
+            "repository".to_string(),
+            "location".to_string(),
+            "commit".to_string(),
+            "impact_percent".to_string(),
+            "affected_lines".to_string(),
+            "app_lines".to_string(),
+        ];
+        header.extend(platforms.iter().map(|p| format!("{}_percent", p.to_lowercase())));
+        header.push("error".to_string());
+
+        let mut csv = header.join(",");
+        csv.push('\n');
+        for repository in &summary.repositories {
+            let mut fields = vec![
+                repository.name.clone(),
+                repository.location.clone(),
+                repository.commit_sha.clone().unwrap_or_default(),
+                format!("{:.2}", repository.impact_ratio * 100.0),
+                repository.affected_lines.to_string(),
+                repository.total_app_lines.to_string(),
+            ];
+            fields.extend(platforms.iter().map(|p| {
+                repository
+                    .platform_ratios
+                    .get(*p)
+                    .map_or(String::new(), |ratio| format!("{:.2}", ratio * 100.0))
+            }));
+            fields.push(repository.error.clone().unwrap_or_default());
+            let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    fn short_commit(repository: &BatchRepository) -> String {
+        repository
+            .commit_sha
+            .as_deref()
+            .map_or("-".to_string(), |sha| sha.chars().take(7).collect())
+    }
+
+    fn batch_ratio(&self, repository: &BatchRepository, platform: &str) -> String {
+        repository
+            .platform_ratios
+            .get(platform)
+            .map_or("-".to_string(), |ratio| self.numbers.percent(*ratio))
+    }
+
+    /// The analyzed directory itself is shown as `.`
+    fn display_root(root: &str) -> &str {
+        if root.is_empty() {
+            "."
+        } else {
+            root
+        }
+    }
+
     /// New method for Clean Architecture: Report ImpactAnalysis
     pub fn report_impact_analysis(
         &self,
@@ -257,37 +711,117 @@ impl Reporter {
     ) -> Result<()> {
         let content = match self.format {
             ReportFormat::Table => self.format_impact_as_table(analysis),
-            ReportFormat::Json => serde_json::to_string_pretty(analysis)?,
+            ReportFormat::Json => serde_json::to_string_pretty(&JsonImpactReport {
+                analysis,
+                formatted: FormattedNumbers::of(analysis, &self.numbers),
+                ratios: ExactRatios::of(analysis, self.numbers.precision()),
+            })?,
             ReportFormat::Markdown => self.format_impact_as_markdown(analysis),
+            ReportFormat::GitHubChecks => serde_json::to_string_pretty(
+                &CheckRunPayload::from_analysis(analysis, self.project_root.as_deref()),
+            )?,
+            ReportFormat::Html => {
+                let sources = match &self.sources {
+                    Some((fs, max_bytes)) => Some(SourceExcerpts::collect(
+                        analysis,
+                        fs.as_ref(),
+                        self.project_root.as_deref(),
+                        *max_bytes,
+                    )?),
+                    None => None,
+                };
+                let mut dashboard = HtmlDashboard::new(analysis, &self.history);
+                if let Some(sources) = &sources {
+                    dashboard = dashboard.with_sources(sources);
+                }
+                if let Some(project_root) = &self.project_root {
+                    dashboard = dashboard.with_project_root(project_root);
+                }
+                if let Some(doc_links) = &self.doc_links {
+                    dashboard = dashboard.with_doc_links(doc_links);
+                }
+                dashboard = dashboard.with_number_format(self.numbers.clone());
+                dashboard.render(analysis.metadata.as_ref().map(Self::provenance_line).as_deref())
+            }
+            ReportFormat::Mermaid => {
+                MermaidDiagram::from_analysis(analysis, self.project_root.as_deref())
+            }
+            ReportFormat::Csv => anyhow::bail!("CSV output is only available for the batch matrix"),
+            ReportFormat::Problems => {
+                ProblemLines::render(analysis, self.fs.as_ref(), self.project_root.as_deref())
+            }
+            ReportFormat::ReviewPack => serde_json::to_string_pretty(&ReviewPack::from_analysis(
+                analysis,
+                self.fs.as_ref(),
+                self.project_root.as_deref(),
+            )?)?,
         };
 
-        if let Some(path) = output_path {
-            fs::write(path, content)?;
-            println!("Results saved to file: {}", path);
-        } else {
-            println!("{}", content);
-        }
-
-        Ok(())
+        self.emit(&content, output_path, Some(self.summary_line(analysis)))
     }
 
     fn format_impact_as_table(&self, analysis: &ImpactAnalysis) -> String {
         let mut output = String::new();
 
         output.push_str("=== KMP Impact Coverage Report (Clean Architecture) ===\n\n");
+        if let Some(line) = Self::diff_line(analysis) {
+            output.push_str(&format!("🔀 {}\n\n", line));
+        }
 
-        output.push_str(&format!("📊 Impact Coverage: {:.2}%\n", analysis.impact_ratio * 100.0));
-        output.push_str(&format!(
-            "   Affected Lines: {} / {}\n\n",
-            analysis.affected_lines, analysis.total_app_lines
-        ));
+        output.push_str(&format!("📊 Impact Coverage: {}\n", self.numbers.percent(analysis.impact_ratio)));
+        let (label, counts) = self.affected_of_total(analysis);
+        output.push_str(&format!("   {}: {}\n\n", label, counts));
 
         output.push_str(&format!("🎯 Direct Impact: {} files\n", analysis.affected_files.len()));
         output.push_str(&format!("📦 KMP Symbols: {}\n", analysis.total_symbols));
-        output.push_str(&format!("📁 Total App Files: {}\n\n", analysis.total_app_files));
+        output.push_str(&format!("📁 Total App Files: {}\n", analysis.total_app_files));
+        output.push_str(&format!("🚀 Shared Code Leverage: {}\n", analysis.roi.describe()));
+        if let Some(line) = self.ui_layout_line(analysis) {
+            output.push_str(&format!("🎨 {}\n", line));
+        }
+        output.push_str(&format!(
+            "📝 Documented Shared API: {} ({} / {})\n",
+            self.numbers.percent(analysis.documentation.documented_ratio),
+            analysis.documentation.documented_symbols,
+            analysis.documentation.total_symbols
+        ));
+        if let Some(line) = Self::suppression_line(analysis) {
+            output.push_str(&format!("🙈 {}\n", line));
+        }
+        if let Some(line) = analysis.funnel.describe_largest_drop() {
+            output.push_str(&format!("🔻 API Funnel: {}\n", line));
+        }
+        output.push('\n');
+
+        // App files the changes of a diff run reach
+        if analysis.diff.is_some() {
+            output.push_str("=== Impacted App Files ===\n\n");
+            let impacted = self.impacted_files(analysis);
+            if impacted.is_empty() {
+                output.push_str("No app file uses the changed shared symbols.\n");
+            } else {
+                let mut impacted_table = Table::new();
+                impacted_table.add_row(Row::new(vec![
+                    Cell::new("Platform"),
+                    Cell::new("File"),
+                    Cell::new("Affected Lines"),
+                    Cell::new("Reached"),
+                ]));
+                for (platform, path, file) in impacted {
+                    impacted_table.add_row(Row::new(vec![
+                        Cell::new(platform),
+                        Cell::new(&path),
+                        Cell::new(&file.affected_lines.to_string()),
+                        Cell::new(if file.direct { "directly" } else { "via dependencies" }),
+                    ]));
+                }
+                output.push_str(&impacted_table.to_string());
+            }
+            output.push('\n');
+        }
 
         // Platform breakdown
-        if !analysis.platform_impacts.is_empty() {
+        if self.shows(ReportSection::Platforms) && !analysis.platform_impacts.is_empty() {
             output.push_str("=== Platform Impact Breakdown ===\n\n");
             let mut platform_table = Table::new();
             platform_table.add_row(Row::new(vec![
@@ -296,56 +830,1430 @@ impl Reporter {
                 Cell::new("Affected Files"),
                 Cell::new("Affected Lines"),
                 Cell::new("Total Lines"),
+                Cell::new("Impact % excl. UI"),
             ]));
 
             for (platform_name, impact) in &analysis.platform_impacts {
                 platform_table.add_row(Row::new(vec![
                     Cell::new(platform_name),
-                    Cell::new(&format!("{:.2}%", impact.impact_ratio * 100.0)),
+                    Cell::new(&self.numbers.percent(impact.impact_ratio)),
                     Cell::new(&impact.affected_files.len().to_string()),
-                    Cell::new(&impact.affected_lines.to_string()),
-                    Cell::new(&impact.total_lines.to_string()),
+                    Cell::new(&self.numbers.count(impact.affected_lines)),
+                    Cell::new(&self.numbers.count(impact.total_lines)),
+                    Cell::new(&self.numbers.percent(impact.ui_layout.impact_ratio_excluding_ui)),
                 ]));
             }
 
             output.push_str(&platform_table.to_string());
-            output.push_str("\n");
+            output.push('\n');
         }
 
-        output
-    }
+        // Impact per architectural layer of each platform
+        let layers = Self::layer_impacts(analysis);
+        if self.shows(ReportSection::Platforms) && !layers.is_empty() {
+            output.push_str("=== Layer Impact ===\n\n");
+            let mut layer_table = Table::new();
+            layer_table.add_row(Row::new(vec![
+                Cell::new("Platform"),
+                Cell::new("Layer"),
+                Cell::new("Impact %"),
+                Cell::new("Affected Files"),
+                Cell::new("Affected Lines"),
+                Cell::new("Total Lines"),
+            ]));
 
-    fn format_impact_as_markdown(&self, analysis: &ImpactAnalysis) -> String {
-        let mut md = String::from("# Kotlin Multiplatform Impact Coverage Report\n\n");
+            for (platform, layer) in layers {
+                layer_table.add_row(Row::new(vec![
+                    Cell::new(platform),
+                    Cell::new(&layer.layer),
+                    Cell::new(&self.numbers.percent(layer.impact_ratio)),
+                    Cell::new(&format!("{} / {}", layer.affected_files, layer.total_files)),
+                    Cell::new(&layer.affected_lines.to_string()),
+                    Cell::new(&layer.total_lines.to_string()),
+                ]));
+            }
 
-        md.push_str("## 📊 Impact Summary\n\n");
-        md.push_str(&format!("- **Impact Coverage**: {:.2}%\n", analysis.impact_ratio * 100.0));
-        md.push_str(&format!(
-            "- **Affected Lines**: {} / {}\n",
-            analysis.affected_lines, analysis.total_app_lines
-        ));
-        md.push_str(&format!("- **Direct Impact Files**: {}\n", analysis.affected_files.len()));
-        md.push_str(&format!("- **Total KMP Symbols**: {}\n\n", analysis.total_symbols));
+            output.push_str(&layer_table.to_string());
+            output.push('\n');
+        }
 
-        // Platform breakdown
-        if !analysis.platform_impacts.is_empty() {
-            md.push_str("## 📱 Platform Impact Breakdown\n\n");
-            md.push_str("| Platform | Impact % | Affected Files | Affected Lines | Total Lines |\n");
-            md.push_str("|----------|----------|----------------|----------------|-------------|\n");
+        // Shared entry points usage flows through, per platform
+        let entry_points = Self::entry_points(analysis);
+        if self.shows(ReportSection::Platforms) && !entry_points.is_empty() {
+            output.push_str("=== Shared Entry Points ===\n\n");
+            let mut entry_table = Table::new();
+            entry_table.add_row(Row::new(vec![
+                Cell::new("Platform"),
+                Cell::new("Entry Point"),
+                Cell::new("Module"),
+                Cell::new("Usages"),
+                Cell::new("Share"),
+                Cell::new("Reaches"),
+            ]));
 
-            for (platform_name, impact) in &analysis.platform_impacts {
-                md.push_str(&format!(
-                    "| {} | {:.2}% | {} | {} | {} |\n",
-                    platform_name,
-                    impact.impact_ratio * 100.0,
-                    impact.affected_files.len(),
-                    impact.affected_lines,
-                    impact.total_lines
-                ));
+            for (platform, entry) in entry_points {
+                entry_table.add_row(Row::new(vec![
+                    Cell::new(platform),
+                    Cell::new(&entry.symbol),
+                    Cell::new(&entry.module),
+                    Cell::new(&entry.usages.to_string()),
+                    Cell::new(&self.numbers.percent(entry.share)),
+                    Cell::new(&Self::reached_summary(entry)),
+                ]));
             }
-            md.push_str("\n");
+
+            output.push_str(&entry_table.to_string());
+            output.push('\n');
         }
 
-        md
+        // Progress toward coverage goals
+        if self.shows(ReportSection::Goals) && !analysis.goals.is_empty() {
+            output.push_str("=== Coverage Goals ===\n\n");
+            let mut goal_table = Table::new();
+            goal_table.add_row(Row::new(vec![
+                Cell::new("Goal"),
+                Cell::new("Target %"),
+                Cell::new("Impact %"),
+                Cell::new("Affected Lines"),
+                Cell::new("Total Lines"),
+                Cell::new("Status"),
+            ]));
+
+            for goal in &analysis.goals {
+                goal_table.add_row(Row::new(vec![
+                    Cell::new(&goal.name),
+                    Cell::new(&self.numbers.percent(goal.target)),
+                    Cell::new(&self.numbers.percent(goal.impact_ratio)),
+                    Cell::new(&goal.affected_lines.to_string()),
+                    Cell::new(&goal.total_lines.to_string()),
+                    Cell::new(Self::goal_status(goal)),
+                ]));
+            }
+
+            output.push_str(&goal_table.to_string());
+            output.push('\n');
+        }
+
+        // Impact per product variant or scheme
+        if self.shows(ReportSection::Platforms) && !analysis.variants.is_empty() {
+            output.push_str("=== Variant Impact ===\n\n");
+            let platforms = Self::variant_platforms(analysis);
+            let mut header = vec![
+                Cell::new("Variant"),
+                Cell::new("Impact %"),
+                Cell::new("Affected Lines"),
+                Cell::new("Total Lines"),
+                Cell::new("Files (Own)"),
+            ];
+            header.extend(platforms.iter().map(|p| Cell::new(&format!("{} %", p))));
+            let mut variant_table = Table::new();
+            variant_table.add_row(Row::new(header));
+
+            for variant in &analysis.variants {
+                let mut row = vec![
+                    Cell::new(&variant.name),
+                    Cell::new(&self.numbers.percent(variant.impact_ratio)),
+                    Cell::new(&variant.affected_lines.to_string()),
+                    Cell::new(&variant.total_lines.to_string()),
+                    Cell::new(&format!("{} ({})", variant.total_files, variant.specific_files)),
+                ];
+                row.extend(platforms.iter().map(|p| Cell::new(&self.variant_ratio(variant, p))));
+                variant_table.add_row(Row::new(row));
+            }
+
+            output.push_str(&variant_table.to_string());
+            output.push('\n');
+        }
+
+        // Shared API funnel
+        if self.shows(ReportSection::Breakdowns) && !analysis.funnel.platforms.is_empty() {
+            output.push_str("=== Shared API Funnel ===\n\n");
+            let mut funnel_table = Table::new();
+            funnel_table.add_row(Row::new(vec![
+                Cell::new("Platform"),
+                Cell::new("Declared"),
+                Cell::new("Exported"),
+                Cell::new("Imported"),
+                Cell::new("Referenced"),
+            ]));
+            for funnel in &analysis.funnel.platforms {
+                funnel_table.add_row(Row::new(vec![
+                    Cell::new(&funnel.platform),
+                    Cell::new(&analysis.funnel.declared.to_string()),
+                    Cell::new(&funnel.exported.to_string()),
+                    Cell::new(&funnel.imported.to_string()),
+                    Cell::new(&funnel.referenced.to_string()),
+                ]));
+            }
+            output.push_str(&funnel_table.to_string());
+            output.push('\n');
+        }
+
+        // Cross-platform parity
+        if self.shows(ReportSection::Breakdowns) && !analysis.parity.symbols.is_empty() {
+            let parity = &analysis.parity;
+            output.push_str("=== Cross-Platform Parity ===\n\n");
+            output.push_str(&format!(
+                "Both: {}  Android-only: {}  iOS-only: {}  Unused: {}\n\n",
+                parity.both_platforms, parity.android_only, parity.ios_only, parity.unused
+            ));
+
+            let candidates = [
+                ("Android-only (iOS adoption candidates)", &parity.android_only_candidates),
+                ("iOS-only (Android adoption candidates)", &parity.ios_only_candidates),
+            ];
+            for (title, symbols) in candidates {
+                if symbols.is_empty() {
+                    continue;
+                }
+                let mut candidate_table = Table::new();
+                candidate_table.add_row(Row::new(vec![Cell::new(title), Cell::new("References")]));
+                for (symbol_name, references) in symbols {
+                    candidate_table.add_row(Row::new(vec![
+                        Cell::new(symbol_name),
+                        Cell::new(&references.to_string()),
+                    ]));
+                }
+                output.push_str(&candidate_table.to_string());
+                output.push('\n');
+            }
+        }
+
+        // Stability tier breakdown
+        if self.shows(ReportSection::Breakdowns) && analysis.stability_tiers.iter().any(|t| t.total_symbols > 0) {
+            output.push_str("=== API Stability Breakdown ===\n\n");
+            let mut tier_table = Table::new();
+            tier_table.add_row(Row::new(vec![
+                Cell::new("Tier"),
+                Cell::new("Symbols"),
+                Cell::new("Used Symbols"),
+                Cell::new("References"),
+                Cell::new("App Files"),
+                Cell::new("References by Platform"),
+            ]));
+
+            for tier in &analysis.stability_tiers {
+                tier_table.add_row(Row::new(vec![
+                    Cell::new(&tier.tier),
+                    Cell::new(&tier.total_symbols.to_string()),
+                    Cell::new(&tier.used_symbols.to_string()),
+                    Cell::new(&tier.references.to_string()),
+                    Cell::new(&tier.affected_files.to_string()),
+                    Cell::new(&Self::format_platform_counts(&tier.platform_references)),
+                ]));
+            }
+
+            output.push_str(&tier_table.to_string());
+            output.push('\n');
+        }
+
+        // Category breakdown
+        if self.shows(ReportSection::Breakdowns) && analysis.categories.iter().any(|c| c.used_symbols > 0) {
+            output.push_str("=== Usage by Category ===\n\n");
+            let mut category_table = Table::new();
+            category_table.add_row(Row::new(vec![
+                Cell::new("Category"),
+                Cell::new("Symbols"),
+                Cell::new("Used Symbols"),
+                Cell::new("Used %"),
+                Cell::new("References"),
+                Cell::new("App Files"),
+                Cell::new("References by Platform"),
+                Cell::new("Threshold"),
+            ]));
+
+            for category in &analysis.categories {
+                category_table.add_row(Row::new(vec![
+                    Cell::new(&category.category),
+                    Cell::new(&category.total_symbols.to_string()),
+                    Cell::new(&category.used_symbols.to_string()),
+                    Cell::new(&self.numbers.percent(category.used_ratio)),
+                    Cell::new(&category.references.to_string()),
+                    Cell::new(&category.affected_files.to_string()),
+                    Cell::new(&Self::format_platform_counts(&category.platform_references)),
+                    Cell::new(&self.category_threshold(category)),
+                ]));
+            }
+
+            output.push_str(&category_table.to_string());
+            output.push('\n');
+        }
+
+        // Call-site kind breakdown
+        if self.shows(ReportSection::Breakdowns) && !analysis.usage_kinds.is_empty() {
+            output.push_str("=== Usage by Call-Site Kind ===\n\n");
+            let mut kind_table = Table::new();
+            kind_table.add_row(Row::new(vec![
+                Cell::new("Kind"),
+                Cell::new("Usages"),
+                Cell::new("Share"),
+                Cell::new("Symbols"),
+                Cell::new("App Files"),
+            ]));
+
+            for (count, share) in Self::usage_kind_shares(analysis) {
+                kind_table.add_row(Row::new(vec![
+                    Cell::new(count.kind.label()),
+                    Cell::new(&count.usages.to_string()),
+                    Cell::new(&self.numbers.percent(share)),
+                    Cell::new(&count.symbols.to_string()),
+                    Cell::new(&count.files.to_string()),
+                ]));
+            }
+
+            output.push_str(&kind_table.to_string());
+            if let Some(line) = Self::pass_through_line(analysis) {
+                output.push_str(&format!("{}\n", line));
+            }
+            output.push('\n');
+        }
+
+        // Per-package breakdown
+        if self.shows(ReportSection::Breakdowns) && !analysis.packages.is_empty() {
+            output.push_str("=== Shared Package Impact ===\n\n");
+            let mut package_table = Table::new();
+            package_table.add_row(Row::new(vec![
+                Cell::new("Package"),
+                Cell::new("Symbols"),
+                Cell::new("Used Symbols"),
+                Cell::new("References"),
+                Cell::new("App Files"),
+                Cell::new("References by Platform"),
+            ]));
+
+            for package in &analysis.packages {
+                package_table.add_row(Row::new(vec![
+                    Cell::new(Self::package_name(&package.package)),
+                    Cell::new(&package.total_symbols.to_string()),
+                    Cell::new(&package.used_symbols.to_string()),
+                    Cell::new(&package.references.to_string()),
+                    Cell::new(&package.affected_files.to_string()),
+                    Cell::new(&Self::format_platform_counts(&package.platform_references)),
+                ]));
+            }
+
+            output.push_str(&package_table.to_string());
+            output.push('\n');
+        }
+
+        // Whom to contact about the most used shared APIs
+        if self.shows(ReportSection::Breakdowns) && !analysis.api_owners.is_empty() {
+            output.push_str("=== Owners of Most Used Shared APIs ===\n\n");
+            let mut owner_table = Table::new();
+            owner_table.add_row(Row::new(vec![
+                Cell::new("Symbol"),
+                Cell::new("Package"),
+                Cell::new("Usages"),
+                Cell::new("Owners"),
+            ]));
+
+            for api in &analysis.api_owners {
+                owner_table.add_row(Row::new(vec![
+                    Cell::new(&api.symbol),
+                    Cell::new(Self::package_name(&api.package)),
+                    Cell::new(&api.usages.to_string()),
+                    Cell::new(&Self::owner_list(&api.owners)),
+                ]));
+            }
+
+            output.push_str(&owner_table.to_string());
+            output.push('\n');
+        }
+
+        // Android Gradle module breakdown
+        if self.shows(ReportSection::Modules) && !analysis.android_modules.is_empty() {
+            output.push_str("=== Android Module Impact ===\n\n");
+            let mut android_table = Table::new();
+            android_table.add_row(Row::new(vec![
+                Cell::new("Module"),
+                Cell::new("Kind"),
+                Cell::new("Reach"),
+                Cell::new("Impact %"),
+                Cell::new("Affected Files"),
+                Cell::new("Affected Lines"),
+                Cell::new("Total Lines"),
+                Cell::new("References"),
+                Cell::new("Via"),
+            ]));
+
+            for module in &analysis.android_modules {
+                android_table.add_row(Row::new(vec![
+                    Cell::new(&module.module),
+                    Cell::new(Self::module_kind(module)),
+                    Cell::new(module.reach.name()),
+                    Cell::new(&self.numbers.percent(module.impact_ratio)),
+                    Cell::new(&module.affected_files.to_string()),
+                    Cell::new(&module.affected_lines.to_string()),
+                    Cell::new(&module.total_lines.to_string()),
+                    Cell::new(&module.references.to_string()),
+                    Cell::new(&Self::via_modules(&module.via_modules)),
+                ]));
+            }
+
+            output.push_str(&android_table.to_string());
+            output.push('\n');
+        }
+
+        // Swift module breakdown
+        if self.shows(ReportSection::Modules) && !analysis.ios_modules.is_empty() {
+            output.push_str("=== iOS Module Impact ===\n\n");
+            let mut ios_table = Table::new();
+            ios_table.add_row(Row::new(vec![
+                Cell::new("Module"),
+                Cell::new("Kind"),
+                Cell::new("Reach"),
+                Cell::new("Impact %"),
+                Cell::new("Affected Files"),
+                Cell::new("Affected Lines"),
+                Cell::new("Total Lines"),
+                Cell::new("References"),
+                Cell::new("Via"),
+            ]));
+
+            for module in &analysis.ios_modules {
+                ios_table.add_row(Row::new(vec![
+                    Cell::new(&module.module),
+                    Cell::new(Self::swift_module_kind(module)),
+                    Cell::new(module.reach.name()),
+                    Cell::new(&self.numbers.percent(module.impact_ratio)),
+                    Cell::new(&module.affected_files.to_string()),
+                    Cell::new(&module.affected_lines.to_string()),
+                    Cell::new(&module.total_lines.to_string()),
+                    Cell::new(&module.references.to_string()),
+                    Cell::new(&Self::via_modules(&module.via_modules)),
+                ]));
+            }
+
+            output.push_str(&ios_table.to_string());
+            output.push('\n');
+        }
+
+        // Module dependencies on KMP modules
+        if self.shows(ReportSection::Modules) && !analysis.module_dependencies.is_empty() {
+            output.push_str("=== Module Dependencies on Shared Code ===\n\n");
+            let mut module_table = Table::new();
+            module_table.add_row(Row::new(vec![
+                Cell::new("Module"),
+                Cell::new("Shared Module"),
+                Cell::new("Declared Via"),
+            ]));
+
+            for dependency in &analysis.module_dependencies {
+                module_table.add_row(Row::new(vec![
+                    Cell::new(&dependency.module),
+                    Cell::new(&dependency.depends_on),
+                    Cell::new(&dependency.via),
+                ]));
+            }
+
+            output.push_str(&module_table.to_string());
+            output.push('\n');
+        }
+
+        // `actual` declarations per platform source set of modules with `expect` declarations
+        if self.shows(ReportSection::Modules) && !analysis.expect_actual.is_empty() {
+            output.push_str("=== Expect/Actual Coverage ===\n\n");
+            let mut actual_table = Table::new();
+            actual_table.add_row(Row::new(vec![
+                Cell::new("Module"),
+                Cell::new("Source Set"),
+                Cell::new("Actualized %"),
+                Cell::new("Actualized"),
+                Cell::new("Missing Actuals"),
+            ]));
+
+            for coverage in &analysis.expect_actual {
+                actual_table.add_row(Row::new(vec![
+                    Cell::new(&coverage.module),
+                    Cell::new(&coverage.source_set),
+                    Cell::new(&self.numbers.ratio(coverage.ratio())),
+                    Cell::new(&format!("{} / {}", coverage.actualized(), coverage.expected)),
+                    Cell::new(&coverage.missing.join(", ")),
+                ]));
+            }
+
+            output.push_str(&actual_table.to_string());
+            output.push('\n');
+        }
+
+        // Deprecated shared API usage
+        if self.shows(ReportSection::Suggestions) && analysis.deprecated_usage_count() > 0 {
+            output.push_str("=== Deprecated Shared API Usage ===\n\n");
+            let mut deprecated_table = Table::new();
+            deprecated_table.add_row(Row::new(vec![
+                Cell::new("Platform"),
+                Cell::new("Symbol"),
+                Cell::new("Location"),
+                Cell::new("Context"),
+            ]));
+
+            let mut platform_names: Vec<&String> = analysis.platform_impacts.keys().collect();
+            platform_names.sort();
+            for platform_name in platform_names {
+                for usage in &analysis.platform_impacts[platform_name].deprecated_usages {
+                    deprecated_table.add_row(Row::new(vec![
+                        Cell::new(platform_name),
+                        Cell::new(&usage.symbol_name),
+                        Cell::new(&format!("{}:{}", usage.file_path, usage.line_number)),
+                        Cell::new(&usage.context),
+                    ]));
+                }
+            }
+
+            output.push_str(&deprecated_table.to_string());
+            output.push('\n');
+        }
+
+        // Stale shared imports
+        if self.shows(ReportSection::Suggestions) && !analysis.unused_imports.is_empty() {
+            output.push_str("=== Unused Shared Imports ===\n\n");
+            let mut import_table = Table::new();
+            import_table.add_row(Row::new(vec![Cell::new("Symbol"), Cell::new("Location"), Cell::new("Import")]));
+
+            for import in &analysis.unused_imports {
+                import_table.add_row(Row::new(vec![
+                    Cell::new(&import.symbol_name),
+                    Cell::new(&format!("{}:{}", import.file_path, import.line_number)),
+                    Cell::new(&import.context),
+                ]));
+            }
+
+            output.push_str(&import_table.to_string());
+            output.push('\n');
+        }
+
+        // App files importing shared code they never use
+        if self.shows(ReportSection::Suggestions) && !analysis.stale_integrations.is_empty() {
+            output.push_str("=== Stale Integrations ===\n\n");
+            let mut stale_table = Table::new();
+            stale_table.add_row(Row::new(vec![Cell::new("File"), Cell::new("Platform"), Cell::new("Imports")]));
+
+            for stale in &analysis.stale_integrations {
+                stale_table.add_row(Row::new(vec![
+                    Cell::new(&stale.file_path),
+                    Cell::new(&stale.platform),
+                    Cell::new(&Self::stale_imports(stale)),
+                ]));
+            }
+
+            output.push_str(&stale_table.to_string());
+            output.push('\n');
+        }
+
+        // Shared API changes since the previous run
+        if let Some(churn) = analysis.api_churn.as_ref().filter(|_| self.shows(ReportSection::ApiChanges)) {
+            output.push_str(&format!("=== {} ===\n\n", Self::api_changes_heading(churn)));
+            if churn.is_empty() {
+                output.push_str("No shared API added, removed, or renamed.\n\n");
+            } else {
+                let mut churn_table = Table::new();
+                churn_table.add_row(Row::new(vec![Cell::new("Change"), Cell::new("Symbol")]));
+                for (change, symbol) in api_changes(churn) {
+                    churn_table.add_row(Row::new(vec![Cell::new(change), Cell::new(&symbol)]));
+                }
+                output.push_str(&churn_table.to_string());
+                output.push('\n');
+            }
+        }
+
+        // Lint diagnostics
+        if self.shows(ReportSection::Diagnostics) && !analysis.diagnostics.is_empty() {
+            output.push_str("=== Diagnostics ===\n\n");
+            let mut diagnostic_table = Table::new();
+            diagnostic_table.add_row(Row::new(vec![
+                Cell::new("Severity"),
+                Cell::new("Rule"),
+                Cell::new("Message"),
+            ]));
+
+            for diagnostic in &analysis.diagnostics {
+                diagnostic_table.add_row(Row::new(vec![
+                    Cell::new(diagnostic.severity.name()),
+                    Cell::new(&diagnostic.rule),
+                    Cell::new(&diagnostic.message),
+                ]));
+            }
+
+            output.push_str(&diagnostic_table.to_string());
+            output.push('\n');
+        }
+
+        if let Some(metadata) = &analysis.metadata {
+            output.push_str(&format!("{}\n", Self::provenance_line(metadata)));
+        }
+
+        output
+    }
+
+    fn format_impact_as_markdown(&self, analysis: &ImpactAnalysis) -> String {
+        let mut md = String::from("# Kotlin Multiplatform Impact Coverage Report\n\n");
+        if let Some(line) = Self::diff_line(analysis) {
+            md.push_str(&format!("> 🔀 {}\n\n", line));
+        }
+
+        md.push_str("## 📊 Impact Summary\n\n");
+        md.push_str(&format!("- **Impact Coverage**: {}\n", self.numbers.percent(analysis.impact_ratio)));
+        let (label, counts) = self.affected_of_total(analysis);
+        md.push_str(&format!("- **{}**: {}\n", label, counts));
+        md.push_str(&format!("- **Direct Impact Files**: {}\n", analysis.affected_files.len()));
+        md.push_str(&format!("- **Total KMP Symbols**: {}\n", analysis.total_symbols));
+        md.push_str(&format!("- **Shared Code Leverage**: {}\n", analysis.roi.describe()));
+        if let Some(line) = self.ui_layout_line(analysis) {
+            md.push_str(&format!("- **Excluding UI Layout**: {}\n", line));
+        }
+        if let Some(line) = Self::suppression_line(analysis) {
+            md.push_str(&format!("- **Suppressed**: {}\n", line));
+        }
+        if let Some(line) = analysis.funnel.describe_largest_drop() {
+            md.push_str(&format!("- **API Funnel**: {}\n", line));
+        }
+        md.push('\n');
+
+        // App files the changes of a diff run reach
+        if analysis.diff.is_some() {
+            md.push_str("## 🔀 Impacted App Files\n\n");
+            let impacted = self.impacted_files(analysis);
+            if impacted.is_empty() {
+                md.push_str("No app file uses the changed shared symbols.\n");
+            } else {
+                md.push_str("| Platform | File | Affected Lines | Reached |\n");
+                md.push_str("|----------|------|----------------|---------|\n");
+                for (platform, path, file) in impacted {
+                    md.push_str(&format!(
+                        "| {} | `{}` | {} | {} |\n",
+                        platform,
+                        path,
+                        file.affected_lines,
+                        if file.direct { "directly" } else { "via dependencies" }
+                    ));
+                }
+            }
+            md.push('\n');
+        }
+
+        // Platform breakdown
+        if self.shows(ReportSection::Platforms) && !analysis.platform_impacts.is_empty() {
+            md.push_str("## 📱 Platform Impact Breakdown\n\n");
+            md.push_str("| Platform | Impact % | Affected Files | Affected Lines | Total Lines | Impact % excl. UI |\n");
+            md.push_str("|----------|----------|----------------|----------------|-------------|-------------------|\n");
+
+            for (platform_name, impact) in &analysis.platform_impacts {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    platform_name,
+                    self.numbers.percent(impact.impact_ratio),
+                    impact.affected_files.len(),
+                    self.numbers.count(impact.affected_lines),
+                    self.numbers.count(impact.total_lines),
+                    self.numbers.percent(impact.ui_layout.impact_ratio_excluding_ui)
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Impact per architectural layer of each platform
+        let layers = Self::layer_impacts(analysis);
+        if self.shows(ReportSection::Platforms) && !layers.is_empty() {
+            md.push_str("## 🧱 Layer Impact\n\n");
+            md.push_str("| Platform | Layer | Impact % | Affected Files | Affected Lines | Total Lines |\n");
+            md.push_str("|----------|-------|----------|----------------|----------------|-------------|\n");
+
+            for (platform, layer) in layers {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} / {} | {} | {} |\n",
+                    platform,
+                    layer.layer,
+                    self.numbers.percent(layer.impact_ratio),
+                    layer.affected_files,
+                    layer.total_files,
+                    layer.affected_lines,
+                    layer.total_lines
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Shared entry points usage flows through, per platform
+        let entry_points = Self::entry_points(analysis);
+        if self.shows(ReportSection::Platforms) && !entry_points.is_empty() {
+            md.push_str("## 🚪 Shared Entry Points\n\n");
+            md.push_str("| Platform | Entry Point | Module | Usages | Share | Reaches |\n");
+            md.push_str("|----------|-------------|--------|--------|-------|---------|\n");
+
+            for (platform, entry) in entry_points {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    platform,
+                    self.markdown_symbol_name(analysis, &entry.symbol),
+                    entry.module,
+                    entry.usages,
+                    self.numbers.percent(entry.share),
+                    Self::reached_summary(entry)
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Progress toward coverage goals
+        if self.shows(ReportSection::Goals) && !analysis.goals.is_empty() {
+            md.push_str("## 🏁 Coverage Goals\n\n");
+            md.push_str("| Goal | Target % | Impact % | Affected Lines | Total Lines | Status |\n");
+            md.push_str("|------|----------|----------|----------------|-------------|--------|\n");
+
+            for goal in &analysis.goals {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    goal.name,
+                    self.numbers.percent(goal.target),
+                    self.numbers.percent(goal.impact_ratio),
+                    goal.affected_lines,
+                    goal.total_lines,
+                    Self::goal_status(goal)
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Impact per product variant or scheme
+        if self.shows(ReportSection::Platforms) && !analysis.variants.is_empty() {
+            let platforms = Self::variant_platforms(analysis);
+            md.push_str("## 🎛️ Variant Impact\n\n");
+            md.push_str("| Variant | Impact % | Affected Lines | Total Lines | Files (Own) |");
+            for platform in &platforms {
+                md.push_str(&format!(" {} % |", platform));
+            }
+            md.push_str("\n|---------|----------|----------------|-------------|-------------|");
+            md.push_str(&"------|".repeat(platforms.len()));
+            md.push('\n');
+
+            for variant in &analysis.variants {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} ({}) |",
+                    variant.name,
+                    self.numbers.percent(variant.impact_ratio),
+                    variant.affected_lines,
+                    variant.total_lines,
+                    variant.total_files,
+                    variant.specific_files
+                ));
+                for platform in &platforms {
+                    md.push_str(&format!(" {} |", self.variant_ratio(variant, platform)));
+                }
+                md.push('\n');
+            }
+            md.push('\n');
+        }
+
+        // Top used symbols
+        if self.shows(ReportSection::TopSymbols) && !analysis.symbol_usages.is_empty() {
+            md.push_str("## 🎯 Top Used KMP Symbols\n\n");
+            md.push_str("| Symbol | References | Used in Files |\n");
+            md.push_str("|--------|------------|---------------|\n");
+
+            let mut symbols: Vec<_> = analysis.symbol_usages.iter().collect();
+            symbols.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
+
+            for (symbol_name, usages) in symbols.iter().take(10) {
+                let used_in_files: HashSet<&str> =
+                    usages.iter().map(|u| u.file_path.as_str()).collect();
+                md.push_str(&format!(
+                    "| {} | {} | {} |\n",
+                    self.markdown_symbol_name(analysis, symbol_name),
+                    usages.len(),
+                    used_in_files.len()
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Shared API funnel
+        if self.shows(ReportSection::Breakdowns) && !analysis.funnel.platforms.is_empty() {
+            md.push_str("## 🔻 Shared API Funnel\n\n");
+            md.push_str("| Platform | Declared | Exported | Imported | Referenced |\n");
+            md.push_str("|----------|----------|----------|----------|------------|\n");
+            for funnel in &analysis.funnel.platforms {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    funnel.platform, analysis.funnel.declared, funnel.exported, funnel.imported, funnel.referenced
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Cross-platform parity
+        if self.shows(ReportSection::Breakdowns) && !analysis.parity.symbols.is_empty() {
+            let parity = &analysis.parity;
+            md.push_str("## ⚖️ Cross-Platform Parity\n\n");
+            md.push_str("| Both Platforms | Android-only | iOS-only | Unused |\n");
+            md.push_str("|----------------|--------------|----------|--------|\n");
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |\n\n",
+                parity.both_platforms, parity.android_only, parity.ios_only, parity.unused
+            ));
+
+            let candidates = [
+                (
+                    "Android-only symbols (candidates for iOS adoption)",
+                    &parity.android_only_candidates,
+                ),
+                (
+                    "iOS-only symbols (candidates for Android adoption)",
+                    &parity.ios_only_candidates,
+                ),
+            ];
+            for (title, symbols) in candidates {
+                if symbols.is_empty() {
+                    continue;
+                }
+                md.push_str(&format!("{}:\n\n", title));
+                md.push_str("| Symbol | References |\n");
+                md.push_str("|--------|------------|\n");
+                for (symbol_name, references) in symbols {
+                    md.push_str(&format!(
+                        "| {} | {} |\n",
+                        self.markdown_symbol_name(analysis, symbol_name),
+                        references
+                    ));
+                }
+                md.push('\n');
+            }
+        }
+
+        // Stability tier breakdown
+        if self.shows(ReportSection::Breakdowns) && analysis.stability_tiers.iter().any(|t| t.total_symbols > 0) {
+            md.push_str("## 🧪 API Stability Breakdown\n\n");
+            md.push_str("| Tier | Symbols | Used Symbols | References | App Files | References by Platform |\n");
+            md.push_str("|------|---------|--------------|------------|-----------|------------------------|\n");
+
+            for tier in &analysis.stability_tiers {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    tier.tier,
+                    tier.total_symbols,
+                    tier.used_symbols,
+                    tier.references,
+                    tier.affected_files,
+                    Self::format_platform_counts(&tier.platform_references)
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Category breakdown
+        if self.shows(ReportSection::Breakdowns) && analysis.categories.iter().any(|c| c.used_symbols > 0) {
+            md.push_str("## 🏷️ Usage by Category\n\n");
+            md.push_str("| Category | Symbols | Used Symbols | Used % | References | App Files | References by Platform | Threshold |\n");
+            md.push_str("|----------|---------|--------------|--------|------------|-----------|------------------------|-----------|\n");
+
+            for category in &analysis.categories {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                    category.category,
+                    category.total_symbols,
+                    category.used_symbols,
+                    self.numbers.percent(category.used_ratio),
+                    category.references,
+                    category.affected_files,
+                    Self::format_platform_counts(&category.platform_references),
+                    self.category_threshold(category)
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Call-site kind breakdown
+        if self.shows(ReportSection::Breakdowns) && !analysis.usage_kinds.is_empty() {
+            md.push_str("## 🔍 Usage by Call-Site Kind\n\n");
+            md.push_str("| Kind | Usages | Share | Symbols | App Files |\n");
+            md.push_str("|------|--------|-------|---------|-----------|\n");
+
+            for (count, share) in Self::usage_kind_shares(analysis) {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    count.kind.label(),
+                    count.usages,
+                    self.numbers.percent(share),
+                    count.symbols,
+                    count.files
+                ));
+            }
+            if let Some(line) = Self::pass_through_line(analysis) {
+                md.push_str(&format!("\n{}\n", line));
+            }
+            md.push('\n');
+        }
+
+        // Per-package breakdown
+        if self.shows(ReportSection::Breakdowns) && !analysis.packages.is_empty() {
+            md.push_str("## 🗂️ Shared Package Impact\n\n");
+            md.push_str("| Package | Symbols | Used Symbols | References | App Files | References by Platform |\n");
+            md.push_str("|---------|---------|--------------|------------|-----------|------------------------|\n");
+
+            for package in &analysis.packages {
+                md.push_str(&format!(
+                    "| `{}` | {} | {} | {} | {} | {} |\n",
+                    Self::package_name(&package.package),
+                    package.total_symbols,
+                    package.used_symbols,
+                    package.references,
+                    package.affected_files,
+                    Self::format_platform_counts(&package.platform_references)
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Whom to contact about the most used shared APIs
+        if self.shows(ReportSection::Breakdowns) && !analysis.api_owners.is_empty() {
+            md.push_str("## 👥 Owners of Most Used Shared APIs\n\n");
+            md.push_str("| Symbol | Package | Usages | Owners |\n");
+            md.push_str("|--------|---------|--------|--------|\n");
+
+            for api in &analysis.api_owners {
+                md.push_str(&format!(
+                    "| {} | `{}` | {} | {} |\n",
+                    self.markdown_symbol_name(analysis, &api.symbol),
+                    Self::package_name(&api.package),
+                    api.usages,
+                    Self::owner_list(&api.owners)
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Android Gradle module breakdown
+        if self.shows(ReportSection::Modules) && !analysis.android_modules.is_empty() {
+            md.push_str("## 🤖 Android Module Impact\n\n");
+            md.push_str("| Module | Kind | Reach | Impact % | Affected Files | Affected Lines | Total Lines | References | Via |\n");
+            md.push_str("|--------|------|-------|----------|----------------|----------------|-------------|------------|-----|\n");
+
+            for module in &analysis.android_modules {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                    module.module,
+                    Self::module_kind(module),
+                    module.reach.name(),
+                    self.numbers.percent(module.impact_ratio),
+                    module.affected_files,
+                    module.affected_lines,
+                    module.total_lines,
+                    module.references,
+                    Self::via_modules(&module.via_modules)
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Swift module breakdown
+        if self.shows(ReportSection::Modules) && !analysis.ios_modules.is_empty() {
+            md.push_str("## 🍎 iOS Module Impact\n\n");
+            md.push_str("| Module | Kind | Reach | Impact % | Affected Files | Affected Lines | Total Lines | References | Via |\n");
+            md.push_str("|--------|------|-------|----------|----------------|----------------|-------------|------------|-----|\n");
+
+            for module in &analysis.ios_modules {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                    module.module,
+                    Self::swift_module_kind(module),
+                    module.reach.name(),
+                    self.numbers.percent(module.impact_ratio),
+                    module.affected_files,
+                    module.affected_lines,
+                    module.total_lines,
+                    module.references,
+                    Self::via_modules(&module.via_modules)
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Module dependencies on KMP modules
+        if self.shows(ReportSection::Modules) && !analysis.module_dependencies.is_empty() {
+            md.push_str("## 🧩 Module Dependencies on Shared Code\n\n");
+            md.push_str("| Module | Shared Module | Declared Via |\n");
+            md.push_str("|--------|---------------|--------------|\n");
+
+            for dependency in &analysis.module_dependencies {
+                md.push_str(&format!(
+                    "| {} | {} | `{}` |\n",
+                    dependency.module, dependency.depends_on, dependency.via
+                ));
+            }
+            md.push('\n');
+        }
+
+        // `actual` declarations per platform source set of modules with `expect` declarations
+        if self.shows(ReportSection::Modules) && !analysis.expect_actual.is_empty() {
+            md.push_str("## 🔌 Expect/Actual Coverage\n\n");
+            md.push_str("| Module | Source Set | Actualized % | Actualized | Missing Actuals |\n");
+            md.push_str("|--------|------------|--------------|------------|-----------------|\n");
+
+            for coverage in &analysis.expect_actual {
+                let missing: Vec<String> = coverage.missing.iter().map(|name| format!("`{}`", name)).collect();
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} / {} | {} |\n",
+                    coverage.module,
+                    coverage.source_set,
+                    self.numbers.ratio(coverage.ratio()),
+                    coverage.actualized(),
+                    coverage.expected,
+                    if missing.is_empty() { "-".to_string() } else { missing.join(", ") }
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Sharing topology: KMP modules → directly affected app packages
+        if self.shows(ReportSection::Modules) && !analysis.symbol_usages.values().all(Vec::is_empty) {
+            md.push_str("## 🕸️ Sharing Topology\n\n");
+            md.push_str("```mermaid\n");
+            md.push_str(&MermaidDiagram::from_analysis(
+                analysis,
+                self.project_root.as_deref(),
+            ));
+            md.push_str("```\n\n");
+        }
+
+        // Deprecated shared API usage
+        if self.shows(ReportSection::Suggestions) && analysis.deprecated_usage_count() > 0 {
+            md.push_str("## ⚠️ Deprecated Shared API Usage\n\n");
+
+            let mut platform_names: Vec<&String> = analysis.platform_impacts.keys().collect();
+            platform_names.sort();
+            for platform_name in platform_names {
+                let usages = &analysis.platform_impacts[platform_name].deprecated_usages;
+                if usages.is_empty() {
+                    continue;
+                }
+
+                md.push_str(&format!("### {} ({} usages)\n\n", platform_name, usages.len()));
+                for usage in usages {
+                    md.push_str(&format!(
+                        "- `{}` at `{}:{}` — `{}`\n",
+                        usage.symbol_name, usage.file_path, usage.line_number, usage.context
+                    ));
+                }
+                md.push('\n');
+            }
+        }
+
+        // Stale shared imports
+        if self.shows(ReportSection::Suggestions) && !analysis.unused_imports.is_empty() {
+            md.push_str(&format!("## 🧹 Unused Shared Imports ({})\n\n", analysis.unused_imports.len()));
+            for import in &analysis.unused_imports {
+                md.push_str(&format!(
+                    "- `{}` at `{}:{}`\n",
+                    import.symbol_name, import.file_path, import.line_number
+                ));
+            }
+            md.push('\n');
+        }
+
+        // App files importing shared code they never use
+        if self.shows(ReportSection::Suggestions) && !analysis.stale_integrations.is_empty() {
+            md.push_str(&format!("## 🔌 Stale Integrations ({})\n\n", analysis.stale_integrations.len()));
+            for stale in &analysis.stale_integrations {
+                md.push_str(&format!(
+                    "- `{}` ({}) imports {}\n",
+                    stale.file_path,
+                    stale.platform,
+                    Self::stale_imports(stale)
+                ));
+            }
+            md.push('\n');
+        }
+
+        // Documentation coverage
+        if self.shows(ReportSection::Breakdowns) {
+            let documentation = &analysis.documentation;
+            md.push_str("## 📝 Documentation Coverage\n\n");
+            md.push_str(&format!(
+                "- **Documented Shared API**: {} ({} / {})\n\n",
+                self.numbers.percent(documentation.documented_ratio),
+                documentation.documented_symbols,
+                documentation.total_symbols
+            ));
+
+            if !documentation.undocumented_hotspots.is_empty() {
+                md.push_str("Heavily used symbols without KDoc:\n\n");
+                md.push_str("| Symbol | References |\n");
+                md.push_str("|--------|------------|\n");
+                for (symbol_name, references) in &documentation.undocumented_hotspots {
+                    md.push_str(&format!(
+                        "| {} | {} |\n",
+                        self.markdown_symbol_name(analysis, symbol_name),
+                        references
+                    ));
+                }
+                md.push('\n');
+            }
+        }
+
+        // Shared API changes since the previous run
+        if let Some(churn) = analysis.api_churn.as_ref().filter(|_| self.shows(ReportSection::ApiChanges)) {
+            md.push_str(&format!("## 🔀 {}\n\n", Self::api_changes_heading(churn)));
+            if churn.is_empty() {
+                md.push_str("No shared API added, removed, or renamed.\n\n");
+            } else {
+                md.push_str("| Change | Symbol |\n");
+                md.push_str("|--------|--------|\n");
+                for (change, symbol) in api_changes(churn) {
+                    md.push_str(&format!("| {} | `{}` |\n", change, symbol));
+                }
+                md.push('\n');
+            }
+        }
+
+        // Lint diagnostics
+        if self.shows(ReportSection::Diagnostics) && !analysis.diagnostics.is_empty() {
+            md.push_str("## 🚨 Diagnostics\n\n");
+            md.push_str("| Severity | Rule | Message |\n");
+            md.push_str("|----------|------|---------|\n");
+            for diagnostic in &analysis.diagnostics {
+                md.push_str(&format!(
+                    "| {} | `{}` | {} |\n",
+                    diagnostic.severity.name(),
+                    diagnostic.rule,
+                    diagnostic.message
+                ));
+            }
+            md.push('\n');
+        }
+
+        if let Some(metadata) = &analysis.metadata {
+            md.push_str(&format!("---\n<sub>{}</sub>\n", Self::provenance_line(metadata)));
+        }
+
+        md
+    }
+
+    /// Label and `affected / total` counts of the app code the impact ratio counts
+    fn affected_of_total(&self, analysis: &ImpactAnalysis) -> (String, String) {
+        let (affected, total) = analysis.affected_of_total();
+        let label = match analysis.metric {
+            ImpactMetric::Files => "Affected Files",
+            ImpactMetric::Lines => "Affected Lines",
+            ImpactMetric::Functions => "Affected Functions",
+        };
+        (label.to_string(), format!("{} / {}", self.numbers.count(affected), self.numbers.count(total)))
+    }
+
+    /// Shared symbols a stale file imports, or the whole framework when it names none
+    fn stale_imports(stale: &StaleIntegration) -> String {
+        if stale.imports.is_empty() {
+            "the shared framework".to_string()
+        } else {
+            stale.imports.join(", ")
+        }
+    }
+
+    /// Layer impacts of every platform in platform name order, or none when no app
+    /// file matched a layer rule
+    fn layer_impacts(analysis: &ImpactAnalysis) -> Vec<(&str, &LayerImpact)> {
+        let mut layers: Vec<(&str, &LayerImpact)> = analysis
+            .platform_impacts
+            .iter()
+            .flat_map(|(platform, impact)| impact.layers.iter().map(move |layer| (platform.as_str(), layer)))
+            .collect();
+        if layers.iter().all(|(_, layer)| layer.layer == AppLayers::OTHER) {
+            return Vec::new();
+        }
+        // Layers keep their precedence order within a platform
+        layers.sort_by_key(|(platform, _)| *platform);
+        layers
+    }
+
+    /// What a diff run was restricted to
+    fn diff_line(analysis: &ImpactAnalysis) -> Option<String> {
+        let diff = analysis.diff.as_ref()?;
+        let mut line = format!(
+            "Changes since {}: {} shared file(s), {} symbol(s) touched",
+            diff.base_ref,
+            diff.changed_files.len(),
+            diff.touched_symbols.len()
+        );
+        if !diff.touched_symbols.is_empty() {
+            line.push_str(&format!(" ({})", diff.touched_symbols.join(", ")));
+        }
+        Some(line)
+    }
+
+    /// Affected app files of a diff run, by platform and path, with the path relative
+    /// to the project root
+    fn impacted_files<'a>(&self, analysis: &'a ImpactAnalysis) -> Vec<(&'a str, String, &'a FileMetrics)> {
+        let mut files: Vec<(&str, String, &FileMetrics)> = analysis
+            .files
+            .iter()
+            .filter(|file| file.affected_lines > 0)
+            .map(|file| {
+                let path = Path::new(&file.path);
+                let relative = self
+                    .project_root
+                    .as_deref()
+                    .and_then(|root| path.strip_prefix(root).ok())
+                    .unwrap_or(path);
+                let relative = relative.to_string_lossy().trim_start_matches("./").replace('\\', "/");
+                (file.platform.as_str(), relative, file)
+            })
+            .collect();
+        files.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        files
+    }
+
+    /// Impact without the UI layout files, when any were found
+    fn ui_layout_line(&self, analysis: &ImpactAnalysis) -> Option<String> {
+        let ui_layout = &analysis.ui_layout;
+        (ui_layout.files > 0).then(|| {
+            format!(
+                "{} impact coverage without {} UI layout file(s), {} of whose {} lines are affected",
+                self.numbers.percent(ui_layout.impact_ratio_excluding_ui),
+                ui_layout.files,
+                ui_layout.affected_lines,
+                ui_layout.total_lines
+            )
+        })
+    }
+
+    /// Platforms any variant has files on, in name order
+    /// How many usages the suppression baseline removed, and how many of its entries are stale
+    fn suppression_line(analysis: &ImpactAnalysis) -> Option<String> {
+        let stale = analysis.stale_suppressions.len();
+        if analysis.suppressed_usages == 0 && stale == 0 {
+            return None;
+        }
+        let mut line = format!("{} usage(s) excluded by the suppression baseline", analysis.suppressed_usages);
+        if stale > 0 {
+            line.push_str(&format!(
+                "; {} stale suppression(s) match nothing (run with --update-suppressions to drop them)",
+                stale
+            ));
+        }
+        Some(line)
+    }
+
+    /// Entry points of every platform, platforms in name order
+    fn entry_points(analysis: &ImpactAnalysis) -> Vec<(&str, &EntryPoint)> {
+        let mut platforms: Vec<(&String, &PlatformImpact)> = analysis.platform_impacts.iter().collect();
+        platforms.sort_by_key(|(name, _)| *name);
+        platforms
+            .into_iter()
+            .flat_map(|(name, impact)| impact.entry_points.iter().map(move |entry| (name.as_str(), entry)))
+            .collect()
+    }
+
+    /// Number of symbols an entry point collapses, with the first few named
+    fn reached_summary(entry: &EntryPoint) -> String {
+        const NAMED: usize = 3;
+        if entry.reached_symbols.is_empty() {
+            return "-".to_string();
+        }
+        let mut names = entry.reached_symbols[..entry.reached_symbols.len().min(NAMED)].join(", ");
+        if entry.reached_symbols.len() > NAMED {
+            names.push_str(", …");
+        }
+        format!("{} ({})", entry.reached_symbols.len(), names)
+    }
+
+    fn variant_platforms(analysis: &ImpactAnalysis) -> Vec<&str> {
+        let platforms: BTreeSet<&str> = analysis
+            .variants
+            .iter()
+            .flat_map(|v| v.platform_ratios.keys().map(String::as_str))
+            .collect();
+        platforms.into_iter().collect()
+    }
+
+    fn variant_ratio(&self, variant: &VariantImpact, platform: &str) -> String {
+        variant
+            .platform_ratios
+            .get(platform)
+            .map_or("-".to_string(), |ratio| self.numbers.percent(*ratio))
+    }
+
+    /// Call-site kinds with their share of all usage sites
+    fn usage_kind_shares(analysis: &ImpactAnalysis) -> Vec<(&UsageKindCount, f64)> {
+        let total: usize = analysis.usage_kinds.iter().map(|k| k.usages).sum();
+        analysis
+            .usage_kinds
+            .iter()
+            .map(|count| (count, count.usages as f64 / total.max(1) as f64))
+            .collect()
+    }
+
+    /// Shared types app code never constructs, extends, or calls into, when there are any
+    fn pass_through_line(analysis: &ImpactAnalysis) -> Option<String> {
+        const SHOWN: usize = 10;
+        let names = &analysis.pass_through_symbols;
+        if names.is_empty() {
+            return None;
+        }
+        let mut line = format!(
+            "Only passed through (type annotations and imports): {}",
+            names.iter().take(SHOWN).cloned().collect::<Vec<_>>().join(", ")
+        );
+        if names.len() > SHOWN {
+            line.push_str(&format!(" and {} more", names.len() - SHOWN));
+        }
+        Some(line)
+    }
+
+    /// Heading of the API change log, with the impact change over the same span
+    fn api_changes_heading(churn: &ApiChurn) -> String {
+        let since = match &churn.since_commit {
+            Some(sha) => sha.chars().take(7).collect(),
+            None => "previous run".to_string(),
+        };
+        format!(
+            "Shared API Changes since {} (impact {:+.2}%)",
+            since,
+            churn.impact_ratio_change * 100.0
+        )
+    }
+
+    /// One-line summary of the run metadata for text reports
+    fn provenance_line(metadata: &RunMetadata) -> String {
+        let mut line = format!(
+            "Generated by kotlin-multiplatform-coverage {} at {}",
+            metadata.tool_version, metadata.generated_at
+        );
+        if let Some(commit) = &metadata.commit_sha {
+            line.push_str(&format!(" from commit {}", &commit[..commit.len().min(12)]));
+        }
+        if let Some(host) = &metadata.host {
+            line.push_str(&format!(" on {}", host));
+        }
+        if let Some(ci) = &metadata.ci {
+            line.push_str(&format!(" in {}", ci.provider));
+            if let Some(pull_request) = ci.pull_request {
+                line.push_str(&format!(" for #{}", pull_request));
+            }
+            if let Some(branch) = &ci.branch {
+                line.push_str(&format!(" on branch {}", branch));
+            }
+            if let Some(build_url) = &ci.build_url {
+                line.push_str(&format!(" ({})", build_url));
+            }
+        }
+        line.push_str(&format!(" (config sha256 {}", &metadata.config_hash[..12]));
+        if metadata.signature.is_some() {
+            line.push_str(", signed");
+        }
+        line.push(')');
+        line
+    }
+
+    /// Kind of an Android module, as shown in reports
+    fn module_kind(module: &AndroidModuleImpact) -> &'static str {
+        match module.kind {
+            GradleModuleKind::Application => "application",
+            GradleModuleKind::Library => "library",
+        }
+    }
+
+    /// Kind of a Swift module, as shown in reports
+    fn swift_module_kind(module: &SwiftModuleImpact) -> &'static str {
+        match module.kind {
+            SwiftModuleKind::XcodeTarget => "xcode target",
+            SwiftModuleKind::Package => "package",
+        }
+    }
+
+    /// Modules shared code arrives through, or `-`
+    fn via_modules(via_modules: &[String]) -> String {
+        if via_modules.is_empty() {
+            "-".to_string()
+        } else {
+            via_modules.join(", ")
+        }
+    }
+
+    /// Threshold of a category and whether it is met, or `-` without one
+    fn category_threshold(&self, category: &CategoryUsage) -> String {
+        let Some(threshold) = category.threshold else {
+            return "-".to_string();
+        };
+        let status = match (category.meets_threshold(), category.enforce) {
+            (true, _) => "met",
+            (false, true) => "missed (enforced)",
+            (false, false) => "missed",
+        };
+        format!("≥ {} ({})", self.numbers.percent(threshold), status)
+    }
+
+    /// Status of a coverage goal, flagging misses that fail the run
+    fn goal_status(goal: &GoalProgress) -> &'static str {
+        match (goal.met, goal.total_lines, goal.enforce) {
+            (true, _, _) => "met",
+            (false, 0, _) => "no matching app code",
+            (false, _, true) => "missed (enforced)",
+            (false, _, false) => "missed",
+        }
+    }
+
+    /// Formats per-platform counts as `Android: 3, iOS: 1`
+    fn format_platform_counts(counts: &BTreeMap<String, usize>) -> String {
+        if counts.is_empty() {
+            return "-".to_string();
+        }
+        counts
+            .iter()
+            .map(|(platform, count)| format!("{}: {}", platform, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Display name of a Kotlin package, with the root package spelled out
+    fn package_name(package: &str) -> &str {
+        if package.is_empty() {
+            "<root>"
+        } else {
+            package
+        }
+    }
+
+    /// Comma-separated owners, or `-` when no `OWNERS` rule covers the API
+    fn owner_list(owners: &[String]) -> String {
+        if owners.is_empty() {
+            "-".to_string()
+        } else {
+            owners.join(", ")
+        }
+    }
+
+    /// Renders a symbol name, linked to its documentation when a doc base URL is configured
+    fn markdown_symbol_name(&self, analysis: &ImpactAnalysis, symbol_name: &str) -> String {
+        self.doc_links
+            .as_ref()
+            .and_then(|resolver| resolver.link_for_name(&analysis.symbols, symbol_name))
+            .map(|link| format!("[{}]({})", symbol_name, link))
+            .unwrap_or_else(|| symbol_name.to_string())
+    }
+}
+/// Change kind and symbol of every API change, renames shown as `old → new`
+/// A JSON impact report: the raw analysis, plus its headline numbers as text reports print them
+#[derive(serde::Serialize)]
+struct JsonImpactReport<'a> {
+    #[serde(flatten)]
+    analysis: &'a ImpactAnalysis,
+    formatted: FormattedNumbers,
+    ratios: ExactRatios,
+}
+
+fn api_changes(churn: &ApiChurn) -> Vec<(&'static str, String)> {
+    let added = churn.added.iter().map(|s| ("added", s.qualified_name()));
+    let removed = churn.removed.iter().map(|s| ("removed", s.qualified_name()));
+    let renamed = churn.renamed.iter().map(|r| {
+        ("renamed", format!("{} → {}", r.from.qualified_name(), r.to.name))
+    });
+    added.chain(removed).chain(renamed).collect()
+}
+
+
+/// A CSV field, quoted when it contains a separator, quote, or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
     }
 }
@@ -0,0 +1,267 @@
+//! Locale-aware formatting of the percentages and counts in human-readable reports
+//! JSON reports keep raw numbers and add the formatted text next to them, so
+//! consumers never parse `45,23 %` back into a number, and the exact fractions the
+//! ratios come from, so comparisons need not trust a rounded percentage.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::domain::{ImpactAnalysis, Ratio, RatioPrecision};
+
+/// Separators of a locale, by language (or language-region) tag
+///
+/// `(tags, decimal separator, grouping separator, text between a number and `%`)`
+const LOCALES: &[(&[&str], char, Option<char>, &str)] = &[
+    (&["en", "ja", "ko", "zh", "he", "th"], '.', Some(','), ""),
+    (&["de", "es", "it", "nl", "pt", "id", "tr", "da", "el"], ',', Some('.'), "\u{a0}"),
+    (&["fr", "ru", "pl", "cs", "sv", "fi", "nb", "uk", "sk", "hu"], ',', Some('\u{202f}'), "\u{a0}"),
+    (&["de-ch", "de-li"], '.', Some('’'), ""),
+    (&["pt-br", "es-mx"], ',', Some('.'), ""),
+];
+
+/// How reports print percentages and counts
+///
+/// The default matches the locale-independent output reports have always had:
+/// `45.23%` and `1234`, percentages rounded half up to two decimals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Tag of the locale the separators come from, if any
+    locale: Option<String>,
+    decimal_separator: char,
+    grouping_separator: Option<char>,
+    percent_spacing: &'static str,
+    precision: RatioPrecision,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            locale: None,
+            decimal_separator: '.',
+            grouping_separator: None,
+            percent_spacing: "",
+            precision: RatioPrecision::default(),
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Separators of a locale such as `de`, `de-DE`, or `fr_FR.UTF-8`
+    ///
+    /// A language-region tag falls back to its language; `C` and `POSIX` keep the default.
+    pub fn for_locale(locale: &str) -> Result<Self> {
+        let tag = locale.split(['.', '@']).next().unwrap_or_default().replace('_', "-").to_lowercase();
+        if matches!(tag.as_str(), "c" | "posix") {
+            return Ok(Self::default());
+        }
+        let language = tag.split('-').next().unwrap_or_default();
+        let find = |wanted: &str| LOCALES.iter().find(|(tags, ..)| tags.contains(&wanted));
+        let Some(&(_, decimal_separator, grouping_separator, percent_spacing)) = find(&tag).or_else(|| find(language))
+        else {
+            anyhow::bail!("Unsupported locale `{}` (languages: {})", locale, Self::languages().join(", "));
+        };
+
+        Ok(Self {
+            locale: Some(locale.to_string()),
+            decimal_separator,
+            grouping_separator,
+            percent_spacing,
+            precision: RatioPrecision::default(),
+        })
+    }
+
+    /// Replaces the decimal separator
+    pub fn with_decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Replaces the thousands separator; `None` prints counts ungrouped
+    pub fn with_grouping_separator(mut self, separator: Option<char>) -> Self {
+        self.grouping_separator = separator;
+        self
+    }
+
+    /// Replaces the decimal places and rounding of percentages
+    pub fn with_precision(mut self, precision: RatioPrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn precision(&self) -> RatioPrecision {
+        self.precision
+    }
+
+    fn languages() -> Vec<&'static str> {
+        let mut languages: Vec<&str> = LOCALES.iter().flat_map(|(tags, ..)| tags.iter().copied()).collect();
+        languages.sort_unstable();
+        languages
+    }
+
+    /// A ratio (0 ~ 1) as a rounded percentage: `45.23%`, `45,23 %`
+    pub fn percent(&self, ratio: f64) -> String {
+        self.ratio(Ratio::approximating(ratio))
+    }
+
+    /// A change of a ratio as a signed percentage: `+1.25%`, `-0.50%`
+    pub fn signed_percent(&self, change: f64) -> String {
+        let sign = if change < 0.0 { "-" } else { "+" };
+        format!("{}{}", sign, self.percent(change.abs()))
+    }
+
+    /// An exact ratio as a rounded percentage
+    pub fn ratio(&self, ratio: Ratio) -> String {
+        let percent = self.precision.percent(ratio);
+        format!("{}{}%", self.decimal(percent, self.precision.decimals as usize), self.percent_spacing)
+    }
+
+    /// A count with thousands grouped: `12,345`, `12.345`
+    pub fn count(&self, count: usize) -> String {
+        self.group(&count.to_string())
+    }
+
+    /// `value` with `decimals` decimals
+    pub fn decimal(&self, value: f64, decimals: usize) -> String {
+        let text = format!("{:.*}", decimals, value);
+        let (sign, text) = match text.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", text.as_str()),
+        };
+        match text.split_once('.') {
+            Some((whole, fraction)) => format!("{}{}{}{}", sign, self.group(whole), self.decimal_separator, fraction),
+            None => format!("{}{}", sign, self.group(text)),
+        }
+    }
+
+    fn group(&self, digits: &str) -> String {
+        let Some(separator) = self.grouping_separator else {
+            return digits.to_string();
+        };
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push(separator);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+}
+
+/// Headline numbers of an impact report as reports print them, next to the raw values
+#[derive(Debug, Serialize)]
+pub struct FormattedNumbers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    pub impact_ratio: String,
+    pub affected_lines: String,
+    pub total_app_lines: String,
+    pub platforms: BTreeMap<String, FormattedPlatform>,
+}
+
+/// A platform's impact as reports print it
+#[derive(Debug, Serialize)]
+pub struct FormattedPlatform {
+    pub impact_ratio: String,
+    pub affected_lines: String,
+    pub total_lines: String,
+}
+
+impl FormattedNumbers {
+    pub fn of(analysis: &ImpactAnalysis, numbers: &NumberFormat) -> Self {
+        Self {
+            locale: numbers.locale.clone(),
+            impact_ratio: numbers.percent(analysis.impact_ratio),
+            affected_lines: numbers.count(analysis.affected_lines),
+            total_app_lines: numbers.count(analysis.total_app_lines),
+            platforms: analysis
+                .platform_impacts
+                .iter()
+                .map(|(name, impact)| {
+                    let formatted = FormattedPlatform {
+                        impact_ratio: numbers.percent(impact.impact_ratio),
+                        affected_lines: numbers.count(impact.affected_lines),
+                        total_lines: numbers.count(impact.total_lines),
+                    };
+                    (name.clone(), formatted)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Impact ratios of a report as exact fractions, next to the percentages reports round them to
+#[derive(Debug, Serialize)]
+pub struct ExactRatios {
+    pub precision: RatioPrecision,
+    pub impact_ratio: ExactRatio,
+    pub platforms: BTreeMap<String, ExactRatio>,
+}
+
+/// Affected over total app code, and the percentage rounded to the report's precision
+#[derive(Debug, Serialize)]
+pub struct ExactRatio {
+    #[serde(flatten)]
+    pub ratio: Ratio,
+    pub rounded_percent: f64,
+}
+
+impl ExactRatios {
+    pub fn of(analysis: &ImpactAnalysis, precision: RatioPrecision) -> Self {
+        let exact = |ratio: Ratio| ExactRatio {
+            ratio,
+            rounded_percent: precision.percent(ratio),
+        };
+        Self {
+            precision,
+            impact_ratio: exact(analysis.exact_impact_ratio()),
+            platforms: analysis
+                .platform_impacts
+                .iter()
+                .map(|(name, impact)| (name.clone(), exact(impact.exact_ratio(analysis.metric))))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Rounding;
+
+    #[test]
+    fn test_number_formats() -> Result<()> {
+        let plain = NumberFormat::default();
+        assert_eq!(plain.percent(0.45234), "45.23%");
+        assert_eq!((plain.signed_percent(0.0125), plain.signed_percent(-0.005)), ("+1.25%".to_string(), "-0.50%".to_string()));
+        assert_eq!(plain.count(1234567), "1234567");
+
+        let english = NumberFormat::for_locale("en-US")?;
+        assert_eq!(english.percent(0.45234), "45.23%");
+        assert_eq!(english.count(1234567), "1,234,567");
+        assert_eq!(english.decimal(-1234.5, 1), "-1,234.5");
+
+        let german = NumberFormat::for_locale("de_DE.UTF-8")?;
+        assert_eq!(german.percent(12.3456), "1.234,56\u{a0}%");
+        assert_eq!(german.count(999), "999");
+        assert_eq!(NumberFormat::for_locale("de-CH")?.count(12345), "12’345");
+        assert_eq!(NumberFormat::for_locale("fr")?.count(12345), "12\u{202f}345");
+
+        let custom = NumberFormat::for_locale("en")?.with_grouping_separator(Some(' '));
+        assert_eq!(custom.count(12345), "12 345");
+        assert_eq!(NumberFormat::for_locale("C")?, NumberFormat::default());
+        assert!(NumberFormat::for_locale("xx").is_err());
+
+        // 12.345% exactly, which a float division may land on either side of
+        let tie = Ratio::new(2469, 20000);
+        let precision = |decimals, rounding| NumberFormat::default().with_precision(RatioPrecision { decimals, rounding });
+        assert_eq!(plain.ratio(tie), "12.35%");
+        assert_eq!(precision(2, Rounding::HalfEven).ratio(tie), "12.34%");
+        assert_eq!(precision(1, Rounding::Down).ratio(Ratio::new(29, 100)), "29.0%");
+        assert_eq!(precision(0, Rounding::Up).ratio(Ratio::new(1, 1000)), "1%");
+        assert_eq!(plain.ratio(Ratio::new(5, 0)), "0.00%");
+
+        Ok(())
+    }
+}
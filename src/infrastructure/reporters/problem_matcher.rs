@@ -0,0 +1,147 @@
+//! Compiler-style diagnostics for editor problem matchers
+//! One line per usage site, `path:line:col: note: uses KMP symbol X`, which the problem
+//! matchers of VS Code and IntelliJ turn into editor markers without a dedicated plugin
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::adapters::FileSystem;
+use crate::domain::{find_word, ImpactAnalysis, Severity, SymbolUsage};
+
+/// Renders an analysis as problem-matcher lines
+pub struct ProblemLines;
+
+impl ProblemLines {
+    /// Usage sites of shared symbols as notes, then diagnostics about shared symbols at
+    /// their declarations, each sorted by location
+    ///
+    /// Files are read through `fs` to find the column of each usage; a usage whose line
+    /// cannot be read points at column 1. Paths are relative to `project_root`, so
+    /// matchers resolve them against the workspace folder.
+    pub fn render(analysis: &ImpactAnalysis, fs: &dyn FileSystem, project_root: Option<&str>) -> String {
+        let mut files: HashMap<&str, Option<Vec<String>>> = HashMap::new();
+        let mut usages: Vec<(String, usize, usize, &str)> = analysis
+            .symbol_usages
+            .values()
+            .flatten()
+            .map(|usage| {
+                let lines = files.entry(usage.file_path.as_str()).or_insert_with(|| {
+                    fs.read_to_string(Path::new(&usage.file_path))
+                        .ok()
+                        .map(|content| content.lines().map(str::to_string).collect())
+                });
+                let column = lines
+                    .as_ref()
+                    .and_then(|lines| lines.get(usage.line_number.checked_sub(1)?))
+                    .map_or(1, |line| Self::column(line, usage));
+                (
+                    Self::relative_path(&usage.file_path, project_root),
+                    usage.line_number,
+                    column,
+                    usage.symbol_name.as_str(),
+                )
+            })
+            .collect();
+        usages.sort();
+
+        let mut output: Vec<String> = usages
+            .into_iter()
+            .map(|(path, line, column, symbol)| format!("{}:{}:{}: note: uses KMP symbol {}", path, line, column, symbol))
+            .collect();
+
+        let mut diagnostics: Vec<(String, usize, String)> = analysis
+            .diagnostics
+            .iter()
+            .filter_map(|diagnostic| {
+                let name = diagnostic.symbol.as_deref()?;
+                let symbol = analysis.symbols.iter().find(|s| s.name == name)?;
+                let line = symbol.lines.map_or(1, |lines| lines.start);
+                let severity = match diagnostic.severity {
+                    Severity::Info => "note",
+                    _ => diagnostic.severity.name(),
+                };
+                Some((
+                    Self::relative_path(&symbol.file_path, project_root),
+                    line,
+                    format!("{}: {} [{}]", severity, diagnostic.message, diagnostic.rule),
+                ))
+            })
+            .collect();
+        diagnostics.sort();
+        output.extend(
+            diagnostics
+                .into_iter()
+                .map(|(path, line, message)| format!("{}:{}:1: {}", path, line, message)),
+        );
+
+        output.join("\n")
+    }
+
+    /// 1-based character column of the usage's symbol on its line, or of the line's code
+    fn column(line: &str, usage: &SymbolUsage) -> usize {
+        let at = find_word(line, &usage.symbol_name)
+            .unwrap_or_else(|| line.len() - line.trim_start().len());
+        line[..at].chars().count() + 1
+    }
+
+    fn relative_path(file_path: &str, project_root: Option<&str>) -> String {
+        let path = Path::new(file_path);
+        let relative = project_root
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        relative
+            .to_string_lossy()
+            .trim_start_matches("./")
+            .replace('\\', "/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::MemoryFileSystem;
+    use crate::domain::{Diagnostic, LineRange, Symbol, SymbolType, UsageKind};
+
+    fn usage(symbol: &str, line: usize) -> SymbolUsage {
+        SymbolUsage {
+            symbol_name: symbol.to_string(),
+            file_path: "/repo/app/Main.kt".to_string(),
+            line_number: line,
+            context: String::new(),
+            kind: UsageKind::Reference,
+        }
+    }
+
+    #[test]
+    fn test_problem_lines() {
+        let mut fs = MemoryFileSystem::new();
+        fs.insert("/repo/app/Main.kt", "fun main() {\n    val user = User(\"é\", formatName(User.EMPTY))\n}\n");
+        let mut analysis = ImpactAnalysis::default();
+        analysis.symbol_usages.insert("User".to_string(), vec![usage("User", 2)]);
+        analysis
+            .symbol_usages
+            .insert("formatName".to_string(), vec![usage("formatName", 2), usage("formatName", 9)]);
+        analysis.symbols.push(Symbol {
+            package: "com.example".to_string(),
+            lines: Some(LineRange::new(4, 6)),
+            ..Symbol::new("formatName", SymbolType::Function, "shared", "/repo/shared/Names.kt")
+        });
+        analysis.diagnostics.push(Diagnostic {
+            rule: "missing-kdoc".to_string(),
+            severity: Severity::Warning,
+            message: "`formatName` has no KDoc".to_string(),
+            symbol: Some("formatName".to_string()),
+        });
+
+        let output = ProblemLines::render(&analysis, &fs, Some("/repo"));
+        assert_eq!(
+            output.lines().collect::<Vec<_>>(),
+            vec![
+                "app/Main.kt:2:16: note: uses KMP symbol User",
+                "app/Main.kt:2:26: note: uses KMP symbol formatName",
+                "app/Main.kt:9:1: note: uses KMP symbol formatName",
+                "shared/Names.kt:4:1: warning: `formatName` has no KDoc [missing-kdoc]",
+            ]
+        );
+    }
+}
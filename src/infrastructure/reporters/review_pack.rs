@@ -0,0 +1,334 @@
+//! Review comment fixtures for pull requests touching shared APIs
+//! The changed lines of a `--diff` run that declare or use a touched shared symbol, per
+//! file, with the reviewers `CODEOWNERS` suggests, for a bot to post as review comments
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::adapters::FileSystem;
+use crate::domain::{CodeOwners, ImpactAnalysis, LineRange};
+
+/// Changed files of a pull request with the snippets to comment on
+#[derive(Debug, Serialize)]
+pub struct ReviewPack {
+    /// Commit, branch, or tag the changes are taken against
+    pub base_ref: String,
+    /// `CODEOWNERS` file the reviewers come from, relative to the project root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub codeowners: Option<String>,
+    /// Files with at least one snippet, in path order
+    pub files: Vec<ReviewFile>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewFile {
+    /// Path relative to the project root
+    pub path: String,
+    /// Owners of the file, then those of the shared files declaring its snippets' symbols
+    pub reviewers: Vec<String>,
+    /// Snippets in line order
+    pub snippets: Vec<ReviewSnippet>,
+}
+
+/// Changed lines to comment on, all within one hunk of the diff
+#[derive(Debug, Serialize)]
+pub struct ReviewSnippet {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub kind: SnippetKind,
+    /// Touched shared symbols the lines declare or use, by name
+    pub symbols: Vec<String>,
+    /// App usage sites of the symbols: all of them for a declaration, those on the lines for a usage
+    pub usages: usize,
+    /// Suggested comment text
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum SnippetKind {
+    /// Changed lines within the declaration of a shared symbol
+    Declaration,
+    /// Changed app lines using a touched shared symbol
+    Usage,
+}
+
+impl ReviewPack {
+    /// Builds the pack of a diff run, reading `CODEOWNERS` below `project_root` through `fs`
+    ///
+    /// A declaration snippet is a changed range clipped to the declarations it overlaps; a
+    /// usage snippet is a changed range of an app file with usage sites on it.
+    pub fn from_analysis(analysis: &ImpactAnalysis, fs: &dyn FileSystem, project_root: Option<&str>) -> Result<Self> {
+        let Some(diff) = &analysis.diff else {
+            anyhow::bail!("The review pack needs a diff run (--diff <BASE_REF>)");
+        };
+        let root = Path::new(project_root.unwrap_or(""));
+        let codeowners = CodeOwners::LOCATIONS
+            .iter()
+            .find(|location| fs.is_file(&root.join(location)))
+            .map(|location| -> Result<_> {
+                Ok((location.to_string(), CodeOwners::parse(&fs.read_to_string(&root.join(location))?)))
+            })
+            .transpose()?;
+        let owners = |path: &str| codeowners.as_ref().map_or(&[][..], |(_, rules)| rules.owners_of(path));
+        let changed_range = |path: &str, line: &LineRange| {
+            diff.changed_lines
+                .get(path)
+                .into_iter()
+                .flatten()
+                .find(|range| range.overlaps(line))
+                .copied()
+        };
+
+        let mut snippets: BTreeMap<(String, LineRange, SnippetKind), Vec<String>> = BTreeMap::new();
+        let mut declared_in: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for symbol in &analysis.symbols {
+            let path = Self::relative_path(&symbol.file_path, project_root);
+            declared_in.entry(symbol.name.as_str()).or_default().push(path.clone());
+            let changed = diff.changed_lines.get(&path).into_iter().flatten();
+            for range in changed.filter(|range| symbol.lines.is_none_or(|lines| range.overlaps(&lines))) {
+                snippets
+                    .entry((path.clone(), *range, SnippetKind::Declaration))
+                    .or_default()
+                    .push(symbol.name.clone());
+            }
+        }
+        for usage in analysis.symbol_usages.values().flatten() {
+            let path = Self::relative_path(&usage.file_path, project_root);
+            let line = LineRange::new(usage.line_number, usage.line_number);
+            if let Some(range) = changed_range(&path, &line) {
+                snippets
+                    .entry((path, range, SnippetKind::Usage))
+                    .or_default()
+                    .push(usage.symbol_name.clone());
+            }
+        }
+
+        let mut files: BTreeMap<String, ReviewFile> = BTreeMap::new();
+        for ((path, range, kind), symbols) in snippets {
+            let mut names = symbols.clone();
+            names.sort();
+            names.dedup();
+            let usages = match kind {
+                SnippetKind::Declaration => names
+                    .iter()
+                    .map(|name| analysis.symbol_usages.get(name).map_or(0, Vec::len))
+                    .sum(),
+                SnippetKind::Usage => symbols.len(),
+            };
+            let lines = match kind {
+                SnippetKind::Declaration => Self::declaration_lines(analysis, &path, &names, range, project_root),
+                SnippetKind::Usage => range,
+            };
+            let body = Self::body(analysis, kind, &names, usages, &declared_in);
+
+            let file = files.entry(path.clone()).or_insert_with(|| ReviewFile {
+                path: path.clone(),
+                reviewers: owners(&path).to_vec(),
+                snippets: Vec::new(),
+            });
+            for declaring in names.iter().flat_map(|name| declared_in.get(name.as_str())).flatten() {
+                for owner in owners(declaring) {
+                    if !file.reviewers.contains(owner) {
+                        file.reviewers.push(owner.clone());
+                    }
+                }
+            }
+            file.snippets.push(ReviewSnippet {
+                start_line: lines.start,
+                end_line: lines.end,
+                kind,
+                symbols: names,
+                usages,
+                body,
+            });
+        }
+        for file in files.values_mut() {
+            file.snippets.sort_by_key(|snippet| (snippet.start_line, snippet.end_line));
+        }
+
+        Ok(Self {
+            base_ref: diff.base_ref.clone(),
+            codeowners: codeowners.map(|(location, _)| location),
+            files: files.into_values().collect(),
+        })
+    }
+
+    /// Part of a changed range within the declarations of `names` in `path`
+    fn declaration_lines(
+        analysis: &ImpactAnalysis,
+        path: &str,
+        names: &[String],
+        range: LineRange,
+        project_root: Option<&str>,
+    ) -> LineRange {
+        let declarations: Vec<LineRange> = analysis
+            .symbols
+            .iter()
+            .filter(|symbol| names.contains(&symbol.name) && Self::relative_path(&symbol.file_path, project_root) == path)
+            .filter_map(|symbol| match symbol.lines {
+                Some(lines) => lines.overlaps(&range).then_some(lines),
+                None => Some(range),
+            })
+            .collect();
+        let start = declarations.iter().map(|lines| lines.start).min().unwrap_or(range.start);
+        let end = declarations.iter().map(|lines| lines.end).max().unwrap_or(range.end);
+        LineRange::new(range.start.max(start), range.end.min(end))
+    }
+
+    fn body(
+        analysis: &ImpactAnalysis,
+        kind: SnippetKind,
+        names: &[String],
+        usages: usize,
+        declared_in: &BTreeMap<&str, Vec<String>>,
+    ) -> String {
+        let symbols = names.iter().map(|name| format!("`{}`", name)).collect::<Vec<_>>().join(", ");
+        match kind {
+            SnippetKind::Declaration if usages == 0 => {
+                format!("Changes shared {}, which app code does not use", symbols)
+            }
+            SnippetKind::Declaration => {
+                let files = names
+                    .iter()
+                    .flat_map(|name| analysis.symbol_usages.get(name))
+                    .flatten()
+                    .map(|usage| usage.file_path.as_str())
+                    .collect::<BTreeSet<_>>()
+                    .len();
+                format!(
+                    "Changes shared {}, used at {} site{} in {} app file{}",
+                    symbols,
+                    usages,
+                    if usages == 1 { "" } else { "s" },
+                    files,
+                    if files == 1 { "" } else { "s" }
+                )
+            }
+            SnippetKind::Usage => {
+                let mut declarations: Vec<&str> = names
+                    .iter()
+                    .flat_map(|name| declared_in.get(name.as_str()))
+                    .flatten()
+                    .map(String::as_str)
+                    .collect();
+                declarations.sort();
+                declarations.dedup();
+                let declarations = declarations.iter().map(|path| format!("`{}`", path)).collect::<Vec<_>>();
+                format!("Uses shared {}, changed in {}", symbols, declarations.join(", "))
+            }
+        }
+    }
+
+    fn relative_path(file_path: &str, project_root: Option<&str>) -> String {
+        let path = Path::new(file_path);
+        let relative = project_root
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        relative
+            .to_string_lossy()
+            .trim_start_matches("./")
+            .replace('\\', "/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::MemoryFileSystem;
+    use crate::domain::{DiffImpact, Symbol, SymbolType, SymbolUsage, UsageKind};
+
+    const CODEOWNERS: &str = "# Shared code\n* @org/mobile\n/shared/ @org/platform @alice # API owners\n*.swift @org/ios\n";
+
+    fn symbol(name: &str, lines: LineRange) -> Symbol {
+        Symbol {
+            package: "com.example".to_string(),
+            lines: Some(lines),
+            ..Symbol::new(name, SymbolType::Class, "shared", "/repo/shared/src/commonMain/kotlin/Models.kt")
+        }
+    }
+
+    fn usage(symbol: &str, file_path: &str, line: usize) -> SymbolUsage {
+        SymbolUsage {
+            symbol_name: symbol.to_string(),
+            file_path: file_path.to_string(),
+            line_number: line,
+            context: String::new(),
+            kind: UsageKind::Reference,
+        }
+    }
+
+    #[test]
+    fn test_codeowners() {
+        let owners = CodeOwners::parse(CODEOWNERS);
+        assert_eq!(owners.rules.len(), 3);
+        assert_eq!(owners.owners_of("shared/src/commonMain/kotlin/Models.kt"), ["@org/platform", "@alice"]);
+        assert_eq!(owners.owners_of("iosApp/iosApp/ContentView.swift"), ["@org/ios"]);
+        assert_eq!(owners.owners_of("app/shared/Main.kt"), ["@org/mobile"]);
+        assert!(CodeOwners::parse("/docs/ @org/docs").owners_of("app/Main.kt").is_empty());
+    }
+
+    #[test]
+    fn test_review_pack() {
+        let mut fs = MemoryFileSystem::new();
+        fs.insert("/repo/.github/CODEOWNERS", CODEOWNERS);
+        let mut analysis = ImpactAnalysis {
+            symbols: vec![symbol("User", LineRange::new(3, 8)), symbol("Address", LineRange::new(10, 12))],
+            ..Default::default()
+        };
+        analysis.symbol_usages.insert(
+            "User".to_string(),
+            vec![
+                usage("User", "/repo/app/Main.kt", 4),
+                usage("User", "/repo/app/Main.kt", 20),
+                usage("User", "/repo/iosApp/iosApp/ContentView.swift", 7),
+            ],
+        );
+        analysis.diff = Some(DiffImpact {
+            base_ref: "origin/main".to_string(),
+            changed_files: vec!["/repo/shared/src/commonMain/kotlin/Models.kt".to_string()],
+            touched_symbols: vec!["Address".to_string(), "User".to_string()],
+            changed_lines: BTreeMap::from([
+                (
+                    "shared/src/commonMain/kotlin/Models.kt".to_string(),
+                    vec![LineRange::new(1, 4), LineRange::new(8, 11)],
+                ),
+                ("app/Main.kt".to_string(), vec![LineRange::new(3, 5)]),
+            ]),
+        });
+
+        let pack = ReviewPack::from_analysis(&analysis, &fs, Some("/repo")).unwrap();
+        assert_eq!(pack.base_ref, "origin/main");
+        assert_eq!(pack.codeowners.as_deref(), Some(".github/CODEOWNERS"));
+        assert_eq!(
+            pack.files.iter().map(|file| file.path.as_str()).collect::<Vec<_>>(),
+            vec!["app/Main.kt", "shared/src/commonMain/kotlin/Models.kt"]
+        );
+
+        let app = &pack.files[0];
+        assert_eq!(app.reviewers, ["@org/mobile", "@org/platform", "@alice"]);
+        assert_eq!(app.snippets.len(), 1);
+        assert_eq!((app.snippets[0].start_line, app.snippets[0].end_line), (3, 5));
+        assert_eq!(app.snippets[0].kind, SnippetKind::Usage);
+        assert_eq!(app.snippets[0].usages, 1);
+        assert_eq!(
+            app.snippets[0].body,
+            "Uses shared `User`, changed in `shared/src/commonMain/kotlin/Models.kt`"
+        );
+
+        let shared = &pack.files[1];
+        assert_eq!(shared.reviewers, ["@org/platform", "@alice"]);
+        let snippets: Vec<(usize, usize, Vec<&str>, usize)> = shared
+            .snippets
+            .iter()
+            .map(|s| (s.start_line, s.end_line, s.symbols.iter().map(String::as_str).collect(), s.usages))
+            .collect();
+        assert_eq!(snippets, vec![(3, 4, vec!["User"], 3), (8, 11, vec!["Address", "User"], 3)]);
+        assert_eq!(shared.snippets[0].body, "Changes shared `User`, used at 3 sites in 2 app files");
+
+        analysis.diff = None;
+        assert!(ReviewPack::from_analysis(&analysis, &fs, Some("/repo")).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+//! Source excerpts for the HTML dashboard
+//! Keeps the lines around each usage site of the files that use shared code,
+//! syntax-highlighted, so reviewers can inspect usages without cloning the repo
+
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+
+use crate::adapters::FileSystem;
+use crate::domain::ImpactAnalysis;
+
+use super::html::escape;
+
+/// Default size budget of all embedded excerpts
+pub const DEFAULT_MAX_SOURCE_BYTES: usize = 1024 * 1024;
+
+/// Lines kept above and below each usage line
+const CONTEXT_LINES: usize = 3;
+
+const KOTLIN_KEYWORDS: &[&str] = &[
+    "abstract", "as", "break", "class", "companion", "continue", "data", "else", "enum", "false",
+    "for", "fun", "if", "import", "in", "interface", "internal", "is", "null", "object", "open",
+    "override", "package", "private", "protected", "public", "return", "sealed", "super",
+    "suspend", "this", "throw", "true", "try", "typealias", "val", "var", "when", "while",
+    // Java
+    "extends", "final", "implements", "new", "static", "void",
+];
+
+const SWIFT_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "case", "class", "continue", "default", "defer", "else",
+    "enum", "extension", "false", "fileprivate", "for", "func", "guard", "if", "import", "in",
+    "init", "let", "nil", "private", "protocol", "public", "return", "self", "static", "struct",
+    "switch", "throw", "throws", "true", "try", "var", "where", "while",
+    // Objective-C
+    "@end", "@implementation", "@import", "@interface", "@property", "NO", "YES", "void",
+];
+
+/// Excerpts of the files with usage sites, within a size budget
+#[derive(Debug, Clone, Default)]
+pub struct SourceExcerpts {
+    pub files: Vec<SourceExcerpt>,
+    /// Files left out because the budget was used up
+    pub omitted_files: usize,
+    pub max_bytes: usize,
+}
+
+/// The usage lines of one file with their surrounding lines
+#[derive(Debug, Clone)]
+pub struct SourceExcerpt {
+    /// Path relative to the project root
+    pub file: String,
+    pub usages: usize,
+    pub lines: Vec<ExcerptLine>,
+}
+
+/// One source line, already highlighted as HTML
+#[derive(Debug, Clone)]
+pub struct ExcerptLine {
+    pub number: usize,
+    pub html: String,
+    pub is_usage: bool,
+    /// Whether lines were skipped right before this one
+    pub after_gap: bool,
+}
+
+impl SourceExcerpts {
+    /// Reads the files with usage sites, most used first, until `max_bytes` of excerpts are kept
+    pub fn collect(
+        analysis: &ImpactAnalysis,
+        fs: &dyn FileSystem,
+        project_root: Option<&str>,
+        max_bytes: usize,
+    ) -> Result<Self> {
+        let mut usage_lines: BTreeMap<&str, BTreeSet<usize>> = BTreeMap::new();
+        let mut usage_symbols: BTreeMap<&str, HashSet<&str>> = BTreeMap::new();
+        for usage in analysis.symbol_usages.values().flatten() {
+            usage_lines
+                .entry(usage.file_path.as_str())
+                .or_default()
+                .insert(usage.line_number);
+            usage_symbols
+                .entry(usage.file_path.as_str())
+                .or_default()
+                .insert(usage.symbol_name.as_str());
+        }
+        let mut files: Vec<(&str, &BTreeSet<usize>)> =
+            usage_lines.iter().map(|(file, lines)| (*file, lines)).collect();
+        files.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
+
+        let mut excerpts = Self {
+            max_bytes,
+            ..Self::default()
+        };
+        let mut used_bytes = 0;
+        for (file, lines) in files {
+            let content = fs.read_to_string(Path::new(file))?;
+            let excerpt = Self::excerpt(file, &content, lines, &usage_symbols[file], project_root);
+            let bytes: usize = excerpt.lines.iter().map(|line| line.html.len()).sum();
+            if used_bytes + bytes > max_bytes {
+                excerpts.omitted_files += 1;
+                continue;
+            }
+            used_bytes += bytes;
+            excerpts.files.push(excerpt);
+        }
+
+        Ok(excerpts)
+    }
+
+    fn excerpt(
+        file: &str,
+        content: &str,
+        usage_lines: &BTreeSet<usize>,
+        symbols: &HashSet<&str>,
+        project_root: Option<&str>,
+    ) -> SourceExcerpt {
+        let keywords = keywords(file);
+        let kept: BTreeSet<usize> = usage_lines
+            .iter()
+            .flat_map(|line| line.saturating_sub(CONTEXT_LINES).max(1)..=line + CONTEXT_LINES)
+            .collect();
+
+        // Every line is scanned so block comments opened above the excerpt carry over
+        let mut in_comment = false;
+        let mut lines = Vec::new();
+        let mut previous = 0;
+        for (index, text) in content.lines().enumerate() {
+            let number = index + 1;
+            let (html, still_in_comment) = highlight(text, keywords, symbols, in_comment);
+            in_comment = still_in_comment;
+            if !kept.contains(&number) {
+                continue;
+            }
+            lines.push(ExcerptLine {
+                number,
+                html,
+                is_usage: usage_lines.contains(&number),
+                after_gap: number > previous + 1,
+            });
+            previous = number;
+        }
+
+        let path = Path::new(file);
+        let relative = project_root
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        SourceExcerpt {
+            file: relative.to_string_lossy().trim_start_matches("./").replace('\\', "/"),
+            usages: usage_lines.len(),
+            lines,
+        }
+    }
+}
+
+fn keywords(file: &str) -> &'static [&'static str] {
+    match Path::new(file).extension().and_then(|e| e.to_str()) {
+        Some("swift" | "m" | "mm" | "h") => SWIFT_KEYWORDS,
+        _ => KOTLIN_KEYWORDS,
+    }
+}
+
+/// Escaped HTML of one line with keywords, strings, comments, and shared symbols wrapped in spans
+///
+/// Returns whether a block comment is still open at the end of the line.
+fn highlight(line: &str, keywords: &[&str], symbols: &HashSet<&str>, mut in_comment: bool) -> (String, bool) {
+    let mut html = String::new();
+    let mut rest = line;
+
+    while !rest.is_empty() {
+        if in_comment {
+            let end = match rest.find("*/") {
+                Some(at) => {
+                    in_comment = false;
+                    at + 2
+                }
+                None => rest.len(),
+            };
+            push_span(&mut html, "com", &rest[..end]);
+            rest = &rest[end..];
+        } else if rest.starts_with("//") {
+            push_span(&mut html, "com", rest);
+            rest = "";
+        } else if rest.starts_with("/*") {
+            in_comment = true;
+            push_span(&mut html, "com", "/*");
+            rest = &rest[2..];
+        } else if rest.starts_with('"') {
+            let end = string_end(rest);
+            push_span(&mut html, "str", &rest[..end]);
+            rest = &rest[end..];
+        } else if rest.starts_with(|c: char| c.is_alphanumeric() || c == '_' || c == '@') {
+            let end = rest
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| !(c.is_alphanumeric() || *c == '_'))
+                .map_or(rest.len(), |(at, _)| at);
+            let word = &rest[..end];
+            if keywords.contains(&word) {
+                push_span(&mut html, "kw", word);
+            } else if symbols.contains(word) {
+                push_span(&mut html, "sym", word);
+            } else {
+                html.push_str(&escape(word));
+            }
+            rest = &rest[end..];
+        } else {
+            let end = rest.chars().next().map_or(1, char::len_utf8);
+            html.push_str(&escape(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+
+    (html, in_comment)
+}
+
+/// Byte length of the string literal at the start of `text`, including both quotes
+fn string_end(text: &str) -> usize {
+    let mut escaped = false;
+    for (at, c) in text.char_indices().skip(1) {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return at + 1,
+            _ => escaped = false,
+        }
+    }
+    text.len()
+}
+
+fn push_span(html: &mut String, class: &str, text: &str) {
+    html.push_str(&format!("<span class=\"{}\">{}</span>", class, escape(text)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::MemoryFileSystem;
+    use crate::domain::{SymbolUsage, UsageKind};
+
+    #[test]
+    fn test_excerpts_keep_usage_context_within_budget() -> Result<()> {
+        let mut fs = MemoryFileSystem::new();
+        let body: String = (1..=20).map(|n| format!("val line{} = {}\n", n, n)).collect();
+        fs.insert(
+            "/repo/app/Main.kt",
+            format!("/* header\n   comment */\nval repo: UserRepository = UserRepositoryImpl() // \"x\"\n{}", body),
+        );
+        fs.insert("/repo/app/Other.kt", "val user = User(\"<id>\")\n");
+
+        let mut analysis = ImpactAnalysis::default();
+        let usage = |file: &str, symbol: &str, line: usize| SymbolUsage {
+            symbol_name: symbol.to_string(),
+            file_path: file.to_string(),
+            line_number: line,
+            context: String::new(),
+            kind: UsageKind::Reference,
+        };
+        analysis.symbol_usages.insert(
+            "UserRepository".to_string(),
+            vec![usage("/repo/app/Main.kt", "UserRepository", 3), usage("/repo/app/Main.kt", "UserRepository", 20)],
+        );
+        analysis
+            .symbol_usages
+            .insert("User".to_string(), vec![usage("/repo/app/Other.kt", "User", 1)]);
+
+        let excerpts = SourceExcerpts::collect(&analysis, &fs, Some("/repo"), DEFAULT_MAX_SOURCE_BYTES)?;
+        assert_eq!(excerpts.files.len(), 2);
+        let main = &excerpts.files[0];
+        assert_eq!(main.file, "app/Main.kt");
+        let numbers: Vec<usize> = main.lines.iter().map(|l| l.number).collect();
+        assert_eq!(numbers, vec![1, 2, 3, 4, 5, 6, 17, 18, 19, 20, 21, 22, 23]);
+        assert!(main.lines[6].after_gap && !main.lines[1].after_gap);
+        assert!(main.lines[2].is_usage && !main.lines[3].is_usage);
+        assert!(main.lines[1].html.starts_with("<span class=\"com\">"));
+        assert!(main.lines[2].html.contains("<span class=\"kw\">val</span>"));
+        assert!(main.lines[2].html.contains("<span class=\"sym\">UserRepository</span>"));
+        assert!(main.lines[2].html.contains("<span class=\"com\">// &quot;x&quot;</span>"));
+        assert!(excerpts.files[1].lines[0].html.contains("<span class=\"str\">&quot;&lt;id&gt;&quot;</span>"));
+
+        let small = SourceExcerpts::collect(&analysis, &fs, Some("/repo"), 200)?;
+        assert_eq!(small.files.len(), 1);
+        assert_eq!(small.files[0].file, "app/Other.kt");
+        assert_eq!(small.omitted_files, 1);
+        Ok(())
+    }
+}
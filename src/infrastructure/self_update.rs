@@ -0,0 +1,423 @@
+//! Release checks and in-place upgrades of the CLI binary
+//! Looks up the latest GitHub release, downloads the build for the running platform,
+//! and swaps it in for the current executable, so binaries baked into CI images do
+//! not silently drift behind
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Latest release of the tool on GitHub
+pub const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/ParkJong-Hun/kotlin-multiplatform-coverage/releases/latest";
+
+/// Seconds between two passive update checks
+pub const NOTICE_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// Name of the executable inside release archives
+const BINARY_NAME: &str = "kotlin-multiplatform-coverage";
+
+/// Largest release download accepted, in bytes
+const MAX_DOWNLOAD_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Time allowed to connect to GitHub
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time allowed for release metadata and checksums; the passive notice runs after every
+/// analysis, so a slow or firewalled network must not hold up the run for long
+const METADATA_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Time allowed to receive a release archive
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A published release, as the GitHub releases API describes it
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub html_url: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// A file attached to a release
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    /// Version without the tag's leading `v`
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    pub fn is_newer_than(&self, current: &str) -> bool {
+        is_newer(self.version(), current)
+    }
+
+    /// The archive built for `target`, e.g. `x86_64-unknown-linux-gnu`, by its exact
+    /// [`archive_name`]
+    pub fn asset_for(&self, target: &str) -> Option<&ReleaseAsset> {
+        let name = archive_name(target);
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+
+    /// The `<asset>.sha256` file published next to an asset, if any
+    fn checksum_for(&self, asset: &ReleaseAsset) -> Option<&ReleaseAsset> {
+        let name = format!("{}.sha256", asset.name);
+        self.assets.iter().find(|candidate| candidate.name == name)
+    }
+}
+
+/// When the passive update check last ran, and the latest version it found
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UpdateCheck {
+    pub checked_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_version: Option<String>,
+}
+
+/// Finds newer releases and installs them over the running executable
+pub struct SelfUpdater {
+    release_url: String,
+}
+
+impl SelfUpdater {
+    pub fn new() -> Self {
+        Self {
+            release_url: LATEST_RELEASE_URL.to_string(),
+        }
+    }
+
+    /// Reads the latest release from another endpoint, such as an internal mirror
+    pub fn with_release_url(mut self, url: &str) -> Self {
+        self.release_url = url.to_string();
+        self
+    }
+
+    pub fn latest_release(&self) -> Result<Release> {
+        let body = fetch(&self.release_url, CONNECT_TIMEOUT, METADATA_TIMEOUT)?;
+        serde_json::from_slice(&body).with_context(|| format!("Invalid release metadata from {}", self.release_url))
+    }
+
+    /// Downloads the release's build for this platform and replaces `executable` with it
+    ///
+    /// The download must match the `<asset>.sha256` file published next to it; a release
+    /// without one is refused, and nothing is extracted or replaced before the check passes.
+    pub fn install(&self, release: &Release, executable: &Path) -> Result<()> {
+        let target = current_target().context("No prebuilt binaries are published for this platform")?;
+        let asset = release
+            .asset_for(target)
+            .with_context(|| format!("Release {} has no {}", release.tag_name, archive_name(target)))?;
+        let checksum = release.checksum_for(asset).with_context(|| {
+            format!(
+                "Release {} publishes no {}.sha256, so {} cannot be verified; refusing to install it",
+                release.tag_name, asset.name, asset.name
+            )
+        })?;
+        let published = fetch(&checksum.browser_download_url, CONNECT_TIMEOUT, METADATA_TIMEOUT)?;
+        let download = fetch(&asset.browser_download_url, CONNECT_TIMEOUT, DOWNLOAD_TIMEOUT)?;
+        verify_checksum(&asset.name, &download, &published)?;
+
+        let binary = extract_binary(&asset.name, &download)?;
+        replace_executable(executable, &binary)
+    }
+
+    /// The newer version to announce, checking GitHub at most once per [`NOTICE_INTERVAL_SECS`]
+    ///
+    /// `state_file` remembers the last check. Failing to reach GitHub only postpones the
+    /// next check, it never fails the run.
+    pub fn newer_version(&self, current: &str, state_file: &Path, now: u64) -> Option<String> {
+        let mut check: UpdateCheck = fs::read_to_string(state_file)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        if now.saturating_sub(check.checked_at) >= NOTICE_INTERVAL_SECS {
+            check = UpdateCheck {
+                checked_at: now,
+                latest_version: self.latest_release().ok().map(|release| release.version().to_string()),
+            };
+            if let Some(parent) = state_file.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if let Ok(json) = serde_json::to_string_pretty(&check) {
+                let _ = fs::write(state_file, json);
+            }
+        }
+
+        check.latest_version.filter(|latest| is_newer(latest, current))
+    }
+}
+
+impl Default for SelfUpdater {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Target triple of the running binary, as release assets are named
+pub fn current_target() -> Option<&'static str> {
+    match (std::env::consts::ARCH, std::env::consts::OS) {
+        ("x86_64", "linux") => Some("x86_64-unknown-linux-gnu"),
+        ("aarch64", "linux") => Some("aarch64-unknown-linux-gnu"),
+        ("x86_64", "macos") => Some("x86_64-apple-darwin"),
+        ("aarch64", "macos") => Some("aarch64-apple-darwin"),
+        ("x86_64", "windows") => Some("x86_64-pc-windows-msvc"),
+        _ => None,
+    }
+}
+
+/// Release archive built for `target`, as the release workflow names it:
+/// `kotlin-multiplatform-coverage-<target>.zip` on Windows, `.tar.gz` elsewhere
+pub fn archive_name(target: &str) -> String {
+    let extension = if target.contains("windows") { "zip" } else { "tar.gz" };
+    format!("{}-{}.{}", BINARY_NAME, target, extension)
+}
+
+/// Checks a download against the contents of its `.sha256` file (`<hex digest>  <name>`)
+fn verify_checksum(asset_name: &str, download: &[u8], published: &[u8]) -> Result<()> {
+    let expected = String::from_utf8_lossy(published)
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let actual = hex::encode(Sha256::digest(download));
+    if expected != actual {
+        bail!("Checksum mismatch for {}: expected {}, got {}", asset_name, expected, actual);
+    }
+    Ok(())
+}
+
+/// Whether `latest` is a later `major.minor.patch` than `current`; pre-release and
+/// build suffixes are ignored, and unparsable versions are never newer
+fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim_start_matches('v').split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(str::parse::<u64>);
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    Some((major, minor, patch))
+}
+
+/// Fetches `url`, giving up once `connect` or `read` runs out
+fn fetch(url: &str, connect: Duration, read: Duration) -> Result<Vec<u8>> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_connect(Some(connect))
+        .timeout_recv_response(Some(read))
+        .timeout_recv_body(Some(read))
+        .build()
+        .into();
+    agent
+        .get(url)
+        .header("Accept", "application/vnd.github+json")
+        .call()
+        .and_then(|mut response| response.body_mut().with_config().limit(MAX_DOWNLOAD_BYTES).read_to_vec())
+        .with_context(|| format!("Failed to download {}", url))
+}
+
+/// The executable inside a `.tar.gz` or `.zip` asset, or the asset itself otherwise
+fn extract_binary(asset_name: &str, download: &[u8]) -> Result<Vec<u8>> {
+    let binary = if cfg!(windows) {
+        format!("{}.exe", BINARY_NAME)
+    } else {
+        BINARY_NAME.to_string()
+    };
+    let is_binary = |path: &Path| path.file_name().is_some_and(|name| name == binary.as_str());
+
+    if asset_name.ends_with(".tar.gz") || asset_name.ends_with(".tgz") {
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(download));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if entry.header().entry_type().is_file() && is_binary(&entry.path()?) {
+                let mut bytes = Vec::new();
+                entry.read_to_end(&mut bytes)?;
+                return Ok(bytes);
+            }
+        }
+    } else if asset_name.ends_with(".zip") {
+        let mut archive = zip::ZipArchive::new(Cursor::new(download))?;
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index)?;
+            if entry.is_file() && entry.enclosed_name().is_some_and(|path| is_binary(&path)) {
+                let mut bytes = Vec::new();
+                entry.read_to_end(&mut bytes)?;
+                return Ok(bytes);
+            }
+        }
+    } else {
+        return Ok(download.to_vec());
+    }
+    bail!("{} does not contain {}", asset_name, binary)
+}
+
+/// Swaps `binary` in for `executable`, restoring the old one when the swap fails
+///
+/// The running executable is renamed rather than overwritten, which Windows allows.
+fn replace_executable(executable: &Path, binary: &[u8]) -> Result<()> {
+    let staged = sibling(executable, "new");
+    fs::write(&staged, binary).with_context(|| format!("Failed to write {}", staged.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+
+    let previous = sibling(executable, "old");
+    fs::rename(executable, &previous).with_context(|| format!("Failed to replace {}", executable.display()))?;
+    if let Err(error) = fs::rename(&staged, executable) {
+        fs::rename(&previous, executable)?;
+        return Err(error).with_context(|| format!("Failed to replace {}", executable.display()));
+    }
+    // Windows keeps the running executable locked; it is left behind there
+    let _ = fs::remove_file(&previous);
+    Ok(())
+}
+
+/// `<executable>.<suffix>` next to the executable
+fn sibling(executable: &Path, suffix: &str) -> PathBuf {
+    let mut name = executable.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    executable.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> ReleaseAsset {
+        ReleaseAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{}", name),
+        }
+    }
+
+    #[test]
+    fn test_release_versions_and_assets() {
+        let release = Release {
+            tag_name: "v0.3.0".to_string(),
+            html_url: String::new(),
+            assets: vec![
+                asset("kotlin-multiplatform-coverage-x86_64-unknown-linux-gnu.tar.gz.sig"),
+                asset("kotlin-multiplatform-coverage-x86_64-unknown-linux-gnu.tar.gz.sha256"),
+                asset("kotlin-multiplatform-coverage-x86_64-unknown-linux-gnu.tar.gz"),
+                asset("kotlin-multiplatform-coverage-aarch64-apple-darwin.tar.gz"),
+                asset("kotlin-multiplatform-coverage-x86_64-pc-windows-msvc.txt"),
+            ],
+        };
+
+        assert_eq!(release.version(), "0.3.0");
+        assert!(release.is_newer_than("0.2.9"));
+        assert!(!release.is_newer_than("0.3.0"));
+        assert!(!release.is_newer_than("1.0.0-beta.1"));
+        assert!(!is_newer("nightly", "0.1.0"));
+
+        let linux = release.asset_for("x86_64-unknown-linux-gnu").unwrap();
+        assert_eq!(linux.name, "kotlin-multiplatform-coverage-x86_64-unknown-linux-gnu.tar.gz");
+        assert!(release.checksum_for(linux).is_some());
+        let macos = release.asset_for("aarch64-apple-darwin").unwrap();
+        assert!(release.checksum_for(macos).is_none());
+        assert!(release.asset_for("x86_64-pc-windows-msvc").is_none());
+    }
+
+    #[test]
+    fn test_install_requires_checksum() {
+        let release = Release {
+            tag_name: "v0.3.0".to_string(),
+            html_url: String::new(),
+            assets: current_target().map(|target| asset(&archive_name(target))).into_iter().collect(),
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let executable = dir.path().join(BINARY_NAME);
+        fs::write(&executable, "old build").unwrap();
+
+        let error = SelfUpdater::new().install(&release, &executable).unwrap_err();
+        if current_target().is_some() {
+            assert!(error.to_string().contains(".sha256"), "{}", error);
+        }
+        assert_eq!(fs::read_to_string(&executable).unwrap(), "old build");
+
+        let digest = hex::encode(Sha256::digest(b"new build"));
+        verify_checksum("kmp.tar.gz", b"new build", format!("{}  kmp.tar.gz\n", digest).as_bytes()).unwrap();
+        assert!(verify_checksum("kmp.tar.gz", b"tampered", digest.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_install_from_tar_gz_replaces_executable() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+        for (path, content) in [("dist/README.md", "docs"), (BINARY_NAME, "new build")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, path, content.as_bytes()).unwrap();
+        }
+        let download = builder.into_inner().unwrap().finish().unwrap();
+
+        if cfg!(not(windows)) {
+            assert_eq!(extract_binary("kmp.tar.gz", &download).unwrap(), b"new build");
+        }
+        assert!(extract_binary("kmp.zip", &download).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let executable = dir.path().join(BINARY_NAME);
+        fs::write(&executable, "old build").unwrap();
+        replace_executable(&executable, b"new build").unwrap();
+        assert_eq!(fs::read_to_string(&executable).unwrap(), "new build");
+        assert!(!sibling(&executable, "old").exists());
+        assert!(!sibling(&executable, "new").exists());
+    }
+
+    #[test]
+    fn test_newer_version_reuses_recent_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_file = dir.path().join("update-check.json");
+        let check = UpdateCheck {
+            checked_at: 1_000,
+            latest_version: Some("0.4.0".to_string()),
+        };
+        fs::write(&state_file, serde_json::to_string(&check).unwrap()).unwrap();
+
+        // Within the interval the stored result is used, so nothing is fetched
+        let updater = SelfUpdater::new().with_release_url("http://127.0.0.1:9/unreachable");
+        assert_eq!(updater.newer_version("0.3.1", &state_file, 2_000), Some("0.4.0".to_string()));
+        assert_eq!(updater.newer_version("0.4.0", &state_file, 2_000), None);
+
+        // Once it is stale, a failed check is recorded and announces nothing
+        assert_eq!(updater.newer_version("0.3.1", &state_file, 1_000 + NOTICE_INTERVAL_SECS), None);
+        let stored: UpdateCheck = serde_json::from_str(&fs::read_to_string(&state_file).unwrap()).unwrap();
+        assert_eq!(stored.checked_at, 1_000 + NOTICE_INTERVAL_SECS);
+        assert_eq!(stored.latest_version, None);
+    }
+
+    #[test]
+    fn test_fetch_times_out() {
+        // A server that accepts but never answers runs into the read timeout
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/releases/latest", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            std::thread::sleep(Duration::from_secs(5));
+            drop(stream);
+        });
+
+        let started = std::time::Instant::now();
+        let error = fetch(&url, Duration::from_secs(1), Duration::from_millis(200)).unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(4));
+        assert!(format!("{:#}", error).contains("timeout"), "{:#}", error);
+    }
+}
@@ -0,0 +1,152 @@
+//! Interactive suppression updates
+//! Walks the (symbol, file) pairs an analysis still counts, asks which are false
+//! positives, and returns the new baseline, like updating a lint baseline
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+
+use crate::domain::{relative_file, ImpactAnalysis, Suppression, SuppressionBaseline, SymbolUsage};
+
+/// Asks about each counted (symbol, file) pair on a terminal or any other reader and writer
+pub struct SuppressionPrompt<'a> {
+    input: &'a mut dyn BufRead,
+    output: &'a mut dyn Write,
+}
+
+impl<'a> SuppressionPrompt<'a> {
+    pub fn new(input: &'a mut dyn BufRead, output: &'a mut dyn Write) -> Self {
+        Self { input, output }
+    }
+
+    /// The baseline without its stale entries, plus the pairs the user chose to suppress
+    ///
+    /// `analysis` must have been run with `baseline` applied, so already suppressed
+    /// pairs are not asked about again. Answer `y`, optionally followed by a reason,
+    /// to suppress a pair; `q` or end of input keeps the answers given so far.
+    pub fn update(
+        &mut self,
+        baseline: &SuppressionBaseline,
+        analysis: &ImpactAnalysis,
+        project_path: &str,
+    ) -> Result<SuppressionBaseline> {
+        let mut suppressions: Vec<Suppression> = baseline
+            .suppressions
+            .iter()
+            .filter(|s| !analysis.stale_suppressions.contains(s))
+            .cloned()
+            .collect();
+        if !analysis.stale_suppressions.is_empty() {
+            writeln!(
+                self.output,
+                "Removing {} stale suppression(s) that no longer match any usage",
+                analysis.stale_suppressions.len()
+            )?;
+        }
+
+        let mut pairs: BTreeMap<(&str, String), Vec<&SymbolUsage>> = BTreeMap::new();
+        for usage in analysis.symbol_usages.values().flatten() {
+            pairs
+                .entry((usage.symbol_name.as_str(), relative_file(project_path, &usage.file_path)))
+                .or_default()
+                .push(usage);
+        }
+
+        let total = pairs.len();
+        for (index, ((symbol, file), usages)) in pairs.into_iter().enumerate() {
+            let first = usages.iter().min_by_key(|u| u.line_number).copied();
+            writeln!(self.output, "[{}/{}] `{}` in {}: {} usage(s)", index + 1, total, symbol, file, usages.len())?;
+            if let Some(first) = first.filter(|u| !u.context.is_empty()) {
+                writeln!(self.output, "    {}: {}", first.line_number, first.context)?;
+            }
+            write!(self.output, "Suppress? [y [reason]/N/q] ")?;
+            self.output.flush()?;
+
+            let mut answer = String::new();
+            if self.input.read_line(&mut answer)? == 0 {
+                break;
+            }
+            let answer = answer.trim();
+            let (choice, reason) = answer.split_once(char::is_whitespace).unwrap_or((answer, ""));
+            match choice.to_lowercase().as_str() {
+                "y" | "yes" => suppressions.push(Suppression {
+                    symbol: symbol.to_string(),
+                    file,
+                    reason: Some(reason.trim().to_string()).filter(|r| !r.is_empty()),
+                }),
+                "q" | "quit" => break,
+                _ => {}
+            }
+        }
+
+        suppressions.sort();
+        suppressions.dedup();
+        Ok(SuppressionBaseline { suppressions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::UsageKind;
+    use std::collections::HashMap;
+    use std::io::Cursor;
+
+    fn usage(symbol: &str, file: &str, line: usize) -> SymbolUsage {
+        SymbolUsage {
+            symbol_name: symbol.to_string(),
+            file_path: file.to_string(),
+            line_number: line,
+            context: format!("val x = {}()", symbol),
+            kind: UsageKind::Instantiation,
+        }
+    }
+
+    #[test]
+    fn test_update_applies_answers_and_drops_stale_entries() {
+        let stale = Suppression {
+            symbol: "Gone".to_string(),
+            file: "app/Old.kt".to_string(),
+            reason: None,
+        };
+        let kept = Suppression {
+            symbol: "User".to_string(),
+            file: "app/Generated.kt".to_string(),
+            reason: Some("generated".to_string()),
+        };
+        let baseline = SuppressionBaseline {
+            suppressions: vec![stale.clone(), kept.clone()],
+        };
+
+        let mut usages = HashMap::new();
+        usages.insert("Logger".to_string(), vec![usage("Logger", "/repo/app/Main.kt", 4)]);
+        usages.insert(
+            "User".to_string(),
+            vec![usage("User", "/repo/app/Main.kt", 9), usage("User", "/repo/app/Main.kt", 3)],
+        );
+        usages.insert("Session".to_string(), vec![usage("Session", "/repo/app/Session.kt", 1)]);
+        let analysis = ImpactAnalysis {
+            symbol_usages: usages,
+            stale_suppressions: vec![stale],
+            ..Default::default()
+        };
+
+        // Pairs are asked in (symbol, file) order: Logger, Session, User
+        let mut input = Cursor::new("y false positive\nn\n");
+        let mut output = Vec::new();
+        let updated = SuppressionPrompt::new(&mut input, &mut output)
+            .update(&baseline, &analysis, "/repo")
+            .unwrap();
+
+        let logger = Suppression {
+            symbol: "Logger".to_string(),
+            file: "app/Main.kt".to_string(),
+            reason: Some("false positive".to_string()),
+        };
+        assert_eq!(updated.suppressions, vec![logger, kept]);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Removing 1 stale suppression(s)"));
+        assert!(output.contains("[1/3] `Logger` in app/Main.kt: 1 usage(s)"));
+        assert!(output.contains("[3/3] `User` in app/Main.kt: 2 usage(s)\n    3: val x = User()"));
+    }
+}
@@ -0,0 +1,59 @@
+//! Browser entry points
+//! JS API of the wasm32 build: analyzes a project held in memory and returns
+//! the JSON report, so a web demo can run the analysis client-side
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use wasm_bindgen::prelude::*;
+
+use crate::adapters::archive::{self, ArchiveFormat};
+use crate::adapters::MemoryFileSystem;
+use crate::infrastructure::{Analyzer, Config};
+
+/// Project root inside the in-memory file tree, as for source archives
+const ROOT: &str = ".";
+
+/// Analyzes a project given as a JSON object mapping relative paths to file contents
+///
+/// A `kmp-coverage.toml` among the files is applied like on the command line.
+#[wasm_bindgen(js_name = analyzeFiles)]
+pub fn analyze_files(files: &str) -> Result<String, JsError> {
+    let result = serde_json::from_str::<BTreeMap<String, String>>(files)
+        .context("Expected a JSON object of file paths to contents")
+        .and_then(|files| {
+            let mut fs = MemoryFileSystem::new();
+            for (path, content) in files {
+                fs.insert(path.trim_start_matches('/'), content);
+            }
+            analyze(fs)
+        });
+    result.map_err(to_js_error)
+}
+
+/// Analyzes a `.zip`, `.tar.gz`, or `.tar` snapshot; `name` is its file name
+#[wasm_bindgen(js_name = analyzeArchive)]
+pub fn analyze_archive(name: &str, bytes: &[u8]) -> Result<String, JsError> {
+    let format = ArchiveFormat::detect(Path::new(name))
+        .ok_or_else(|| JsError::new(&format!("Unsupported archive format: {}", name)))?;
+    archive::load_archive_bytes(bytes, format)
+        .and_then(analyze)
+        .map_err(to_js_error)
+}
+
+/// Runs the analysis and serializes it as the `--format json` report
+///
+/// Symbol manifests from `[federation]` are not loaded: the browser has no
+/// file system outside the project and no blocking HTTP client.
+fn analyze(fs: MemoryFileSystem) -> Result<String> {
+    let config = Config::discover(&fs, Path::new(ROOT))?;
+    let analysis =
+        Analyzer::with_file_system(Arc::new(fs), config.analysis_options(), Vec::new())
+            .analyze(ROOT)?;
+    Ok(serde_json::to_string_pretty(&analysis)?)
+}
+
+fn to_js_error(error: anyhow::Error) -> JsError {
+    JsError::new(&format!("{:#}", error))
+}
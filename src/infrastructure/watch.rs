@@ -0,0 +1,105 @@
+//! Watch mode
+//! Notices when source files of the project are added, removed, or modified, so
+//! `--watch` can re-run the analysis and editors pick up the new usages live
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::adapters::file_system::{FileStamp, FileSystem};
+
+/// Printed before each run's output, for the `beginsPattern` of background problem matchers
+pub const RUN_STARTED: &str = "kmp-coverage: analysis started";
+
+/// Printed after each run's output, for the `endsPattern` of background problem matchers
+pub const RUN_FINISHED: &str = "kmp-coverage: analysis finished";
+
+/// Extensions of the files whose changes trigger a new run: sources, build scripts,
+/// and Xcode projects, but not reports written into the project
+const WATCHED_EXTENSIONS: &[&str] = &[
+    "kt", "kts", "java", "swift", "m", "mm", "h", "js", "jsx", "ts", "tsx", "gradle", "toml", "pbxproj",
+];
+
+/// Polls the project's files for changes
+pub struct ProjectWatcher {
+    fs: Arc<dyn FileSystem>,
+    root: PathBuf,
+    stamps: BTreeMap<PathBuf, FileStamp>,
+}
+
+impl ProjectWatcher {
+    /// Starts watching the files below `root`, as they are now
+    pub fn new(fs: Arc<dyn FileSystem>, root: &Path) -> Self {
+        let mut watcher = Self {
+            fs,
+            root: root.to_path_buf(),
+            stamps: BTreeMap::new(),
+        };
+        watcher.stamps = watcher.snapshot();
+        watcher
+    }
+
+    /// Whether a watched file was added, removed, or modified since the previous check
+    pub fn changed(&mut self) -> bool {
+        let stamps = self.snapshot();
+        let changed = stamps != self.stamps;
+        self.stamps = stamps;
+        changed
+    }
+
+    /// Blocks until a watched file changes, checking every `interval`
+    pub fn wait_for_change(&mut self, interval: Duration) {
+        while !self.changed() {
+            thread::sleep(interval);
+        }
+    }
+
+    fn snapshot(&self) -> BTreeMap<PathBuf, FileStamp> {
+        self.fs
+            .walk(&self.root, usize::MAX)
+            .into_iter()
+            .filter(|entry| !entry.is_dir)
+            .filter(|entry| {
+                entry
+                    .path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| WATCHED_EXTENSIONS.contains(&ext))
+            })
+            .filter_map(|entry| {
+                let stamp = self.fs.stamp(&entry.path).ok()?;
+                Some((entry.path, stamp))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detects_source_changes() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("Main.kt"), "fun main() {}\n").unwrap();
+        let mut watcher = ProjectWatcher::new(Arc::new(crate::adapters::OsFileSystem), root);
+        assert!(!watcher.changed());
+
+        // Reports written into the project do not trigger a run
+        std::fs::write(root.join("report.json"), "{}").unwrap();
+        assert!(!watcher.changed());
+
+        std::fs::write(root.join("Main.kt"), "fun main() {\n    println()\n}\n").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        std::fs::write(root.join("View.swift"), "import Shared\n").unwrap();
+        assert!(watcher.changed());
+        std::fs::remove_file(root.join("View.swift")).unwrap();
+        assert!(watcher.changed());
+    }
+}
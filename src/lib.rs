@@ -1,6 +1,6 @@
-/// Kotlin Multiplatform Coverage - Library Interface
-///
-/// This module exposes the library's public API for use in tests and as a library.
+//! Kotlin Multiplatform Coverage - Library Interface
+//!
+//! This module exposes the library's public API for use in tests and as a library.
 
 // Re-export public modules for library usage
 pub mod domain;
@@ -9,10 +9,11 @@ pub mod adapters;
 pub mod infrastructure;
 pub mod utils;
 pub mod analyzer;
+pub mod parser;
 
 // Re-export commonly used types for convenience
 pub use domain::{
-    DependencyRepository, ImpactAnalysis, Platform, PlatformImpact,
+    AnalysisHooks, DependencyRepository, ImpactAnalysis, Platform, PlatformImpact,
     SourceFile, SourceFileRepository, Symbol, SymbolRepository,
-    SymbolType, SymbolUsage, SymbolUsageRepository,
+    SymbolFilter, SymbolType, SymbolUsage, SymbolUsageRepository, UsageFilter,
 };
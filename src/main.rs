@@ -1,25 +1,44 @@
-use anyhow::Result;
-use clap::Parser;
-use log::info;
+use anyhow::{Context, Result};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use log::{info, warn};
 
-// Clean Architecture Layers
-mod domain;
-mod use_cases;
-mod adapters;
-mod infrastructure;
+use kotlin_multiplatform_coverage::adapters::archive::{self, ArchiveFormat};
+use kotlin_multiplatform_coverage::adapters::symbol_manifest::SymbolManifest;
+use kotlin_multiplatform_coverage::adapters::{
+    FileSystem, IgnoringFileSystem, OsFileSystem, RunHistoryRepositoryImpl, RunStatsRepositoryImpl,
+    SourceFileRepositoryImpl, SuppressionRepositoryImpl, SymbolHistoryRepositoryImpl, SymbolRepositoryImpl,
+};
+use kotlin_multiplatform_coverage::infrastructure::exit_status;
+use kotlin_multiplatform_coverage::infrastructure::project_path;
+use kotlin_multiplatform_coverage::infrastructure::provenance::SIGNING_KEY_ENV;
+use kotlin_multiplatform_coverage::infrastructure::redaction::REDACTION_SALT_ENV;
+use kotlin_multiplatform_coverage::infrastructure::reporters::source_viewer::DEFAULT_MAX_SOURCE_BYTES;
+use kotlin_multiplatform_coverage::infrastructure::reporters::ReportSection;
+use kotlin_multiplatform_coverage::infrastructure::{
+    Analyzer, BatchRunner, BatchSource, Capabilities, Config, DiffViewer, ProjectWatcher, Provenance, Redactor,
+    Reporter, RunFailure, SelfUpdater, SuppressionPrompt,
+};
+use kotlin_multiplatform_coverage::infrastructure::watch::{RUN_FINISHED, RUN_STARTED};
+use kotlin_multiplatform_coverage::utils::GitUtils;
+use kotlin_multiplatform_coverage::domain::{AnalysisScope, ApiChurn, DetectionSettings, DiffScope, ImpactAnalysis, ImpactMetric, ImpactThreshold, ReportDiff, RunSample, Severity, SourceFileRepository, SuppressionRepository, SymbolParser, ThresholdKind, WarmStartState};
+use kotlin_multiplatform_coverage::use_cases::{
+    AnalysisOptions, ExtractSymbolsUseCase, LintDriftUseCase, RecordRunStatsUseCase, TrackHistoryUseCase,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-// Supporting utilities (cross-cutting concerns)
-mod utils;
+/// Report format used when neither `--format` nor `[report] format` names one
+const DEFAULT_FORMAT: &str = "table";
 
-// Legacy modules (to be phased out)
-mod analyzer;
+/// Report format of `--watch` runs unless `--format` names one
+const WATCH_FORMAT: &str = "problems";
 
-use adapters::{
-    DependencyRepositoryImpl, SourceFileRepositoryImpl, SymbolRepositoryImpl,
-    SymbolUsageRepositoryImpl,
-};
-use infrastructure::Reporter;
-use use_cases::AnalyzeImpactUseCase;
+/// How often `--watch` checks the project's files for changes
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
 
 /// Kotlin Multiplatform Coverage Analyzer
 ///
@@ -27,31 +46,247 @@ use use_cases::AnalyzeImpactUseCase;
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Project path to analyze
-    #[arg(short, long, default_value = ".")]
+    #[arg(short, long, default_value = ".", global = true)]
     path: String,
 
-    /// Output format (json, table, markdown)
-    #[arg(short, long, default_value = "table")]
-    format: String,
+    /// Output format (json, table, markdown, github-checks, html, mermaid, csv, review-pack; default: `[report] format`, else table)
+    #[arg(short, long, global = true)]
+    format: Option<String>,
 
     /// Enable verbose logging
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     verbose: bool,
 
+    /// Print only the one-line summary of an analysis (nothing for other reports) and errors, for scripts
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
     /// Output file path to save results
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     output: Option<String>,
+
+    /// Config file path (default: kmp-coverage.toml in the project root)
+    #[arg(short, long, global = true)]
+    config: Option<String>,
+
+    /// Base URL of the generated API docs (e.g. a Dokka site) used to link symbols in reports
+    #[arg(long)]
+    doc_base_url: Option<String>,
+
+    /// Report sections to show after the summary, e.g. `platforms,top-symbols` or `no-diagnostics`
+    #[arg(long, value_name = "SECTION", value_delimiter = ',')]
+    sections: Vec<String>,
+
+    /// Parser of shared Kotlin declarations: regex, or treesitter in builds with the `treesitter` feature (default: `[analysis] parser`, else regex)
+    #[arg(long, value_name = "PARSER", global = true, value_parser = parse_symbol_parser)]
+    parser: Option<SymbolParser>,
+
+    /// What impact coverage counts: files, lines, or functions (default: `[analysis] metric`, else lines)
+    #[arg(long, value_name = "METRIC", value_parser = parse_impact_metric)]
+    metric: Option<ImpactMetric>,
+
+    /// Locale of the percentages and counts in table and Markdown reports, e.g. `de-DE` (default: `[report] locale`)
+    #[arg(long, global = true)]
+    locale: Option<String>,
+
+    /// Decimal places of the percentages in reports, 0 to 6 (default: `[report] precision`, else 2)
+    #[arg(long, value_name = "DECIMALS", global = true)]
+    precision: Option<u32>,
+
+    /// Embed highlighted source excerpts of the usage sites in the HTML report
+    #[arg(long)]
+    embed_sources: bool,
+
+    /// Hash file paths and strip code context from the report, keeping the aggregate numbers
+    #[arg(long, conflicts_with = "embed_sources")]
+    redact: bool,
+
+    /// Symbol manifest (path or URL) of shared code living in another repository; repeatable
+    #[arg(long = "symbols-from", value_name = "PATH_OR_URL")]
+    symbols_from: Vec<String>,
+
+    /// Kotlin/Native framework header (e.g. `Shared.framework/Headers/Shared.h`) to read the exact
+    /// Objective-C and Swift names of shared classes from, relative to the project path; repeatable
+    #[arg(long = "objc-header", value_name = "PATH")]
+    objc_headers: Vec<String>,
+
+    /// Read the framework headers Kotlin/Native wrote to the shared modules' build output
+    #[arg(long)]
+    find_objc_headers: bool,
+
+    /// Attribute usages of `actual typealias` targets (e.g. AndroidFoo) to the shared alias
+    #[arg(long)]
+    resolve_typealiases: bool,
+
+    /// Count sample, demo, and benchmark app modules as app code
+    #[arg(long)]
+    include_samples: bool,
+
+    /// Count import statements of shared symbols, and files that only import from affected files, as impact
+    #[arg(long)]
+    count_imports: bool,
+
+    /// Ask which counted usages are false positives, write them to the suppression baseline, then exit
+    #[arg(long, conflicts_with = "redact")]
+    update_suppressions: bool,
+
+    /// Treat symbols declared in test source sets (commonTest, iosTest, ...) as shared API
+    #[arg(long, global = true)]
+    include_test_symbols: bool,
+
+    /// Treat `internal` declarations as shared API, e.g. when app source sets live in the shared module
+    #[arg(long, global = true)]
+    include_internal: bool,
+
+    /// Directory levels below the project path searched for build files and Xcode projects (default: 5)
+    #[arg(long, value_name = "LEVELS", global = true)]
+    detection_depth: Option<usize>,
+
+    /// Directory (relative to the project path) also searched for nested projects; repeatable
+    #[arg(long = "project-root-hint", value_name = "DIR", global = true)]
+    project_root_hints: Vec<String>,
+
+    /// Glob (relative to the project path) of the only files to scan, e.g. `shared/**`; repeatable
+    #[arg(long = "include", value_name = "GLOB", global = true)]
+    includes: Vec<String>,
+
+    /// Glob (relative to the project path) of files never to scan, e.g. `**/build/**` or `*Test.kt`; repeatable
+    #[arg(long = "exclude", value_name = "GLOB", global = true)]
+    excludes: Vec<String>,
+
+    /// Also detect projects from Bazel and Buck BUILD targets, scanning the files their `srcs` match
+    #[arg(long, global = true)]
+    build_targets: bool,
+
+    /// Also scan what .gitignore files ignore and build output directories (build/, .gradle/, DerivedData/, Pods/)
+    #[arg(long, global = true)]
+    no_ignore: bool,
+
+    /// Android product flavor or iOS scheme to report impact for separately; repeatable
+    #[arg(long = "variant", value_name = "NAME")]
+    variants: Vec<String>,
+
+    /// Directory (relative to the project path) to restrict symbol extraction and app scanning to; repeatable
+    #[arg(long = "scope", value_name = "DIR", global = true)]
+    scopes: Vec<String>,
+
+    /// JSON report of a previous run to reuse the results of unchanged files from; this run's
+    /// report then records its own for the next (a missing file starts cold)
+    #[arg(long, value_name = "REPORT", conflicts_with = "redact")]
+    resume: Option<PathBuf>,
+
+    /// Analyze only the shared symbols changed since a commit, branch, or tag, and report the app files they impact
+    #[arg(long, value_name = "BASE_REF")]
+    diff: Option<String>,
+
+    /// Minimum impact coverage in percent, overall (`40`) or per platform (`ios=25`); repeatable
+    #[arg(long = "min-impact", value_name = "[PLATFORM=]PERCENT", value_parser = parse_min_impact)]
+    min_impact: Vec<ImpactThreshold>,
+
+    /// Maximum impact coverage in percent, overall (`80`) or per platform (`android=60`); repeatable
+    #[arg(long = "max-impact", value_name = "[PLATFORM=]PERCENT", value_parser = parse_max_impact)]
+    max_impact: Vec<ImpactThreshold>,
+
+    /// Exit with an error if app code uses any deprecated shared API
+    #[arg(long)]
+    fail_on_deprecated_usage: bool,
+
+    /// Exit with an error if any coverage goal is missed, not only enforced ones
+    #[arg(long)]
+    fail_on_goal_miss: bool,
+
+    /// Re-run the analysis whenever a source file changes, reporting usages as compiler-style
+    /// `path:line:col: note:` lines for editor problem matchers (default format: problems)
+    #[arg(long, conflicts_with_all = ["update_suppressions", "output"])]
+    watch: bool,
+
+    /// Print version, supported platforms, formats, and features as JSON, then exit
+    #[arg(long)]
+    version_json: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Extract shared symbols only and write a portable symbol manifest (JSON)
+    ExportSymbols {
+        /// Source name recorded in the manifest (default: project directory name)
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// List the detected projects with the strategy and confidence of each detection (table, json, markdown)
+    Projects,
+    /// Analyze every repository of a list with shared config and report a repository × platform matrix
+    /// (table, json, markdown, csv)
+    Batch {
+        /// Repository list: one local path (relative to the list) or clone URL per line, optionally followed by a name
+        list: PathBuf,
+        /// Directory remote repositories are cloned into (default: .kmp-coverage/batch next to the list)
+        #[arg(long)]
+        clone_dir: Option<PathBuf>,
+    },
+    /// Compare two JSON reports: headline, platform, symbol usage, and per-file changes
+    /// (table, json, markdown)
+    Diff {
+        /// Earlier report, written with `-f json`
+        before: PathBuf,
+        /// Later report, written with `-f json`
+        after: PathBuf,
+        /// Browse the changes page by page instead of printing a report
+        #[arg(long)]
+        interactive: bool,
+    },
+    /// Replace this binary with the latest GitHub release built for this platform
+    SelfUpdate {
+        /// Only tell whether a newer release is published
+        #[arg(long)]
+        check: bool,
+    },
+    /// Print a shell completion script, or the man page with `--man`
+    Completions {
+        /// Shell to complete for (bash, zsh, fish, powershell, elvish)
+        #[arg(value_enum, required_unless_present = "man")]
+        shell: Option<Shell>,
+        /// Print the man page (roff) instead
+        #[arg(long, conflicts_with = "shell")]
+        man: bool,
+    },
 }
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {:#}", error);
+            ExitCode::from(exit_status::exit_code(&error))
+        }
+    }
+}
+
+fn run() -> Result<()> {
     let args = Args::parse();
 
+    if args.version_json {
+        println!("{}", serde_json::to_string_pretty(&Capabilities::current())?);
+        return Ok(());
+    }
+
+    if let Some(Command::Completions { shell, man }) = &args.command {
+        return completions(*shell, *man);
+    }
+
     // Initialize logger
     if args.verbose {
         env_logger::Builder::from_default_env()
             .filter_level(log::LevelFilter::Debug)
             .init();
+    } else if args.quiet {
+        env_logger::Builder::from_default_env()
+            .filter_level(log::LevelFilter::Error)
+            .init();
     } else {
         env_logger::Builder::from_default_env()
             .filter_level(log::LevelFilter::Info)
@@ -61,28 +296,604 @@ fn main() -> Result<()> {
     info!("Starting Kotlin Multiplatform Coverage Analyzer (Clean Architecture)");
     info!("Analysis path: {}", args.path);
 
-    // Clean Architecture: Dependency Injection
-    // Create repository implementations (adapters)
-    let symbol_repo = SymbolRepositoryImpl::new();
-    let source_file_repo = SourceFileRepositoryImpl::new();
-    let symbol_usage_repo = SymbolUsageRepositoryImpl::new();
-    let dependency_repo = DependencyRepositoryImpl::new();
+    match &args.command {
+        Some(Command::ExportSymbols { source }) => export_symbols(&args, source.as_deref()),
+        Some(Command::Projects) => list_projects(&args),
+        Some(Command::Batch { list, clone_dir }) => batch(&args, list, clone_dir.as_deref()),
+        Some(Command::Diff { before, after, interactive }) => diff(&args, before, after, *interactive),
+        Some(Command::SelfUpdate { check }) => self_update(*check),
+        Some(Command::Completions { .. }) => unreachable!("completions are printed before logging starts"),
+        None if args.watch => watch(&args),
+        None => analyze(&args),
+    }
+}
+
+/// The project named by `--path`: a directory on disk, or a source archive loaded into memory
+struct Project {
+    fs: Arc<dyn FileSystem>,
+    /// Path to analyze within `fs`
+    root: String,
+    /// Directory on disk that relative manifest and history paths resolve against
+    base_dir: PathBuf,
+    /// Default source name for exported manifests
+    name: String,
+    /// Commit checked out in the project directory, when it is a git work tree
+    commit_sha: Option<String>,
+}
+
+impl Project {
+    /// Opens the project, failing early with a [`ProjectPathError`] when the path does not exist
+    fn open(path: &str) -> Result<Self> {
+        project_path::validate_on_disk(path)?;
+        Self::load(path)
+    }
+
+    /// Fails with a [`ProjectPathError`] when detection finds nothing to analyze
+    fn validate(&self, path: &str, detection: &DetectionSettings) -> Result<()> {
+        let fs = self.traversed(detection);
+        project_path::validate_projects(fs.as_ref(), Path::new(&self.root), path, detection)?;
+        Ok(())
+    }
+
+    /// The project's files without the paths `detection` ignores
+    fn traversed(&self, detection: &DetectionSettings) -> Arc<dyn FileSystem> {
+        IgnoringFileSystem::wrap(self.fs.clone(), detection)
+    }
+
+    fn config(&self, args: &Args) -> Result<Config> {
+        match &args.config {
+            Some(path) => Config::load(Path::new(path)),
+            None => Config::discover(self.fs.as_ref(), Path::new(&self.root)),
+        }
+    }
+
+    fn load(path: &str) -> Result<Self> {
+        let path_ref = Path::new(path);
+        let file_name = |p: &Path| {
+            p.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+
+        match ArchiveFormat::detect(path_ref).filter(|_| path_ref.is_file()) {
+            Some(format) => {
+                info!("Loading source archive: {}", path);
+                let fs = archive::load_archive(path_ref, format)?;
+                Ok(Self {
+                    fs: Arc::new(fs),
+                    root: ".".to_string(),
+                    base_dir: path_ref.parent().unwrap_or(Path::new(".")).to_path_buf(),
+                    name: format.strip_extension(&file_name(path_ref)).to_string(),
+                    commit_sha: None,
+                })
+            }
+            None => Ok(Self {
+                fs: Arc::new(OsFileSystem),
+                root: path.to_string(),
+                base_dir: path_ref.to_path_buf(),
+                name: file_name(&path_ref.canonicalize()?),
+                commit_sha: GitUtils::head_commit(path_ref),
+            }),
+        }
+    }
+}
 
-    // Create use case with injected dependencies
-    let analyze_use_case = AnalyzeImpactUseCase::new(
-        &symbol_repo,
-        &source_file_repo,
-        &symbol_usage_repo,
-        &dependency_repo,
-    );
+/// Runs the full impact analysis and reports it
+fn analyze(args: &Args) -> Result<()> {
+    let started = Instant::now();
+    let project = Project::open(&args.path)?;
+    let config = project.config(args)?;
+    let sections = ReportSection::select(if args.sections.is_empty() { &config.report.sections } else { &args.sections })?;
+    let reporter = Reporter::new(report_format(args, &config))?
+        .with_quiet(args.quiet)
+        .with_number_format(config.number_format(args.locale.as_deref(), args.precision)?)
+        .with_file_system(project.fs.clone());
+    let mut options = analysis_options(args, &config)?;
+    project.validate(&args.path, &options.detection)?;
+    project_path::validate_scope(project.fs.as_ref(), Path::new(&project.root), &options.scope)?;
+    if args.fail_on_goal_miss {
+        for goal in &mut options.goals {
+            goal.enforce = true;
+        }
+    }
+    if let Some(base_ref) = &args.diff {
+        if project.commit_sha.is_none() {
+            anyhow::bail!("--diff needs {} to be in a git work tree with commits", args.path);
+        }
+        options.diff = Some(DiffScope {
+            base_ref: base_ref.clone(),
+            files: GitUtils::changed_lines(Path::new(&project.root), base_ref)?,
+        });
+    }
 
-    // Execute use case
-    let impact_analysis = analyze_use_case.execute(&args.path)?;
+    let suppression_repo = SuppressionRepositoryImpl::new(project.base_dir.join(&config.suppressions.file));
+    options.suppressions = suppression_repo.load()?;
+    let baseline = options.suppressions.clone();
+
+    let manifests = config
+        .federation
+        .sources
+        .iter()
+        .chain(&args.symbols_from)
+        .map(|location| SymbolManifest::load_from(location, &project.base_dir))
+        .collect::<Result<Vec<_>>>()?;
+
+    let thresholds = config.impact_thresholds(&args.min_impact, &args.max_impact)?;
+    let config_hash = Provenance::config_hash(&config, &options, &thresholds)?;
+
+    // Clean Architecture: the analyzer wires repository implementations into the use case
+    let analyzer = Analyzer::with_file_system(project.fs.clone(), options, manifests);
+    let mut impact_analysis = match &args.resume {
+        Some(report) => analyzer.analyze_with_warm_start(&project.root, load_warm_start(report)?)?,
+        None => analyzer.analyze(&project.root)?,
+    };
+
+    // Suppressions: ask which counted usages are false positives, like updating a lint baseline
+    if args.update_suppressions {
+        let stdin = std::io::stdin();
+        let mut input = stdin.lock();
+        let mut output = std::io::stdout();
+        let updated = SuppressionPrompt::new(&mut input, &mut output).update(
+            &baseline,
+            &impact_analysis,
+            &project.root,
+        )?;
+        suppression_repo.save(&updated)?;
+        info!(
+            "Suppression baseline written to {} ({} entries)",
+            project.base_dir.join(&config.suppressions.file).display(),
+            updated.suppressions.len()
+        );
+        return Ok(());
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    // Lint: new shared API still consumed by a single platform
+    // Diff and scoped runs only see part of the shared API, so they leave the symbol and run
+    // histories alone; watch runs are drafts of the same run
+    let full_run = args.diff.is_none() && args.scopes.is_empty() && !args.watch;
+    let drift = &config.lint.single_platform_drift;
+    if drift.enabled && full_run {
+        let history_repo =
+            SymbolHistoryRepositoryImpl::new(project.base_dir.join(&drift.history_file));
+        let diagnostics = LintDriftUseCase::new(&history_repo, config.drift_policy())
+            .execute(&impact_analysis.parity, now)?;
+        impact_analysis.diagnostics.extend(diagnostics);
+    }
+
+    // Provenance: record what produced these numbers; signed below, once the report is final
+    let provenance = Provenance::new().with_signing_key(std::env::var(SIGNING_KEY_ENV).ok().as_deref());
+    let metadata = Provenance::metadata(config_hash, project.commit_sha.clone(), now);
+    provenance.stamp(&mut impact_analysis, metadata);
+
+    // History: keep this run's headline numbers for trend charts
+    let history = if config.history.enabled && full_run {
+        let history_repo = RunHistoryRepositoryImpl::new(project.base_dir.join(&config.history.file));
+        TrackHistoryUseCase::new(&history_repo, config.history.max_runs)
+            .execute(&impact_analysis, now)?
+    } else {
+        Vec::new()
+    };
+    // API change log: what the shared API gained and lost since the previous run
+    if let [.., previous, current] = history.as_slice() {
+        impact_analysis.api_churn = ApiChurn::between(previous, current);
+    }
+
+    // Redaction: share the report without exposing file names or code
+    if args.redact {
+        Redactor::new(&project.root)
+            .with_salt(std::env::var(REDACTION_SALT_ENV).ok().as_deref())
+            .redact(&mut impact_analysis);
+    }
+    // Signature: covers the report exactly as it is emitted
+    provenance.sign(&mut impact_analysis)?;
 
     // Report results (infrastructure layer)
-    let reporter = Reporter::new(&args.format)?;
+    let doc_base_url = args.doc_base_url.as_deref().or(config.report.doc_base_url.as_deref());
+    let reporter = reporter
+        .with_doc_base_url(doc_base_url)
+        .with_project_root(&project.root)
+        .with_history(history)
+        .with_sections(sections);
+    let reporter = if !args.redact && (args.embed_sources || config.report.embed_sources) {
+        let max_bytes = config.report.max_source_bytes.unwrap_or(DEFAULT_MAX_SOURCE_BYTES);
+        reporter.with_source_excerpts(project.fs.clone(), max_bytes)
+    } else {
+        reporter
+    };
     reporter.report_impact_analysis(&impact_analysis, args.output.as_deref())?;
 
+    // Update notice: binaries baked into CI images drift behind, only when opted in
+    if let Some(state_file) = config.update.state_file(&project.base_dir).filter(|_| config.update.notify) {
+        if let Some(latest) = SelfUpdater::new().newer_version(env!("CARGO_PKG_VERSION"), &state_file, now) {
+            warn!(
+                "kotlin-multiplatform-coverage {} is available (installed: {}); run `kotlin-multiplatform-coverage self-update`",
+                latest,
+                env!("CARGO_PKG_VERSION")
+            );
+        }
+    }
+
+    // Stats: anonymous, machine-local run statistics, only when opted in; watch mode
+    // re-runs would inflate the counts, so only standalone runs are recorded
+    let stats_file = config.stats.stats_file(&project.base_dir).filter(|_| config.stats.enabled && !args.watch);
+    if let Some(stats_file) = stats_file {
+        let stats_repo = RunStatsRepositoryImpl::new(stats_file);
+        let sample = RunSample {
+            duration_ms: started.elapsed().as_millis() as u64,
+            app_files: impact_analysis.total_app_files,
+            shared_symbols: impact_analysis.total_symbols,
+            format: report_format(args, &config).to_lowercase(),
+            features: used_features(args, &config),
+        };
+        RecordRunStatsUseCase::new(&stats_repo).execute(&sample, now)?;
+    }
+
+    let violations = impact_analysis.violated_thresholds(&thresholds);
+    if !violations.is_empty() {
+        return Err(RunFailure::ImpactThresholds { violations }.into());
+    }
+
+    let deprecated_usages = impact_analysis.deprecated_usage_count();
+    if args.fail_on_deprecated_usage && deprecated_usages > 0 {
+        return Err(RunFailure::DeprecatedUsage { count: deprecated_usages }.into());
+    }
+
+    let missed_goals = impact_analysis.missed_enforced_goals();
+    if !missed_goals.is_empty() {
+        let names = missed_goals.iter().map(|g| g.name.clone()).collect();
+        return Err(RunFailure::MissedGoals { names }.into());
+    }
+
+    let missed_categories = impact_analysis.missed_enforced_categories();
+    if !missed_categories.is_empty() {
+        let names = missed_categories.iter().map(|c| c.category.clone()).collect();
+        return Err(RunFailure::MissedCategories { names }.into());
+    }
+
+    let errors = impact_analysis
+        .diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        return Err(RunFailure::LintErrors { count: errors }.into());
+    }
+
     info!("Analysis completed");
     Ok(())
 }
+
+/// Re-runs the analysis after every change to the project's source files, until interrupted
+///
+/// Each run's report sits between [`RUN_STARTED`] and [`RUN_FINISHED`] lines, so background
+/// problem matchers know when to replace their markers. A failed run is reported and
+/// watching goes on.
+fn watch(args: &Args) -> Result<()> {
+    let project = Project::open(&args.path)?;
+    let config = project.config(args)?;
+    let detection = detection_settings(args, &config);
+    let mut watcher = ProjectWatcher::new(project.traversed(&detection), Path::new(&project.root));
+    loop {
+        println!("{}", RUN_STARTED);
+        if let Err(error) = analyze(args) {
+            eprintln!("Error: {:#}", error);
+        }
+        println!("{}", RUN_FINISHED);
+        watcher.wait_for_change(WATCH_INTERVAL);
+    }
+}
+
+/// Results recorded in a previous JSON report for `--resume`
+fn load_warm_start(report: &Path) -> Result<WarmStartState> {
+    /// The only part of the report read back
+    #[derive(serde::Deserialize)]
+    struct ResumedReport {
+        #[serde(default)]
+        warm_start: Option<WarmStartState>,
+    }
+
+    if !report.exists() {
+        info!("No report to resume from at {}; starting cold", report.display());
+        return Ok(WarmStartState::default());
+    }
+    let content = fs::read_to_string(report)
+        .with_context(|| format!("Failed to read report {}", report.display()))?;
+    let resumed: ResumedReport = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not a JSON report (written with `-f json`)", report.display()))?;
+    Ok(resumed.warm_start.unwrap_or_else(|| {
+        warn!("{} records no warm start data; starting cold", report.display());
+        WarmStartState::default()
+    }))
+}
+
+/// Names of the optional flags and config sections a run uses, for the run statistics
+fn used_features(args: &Args, config: &Config) -> Vec<String> {
+    let analysis = &config.analysis;
+    [
+        ("resolve-typealiases", args.resolve_typealiases || analysis.resolve_actual_typealiases),
+        ("include-samples", args.include_samples || analysis.include_sample_modules),
+        ("include-test-symbols", args.include_test_symbols || analysis.include_test_symbols),
+        ("include-internal", args.include_internal || analysis.include_internal),
+        ("count-imports", args.count_imports || analysis.count_imports),
+        ("objc-headers", !args.objc_headers.is_empty() || !analysis.objc_headers.is_empty()),
+        ("find-objc-headers", args.find_objc_headers || analysis.find_objc_headers),
+        ("update-suppressions", args.update_suppressions),
+        ("symbols-from", !args.symbols_from.is_empty() || !config.federation.sources.is_empty()),
+        ("scope", !args.scopes.is_empty()),
+        ("diff", args.diff.is_some()),
+        ("resume", args.resume.is_some()),
+        ("variants", !args.variants.is_empty() || !config.analysis.variants.is_empty()),
+        ("ui-layout-paths", !config.analysis.ui_layout_paths.is_empty()),
+        ("project-root-hint", !args.project_root_hints.is_empty() || !config.detection.root_hints.is_empty()),
+        ("file-globs", !detection_settings(args, config).files.is_empty()),
+        ("build-targets", args.build_targets || config.detection.build_targets),
+        ("no-ignore", args.no_ignore || !config.detection.ignore_files || config.detection.ignored_dirs.is_empty()),
+        ("detection-depth", args.detection_depth.is_some() || config.detection.depth != DetectionSettings::default().depth),
+        ("embed-sources", args.embed_sources || config.report.embed_sources),
+        ("parser", args.parser.unwrap_or(analysis.parser) != SymbolParser::Regex),
+        ("metric", args.metric.unwrap_or(analysis.metric) != ImpactMetric::Lines),
+        ("locale", args.locale.is_some() || config.report.locale.is_some()),
+        ("precision", args.precision.is_some() || config.report.precision.is_some()),
+        ("sections", !args.sections.is_empty() || !config.report.sections.is_empty()),
+        ("redact", args.redact),
+        ("goals", !config.goals.is_empty()),
+        ("history", config.history.enabled),
+        ("single-platform-drift", config.lint.single_platform_drift.enabled),
+        ("update-notify", config.update.notify),
+        (
+            "impact-thresholds",
+            !args.min_impact.is_empty()
+                || !args.max_impact.is_empty()
+                || !config.thresholds.min_impact.is_empty()
+                || !config.thresholds.max_impact.is_empty(),
+        ),
+        ("fail-on-deprecated-usage", args.fail_on_deprecated_usage),
+        ("fail-on-goal-miss", args.fail_on_goal_miss),
+    ]
+    .into_iter()
+    .filter(|(_, used)| *used)
+    .map(|(name, _)| name.to_string())
+    .collect()
+}
+
+fn parse_symbol_parser(name: &str) -> Result<SymbolParser, String> {
+    SymbolParser::parse(name)
+}
+
+fn parse_impact_metric(name: &str) -> Result<ImpactMetric, String> {
+    ImpactMetric::parse(name)
+}
+
+fn parse_min_impact(spec: &str) -> Result<ImpactThreshold, String> {
+    ImpactThreshold::parse(ThresholdKind::Min, spec)
+}
+
+fn parse_max_impact(spec: &str) -> Result<ImpactThreshold, String> {
+    ImpactThreshold::parse(ThresholdKind::Max, spec)
+}
+
+/// `--format`, else problem-matcher lines with `--watch`, else `[report] format`, else [`DEFAULT_FORMAT`]
+fn report_format<'a>(args: &'a Args, config: &'a Config) -> &'a str {
+    match args.format.as_deref() {
+        Some(format) => format,
+        None if args.watch => WATCH_FORMAT,
+        None => config.report.format.as_deref().unwrap_or(DEFAULT_FORMAT),
+    }
+}
+
+/// Analysis options of the config file with command line overrides applied
+fn analysis_options(args: &Args, config: &Config) -> Result<AnalysisOptions> {
+    let mut options = config.analysis_options();
+    options.resolve_actual_typealiases |= args.resolve_typealiases;
+    options.include_sample_modules |= args.include_samples;
+    options.include_test_symbols |= args.include_test_symbols;
+    options.include_internal |= args.include_internal;
+    options.count_imports |= args.count_imports;
+    options.objc_headers.paths.extend(args.objc_headers.iter().cloned());
+    options.objc_headers.find_in_build_output |= args.find_objc_headers;
+    options.detection = detection_settings(args, config);
+    options.scope = AnalysisScope::new(args.scopes.clone());
+    options.parser = symbol_parser(args, config)?;
+    options.metric = args.metric.unwrap_or(options.metric);
+    Ok(options)
+}
+
+/// `--parser`, else `[analysis] parser`, when this build has it
+fn symbol_parser(args: &Args, config: &Config) -> Result<SymbolParser> {
+    let parser = args.parser.unwrap_or(config.analysis.parser);
+    if !parser.is_available() {
+        anyhow::bail!(
+            "This build has no {} parser; build with `--features {}` to use it",
+            parser.name(),
+            parser.name()
+        );
+    }
+    Ok(parser)
+}
+
+/// Detection settings of the config file with command line overrides applied
+fn detection_settings(args: &Args, config: &Config) -> DetectionSettings {
+    let mut detection = config.detection_settings();
+    if let Some(depth) = args.detection_depth {
+        detection.depth = depth;
+    }
+    detection.root_hints.extend(args.project_root_hints.iter().cloned());
+    detection.files.include.extend(args.includes.iter().cloned());
+    detection.files.exclude.extend(args.excludes.iter().cloned());
+    detection.build_targets |= args.build_targets;
+    if args.no_ignore {
+        detection.ignored_dirs.clear();
+        detection.ignore_files = false;
+    }
+    for variant in &args.variants {
+        if !detection.variants.contains(variant) {
+            detection.variants.push(variant.clone());
+        }
+    }
+    detection
+}
+
+/// Lists the detected projects, including those with low confidence, without analyzing them
+fn list_projects(args: &Args) -> Result<()> {
+    let project = Project::open(&args.path)?;
+    let config = project.config(args)?;
+    let detection = detection_settings(args, &config);
+    let projects = SourceFileRepositoryImpl::new()
+        .with_file_system(project.traversed(&detection))
+        .with_detection(detection)
+        .find_projects(&project.root)?;
+
+    Reporter::new(report_format(args, &config))?
+        .with_quiet(args.quiet)
+        .report_projects(&projects, args.output.as_deref())
+}
+
+/// Extracts shared symbols and writes them as a symbol manifest
+fn export_symbols(args: &Args, source: Option<&str>) -> Result<()> {
+    let project = Project::open(&args.path)?;
+    let config = project.config(args)?;
+    let detection = detection_settings(args, &config);
+    let scope = AnalysisScope::new(args.scopes.clone());
+    project.validate(&args.path, &detection)?;
+    project_path::validate_scope(project.fs.as_ref(), Path::new(&project.root), &scope)?;
+    let source_file_repo = SourceFileRepositoryImpl::new()
+        .with_file_system(project.traversed(&detection))
+        .with_detection(detection);
+    let symbol_repo = SymbolRepositoryImpl::new()
+        .with_file_system(project.fs.clone())
+        .with_parser(symbol_parser(args, &config)?);
+
+    let mut kmp_files = source_file_repo.find_kmp_files(&project.root)?;
+    kmp_files.retain(|file| scope.contains(&project.root, file));
+    let mut symbols = ExtractSymbolsUseCase::new(&symbol_repo)
+        .with_test_symbols(config.analysis.include_test_symbols || args.include_test_symbols)
+        .with_internal_symbols(config.analysis.include_internal || args.include_internal)
+        .execute(&kmp_files)?;
+
+    // Keep the manifest portable: record file paths relative to the project root
+    for symbol in &mut symbols {
+        if let Ok(relative) = Path::new(&symbol.file_path).strip_prefix(&project.root) {
+            symbol.file_path = relative.to_string_lossy().to_string();
+        }
+    }
+
+    let source = source.map(str::to_string).unwrap_or(project.name);
+    let manifest = SymbolManifest::from_symbols(&source, symbols);
+    let json = serde_json::to_string_pretty(&manifest)?;
+
+    if let Some(output) = &args.output {
+        fs::write(output, json)?;
+        info!("Symbol manifest written to: {}", output);
+    } else {
+        println!("{}", json);
+    }
+
+    Ok(())
+}
+
+/// Analyzes each repository of a list with the shared config and reports the matrix
+///
+/// The config comes from `--config`, or `kmp-coverage.toml` next to the list.
+fn batch(args: &Args, list: &Path, clone_dir: Option<&Path>) -> Result<()> {
+    let content = fs::read_to_string(list)
+        .with_context(|| format!("Failed to read repository list {}", list.display()))?;
+    let sources = BatchSource::parse_list(&content)?;
+    if sources.is_empty() {
+        anyhow::bail!("Repository list {} names no repositories", list.display());
+    }
+    let base_dir = list.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+
+    let config = match &args.config {
+        Some(path) => Config::load(Path::new(path))?,
+        None => Config::discover(&OsFileSystem, base_dir)?,
+    };
+    let options = analysis_options(args, &config)?;
+    let manifests = config
+        .federation
+        .sources
+        .iter()
+        .chain(&args.symbols_from)
+        .map(|location| SymbolManifest::load_from(location, base_dir))
+        .collect::<Result<Vec<_>>>()?;
+
+    let summary = BatchRunner::new(options, manifests, base_dir)
+        .with_clone_dir(clone_dir)
+        .run(&sources);
+    Reporter::new(report_format(args, &config))?
+        .with_quiet(args.quiet)
+        .with_number_format(config.number_format(args.locale.as_deref(), args.precision)?)
+        .report_batch(&summary, args.output.as_deref())?;
+
+    let failures = summary.failures();
+    if !failures.is_empty() {
+        return Err(RunFailure::PartialBatch {
+            failed: failures.len(),
+            total: summary.repositories.len(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Compares two JSON reports, as a report or interactively
+fn diff(args: &Args, before: &Path, after: &Path, interactive: bool) -> Result<()> {
+    let load = |path: &Path| -> Result<ImpactAnalysis> {
+        let content =
+            fs::read_to_string(path).with_context(|| format!("Failed to read report {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("{} is not a JSON report (written with `-f json`)", path.display()))
+    };
+    let diff = ReportDiff::between(&load(before)?, &load(after)?);
+
+    if interactive {
+        let stdin = std::io::stdin();
+        let mut input = stdin.lock();
+        let mut output = std::io::stdout();
+        return DiffViewer::new(&mut input, &mut output).run(&diff);
+    }
+    Reporter::new(args.format.as_deref().unwrap_or(DEFAULT_FORMAT))?
+        .with_quiet(args.quiet)
+        .report_diff(&diff, args.output.as_deref())
+}
+
+/// Installs the latest release over the running binary, or only reports it with `--check`
+fn self_update(check: bool) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let updater = SelfUpdater::new();
+    let release = updater.latest_release()?;
+    if !release.is_newer_than(current) {
+        println!("kotlin-multiplatform-coverage {} is up to date", current);
+        return Ok(());
+    }
+    if check {
+        println!(
+            "kotlin-multiplatform-coverage {} is available (installed: {}): {}",
+            release.version(),
+            current,
+            release.html_url
+        );
+        return Ok(());
+    }
+
+    updater.install(&release, &std::env::current_exe()?)?;
+    println!("Updated kotlin-multiplatform-coverage {} -> {}", current, release.version());
+    Ok(())
+}
+
+/// Prints the completion script for `shell`, or the man page, to stdout
+fn completions(shell: Option<Shell>, man: bool) -> Result<()> {
+    let mut command = Args::command();
+    // Rendered to a buffer first: `clap_complete` panics on write errors such as a closed pipe
+    let mut buffer = Vec::new();
+    match shell {
+        Some(shell) if !man => {
+            let name = command.get_name().to_string();
+            clap_complete::generate(shell, &mut command, name, &mut buffer);
+        }
+        _ => clap_mangen::Man::new(command).render(&mut buffer)?,
+    }
+    std::io::Write::write_all(&mut std::io::stdout(), &buffer)?;
+    Ok(())
+}
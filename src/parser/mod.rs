@@ -1,21 +1,242 @@
-use anyhow::Result;
+use regex::Regex;
 use std::path::Path;
 
+/// Plugin IDs of the aliases Kotlin Multiplatform project templates declare in their
+/// version catalogs (`alias(libs.plugins.kotlinMultiplatform)`), keyed by accessor
+/// without separators and case
+const KNOWN_PLUGIN_ALIASES: &[(&str, &str)] = &[
+    ("kotlinmultiplatform", "org.jetbrains.kotlin.multiplatform"),
+    ("multiplatform", "org.jetbrains.kotlin.multiplatform"),
+    ("androidapplication", "com.android.application"),
+    ("androidlibrary", "com.android.library"),
+    ("androidkotlinmultiplatformlibrary", "com.android.kotlin.multiplatform.library"),
+    ("androidtest", "com.android.test"),
+    ("androidxbenchmark", "androidx.benchmark"),
+    ("benchmark", "androidx.benchmark"),
+    ("androidxbaselineprofile", "androidx.baselineprofile"),
+    ("baselineprofile", "androidx.baselineprofile"),
+    ("kotlinandroid", "org.jetbrains.kotlin.android"),
+    ("kotlinjs", "org.jetbrains.kotlin.js"),
+    ("kotlinjvm", "org.jetbrains.kotlin.jvm"),
+    ("composemultiplatform", "org.jetbrains.compose"),
+    ("jetbrainscompose", "org.jetbrains.compose"),
+];
+
+/// IDs of the Kotlin Multiplatform plugin: the current one and the legacy Groovy one
+const MULTIPLATFORM_PLUGINS: &[&str] = &["org.jetbrains.kotlin.multiplatform", "kotlin-multiplatform"];
+
 /// Parser for Gradle build files
-pub struct GradleParser;
+///
+/// Reads what the scripts declare rather than what they mention: plugins applied in
+/// the `plugins` block or with `apply plugin`, targets of the `kotlin` block, and
+/// `project(...)` dependencies. Commented-out lines and plugins declared with
+/// `apply false` are left out.
+#[derive(Clone)]
+pub struct GradleParser {
+    /// Match: id("com.android.application"), id 'com.android.application'
+    id_regex: Regex,
+    /// Match: kotlin("multiplatform")
+    kotlin_regex: Regex,
+    /// Match: alias(libs.plugins.kotlinMultiplatform), alias libs.plugins.kotlinMultiplatform
+    alias_regex: Regex,
+    /// Match: `kotlin-dsl`, application, java-library (core plugins by bare name)
+    bare_regex: Regex,
+    /// Match: apply plugin: 'kotlin-android', apply(plugin = "kotlin-android")
+    apply_regex: Regex,
+    /// Match: androidTarget(), iosArm64(), js(IR) {, jvm("desktop")
+    target_regex: Regex,
+    /// Match: project(":shared"), project(path = ":shared"), project(path: ':shared')
+    project_regex: Regex,
+    /// Match: rootProject.name = "app"
+    name_regex: Regex,
+}
+
+/// Syntax of a Gradle script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    Kotlin,
+    Groovy,
+}
 
 impl GradleParser {
-    /// Parses build.gradle.kts file
-    pub fn parse_kotlin_build_file(path: &Path) -> Result<BuildFileInfo> {
-        // TODO: Implement actual parsing logic
-        Ok(BuildFileInfo::default())
+    pub fn new() -> Self {
+        Self {
+            id_regex: Regex::new(r#"^id\s*\(?\s*["']([\w.-]+)["']"#).unwrap(),
+            kotlin_regex: Regex::new(r#"^kotlin\s*\(\s*"([\w.-]+)"\s*\)"#).unwrap(),
+            alias_regex: Regex::new(r"^alias\s*\(?\s*libs\.plugins\.([\w.]+)").unwrap(),
+            bare_regex: Regex::new(r"^`?([A-Za-z][\w-]*)`?$").unwrap(),
+            apply_regex: Regex::new(r#"\bapply\s*\(?\s*plugin\s*[:=]\s*["']([\w.-]+)["']"#).unwrap(),
+            target_regex: Regex::new(
+                r"\b(androidNative\w*|androidTarget|androidLibrary|android|jvm|js|wasmJs|wasmWasi|ios\w*|macos\w*|watchos\w*|tvos\w*|linux\w*|mingw\w*)\s*[({]",
+            )
+            .unwrap(),
+            project_regex: Regex::new(r#"\bproject\(\s*(?:path\s*[=:]\s*)?["']([^"']+)["']"#).unwrap(),
+            name_regex: Regex::new(r#"rootProject\.name\s*=\s*["']([^"']+)["']"#).unwrap(),
+        }
+    }
+
+    /// Parses a build script, in the Kotlin DSL if its name ends with `.kts`, else in Groovy
+    pub fn parse(&self, path: &Path, content: &str) -> BuildFileInfo {
+        if path.extension().is_some_and(|ext| ext == "kts") {
+            self.parse_kotlin_build_file(content)
+        } else {
+            self.parse_groovy_build_file(content)
+        }
     }
 
-    /// Parses build.gradle file
-    pub fn parse_groovy_build_file(path: &Path) -> Result<BuildFileInfo> {
-        // TODO: Implement actual parsing logic
-        Ok(BuildFileInfo::default())
+    /// Parses the content of a build.gradle.kts file
+    pub fn parse_kotlin_build_file(&self, content: &str) -> BuildFileInfo {
+        self.parse_script(content, Dialect::Kotlin)
+    }
+
+    /// Parses the content of a build.gradle file
+    pub fn parse_groovy_build_file(&self, content: &str) -> BuildFileInfo {
+        self.parse_script(content, Dialect::Groovy)
+    }
+
+    fn parse_script(&self, content: &str, dialect: Dialect) -> BuildFileInfo {
+        let code = strip_comments(content);
+        let mut info = BuildFileInfo {
+            name: self.name_regex.captures(&code).map(|cap| cap[1].to_string()),
+            ..BuildFileInfo::default()
+        };
+
+        let mut add_plugin = |plugin: String| {
+            if !info.plugins.contains(&plugin) {
+                info.plugins.push(plugin);
+            }
+        };
+        if let Some(block) = top_level_block(&code, "plugins") {
+            for statement in block.split(['\n', ';']).map(str::trim).filter(|s| !s.is_empty()) {
+                if statement.contains("apply false") || statement.contains("apply(false)") {
+                    continue;
+                }
+                if let Some(plugin) = self.plugin_of(statement, dialect) {
+                    add_plugin(plugin);
+                }
+            }
+        }
+        for cap in self.apply_regex.captures_iter(&code) {
+            add_plugin(cap[1].to_string());
+        }
+        info.is_multiplatform = info.plugins.iter().any(|plugin| MULTIPLATFORM_PLUGINS.contains(&plugin.as_str()));
+
+        if let Some(block) = top_level_block(&code, "kotlin") {
+            for cap in self.target_regex.captures_iter(block) {
+                let name = &cap[1];
+                // Source sets (`iosMain { }`) configure targets rather than declare them
+                if name.ends_with("Main") || name.ends_with("Test") {
+                    continue;
+                }
+                if let Some(target) = KotlinTarget::of_preset(name) {
+                    if !info.targets.contains(&target) {
+                        info.targets.push(target);
+                    }
+                }
+            }
+        }
+
+        for cap in self.project_regex.captures_iter(&code) {
+            let target = &cap[1];
+            let module = if target.starts_with(':') {
+                target.to_string()
+            } else {
+                format!(":{}", target)
+            };
+            if !info.dependencies.contains(&module) {
+                info.dependencies.push(module);
+            }
+        }
+
+        info
     }
+
+    /// Plugin ID a statement of the `plugins` block applies
+    ///
+    /// Version catalog aliases resolve to the plugin ID when they are one of the
+    /// well-known template aliases, else stay `libs.plugins.<accessor>`.
+    fn plugin_of(&self, statement: &str, dialect: Dialect) -> Option<String> {
+        if let Some(cap) = self.id_regex.captures(statement) {
+            return Some(cap[1].to_string());
+        }
+        if dialect == Dialect::Kotlin {
+            if let Some(cap) = self.kotlin_regex.captures(statement) {
+                return Some(format!("org.jetbrains.kotlin.{}", &cap[1]));
+            }
+        }
+        if let Some(cap) = self.alias_regex.captures(statement) {
+            let accessor = &cap[1];
+            let key: String = accessor.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+            return Some(
+                KNOWN_PLUGIN_ALIASES
+                    .iter()
+                    .find(|(alias, _)| *alias == key)
+                    .map_or_else(|| format!("libs.plugins.{}", accessor), |(_, id)| id.to_string()),
+            );
+        }
+        let bare = statement.split_whitespace().next().unwrap_or_default();
+        if bare == statement || bare.starts_with('`') {
+            return self.bare_regex.captures(bare).map(|cap| cap[1].to_string());
+        }
+        None
+    }
+}
+
+impl Default for GradleParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lines of a script without `//` and `/* */` comment lines
+fn strip_comments(content: &str) -> String {
+    let mut code = String::with_capacity(content.len());
+    let mut in_block = false;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if in_block {
+            in_block = !trimmed.contains("*/");
+            code.push('\n');
+            continue;
+        }
+        if trimmed.starts_with("/*") {
+            in_block = !trimmed.contains("*/");
+        } else if !trimmed.starts_with("//") {
+            code.push_str(line);
+        }
+        code.push('\n');
+    }
+    code
+}
+
+/// Body of the first `name { ... }` block opened at the start of a line
+fn top_level_block<'c>(code: &'c str, name: &str) -> Option<&'c str> {
+    let mut offset = 0;
+    for line in code.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let opens = trimmed
+            .strip_prefix(name)
+            .is_some_and(|rest| rest.trim_start().starts_with('{'));
+        if opens {
+            let start = offset + line.find('{')? + 1;
+            let mut depth = 1;
+            for (index, c) in code[start..].char_indices() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(&code[start..start + index]);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            return Some(&code[start..]);
+        }
+        offset += line.len();
+    }
+    None
 }
 
 /// Parser for Kotlin source files
@@ -24,8 +245,11 @@ pub struct KotlinParser;
 impl KotlinParser {
     /// Extracts import statements from Kotlin source files
     pub fn parse_imports(content: &str) -> Vec<String> {
-        // TODO: Implement actual parsing logic
-        Vec::new()
+        content
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("import "))
+            .map(|import| import.trim().trim_end_matches(';').to_string())
+            .collect()
     }
 
     /// Counts code lines (excluding comments)
@@ -40,15 +264,168 @@ impl KotlinParser {
     }
 }
 
+/// Kind of target a `kotlin` block declares
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KotlinTarget {
+    /// `androidTarget()`, or `androidLibrary { }` of the Android KMP library plugin
+    Android,
+    /// `iosArm64()`, `iosSimulatorArm64()`, ...
+    Ios,
+    Jvm,
+    Js,
+    /// `wasmJs()`, `wasmWasi()`
+    Wasm,
+    /// Other Kotlin/Native targets: macOS, watchOS, tvOS, Linux, Windows, Android NDK
+    Native,
+}
+
+impl KotlinTarget {
+    /// Kind of the target a preset function (`iosX64`, `jvm`, ...) declares
+    pub fn of_preset(preset: &str) -> Option<Self> {
+        Some(match preset {
+            "androidTarget" | "androidLibrary" | "android" => Self::Android,
+            "jvm" => Self::Jvm,
+            "js" => Self::Js,
+            "wasmJs" | "wasmWasi" => Self::Wasm,
+            _ if preset.starts_with("androidNative") => Self::Native,
+            _ if preset.starts_with("ios") => Self::Ios,
+            _ if ["macos", "watchos", "tvos", "linux", "mingw"].iter().any(|p| preset.starts_with(p)) => Self::Native,
+            _ => return None,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Android => "android",
+            Self::Ios => "ios",
+            Self::Jvm => "jvm",
+            Self::Js => "js",
+            Self::Wasm => "wasm",
+            Self::Native => "native",
+        }
+    }
+}
+
 /// Build file information
 #[derive(Debug, Default)]
 pub struct BuildFileInfo {
-    /// Project name
+    /// Project name, set by `rootProject.name` in settings scripts
     pub name: Option<String>,
-    /// List of plugins
+    /// IDs of the applied plugins, in declaration order
     pub plugins: Vec<String>,
-    /// List of dependencies
+    /// Gradle paths of the modules depended on through `project(...)` (`:shared`)
     pub dependencies: Vec<String>,
     /// Whether KMP plugin is used
     pub is_multiplatform: bool,
+    /// Kinds of the targets the `kotlin` block declares
+    pub targets: Vec<KotlinTarget>,
+}
+
+impl BuildFileInfo {
+    /// Whether the script applies the plugin with the given ID
+    pub fn applies(&self, plugin: &str) -> bool {
+        self.plugins.iter().any(|applied| applied == plugin)
+    }
+
+    /// Whether the script applies the Android application or library plugin
+    pub fn is_android(&self) -> bool {
+        ["com.android.application", "com.android.library", "com.android.kotlin.multiplatform.library"]
+            .iter()
+            .any(|plugin| self.applies(plugin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kotlin_build_file() {
+        let info = GradleParser::new().parse_kotlin_build_file(
+            r#"
+plugins {
+    kotlin("multiplatform")
+    alias(libs.plugins.androidLibrary)
+    alias(libs.plugins.sqldelight)
+    id("maven-publish")
+    `kotlin-dsl`
+    // id("com.android.application")
+}
+
+kotlin {
+    jvmToolchain(17)
+    androidTarget()
+    listOf(iosX64(), iosArm64(), iosSimulatorArm64()).forEach { it.binaries.framework { baseName = "Shared" } }
+    jvm("desktop")
+    js(IR) { browser() }
+    sourceSets {
+        iosMain { }
+        commonMain.dependencies {
+            implementation(project(":core:model"))
+            api(project(path = ":core:network"))
+        }
+    }
+}
+"#,
+        );
+        assert!(info.is_multiplatform);
+        assert_eq!(
+            info.plugins,
+            vec![
+                "org.jetbrains.kotlin.multiplatform",
+                "com.android.library",
+                "libs.plugins.sqldelight",
+                "maven-publish",
+                "kotlin-dsl",
+            ]
+        );
+        assert!(info.is_android());
+        assert_eq!(
+            info.targets,
+            vec![KotlinTarget::Android, KotlinTarget::Ios, KotlinTarget::Jvm, KotlinTarget::Js]
+        );
+        assert_eq!(info.dependencies, vec![":core:model", ":core:network"]);
+    }
+
+    #[test]
+    fn test_parse_groovy_build_file() {
+        let info = GradleParser::new().parse_groovy_build_file(
+            r#"
+plugins {
+    id 'com.android.application'
+    id 'org.jetbrains.kotlin.multiplatform' version '2.0.0' apply false
+}
+apply plugin: 'kotlin-android'
+
+dependencies {
+    implementation project(':shared')
+    implementation project(path: ':feature:auth')
+}
+"#,
+        );
+        assert!(!info.is_multiplatform);
+        assert_eq!(info.plugins, vec!["com.android.application", "kotlin-android"]);
+        assert!(info.targets.is_empty());
+        assert_eq!(info.dependencies, vec![":shared", ":feature:auth"]);
+    }
+
+    #[test]
+    fn test_root_build_file_declares_plugins_without_applying_them() {
+        let parser = GradleParser::new();
+        let info = parser.parse(
+            Path::new("build.gradle.kts"),
+            "plugins {\n    kotlin(\"multiplatform\").apply(false)\n    alias(libs.plugins.androidApplication) apply false\n}\n",
+        );
+        assert!(!info.is_multiplatform);
+        assert!(info.plugins.is_empty());
+
+        let settings = parser.parse(Path::new("settings.gradle.kts"), "rootProject.name = \"MyApp\"\ninclude(\":shared\")\n");
+        assert_eq!(settings.name.as_deref(), Some("MyApp"));
+    }
+
+    #[test]
+    fn test_parse_imports() {
+        let imports = KotlinParser::parse_imports("package app\n\nimport com.example.User\nimport com.example.Session as AppSession;\n");
+        assert_eq!(imports, vec!["com.example.User", "com.example.Session as AppSession"]);
+    }
 }
@@ -1,13 +1,77 @@
 use anyhow::Result;
 use log::info;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
 
 use crate::domain::{
-    DependencyRepository, ImpactAnalysis, Platform, PlatformImpact, SourceFileRepository,
-    SymbolRepository, SymbolUsageRepository,
+    AnalysisHooks, AnalysisScope, ApiFunnel, ApiOwnership, AppLayers, AndroidModuleImpact, CategoryUsage, CoverageGoal, DependencyRepository,
+    DetectionSettings, Diagnostic, DiffImpact, ExplicitApiMode, DiffScope, ImpactMetric, ImpactMetrics, MetricImpact, DocumentationCoverage, EntryPoint, FileMetrics, LayerImpact, SuppressionBaseline, GoalProgress, is_test_source_path, GradleModule, ImpactAnalysis, InputLimits, ModuleDependency, ModuleReach,
+    PackageUsage, ParitySummary, Platform, PlatformImpact, ProjectDetection, Ratio, Severity,
+    SharedCodeRoi, SourceFileRepository,
+    StabilityTierUsage, StabilityTiers, SwiftModule, SwiftModuleImpact, Symbol, SymbolCategories, SymbolParser, MatchEngine, FrameworkHeaderSources,
+    OwnerRule, SymbolRepository, SymbolUsageRepository, UiLayoutImpact, UsageKindCount, VariantImpact, path_variant,
+    pass_through_symbols, stale_integrations, unused_imports,
 };
 
-use super::{CalculateDependenciesUseCase, DetectUsageUseCase, ExtractSymbolsUseCase};
+use super::{CalculateDependenciesUseCase, DetectUsageUseCase, ExpectActualCoverageUseCase, ExtractSymbolsUseCase};
+
+/// Rule of the diagnostics recording which strategy found each project
+pub const PROJECT_DETECTION_RULE: &str = "project-detection";
+
+/// Rule of the diagnostics recording how many files of a project the exclusion settings skipped
+pub const EXCLUDED_SOURCES_RULE: &str = "excluded-sources";
+
+/// Rule of the diagnostics flagging declarations public only by default in modules in explicit API mode
+pub const EXPLICIT_API_RULE: &str = "explicit-api";
+
+/// Options that tune how the impact analysis classifies and counts symbols
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisOptions {
+    /// Stability tiers used to break down app usage by annotation
+    pub stability_tiers: StabilityTiers,
+    /// Rules that sort shared symbols into categories
+    pub categories: SymbolCategories,
+    /// Rules that split app code into architectural layers
+    pub layers: AppLayers,
+    /// Attribute usages of `actual typealias` targets to the shared alias
+    pub resolve_actual_typealiases: bool,
+    /// Count sample, demo, and benchmark app modules as app code
+    pub include_sample_modules: bool,
+    /// Extract symbols declared in test source sets (`commonTest`, `iosTest`, ...) as shared API
+    pub include_test_symbols: bool,
+    /// Extract `internal` symbols as shared API, for apps that are modules of the same Gradle module
+    pub include_internal: bool,
+    /// Guards against pathological app files
+    pub input_limits: InputLimits,
+    /// Coverage targets per platform or app module
+    pub goals: Vec<CoverageGoal>,
+    /// Filters library consumers register on symbols and usages
+    pub hooks: AnalysisHooks,
+    /// Depth, extra roots, and variants of project detection
+    pub detection: DetectionSettings,
+    /// Count import statements, and files that only import from affected files, as impact
+    pub count_imports: bool,
+    /// Subtrees both symbol extraction and app scanning are restricted to
+    pub scope: AnalysisScope,
+    /// Entry points reported per platform (default: [`EntryPoint::DEFAULT_LIMIT`])
+    pub max_entry_points: Option<usize>,
+    /// App paths counted as UI layout besides the files recognized as such, in the
+    /// pattern syntax of [`DetectionSettings::excluded_by`]
+    pub ui_layout_paths: Vec<String>,
+    /// (symbol, file) pairs whose usages are known false positives
+    pub suppressions: SuppressionBaseline,
+    /// Lines changed since a base commit; when set, only the shared symbols whose
+    /// declarations the changes touch are analyzed
+    pub diff: Option<DiffScope>,
+    /// Parser shared Kotlin declarations are extracted with
+    pub parser: SymbolParser,
+    /// Engine app files are searched for shared symbol names with
+    pub matcher: MatchEngine,
+    /// Kotlin/Native framework headers the exported names of shared classifiers are read from
+    pub objc_headers: FrameworkHeaderSources,
+    /// What impact ratios count: files, code lines, or functions
+    pub metric: ImpactMetric,
+}
 
 /// Use Case: Analyze KMP Impact
 ///
@@ -18,6 +82,7 @@ pub struct AnalyzeImpactUseCase<'a> {
     source_file_repository: &'a dyn SourceFileRepository,
     symbol_usage_repository: &'a dyn SymbolUsageRepository,
     dependency_repository: &'a dyn DependencyRepository,
+    options: AnalysisOptions,
 }
 
 impl<'a> AnalyzeImpactUseCase<'a> {
@@ -32,30 +97,97 @@ impl<'a> AnalyzeImpactUseCase<'a> {
             source_file_repository,
             symbol_usage_repository,
             dependency_repository,
+            options: AnalysisOptions::default(),
         }
     }
 
+    /// Replaces the default analysis options
+    pub fn with_options(mut self, options: AnalysisOptions) -> Self {
+        self.options = options;
+        self
+    }
+
     /// Execute the complete impact analysis
     pub fn execute(&self, project_path: &str) -> Result<ImpactAnalysis> {
         info!("Starting impact analysis for project: {}", project_path);
 
         // Step 1: Find all source files
-        let kmp_files = self.source_file_repository.find_kmp_files(project_path)?;
-        let app_files = self.source_file_repository.find_app_files(project_path)?;
+        let scope = &self.options.scope;
+        let mut kmp_files = self.source_file_repository.find_kmp_files(project_path)?;
+        let mut app_files = self.source_file_repository.find_app_files(project_path)?;
+        let projects = self.source_file_repository.find_projects(project_path)?;
+        if scope.is_restricted() {
+            info!("Restricting analysis to: {}", scope.roots.join(", "));
+            kmp_files.retain(|file| scope.contains(project_path, file));
+            for files in app_files.values_mut() {
+                files.retain(|file| scope.contains(project_path, file));
+            }
+            app_files.retain(|_, files| !files.is_empty());
+        }
 
         info!("Found {} KMP files", kmp_files.len());
         info!("Found {} platforms with app files", app_files.len());
 
         // Step 2: Extract KMP symbols
-        let extract_use_case = ExtractSymbolsUseCase::new(self.symbol_repository);
-        let symbols = extract_use_case.execute(&kmp_files)?;
+        let extract_use_case = ExtractSymbolsUseCase::new(self.symbol_repository)
+            .with_test_symbols(self.options.include_test_symbols)
+            .with_internal_symbols(self.options.include_internal)
+            .with_hooks(self.options.hooks.clone());
+        let mut symbols = extract_use_case.execute(&kmp_files)?;
+        // Resources and generated types are found per project, not per KMP file
+        let in_scope = |symbol: &Symbol| scope.contains(project_path, &symbol.file_path);
+        symbols.extend(extract_use_case.resources(project_path)?.into_iter().filter(in_scope));
+        symbols.extend(extract_use_case.generated(project_path)?.into_iter().filter(in_scope));
+        extract_use_case.export_names(project_path, &kmp_files, &mut symbols)?;
+        let diff = self.options.diff.as_ref().map(|diff| {
+            symbols.retain(|symbol| diff.touches(project_path, symbol));
+            info!("Restricting analysis to {} symbol(s) touched since {}", symbols.len(), diff.base_ref);
+            let mut changed_files: Vec<String> = kmp_files
+                .iter()
+                .filter(|file| diff.changed_lines(project_path, file).is_some())
+                .cloned()
+                .collect();
+            changed_files.sort();
+            let touched_symbols: BTreeSet<String> = symbols.iter().map(|symbol| symbol.name.clone()).collect();
+            DiffImpact {
+                base_ref: diff.base_ref.clone(),
+                changed_files,
+                touched_symbols: touched_symbols.into_iter().collect(),
+                changed_lines: diff.files.clone(),
+            }
+        });
 
         // Step 3: Detect symbol usage across all platforms
         let detect_use_case = DetectUsageUseCase::new(
             self.source_file_repository,
             self.symbol_usage_repository,
+        )
+        .with_typealias_resolution(self.options.resolve_actual_typealiases)
+        .with_limits(self.options.input_limits)
+        .with_hooks(self.options.hooks.clone());
+        let mut detection = detect_use_case.execute(&app_files, &symbols)?;
+        let (suppressed_usages, stale_suppressions) = self.options.suppressions.apply(
+            project_path,
+            &mut detection.usages,
+            &mut detection.imports,
         );
-        let symbol_usages = detect_use_case.execute(&app_files, &symbols)?;
+        let mut symbol_usages = detection.usages;
+        let unused_imports = unused_imports(&detection.imports, &symbol_usages);
+        let stale_integrations = stale_integrations(
+            &detection.framework_import_files,
+            &detection.imports,
+            &symbol_usages,
+            &file_platforms(&app_files),
+        );
+        let funnel =
+            ApiFunnel::from_usages(&symbols, &symbol_usages, &detection.imports, &file_platforms(&app_files));
+        let usage_kinds =
+            UsageKindCount::tally(symbol_usages.values().chain(detection.imports.values()).flatten());
+        if self.options.count_imports {
+            for (name, imports) in detection.imports {
+                symbol_usages.entry(name).or_default().extend(imports);
+            }
+        }
         let direct_affected_files = detect_use_case.get_affected_files(&symbol_usages);
 
         // Step 4: Build dependency graph and calculate transitive impact
@@ -67,31 +199,152 @@ impl<'a> AnalyzeImpactUseCase<'a> {
         dep_use_case.build_graph(&all_files)?;
 
         let transitive_files = dep_use_case.calculate_transitive(&direct_affected_files)?;
+        let module_dependencies = dep_use_case.module_dependencies(project_path)?;
+        let gradle_modules = dep_use_case.android_modules(project_path)?;
+        let swift_modules = dep_use_case.swift_modules(project_path)?;
 
         // Step 5: Calculate metrics per platform
-        let platform_impacts = self.calculate_platform_impacts(
+        // Usages are matched by simple name, so a name counts as deprecated only when every package
+        // declaring it deprecates it; within a package, one annotated `expect` or overload suffices
+        let deprecated_declarations: HashSet<(&str, &str)> = symbols
+            .iter()
+            .filter(|s| s.is_deprecated())
+            .map(|s| (s.package.as_str(), s.name.as_str()))
+            .collect();
+        let mut deprecated_symbols: HashSet<String> =
+            deprecated_declarations.iter().map(|(_, name)| name.to_string()).collect();
+        for symbol in &symbols {
+            if !deprecated_declarations.contains(&(symbol.package.as_str(), symbol.name.as_str())) {
+                deprecated_symbols.remove(&symbol.name);
+            }
+        }
+        let ui_layout_paths = &self.options.ui_layout_paths;
+        let impact_files = ImpactFiles {
+            direct: direct_affected_files.iter().map(String::as_str).collect(),
+            transitive: transitive_files.iter().map(String::as_str).collect(),
+            code_lines: &detection.code_lines,
+            functions: &detection.functions,
+            ui_layout: app_files
+                .values()
+                .flatten()
+                .map(String::as_str)
+                .filter(|file| {
+                    let relative = Path::new(file).strip_prefix(project_path).unwrap_or(Path::new(file));
+                    detection.ui_layout_files.contains(*file)
+                        || DetectionSettings::excluded_by(ui_layout_paths, relative).is_some()
+                })
+                .collect(),
+        };
+        let mut platform_impacts = self.calculate_platform_impacts(
             &app_files,
             &symbol_usages,
-            &direct_affected_files,
-            &transitive_files,
+            &impact_files,
+            &deprecated_symbols,
         )?;
+        let shared_dependencies = dep_use_case.file_dependencies(&kmp_files)?;
+        self.calculate_entry_points(
+            &mut platform_impacts,
+            &app_files,
+            &symbols,
+            &symbol_usages,
+            &shared_dependencies,
+        );
+
+        let android_modules = self.calculate_android_module_impacts(
+            &gradle_modules,
+            &module_dependencies,
+            &app_files,
+            &symbol_usages,
+            &impact_files,
+        );
+
+        let ios_modules =
+            self.calculate_swift_module_impacts(&swift_modules, &app_files, &symbol_usages, &impact_files);
+
+        let goals =
+            self.calculate_goal_progress(project_path, &app_files, &platform_impacts, &impact_files);
+        let variants = self.calculate_variant_impacts(project_path, &app_files, &impact_files);
+        let mut files = self.calculate_file_metrics(project_path, &app_files, &symbol_usages, &impact_files);
+        let direct_set: HashSet<String> = direct_affected_files.iter().cloned().collect();
+        for file in files.iter_mut().filter(|f| f.affected_lines > 0 && !f.direct) {
+            file.affected_via = dep_use_case.dependency_chain(&file.path, &direct_set)?;
+        }
+        self.calculate_layer_impacts(&mut platform_impacts, &files);
 
         // Step 6: Aggregate overall metrics
+        let shared_lines = self.count_shared_lines(&kmp_files)?;
+        let documentation = DocumentationCoverage::from_symbols(&symbols, &symbol_usages);
+        let parity =
+            ParitySummary::from_usages(&symbols, &symbol_usages, &file_platforms(&app_files));
+        let stability_tiers =
+            self.calculate_stability_breakdown(&symbols, &symbol_usages, &app_files);
+        let categories = self.calculate_category_breakdown(&symbols, &symbol_usages, &app_files);
+        let packages = self.calculate_package_breakdown(&symbols, &symbol_usages, &app_files);
+        let owner_rules = self.symbol_repository.find_owner_rules(&kmp_files)?;
+        let explicit_api = self.explicit_api_diagnostics(project_path, &kmp_files)?;
+        let api_owners = self.calculate_api_owners(&symbols, &symbol_usages, &owner_rules);
+        let pass_through_symbols = pass_through_symbols(&symbols, &symbol_usages);
+        let expect_actual = ExpectActualCoverageUseCase::new(self.symbol_repository).execute(&kmp_files)?;
+        let mut ui_layout = UiLayoutImpact::default();
+        for impact in platform_impacts.values() {
+            ui_layout.files += impact.ui_layout.files;
+            ui_layout.total_lines += impact.ui_layout.total_lines;
+            ui_layout.affected_lines += impact.ui_layout.affected_lines;
+        }
+
         let mut impact_analysis = ImpactAnalysis {
             total_symbols: symbols.len(),
+            symbols,
             total_app_files: app_files.values().map(|v| v.len()).sum(),
             total_app_lines: platform_impacts.values().map(|p| p.total_lines).sum(),
             affected_files: direct_affected_files.iter().cloned().collect(),
             affected_lines: platform_impacts.values().map(|p| p.affected_lines).sum(),
             impact_ratio: 0.0,
+            metric: self.options.metric,
+            metrics: ImpactMetrics::sum(platform_impacts.values().map(|p| &p.metrics)),
             platform_impacts: platform_impacts
                 .into_iter()
                 .map(|(k, v)| (k.name().to_string(), v))
                 .collect(),
+            ui_layout,
             symbol_usages,
+            documentation,
+            roi: SharedCodeRoi::default(),
+            parity,
+            funnel,
+            stability_tiers,
+            categories,
+            packages,
+            api_owners,
+            module_dependencies,
+            android_modules,
+            ios_modules,
+            goals,
+            variants,
+            expect_actual,
+            files,
+            usage_kinds,
+            pass_through_symbols,
+            unused_imports,
+            stale_integrations,
+            suppressed_usages,
+            stale_suppressions,
+            diagnostics: Self::detection_diagnostics(&projects)
+                .into_iter()
+                .chain(detection.diagnostics)
+                .chain(explicit_api)
+                .collect(),
+            metadata: None,
+            api_churn: None,
+            diff,
+            warm_start: None,
         };
 
         impact_analysis.calculate_impact_ratio();
+        impact_analysis
+            .ui_layout
+            .calculate_impact_ratio(impact_analysis.total_app_lines, impact_analysis.affected_lines);
+        impact_analysis.roi = SharedCodeRoi::new(shared_lines, &impact_analysis.platform_impacts);
 
         info!(
             "Impact analysis complete: {:.2}% impact coverage",
@@ -101,76 +354,736 @@ impl<'a> AnalyzeImpactUseCase<'a> {
         Ok(impact_analysis)
     }
 
+    /// Code lines of the shared modules, leaving out test source sets (`commonTest`, `iosTest`, ...)
+    fn count_shared_lines(&self, kmp_files: &[String]) -> Result<usize> {
+        let mut lines = 0;
+        for file in kmp_files {
+            if is_test_source_path(file) {
+                continue;
+            }
+            let source_file = self.source_file_repository.read_source_file(file)?;
+            lines += self
+                .source_file_repository
+                .count_code_lines(&source_file.content, Platform::Android);
+        }
+        Ok(lines)
+    }
+
+    /// One informational finding per detected project, naming the strategy that found it,
+    /// and one per project with files skipped as build output, generated, or vendored code
+    fn detection_diagnostics(projects: &[ProjectDetection]) -> Vec<Diagnostic> {
+        let root = |project: &ProjectDetection| {
+            if project.root.is_empty() { ".".to_string() } else { project.root.clone() }
+        };
+        let detected = projects.iter().map(|project| Diagnostic {
+            rule: PROJECT_DETECTION_RULE.to_string(),
+            severity: Severity::Info,
+            message: format!(
+                "{} project `{}` detected by {} (confidence {:.2})",
+                project.project_type,
+                root(project),
+                project.detected_by,
+                project.confidence
+            ),
+            symbol: None,
+        });
+        let excluded = projects.iter().filter(|project| project.excluded_files > 0).map(|project| Diagnostic {
+            rule: EXCLUDED_SOURCES_RULE.to_string(),
+            severity: Severity::Info,
+            message: format!(
+                "{} project `{}`: {} file(s) skipped as build output, generated code, test fixtures, or vendored dependencies",
+                project.project_type,
+                root(project),
+                project.excluded_files
+            ),
+            symbol: None,
+        });
+        detected.chain(excluded).collect()
+    }
+
+    /// One warning per declaration of a module in explicit API mode without a visibility modifier
+    ///
+    /// In strict mode the compiler rejects such declarations, so they were left out of the
+    /// shared API; in warning mode they compile as public and were kept.
+    fn explicit_api_diagnostics(&self, project_path: &str, kmp_files: &[String]) -> Result<Vec<Diagnostic>> {
+        let mut declarations = self.symbol_repository.find_implicit_public_declarations(kmp_files)?;
+        if !self.options.include_test_symbols {
+            declarations.retain(|declaration| !is_test_source_path(&declaration.file_path));
+        }
+        Ok(declarations
+            .into_iter()
+            .map(|declaration| {
+                let file = Path::new(&declaration.file_path);
+                let relative = file.strip_prefix(project_path).unwrap_or(file);
+                let consequence = match declaration.mode {
+                    ExplicitApiMode::Strict => "fails to compile, so it is not counted as shared API",
+                    ExplicitApiMode::Warning => "is still exported, but add `public` or narrow its visibility",
+                };
+                Diagnostic {
+                    rule: EXPLICIT_API_RULE.to_string(),
+                    severity: Severity::Warning,
+                    message: format!(
+                        "`{}` ({}:{}) is public only by default; in explicit API {} mode it {}",
+                        declaration.name,
+                        relative.display(),
+                        declaration.line,
+                        declaration.mode.name(),
+                        consequence
+                    ),
+                    symbol: Some(declaration.name),
+                }
+            })
+            .collect())
+    }
+
     /// Calculate platform-specific impacts
     fn calculate_platform_impacts(
         &self,
         app_files: &HashMap<Platform, Vec<String>>,
         symbol_usages: &HashMap<String, Vec<crate::domain::SymbolUsage>>,
-        direct_files: &[String],
-        transitive_files: &[String],
+        impact_files: &ImpactFiles,
+        deprecated_symbols: &HashSet<String>,
     ) -> Result<HashMap<Platform, PlatformImpact>> {
         let mut platform_impacts = HashMap::new();
 
         for (platform, files) in app_files {
             let mut impact = PlatformImpact::new(platform.name().to_string());
             impact.total_files = files.len();
+            let platform_files: HashSet<&str> = files.iter().map(String::as_str).collect();
+            let (mut affected_files, mut functions, mut affected_functions) = (0, 0, 0);
 
-            // Calculate total lines
             for file_path in files {
-                if let Ok(file) = self.source_file_repository.read_source_file(file_path) {
-                    impact.total_lines += self
-                        .source_file_repository
-                        .count_code_lines(&file.content, platform.clone());
+                let lines = impact_files.lines(file_path);
+                let affected = impact_files.is_affected(file_path);
+                impact.total_lines += lines;
+                functions += impact_files.functions(file_path);
+                if affected {
+                    impact.affected_lines += lines;
+                    affected_files += 1;
+                    affected_functions += impact_files.functions(file_path);
+                }
+                if impact_files.direct.contains(file_path.as_str()) {
+                    impact.affected_files.insert(file_path.clone());
+                }
+                if impact_files.ui_layout.contains(file_path.as_str()) {
+                    impact.ui_layout.files += 1;
+                    impact.ui_layout.total_lines += lines;
+                    if affected {
+                        impact.ui_layout.affected_lines += lines;
+                    }
                 }
             }
 
-            // Find affected files for this platform
-            let platform_direct: HashSet<String> = direct_files
+            // Calculate top symbols for this platform
+            impact.top_symbols = self.calculate_top_symbols(symbol_usages, &platform_files);
+
+            // Collect usages of deprecated shared API on this platform
+            impact.deprecated_usages = symbol_usages
                 .iter()
-                .filter(|f| files.contains(f))
+                .filter(|(name, _)| deprecated_symbols.contains(*name))
+                .flat_map(|(_, usages)| usages.iter())
+                .filter(|u| platform_files.contains(u.file_path.as_str()))
                 .cloned()
                 .collect();
+            impact.deprecated_usages.sort_by(|a, b| {
+                (&a.file_path, a.line_number, &a.symbol_name)
+                    .cmp(&(&b.file_path, b.line_number, &b.symbol_name))
+            });
 
-            let platform_transitive: HashSet<String> = transitive_files
-                .iter()
-                .filter(|f| files.contains(f))
-                .cloned()
+            impact.metrics = ImpactMetrics {
+                files: MetricImpact::new(affected_files, impact.total_files),
+                lines: MetricImpact::new(impact.affected_lines, impact.total_lines),
+                functions: MetricImpact::new(affected_functions, functions),
+            };
+            impact.impact_ratio = impact.exact_ratio(self.options.metric).value();
+            impact.ui_layout.calculate_impact_ratio(impact.total_lines, impact.affected_lines);
+
+            platform_impacts.insert(platform.clone(), impact);
+        }
+
+        Ok(platform_impacts)
+    }
+
+    /// Lines, affected lines, used symbols, and layer of every app file
+    fn calculate_file_metrics(
+        &self,
+        project_path: &str,
+        app_files: &HashMap<Platform, Vec<String>>,
+        symbol_usages: &HashMap<String, Vec<crate::domain::SymbolUsage>>,
+        impact_files: &ImpactFiles,
+    ) -> Vec<FileMetrics> {
+        let mut file_usages: HashMap<&str, (usize, BTreeSet<&str>)> = HashMap::new();
+        for usage in symbol_usages.values().flatten() {
+            let (count, names) = file_usages.entry(usage.file_path.as_str()).or_default();
+            *count += 1;
+            names.insert(usage.symbol_name.as_str());
+        }
+
+        let mut files: Vec<FileMetrics> = file_platforms(app_files)
+            .into_iter()
+            .map(|(path, platform)| {
+                let total_lines = impact_files.lines(path);
+                let (usages, symbols) = file_usages.remove(path).unwrap_or_default();
+                let relative = Path::new(path).strip_prefix(project_path).unwrap_or(Path::new(path));
+                FileMetrics {
+                    path: path.to_string(),
+                    platform: platform.name().to_string(),
+                    total_lines,
+                    affected_lines: if impact_files.is_affected(path) { total_lines } else { 0 },
+                    direct: impact_files.direct.contains(path),
+                    usages,
+                    symbols: symbols.into_iter().map(str::to_string).collect(),
+                    ui_layout: impact_files.ui_layout.contains(path),
+                    layer: self.options.layers.classify(relative).to_string(),
+                    affected_via: Vec::new(),
+                }
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+
+    /// Split each platform's lines and affected lines by the layers of its app files
+    fn calculate_layer_impacts(
+        &self,
+        platform_impacts: &mut HashMap<Platform, PlatformImpact>,
+        files: &[FileMetrics],
+    ) {
+        for (platform, impact) in platform_impacts.iter_mut() {
+            let mut layers: BTreeMap<&str, LayerImpact> = BTreeMap::new();
+            for file in files.iter().filter(|f| f.platform == platform.name()) {
+                let layer = layers.entry(file.layer.as_str()).or_insert_with(|| LayerImpact {
+                    layer: file.layer.clone(),
+                    ..Default::default()
+                });
+                layer.total_files += 1;
+                layer.total_lines += file.total_lines;
+                layer.affected_lines += file.affected_lines;
+                if file.direct {
+                    layer.affected_files += 1;
+                }
+            }
+
+            impact.layers = self
+                .options
+                .layers
+                .names()
+                .into_iter()
+                .filter_map(|name| layers.remove(name))
+                .map(|mut layer| {
+                    if layer.total_lines > 0 {
+                        layer.impact_ratio = layer.affected_lines as f64 / layer.total_lines as f64;
+                    }
+                    layer
+                })
                 .collect();
+        }
+    }
 
-            impact.affected_files = platform_direct.clone();
+    /// Collapse each platform's used symbols into the shared entry points its usage flows through
+    fn calculate_entry_points(
+        &self,
+        platform_impacts: &mut HashMap<Platform, PlatformImpact>,
+        app_files: &HashMap<Platform, Vec<String>>,
+        symbols: &[Symbol],
+        symbol_usages: &HashMap<String, Vec<crate::domain::SymbolUsage>>,
+        shared_dependencies: &HashMap<String, HashSet<String>>,
+    ) {
+        let limit = self.options.max_entry_points.unwrap_or(EntryPoint::DEFAULT_LIMIT);
+        for (platform, impact) in platform_impacts.iter_mut() {
+            let platform_files: HashSet<&str> = app_files
+                .get(platform)
+                .map(|files| files.iter().map(String::as_str).collect())
+                .unwrap_or_default();
+            let usages: Vec<&crate::domain::SymbolUsage> = symbol_usages
+                .values()
+                .flatten()
+                .filter(|u| platform_files.contains(u.file_path.as_str()))
+                .collect();
+            impact.entry_points = EntryPoint::collapse(symbols, &usages, shared_dependencies, limit);
+        }
+    }
 
-            // Calculate affected lines
-            for file_path in platform_direct.iter().chain(platform_transitive.iter()) {
-                if let Ok(file) = self.source_file_repository.read_source_file(file_path) {
-                    impact.affected_lines += self
-                        .source_file_repository
-                        .count_code_lines(&file.content, platform.clone());
+    /// Calculate impact per Android Gradle module and how shared code reaches it
+    fn calculate_android_module_impacts(
+        &self,
+        modules: &[GradleModule],
+        module_dependencies: &[ModuleDependency],
+        app_files: &HashMap<Platform, Vec<String>>,
+        symbol_usages: &HashMap<String, Vec<crate::domain::SymbolUsage>>,
+        impact_files: &ImpactFiles,
+    ) -> Vec<AndroidModuleImpact> {
+        let declaring: HashSet<&str> = module_dependencies
+            .iter()
+            .map(|d| d.module.as_str())
+            .collect();
+        let nodes: Vec<ModuleNode> = modules
+            .iter()
+            .map(|m| ModuleNode {
+                name: &m.path,
+                dir: &m.dir,
+                dependencies: &m.dependencies,
+                declares_shared_dependency: declaring.contains(m.path.as_str()),
+            })
+            .collect();
+
+        let measures = self.measure_modules(
+            &nodes,
+            Platform::Android,
+            app_files,
+            symbol_usages,
+            impact_files,
+        );
+
+        let mut impacts: Vec<AndroidModuleImpact> = modules
+            .iter()
+            .zip(&nodes)
+            .zip(measures)
+            .filter_map(|((module, node), measure)| {
+                let measure = measure?;
+                Some(AndroidModuleImpact {
+                    module: module.path.clone(),
+                    kind: module.kind,
+                    reach: measure.reach,
+                    declares_shared_dependency: node.declares_shared_dependency,
+                    via_modules: measure.via_modules,
+                    total_files: measure.total_files,
+                    total_lines: measure.total_lines,
+                    affected_files: measure.affected_files,
+                    affected_lines: measure.affected_lines,
+                    impact_ratio: measure.impact_ratio,
+                    references: measure.references,
+                })
+            })
+            .collect();
+
+        impacts.sort_by(|a, b| (a.kind, &a.module).cmp(&(b.kind, &b.module)));
+        impacts
+    }
+
+    /// Calculate impact per Swift module (SPM target or Xcode target)
+    fn calculate_swift_module_impacts(
+        &self,
+        modules: &[SwiftModule],
+        app_files: &HashMap<Platform, Vec<String>>,
+        symbol_usages: &HashMap<String, Vec<crate::domain::SymbolUsage>>,
+        impact_files: &ImpactFiles,
+    ) -> Vec<SwiftModuleImpact> {
+        let nodes: Vec<ModuleNode> = modules
+            .iter()
+            .map(|m| ModuleNode {
+                name: &m.name,
+                dir: &m.dir,
+                dependencies: &m.dependencies,
+                declares_shared_dependency: false,
+            })
+            .collect();
+
+        let measures = self.measure_modules(
+            &nodes,
+            Platform::IOS,
+            app_files,
+            symbol_usages,
+            impact_files,
+        );
+
+        let mut impacts: Vec<SwiftModuleImpact> = modules
+            .iter()
+            .zip(measures)
+            .filter_map(|(module, measure)| {
+                let measure = measure?;
+                Some(SwiftModuleImpact {
+                    module: module.name.clone(),
+                    kind: module.kind,
+                    reach: measure.reach,
+                    via_modules: measure.via_modules,
+                    total_files: measure.total_files,
+                    total_lines: measure.total_lines,
+                    affected_files: measure.affected_files,
+                    affected_lines: measure.affected_lines,
+                    impact_ratio: measure.impact_ratio,
+                    references: measure.references,
+                })
+            })
+            .collect();
+
+        impacts.sort_by(|a, b| (a.kind, &a.module).cmp(&(b.kind, &b.module)));
+        impacts
+    }
+
+    /// Measures every module of one platform, in the order given
+    ///
+    /// Each app file belongs to the innermost module directory containing it;
+    /// modules without app files yield `None`.
+    fn measure_modules(
+        &self,
+        nodes: &[ModuleNode],
+        platform: Platform,
+        app_files: &HashMap<Platform, Vec<String>>,
+        symbol_usages: &HashMap<String, Vec<crate::domain::SymbolUsage>>,
+        impact_files: &ImpactFiles,
+    ) -> Vec<Option<ModuleMeasure>> {
+        let Some(platform_files) = app_files.get(&platform) else {
+            return vec![None; nodes.len()];
+        };
+
+        let mut module_files: HashMap<&str, Vec<&str>> = HashMap::new();
+        for file in platform_files {
+            let owner = nodes
+                .iter()
+                .filter(|m| Path::new(file).starts_with(m.dir))
+                .max_by_key(|m| m.dir.len());
+            if let Some(module) = owner {
+                module_files.entry(module.name).or_default().push(file.as_str());
+            }
+        }
+
+        let mut file_references: HashMap<&str, usize> = HashMap::new();
+        for usage in symbol_usages.values().flatten() {
+            *file_references.entry(usage.file_path.as_str()).or_default() += 1;
+        }
+
+        let mut measures: Vec<Option<ModuleMeasure>> = nodes
+            .iter()
+            .map(|module| {
+                let files = module_files.get(module.name)?;
+                let mut measure = ModuleMeasure {
+                    total_files: files.len(),
+                    affected_files: files
+                        .iter()
+                        .filter(|f| impact_files.direct.contains(*f))
+                        .count(),
+                    references: files.iter().filter_map(|f| file_references.get(f)).sum(),
+                    ..Default::default()
+                };
+
+                for file_path in files {
+                    let lines = impact_files.lines(file_path);
+                    measure.total_lines += lines;
+                    if impact_files.is_affected(file_path) {
+                        measure.affected_lines += lines;
+                    }
+                }
+                if measure.total_lines > 0 {
+                    measure.impact_ratio =
+                        measure.affected_lines as f64 / measure.total_lines as f64;
+                }
+                Some(measure)
+            })
+            .collect();
+
+        // Modules whose own code touches shared code, directly or by declaration
+        let sharing: HashSet<&str> = nodes
+            .iter()
+            .zip(&measures)
+            .filter(|(node, measure)| {
+                node.declares_shared_dependency
+                    || measure.as_ref().is_some_and(|m| m.references > 0)
+            })
+            .map(|(node, _)| node.name)
+            .collect();
+        let graph: HashMap<&str, &[String]> = nodes
+            .iter()
+            .map(|m| (m.name, m.dependencies))
+            .collect();
+
+        for (node, measure) in nodes.iter().zip(&mut measures) {
+            let Some(measure) = measure else {
+                continue;
+            };
+            let mut via = BTreeSet::new();
+            let mut visited = HashSet::new();
+            let mut stack: Vec<&str> = node.dependencies.iter().map(String::as_str).collect();
+            while let Some(module) = stack.pop() {
+                if !visited.insert(module) {
+                    continue;
+                }
+                if sharing.contains(module) {
+                    via.insert(module.to_string());
+                }
+                if let Some(deps) = graph.get(module) {
+                    stack.extend(deps.iter().map(String::as_str));
                 }
             }
+            via.remove(node.name);
 
-            // Calculate top symbols for this platform
-            impact.top_symbols = self.calculate_top_symbols(symbol_usages, files);
+            measure.via_modules = via.into_iter().collect();
+            measure.reach = if measure.references > 0 {
+                ModuleReach::Direct
+            } else if !measure.via_modules.is_empty() {
+                ModuleReach::ViaModules
+            } else {
+                ModuleReach::None
+            };
+        }
 
-            impact.calculate_impact_ratio();
+        measures
+    }
 
-            platform_impacts.insert(platform.clone(), impact);
+    /// Measure progress toward each coverage goal
+    ///
+    /// Platform goals reuse the platform impact; module goals count the lines
+    /// of the app files inside the module.
+    fn calculate_goal_progress(
+        &self,
+        project_path: &str,
+        app_files: &HashMap<Platform, Vec<String>>,
+        platform_impacts: &HashMap<Platform, PlatformImpact>,
+        impact_files: &ImpactFiles,
+    ) -> Vec<GoalProgress> {
+        self.options
+            .goals
+            .iter()
+            .map(|goal| {
+                let (affected_lines, total_lines) = match goal.platform() {
+                    Some(platform) => platform_impacts
+                        .get(&platform)
+                        .map_or((0, 0), |impact| (impact.affected_lines, impact.total_lines)),
+                    None => {
+                        let mut lines = (0, 0);
+                        for file_path in app_files.values().flatten() {
+                            let relative = Path::new(file_path)
+                                .strip_prefix(project_path)
+                                .unwrap_or(Path::new(file_path));
+                            if !goal.matches_module_file(relative) {
+                                continue;
+                            }
+                            let count = impact_files.lines(file_path);
+                            lines.1 += count;
+                            if impact_files.is_affected(file_path) {
+                                lines.0 += count;
+                            }
+                        }
+                        lines
+                    }
+                };
+
+                let impact_ratio = if total_lines > 0 {
+                    affected_lines as f64 / total_lines as f64
+                } else {
+                    0.0
+                };
+                GoalProgress {
+                    name: goal.name.clone(),
+                    target: goal.target.value(),
+                    impact_ratio,
+                    affected_lines,
+                    total_lines,
+                    met: total_lines > 0 && Ratio::new(affected_lines, total_lines) >= goal.target,
+                    enforce: goal.enforce,
+                }
+            })
+            .collect()
+    }
+
+    /// Impact of each requested variant: the mobile app files it builds, i.e. the
+    /// common ones and its own, but not those specific to another variant
+    fn calculate_variant_impacts(
+        &self,
+        project_path: &str,
+        app_files: &HashMap<Platform, Vec<String>>,
+        impact_files: &ImpactFiles,
+    ) -> Vec<VariantImpact> {
+        let variants = &self.options.detection.variants;
+        variants
+            .iter()
+            .map(|variant| {
+                let mut impact = VariantImpact {
+                    name: variant.clone(),
+                    total_files: 0,
+                    specific_files: 0,
+                    total_lines: 0,
+                    affected_lines: 0,
+                    impact_ratio: 0.0,
+                    platform_ratios: BTreeMap::new(),
+                };
+                for (platform, files) in app_files {
+                    if matches!(platform, Platform::Server | Platform::Desktop | Platform::Web) {
+                        continue;
+                    }
+                    let (mut affected, mut total) = (0, 0);
+                    for file_path in files {
+                        let relative = Path::new(file_path)
+                            .strip_prefix(project_path)
+                            .unwrap_or(Path::new(file_path));
+                        match path_variant(relative, variants) {
+                            Some(owner) if owner != variant => continue,
+                            Some(_) => impact.specific_files += 1,
+                            None => {}
+                        }
+                        let lines = impact_files.lines(file_path);
+                        impact.total_files += 1;
+                        total += lines;
+                        if impact_files.is_affected(file_path) {
+                            affected += lines;
+                        }
+                    }
+                    impact.total_lines += total;
+                    impact.affected_lines += affected;
+                    let ratio = if total > 0 { affected as f64 / total as f64 } else { 0.0 };
+                    impact.platform_ratios.insert(platform.name().to_string(), ratio);
+                }
+                if impact.total_lines > 0 {
+                    impact.impact_ratio = impact.affected_lines as f64 / impact.total_lines as f64;
+                }
+                impact
+            })
+            .collect()
+    }
+
+    /// Break down app usage of shared symbols by stability tier
+    fn calculate_stability_breakdown(
+        &self,
+        symbols: &[Symbol],
+        symbol_usages: &HashMap<String, Vec<crate::domain::SymbolUsage>>,
+        app_files: &HashMap<Platform, Vec<String>>,
+    ) -> Vec<StabilityTierUsage> {
+        let tiers = &self.options.stability_tiers;
+        tally_usage_by(&tiers.names(), |s| tiers.classify(s), symbols, symbol_usages, app_files)
+            .into_iter()
+            .map(|(tier, tally)| StabilityTierUsage {
+                tier,
+                total_symbols: tally.total_symbols,
+                used_symbols: tally.used_symbols,
+                references: tally.references,
+                affected_files: tally.affected_files,
+                platform_references: tally.platform_references,
+            })
+            .collect()
+    }
+
+    /// Break down app usage of shared symbols by category
+    fn calculate_category_breakdown(
+        &self,
+        symbols: &[Symbol],
+        symbol_usages: &HashMap<String, Vec<crate::domain::SymbolUsage>>,
+        app_files: &HashMap<Platform, Vec<String>>,
+    ) -> Vec<CategoryUsage> {
+        let categories = &self.options.categories;
+        tally_usage_by(&categories.names(), |s| categories.classify(s), symbols, symbol_usages, app_files)
+            .into_iter()
+            .map(|(category, tally)| {
+                let definition = categories.get(&category);
+                CategoryUsage {
+                    threshold: definition.and_then(|d| d.threshold),
+                    enforce: definition.is_some_and(|d| d.enforce),
+                    category,
+                    total_symbols: tally.total_symbols,
+                    used_symbols: tally.used_symbols,
+                    used_ratio: if tally.total_symbols > 0 {
+                        tally.used_symbols as f64 / tally.total_symbols as f64
+                    } else {
+                        0.0
+                    },
+                    references: tally.references,
+                    affected_files: tally.affected_files,
+                    platform_references: tally.platform_references,
+                }
+            })
+            .collect()
+    }
+
+    /// Break down app usage of shared symbols by Kotlin package
+    fn calculate_package_breakdown(
+        &self,
+        symbols: &[Symbol],
+        symbol_usages: &HashMap<String, Vec<crate::domain::SymbolUsage>>,
+        app_files: &HashMap<Platform, Vec<String>>,
+    ) -> Vec<PackageUsage> {
+        let file_platforms = file_platforms(app_files);
+
+        let mut packages: BTreeMap<&str, (PackageUsage, HashSet<&str>)> = BTreeMap::new();
+        // Usages are keyed by name, so count each name once per package
+        let mut counted_names: HashSet<(&str, &str)> = HashSet::new();
+
+        for symbol in symbols {
+            let (package, files) = packages.entry(symbol.package.as_str()).or_insert_with(|| {
+                let usage = PackageUsage {
+                    package: symbol.package.clone(),
+                    ..Default::default()
+                };
+                (usage, HashSet::new())
+            });
+            package.total_symbols += 1;
+
+            if !counted_names.insert((symbol.package.as_str(), symbol.name.as_str())) {
+                continue;
+            }
+            let Some(usages) = symbol_usages.get(&symbol.name).filter(|u| !u.is_empty()) else {
+                continue;
+            };
+
+            package.used_symbols += 1;
+            package.references += usages.len();
+            for usage in usages {
+                files.insert(usage.file_path.as_str());
+                if let Some(platform) = file_platforms.get(usage.file_path.as_str()) {
+                    *package
+                        .platform_references
+                        .entry(platform.name().to_string())
+                        .or_default() += 1;
+                }
+            }
         }
 
-        Ok(platform_impacts)
+        let mut breakdown: Vec<PackageUsage> = packages
+            .into_values()
+            .map(|(mut package, files)| {
+                package.affected_files = files.len();
+                package
+            })
+            .collect();
+        breakdown.sort_by(|a, b| {
+            b.references
+                .cmp(&a.references)
+                .then_with(|| a.package.cmp(&b.package))
+        });
+        breakdown
+    }
+
+    /// Owners of the most used shared symbols, by the `OWNERS` files of their modules
+    fn calculate_api_owners(
+        &self,
+        symbols: &[Symbol],
+        symbol_usages: &HashMap<String, Vec<crate::domain::SymbolUsage>>,
+        rules: &[OwnerRule],
+    ) -> Vec<ApiOwnership> {
+        if rules.is_empty() {
+            return Vec::new();
+        }
+
+        // Usages are keyed by name, so the first declaration of a name stands for it
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut owners: Vec<ApiOwnership> = symbols
+            .iter()
+            .filter(|symbol| seen.insert(symbol.name.as_str()))
+            .filter_map(|symbol| {
+                let usages = symbol_usages.get(&symbol.name).map_or(0, Vec::len);
+                (usages > 0).then(|| ApiOwnership {
+                    symbol: symbol.name.clone(),
+                    package: symbol.package.clone(),
+                    owners: OwnerRule::owners_of(rules, symbol).unwrap_or_default().to_vec(),
+                    usages,
+                })
+            })
+            .collect();
+        owners.sort_by(|a, b| b.usages.cmp(&a.usages).then_with(|| a.symbol.cmp(&b.symbol)));
+        owners.truncate(ApiOwnership::LIMIT);
+        owners
     }
 
     /// Calculate top used symbols for a platform
     fn calculate_top_symbols(
         &self,
         symbol_usages: &HashMap<String, Vec<crate::domain::SymbolUsage>>,
-        platform_files: &[String],
+        platform_files: &HashSet<&str>,
     ) -> Vec<(String, usize)> {
         let mut symbol_counts: HashMap<String, usize> = HashMap::new();
 
         for (symbol_name, usages) in symbol_usages {
             let count = usages
                 .iter()
-                .filter(|u| platform_files.contains(&u.file_path))
+                .filter(|u| platform_files.contains(u.file_path.as_str()))
                 .count();
 
             if count > 0 {
@@ -178,10 +1091,206 @@ impl<'a> AnalyzeImpactUseCase<'a> {
             }
         }
 
+        // Ties in name order, so equally used symbols keep their places from run to run
         let mut top_symbols: Vec<(String, usize)> = symbol_counts.into_iter().collect();
-        top_symbols.sort_by(|a, b| b.1.cmp(&a.1));
+        top_symbols.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         top_symbols.truncate(10);
 
         top_symbols
     }
 }
+
+/// Maps each app file to the platform it belongs to
+fn file_platforms(app_files: &HashMap<Platform, Vec<String>>) -> HashMap<&str, &Platform> {
+    app_files
+        .iter()
+        .flat_map(|(platform, files)| files.iter().map(move |f| (f.as_str(), platform)))
+        .collect()
+}
+
+/// An app module as seen by the per-module breakdown
+struct ModuleNode<'m> {
+    name: &'m str,
+    dir: &'m str,
+    dependencies: &'m [String],
+    declares_shared_dependency: bool,
+}
+
+/// Impact figures of one app module
+#[derive(Debug, Clone)]
+struct ModuleMeasure {
+    total_files: usize,
+    total_lines: usize,
+    affected_files: usize,
+    affected_lines: usize,
+    impact_ratio: f64,
+    references: usize,
+    reach: ModuleReach,
+    via_modules: Vec<String>,
+}
+
+impl Default for ModuleMeasure {
+    fn default() -> Self {
+        Self {
+            total_files: 0,
+            total_lines: 0,
+            affected_files: 0,
+            affected_lines: 0,
+            impact_ratio: 0.0,
+            references: 0,
+            reach: ModuleReach::None,
+            via_modules: Vec::new(),
+        }
+    }
+}
+
+/// App usage of the shared symbols in one group
+#[derive(Debug, Clone, Default)]
+struct UsageTally {
+    total_symbols: usize,
+    used_symbols: usize,
+    references: usize,
+    affected_files: usize,
+    platform_references: BTreeMap<String, usize>,
+}
+
+/// Tallies app usage of shared symbols per group, in the order of `names`
+///
+/// Symbols whose group is not listed count toward the first group. Usages are keyed by
+/// name, so the usages of a name declared in several groups count toward the first of
+/// them only; each declaration still counts toward its own group's total.
+fn tally_usage_by<'s>(
+    names: &[&str],
+    classify: impl Fn(&'s Symbol) -> &'s str,
+    symbols: &'s [Symbol],
+    symbol_usages: &HashMap<String, Vec<crate::domain::SymbolUsage>>,
+    app_files: &HashMap<Platform, Vec<String>>,
+) -> Vec<(String, UsageTally)> {
+    let file_platforms = file_platforms(app_files);
+
+    let group_of = |symbol: &'s Symbol| names.iter().position(|n| *n == classify(symbol)).unwrap_or(0);
+    let mut name_groups: HashMap<&str, usize> = HashMap::new();
+    for symbol in symbols {
+        let index = group_of(symbol);
+        name_groups
+            .entry(symbol.name.as_str())
+            .and_modify(|group| *group = (*group).min(index))
+            .or_insert(index);
+    }
+
+    let mut tallies: Vec<UsageTally> = vec![UsageTally::default(); names.len()];
+    let mut group_files: Vec<HashSet<&str>> = vec![HashSet::new(); names.len()];
+    let mut counted_names: HashSet<&str> = HashSet::new();
+
+    for symbol in symbols {
+        let index = group_of(symbol);
+        let tally = &mut tallies[index];
+        tally.total_symbols += 1;
+
+        if name_groups[symbol.name.as_str()] != index || !counted_names.insert(symbol.name.as_str()) {
+            continue;
+        }
+        let Some(usages) = symbol_usages.get(&symbol.name).filter(|u| !u.is_empty()) else {
+            continue;
+        };
+
+        tally.used_symbols += 1;
+        tally.references += usages.len();
+        for usage in usages {
+            group_files[index].insert(usage.file_path.as_str());
+            if let Some(platform) = file_platforms.get(usage.file_path.as_str()) {
+                *tally
+                    .platform_references
+                    .entry(platform.name().to_string())
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    names
+        .iter()
+        .zip(tallies)
+        .zip(group_files)
+        .map(|((name, mut tally), files)| {
+            tally.affected_files = files.len();
+            (name.to_string(), tally)
+        })
+        .collect()
+}
+
+/// App files reached by shared code, and the code lines and functions of every app file
+///
+/// Line and function counts come from usage detection, so files are read only once per run.
+struct ImpactFiles<'r> {
+    direct: HashSet<&'r str>,
+    transitive: HashSet<&'r str>,
+    code_lines: &'r HashMap<String, usize>,
+    functions: &'r HashMap<String, usize>,
+    /// App files that only declare UI layout or match the configured UI layout paths
+    ui_layout: HashSet<&'r str>,
+}
+
+impl ImpactFiles<'_> {
+    fn lines(&self, file_path: &str) -> usize {
+        self.code_lines.get(file_path).copied().unwrap_or(0)
+    }
+
+    fn functions(&self, file_path: &str) -> usize {
+        self.functions.get(file_path).copied().unwrap_or(0)
+    }
+
+    /// Whether the file uses shared code directly or depends on a file that does
+    fn is_affected(&self, file_path: &str) -> bool {
+        self.direct.contains(file_path) || self.transitive.contains(file_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{SymbolType, SymbolUsage, UsageKind};
+
+    fn symbol(name: &str, package: &str, annotations: &[&str]) -> Symbol {
+        Symbol {
+            package: package.to_string(),
+            annotations: annotations.iter().map(|a| a.to_string()).collect(),
+            ..Symbol::new(name, SymbolType::Class, "shared", format!("shared/src/commonMain/kotlin/{}.kt", name))
+        }
+    }
+
+    fn usage(name: &str, file: &str) -> SymbolUsage {
+        SymbolUsage {
+            symbol_name: name.to_string(),
+            file_path: file.to_string(),
+            line_number: 1,
+            context: String::new(),
+            kind: UsageKind::Reference,
+        }
+    }
+
+    #[test]
+    fn test_name_in_several_groups_counts_once() {
+        let tiers = StabilityTiers::default();
+        let symbols = vec![
+            symbol("Session", "com.example.auth", &[]),
+            symbol("Session", "com.example.beta", &["ExperimentalApi"]),
+            symbol("Logger", "com.example", &[]),
+        ];
+        let symbol_usages = HashMap::from([
+            ("Session".to_string(), vec![usage("Session", "app/Login.kt"), usage("Session", "app/Home.kt")]),
+            ("Logger".to_string(), vec![usage("Logger", "app/Login.kt")]),
+        ]);
+        let app_files =
+            HashMap::from([(Platform::Android, vec!["app/Login.kt".to_string(), "app/Home.kt".to_string()])]);
+
+        let tallies = tally_usage_by(&tiers.names(), |s| tiers.classify(s), &symbols, &symbol_usages, &app_files);
+        let tally = |tier: &str| tallies.iter().find(|(name, _)| name == tier).map(|(_, tally)| tally).unwrap();
+        let (experimental, stable) = (tally("experimental"), tally(StabilityTiers::STABLE));
+
+        // `Session` usages go to the first listed tier declaring it; declarations count where they are
+        assert_eq!((experimental.total_symbols, experimental.used_symbols, experimental.references), (1, 1, 2));
+        assert_eq!((stable.total_symbols, stable.used_symbols, stable.references), (2, 1, 1));
+        let references: usize = tallies.iter().map(|(_, tally)| tally.references).sum();
+        assert_eq!(references, 3);
+    }
+}
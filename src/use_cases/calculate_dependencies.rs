@@ -1,7 +1,8 @@
 use anyhow::Result;
 use log::info;
+use std::collections::{HashMap, HashSet};
 
-use crate::domain::DependencyRepository;
+use crate::domain::{DependencyEdge, DependencyRepository, GradleModule, ModuleDependency, SwiftModule};
 
 /// Use Case: Calculate Dependencies
 ///
@@ -25,6 +26,37 @@ impl<'a> CalculateDependenciesUseCase<'a> {
         Ok(())
     }
 
+    /// Find which app modules depend on KMP modules at the build level
+    pub fn module_dependencies(&self, project_path: &str) -> Result<Vec<ModuleDependency>> {
+        let dependencies = self.dependency_repository.build_module_graph(project_path)?;
+        info!("Found {} module dependencies on KMP modules", dependencies.len());
+        Ok(dependencies)
+    }
+
+    /// Find Android Gradle modules and how they depend on each other
+    pub fn android_modules(&self, project_path: &str) -> Result<Vec<GradleModule>> {
+        let modules = self.dependency_repository.find_android_modules(project_path)?;
+        info!("Found {} Android Gradle modules", modules.len());
+        Ok(modules)
+    }
+
+    /// Find Swift modules of the iOS app and how they depend on each other
+    pub fn swift_modules(&self, project_path: &str) -> Result<Vec<SwiftModule>> {
+        let modules = self.dependency_repository.find_swift_modules(project_path)?;
+        info!("Found {} Swift modules", modules.len());
+        Ok(modules)
+    }
+
+    /// Calculate the files each of the given files depends on, directly or transitively
+    pub fn file_dependencies(&self, files: &[String]) -> Result<HashMap<String, HashSet<String>>> {
+        self.dependency_repository.calculate_file_dependencies(files)
+    }
+
+    /// Why a file depends on any of `targets`: the shortest chain of imports leading there
+    pub fn dependency_chain(&self, file: &str, targets: &HashSet<String>) -> Result<Vec<DependencyEdge>> {
+        self.dependency_repository.find_dependency_chain(file, targets)
+    }
+
     /// Calculate transitive dependencies (files that depend on the given files)
     pub fn calculate_transitive(&self, direct_files: &[String]) -> Result<Vec<String>> {
         info!("Calculating transitive dependencies for {} files", direct_files.len());
@@ -1,8 +1,31 @@
 use anyhow::Result;
-use log::info;
-use std::collections::HashMap;
+use log::{info, warn};
+use std::collections::{HashMap, HashSet};
 
-use crate::domain::{Platform, SourceFileRepository, Symbol, SymbolUsage, SymbolUsageRepository};
+use crate::domain::{
+    AnalysisHooks, Diagnostic, InputLimits, Platform, Severity, SourceFile, SourceFileRepository, Symbol,
+    SymbolUsage, SymbolUsageRepository, UsageKind,
+};
+
+/// Rule identifier reported when an app file trips one of the [`InputLimits`]
+pub const PATHOLOGICAL_INPUT_RULE: &str = "pathological-input";
+
+/// Symbol usages found in app code, with findings about files that were not fully scanned
+#[derive(Debug, Default)]
+pub struct UsageDetection {
+    pub usages: HashMap<String, Vec<SymbolUsage>>,
+    /// Import statements of shared symbols, kept apart since importing alone has no impact
+    pub imports: HashMap<String, Vec<SymbolUsage>>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Code lines of every app file, counted while it was read for detection
+    pub code_lines: HashMap<String, usize>,
+    /// Function and method declarations of every app file
+    pub functions: HashMap<String, usize>,
+    /// App files that only declare UI layout, such as Compose screens and SwiftUI views
+    pub ui_layout_files: HashSet<String>,
+    /// App files importing the shared framework as a whole, such as Swift's `import Shared`
+    pub framework_import_files: HashSet<String>,
+}
 
 /// Use Case: Detect Symbol Usage
 ///
@@ -10,6 +33,9 @@ use crate::domain::{Platform, SourceFileRepository, Symbol, SymbolUsage, SymbolU
 pub struct DetectUsageUseCase<'a> {
     source_file_repository: &'a dyn SourceFileRepository,
     symbol_usage_repository: &'a dyn SymbolUsageRepository,
+    resolve_actual_typealiases: bool,
+    limits: InputLimits,
+    hooks: AnalysisHooks,
 }
 
 impl<'a> DetectUsageUseCase<'a> {
@@ -20,43 +46,231 @@ impl<'a> DetectUsageUseCase<'a> {
         Self {
             source_file_repository,
             symbol_usage_repository,
+            resolve_actual_typealiases: false,
+            limits: InputLimits::default(),
+            hooks: AnalysisHooks::default(),
         }
     }
 
+    /// Attribute usages of `actual typealias` targets (e.g. `AndroidFoo`) to the shared alias
+    pub fn with_typealias_resolution(mut self, enabled: bool) -> Self {
+        self.resolve_actual_typealiases = enabled;
+        self
+    }
+
+    /// Replaces the default guards against pathological app files
+    pub fn with_limits(mut self, limits: InputLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Runs the registered usage filters on every usage before it is counted
+    pub fn with_hooks(mut self, hooks: AnalysisHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
     /// Execute the use case
     pub fn execute(
         &self,
         app_files_by_platform: &HashMap<Platform, Vec<String>>,
         symbols: &[Symbol],
-    ) -> Result<HashMap<String, Vec<SymbolUsage>>> {
+    ) -> Result<UsageDetection> {
         info!("Detecting symbol usage across platforms");
 
         let mut all_usages: HashMap<String, Vec<SymbolUsage>> = HashMap::new();
+        let mut imports: HashMap<String, Vec<SymbolUsage>> = HashMap::new();
+        let mut diagnostics = Vec::new();
+        let mut code_lines = HashMap::new();
+        let mut functions = HashMap::new();
+        let mut ui_layout_files = HashSet::new();
+        let mut framework_import_files = HashSet::new();
 
-        for (platform, file_paths) in app_files_by_platform {
+        let alias_targets = self.alias_targets(symbols);
+        let mut search_symbols = symbols.to_vec();
+        search_symbols.extend(symbols.iter().filter_map(|s| {
+            let target = s.actual_target.as_ref().filter(|t| alias_targets.contains_key(*t))?;
+            Some(Symbol {
+                name: target.clone(),
+                ..s.clone()
+            })
+        }));
+
+        // Platforms in name order, so usage lists and diagnostics come out the same every run
+        let mut platforms: Vec<_> = app_files_by_platform.iter().collect();
+        platforms.sort_by_key(|(platform, _)| platform.name());
+        for (platform, file_paths) in platforms {
             info!("Analyzing {} {} files", file_paths.len(), platform.name());
 
             for file_path in file_paths {
                 // Read source file
                 let source_file = self.source_file_repository.read_source_file(file_path)?;
+                code_lines.insert(
+                    file_path.clone(),
+                    self.source_file_repository
+                        .count_code_lines(&source_file.content, platform.clone()),
+                );
+                functions.insert(
+                    file_path.clone(),
+                    self.source_file_repository
+                        .count_functions(&source_file.content, platform.clone()),
+                );
+                if self.source_file_repository.is_ui_layout(&source_file.content, platform.clone()) {
+                    ui_layout_files.insert(file_path.clone());
+                }
+                if self
+                    .source_file_repository
+                    .imports_shared_framework(&source_file.content, platform.clone())
+                {
+                    framework_import_files.insert(file_path.clone());
+                }
+                let Some(source_file) = self.guard_source_file(source_file, &mut diagnostics)
+                else {
+                    continue;
+                };
 
                 // Detect symbol usage
-                let usages = self.symbol_usage_repository.detect_symbol_usage(&source_file, symbols)?;
+                let usages = self
+                    .symbol_usage_repository
+                    .detect_symbol_usage(&source_file, &search_symbols)?;
 
-                // Aggregate usages by symbol name
-                for usage in usages {
+                // Aggregate usages by symbol name, crediting alias targets to the shared symbol.
+                // A credited usage on a line that already uses the shared symbol, directly or
+                // through another target, is the same use and is dropped; direct usages are
+                // all kept, as the matcher reports them.
+                let mut seen_lines: HashSet<(String, usize)> = usages
+                    .iter()
+                    .filter(|usage| {
+                        usage.kind != UsageKind::ImportOnly && !alias_targets.contains_key(&usage.symbol_name)
+                    })
+                    .map(|usage| (usage.symbol_name.clone(), usage.line_number))
+                    .collect();
+                let mut file_counts: HashMap<String, usize> = HashMap::new();
+                for mut usage in usages {
+                    let aliased = match alias_targets.get(&usage.symbol_name) {
+                        Some(shared_name) => {
+                            usage.symbol_name = shared_name.clone();
+                            true
+                        }
+                        None => false,
+                    };
+                    if !self.hooks.keep_usage(&mut usage) {
+                        continue;
+                    }
+                    if usage.kind == UsageKind::ImportOnly {
+                        imports.entry(usage.symbol_name.clone()).or_default().push(usage);
+                        continue;
+                    }
+                    if aliased && !seen_lines.insert((usage.symbol_name.clone(), usage.line_number)) {
+                        continue;
+                    }
+                    let count = file_counts.entry(usage.symbol_name.clone()).or_default();
+                    *count += 1;
+                    if *count > self.limits.max_usages_per_symbol_per_file {
+                        continue;
+                    }
+                    usage.context = truncate_chars(&usage.context, self.limits.max_context_length);
                     all_usages
                         .entry(usage.symbol_name.clone())
-                        .or_insert_with(Vec::new)
+                        .or_default()
                         .push(usage);
                 }
+
+                let mut capped: Vec<(String, usize)> = file_counts
+                    .into_iter()
+                    .filter(|(_, count)| *count > self.limits.max_usages_per_symbol_per_file)
+                    .collect();
+                capped.sort();
+                diagnostics.extend(capped.into_iter().map(|(name, count)| Diagnostic {
+                    rule: PATHOLOGICAL_INPUT_RULE.to_string(),
+                    severity: Severity::Warning,
+                    message: format!(
+                        "Kept {} of {} usage locations of `{}` in {}",
+                        self.limits.max_usages_per_symbol_per_file, count, name, file_path
+                    ),
+                    symbol: Some(name),
+                }));
             }
         }
 
         let total_usages: usize = all_usages.values().map(|v| v.len()).sum();
         info!("Found {} total symbol usages", total_usages);
 
-        Ok(all_usages)
+        Ok(UsageDetection {
+            usages: all_usages,
+            imports,
+            diagnostics,
+            code_lines,
+            functions,
+            ui_layout_files,
+            framework_import_files,
+        })
+    }
+
+    /// Applies the file size and line length limits before a file is scanned
+    ///
+    /// Oversized files are skipped. Overlong lines (typically minified code) are
+    /// blanked so line numbers stay correct while the scan stays cheap.
+    fn guard_source_file(
+        &self,
+        mut source_file: SourceFile,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Option<SourceFile> {
+        let limits = &self.limits;
+        let mut finding = |message: String| {
+            warn!("{}", message);
+            diagnostics.push(Diagnostic {
+                rule: PATHOLOGICAL_INPUT_RULE.to_string(),
+                severity: Severity::Warning,
+                message,
+                symbol: None,
+            });
+        };
+
+        if source_file.content.len() > limits.max_file_bytes {
+            finding(format!(
+                "Skipped {}: {} bytes exceeds the {} byte limit",
+                source_file.path,
+                source_file.content.len(),
+                limits.max_file_bytes
+            ));
+            return None;
+        }
+
+        let overlong = source_file
+            .content
+            .lines()
+            .filter(|line| line.len() > limits.max_line_length)
+            .count();
+        if overlong > 0 {
+            finding(format!(
+                "Skipped {} line(s) longer than {} bytes in {} (minified or generated code?)",
+                overlong, limits.max_line_length, source_file.path
+            ));
+            source_file.content = source_file
+                .content
+                .lines()
+                .map(|line| if line.len() > limits.max_line_length { "" } else { line })
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+
+        Some(source_file)
+    }
+
+    /// Maps `actual typealias` target names to the shared alias they implement
+    fn alias_targets(&self, symbols: &[Symbol]) -> HashMap<String, String> {
+        if !self.resolve_actual_typealiases {
+            return HashMap::new();
+        }
+
+        let shared_names: HashSet<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        symbols
+            .iter()
+            .filter_map(|s| s.actual_target.as_ref().map(|target| (target, &s.name)))
+            .filter(|(target, _)| !shared_names.contains(target.as_str()))
+            .map(|(target, name)| (target.clone(), name.clone()))
+            .collect()
     }
 
     /// Get files that directly use symbols
@@ -75,3 +289,11 @@ impl<'a> DetectUsageUseCase<'a> {
         affected_files.into_iter().collect()
     }
 }
+
+/// Keeps at most `max` characters of `text`
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text.to_string(),
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::Result;
+use log::info;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use crate::domain::{
+    is_test_source_set, module_source_set, source_set_depends_on, ActualizationCoverage, Symbol, SymbolRepository,
+    SymbolType,
+};
+
+/// Use Case: Expect/Actual Coverage
+///
+/// Responsibility: Find, for each platform source set of a KMP module, the `expect`
+/// declarations it sees without an `actual` of the same name
+///
+/// Only leaf source sets are checked, the ones no other source set of the module depends
+/// on: an `actual` in `iosMain` serves `iosArm64Main`, while `iosMain` itself needs none
+/// when its targets each have one. Test source sets are left out.
+pub struct ExpectActualCoverageUseCase<'a> {
+    symbol_repository: &'a dyn SymbolRepository,
+}
+
+impl<'a> ExpectActualCoverageUseCase<'a> {
+    pub fn new(symbol_repository: &'a dyn SymbolRepository) -> Self {
+        Self { symbol_repository }
+    }
+
+    /// Execute the use case
+    pub fn execute(&self, kmp_file_paths: &[String]) -> Result<Vec<ActualizationCoverage>> {
+        let symbols = self.symbol_repository.extract_kmp_symbols(kmp_file_paths)?;
+        let coverage = Self::coverage(kmp_file_paths, &symbols);
+
+        let missing: usize = coverage.iter().map(|c| c.missing.len()).sum();
+        if missing > 0 {
+            info!("{} expect declaration(s) lack an actual on some source set", missing);
+        }
+        Ok(coverage)
+    }
+
+    fn coverage(kmp_file_paths: &[String], symbols: &[Symbol]) -> Vec<ActualizationCoverage> {
+        fn production(path: &str) -> Option<(&str, &str)> {
+            module_source_set(path).filter(|(_, set)| !is_test_source_set(set))
+        }
+
+        let mut source_sets: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for (module, source_set) in kmp_file_paths.iter().filter_map(|path| production(path)) {
+            if source_set != "commonMain" {
+                source_sets.entry(module).or_default().insert(source_set);
+            }
+        }
+
+        // (module, package, name) of each actual, by the source set providing it
+        let mut actuals: HashSet<(&str, &str, &str, &str)> = HashSet::new();
+        let mut expects: BTreeMap<&str, Vec<(&Symbol, &str)>> = BTreeMap::new();
+        for symbol in symbols {
+            let Some((_, source_set)) = production(&symbol.file_path) else {
+                continue;
+            };
+            if symbol.symbol_type == SymbolType::Actual || symbol.actual_target.is_some() {
+                actuals.insert((&symbol.module, &symbol.package, &symbol.name, source_set));
+            } else if symbol.symbol_type == SymbolType::Expect {
+                expects.entry(&symbol.module).or_default().push((symbol, source_set));
+            }
+        }
+
+        let mut coverage = Vec::new();
+        for (module, expects) in expects {
+            let Some(sets) = source_sets.get(module) else {
+                continue;
+            };
+            let leaves = sets
+                .iter()
+                .filter(|set| !sets.iter().any(|other| other != *set && source_set_depends_on(other, set)));
+            for &leaf in leaves {
+                let mut expected = BTreeSet::new();
+                let mut missing = BTreeSet::new();
+                for &(symbol, expect_set) in &expects {
+                    if leaf == expect_set || !source_set_depends_on(leaf, expect_set) {
+                        continue;
+                    }
+                    let qualified = match symbol.package.as_str() {
+                        "" => symbol.name.clone(),
+                        package => format!("{}.{}", package, symbol.name),
+                    };
+                    let actualized = actuals.iter().any(|&(m, package, name, actual_set)| {
+                        (m, package, name) == (module, symbol.package.as_str(), symbol.name.as_str())
+                            && source_set_depends_on(leaf, actual_set)
+                    });
+                    if !actualized {
+                        missing.insert(qualified.clone());
+                    }
+                    expected.insert(qualified);
+                }
+                if !expected.is_empty() {
+                    coverage.push(ActualizationCoverage {
+                        module: module.to_string(),
+                        source_set: leaf.to_string(),
+                        expected: expected.len(),
+                        missing: missing.into_iter().collect(),
+                    });
+                }
+            }
+        }
+        coverage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, symbol_type: SymbolType, source_set: &str) -> Symbol {
+        Symbol {
+            package: "com.example".to_string(),
+            ..Symbol::new(name, symbol_type, "shared", format!("/repo/shared/src/{}/kotlin/Platform.kt", source_set))
+        }
+    }
+
+    #[test]
+    fn test_actualization_coverage() {
+        let files: Vec<String> = ["commonMain", "androidMain", "iosMain", "iosArm64Main", "jvmMain", "commonTest"]
+            .iter()
+            .map(|set| format!("/repo/shared/src/{}/kotlin/Platform.kt", set))
+            .collect();
+        let mut date = symbol("PlatformDate", SymbolType::TypeAlias, "androidMain");
+        date.actual_target = Some("Date".to_string());
+        let symbols = vec![
+            symbol("Platform", SymbolType::Expect, "commonMain"),
+            symbol("PlatformDate", SymbolType::Expect, "commonMain"),
+            symbol("Platform", SymbolType::Actual, "androidMain"),
+            symbol("Platform", SymbolType::Actual, "iosMain"),
+            date,
+            symbol("PlatformDate", SymbolType::Actual, "commonTest"),
+        ];
+
+        let coverage = ExpectActualCoverageUseCase::coverage(&files, &symbols);
+        let found: Vec<(&str, usize, Vec<&str>)> = coverage
+            .iter()
+            .map(|c| (c.source_set.as_str(), c.expected, c.missing.iter().map(String::as_str).collect()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("androidMain", 2, vec![]),
+                ("iosArm64Main", 2, vec!["com.example.PlatformDate"]),
+                ("jvmMain", 2, vec!["com.example.Platform", "com.example.PlatformDate"]),
+            ]
+        );
+        assert_eq!(coverage[1].actualized(), 1);
+    }
+}
@@ -1,35 +1,112 @@
 use anyhow::Result;
-use log::info;
+use log::{debug, info};
 
-use crate::domain::{Symbol, SymbolRepository};
+use crate::domain::{AnalysisHooks, Symbol, SymbolRepository};
 
 /// Use Case: Extract KMP Symbols
 ///
 /// Responsibility: Extract all public symbols from KMP source files
+///
+/// Symbols declared in test source sets are left out unless
+/// [`with_test_symbols`](Self::with_test_symbols) is set, and `internal`
+/// symbols unless [`with_internal_symbols`](Self::with_internal_symbols) is.
 pub struct ExtractSymbolsUseCase<'a> {
     symbol_repository: &'a dyn SymbolRepository,
+    hooks: AnalysisHooks,
+    include_test_symbols: bool,
+    include_internal_symbols: bool,
 }
 
 impl<'a> ExtractSymbolsUseCase<'a> {
     pub fn new(symbol_repository: &'a dyn SymbolRepository) -> Self {
-        Self { symbol_repository }
+        Self {
+            symbol_repository,
+            hooks: AnalysisHooks::default(),
+            include_test_symbols: false,
+            include_internal_symbols: false,
+        }
+    }
+
+    /// Keeps symbols declared in test source sets (`commonTest`, `androidUnitTest`, ...)
+    pub fn with_test_symbols(mut self, include: bool) -> Self {
+        self.include_test_symbols = include;
+        self
+    }
+
+    /// Keeps `internal` symbols, which only the shared module itself and its tests can use
+    pub fn with_internal_symbols(mut self, include: bool) -> Self {
+        self.include_internal_symbols = include;
+        self
+    }
+
+    /// Runs the registered symbol filters on everything extracted
+    pub fn with_hooks(mut self, hooks: AnalysisHooks) -> Self {
+        self.hooks = hooks;
+        self
     }
 
     /// Execute the use case
     pub fn execute(&self, kmp_file_paths: &[String]) -> Result<Vec<Symbol>> {
         info!("Extracting symbols from {} KMP files", kmp_file_paths.len());
 
-        let symbols = self.symbol_repository.extract_kmp_symbols(kmp_file_paths)?;
+        let symbols = self.filter(self.symbol_repository.extract_kmp_symbols(kmp_file_paths)?);
 
         info!("Extracted {} symbols", symbols.len());
         Ok(symbols)
     }
+
+    /// Find the shared resource accessors of the project's KMP modules
+    pub fn resources(&self, project_path: &str) -> Result<Vec<Symbol>> {
+        let resources = self.filter(self.symbol_repository.extract_resource_symbols(project_path)?);
+
+        info!("Found {} shared resources", resources.len());
+        Ok(resources)
+    }
+
+    /// Find the types SQLDelight and Apollo generate from the KMP modules' sources
+    pub fn generated(&self, project_path: &str) -> Result<Vec<Symbol>> {
+        let generated =
+            self.filter(self.symbol_repository.extract_generated_symbols(project_path)?);
+
+        info!("Found {} generated shared types", generated.len());
+        Ok(generated)
+    }
+
+    /// Gives shared classifiers the Objective-C and Swift names the project's framework
+    /// headers declare, returning how many it named
+    pub fn export_names(&self, project_path: &str, kmp_file_paths: &[String], symbols: &mut [Symbol]) -> Result<usize> {
+        let headers = self.symbol_repository.find_framework_headers(project_path, kmp_file_paths)?;
+        let named: usize = headers.iter().map(|header| header.export_names(symbols)).sum();
+        if !headers.is_empty() {
+            info!("Read exported names of {} symbols from {} framework headers", named, headers.len());
+        }
+        Ok(named)
+    }
+
+    /// Drops test-only and internal symbols, then runs the registered symbol filters
+    fn filter(&self, mut symbols: Vec<Symbol>) -> Vec<Symbol> {
+        if !self.include_internal_symbols {
+            let before = symbols.len();
+            symbols.retain(|s| s.is_public);
+            if symbols.len() < before {
+                debug!("Skipped {} internal symbols", before - symbols.len());
+            }
+        }
+        if !self.include_test_symbols {
+            let before = symbols.len();
+            symbols.retain(|s| !s.is_test_only());
+            if symbols.len() < before {
+                debug!("Skipped {} symbols declared in test source sets", before - symbols.len());
+            }
+        }
+        self.hooks.filter_symbols(symbols)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::{SymbolType};
+    use crate::domain::{SymbolFilter, SymbolType};
 
     struct MockSymbolRepository;
 
@@ -37,12 +114,13 @@ mod tests {
         fn extract_kmp_symbols(&self, _paths: &[String]) -> Result<Vec<Symbol>> {
             Ok(vec![
                 Symbol {
-                    name: "UserRepository".to_string(),
-                    symbol_type: SymbolType::Class,
-                    module: "shared".to_string(),
-                    file_path: "shared/src/User.kt".to_string(),
-                    is_public: true,
-                }
+                    package: "com.example".to_string(),
+                    ..Symbol::new("UserRepository", SymbolType::Class, "shared", "shared/src/User.kt")
+                },
+                Symbol {
+                    package: "com.example".to_string(),
+                    ..Symbol::new("FakeUserRepository", SymbolType::Class, "shared", "shared/src/commonTest/kotlin/FakeUserRepository.kt")
+                },
             ])
         }
     }
@@ -52,8 +130,55 @@ mod tests {
         let repo = MockSymbolRepository;
         let use_case = ExtractSymbolsUseCase::new(&repo);
 
-        let symbols = use_case.execute(&vec!["test.kt".to_string()]).unwrap();
+        let symbols = use_case.execute(&["test.kt".to_string()]).unwrap();
         assert_eq!(symbols.len(), 1);
         assert_eq!(symbols[0].name, "UserRepository");
     }
+
+    #[test]
+    fn test_test_symbols_are_opt_in() {
+        let repo = MockSymbolRepository;
+
+        let symbols = ExtractSymbolsUseCase::new(&repo)
+            .with_test_symbols(true)
+            .execute(&["test.kt".to_string()])
+            .unwrap();
+        assert_eq!(symbols.len(), 2);
+        assert!(symbols[1].is_test_only());
+        assert!(!symbols[0].is_test_only());
+    }
+
+    struct RenameRepositories;
+
+    impl SymbolFilter for RenameRepositories {
+        fn filter_symbol(&self, symbol: &mut Symbol) -> bool {
+            symbol.name = symbol.name.replace("Repository", "Repo");
+            true
+        }
+    }
+
+    struct DropAll;
+
+    impl SymbolFilter for DropAll {
+        fn filter_symbol(&self, _symbol: &mut Symbol) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_symbol_filters() {
+        let repo = MockSymbolRepository;
+
+        let renamed = ExtractSymbolsUseCase::new(&repo)
+            .with_hooks(AnalysisHooks::new().with_symbol_filter(RenameRepositories))
+            .execute(&["test.kt".to_string()])
+            .unwrap();
+        assert_eq!(renamed[0].name, "UserRepo");
+
+        let dropped = ExtractSymbolsUseCase::new(&repo)
+            .with_hooks(AnalysisHooks::new().with_symbol_filter(DropAll))
+            .execute(&["test.kt".to_string()])
+            .unwrap();
+        assert!(dropped.is_empty());
+    }
 }
@@ -0,0 +1,184 @@
+use anyhow::Result;
+use log::info;
+
+use crate::domain::{
+    Diagnostic, Parity, ParitySummary, Severity, SymbolHistory, SymbolHistoryRepository,
+    SymbolSighting,
+};
+
+/// Rule identifier reported on single-platform drift findings
+pub const SINGLE_PLATFORM_DRIFT_RULE: &str = "single-platform-drift";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// When a new shared symbol that only one platform uses should be flagged
+#[derive(Debug, Clone)]
+pub struct DriftPolicy {
+    /// Flag once the symbol has been seen in at least this many runs
+    pub after_runs: Option<u32>,
+    /// Flag once the symbol was first seen at least this many days ago
+    pub after_days: Option<u64>,
+    pub severity: Severity,
+}
+
+impl Default for DriftPolicy {
+    fn default() -> Self {
+        Self {
+            after_runs: Some(5),
+            after_days: None,
+            severity: Severity::Warning,
+        }
+    }
+}
+
+/// Use Case: Lint Single-Platform Drift
+///
+/// Responsibility: Track when shared symbols appear and flag new ones that
+/// still have a single consuming platform once the policy's grace period ends
+pub struct LintDriftUseCase<'a> {
+    history_repository: &'a dyn SymbolHistoryRepository,
+    policy: DriftPolicy,
+}
+
+impl<'a> LintDriftUseCase<'a> {
+    pub fn new(history_repository: &'a dyn SymbolHistoryRepository, policy: DriftPolicy) -> Self {
+        Self {
+            history_repository,
+            policy,
+        }
+    }
+
+    /// Records this run in the symbol history and returns drift findings
+    ///
+    /// `now` is a Unix timestamp in seconds.
+    pub fn execute(&self, parity: &ParitySummary, now: u64) -> Result<Vec<Diagnostic>> {
+        let previous = self.history_repository.load()?;
+        let is_baseline_run = previous.is_none();
+        let previous = previous.unwrap_or_default();
+
+        // Symbols that disappeared are dropped so a re-added symbol counts as new
+        let mut history = SymbolHistory::default();
+        for symbol in &parity.symbols {
+            let sighting = match previous.symbols.get(&symbol.symbol_name) {
+                Some(seen) => SymbolSighting {
+                    runs: seen.runs + 1,
+                    ..seen.clone()
+                },
+                None => SymbolSighting {
+                    first_seen: now,
+                    runs: 1,
+                    baseline: is_baseline_run,
+                },
+            };
+            history.symbols.insert(symbol.symbol_name.clone(), sighting);
+        }
+        self.history_repository.save(&history)?;
+
+        if is_baseline_run {
+            info!("Recorded symbol history baseline ({} symbols)", history.symbols.len());
+        }
+
+        let diagnostics: Vec<Diagnostic> = parity
+            .symbols
+            .iter()
+            .filter_map(|symbol| {
+                let sighting = &history.symbols[&symbol.symbol_name];
+                let platform = match symbol.parity {
+                    Parity::AndroidOnly => "Android",
+                    Parity::IosOnly => "iOS",
+                    Parity::BothPlatforms | Parity::Unused => return None,
+                };
+                if sighting.baseline || !self.grace_period_over(sighting, now) {
+                    return None;
+                }
+
+                let days = now.saturating_sub(sighting.first_seen) / SECONDS_PER_DAY;
+                Some(Diagnostic {
+                    rule: SINGLE_PLATFORM_DRIFT_RULE.to_string(),
+                    severity: self.policy.severity,
+                    message: format!(
+                        "`{}` was added {} day(s) ago ({} runs) and is still only used on {}",
+                        symbol.symbol_name, days, sighting.runs, platform
+                    ),
+                    symbol: Some(symbol.symbol_name.clone()),
+                })
+            })
+            .collect();
+
+        info!("Single-platform drift: {} finding(s)", diagnostics.len());
+        Ok(diagnostics)
+    }
+
+    fn grace_period_over(&self, sighting: &SymbolSighting, now: u64) -> bool {
+        let runs_over = self.policy.after_runs.is_some_and(|runs| sighting.runs >= runs);
+        let days_over = self.policy.after_days.is_some_and(|days| {
+            now.saturating_sub(sighting.first_seen) >= days * SECONDS_PER_DAY
+        });
+        runs_over || days_over
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::SymbolParity;
+    use std::sync::Mutex;
+
+    struct MemoryHistoryRepository {
+        history: Mutex<Option<SymbolHistory>>,
+    }
+
+    impl SymbolHistoryRepository for MemoryHistoryRepository {
+        fn load(&self) -> Result<Option<SymbolHistory>> {
+            Ok(self.history.lock().unwrap().clone())
+        }
+
+        fn save(&self, history: &SymbolHistory) -> Result<()> {
+            *self.history.lock().unwrap() = Some(history.clone());
+            Ok(())
+        }
+    }
+
+    fn parity(symbols: &[(&str, Parity)]) -> ParitySummary {
+        ParitySummary {
+            symbols: symbols
+                .iter()
+                .map(|(name, parity)| SymbolParity {
+                    symbol_name: name.to_string(),
+                    parity: *parity,
+                    android_references: 0,
+                    ios_references: 0,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_flags_new_single_platform_symbols_after_grace_runs() {
+        let repo = MemoryHistoryRepository {
+            history: Mutex::new(None),
+        };
+        let policy = DriftPolicy {
+            after_runs: Some(2),
+            ..Default::default()
+        };
+        let use_case = LintDriftUseCase::new(&repo, policy);
+
+        // Baseline run: existing symbols are never flagged
+        let baseline = parity(&[("Legacy", Parity::AndroidOnly)]);
+        assert!(use_case.execute(&baseline, 0).unwrap().is_empty());
+
+        let current = parity(&[
+            ("Legacy", Parity::AndroidOnly),
+            ("NewApi", Parity::IosOnly),
+            ("Shared", Parity::BothPlatforms),
+        ]);
+        assert!(use_case.execute(&current, 10).unwrap().is_empty());
+
+        let diagnostics = use_case.execute(&current, 20).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].symbol.as_deref(), Some("NewApi"));
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+}
@@ -1,12 +1,20 @@
-/// Use Cases layer - Application business rules
-/// Orchestrates domain entities and repository interfaces
+//! Use Cases layer - Application business rules
+//! Orchestrates domain entities and repository interfaces
 
 pub mod analyze_impact;
 pub mod extract_symbols;
 pub mod detect_usage;
 pub mod calculate_dependencies;
+pub mod lint_drift;
+pub mod track_history;
+pub mod record_stats;
+pub mod expect_actual_coverage;
 
-pub use analyze_impact::AnalyzeImpactUseCase;
+pub use analyze_impact::{AnalysisOptions, AnalyzeImpactUseCase};
 pub use extract_symbols::ExtractSymbolsUseCase;
-pub use detect_usage::DetectUsageUseCase;
+pub use detect_usage::{DetectUsageUseCase, UsageDetection};
 pub use calculate_dependencies::CalculateDependenciesUseCase;
+pub use lint_drift::{DriftPolicy, LintDriftUseCase};
+pub use track_history::TrackHistoryUseCase;
+pub use record_stats::RecordRunStatsUseCase;
+pub use expect_actual_coverage::ExpectActualCoverageUseCase;
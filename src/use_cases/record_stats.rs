@@ -0,0 +1,28 @@
+use anyhow::Result;
+use log::info;
+
+use crate::domain::{RunSample, RunStatsRepository};
+
+/// Use Case: Record Run Statistics
+///
+/// Responsibility: Add this run to the anonymous, machine-local statistics
+/// users can attach to bug reports
+pub struct RecordRunStatsUseCase<'a> {
+    stats_repository: &'a dyn RunStatsRepository,
+}
+
+impl<'a> RecordRunStatsUseCase<'a> {
+    pub fn new(stats_repository: &'a dyn RunStatsRepository) -> Self {
+        Self { stats_repository }
+    }
+
+    /// `now` is a Unix timestamp in seconds.
+    pub fn execute(&self, sample: &RunSample, now: u64) -> Result<()> {
+        let mut stats = self.stats_repository.load()?;
+        stats.record(sample, env!("CARGO_PKG_VERSION"), now);
+        self.stats_repository.save(&stats)?;
+
+        info!("Recorded run statistics ({} runs)", stats.runs);
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use anyhow::Result;
+use log::info;
+
+use crate::domain::{ImpactAnalysis, RunHistoryRepository, RunSnapshot};
+
+/// Use Case: Track Run History
+///
+/// Responsibility: Append the headline numbers of this run to the run history,
+/// keeping only the most recent runs, so reports can chart trends
+pub struct TrackHistoryUseCase<'a> {
+    history_repository: &'a dyn RunHistoryRepository,
+    max_runs: usize,
+}
+
+impl<'a> TrackHistoryUseCase<'a> {
+    pub fn new(history_repository: &'a dyn RunHistoryRepository, max_runs: usize) -> Self {
+        Self {
+            history_repository,
+            max_runs,
+        }
+    }
+
+    /// Records this run and returns the history including it, oldest first
+    ///
+    /// `now` is a Unix timestamp in seconds.
+    pub fn execute(&self, analysis: &ImpactAnalysis, now: u64) -> Result<Vec<RunSnapshot>> {
+        let mut runs = self.history_repository.load()?;
+        runs.push(RunSnapshot::from_analysis(analysis, now));
+        if runs.len() > self.max_runs {
+            runs.drain(..runs.len() - self.max_runs);
+        }
+        self.history_repository.save(&runs)?;
+
+        info!("Recorded run history ({} runs)", runs.len());
+        Ok(runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ApiChurn, ApiSymbol, Symbol, SymbolType};
+    use std::sync::Mutex;
+
+    struct MemoryRunHistoryRepository {
+        runs: Mutex<Vec<RunSnapshot>>,
+    }
+
+    impl RunHistoryRepository for MemoryRunHistoryRepository {
+        fn load(&self) -> Result<Vec<RunSnapshot>> {
+            Ok(self.runs.lock().unwrap().clone())
+        }
+
+        fn save(&self, runs: &[RunSnapshot]) -> Result<()> {
+            *self.runs.lock().unwrap() = runs.to_vec();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_keeps_most_recent_runs() {
+        let repo = MemoryRunHistoryRepository {
+            runs: Mutex::new(Vec::new()),
+        };
+        let use_case = TrackHistoryUseCase::new(&repo, 2);
+        let analysis = ImpactAnalysis {
+            impact_ratio: 0.25,
+            ..Default::default()
+        };
+
+        for now in [10, 20, 30] {
+            use_case.execute(&analysis, now).unwrap();
+        }
+
+        let runs = repo.load().unwrap();
+        let timestamps: Vec<u64> = runs.iter().map(|r| r.generated_at).collect();
+        assert_eq!(timestamps, vec![20, 30]);
+        assert_eq!(runs[1].impact_ratio, 0.25);
+    }
+
+    fn symbol(name: &str, file: &str) -> Symbol {
+        Symbol {
+            package: "com.example".to_string(),
+            ..Symbol::new(name, SymbolType::Class, "shared", format!("/repo/shared/src/commonMain/kotlin/{}", file))
+        }
+    }
+
+    #[test]
+    fn test_api_churn_between_runs() {
+        let repo = MemoryRunHistoryRepository {
+            runs: Mutex::new(Vec::new()),
+        };
+        let use_case = TrackHistoryUseCase::new(&repo, 10);
+        let before = ImpactAnalysis {
+            impact_ratio: 0.2,
+            symbols: vec![
+                symbol("UserRepository", "UserRepository.kt"),
+                symbol("Logger", "Logger.kt"),
+                symbol("Legacy", "Compat.kt"),
+            ],
+            ..Default::default()
+        };
+        let after = ImpactAnalysis {
+            impact_ratio: 0.25,
+            symbols: vec![
+                symbol("UserRepository", "UserRepository.kt"),
+                symbol("AppLogger", "AppLogger.kt"),
+                symbol("Analytics", "Tracking.kt"),
+            ],
+            ..Default::default()
+        };
+
+        use_case.execute(&before, 10).unwrap();
+        let runs = use_case.execute(&after, 20).unwrap();
+        let churn = ApiChurn::between(&runs[0], &runs[1]).unwrap();
+
+        let names = |symbols: &[ApiSymbol]| symbols.iter().map(|s| s.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&churn.added), vec!["Analytics"]);
+        assert_eq!(names(&churn.removed), vec!["Legacy"]);
+        assert_eq!(churn.renamed.len(), 1);
+        assert_eq!(churn.renamed[0].from.qualified_name(), "com.example.Logger");
+        assert_eq!(churn.renamed[0].to.name, "AppLogger");
+        assert_eq!(churn.since, 10);
+        assert!((churn.impact_ratio_change - 0.05).abs() < 1e-9);
+
+        // Runs recorded before the API was kept have nothing to compare against
+        let mut legacy = runs[0].clone();
+        legacy.api = None;
+        assert!(ApiChurn::between(&legacy, &runs[1]).is_none());
+    }
+}
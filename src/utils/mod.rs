@@ -1,59 +1,59 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+
+use crate::adapters::file_system::FileSystem;
+use crate::domain::LineRange;
 
 /// File system utility functions
 pub struct FileUtils;
 
 impl FileUtils {
     /// Finds files matching a specific pattern in a directory
-    pub fn find_files(root: &Path, pattern: &str) -> Vec<PathBuf> {
-        WalkDir::new(root)
+    pub fn find_files(fs: &dyn FileSystem, root: &Path, pattern: &str) -> Vec<PathBuf> {
+        fs.walk(root, usize::MAX)
             .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
+            .filter(|e| !e.is_dir)
             .filter(|e| {
-                e.path()
+                e.path
                     .file_name()
                     .and_then(|n| n.to_str())
                     .map(|n| n.contains(pattern))
                     .unwrap_or(false)
             })
-            .map(|e| e.path().to_path_buf())
+            .map(|e| e.path)
             .collect()
     }
 
     /// Finds Kotlin source files
-    pub fn find_kotlin_files(root: &Path) -> Vec<PathBuf> {
-        WalkDir::new(root)
+    pub fn find_kotlin_files(fs: &dyn FileSystem, root: &Path) -> Vec<PathBuf> {
+        fs.walk(root, usize::MAX)
             .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
+            .filter(|e| !e.is_dir)
             .filter(|e| {
-                e.path()
+                e.path
                     .extension()
                     .and_then(|ext| ext.to_str())
                     .map(|ext| ext == "kt" || ext == "kts")
                     .unwrap_or(false)
             })
-            .map(|e| e.path().to_path_buf())
+            .map(|e| e.path)
             .collect()
     }
 
     /// Finds Gradle build files
     #[allow(dead_code)]
-    pub fn find_gradle_files(root: &Path) -> Vec<PathBuf> {
-        WalkDir::new(root)
+    pub fn find_gradle_files(fs: &dyn FileSystem, root: &Path) -> Vec<PathBuf> {
+        fs.walk(root, usize::MAX)
             .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
+            .filter(|e| !e.is_dir)
             .filter(|e| {
-                e.path()
+                e.path
                     .file_name()
                     .and_then(|n| n.to_str())
                     .map(|n| n == "build.gradle" || n == "build.gradle.kts")
                     .unwrap_or(false)
             })
-            .map(|e| e.path().to_path_buf())
+            .map(|e| e.path)
             .collect()
     }
 }
@@ -63,6 +63,7 @@ impl FileUtils {
 pub struct GitUtils;
 
 #[allow(dead_code)]
+#[cfg(not(target_arch = "wasm32"))]
 impl GitUtils {
     /// Checks if a git repository exists
     pub fn is_git_repo(path: &Path) -> bool {
@@ -75,4 +76,98 @@ impl GitUtils {
         let head = repo.head().ok()?;
         head.shorthand().map(|s| s.to_string())
     }
+
+    /// Gets the commit SHA checked out in the repository containing `path`
+    pub fn head_commit(path: &Path) -> Option<String> {
+        let repo = git2::Repository::discover(path).ok()?;
+        let commit = repo.head().ok()?.peel_to_commit().ok()?;
+        Some(commit.id().to_string())
+    }
+
+    /// Lines changed in the work tree since `base_ref`, per file relative to `path`
+    ///
+    /// Staged, unstaged, and untracked changes all count. A deletion marks the line
+    /// the removed lines stood before; files outside `path` are left out.
+    pub fn changed_lines(path: &Path, base_ref: &str) -> anyhow::Result<BTreeMap<String, Vec<LineRange>>> {
+        let git_error = |error: git2::Error| anyhow::anyhow!("Failed to diff against {}: {}", base_ref, error.message());
+        let repo = git2::Repository::discover(path).map_err(git_error)?;
+        let base = repo
+            .revparse_single(base_ref)
+            .and_then(|object| object.peel_to_tree())
+            .map_err(git_error)?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| anyhow::anyhow!("{} is not in a git work tree", path.display()))?
+            .canonicalize()?;
+        let root = path.canonicalize()?;
+
+        let mut options = git2::DiffOptions::new();
+        options
+            .context_lines(0)
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        let diff = repo
+            .diff_tree_to_workdir_with_index(Some(&base), Some(&mut options))
+            .map_err(git_error)?;
+
+        let mut changes: BTreeMap<String, Vec<LineRange>> = BTreeMap::new();
+        diff.foreach(
+            &mut |_, _| true,
+            None,
+            Some(&mut |delta, hunk| {
+                let Some(file) = delta.new_file().path() else {
+                    return true;
+                };
+                let Ok(relative) = workdir.join(file).strip_prefix(&root).map(Path::to_path_buf) else {
+                    return true;
+                };
+                let start = (hunk.new_start() as usize).max(1);
+                let end = start + (hunk.new_lines() as usize).max(1) - 1;
+                changes
+                    .entry(relative.to_string_lossy().replace('\\', "/"))
+                    .or_default()
+                    .push(LineRange::new(start, end));
+                true
+            }),
+            None,
+        )
+        .map_err(git_error)?;
+        Ok(changes)
+    }
+
+    /// Clones `url` into `destination`, or keeps the clone already there
+    pub fn clone_or_reuse(url: &str, destination: &Path) -> anyhow::Result<()> {
+        if Self::is_git_repo(destination) {
+            return Ok(());
+        }
+        git2::Repository::clone(url, destination)
+            .map_err(|error| anyhow::anyhow!("Failed to clone {}: {}", url, error.message()))?;
+        Ok(())
+    }
+}
+
+/// The browser build has no repository on disk, so it never sees git metadata
+#[allow(dead_code)]
+#[cfg(target_arch = "wasm32")]
+impl GitUtils {
+    pub fn is_git_repo(_path: &Path) -> bool {
+        false
+    }
+
+    pub fn get_current_branch(_path: &Path) -> Option<String> {
+        None
+    }
+
+    pub fn head_commit(_path: &Path) -> Option<String> {
+        None
+    }
+
+    pub fn changed_lines(_path: &Path, base_ref: &str) -> anyhow::Result<BTreeMap<String, Vec<LineRange>>> {
+        anyhow::bail!("Cannot diff against {} in the browser build", base_ref)
+    }
+
+    pub fn clone_or_reuse(url: &str, _destination: &Path) -> anyhow::Result<()> {
+        anyhow::bail!("Cannot clone {} in the browser build", url)
+    }
 }
@@ -1,19 +1,29 @@
-/// Integration tests for Kotlin Multiplatform Coverage Analyzer
-/// Tests the complete flow from symbol extraction to impact analysis
+//! Integration tests for Kotlin Multiplatform Coverage Analyzer
+//! Tests the complete flow from symbol extraction to impact analysis
 
 use anyhow::Result;
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 use tempfile::TempDir;
 
 // Import from the library
 use kotlin_multiplatform_coverage::{
     adapters::{
-        DependencyRepositoryImpl, SourceFileRepositoryImpl, SymbolRepositoryImpl,
-        SymbolUsageRepositoryImpl,
+        DependencyRepositoryImpl, MemoryFileSystem, SourceFileRepositoryImpl,
+        SymbolRepositoryImpl, SymbolUsageRepositoryImpl,
     },
-    domain::{SourceFileRepository, SymbolRepository, SymbolUsageRepository},
-    use_cases::AnalyzeImpactUseCase,
+    domain::{
+        path_variant, AnalysisScope, Platform, CoverageGoal, DetectionSettings, FileFilter, FrameworkHeaderSources, GradleModuleKind, ImpactAnalysis, ImpactMetric, ImpactThreshold, InputLimits, MatchEngine, ModuleReach,
+        Parity, Ratio, SourceFileRepository, SwiftModuleKind, SymbolRepository, SymbolType,
+        SymbolUsageRepository, ThresholdKind, UsageKind, WarmStartState,
+    },
+    infrastructure::Analyzer,
+    use_cases::{
+        analyze_impact::{EXPLICIT_API_RULE, PROJECT_DETECTION_RULE}, detect_usage::PATHOLOGICAL_INPUT_RULE, AnalysisOptions,
+        AnalyzeImpactUseCase,
+    },
+    AnalysisHooks, Symbol, SymbolFilter, SymbolUsage, UsageFilter,
 };
 
 /// Creates a temporary KMP project structure for testing
@@ -315,6 +325,47 @@ fn test_end_to_end_impact_analysis() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_match_engines_compile_patterns_once() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let project_path = temp_project.path().to_str().unwrap();
+    // More app files than platforms, so per-file compilation would show
+    let android = temp_project.path().join("app/src/main/java/com/example/android");
+    for index in 0..20 {
+        fs::write(
+            android.join(format!("Screen{}.kt", index)),
+            format!("package com.example.android\n\nclass Screen{} {{\n    val user = User(\"{}\", \"n\")\n}}\n", index, index),
+        )?;
+    }
+
+    let mut results = Vec::new();
+    for engine in MatchEngine::ALL {
+        let symbol_repo = SymbolRepositoryImpl::new();
+        let source_file_repo = SourceFileRepositoryImpl::new();
+        let symbol_usage_repo = SymbolUsageRepositoryImpl::new().with_match_engine(engine);
+        let dependency_repo = DependencyRepositoryImpl::new();
+        let analysis =
+            AnalyzeImpactUseCase::new(&symbol_repo, &source_file_repo, &symbol_usage_repo, &dependency_repo)
+                .execute(project_path)?;
+
+        let compilations = symbol_usage_repo.pattern_cache().compilations();
+        assert!(compilations > 0);
+        assert!(compilations <= analysis.platform_impacts.len(), "{} compiled {} times", engine.name(), compilations);
+        let mut sites: Vec<(String, String, usize)> = analysis
+            .symbol_usages
+            .values()
+            .flatten()
+            .map(|usage| (usage.symbol_name.clone(), usage.file_path.clone(), usage.line_number))
+            .collect();
+        sites.sort();
+        results.push((analysis.affected_lines, sites));
+    }
+    assert!(results.iter().all(|result| *result == results[0]));
+    assert!(results[0].1.iter().filter(|(symbol, _, _)| symbol == "User").count() >= 20);
+
+    Ok(())
+}
+
 #[test]
 fn test_symbol_extraction() -> Result<()> {
     let temp_project = create_test_kmp_project()?;
@@ -329,7 +380,7 @@ fn test_symbol_extraction() -> Result<()> {
 
     // Extract symbols
     let symbols = symbol_repo.extract_kmp_symbols(&kmp_files)?;
-    assert!(symbols.len() > 0, "Should extract symbols");
+    assert!(!symbols.is_empty(), "Should extract symbols");
 
     // Verify specific symbols
     let symbol_names: Vec<String> = symbols.iter().map(|s| s.name.clone()).collect();
@@ -364,7 +415,7 @@ fn test_platform_detection() -> Result<()> {
     let app_files = source_file_repo.find_app_files(project_path)?;
 
     // Should detect both platforms
-    assert!(app_files.len() >= 1, "Should detect at least one platform");
+    assert!(!app_files.is_empty(), "Should detect at least one platform");
 
     // Count files per platform
     for (platform, files) in &app_files {
@@ -466,3 +517,2179 @@ fn test_impact_ratio_calculation() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_deprecated_usage_tracking() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let project_path = temp_project.path();
+
+    fs::write(
+        project_path.join("shared/src/commonMain/kotlin/com/example/Legacy.kt"),
+        r#"
+package com.example
+
+@Deprecated("Use UserRepository instead")
+class LegacyUserStore
+"#,
+    )?;
+    fs::write(
+        project_path.join("app/src/main/java/com/example/android/LegacyScreen.kt"),
+        r#"
+package com.example.android
+
+class LegacyScreen {
+    private val store = LegacyUserStore()
+    private val formatter = Formatter()
+}
+"#,
+    )?;
+    // `Formatter` is deprecated in one package but not in another; usages cannot tell them apart
+    let legacy = project_path.join("shared/src/commonMain/kotlin/com/example/legacy");
+    fs::create_dir_all(&legacy)?;
+    fs::write(legacy.join("Formatter.kt"), "package com.example.legacy\n\n@Deprecated(\"Gone\")\nclass Formatter\n")?;
+    let text = project_path.join("shared/src/commonMain/kotlin/com/example/text");
+    fs::create_dir_all(&text)?;
+    fs::write(text.join("Formatter.kt"), "package com.example.text\n\nclass Formatter\n")?;
+
+    let symbol_repo = SymbolRepositoryImpl::new();
+    let source_file_repo = SourceFileRepositoryImpl::new();
+    let symbol_usage_repo = SymbolUsageRepositoryImpl::new();
+    let dependency_repo = DependencyRepositoryImpl::new();
+
+    let analyze_use_case = AnalyzeImpactUseCase::new(
+        &symbol_repo,
+        &source_file_repo,
+        &symbol_usage_repo,
+        &dependency_repo,
+    );
+    let impact_analysis = analyze_use_case.execute(project_path.to_str().unwrap())?;
+
+    let android_impact = &impact_analysis.platform_impacts["Android"];
+    assert_eq!(android_impact.deprecated_usages.len(), 1);
+    assert_eq!(android_impact.deprecated_usages[0].symbol_name, "LegacyUserStore");
+    assert!(impact_analysis.platform_impacts["iOS"].deprecated_usages.is_empty());
+    assert_eq!(impact_analysis.deprecated_usage_count(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_actual_typealias_resolution() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let project_path = temp_project.path();
+
+    let android_main = project_path.join("shared/src/androidMain/kotlin/com/example");
+    fs::create_dir_all(&android_main)?;
+    fs::write(
+        android_main.join("Platform.kt"),
+        "package com.example\n\nactual typealias PlatformDate = java.util.Date\n",
+    )?;
+    fs::write(
+        project_path.join("app/src/main/java/com/example/android/Clock.kt"),
+        "package com.example.android\n\nclass Clock {\n    val now = Date()\n    val start: PlatformDate = Date()\n    val span = Date() to Date()\n}\n",
+    )?;
+
+    let symbol_repo = SymbolRepositoryImpl::new();
+    let source_file_repo = SourceFileRepositoryImpl::new();
+    let symbol_usage_repo = SymbolUsageRepositoryImpl::new();
+    let dependency_repo = DependencyRepositoryImpl::new();
+    let path = project_path.to_str().unwrap();
+
+    let without_resolution = AnalyzeImpactUseCase::new(
+        &symbol_repo,
+        &source_file_repo,
+        &symbol_usage_repo,
+        &dependency_repo,
+    )
+    .execute(path)?;
+    assert_eq!(without_resolution.symbol_usages["PlatformDate"].len(), 1);
+
+    let with_resolution = AnalyzeImpactUseCase::new(
+        &symbol_repo,
+        &source_file_repo,
+        &symbol_usage_repo,
+        &dependency_repo,
+    )
+    .with_options(AnalysisOptions {
+        resolve_actual_typealiases: true,
+        ..Default::default()
+    })
+    .execute(path)?;
+    // A target credited on a line that already uses the alias, or twice on one line, counts once
+    let usages = &with_resolution.symbol_usages["PlatformDate"];
+    let mut lines: Vec<usize> = usages.iter().map(|usage| usage.line_number).collect();
+    lines.sort();
+    assert_eq!(lines, vec![4, 5, 6]);
+    assert!(usages[0].file_path.ends_with("Clock.kt"));
+
+    Ok(())
+}
+
+#[test]
+fn test_package_breakdown() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let project_path = temp_project.path();
+
+    let billing_path = project_path.join("shared/src/commonMain/kotlin/com/example/billing");
+    fs::create_dir_all(&billing_path)?;
+    fs::write(
+        billing_path.join("Invoice.kt"),
+        "package com.example.billing\n\nclass Invoice\n",
+    )?;
+
+    let symbol_repo = SymbolRepositoryImpl::new();
+    let source_file_repo = SourceFileRepositoryImpl::new();
+    let symbol_usage_repo = SymbolUsageRepositoryImpl::new();
+    let dependency_repo = DependencyRepositoryImpl::new();
+
+    let analysis = AnalyzeImpactUseCase::new(
+        &symbol_repo,
+        &source_file_repo,
+        &symbol_usage_repo,
+        &dependency_repo,
+    )
+    .execute(project_path.to_str().unwrap())?;
+
+    let packages: Vec<&str> = analysis.packages.iter().map(|p| p.package.as_str()).collect();
+    assert_eq!(packages, vec!["com.example", "com.example.billing"]);
+
+    let example = &analysis.packages[0];
+    assert!(example.references > 0);
+    assert!(example.platform_references.contains_key("Android"));
+    assert!(example.platform_references.contains_key("iOS"));
+
+    let billing = &analysis.packages[1];
+    assert_eq!(billing.total_symbols, 1);
+    assert_eq!(billing.used_symbols, 0);
+    assert_eq!(billing.references, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_cross_platform_parity() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let project_path = temp_project.path();
+
+    let symbol_repo = SymbolRepositoryImpl::new();
+    let source_file_repo = SourceFileRepositoryImpl::new();
+    let symbol_usage_repo = SymbolUsageRepositoryImpl::new();
+    let dependency_repo = DependencyRepositoryImpl::new();
+
+    let analysis = AnalyzeImpactUseCase::new(
+        &symbol_repo,
+        &source_file_repo,
+        &symbol_usage_repo,
+        &dependency_repo,
+    )
+    .execute(project_path.to_str().unwrap())?;
+
+    let parity = &analysis.parity;
+    let parity_of = |name: &str| {
+        parity
+            .symbols
+            .iter()
+            .find(|s| s.symbol_name == name)
+            .map(|s| s.parity)
+    };
+
+    // formatUserName is only called from MainActivity.kt
+    assert_eq!(parity_of("UserRepositoryImpl"), Some(Parity::BothPlatforms));
+    assert_eq!(parity_of("formatUserName"), Some(Parity::AndroidOnly));
+    assert!(parity
+        .android_only_candidates
+        .iter()
+        .any(|(name, _)| name == "formatUserName"));
+    assert_eq!(
+        parity.both_platforms + parity.android_only + parity.ios_only + parity.unused,
+        parity.symbols.len()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_shared_analyzer_concurrent_runs() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path().to_str().unwrap().to_string();
+    let analyzer = Analyzer::new();
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let analyzer = analyzer.clone();
+            let path = path.clone();
+            std::thread::spawn(move || analyzer.analyze(&path))
+        })
+        .collect();
+    let results = handles
+        .into_iter()
+        .map(|h| h.join().unwrap())
+        .collect::<Result<Vec<_>>>()?;
+
+    for analysis in &results {
+        assert_eq!(analysis.total_symbols, results[0].total_symbols);
+        assert_eq!(analysis.affected_lines, results[0].affected_lines);
+        assert_eq!(analysis.affected_files, results[0].affected_files);
+    }
+
+    // Cached files are re-read once they change
+    let shared_file = temp_project
+        .path()
+        .join("shared/src/commonMain/kotlin/com/example/Utils.kt");
+    let mut content = fs::read_to_string(&shared_file)?;
+    content.push_str("\nclass AddedLater\n");
+    fs::write(&shared_file, content)?;
+
+    let rerun = analyzer.analyze(&path)?;
+    assert_eq!(rerun.total_symbols, results[0].total_symbols + 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_analyze_in_memory_file_system() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let on_disk = Analyzer::new().analyze(temp_project.path().to_str().unwrap())?;
+
+    // Mirror the same project into memory under a path that does not exist on disk
+    let mut memory = MemoryFileSystem::new();
+    for entry in walkdir::WalkDir::new(temp_project.path()) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            let relative = entry.path().strip_prefix(temp_project.path())?;
+            let content = fs::read_to_string(entry.path())?;
+            memory.insert(Path::new("/virtual/repo").join(relative), content);
+        }
+    }
+
+    let analyzer =
+        Analyzer::with_file_system(Arc::new(memory), AnalysisOptions::default(), Vec::new());
+    let in_memory = analyzer.analyze("/virtual/repo")?;
+
+    assert_eq!(in_memory.total_symbols, on_disk.total_symbols);
+    assert_eq!(in_memory.total_app_files, on_disk.total_app_files);
+    assert_eq!(in_memory.affected_lines, on_disk.affected_lines);
+    assert_eq!(in_memory.affected_files.len(), on_disk.affected_files.len());
+
+    Ok(())
+}
+
+#[test]
+fn test_sample_modules_excluded_from_app_files() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    let baseline = Analyzer::new().analyze(path.to_str().unwrap())?;
+
+    // A demo app using the shared code, detected like any other Android module
+    let sample = path.join("sample-app");
+    fs::create_dir_all(sample.join("src/main/kotlin"))?;
+    fs::write(
+        sample.join("build.gradle.kts"),
+        "plugins { id(\"com.android.application\") }",
+    )?;
+    fs::write(sample.join("src/main/AndroidManifest.xml"), "<manifest/>")?;
+    fs::write(
+        sample.join("src/main/kotlin/Demo.kt"),
+        "fun demo() {\n    val repo = UserRepository()\n}\n",
+    )?;
+
+    let excluded = Analyzer::new().analyze(path.to_str().unwrap())?;
+    assert_eq!(excluded.total_app_files, baseline.total_app_files);
+
+    let options = AnalysisOptions {
+        include_sample_modules: true,
+        ..Default::default()
+    };
+    let included = Analyzer::with_configuration(options, Vec::new())
+        .analyze(path.to_str().unwrap())?;
+    assert_eq!(included.total_app_files, baseline.total_app_files + 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_pathological_input_guards() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    let android_path = path.join("app/src/main/java/com/example/android");
+
+    // A minified one-liner and a file hammering the same symbol on every line
+    let minified = format!("val repo = UserRepository(); {}", "val x = 1; ".repeat(500));
+    fs::write(android_path.join("Minified.kt"), minified)?;
+    let repetitive = "val repo = UserRepository()\n".repeat(20);
+    fs::write(android_path.join("Repetitive.kt"), repetitive)?;
+
+    let options = AnalysisOptions {
+        input_limits: InputLimits {
+            max_line_length: 1_000,
+            max_usages_per_symbol_per_file: 5,
+            max_context_length: 10,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let analysis =
+        Analyzer::with_configuration(options, Vec::new()).analyze(path.to_str().unwrap())?;
+
+    let usages = &analysis.symbol_usages["UserRepository"];
+    assert!(!usages.iter().any(|u| u.file_path.ends_with("Minified.kt")));
+    let repetitive_usages: Vec<_> = usages
+        .iter()
+        .filter(|u| u.file_path.ends_with("Repetitive.kt"))
+        .collect();
+    assert_eq!(repetitive_usages.len(), 5);
+    assert!(repetitive_usages.iter().all(|u| u.context.chars().count() <= 10));
+
+    let findings: Vec<_> = analysis
+        .diagnostics
+        .iter()
+        .filter(|d| d.rule == PATHOLOGICAL_INPUT_RULE)
+        .collect();
+    assert_eq!(findings.len(), 2);
+    assert!(findings.iter().any(|d| d.message.contains("Minified.kt")));
+    assert!(findings
+        .iter()
+        .any(|d| d.symbol.as_deref() == Some("UserRepository") && d.message.contains("20")));
+
+    Ok(())
+}
+
+#[test]
+fn test_coverage_goal_progress() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    // An Android file without shared code keeps the module goal out of reach
+    fs::write(
+        path.join("app/src/main/java/com/example/android/Settings.kt"),
+        "package com.example.android\n\nclass Settings {\n    fun open() {}\n}\n",
+    )?;
+    let baseline = Analyzer::new().analyze(path.to_str().unwrap())?;
+    let android = &baseline.platform_impacts["Android"];
+
+    let goal = |name: &str, target: Ratio, enforce: bool| CoverageGoal {
+        name: name.to_string(),
+        target,
+        enforce,
+    };
+    let options = AnalysisOptions {
+        goals: vec![
+            goal("android", android.exact_impact_ratio(), true),
+            goal("app", Ratio::new(1, 1), false),
+            goal(":missing", Ratio::default(), true),
+        ],
+        ..Default::default()
+    };
+    let analysis =
+        Analyzer::with_configuration(options, Vec::new()).analyze(path.to_str().unwrap())?;
+
+    let android_goal = &analysis.goals[0];
+    assert!(android_goal.met);
+    assert_eq!(android_goal.total_lines, android.total_lines);
+
+    // The Android app lives in the `app` module, so the module goal sees the same lines
+    let module_goal = &analysis.goals[1];
+    assert_eq!(module_goal.total_lines, android.total_lines);
+    assert_eq!(module_goal.affected_lines, android.affected_lines);
+    assert!(!module_goal.met);
+
+    let missing_goal = &analysis.goals[2];
+    assert_eq!(missing_goal.total_lines, 0);
+    assert!(!missing_goal.met);
+
+    let missed: Vec<&str> = analysis
+        .missed_enforced_goals()
+        .iter()
+        .map(|g| g.name.as_str())
+        .collect();
+    assert_eq!(missed, vec![":missing"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_android_module_impact() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+
+    // A feature module using shared code, and one that only depends on it
+    for (dir, build, source) in [
+        (
+            "feature/auth",
+            "plugins { id(\"com.android.library\") }\ndependencies { implementation(project(\":shared\")) }",
+            "class Login {\n    val repo = UserRepository()\n}\n",
+        ),
+        (
+            "feature/settings",
+            "plugins { id(\"com.android.library\") }\ndependencies { implementation(project(\":feature:auth\")) }",
+            "class Settings {\n    val theme = \"dark\"\n}\n",
+        ),
+    ] {
+        let module = path.join(dir);
+        fs::create_dir_all(module.join("src/main/kotlin"))?;
+        fs::write(module.join("build.gradle.kts"), build)?;
+        fs::write(module.join("src/main/AndroidManifest.xml"), "<manifest/>")?;
+        fs::write(module.join("src/main/kotlin/Feature.kt"), source)?;
+    }
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    let module = |name: &str| {
+        analysis
+            .android_modules
+            .iter()
+            .find(|m| m.module == name)
+            .unwrap_or_else(|| panic!("missing module {}", name))
+    };
+
+    assert_eq!(analysis.android_modules[0].module, ":app");
+    assert_eq!(analysis.android_modules[0].kind, GradleModuleKind::Application);
+
+    let auth = module(":feature:auth");
+    assert_eq!(auth.reach, ModuleReach::Direct);
+    assert!(auth.declares_shared_dependency);
+    assert_eq!(auth.references, 1);
+    assert_eq!(auth.affected_files, 1);
+
+    let settings = module(":feature:settings");
+    assert_eq!(settings.kind, GradleModuleKind::Library);
+    assert_eq!(settings.reach, ModuleReach::ViaModules);
+    assert_eq!(settings.via_modules, vec![":feature:auth"]);
+    assert_eq!(settings.affected_lines, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_ios_module_impact() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+
+    // A local package with a feature using shared code, and one that only depends on it
+    let package = path.join("iosApp/Packages/Features");
+    fs::create_dir_all(&package)?;
+    fs::write(
+        package.join("Package.swift"),
+        r#"
+let package = Package(
+    name: "Features",
+    targets: [
+        .target(name: "FeatureAuth"),
+        .target(name: "FeatureSettings", dependencies: ["FeatureAuth"]),
+    ]
+)
+"#,
+    )?;
+    for (target, source) in [
+        ("FeatureAuth", "import Shared\n\nlet repository = UserRepositoryImpl()\n"),
+        ("FeatureSettings", "import FeatureAuth\n\nlet theme = \"dark\"\n"),
+    ] {
+        let sources = package.join("Sources").join(target);
+        fs::create_dir_all(&sources)?;
+        fs::write(sources.join(format!("{}.swift", target)), source)?;
+    }
+    fs::write(
+        path.join("iosApp/iosApp.xcodeproj/project.pbxproj"),
+        r#"
+/* Begin PBXNativeTarget section */
+		7555FF7A242A565900829871 /* iosApp */ = {
+			isa = PBXNativeTarget;
+			name = iosApp;
+			packageProductDependencies = (
+				7A1B2C3D4E5F000000000001 /* FeatureSettings */,
+			);
+			productType = "com.apple.product-type.application";
+		};
+/* End PBXNativeTarget section */
+"#,
+    )?;
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    let module = |name: &str| {
+        analysis
+            .ios_modules
+            .iter()
+            .find(|m| m.module == name)
+            .unwrap_or_else(|| panic!("missing module {}", name))
+    };
+
+    assert_eq!(analysis.ios_modules[0].module, "iosApp");
+    assert_eq!(analysis.ios_modules[0].kind, SwiftModuleKind::XcodeTarget);
+    assert_eq!(analysis.ios_modules[0].total_files, 2);
+
+    let auth = module("FeatureAuth");
+    assert_eq!(auth.kind, SwiftModuleKind::Package);
+    assert_eq!(auth.reach, ModuleReach::Direct);
+    assert_eq!(auth.references, 1);
+    assert_eq!(auth.affected_files, 1);
+
+    let settings = module("FeatureSettings");
+    assert_eq!(settings.reach, ModuleReach::ViaModules);
+    assert_eq!(settings.via_modules, vec!["FeatureAuth"]);
+    assert_eq!(settings.affected_lines, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_shared_resource_usage() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+
+    let strings = path.join("shared/src/commonMain/moko-resources/base");
+    fs::create_dir_all(&strings)?;
+    fs::write(
+        strings.join("strings.xml"),
+        "<resources>\n    <string name=\"title\">Title</string>\n    <string name=\"unused\">Unused</string>\n</resources>\n",
+    )?;
+    fs::write(
+        path.join("app/src/main/java/com/example/android/TitleView.kt"),
+        "package com.example.android\n\nfun title() = stringResource(MR.strings.title)\n",
+    )?;
+    fs::write(
+        path.join("iosApp/iosApp/TitleView.swift"),
+        "import Shared\n\nlet title = MR.strings().title.desc().localized()\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+
+    let resource = analysis
+        .symbols
+        .iter()
+        .find(|s| s.name == "MR.strings.title")
+        .expect("resource symbol");
+    assert_eq!(resource.symbol_type, SymbolType::Resource);
+    assert_eq!(resource.module, "shared");
+
+    let usages = &analysis.symbol_usages["MR.strings.title"];
+    assert_eq!(usages.len(), 2);
+    assert!(usages.iter().any(|u| u.file_path.ends_with("TitleView.swift")));
+    assert!(!analysis.symbol_usages.contains_key("MR.strings.unused"));
+
+    Ok(())
+}
+
+#[test]
+fn test_generated_shared_api_usage() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+
+    let queries = path.join("shared/src/commonMain/sqldelight/com/example/db");
+    fs::create_dir_all(&queries)?;
+    fs::write(
+        queries.join("Player.sq"),
+        "CREATE TABLE player (\n  id INTEGER NOT NULL PRIMARY KEY,\n  name TEXT NOT NULL\n);\n\nselectAll:\nSELECT * FROM player;\n",
+    )?;
+    let operations = path.join("shared/src/commonMain/graphql");
+    fs::create_dir_all(&operations)?;
+    fs::write(
+        operations.join("GetUser.graphql"),
+        "query GetUser($id: ID!) {\n  user(id: $id) { name }\n}\n",
+    )?;
+    fs::write(
+        path.join("app/src/main/java/com/example/android/PlayerList.kt"),
+        "package com.example.android\n\nclass PlayerList(database: AppDatabase) {\n    private val queries: PlayerQueries = database.playerQueries\n\n    fun first(): Player = queries.selectAll().executeAsOne()\n}\n",
+    )?;
+    fs::write(
+        path.join("iosApp/iosApp/UserLoader.swift"),
+        "import Shared\n\nlet query = GetUserQuery(id: \"1\")\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+
+    let generated = analysis
+        .symbols
+        .iter()
+        .find(|s| s.name == "PlayerQueries")
+        .expect("generated symbol");
+    assert_eq!(generated.module, "shared");
+    assert_eq!(generated.package, "com.example.db");
+
+    assert_eq!(analysis.symbol_usages["PlayerQueries"].len(), 1);
+    assert_eq!(analysis.symbol_usages["Player"].len(), 1);
+    assert!(analysis.symbol_usages["GetUserQuery"][0]
+        .file_path
+        .ends_with("UserLoader.swift"));
+
+    Ok(())
+}
+
+struct SkipLogger;
+
+impl SymbolFilter for SkipLogger {
+    fn filter_symbol(&self, symbol: &mut Symbol) -> bool {
+        symbol.name != "Logger"
+    }
+}
+
+struct AndroidOnly;
+
+impl UsageFilter for AndroidOnly {
+    fn filter_usage(&self, usage: &mut SymbolUsage) -> bool {
+        !usage.file_path.ends_with(".swift")
+    }
+}
+
+#[test]
+fn test_analysis_hooks() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path().to_str().unwrap();
+    let baseline = Analyzer::new().analyze(path)?;
+    assert!(baseline.symbols.iter().any(|s| s.name == "Logger"));
+    assert!(!baseline.platform_impacts["iOS"].affected_files.is_empty());
+
+    let options = AnalysisOptions {
+        hooks: AnalysisHooks::new()
+            .with_symbol_filter(SkipLogger)
+            .with_usage_filter(AndroidOnly),
+        ..Default::default()
+    };
+    let analysis = Analyzer::with_configuration(options, Vec::new()).analyze(path)?;
+
+    assert!(analysis.symbols.iter().all(|s| s.name != "Logger"));
+    assert!(!analysis.symbol_usages.contains_key("Logger"));
+    assert!(analysis.platform_impacts["iOS"].affected_files.is_empty());
+    assert_eq!(
+        analysis.platform_impacts["Android"].affected_files,
+        baseline.platform_impacts["Android"].affected_files
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_server_platform_impact() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+
+    let server = path.join("server/src/main/kotlin/com/example/server");
+    fs::create_dir_all(&server)?;
+    fs::write(
+        path.join("server/build.gradle.kts"),
+        "plugins {\n    kotlin(\"jvm\")\n    id(\"io.ktor.plugin\")\n}\n\ndependencies {\n    implementation(project(\":shared\"))\n}\n",
+    )?;
+    fs::write(
+        server.join("Routes.kt"),
+        "package com.example.server\n\nfun userRoute(id: String) {\n    val repository = UserRepositoryImpl()\n    println(repository.getUser(id))\n}\n",
+    )?;
+    fs::write(
+        server.join("Health.kt"),
+        "package com.example.server\n\nfun health() = \"ok\"\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+
+    let impact = &analysis.platform_impacts["Server"];
+    assert_eq!(impact.total_files, 2);
+    assert_eq!(impact.affected_files.len(), 1);
+    assert!(impact.impact_ratio > 0.0 && impact.impact_ratio < 1.0);
+    assert!(analysis.platform_impacts.contains_key("Android"));
+
+    Ok(())
+}
+
+#[test]
+fn test_shared_code_roi() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    let baseline = Analyzer::new().analyze(path.to_str().unwrap())?;
+
+    // Shared tests are not shared production code
+    let tests = path.join("shared/src/commonTest/kotlin/com/example");
+    fs::create_dir_all(&tests)?;
+    fs::write(tests.join("UserTest.kt"), "package com.example\n\nclass UserTest {\n    fun test() {}\n}\n")?;
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+
+    let roi = &analysis.roi;
+    assert!(roi.shared_lines > 0);
+    assert_eq!(roi.shared_lines, baseline.roi.shared_lines);
+    assert_eq!(roi.affected_app_lines, analysis.affected_lines);
+    assert_eq!(
+        roi.platform_affected_lines["Android"],
+        analysis.platform_impacts["Android"].affected_lines
+    );
+    assert!((roi.leverage - roi.affected_app_lines as f64 / roi.shared_lines as f64).abs() < 1e-9);
+    assert!(roi.formula.contains("shared code lines"));
+
+    Ok(())
+}
+
+#[test]
+fn test_detection_depth_and_root_hints() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+
+    // An Android app nested deeper than the default detection depth
+    let nested = path.join("products/mobile/clients/legacy/android/app");
+    fs::create_dir_all(nested.join("src/main/kotlin"))?;
+    fs::write(nested.join("build.gradle.kts"), "plugins { id(\"com.android.application\") }")?;
+    fs::write(
+        nested.join("src/main/kotlin/LegacyScreen.kt"),
+        "package com.example.legacy\n\nimport com.example.UserRepository\nimport com.example.UserRepositoryImpl\n\nclass LegacyScreen {\n    private val repository: UserRepository = UserRepositoryImpl()\n}\n",
+    )?;
+
+    let detected = |analysis: &ImpactAnalysis| -> Vec<String> {
+        analysis
+            .diagnostics
+            .iter()
+            .filter(|d| d.rule == PROJECT_DETECTION_RULE)
+            .map(|d| d.message.clone())
+            .collect()
+    };
+
+    let baseline = Analyzer::new().analyze(path.to_str().unwrap())?;
+    assert!(detected(&baseline).contains(&"KMP project `shared` detected by gradle-plugin (confidence 1.00)".to_string()));
+    assert!(!detected(&baseline).iter().any(|m| m.contains("legacy")));
+
+    let options = AnalysisOptions {
+        detection: DetectionSettings {
+            root_hints: vec!["products/mobile/clients".to_string()],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let hinted =
+        Analyzer::with_configuration(options, Vec::new()).analyze(path.to_str().unwrap())?;
+    assert!(detected(&hinted).iter().any(|m| m.contains("legacy/android/app")));
+    assert!(hinted
+        .affected_files
+        .iter()
+        .any(|f| f.ends_with("LegacyScreen.kt")));
+
+    Ok(())
+}
+
+#[test]
+fn test_file_globs_filter_scanned_files() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    let generated = path.join("app/src/main/java/com/example/android/generated");
+    fs::create_dir_all(&generated)?;
+    fs::write(
+        generated.join("UserBinding.kt"),
+        "package com.example.android.generated\n\nimport com.example.User\n\nclass UserBinding(val user: User)\n",
+    )?;
+
+    let analyze = |include: &[&str], exclude: &[&str]| {
+        let options = AnalysisOptions {
+            detection: DetectionSettings {
+                files: FileFilter {
+                    include: include.iter().map(|g| g.to_string()).collect(),
+                    exclude: exclude.iter().map(|g| g.to_string()).collect(),
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        Analyzer::with_configuration(options, Vec::new()).analyze(path.to_str().unwrap())
+    };
+    let scanned = |analysis: &ImpactAnalysis, name: &str| analysis.files.iter().any(|f| f.path.ends_with(name));
+
+    let analysis = analyze(&[], &[])?;
+    assert!(scanned(&analysis, "UserBinding.kt"));
+
+    let analysis = analyze(&[], &["**/generated/**", "UserAdapter.kt"])?;
+    assert!(!scanned(&analysis, "UserBinding.kt"));
+    assert!(!scanned(&analysis, "UserAdapter.kt"));
+    assert!(scanned(&analysis, "MainActivity.kt"));
+    assert!(!analysis.affected_files.iter().any(|f| f.ends_with("UserBinding.kt")));
+
+    let analysis = analyze(&["shared/**", "iosApp/**"], &[])?;
+    assert!(!scanned(&analysis, "MainActivity.kt"));
+    assert!(scanned(&analysis, "ContentView.swift"));
+    assert!(analysis.symbols.iter().any(|s| s.name == "UserRepository"));
+
+    Ok(())
+}
+
+#[test]
+fn test_included_build_outside_project() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    // The apps live in `main/`; the shared module is a sibling build it includes
+    let main = path.join("main");
+    fs::create_dir_all(&main)?;
+    fs::rename(path.join("app"), main.join("app"))?;
+    fs::rename(path.join("iosApp"), main.join("iosApp"))?;
+    fs::write(main.join("settings.gradle.kts"), "includeBuild(\"../shared\")\ninclude(\":app\")\n")?;
+
+    let analysis = Analyzer::new().analyze(main.to_str().unwrap())?;
+    assert!(analysis.symbols.iter().any(|s| s.name == "UserRepository"));
+    assert!(analysis.affected_files.iter().any(|f| f.ends_with("MainActivity.kt")));
+
+    fs::write(main.join("settings.gradle.kts"), "include(\":app\")\n")?;
+    let analysis = Analyzer::new().analyze(main.to_str().unwrap())?;
+    assert!(!analysis.symbols.iter().any(|s| s.name == "UserRepository"));
+
+    Ok(())
+}
+
+#[test]
+fn test_explicit_api_mode() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    let build_file = path.join("shared/build.gradle.kts");
+    let build_script = fs::read_to_string(&build_file)?;
+    fs::write(
+        path.join("shared/src/commonMain/kotlin/com/example/Session.kt"),
+        "package com.example\n\npublic class Session {\n    internal fun token(): String = \"\"\n}\n",
+    )?;
+    let explicit_api = |analysis: &ImpactAnalysis| -> Vec<String> {
+        analysis
+            .diagnostics
+            .iter()
+            .filter(|d| d.rule == EXPLICIT_API_RULE)
+            .filter_map(|d| d.symbol.clone())
+            .collect()
+    };
+    let has_symbol = |analysis: &ImpactAnalysis, name: &str| analysis.symbols.iter().any(|s| s.name == name);
+
+    // Without explicit API mode only the `internal` member is left out
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    assert!(has_symbol(&analysis, "Session"));
+    assert!(!has_symbol(&analysis, "token"));
+    assert!(explicit_api(&analysis).is_empty());
+
+    fs::write(&build_file, build_script.replace("kotlin {", "kotlin {\n    explicitApi()"))?;
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    assert!(has_symbol(&analysis, "Session"));
+    assert!(!has_symbol(&analysis, "UserRepository"));
+    assert!(explicit_api(&analysis).contains(&"UserRepository".to_string()));
+    assert!(!explicit_api(&analysis).contains(&"Session".to_string()));
+
+    fs::write(&build_file, build_script.replace("kotlin {", "kotlin {\n    explicitApiWarning()"))?;
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    assert!(has_symbol(&analysis, "Session"));
+    assert!(has_symbol(&analysis, "UserRepository"));
+    assert!(explicit_api(&analysis).contains(&"UserRepository".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn test_api_funnel() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    let android_main = path.join("shared/src/androidMain/kotlin/com/example");
+    fs::create_dir_all(&android_main)?;
+    fs::write(
+        android_main.join("AndroidContext.kt"),
+        "package com.example\n\nclass AndroidContext\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    let funnel = &analysis.funnel;
+    assert_eq!(funnel.declared, analysis.symbols.iter().filter(|s| s.is_public).count());
+    let platform = |name: &str| funnel.platforms.iter().find(|p| p.platform == name).unwrap();
+
+    let android = platform("Android");
+    let ios = platform("iOS");
+    assert_eq!(android.exported, funnel.declared);
+    assert_eq!(ios.exported, funnel.declared - 1);
+    for stages in [android, ios] {
+        assert!(stages.exported >= stages.imported && stages.imported >= stages.referenced);
+        assert!(stages.referenced > 0);
+    }
+    // `AndroidContext` is exported to Android but used nowhere
+    assert!(android.referenced < android.exported);
+    assert!(funnel.describe_largest_drop().is_some());
+
+    Ok(())
+}
+
+#[test]
+fn test_ignored_files_are_not_scanned() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    fs::create_dir_all(path.join(".git"))?;
+    fs::write(path.join(".gitignore"), "Scratch.kt\n")?;
+    fs::write(
+        path.join("app/src/main/java/com/example/android/Scratch.kt"),
+        "package com.example.android\n\nimport com.example.Logger\n\nfun scratch() {\n    Logger.log(\"scratch\")\n}\n",
+    )?;
+    let scans_scratch = |analysis: &ImpactAnalysis| analysis.affected_files.iter().any(|f| f.ends_with("Scratch.kt"));
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    assert!(!scans_scratch(&analysis));
+    assert!(analysis.affected_files.iter().any(|f| f.ends_with("MainActivity.kt")));
+
+    let options = AnalysisOptions {
+        detection: DetectionSettings {
+            ignore_files: false,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let analysis = Analyzer::with_configuration(options, Vec::new()).analyze(path.to_str().unwrap())?;
+    assert!(scans_scratch(&analysis));
+
+    Ok(())
+}
+
+#[test]
+fn test_desktop_app_usage() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    let desktop_main = path.join("shared/src/desktopMain/kotlin/com/example");
+    fs::create_dir_all(&desktop_main)?;
+    fs::write(
+        desktop_main.join("Tray.kt"),
+        "package com.example\n\nclass TrayNotifier {\n    fun notify(user: User) {}\n}\n",
+    )?;
+    fs::create_dir_all(path.join("desktopApp/src/main/kotlin"))?;
+    fs::write(
+        path.join("desktopApp/build.gradle.kts"),
+        "plugins { kotlin(\"jvm\"); id(\"org.jetbrains.compose\") }\ncompose.desktop {\n    application { mainClass = \"MainKt\" }\n}\n",
+    )?;
+    fs::write(
+        path.join("desktopApp/src/main/kotlin/Main.kt"),
+        "import com.example.TrayNotifier\nimport com.example.User\n\nfun main() {\n    TrayNotifier().notify(User(\"1\", \"Ada\", \"ada@example.com\"))\n}\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    let desktop = &analysis.platform_impacts["Desktop"];
+    assert_eq!(desktop.total_files, 1);
+    assert!(desktop.affected_lines > 0);
+    assert!(!analysis.platform_impacts["Android"].top_symbols.iter().any(|(name, _)| name == "TrayNotifier"));
+
+    let symbol = SymbolRepositoryImpl::new()
+        .extract_kmp_symbols(&[desktop_main.join("Tray.kt").to_string_lossy().to_string()])?
+        .into_iter()
+        .find(|s| s.name == "TrayNotifier")
+        .unwrap();
+    assert!(symbol.exported_to(&Platform::Desktop));
+    assert!(!symbol.exported_to(&Platform::Android));
+
+    Ok(())
+}
+
+#[test]
+fn test_kotlin_native_export_names() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    fs::write(
+        path.join("shared/src/commonMain/kotlin/com/example/Session.kt"),
+        "package com.example\n\n@ObjCName(swiftName = \"AppSession\")\nclass Session\n\nclass Token\n",
+    )?;
+    fs::write(
+        path.join("iosApp/iosApp/SessionView.swift"),
+        "import Shared\n\nstruct SessionView {\n    let session = AppSession()\n    let token: Token_ = Token_()\n}\n",
+    )?;
+    fs::write(
+        path.join("iosApp/iosApp/LegacyBridge.m"),
+        "#import <Shared/Shared.h>\n\nvoid legacy(void) {\n    SharedUser *user = [[SharedUser alloc] init];\n}\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    let files_using = |symbol: &str| -> Vec<String> {
+        analysis
+            .symbol_usages
+            .get(symbol)
+            .into_iter()
+            .flatten()
+            .filter_map(|usage| Path::new(&usage.file_path).file_name().map(|name| name.to_string_lossy().to_string()))
+            .collect()
+    };
+    assert_eq!(files_using("Session"), vec!["SessionView.swift"]);
+    assert_eq!(files_using("Token"), vec!["SessionView.swift"]);
+    assert!(files_using("User").contains(&"LegacyBridge.m".to_string()));
+    let context = &analysis.symbol_usages["Session"][0].context;
+    assert_eq!(context, "let session = AppSession()");
+
+    Ok(())
+}
+
+#[test]
+fn test_framework_header_names() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    fs::write(
+        path.join("shared/src/commonMain/kotlin/com/example/Token.kt"),
+        "package com.example\n\nclass Token\n",
+    )?;
+    // The header exports the shared class as `Token_`, so `Token` in Swift is a type of the app
+    let headers = path.join("shared/build/bin/iosSimulatorArm64/debugFramework/Shared.framework/Headers");
+    fs::create_dir_all(&headers)?;
+    fs::write(
+        headers.join("Shared.h"),
+        "__attribute__((swift_name(\"KotlinBase\")))\n@interface SharedBase : NSObject\n@end\n\n\
+         __attribute__((objc_subclassing_restricted))\n__attribute__((swift_name(\"Token_\")))\n\
+         @interface SharedToken_ : SharedBase\n@end\n",
+    )?;
+    fs::write(
+        path.join("iosApp/iosApp/TokenView.swift"),
+        "import Shared\n\nstruct TokenView {\n    let local = Token()\n    let shared = Token_()\n}\n",
+    )?;
+
+    let lines_using_token = |options: AnalysisOptions| -> Result<Vec<usize>> {
+        let analysis = Analyzer::with_configuration(options, Vec::new()).analyze(path.to_str().unwrap())?;
+        let mut lines: Vec<usize> =
+            analysis.symbol_usages.get("Token").into_iter().flatten().map(|usage| usage.line_number).collect();
+        lines.sort();
+        Ok(lines)
+    };
+    assert_eq!(lines_using_token(AnalysisOptions::default())?, vec![4, 5]);
+    let options = AnalysisOptions {
+        objc_headers: FrameworkHeaderSources {
+            paths: Vec::new(),
+            find_in_build_output: true,
+        },
+        ..Default::default()
+    };
+    assert_eq!(lines_using_token(options)?, vec![5]);
+
+    Ok(())
+}
+
+#[test]
+fn test_localized_report_numbers() -> Result<()> {
+    use kotlin_multiplatform_coverage::infrastructure::reporters::NumberFormat;
+    use kotlin_multiplatform_coverage::infrastructure::Reporter;
+
+    let temp_project = create_test_kmp_project()?;
+    let mut analysis = Analyzer::new().analyze(temp_project.path().to_str().unwrap())?;
+    analysis.total_app_lines = 12345;
+    analysis.impact_ratio = analysis.affected_lines as f64 / 12345.0;
+    let german = NumberFormat::for_locale("de-DE")?;
+
+    let md_path = temp_project.path().join("report.md");
+    Reporter::new("markdown")?
+        .with_number_format(german.clone())
+        .report_impact_analysis(&analysis, md_path.to_str())?;
+    let md = fs::read_to_string(&md_path)?;
+    assert!(md.contains(&format!("- **Impact Coverage**: {}\n", german.percent(analysis.impact_ratio))));
+    assert!(md.contains(&format!("- **Affected Lines**: {} / 12.345\n", analysis.affected_lines)));
+
+    // JSON keeps the raw numbers, reads back as a report, and adds the formatted text
+    let json_path = temp_project.path().join("report.json");
+    Reporter::new("json")?
+        .with_number_format(german)
+        .report_impact_analysis(&analysis, json_path.to_str())?;
+    let content = fs::read_to_string(&json_path)?;
+    let json: serde_json::Value = serde_json::from_str(&content)?;
+    assert_eq!(json["total_app_lines"], 12345);
+    assert_eq!(json["formatted"]["locale"], "de-DE");
+    assert_eq!(json["formatted"]["total_app_lines"], "12.345");
+    assert!(json["formatted"]["impact_ratio"].as_str().is_some_and(|ratio| ratio.contains(',')));
+    assert!(json["formatted"]["platforms"]["Android"]["impact_ratio"].is_string());
+    assert_eq!(json["ratios"]["impact_ratio"]["denominator"], 12345);
+    assert_eq!(json["ratios"]["precision"]["rounding"], "half-up");
+    let reread: ImpactAnalysis = serde_json::from_str(&content)?;
+    assert_eq!(reread.total_app_lines, 12345);
+
+    Ok(())
+}
+
+#[test]
+fn test_reads_reports_from_earlier_versions() -> Result<()> {
+    // A report with only the fields of the first release, as kept for baselines and `diff`
+    let baseline = r#"{
+        "total_symbols": 3,
+        "total_app_files": 2,
+        "total_app_lines": 100,
+        "affected_files": ["/repo/app/Main.kt"],
+        "affected_lines": 40,
+        "impact_ratio": 0.4,
+        "platform_impacts": {
+            "Android": {
+                "platform_name": "Android",
+                "total_files": 2,
+                "total_lines": 100,
+                "affected_files": ["/repo/app/Main.kt"],
+                "affected_lines": 40,
+                "impact_ratio": 0.4,
+                "top_symbols": [["User", 2]]
+            }
+        },
+        "symbol_usages": {
+            "User": [{"symbol_name": "User", "file_path": "/repo/app/Main.kt", "line_number": 3, "context": "val user = User()"}]
+        }
+    }"#;
+
+    let report: ImpactAnalysis = serde_json::from_str(baseline)?;
+    assert_eq!(report.affected_lines, 40);
+    assert_eq!(report.platform_impacts["Android"].top_symbols, vec![("User".to_string(), 2)]);
+    assert!(report.documentation.total_symbols == 0 && report.goals.is_empty() && report.diagnostics.is_empty());
+    assert!(report.metadata.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_problem_matcher_lines() -> Result<()> {
+    use kotlin_multiplatform_coverage::infrastructure::Reporter;
+
+    let temp_project = create_test_kmp_project()?;
+    let root = temp_project.path().to_str().unwrap();
+    let analysis = Analyzer::new().analyze(root)?;
+    let path = temp_project.path().join("problems.txt");
+    Reporter::new("problems")?
+        .with_project_root(root)
+        .report_impact_analysis(&analysis, path.to_str())?;
+    let output = fs::read_to_string(&path)?;
+
+    let activity = "app/src/main/java/com/example/android/MainActivity.kt";
+    assert!(output.contains(&format!("{}:11:29: note: uses KMP symbol UserRepository\n", activity)));
+    assert!(output.contains(&format!("{}:11:46: note: uses KMP symbol UserRepositoryImpl\n", activity)));
+    assert!(output
+        .lines()
+        .all(|line| !line.starts_with('/') && line.split(':').nth(2).is_some_and(|col| col.parse::<usize>().is_ok())));
+
+    Ok(())
+}
+
+#[cfg(feature = "treesitter")]
+#[test]
+fn test_tree_sitter_parser() -> Result<()> {
+    use kotlin_multiplatform_coverage::domain::SymbolParser;
+
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path().to_str().unwrap();
+    let options = AnalysisOptions {
+        parser: SymbolParser::TreeSitter,
+        ..Default::default()
+    };
+    let analysis = Analyzer::with_configuration(options, Vec::new()).analyze(path)?;
+    let regex = Analyzer::new().analyze(path)?;
+
+    // Both parsers see `data class User`; overrides are the interface's API
+    let count = |analysis: &ImpactAnalysis, name: &str| analysis.symbols.iter().filter(|s| s.name == name).count();
+    assert_eq!(count(&analysis, "User"), 1);
+    assert_eq!(count(&regex, "User"), 1);
+    assert_eq!(count(&analysis, "getUser"), 1);
+    assert!(analysis.affected_lines >= regex.affected_lines);
+
+    Ok(())
+}
+
+#[test]
+fn test_internal_symbols() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    // Before, a file mentioning `internal ` anywhere lost all of its public declarations
+    fs::write(
+        path.join("shared/src/commonMain/kotlin/com/example/Session.kt"),
+        "package com.example\n\nclass SessionStore {\n    internal fun clear() {}\n}\n\ninternal class TokenCache\n\nprivate fun trace() {}\n",
+    )?;
+    let path = path.to_str().unwrap();
+
+    let names = |analysis: &ImpactAnalysis| -> Vec<String> { analysis.symbols.iter().map(|s| s.name.clone()).collect() };
+    let public = names(&Analyzer::new().analyze(path)?);
+    assert!(public.contains(&"SessionStore".to_string()));
+    assert!(!public.contains(&"TokenCache".to_string()));
+    assert!(!public.contains(&"clear".to_string()));
+
+    let options = AnalysisOptions {
+        include_internal: true,
+        ..Default::default()
+    };
+    let analysis = Analyzer::with_configuration(options, Vec::new()).analyze(path)?;
+    let all = names(&analysis);
+    assert!(all.contains(&"TokenCache".to_string()));
+    assert!(all.contains(&"clear".to_string()));
+    assert!(!all.contains(&"trace".to_string()));
+    assert!(analysis.symbols.iter().any(|s| s.name == "TokenCache" && !s.is_public));
+
+    Ok(())
+}
+
+#[test]
+fn test_impact_metrics() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    fs::write(
+        path.join("app/src/main/java/com/example/android/Settings.kt"),
+        "package com.example.android\n\nclass Settings {\n    fun open() {}\n    fun close() {}\n}\n",
+    )?;
+    let path = path.to_str().unwrap();
+
+    let options = AnalysisOptions {
+        metric: ImpactMetric::Functions,
+        ..Default::default()
+    };
+    let analysis = Analyzer::with_configuration(options, Vec::new()).analyze(path)?;
+    let android = &analysis.platform_impacts["Android"];
+    assert_eq!((android.metrics.files.affected, android.metrics.files.total), (2, 3));
+    assert_eq!((android.metrics.functions.affected, android.metrics.functions.total), (3, 5));
+    assert_eq!(android.metrics.lines.total, android.total_lines);
+    assert_eq!(android.impact_ratio, 0.6);
+
+    // The overall ratio and thresholds count functions too
+    let functions = analysis.metrics.functions;
+    assert_eq!((functions.affected, functions.total), (5, 7));
+    assert_eq!(analysis.exact_impact_ratio(), Ratio::new(5, 7));
+    assert_eq!(analysis.affected_of_total(), (5, 7));
+    let threshold = ImpactThreshold::parse(ThresholdKind::Min, "android=60").unwrap();
+    assert!(analysis.violated_thresholds(&[threshold]).is_empty());
+
+    let lines = Analyzer::new().analyze(path)?;
+    assert_eq!(lines.metric, ImpactMetric::Lines);
+    assert_eq!(lines.metrics, analysis.metrics);
+    assert_eq!(lines.exact_impact_ratio(), Ratio::new(lines.affected_lines, lines.total_app_lines));
+
+    assert_eq!(ImpactMetric::parse("Files"), Ok(ImpactMetric::Files));
+    assert!(ImpactMetric::parse("symbols").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_extension_and_companion_usage() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    fs::write(
+        path.join("shared/src/commonMain/kotlin/com/example/Session.kt"),
+        "package com.example\n\nfun String.toSlug(): String = lowercase()\n\nclass Session {\n    companion object {\n        @JvmStatic fun restore(): Session = Session()\n    }\n}\n",
+    )?;
+    fs::write(
+        path.join("app/src/main/java/com/example/android/Restore.kt"),
+        "package com.example.android\n\nfun restore(title: String) {\n    val session = com.example.Session.restore()\n    println(title.toSlug())\n}\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    for name in ["toSlug", "restore"] {
+        let usages = &analysis.symbol_usages[name];
+        assert!(usages.iter().any(|u| u.file_path.ends_with("Restore.kt")), "{} is not attributed", name);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_enum_entry_usage() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    fs::write(
+        path.join("shared/src/commonMain/kotlin/com/example/Role.kt"),
+        "package com.example\n\nenum class Role {\n    ADMIN,\n    MEMBER,\n}\n\nsealed interface Session\n",
+    )?;
+    fs::write(
+        path.join("app/src/main/java/com/example/android/Access.kt"),
+        "package com.example.android\n\nimport com.example.Role\n\nfun isAdmin(role: Role) = when (role) {\n    Role.ADMIN -> true\n    else -> false\n}\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    let kind = |name: &str| analysis.symbols.iter().find(|s| s.name == name).map(|s| s.symbol_type.clone());
+    assert_eq!(kind("Role"), Some(SymbolType::Enum));
+    assert_eq!(kind("ADMIN"), Some(SymbolType::EnumEntry));
+    assert_eq!(kind("Session"), Some(SymbolType::SealedClass));
+    assert_eq!(kind("User"), Some(SymbolType::DataClass));
+    assert!(analysis.symbol_usages["ADMIN"].iter().any(|u| u.file_path.ends_with("Access.kt")));
+    assert!(!analysis.symbol_usages.contains_key("MEMBER"));
+
+    Ok(())
+}
+
+#[test]
+fn test_web_app_usage() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    fs::write(
+        path.join("shared/src/commonMain/kotlin/com/example/Session.kt"),
+        "package com.example\n\n@JsExport\n@JsName(\"WebSession\")\nclass Session(val token: String)\n",
+    )?;
+    fs::create_dir_all(path.join("web/src"))?;
+    fs::write(path.join("web/package.json"), "{ \"name\": \"web\" }")?;
+    fs::write(
+        path.join("web/src/App.ts"),
+        "import { WebSession as AppSession } from \"shared\";\n\n// The session of the signed in user\nexport const session = new AppSession(\"token\");\n",
+    )?;
+    fs::write(path.join("web/src/shared.d.ts"), "export declare class WebSession {}\n")?;
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    let web = &analysis.platform_impacts["Web"];
+    assert_eq!(web.total_files, 1);
+    assert_eq!(web.total_lines, 2);
+    assert!(web.affected_files.iter().any(|f| f.ends_with("App.ts")));
+
+    assert_eq!(analysis.symbol_usages["Session"][0].line_number, 4);
+
+    // The renamed import of the exported name is credited to the shared symbol
+    let source_file_repo = SourceFileRepositoryImpl::new();
+    let symbols = SymbolRepositoryImpl::new().extract_kmp_symbols(&source_file_repo.find_kmp_files(path.to_str().unwrap())?)?;
+    let app_file = source_file_repo.read_source_file(path.join("web/src/App.ts").to_str().unwrap())?;
+    let usages = SymbolUsageRepositoryImpl::new().detect_symbol_usage(&app_file, &symbols)?;
+    let kinds: Vec<_> = usages.iter().map(|u| (u.symbol_name.as_str(), u.line_number, u.kind)).collect();
+    assert!(kinds.contains(&("Session", 1, UsageKind::ImportOnly)));
+    assert!(kinds.contains(&("Session", 4, UsageKind::Instantiation)));
+
+    Ok(())
+}
+
+#[test]
+fn test_impact_thresholds() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let analysis = Analyzer::new().analyze(temp_project.path().to_str().unwrap())?;
+    let overall = analysis.impact_ratio * 100.0;
+    let threshold = |kind, spec: &str| ImpactThreshold::parse(kind, spec).unwrap();
+
+    let met = [
+        threshold(ThresholdKind::Min, &format!("{}", overall.floor())),
+        threshold(ThresholdKind::Max, "100"),
+        threshold(ThresholdKind::Min, "Android=0"),
+    ];
+    assert!(analysis.violated_thresholds(&met).is_empty());
+
+    let violations = analysis.violated_thresholds(&[
+        threshold(ThresholdKind::Max, "0"),
+        threshold(ThresholdKind::Min, "server=10%"),
+    ]);
+    assert_eq!(violations.len(), 2);
+    assert!(violations[0].starts_with("Overall impact"));
+    assert!(violations[0].ends_with("is above the maximum of 0%"));
+    assert_eq!(violations[1], "Server impact 0.00% is below the minimum of 10%");
+
+    assert!(ImpactThreshold::parse(ThresholdKind::Min, "watchos=10").is_err());
+    assert!(ImpactThreshold::parse(ThresholdKind::Min, "120").is_err());
+    assert!(ImpactThreshold::parse(ThresholdKind::Max, "lots").is_err());
+
+    // 143/1000 is exactly 14.3%, though `14.3 / 100.0` is a hair above `143.0 / 1000.0`
+    let mut exact = ImpactAnalysis {
+        affected_lines: 143,
+        total_app_lines: 1000,
+        ..Default::default()
+    };
+    exact.calculate_impact_ratio();
+    let bounds = [
+        threshold(ThresholdKind::Min, "14.3"),
+        threshold(ThresholdKind::Max, "14.3%"),
+    ];
+    assert!(exact.violated_thresholds(&bounds).is_empty());
+    assert_eq!(exact.violated_thresholds(&[threshold(ThresholdKind::Min, "14.31")]).len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_warm_start_matches_cold_analysis() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path().to_str().unwrap();
+    let headline = |analysis: &ImpactAnalysis| {
+        let mut affected: Vec<String> = analysis.affected_files.iter().cloned().collect();
+        affected.sort();
+        (analysis.total_symbols, analysis.affected_lines, analysis.total_app_lines, affected)
+    };
+
+    let cold = Analyzer::new().analyze_with_warm_start(path, WarmStartState::default())?;
+    let state = cold.warm_start.clone().expect("warm start data is recorded");
+    assert!(state.files.values().any(|file| !file.symbols.is_empty()));
+    assert!(state.files.values().any(|file| !file.usages.is_empty()));
+
+    // Resumed from the JSON report, in a fresh process without any in-memory caches
+    let report: ImpactAnalysis = serde_json::from_str(&serde_json::to_string(&cold)?)?;
+    let warm = Analyzer::new().analyze_with_warm_start(path, report.warm_start.unwrap())?;
+    assert_eq!(headline(&warm), headline(&cold));
+
+    let android = temp_project.path().join("app/src/main/java/com/example/android");
+    fs::write(android.join("UserAdapter.kt"), "package com.example.android\n\nclass UserAdapter\n")?;
+    let changed = Analyzer::new().analyze_with_warm_start(path, state)?;
+    assert!(!changed.affected_files.iter().any(|f| f.ends_with("UserAdapter.kt")));
+    assert_eq!(headline(&changed), headline(&Analyzer::new().analyze(path)?));
+
+    Ok(())
+}
+
+#[test]
+fn test_test_only_symbols_are_excluded() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    // Flat source set layout, which the KMP source set lookup also covers
+    let tests = path.join("shared/commonTest/kotlin/com/example");
+    fs::create_dir_all(&tests)?;
+    fs::write(
+        tests.join("FakeUserRepository.kt"),
+        "package com.example\n\nclass FakeUserRepository : UserRepository\n",
+    )?;
+
+    let has_fake = |analysis: &ImpactAnalysis| {
+        analysis.symbols.iter().any(|s| s.name == "FakeUserRepository")
+    };
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    assert!(!has_fake(&analysis));
+    assert!(analysis.symbols.iter().any(|s| s.name == "UserRepository"));
+
+    let options = AnalysisOptions {
+        include_test_symbols: true,
+        ..Default::default()
+    };
+    let analysis =
+        Analyzer::with_configuration(options, Vec::new()).analyze(path.to_str().unwrap())?;
+    assert!(has_fake(&analysis));
+
+    Ok(())
+}
+
+#[test]
+fn test_scoped_analysis() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path().to_str().unwrap();
+
+    let scoped = |roots: &[&str]| {
+        let options = AnalysisOptions {
+            scope: AnalysisScope::new(roots.iter().map(|r| r.to_string()).collect()),
+            ..Default::default()
+        };
+        Analyzer::with_configuration(options, Vec::new()).analyze(path)
+    };
+
+    let analysis = scoped(&["shared", "app/"])?;
+    assert!(analysis.platform_impacts.contains_key("Android"));
+    assert!(!analysis.platform_impacts.contains_key("iOS"));
+    assert!(analysis.symbols.iter().any(|s| s.name == "UserRepository"));
+    assert!(analysis.affected_lines > 0);
+
+    // Without the shared subtree there is nothing to be affected by
+    let analysis = scoped(&["./app"])?;
+    assert!(analysis.symbols.is_empty());
+    assert_eq!(analysis.affected_lines, 0);
+    assert!(analysis.total_app_lines > 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_variant_impact() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let root = temp_project.path();
+    let paid = root.join("app/src/paid/java/com/example/android");
+    fs::create_dir_all(&paid)?;
+    fs::write(
+        paid.join("PremiumSync.kt"),
+        "package com.example.android\n\nimport com.example.UserRepository\nimport com.example.UserRepositoryImpl\n\nclass PremiumSync {\n    private val repository: UserRepository = UserRepositoryImpl()\n}\n",
+    )?;
+    let free = root.join("app/src/freeDebug/java/com/example/android");
+    fs::create_dir_all(&free)?;
+    fs::write(
+        free.join("AdBanner.kt"),
+        "package com.example.android\n\nclass AdBanner {\n    fun show() = println(\"ad\")\n}\n",
+    )?;
+
+    let variants = vec!["paid".to_string(), "free".to_string()];
+    assert_eq!(path_variant(Path::new("app/src/paidRelease/Main.kt"), &variants), Some("paid"));
+    assert_eq!(path_variant(Path::new("app/src/main/Main.kt"), &variants), None);
+    assert_eq!(path_variant(Path::new("iosAppFree/Views/Main.swift"), &variants), Some("free"));
+    assert_eq!(path_variant(Path::new("app/src/freemium/Main.kt"), &variants), None);
+
+    let options = AnalysisOptions {
+        detection: DetectionSettings {
+            variants,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let analysis = Analyzer::with_configuration(options, Vec::new()).analyze(root.to_str().unwrap())?;
+    assert_eq!(analysis.variants.len(), 2);
+    let (paid, free) = (&analysis.variants[0], &analysis.variants[1]);
+    assert_eq!((paid.name.as_str(), free.name.as_str()), ("paid", "free"));
+    assert_eq!((paid.specific_files, free.specific_files), (1, 1));
+    // Both variants share every other file; only the variant-specific ones differ
+    assert_eq!(paid.total_files, free.total_files);
+    assert!(paid.affected_lines > free.affected_lines);
+    assert!(paid.impact_ratio > free.impact_ratio);
+    assert!(paid.platform_ratios.contains_key("Android"));
+
+    Ok(())
+}
+
+#[test]
+fn test_usage_kinds() -> Result<()> {
+    assert_eq!(UsageKind::classify("class Cache : UserRepository {", "UserRepository"), UsageKind::Inheritance);
+    assert_eq!(UsageKind::classify("struct Row: Identifiable, UserView {", "UserView"), UsageKind::Inheritance);
+    assert_eq!(UsageKind::classify("Foo foo = new User(id);", "User"), UsageKind::Instantiation);
+    assert_eq!(UsageKind::classify("fun show(users: List<User>)", "User"), UsageKind::TypeAnnotation);
+    assert_eq!(UsageKind::classify("let user = value as? User", "User"), UsageKind::TypeAnnotation);
+    assert_eq!(UsageKind::classify("Platform.shared.name", "Platform"), UsageKind::StaticAccess);
+    assert_eq!(UsageKind::classify("import com.example.User", "User"), UsageKind::ImportOnly);
+    assert_eq!(UsageKind::classify("val name = formatUserName(user)", "formatUserName"), UsageKind::Reference);
+
+    let temp_project = create_test_kmp_project()?;
+    let analysis = Analyzer::new().analyze(temp_project.path().to_str().unwrap())?;
+
+    let kind_at = |symbol: &str, context: &str| {
+        analysis.symbol_usages[symbol]
+            .iter()
+            .find(|u| u.context.starts_with(context))
+            .map(|u| u.kind)
+    };
+    let declaration = "private val repository: UserRepository = UserRepositoryImpl()";
+    assert_eq!(kind_at("UserRepository", declaration), Some(UsageKind::TypeAnnotation));
+    assert_eq!(kind_at("UserRepositoryImpl", declaration), Some(UsageKind::Instantiation));
+    assert_eq!(kind_at("Logger", "Logger.log("), Some(UsageKind::StaticAccess));
+
+    // Imports are counted per kind but never become usages
+    assert!(analysis
+        .symbol_usages
+        .values()
+        .flatten()
+        .all(|u| u.kind != UsageKind::ImportOnly));
+    let imports = analysis.usage_kinds.iter().find(|k| k.kind == UsageKind::ImportOnly).unwrap();
+    assert_eq!((imports.usages, imports.files), (6, 2));
+    assert!(analysis.usage_kinds.iter().any(|k| k.kind == UsageKind::Instantiation));
+
+    Ok(())
+}
+
+#[test]
+fn test_import_only_matches() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let root = temp_project.path();
+    let android = root.join("app/src/main/java/com/example/android");
+    fs::write(
+        android.join("Stale.kt"),
+        "package com.example.android\n\nimport com.example.Logger\n\nclass Stale {\n    fun run() = println(\"no shared code\")\n}\n",
+    )?;
+    fs::write(
+        android.join("Aliased.kt"),
+        "package com.example.android\n\nimport com.example.UserRepositoryImpl as Repo\n\nclass Aliased {\n    private val repo = Repo()\n}\n",
+    )?;
+    let path = root.to_str().unwrap();
+    let is_file = |file: &str, name: &str| file.ends_with(name);
+
+    let analysis = Analyzer::new().analyze(path)?;
+    assert!(!analysis.affected_files.iter().any(|f| is_file(f, "Stale.kt")));
+    assert!(analysis.affected_files.iter().any(|f| is_file(f, "Aliased.kt")));
+    let stale: Vec<(&str, usize)> = analysis
+        .unused_imports
+        .iter()
+        .map(|u| (u.symbol_name.as_str(), u.line_number))
+        .collect();
+    assert_eq!(stale, vec![("Logger", 3)]);
+    assert!(is_file(&analysis.unused_imports[0].file_path, "Stale.kt"));
+    let aliased = analysis.symbol_usages["UserRepositoryImpl"]
+        .iter()
+        .find(|u| is_file(&u.file_path, "Aliased.kt"))
+        .unwrap();
+    assert_eq!((aliased.line_number, aliased.kind), (6, UsageKind::Instantiation));
+
+    let options = AnalysisOptions {
+        count_imports: true,
+        ..Default::default()
+    };
+    let counted = Analyzer::with_configuration(options, Vec::new()).analyze(path)?;
+    assert!(counted.affected_files.iter().any(|f| is_file(f, "Stale.kt")));
+    assert!(counted.affected_lines > analysis.affected_lines);
+    assert_eq!(counted.unused_imports.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_stale_integrations() -> Result<()> {
+    use kotlin_multiplatform_coverage::infrastructure::Reporter;
+
+    let temp_project = create_test_kmp_project()?;
+    let root = temp_project.path();
+    fs::write(
+        root.join("app/src/main/java/com/example/android/Stale.kt"),
+        "package com.example.android\n\nimport com.example.Logger\n\nclass Stale {\n    fun run() = println(\"no shared code\")\n}\n",
+    )?;
+    fs::write(
+        root.join("iosApp/iosApp/Orphan.swift"),
+        "import SwiftUI\nimport Shared\n\nstruct Orphan: View {\n    var body: some View { Text(\"Hello\") }\n}\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(root.to_str().unwrap())?;
+    let stale: Vec<(&str, &str, Vec<&str>)> = analysis
+        .stale_integrations
+        .iter()
+        .map(|s| {
+            let name = Path::new(&s.file_path).file_name().unwrap().to_str().unwrap();
+            (name, s.platform.as_str(), s.imports.iter().map(String::as_str).collect())
+        })
+        .collect();
+    assert_eq!(stale, vec![("Stale.kt", "Android", vec!["Logger"]), ("Orphan.swift", "iOS", vec![])]);
+
+    let md_path = root.join("report.md");
+    Reporter::new("markdown")?.report_impact_analysis(&analysis, md_path.to_str())?;
+    let md = fs::read_to_string(&md_path)?;
+    assert!(md.contains("## 🔌 Stale Integrations (2)"));
+    assert!(md.contains("(iOS) imports the shared framework\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_batch_matrix() -> Result<()> {
+    use kotlin_multiplatform_coverage::infrastructure::{BatchRunner, BatchSource, Reporter};
+
+    let first = create_test_kmp_project()?;
+    let second = create_test_kmp_project()?;
+    let workspace = tempfile::tempdir()?;
+    let list = format!(
+        "# Apps of the platform org\n{} consumer\n{} wallet\nmissing-app\n",
+        first.path().display(),
+        second.path().display()
+    );
+    let sources = BatchSource::parse_list(&list)?;
+    assert_eq!(sources.len(), 3);
+    assert!(sources.iter().all(|source| !source.is_remote()));
+
+    let summary = BatchRunner::new(AnalysisOptions::default(), Vec::new(), workspace.path()).run(&sources);
+    let names: Vec<&str> = summary.repositories.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["consumer", "wallet", "missing-app"]);
+    let (consumer, wallet) = (&summary.repositories[0], &summary.repositories[1]);
+    assert!(consumer.error.is_none());
+    assert!(consumer.impact_ratio > 0.0);
+    assert_eq!(consumer.impact_ratio, wallet.impact_ratio);
+    assert!(consumer.platform_ratios.contains_key("Android"));
+    assert!(summary.platforms().contains(&"Android"));
+    assert_eq!(summary.failures().len(), 1);
+    assert!(summary.failures()[0].error.as_deref().unwrap().contains("missing-app"));
+
+    let csv_path = workspace.path().join("matrix.csv");
+    Reporter::new("csv")?.report_batch(&summary, csv_path.to_str())?;
+    let csv = fs::read_to_string(&csv_path)?;
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("repository,location,commit,impact_percent,affected_lines,app_lines,"));
+    assert!(lines[0].contains("android_percent") && lines[0].ends_with(",error"));
+    assert!(lines[1].starts_with("consumer,"));
+    assert!(lines[3].starts_with("missing-app,missing-app,,0.00,0,0,"));
+
+    let md_path = workspace.path().join("matrix.md");
+    Reporter::new("markdown")?.report_batch(&summary, md_path.to_str())?;
+    let md = fs::read_to_string(&md_path)?;
+    assert!(md.contains("| consumer | `-` |"));
+    assert!(md.contains("## ⚠️ Failed Repositories (1)"));
+    assert!(Reporter::new("csv")?.report_impact_analysis(&ImpactAnalysis::default(), None).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_entry_points_collapse_into_facades() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let root = temp_project.path();
+    let sdk = root.join("shared/src/commonMain/kotlin/com/example/sdk");
+    fs::create_dir_all(&sdk)?;
+    fs::write(
+        sdk.join("AppSdk.kt"),
+        "package com.example.sdk\n\nimport com.example.UserRepository\n\nobject AppSdk {\n    fun users(): UserRepository = com.example.UserRepositoryImpl()\n}\n",
+    )?;
+    let android = root.join("app/src/main/java/com/example/android");
+    fs::write(
+        android.join("SdkScreen.kt"),
+        "package com.example.android\n\nimport com.example.sdk.AppSdk\n\nclass SdkScreen {\n    private val users = AppSdk.users()\n}\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(root.to_str().unwrap())?;
+    let entry_points = &analysis.platform_impacts["Android"].entry_points;
+    assert!(!entry_points.is_empty());
+    // The repository file is reached through the facade, so it is no entry point of its own
+    let sdk_entry = entry_points.iter().find(|e| e.symbol == "AppSdk").expect("AppSdk entry point");
+    assert!(sdk_entry.file.ends_with("AppSdk.kt"));
+    assert!(sdk_entry.reached_symbols.contains(&"UserRepository".to_string()));
+    assert!(entry_points.iter().all(|e| !e.file.ends_with("User.kt")));
+    assert_eq!(entry_points[0].symbol, "AppSdk");
+    assert!(sdk_entry.share > 0.0 && sdk_entry.share <= 1.0);
+
+    let limited = Analyzer::with_configuration(
+        AnalysisOptions {
+            max_entry_points: Some(1),
+            ..Default::default()
+        },
+        Vec::new(),
+    )
+    .analyze(root.to_str().unwrap())?;
+    assert_eq!(limited.platform_impacts["Android"].entry_points.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_suppression_baseline() -> Result<()> {
+    use kotlin_multiplatform_coverage::adapters::SuppressionRepositoryImpl;
+    use kotlin_multiplatform_coverage::domain::{Suppression, SuppressionBaseline, SuppressionRepository};
+
+    let temp_project = create_test_kmp_project()?;
+    let root = temp_project.path().to_str().unwrap();
+    let unsuppressed = Analyzer::new().analyze(root)?;
+    let main_activity = "app/src/main/java/com/example/android/MainActivity.kt";
+    let in_main = |analysis: &ImpactAnalysis| {
+        analysis
+            .symbol_usages
+            .get("Logger")
+            .map_or(0, |usages| usages.iter().filter(|u| u.file_path.ends_with("MainActivity.kt")).count())
+    };
+    let logger_usages = in_main(&unsuppressed);
+    assert!(logger_usages > 0);
+
+    // The baseline round-trips through its file
+    let repository = SuppressionRepositoryImpl::new(temp_project.path().join("kmp-coverage-suppressions.json"));
+    assert!(repository.load()?.is_empty());
+    let stale = Suppression {
+        symbol: "Logger".to_string(),
+        file: "app/src/main/java/com/example/android/Removed.kt".to_string(),
+        reason: None,
+    };
+    repository.save(&SuppressionBaseline {
+        suppressions: vec![
+            Suppression {
+                symbol: "Logger".to_string(),
+                file: format!("./{}", main_activity),
+                reason: Some("debug logging only".to_string()),
+            },
+            stale.clone(),
+        ],
+    })?;
+
+    let options = AnalysisOptions {
+        suppressions: repository.load()?,
+        ..Default::default()
+    };
+    let suppressed = Analyzer::with_configuration(options, Vec::new()).analyze(root)?;
+    assert_eq!(in_main(&suppressed), 0);
+    assert_eq!(suppressed.suppressed_usages, logger_usages);
+    assert_eq!(suppressed.stale_suppressions, vec![stale]);
+    let total = |analysis: &ImpactAnalysis| analysis.symbol_usages.values().map(Vec::len).sum::<usize>();
+    assert_eq!(total(&suppressed), total(&unsuppressed) - logger_usages);
+
+    Ok(())
+}
+
+#[test]
+fn test_file_metrics() -> Result<()> {
+    use kotlin_multiplatform_coverage::infrastructure::Reporter;
+
+    let temp_project = create_test_kmp_project()?;
+    let analysis = Analyzer::new().analyze(temp_project.path().to_str().unwrap())?;
+
+    assert_eq!(analysis.files.len(), analysis.total_app_files);
+    assert!(analysis.files.windows(2).all(|pair| pair[0].path < pair[1].path));
+    assert_eq!(analysis.files.iter().map(|f| f.total_lines).sum::<usize>(), analysis.total_app_lines);
+    assert_eq!(analysis.files.iter().map(|f| f.affected_lines).sum::<usize>(), analysis.affected_lines);
+
+    let main = analysis.files.iter().find(|f| f.path.ends_with("MainActivity.kt")).unwrap();
+    assert_eq!(main.platform, "Android");
+    assert!(main.direct);
+    assert_eq!(main.affected_lines, main.total_lines);
+    assert!(main.symbols.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(["Logger", "UserRepositoryImpl", "formatUserName"]
+        .iter()
+        .all(|name| main.symbols.iter().any(|s| s == name)));
+    let main_usages: usize = analysis
+        .symbol_usages
+        .values()
+        .flatten()
+        .filter(|u| u.file_path == main.path)
+        .count();
+    assert_eq!(main.usages, main_usages);
+
+    let json_path = temp_project.path().join("report.json");
+    Reporter::new("json")?.report_impact_analysis(&analysis, json_path.to_str())?;
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&json_path)?)?;
+    let files = json["files"].as_array().unwrap();
+    assert_eq!(files.len(), analysis.files.len());
+    assert!(files.iter().any(|f| f["platform"] == "iOS" && f["symbols"].as_array().is_some_and(|s| !s.is_empty())));
+    // The extracted declarations stay out of the report; usages name their symbols
+    assert!(!analysis.symbols.is_empty());
+    assert!(json.get("symbols").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_api_owners_from_owners_file() -> Result<()> {
+    use kotlin_multiplatform_coverage::infrastructure::Reporter;
+
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path().to_str().unwrap();
+    assert!(Analyzer::new().analyze(path)?.api_owners.is_empty());
+
+    fs::write(
+        temp_project.path().join("shared/OWNERS"),
+        "# Shared API owners\n* @platform-team\ncom.example @mobile-core alice@example.com\ncom.example.legacy @legacy-team\n",
+    )?;
+    let analysis = Analyzer::new().analyze(path)?;
+
+    assert!(!analysis.api_owners.is_empty());
+    assert!(analysis.api_owners.windows(2).all(|pair| pair[0].usages >= pair[1].usages));
+    assert!(analysis
+        .api_owners
+        .iter()
+        .all(|api| api.owners == ["@mobile-core", "alice@example.com"]));
+    let logger = analysis.api_owners.iter().find(|api| api.symbol == "Logger").unwrap();
+    assert_eq!(logger.usages, analysis.symbol_usages["Logger"].len());
+
+    let md_path = temp_project.path().join("report.md");
+    Reporter::new("markdown")?.report_impact_analysis(&analysis, md_path.to_str())?;
+    let md = fs::read_to_string(&md_path)?;
+    assert!(md.contains("## 👥 Owners of Most Used Shared APIs"));
+    assert!(md.contains("| Logger | `com.example` |"));
+
+    Ok(())
+}
+
+#[test]
+fn test_report_sections() -> Result<()> {
+    use kotlin_multiplatform_coverage::infrastructure::reporters::ReportSection;
+    use kotlin_multiplatform_coverage::infrastructure::Reporter;
+
+    let temp_project = create_test_kmp_project()?;
+    let analysis = Analyzer::new().analyze(temp_project.path().to_str().unwrap())?;
+    let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+    let render = |sections: &[&str]| -> Result<String> {
+        let md_path = temp_project.path().join("report.md");
+        Reporter::new("markdown")?
+            .with_sections(ReportSection::select(&names(sections))?)
+            .report_impact_analysis(&analysis, md_path.to_str())?;
+        Ok(fs::read_to_string(&md_path)?)
+    };
+
+    let full = render(&[])?;
+    assert!(full.contains("## 📱 Platform Impact Breakdown"));
+    assert!(full.contains("## 🎯 Top Used KMP Symbols"));
+    assert!(full.contains("## 📝 Documentation Coverage"));
+
+    let summary = render(&["platforms"])?;
+    assert!(summary.contains("## 📊 Impact Summary"));
+    assert!(summary.contains("## 📱 Platform Impact Breakdown"));
+    assert!(!summary.contains("## 🎯 Top Used KMP Symbols"));
+    assert!(!summary.contains("## 📝 Documentation Coverage"));
+
+    let trimmed = render(&["no-breakdowns", "no-modules"])?;
+    assert!(trimmed.contains("## 🎯 Top Used KMP Symbols"));
+    assert!(!trimmed.contains("## 📝 Documentation Coverage"));
+    assert!(!trimmed.contains("## 🕸️ Sharing Topology"));
+
+    let error = ReportSection::select(&names(&["platform"])).unwrap_err().to_string();
+    assert!(error.contains("Unknown report section `platform`"));
+
+    Ok(())
+}
+
+#[test]
+fn test_ui_layout_files() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    fs::write(
+        path.join("app/src/main/java/com/example/android/ProfileScreen.kt"),
+        r#"package com.example.android
+
+@Composable
+fun ProfileScreen(name: String) {
+    Column {
+        Text(formatUserName(name))
+    }
+}
+
+@Preview
+@Composable
+fun ProfileScreenPreview() = ProfileScreen("Ada")
+"#,
+    )?;
+    fs::write(
+        path.join("iosApp/iosApp/BadgeView.swift"),
+        "import SwiftUI\n\nstruct BadgeView: View {\n    var body: some View {\n        Text(\"New\")\n    }\n}\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    let ui_files: Vec<&str> = analysis
+        .files
+        .iter()
+        .filter(|f| f.ui_layout)
+        .map(|f| f.path.rsplit('/').next().unwrap())
+        .collect();
+    assert_eq!(ui_files, vec!["ProfileScreen.kt", "BadgeView.swift"]);
+
+    let ui_layout = &analysis.ui_layout;
+    assert_eq!(ui_layout.files, 2);
+    let ui_lines: usize = analysis.files.iter().filter(|f| f.ui_layout).map(|f| f.total_lines).sum();
+    assert_eq!(ui_layout.total_lines, ui_lines);
+    let expected = (analysis.affected_lines - ui_layout.affected_lines) as f64
+        / (analysis.total_app_lines - ui_layout.total_lines) as f64;
+    assert!((ui_layout.impact_ratio_excluding_ui - expected).abs() < 1e-9);
+    assert_eq!(analysis.platform_impacts["iOS"].ui_layout.files, 1);
+    assert_eq!(analysis.platform_impacts["iOS"].ui_layout.affected_lines, 0);
+
+    // Configured paths count as UI layout whatever they declare
+    let options = AnalysisOptions {
+        ui_layout_paths: vec!["*ViewModel.swift".to_string()],
+        ..Default::default()
+    };
+    let configured = Analyzer::with_configuration(options, Vec::new()).analyze(path.to_str().unwrap())?;
+    assert_eq!(configured.ui_layout.files, 3);
+    assert!(configured.files.iter().any(|f| f.ui_layout && f.path.ends_with("UserViewModel.swift")));
+
+    Ok(())
+}
+
+#[test]
+fn test_layer_impact() -> Result<()> {
+    use kotlin_multiplatform_coverage::domain::{AppLayer, AppLayers};
+
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    let android = path.join("app/src/main/java/com/example/android");
+    fs::create_dir_all(android.join("data"))?;
+    fs::create_dir_all(android.join("ui"))?;
+    fs::write(
+        android.join("data/UserStore.kt"),
+        "package com.example.android.data\n\nclass UserStore {\n    fun save() {\n        Logger.log(\"saved\")\n    }\n}\n",
+    )?;
+    fs::write(
+        android.join("ui/Header.kt"),
+        "package com.example.android.ui\n\nclass Header {\n    val title = \"Users\"\n}\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(path.to_str().unwrap())?;
+    let layers = &analysis.platform_impacts["Android"].layers;
+    let names: Vec<&str> = layers.iter().map(|l| l.layer.as_str()).collect();
+    assert_eq!(names, vec!["presentation", "data", AppLayers::OTHER]);
+    assert_eq!(layers.iter().map(|l| l.total_lines).sum::<usize>(), analysis.platform_impacts["Android"].total_lines);
+
+    let presentation = &layers[0];
+    assert_eq!((presentation.total_files, presentation.affected_files, presentation.affected_lines), (1, 0, 0));
+    let data = &layers[1];
+    assert_eq!(data.affected_files, 1);
+    assert_eq!(data.impact_ratio, 1.0);
+    let store = analysis.files.iter().find(|f| f.path.ends_with("UserStore.kt")).unwrap();
+    assert_eq!(store.layer, "data");
+
+    // Configured rules replace the built-in layers
+    let options = AnalysisOptions {
+        layers: AppLayers::new(vec![AppLayer {
+            name: "storage".to_string(),
+            paths: vec!["*Store.kt".to_string()],
+            ..Default::default()
+        }]),
+        ..Default::default()
+    };
+    let configured = Analyzer::with_configuration(options, Vec::new()).analyze(path.to_str().unwrap())?;
+    let names: Vec<&str> = configured.platform_impacts["Android"].layers.iter().map(|l| l.layer.as_str()).collect();
+    assert_eq!(names, vec!["storage", AppLayers::OTHER]);
+
+    Ok(())
+}
+
+#[test]
+fn test_transitive_files_record_import_chain() -> Result<()> {
+    let temp_project = create_test_kmp_project()?;
+    let android = temp_project.path().join("app/src/main/java/com/example/android");
+    fs::create_dir_all(android.join("nav"))?;
+    fs::write(
+        android.join("nav/Navigator.kt"),
+        "package com.example.android.nav\n\nimport com.example.android.MainActivity\n\nclass Navigator {\n    fun start() = MainActivity().createUser()\n}\n",
+    )?;
+    fs::write(
+        android.join("Router.kt"),
+        "package com.example.android\n\nimport com.example.android.nav.Navigator\n\nclass Router {\n    val navigator = Navigator()\n}\n",
+    )?;
+
+    let analysis = Analyzer::new().analyze(temp_project.path().to_str().unwrap())?;
+    let file = |name: &str| analysis.files.iter().find(|f| f.path.ends_with(name)).unwrap();
+
+    // Files using shared code directly need no chain
+    assert!(file("MainActivity.kt").affected_via.is_empty());
+
+    let chain = &file("Router.kt").affected_via;
+    assert_eq!(chain.len(), 2);
+    assert!(chain[0].from.ends_with("Router.kt") && chain[0].to.ends_with("Navigator.kt"));
+    assert!(chain[1].from.ends_with("Navigator.kt") && chain[1].to.ends_with("MainActivity.kt"));
+    let import = &chain[1].imports[0];
+    assert_eq!(import.line, 3);
+    assert_eq!(import.statement, "import com.example.android.MainActivity");
+    assert!(import.used);
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_restricts_analysis_to_touched_symbols() -> Result<()> {
+    use kotlin_multiplatform_coverage::domain::DiffScope;
+    use kotlin_multiplatform_coverage::utils::GitUtils;
+
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    let repo = git2::Repository::init(path)?;
+    let mut index = repo.index()?;
+    index.add_all(["*"], git2::IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = git2::Signature::now("Test", "test@example.com")?;
+    repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[])?;
+
+    // Change the body of formatUserName only
+    let utils = path.join("shared/src/commonMain/kotlin/com/example/Utils.kt");
+    let content = fs::read_to_string(&utils)?;
+    fs::write(&utils, content.replace("return \"${user.name} <${user.email}>\"", "return user.name.trim()"))?;
+
+    let files = GitUtils::changed_lines(path, "HEAD")?;
+    assert_eq!(files.keys().collect::<Vec<_>>(), vec!["shared/src/commonMain/kotlin/com/example/Utils.kt"]);
+
+    let options = AnalysisOptions {
+        diff: Some(DiffScope { base_ref: "HEAD".to_string(), files }),
+        ..Default::default()
+    };
+    let analysis = Analyzer::with_configuration(options, Vec::new()).analyze(path.to_str().unwrap())?;
+    let diff = analysis.diff.as_ref().unwrap();
+    assert_eq!(diff.touched_symbols, vec!["formatUserName"]);
+    assert_eq!(diff.changed_files.len(), 1);
+    assert!(analysis.symbol_usages.keys().all(|name| name == "formatUserName"));
+
+    let affected: Vec<&str> = analysis
+        .files
+        .iter()
+        .filter(|f| f.affected_lines > 0)
+        .map(|f| f.path.rsplit('/').next().unwrap())
+        .collect();
+    assert!(affected.contains(&"MainActivity.kt"));
+    assert!(!affected.contains(&"UserAdapter.kt"));
+
+    Ok(())
+}
+
+#[test]
+fn test_review_pack_of_diff() -> Result<()> {
+    use kotlin_multiplatform_coverage::adapters::OsFileSystem;
+    use kotlin_multiplatform_coverage::domain::DiffScope;
+    use kotlin_multiplatform_coverage::infrastructure::reporters::review_pack::{ReviewPack, SnippetKind};
+    use kotlin_multiplatform_coverage::utils::GitUtils;
+
+    let temp_project = create_test_kmp_project()?;
+    let path = temp_project.path();
+    let repo = git2::Repository::init(path)?;
+    let mut index = repo.index()?;
+    index.add_all(["*"], git2::IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = git2::Signature::now("Test", "test@example.com")?;
+    repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[])?;
+
+    // Change formatUserName and the Android call site using it
+    let utils = path.join("shared/src/commonMain/kotlin/com/example/Utils.kt");
+    let content = fs::read_to_string(&utils)?;
+    fs::write(&utils, content.replace("return \"${user.name} <${user.email}>\"", "return user.name.trim()"))?;
+    let activity = path.join("app/src/main/java/com/example/android/MainActivity.kt");
+    let content = fs::read_to_string(&activity)?;
+    fs::write(&activity, content.replace("\"User created: ", "\"Created: "))?;
+    fs::create_dir_all(path.join(".github"))?;
+    fs::write(path.join(".github/CODEOWNERS"), "* @mobile\n/shared/ @platform\n")?;
+
+    let options = AnalysisOptions {
+        diff: Some(DiffScope {
+            base_ref: "HEAD".to_string(),
+            files: GitUtils::changed_lines(path, "HEAD")?,
+        }),
+        ..Default::default()
+    };
+    let root = path.to_str().unwrap();
+    let analysis = Analyzer::with_configuration(options, Vec::new()).analyze(root)?;
+    let pack = ReviewPack::from_analysis(&analysis, &OsFileSystem, Some(root))?;
+
+    assert_eq!(pack.codeowners.as_deref(), Some(".github/CODEOWNERS"));
+    let files: Vec<(&str, &[String], SnippetKind)> = pack
+        .files
+        .iter()
+        .map(|file| (file.path.as_str(), file.reviewers.as_slice(), file.snippets[0].kind))
+        .collect();
+    assert_eq!(
+        files,
+        vec![
+            (
+                "app/src/main/java/com/example/android/MainActivity.kt",
+                &["@mobile".to_string(), "@platform".to_string()][..],
+                SnippetKind::Usage
+            ),
+            (
+                "shared/src/commonMain/kotlin/com/example/Utils.kt",
+                &["@platform".to_string()][..],
+                SnippetKind::Declaration
+            ),
+        ]
+    );
+    assert!(pack.files.iter().all(|file| file.snippets.len() == 1));
+    assert!(pack.files.iter().all(|file| file.snippets[0].symbols == ["formatUserName"]));
+
+    Ok(())
+}
+
+#[test]
+fn test_android_generated_sources_are_excluded() -> Result<()> {
+    use kotlin_multiplatform_coverage::use_cases::analyze_impact::EXCLUDED_SOURCES_RULE;
+
+    let temp_project = create_test_kmp_project()?;
+    let generated = temp_project
+        .path()
+        .join("app/src/main/java/com/example/android/RoutesFileGenerated.kt");
+    fs::write(&generated, "package com.example.android\n\nval routes = listOf(User(\"1\", \"a\", \"b\"))\n")?;
+    let path = temp_project.path().to_str().unwrap();
+
+    let analysis = Analyzer::new().analyze(path)?;
+    assert!(!analysis.files.iter().any(|f| f.path.ends_with("RoutesFileGenerated.kt")));
+    let skipped: Vec<&str> = analysis
+        .diagnostics
+        .iter()
+        .filter(|d| d.rule == EXCLUDED_SOURCES_RULE)
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(skipped.len(), 1);
+    assert!(skipped[0].starts_with("Android project `app`: 1 file(s) skipped"));
+
+    let options = AnalysisOptions {
+        detection: DetectionSettings {
+            android_excluded_paths: Vec::new(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let everything = Analyzer::with_configuration(options, Vec::new()).analyze(path)?;
+    assert!(everything.files.iter().any(|f| f.path.ends_with("RoutesFileGenerated.kt")));
+    assert!(!everything.diagnostics.iter().any(|d| d.rule == EXCLUDED_SOURCES_RULE));
+
+    Ok(())
+}
+
+#[test]
+fn test_report_diff() -> Result<()> {
+    use kotlin_multiplatform_coverage::domain::{FileStatus, ReportDiff};
+    use kotlin_multiplatform_coverage::infrastructure::Reporter;
+
+    let temp_project = create_test_kmp_project()?;
+    let root = temp_project.path();
+    let reports = tempfile::tempdir()?;
+    let write_report = |name: &str| -> Result<ImpactAnalysis> {
+        let analysis = Analyzer::new().analyze(root.to_str().unwrap())?;
+        let path = reports.path().join(name);
+        Reporter::new("json")?.report_impact_analysis(&analysis, path.to_str())?;
+        Ok(serde_json::from_str(&fs::read_to_string(&path)?)?)
+    };
+
+    let before = write_report("before.json")?;
+    let android = root.join("app/src/main/java/com/example/android");
+    fs::write(
+        android.join("UserAdapter.kt"),
+        "package com.example.android\n\nclass UserAdapter {\n    fun show() {\n        Logger.log(\"shown\")\n    }\n}\n",
+    )?;
+    fs::write(
+        android.join("Settings.kt"),
+        "package com.example.android\n\nclass Settings {\n    val repository = UserRepositoryImpl()\n}\n",
+    )?;
+    let after = write_report("after.json")?;
+
+    let diff = ReportDiff::between(&before, &after);
+    assert!(diff.total_app_lines.after > diff.total_app_lines.before);
+    let android_delta = diff.platforms.iter().find(|p| p.platform == "Android").unwrap();
+    assert_eq!(android_delta.total_lines.before, before.platform_impacts["Android"].total_lines);
+    assert!(diff.platforms.iter().any(|p| p.platform == "iOS"));
+
+    let logger = diff.symbols.iter().find(|s| s.symbol == "Logger").unwrap();
+    assert_eq!(logger.usages.delta(), 1);
+    let paths: Vec<(&str, FileStatus)> = diff.files.iter().map(|f| (f.path.as_str(), f.status)).collect();
+    assert_eq!(
+        paths,
+        vec![
+            ("app/src/main/java/com/example/android/Settings.kt", FileStatus::Added),
+            ("app/src/main/java/com/example/android/UserAdapter.kt", FileStatus::Changed),
+        ]
+    );
+    assert_eq!(diff.files[0].added_symbols, vec!["UserRepositoryImpl"]);
+    assert!(diff.files[1].added_symbols.contains(&"Logger".to_string()));
+
+    let md_path = reports.path().join("diff.md");
+    Reporter::new("markdown")?.report_diff(&diff, md_path.to_str())?;
+    let md = fs::read_to_string(&md_path)?;
+    assert!(md.contains("| `app/src/main/java/com/example/android/UserAdapter.kt` | Android | changed |"));
+    assert!(md.contains("| `Logger` | "));
+    assert!(Reporter::new("html")?.report_diff(&diff, None).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,241 @@
+//! Property-based tests of the counting invariants
+//!
+//! Random project trees (shared classes, Android and iOS app files mixing usages,
+//! plain code, comments, and blank lines) are analyzed in memory, and the figures
+//! of every report are checked against each other, against the generated tree, and
+//! against the analyses of per-app shards merged back together.
+
+use proptest::prelude::*;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use kotlin_multiplatform_coverage::{
+    adapters::MemoryFileSystem,
+    domain::{AnalysisScope, ImpactAnalysis, WarmStartState},
+    infrastructure::Analyzer,
+    use_cases::AnalysisOptions,
+};
+
+const ROOT: &str = "/project";
+
+/// Names of the shared classes a tree may declare
+const SYMBOLS: [&str; 6] = ["Account", "Billing", "Cart", "Device", "Event", "Feed"];
+
+#[derive(Debug, Clone)]
+enum Line {
+    /// Instantiates the shared class of this index
+    Uses(usize),
+    Plain,
+    /// Names a shared class in a comment, which is no usage
+    Comment(usize),
+    Blank,
+}
+
+#[derive(Debug, Clone)]
+struct ProjectTree {
+    symbols: usize,
+    android: Vec<Vec<Line>>,
+    ios: Vec<Vec<Line>>,
+}
+
+impl ProjectTree {
+    fn file_system(&self) -> MemoryFileSystem {
+        let mut fs = MemoryFileSystem::new();
+        fs.insert(
+            format!("{}/shared/build.gradle.kts", ROOT),
+            "plugins {\n    kotlin(\"multiplatform\")\n}\n",
+        );
+        let classes: String = SYMBOLS[..self.symbols]
+            .iter()
+            .map(|name| format!("class {}\n", name))
+            .collect();
+        fs.insert(
+            format!("{}/shared/src/commonMain/kotlin/com/example/Model.kt", ROOT),
+            format!("package com.example\n\n{}", classes),
+        );
+
+        fs.insert(
+            format!("{}/app/build.gradle.kts", ROOT),
+            "plugins {\n    id(\"com.android.application\")\n}\n",
+        );
+        for (index, lines) in self.android.iter().enumerate() {
+            fs.insert(self.android_path(index), self.source(index, lines, false));
+        }
+
+        fs.insert(format!("{}/iosApp/iosApp.xcodeproj/project.pbxproj", ROOT), "// Xcode project file");
+        for (index, lines) in self.ios.iter().enumerate() {
+            fs.insert(self.ios_path(index), self.source(index, lines, true));
+        }
+        fs
+    }
+
+    fn android_path(&self, index: usize) -> String {
+        format!("{}/app/src/main/java/com/example/android/Screen{}.kt", ROOT, index)
+    }
+
+    fn ios_path(&self, index: usize) -> String {
+        format!("{}/iosApp/iosApp/Screen{}.swift", ROOT, index)
+    }
+
+    fn source(&self, index: usize, lines: &[Line], swift: bool) -> String {
+        let (header, binding) = if swift {
+            (format!("import Shared\n\nstruct Screen{} {{\n", index), "let")
+        } else {
+            (format!("package com.example.android\n\nclass Screen{} {{\n", index), "val")
+        };
+        let mut source = header;
+        for (number, line) in lines.iter().enumerate() {
+            let line = match line {
+                Line::Uses(symbol) => format!("    {} v{} = {}()", binding, number, SYMBOLS[symbol % self.symbols]),
+                Line::Plain => format!("    {} x{} = {}", binding, number, number),
+                Line::Comment(symbol) => format!("    // {}() is not called here", SYMBOLS[symbol % self.symbols]),
+                Line::Blank => String::new(),
+            };
+            source.push_str(&line);
+            source.push('\n');
+        }
+        source.push_str("}\n");
+        source
+    }
+
+    /// App files that instantiate a shared class
+    fn using_files(&self) -> HashSet<String> {
+        let uses = |lines: &Vec<Line>| lines.iter().any(|line| matches!(line, Line::Uses(_)));
+        let android = (0..self.android.len()).filter(|i| uses(&self.android[*i])).map(|i| self.android_path(i));
+        let ios = (0..self.ios.len()).filter(|i| uses(&self.ios[*i])).map(|i| self.ios_path(i));
+        android.chain(ios).collect()
+    }
+
+    fn analyzer(&self) -> Analyzer {
+        Analyzer::with_file_system(Arc::new(self.file_system()), AnalysisOptions::default(), Vec::new())
+    }
+
+    /// Analyzer restricted to the given directories, as `--scope` restricts a run
+    fn scoped_analyzer(&self, roots: &[&str]) -> Analyzer {
+        let options = AnalysisOptions {
+            scope: AnalysisScope::new(roots.iter().map(|root| root.to_string()).collect()),
+            ..Default::default()
+        };
+        Analyzer::with_file_system(Arc::new(self.file_system()), options, Vec::new())
+    }
+}
+
+fn line() -> impl Strategy<Value = Line> {
+    prop_oneof![
+        3 => (0..SYMBOLS.len()).prop_map(Line::Uses),
+        4 => Just(Line::Plain),
+        1 => (0..SYMBOLS.len()).prop_map(Line::Comment),
+        1 => Just(Line::Blank),
+    ]
+}
+
+fn app_files() -> impl Strategy<Value = Vec<Vec<Line>>> {
+    prop::collection::vec(prop::collection::vec(line(), 0..12), 1..5)
+}
+
+fn project_tree() -> impl Strategy<Value = ProjectTree> {
+    (1..=SYMBOLS.len(), app_files(), app_files()).prop_map(|(symbols, android, ios)| ProjectTree {
+        symbols,
+        android,
+        ios,
+    })
+}
+
+fn is_ratio(ratio: f64) -> bool {
+    (0.0..=1.0).contains(&ratio)
+}
+
+/// The JSON report of an analysis, without warm start data
+fn report(analysis: &ImpactAnalysis) -> String {
+    let mut analysis = analysis.clone();
+    analysis.warm_start = None;
+    serde_json::to_string_pretty(&analysis).unwrap()
+}
+
+/// Usage sites of an analysis as (file, line, symbol), sorted
+fn usage_sites(analysis: &ImpactAnalysis) -> Vec<(String, usize, String)> {
+    let mut sites: Vec<_> = analysis
+        .symbol_usages
+        .values()
+        .flatten()
+        .map(|usage| (usage.file_path.clone(), usage.line_number, usage.symbol_name.clone()))
+        .collect();
+    sites.sort();
+    sites
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(24))]
+
+    #[test]
+    fn counts_stay_within_totals(tree in project_tree()) {
+        let analysis = tree.analyzer().analyze(ROOT).unwrap();
+
+        prop_assert!(analysis.affected_lines <= analysis.total_app_lines);
+        prop_assert!(analysis.affected_files.len() <= analysis.total_app_files);
+        prop_assert!(is_ratio(analysis.impact_ratio), "impact ratio {}", analysis.impact_ratio);
+        prop_assert!(is_ratio(analysis.ui_layout.impact_ratio_excluding_ui));
+        for (name, platform) in &analysis.platform_impacts {
+            prop_assert!(platform.affected_lines <= platform.total_lines, "{}", name);
+            prop_assert!(platform.affected_files.len() <= platform.total_files, "{}", name);
+            prop_assert!(is_ratio(platform.impact_ratio), "{} impact ratio {}", name, platform.impact_ratio);
+        }
+        for file in &analysis.files {
+            prop_assert!(file.affected_lines <= file.total_lines, "{}", file.path);
+        }
+
+        // Files that instantiate a shared class are exactly the affected ones;
+        // shared classes named only in comments affect nothing
+        let affected: HashSet<String> = analysis.affected_files.iter().cloned().collect();
+        prop_assert_eq!(affected, tree.using_files());
+    }
+
+    #[test]
+    fn breakdowns_add_up_to_the_totals(tree in project_tree()) {
+        let analysis = tree.analyzer().analyze(ROOT).unwrap();
+        let platforms = analysis.platform_impacts.values();
+
+        prop_assert_eq!(platforms.clone().map(|p| p.total_lines).sum::<usize>(), analysis.total_app_lines);
+        prop_assert_eq!(platforms.clone().map(|p| p.affected_lines).sum::<usize>(), analysis.affected_lines);
+        prop_assert_eq!(platforms.clone().map(|p| p.total_files).sum::<usize>(), analysis.total_app_files);
+        let merged: HashSet<&String> = platforms.flat_map(|p| &p.affected_files).collect();
+        prop_assert_eq!(merged, analysis.affected_files.iter().collect::<HashSet<_>>());
+
+        prop_assert_eq!(analysis.files.len(), analysis.total_app_files);
+        prop_assert_eq!(analysis.files.iter().map(|f| f.total_lines).sum::<usize>(), analysis.total_app_lines);
+        prop_assert_eq!(analysis.files.iter().map(|f| f.affected_lines).sum::<usize>(), analysis.affected_lines);
+    }
+
+    #[test]
+    fn runs_are_deterministic(tree in project_tree()) {
+        // Byte-identical reports, sets and maps included
+        let first = tree.analyzer().analyze_with_warm_start(ROOT, WarmStartState::default()).unwrap();
+        let second = tree.analyzer().analyze_with_warm_start(ROOT, WarmStartState::default()).unwrap();
+        prop_assert_eq!(serde_json::to_string(&first).unwrap(), serde_json::to_string(&second).unwrap());
+        prop_assert_eq!(report(&first), report(&tree.analyzer().analyze(ROOT).unwrap()));
+
+        // A run resumed from the first one's results reports the same
+        let state = first.warm_start.clone().unwrap();
+        let resumed = tree.analyzer().analyze_with_warm_start(ROOT, state).unwrap();
+        prop_assert_eq!(report(&resumed), report(&first));
+    }
+
+    #[test]
+    fn shards_merge_into_the_full_analysis(tree in project_tree()) {
+        let full = tree.analyzer().analyze(ROOT).unwrap();
+        // One shard per app, each scoped to the shared module and that app's directory
+        let shards: Vec<ImpactAnalysis> = ["app", "iosApp"]
+            .iter()
+            .map(|app| tree.scoped_analyzer(&["shared", app]).analyze(ROOT).unwrap())
+            .collect();
+
+        prop_assert_eq!(shards.iter().map(|s| s.total_app_files).sum::<usize>(), full.total_app_files);
+        prop_assert_eq!(shards.iter().map(|s| s.total_app_lines).sum::<usize>(), full.total_app_lines);
+        prop_assert_eq!(shards.iter().map(|s| s.affected_lines).sum::<usize>(), full.affected_lines);
+        let merged: HashSet<&String> = shards.iter().flat_map(|s| &s.affected_files).collect();
+        prop_assert_eq!(merged, full.affected_files.iter().collect::<HashSet<_>>());
+        let mut sites: Vec<_> = shards.iter().flat_map(usage_sites).collect();
+        sites.sort();
+        prop_assert_eq!(sites, usage_sites(&full));
+    }
+}